@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest file kept next to the backups
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Metadata recorded for a single backup file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub project: String,
+    pub service: String,
+    pub kind: String,
+    pub sha256: String,
+    pub size: u64,
+    pub created_at: String,
+}
+
+/// Integrity manifest for a backup directory, keyed by file name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupManifest {
+    #[serde(default)]
+    pub backups: BTreeMap<String, ManifestEntry>,
+}
+
+/// Outcome of checking a backup file against the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// The checksum matches the recorded one
+    Verified,
+    /// The file predates the manifest or was copied in from elsewhere
+    Unknown,
+}
+
+impl BackupManifest {
+    /// Load the manifest from a backup directory, or an empty one if missing
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read backup manifest: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse backup manifest: {}", path.display()))
+    }
+
+    /// Write the manifest back to the backup directory
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(MANIFEST_FILE);
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write backup manifest: {}", path.display()))
+    }
+
+    /// Record a backup file, hashing its current contents
+    pub fn record(&mut self, file: &Path, project: &str, service: &str, kind: &str) -> Result<&ManifestEntry> {
+        let data = std::fs::read(file)
+            .with_context(|| format!("Failed to read backup file: {}", file.display()))?;
+
+        let entry = ManifestEntry {
+            project: project.to_string(),
+            service: service.to_string(),
+            kind: kind.to_string(),
+            sha256: sha256_hex(&data),
+            size: data.len() as u64,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let key = file_key(file)?;
+        self.backups.insert(key.clone(), entry);
+        Ok(&self.backups[&key])
+    }
+
+    /// Add the entries of another manifest, keeping this one's where both have a file
+    pub fn merge(&mut self, other: BackupManifest) {
+        for (file, entry) in other.backups {
+            self.backups.entry(file).or_insert(entry);
+        }
+    }
+
+    /// Forget a backup file
+    pub fn remove(&mut self, file: &Path) {
+        if let Ok(key) = file_key(file) {
+            self.backups.remove(&key);
+        }
+    }
+
+    /// Check backup data against the recorded checksum
+    pub fn verify(&self, file: &Path, data: &[u8]) -> Result<Verification> {
+        let Some(entry) = self.backups.get(&file_key(file)?) else {
+            return Ok(Verification::Unknown);
+        };
+
+        let actual = sha256_hex(data);
+        if actual != entry.sha256 {
+            anyhow::bail!(
+                "Checksum mismatch for {}: expected {}, got {}. The backup is corrupted or was modified",
+                file.display(),
+                entry.sha256,
+                actual
+            );
+        }
+
+        Ok(Verification::Verified)
+    }
+}
+
+/// Directory holding the manifest that covers a backup file
+pub fn manifest_dir(file: &Path) -> PathBuf {
+    match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+fn file_key(file: &Path) -> Result<String> {
+    file.file_name()
+        .and_then(|n| n.to_str())
+        .map(str::to_string)
+        .with_context(|| format!("Invalid backup file name: {}", file.display()))
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("shop_postgres_20240101_000000.sql.gz");
+        std::fs::write(&file, b"dump").unwrap();
+
+        let mut manifest = BackupManifest::default();
+        manifest.record(&file, "shop", "postgres", "postgres").unwrap();
+        manifest.save(dir.path()).unwrap();
+
+        let manifest = BackupManifest::load(dir.path()).unwrap();
+        assert_eq!(manifest.verify(&file, b"dump").unwrap(), Verification::Verified);
+        assert!(manifest.verify(&file, b"tampered").is_err());
+
+        let other = dir.path().join("legacy.sql");
+        assert_eq!(manifest.verify(&other, b"dump").unwrap(), Verification::Unknown);
+    }
+
+    #[test]
+    fn test_merge_keeps_local_entries() {
+        let entry = |sha256: &str| ManifestEntry {
+            project: "shop".to_string(),
+            service: "postgres".to_string(),
+            kind: "postgres".to_string(),
+            sha256: sha256.to_string(),
+            size: 4,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+        };
+        let mut local = BackupManifest::default();
+        local.backups.insert("a.sql.gz".to_string(), entry("local"));
+        let mut remote = BackupManifest::default();
+        remote.backups.insert("a.sql.gz".to_string(), entry("remote"));
+        remote.backups.insert("b.sql.gz".to_string(), entry("remote"));
+
+        local.merge(remote);
+        assert_eq!(local.backups.len(), 2);
+        assert_eq!(local.backups["a.sql.gz"].sha256, "local");
+        assert_eq!(local.backups["b.sql.gz"].sha256, "remote");
+    }
+}
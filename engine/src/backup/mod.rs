@@ -0,0 +1,532 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::config::{BackupUploadConfig, ZeroConfig};
+use crate::core::Engine;
+
+pub mod manifest;
+pub mod schedule;
+pub mod upload;
+
+pub use manifest::{BackupManifest, Verification};
+pub use schedule::CronSchedule;
+pub use upload::S3Target;
+
+/// Default directory backups are written to
+pub const DEFAULT_OUTPUT_DIR: &str = "./backups";
+
+/// Extension appended to compressed backups
+const GZIP_EXTENSION: &str = "gz";
+
+/// Magic bytes at the start of a `mongodump --archive` file
+const MONGO_ARCHIVE_MAGIC: [u8; 4] = [0x6d, 0xe2, 0x99, 0x81];
+
+/// Steps of a backup or restore, reported to progress callbacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupStage {
+    Dumping,
+    Compressing,
+    Writing,
+    Verifying,
+    Decompressing,
+    Restoring,
+    Restarting,
+}
+
+/// A backup file on disk, with manifest metadata when available
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub service: String,
+    pub path: PathBuf,
+    pub file_name: String,
+    pub size: u64,
+    /// `YYYYmmdd_HHMMSS` timestamp taken from the file name
+    pub timestamp: String,
+    pub kind: Option<String>,
+    pub sha256: Option<String>,
+}
+
+/// Database engines that support backup and restore
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupKind {
+    Postgres,
+    Mysql,
+    Mongo,
+    Redis,
+}
+
+impl BackupKind {
+    /// Detect the backup kind from a service name
+    pub fn for_service(service: &str) -> Option<Self> {
+        if service.contains("postgres") || service.contains("timescale") {
+            Some(Self::Postgres)
+        } else if service.contains("mysql") || service.contains("mariadb") {
+            Some(Self::Mysql)
+        } else if service.contains("mongo") {
+            Some(Self::Mongo)
+        } else if service.contains("redis") {
+            Some(Self::Redis)
+        } else {
+            None
+        }
+    }
+
+    /// Get the engine name as string
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Postgres => "PostgreSQL",
+            Self::Mysql => "MySQL",
+            Self::Mongo => "MongoDB",
+            Self::Redis => "Redis",
+        }
+    }
+
+    /// Command that writes a dump to stdout inside the container
+    ///
+    /// Credentials are read from the container's own environment so the
+    /// generated passwords never leave the container. Redis takes none from
+    /// its environment, so it runs with [`exec_env`](Self::exec_env).
+    pub fn dump_command(&self) -> Vec<String> {
+        let script = match self {
+            Self::Postgres => r#"pg_dump -U "${POSTGRES_USER:-postgres}" "${POSTGRES_DB:-postgres}""#,
+            Self::Mysql => r#"MYSQL_PWD="$MYSQL_ROOT_PASSWORD" mysqldump -u root --all-databases --single-transaction --routines"#,
+            Self::Mongo => r#"mongodump --archive --quiet ${MONGO_INITDB_ROOT_USERNAME:+--username "$MONGO_INITDB_ROOT_USERNAME" --password "$MONGO_INITDB_ROOT_PASSWORD" --authenticationDatabase admin}"#,
+            Self::Redis => r#"redis-cli SAVE >/dev/null && cat "$(redis-cli CONFIG GET dir | tail -n 1)/$(redis-cli CONFIG GET dbfilename | tail -n 1)""#,
+        };
+        shell(script)
+    }
+
+    /// Command that reads a dump from stdin and loads it inside the container
+    pub fn restore_command(&self) -> Vec<String> {
+        let script = match self {
+            Self::Postgres => r#"psql -q -v ON_ERROR_STOP=1 -U "${POSTGRES_USER:-postgres}" -d "${POSTGRES_DB:-postgres}""#,
+            Self::Mysql => r#"MYSQL_PWD="$MYSQL_ROOT_PASSWORD" mysql -u root"#,
+            Self::Mongo => r#"mongorestore --archive --drop --quiet ${MONGO_INITDB_ROOT_USERNAME:+--username "$MONGO_INITDB_ROOT_USERNAME" --password "$MONGO_INITDB_ROOT_PASSWORD" --authenticationDatabase admin}"#,
+            // Disable saving so the restart loads the new snapshot instead of overwriting it,
+            // and remove the append-only files, which Redis would load instead of the snapshot
+            Self::Redis => concat!(
+                r#"DIR="$(redis-cli CONFIG GET dir | tail -n 1)"; FILE="$(redis-cli CONFIG GET dbfilename | tail -n 1)"; "#,
+                r#"AOF_DIR="$(redis-cli CONFIG GET appenddirname | tail -n 1)"; AOF="$(redis-cli CONFIG GET appendfilename | tail -n 1)"; "#,
+                r#"cat > "$DIR/$FILE.restore" && redis-cli CONFIG SET appendonly no >/dev/null && redis-cli CONFIG SET save "" >/dev/null "#,
+                r#"&& { [ -z "$AOF_DIR" ] || rm -rf "${DIR:?}/$AOF_DIR"; } && { [ -z "$AOF" ] || rm -f "${DIR:?}/$AOF"; } "#,
+                r#"&& mv "$DIR/$FILE.restore" "$DIR/$FILE""#,
+            ),
+        };
+        shell(script)
+    }
+
+    /// Environment the dump and restore commands run with: the Redis `password`
+    /// from the credential store, which `redis-cli` reads from `REDISCLI_AUTH`
+    /// so it stays out of the process list and the dump
+    pub fn exec_env(&self, password: Option<&str>) -> Vec<String> {
+        match (self, password) {
+            (Self::Redis, Some(password)) => vec![format!("REDISCLI_AUTH={}", password)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// File extension used for dumps of this kind
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Postgres | Self::Mysql => "sql",
+            Self::Mongo => "archive",
+            Self::Redis => "rdb",
+        }
+    }
+
+    /// Whether the service must be restarted to pick up a restored dump
+    pub fn restart_after_restore(&self) -> bool {
+        matches!(self, Self::Redis)
+    }
+
+    /// Check that a dump looks like it was produced for this engine
+    pub fn validate_dump(&self, data: &[u8]) -> Result<()> {
+        let head = String::from_utf8_lossy(&data[..data.len().min(4096)]);
+
+        let valid = match self {
+            Self::Postgres => {
+                data.starts_with(b"PGDMP") || head.contains("PostgreSQL database dump")
+            }
+            Self::Mysql => {
+                head.contains("MySQL dump") || head.contains("MariaDB dump") || head.contains("mysqldump")
+            }
+            Self::Mongo => data.starts_with(&MONGO_ARCHIVE_MAGIC),
+            Self::Redis => data.starts_with(b"REDIS"),
+        };
+
+        if !valid {
+            anyhow::bail!(
+                "File does not look like a {} backup; refusing to restore it",
+                self.name()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn shell(script: &str) -> Vec<String> {
+    vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+}
+
+/// Credential store key of the password `redis-cli` authenticates with
+fn redis_password_key(service: &str) -> String {
+    format!("{}_PASSWORD", service)
+}
+
+/// List every database-type service declared in the configuration
+pub fn database_services(config: &ZeroConfig) -> Vec<String> {
+    let mut services: Vec<String> = config
+        .services
+        .keys()
+        .filter(|name| BackupKind::for_service(name).is_some())
+        .cloned()
+        .collect();
+    services.sort();
+    services
+}
+
+/// Resolve the output directory from the CLI flag, the `backups:` section, or the default
+pub fn output_dir(config: &ZeroConfig, output: Option<&str>) -> PathBuf {
+    output
+        .map(str::to_string)
+        .or_else(|| config.backups.as_ref().and_then(|b| b.output.clone()))
+        .unwrap_or_else(|| DEFAULT_OUTPUT_DIR.to_string())
+        .into()
+}
+
+/// Dump a service's database into a compressed archive and return the file path
+///
+/// Files are named `{project}_{service}_{timestamp}.{ext}.gz` and their SHA-256
+/// is recorded in the directory's manifest.
+pub async fn backup_service(engine: &Engine, service: &str, output_dir: &Path) -> Result<PathBuf> {
+    backup_service_with_progress(engine, service, output_dir, |_| {}).await
+}
+
+/// Same as [`backup_service`], calling `progress` as each stage begins
+pub async fn backup_service_with_progress(
+    engine: &Engine,
+    service: &str,
+    output_dir: &Path,
+    mut progress: impl FnMut(BackupStage),
+) -> Result<PathBuf> {
+    let kind = BackupKind::for_service(service)
+        .with_context(|| format!("Backup not supported for service type: {}", service))?;
+
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create backup directory: {}", output_dir.display()))?;
+
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+
+    info!("Backing up {} ({})", service, kind.name());
+    progress(BackupStage::Dumping);
+    let password = engine.credential(&redis_password_key(service)).await;
+    let backup_data = engine.exec_command_with_env(service, kind.dump_command(), kind.exec_env(password.as_deref()), None).await?;
+    kind.validate_dump(&backup_data)
+        .with_context(|| format!("Backup of {} produced unexpected output", service))?;
+
+    let backup_file = output_dir.join(format!(
+        "{}_{}_{}.{}.{}",
+        engine.project_name(),
+        service,
+        timestamp,
+        kind.file_extension(),
+        GZIP_EXTENSION
+    ));
+    progress(BackupStage::Compressing);
+    let compressed = compress(&backup_data)?;
+
+    progress(BackupStage::Writing);
+    std::fs::write(&backup_file, compressed)
+        .with_context(|| format!("Failed to write backup file: {}", backup_file.display()))?;
+
+    let mut manifest = BackupManifest::load(output_dir)?;
+    manifest.record(&backup_file, engine.project_name(), service, kind.name())?;
+    manifest.save(output_dir)?;
+
+    Ok(backup_file)
+}
+
+/// Load a backup file into a service after checking its integrity and format
+///
+/// Compressed (`.gz`) and legacy uncompressed dumps are both accepted.
+pub async fn restore_service(engine: &Engine, service: &str, file: &Path) -> Result<(BackupKind, Verification)> {
+    restore_service_with_progress(engine, service, file, |_| {}).await
+}
+
+/// Same as [`restore_service`], calling `progress` as each stage begins
+pub async fn restore_service_with_progress(
+    engine: &Engine,
+    service: &str,
+    file: &Path,
+    mut progress: impl FnMut(BackupStage),
+) -> Result<(BackupKind, Verification)> {
+    let kind = BackupKind::for_service(service)
+        .with_context(|| format!("Restore not supported for service type: {}", service))?;
+
+    let raw = std::fs::read(file)
+        .with_context(|| format!("Failed to read backup file: {}", file.display()))?;
+
+    progress(BackupStage::Verifying);
+    let verification = BackupManifest::load(&manifest::manifest_dir(file))?.verify(file, &raw)?;
+    if verification == Verification::Unknown {
+        warn!("{} is not listed in the backup manifest; skipping checksum verification", file.display());
+    }
+
+    let data = if file.extension().and_then(|e| e.to_str()) == Some(GZIP_EXTENSION) {
+        progress(BackupStage::Decompressing);
+        decompress(&raw).with_context(|| format!("Failed to decompress backup: {}", file.display()))?
+    } else {
+        raw
+    };
+    kind.validate_dump(&data)?;
+
+    info!("Restoring {} ({}) from {}", service, kind.name(), file.display());
+    progress(BackupStage::Restoring);
+    let password = engine.credential(&redis_password_key(service)).await;
+    engine.exec_command_with_env(service, kind.restore_command(), kind.exec_env(password.as_deref()), Some(&data)).await?;
+
+    if kind.restart_after_restore() {
+        progress(BackupStage::Restarting);
+        engine.restart_service(service).await?;
+    }
+
+    Ok((kind, verification))
+}
+
+/// Ship a backup and the refreshed manifest to remote storage, returning the backup's URI
+///
+/// Other machines may upload to the same target, so the remote manifest is
+/// merged with the local one rather than replaced by it.
+pub async fn upload_backup(config: &BackupUploadConfig, file: &Path) -> Result<String> {
+    let uri = upload::upload_file(config, file).await?;
+
+    let dir = manifest::manifest_dir(file);
+    if dir.join(manifest::MANIFEST_FILE).exists() {
+        let mut merged = BackupManifest::load(&dir)?;
+        if let Some(remote) = upload::download_bytes(config, manifest::MANIFEST_FILE).await? {
+            let remote: BackupManifest = serde_json::from_slice(&remote)
+                .context("Failed to parse the remote backup manifest")?;
+            merged.merge(remote);
+        }
+        upload::upload_bytes(config, manifest::MANIFEST_FILE, &serde_json::to_vec_pretty(&merged)?).await?;
+    }
+
+    Ok(uri)
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+/// List existing backups for a service, oldest first
+///
+/// Matches both `{project}_{service}_{timestamp}` archives and older
+/// `{service}_{timestamp}` dumps.
+pub fn list_backups(output_dir: &Path, project: &str, service: &str) -> Result<Vec<PathBuf>> {
+    if !output_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let qualified = format!("{}_{}", project, service);
+    let mut backups: Vec<(String, PathBuf)> = std::fs::read_dir(output_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let (label, timestamp) = split_backup_name(name)?;
+            (label == service || label == qualified).then(|| (timestamp.to_string(), path.clone()))
+        })
+        .collect();
+
+    // Timestamps are zero-padded, so lexical order is chronological
+    backups.sort();
+    Ok(backups.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Describe a service's backups, newest first, using the manifest where possible
+pub fn backup_entries(output_dir: &Path, project: &str, service: &str) -> Result<Vec<BackupEntry>> {
+    let manifest = BackupManifest::load(output_dir)?;
+
+    let mut entries = Vec::new();
+    for path in list_backups(output_dir, project, service)?.into_iter().rev() {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        let timestamp = split_backup_name(&file_name)
+            .map(|(_, timestamp)| timestamp.to_string())
+            .unwrap_or_default();
+        let recorded = manifest.backups.get(&file_name);
+        let size = match recorded {
+            Some(entry) => entry.size,
+            None => std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        };
+
+        entries.push(BackupEntry {
+            service: service.to_string(),
+            kind: recorded.map(|entry| entry.kind.clone()),
+            sha256: recorded.map(|entry| entry.sha256.clone()),
+            path,
+            file_name,
+            size,
+            timestamp,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Split a backup file name into its label and `YYYYmmdd_HHMMSS` timestamp
+fn split_backup_name(name: &str) -> Option<(&str, &str)> {
+    let stem_end = name.find('.').unwrap_or(name.len());
+    let stem = &name[..stem_end];
+    if stem.len() < 17 || !stem.is_char_boundary(stem.len() - 16) {
+        return None;
+    }
+
+    let (label, rest) = stem.split_at(stem.len() - 16);
+    let timestamp = rest.strip_prefix('_')?;
+    is_timestamp(timestamp).then_some((label, timestamp))
+}
+
+/// Check that a string is a `YYYYmmdd_HHMMSS` timestamp
+fn is_timestamp(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 15
+        && bytes[..8].iter().all(u8::is_ascii_digit)
+        && bytes[8] == b'_'
+        && bytes[9..15].iter().all(u8::is_ascii_digit)
+}
+
+/// Delete all but the newest `keep` backups of a service and return the removed files
+pub fn apply_retention(output_dir: &Path, project: &str, service: &str, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(output_dir, project, service)?;
+    let excess = backups.len().saturating_sub(keep);
+    let mut removed = Vec::new();
+
+    if excess == 0 {
+        return Ok(removed);
+    }
+
+    let mut manifest = BackupManifest::load(output_dir)?;
+    for path in backups.into_iter().take(excess) {
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to remove old backup: {}", path.display()))?;
+        manifest.remove(&path);
+        removed.push(path);
+    }
+    manifest.save(output_dir)?;
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_kind_detection() {
+        assert_eq!(BackupKind::for_service("postgres"), Some(BackupKind::Postgres));
+        assert_eq!(BackupKind::for_service("timescaledb"), Some(BackupKind::Postgres));
+        assert_eq!(BackupKind::for_service("orders-mysql"), Some(BackupKind::Mysql));
+        assert_eq!(BackupKind::for_service("mongodb"), Some(BackupKind::Mongo));
+        assert_eq!(BackupKind::for_service("redis"), Some(BackupKind::Redis));
+        assert_eq!(BackupKind::for_service("rabbitmq"), None);
+    }
+
+    #[test]
+    fn test_validate_dump_formats() {
+        assert!(BackupKind::Postgres.validate_dump(b"--\n-- PostgreSQL database dump\n--\n").is_ok());
+        assert!(BackupKind::Mysql.validate_dump(b"-- MySQL dump 10.13  Distrib 8.0.36\n").is_ok());
+        assert!(BackupKind::Mongo.validate_dump(&[0x6d, 0xe2, 0x99, 0x81, 0x00]).is_ok());
+        assert!(BackupKind::Redis.validate_dump(b"REDIS0011\xfa").is_ok());
+
+        // A Postgres dump must not be restored into MySQL or Redis
+        let pg_dump = b"-- PostgreSQL database dump\nCREATE TABLE users ();\n";
+        assert!(BackupKind::Mysql.validate_dump(pg_dump).is_err());
+        assert!(BackupKind::Redis.validate_dump(pg_dump).is_err());
+    }
+
+    #[test]
+    fn test_redis_commands_authenticate_with_the_stored_password() {
+        assert_eq!(BackupKind::Redis.exec_env(Some("it's")), ["REDISCLI_AUTH=it's"]);
+        assert!(BackupKind::Redis.exec_env(None).is_empty());
+        assert!(BackupKind::Postgres.exec_env(Some("secret")).is_empty());
+        assert!(BackupKind::Redis.dump_command()[2].starts_with("redis-cli SAVE"));
+
+        let restore = BackupKind::Redis.restore_command();
+        assert!(!restore[2].contains("-a "));
+        assert!(restore[2].contains(r#"rm -rf "${DIR:?}/$AOF_DIR""#));
+    }
+
+    #[test]
+    fn test_apply_retention_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "postgres_20240101_000000.sql",
+            "postgres_20240102_000000.sql",
+            "shop_postgres_20240103_000000.sql.gz",
+            "postgres-replica_20240101_000000.sql",
+            "mysql_20240101_000000.sql",
+        ] {
+            std::fs::write(dir.path().join(name), "dump").unwrap();
+        }
+
+        let removed = apply_retention(dir.path(), "shop", "postgres", 2).unwrap();
+        assert_eq!(removed, vec![dir.path().join("postgres_20240101_000000.sql")]);
+
+        let remaining = list_backups(dir.path(), "shop", "postgres").unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(dir.path().join("postgres-replica_20240101_000000.sql").exists());
+        assert!(dir.path().join("mysql_20240101_000000.sql").exists());
+    }
+
+    #[test]
+    fn test_backup_entries_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["shop_redis_20240101_000000.rdb.gz", "shop_redis_20240102_000000.rdb.gz"] {
+            std::fs::write(dir.path().join(name), "snapshot").unwrap();
+        }
+
+        let mut manifest = BackupManifest::default();
+        manifest.record(&dir.path().join("shop_redis_20240102_000000.rdb.gz"), "shop", "redis", "redis").unwrap();
+        manifest.save(dir.path()).unwrap();
+
+        let entries = backup_entries(dir.path(), "shop", "redis").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].timestamp, "20240102_000000");
+        assert_eq!(entries[0].kind.as_deref(), Some("redis"));
+        assert!(entries[1].sha256.is_none());
+        assert_eq!(entries[1].size, 8);
+    }
+
+    #[test]
+    fn test_split_backup_name() {
+        assert_eq!(split_backup_name("shop_redis_20240101_000000.rdb.gz"), Some(("shop_redis", "20240101_000000")));
+        assert_eq!(split_backup_name("notes.txt"), None);
+        // Multi-byte characters where the timestamp would start must not panic
+        assert_eq!(split_backup_name("éééééééééx.sql"), None);
+    }
+
+    #[test]
+    fn test_compression_round_trip() {
+        let dump = b"-- PostgreSQL database dump\n".repeat(100);
+        let compressed = compress(&dump).unwrap();
+        assert!(compressed.len() < dump.len());
+        assert_eq!(decompress(&compressed).unwrap(), dump);
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+/// A five-field cron expression (`minute hour day-of-month month day-of-week`)
+///
+/// Each field supports `*`, single values, ranges (`1-5`), lists (`1,15`) and
+/// steps (`*/15`, `0-30/10`). The shorthands `@hourly`, `@daily`, `@weekly`
+/// and `@monthly` are also accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    days_of_week: Vec<u32>,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            anyhow::bail!(
+                "Invalid schedule '{}': expected 5 fields (minute hour day month weekday)",
+                expr
+            );
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59).context("Invalid minute field")?,
+            hours: parse_field(fields[1], 0, 23).context("Invalid hour field")?,
+            days_of_month: parse_field(fields[2], 1, 31).context("Invalid day-of-month field")?,
+            months: parse_field(fields[3], 1, 12).context("Invalid month field")?,
+            // Both 0 and 7 mean Sunday
+            days_of_week: parse_field(fields[4], 0, 7)
+                .context("Invalid day-of-week field")?
+                .into_iter()
+                .map(|d| d % 7)
+                .collect(),
+        })
+    }
+
+    /// Check if the schedule fires at the given minute
+    pub fn matches(&self, time: &NaiveDateTime) -> bool {
+        self.minutes.contains(&time.minute())
+            && self.hours.contains(&time.hour())
+            && self.days_of_month.contains(&time.day())
+            && self.months.contains(&time.month())
+            && self.days_of_week.contains(&time.weekday().num_days_from_sunday())
+    }
+
+    /// Find the next minute strictly after `after` at which the schedule fires
+    pub fn next_after(&self, after: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut candidate = after.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+
+        // A year of minutes covers every valid expression
+        for _ in 0..(366 * 24 * 60) {
+            if self.matches(&candidate) {
+                return Some(candidate);
+            }
+            candidate += chrono::Duration::minutes(1);
+        }
+
+        None
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>> {
+    let mut values = Vec::new();
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().context("Invalid step")?),
+            None => (part, 1),
+        };
+
+        if step == 0 {
+            anyhow::bail!("Step cannot be zero");
+        }
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse::<u32>()?, end.parse::<u32>()?)
+        } else {
+            let value = range.parse::<u32>()?;
+            // `5/10` means "from 5 to max every 10"
+            if part.contains('/') { (value, max) } else { (value, value) }
+        };
+
+        if start < min || end > max || start > end {
+            anyhow::bail!("Value out of range {}-{}: {}", min, max, part);
+        }
+
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        let schedule = CronSchedule::parse("*/15 2 * * 1-5").unwrap();
+        assert!(schedule.matches(&at("2024-01-01 02:30"))); // Monday
+        assert!(!schedule.matches(&at("2024-01-01 02:31")));
+        assert!(!schedule.matches(&at("2024-01-06 02:30"))); // Saturday
+    }
+
+    #[test]
+    fn test_shorthand_and_next() {
+        let schedule = CronSchedule::parse("@daily").unwrap();
+        assert_eq!(schedule.next_after(&at("2024-01-01 10:00")), Some(at("2024-01-02 00:00")));
+    }
+
+    #[test]
+    fn test_sunday_as_seven() {
+        let schedule = CronSchedule::parse("0 3 * * 7").unwrap();
+        assert!(schedule.matches(&at("2024-01-07 03:00")));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::info;
+
+use crate::config::BackupUploadConfig;
+
+/// An `s3://bucket/prefix` destination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Target {
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl S3Target {
+    /// Parse an `s3://bucket/path` URI
+    pub fn parse(uri: &str) -> Result<Self> {
+        let rest = uri
+            .strip_prefix("s3://")
+            .with_context(|| format!("Unsupported backup target: {}. Expected s3://bucket/path", uri))?;
+
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            anyhow::bail!("Backup target is missing a bucket name: {}", uri);
+        }
+
+        Ok(Self {
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+        })
+    }
+
+    /// Full object URI for a file name under this target
+    pub fn object_uri(&self, file_name: &str) -> String {
+        if self.prefix.is_empty() {
+            format!("s3://{}/{}", self.bucket, file_name)
+        } else {
+            format!("s3://{}/{}/{}", self.bucket, self.prefix, file_name)
+        }
+    }
+}
+
+const AWS_CLI_MISSING: &str = "Failed to run the AWS CLI. Install it from https://aws.amazon.com/cli/ to upload backups";
+
+/// Copy a backup file to the configured S3-compatible storage and return its URI
+pub async fn upload_file(config: &BackupUploadConfig, file: &Path) -> Result<String> {
+    let target = S3Target::parse(&config.target)?;
+    let file_name = file
+        .file_name()
+        .and_then(|n| n.to_str())
+        .with_context(|| format!("Invalid backup file name: {}", file.display()))?;
+    let uri = target.object_uri(file_name);
+
+    info!("Uploading {} to {}", file.display(), uri);
+
+    let output = s3_cp(config, file.as_os_str(), uri.as_ref()).output().await.context(AWS_CLI_MISSING)?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Upload to {} failed: {}",
+            uri,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(uri)
+}
+
+/// Contents of a file under the configured target, `None` when there is none
+pub async fn download_bytes(config: &BackupUploadConfig, file_name: &str) -> Result<Option<Vec<u8>>> {
+    let uri = S3Target::parse(&config.target)?.object_uri(file_name);
+    let output = s3_cp(config, "-".as_ref(), uri.as_ref()).output().await.context(AWS_CLI_MISSING)?;
+    if output.status.success() {
+        return Ok(Some(output.stdout));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("(404)") || stderr.contains("does not exist") || stderr.contains("NoSuchKey") {
+        return Ok(None);
+    }
+    anyhow::bail!("Download of {} failed: {}", uri, stderr.trim())
+}
+
+/// Write data to a file under the configured target and return its URI
+pub async fn upload_bytes(config: &BackupUploadConfig, file_name: &str, data: &[u8]) -> Result<String> {
+    let uri = S3Target::parse(&config.target)?.object_uri(file_name);
+    info!("Uploading {} to {}", file_name, uri);
+
+    let mut child = s3_cp(config, "-".as_ref(), uri.as_ref())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context(AWS_CLI_MISSING)?;
+    let mut stdin = child.stdin.take().context("The AWS CLI has no stdin")?;
+    stdin.write_all(data).await?;
+    drop(stdin);
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        anyhow::bail!("Upload to {} failed: {}", uri, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(uri)
+}
+
+/// `aws s3 cp` between two locations, `-` standing for stdin or stdout
+fn s3_cp(config: &BackupUploadConfig, from: &std::ffi::OsStr, to: &std::ffi::OsStr) -> Command {
+    let mut cmd = Command::new("aws");
+    cmd.arg("s3").arg("cp").arg(from).arg(to).arg("--only-show-errors");
+    if let Some(endpoint) = &config.endpoint {
+        cmd.arg("--endpoint-url").arg(endpoint);
+    }
+    if let Some(region) = &config.region {
+        cmd.arg("--region").arg(region);
+    }
+    if let Some(profile) = &config.profile {
+        cmd.arg("--profile").arg(profile);
+    }
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_s3_target() {
+        let target = S3Target::parse("s3://team-backups/shop/db/").unwrap();
+        assert_eq!(target.bucket, "team-backups");
+        assert_eq!(target.object_uri("a.sql.gz"), "s3://team-backups/shop/db/a.sql.gz");
+
+        let bare = S3Target::parse("s3://team-backups").unwrap();
+        assert_eq!(bare.object_uri("a.sql.gz"), "s3://team-backups/a.sql.gz");
+
+        assert!(S3Target::parse("gs://bucket").is_err());
+        assert!(S3Target::parse("s3:///path").is_err());
+    }
+}
@@ -0,0 +1,419 @@
+//! Interactive dashboard of `zero monitor`
+//!
+//! A table of the project's services with their resource usage, sparklines of
+//! the selected service's recent CPU, memory and network use, and a pane with
+//! its logs. Keys restart, stop or show the logs of the selected service, much
+//! like lazydocker scoped to one project.
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Sparkline, Table, TableState, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::core::Engine;
+use crate::logs::LogFilter;
+use crate::orchestrator::overview::ServiceInfo;
+
+/// Samples kept per service, enough to fill a wide terminal
+const HISTORY: usize = 120;
+
+/// Log lines fetched for the logs pane
+const LOG_TAIL: usize = 200;
+
+static OPEN: AtomicBool = AtomicBool::new(false);
+
+/// Whether the dashboard owns the terminal, so log events must not be printed
+pub fn is_open() -> bool {
+    OPEN.load(Ordering::Relaxed)
+}
+
+/// Recent resource usage of one service
+#[derive(Debug, Default)]
+struct History {
+    /// CPU in hundredths of a percent, as sparklines only take integers
+    cpu: VecDeque<u64>,
+    memory: VecDeque<u64>,
+    /// Network bytes per second, received and sent
+    rx: VecDeque<u64>,
+    tx: VecDeque<u64>,
+    /// Network counters of the previous sample, to turn into rates
+    last_network: Option<(u64, u64)>,
+}
+
+impl History {
+    fn push(&mut self, info: &ServiceInfo, interval: Duration) {
+        let Some(stats) = &info.stats else {
+            self.last_network = None;
+            return;
+        };
+
+        let seconds = interval.as_secs_f64().max(1.0);
+        let (rx, tx) = match self.last_network {
+            Some((rx, tx)) => (
+                (stats.network.rx.saturating_sub(rx) as f64 / seconds) as u64,
+                (stats.network.tx.saturating_sub(tx) as f64 / seconds) as u64,
+            ),
+            None => (0, 0),
+        };
+        self.last_network = Some((stats.network.rx, stats.network.tx));
+
+        for (series, value) in [
+            (&mut self.cpu, (stats.cpu * 100.0).round() as u64),
+            (&mut self.memory, stats.memory.usage),
+            (&mut self.rx, rx),
+            (&mut self.tx, tx),
+        ] {
+            if series.len() == HISTORY {
+                series.pop_front();
+            }
+            series.push_back(value);
+        }
+    }
+}
+
+/// What the dashboard shows, updated by samples and keys
+#[derive(Debug, Default)]
+struct Dashboard {
+    project: String,
+    services: Vec<ServiceInfo>,
+    history: HashMap<String, History>,
+    table: TableState,
+    show_logs: bool,
+    logs: String,
+    status: Option<String>,
+}
+
+impl Dashboard {
+    fn new(project: &str) -> Self {
+        Self { project: project.to_string(), show_logs: true, ..Default::default() }
+    }
+
+    /// Take a new sample of the project's containers
+    fn update(&mut self, mut services: Vec<ServiceInfo>, interval: Duration) {
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+        let selected = self.selected().map(|info| info.name.clone());
+
+        for info in &services {
+            self.history.entry(info.name.clone()).or_default().push(info, interval);
+        }
+        self.history.retain(|name, _| services.iter().any(|info| &info.name == name));
+        self.services = services;
+
+        // Keep the same service selected as containers come and go
+        let index = selected
+            .and_then(|name| self.services.iter().position(|info| info.name == name))
+            .or(if self.services.is_empty() { None } else { Some(0) });
+        self.table.select(index.map(|index| index.min(self.services.len().saturating_sub(1))));
+    }
+
+    fn selected(&self) -> Option<&ServiceInfo> {
+        self.table.selected().and_then(|index| self.services.get(index))
+    }
+
+    /// Service name of the selected container, as the engine addresses it
+    fn selected_service(&self) -> Option<String> {
+        let prefix = format!("{}_", self.project);
+        self.selected().map(|info| info.name.strip_prefix(&prefix).unwrap_or(&info.name).to_string())
+    }
+
+    fn select_next(&mut self, step: isize) {
+        if self.services.is_empty() {
+            return;
+        }
+        let last = self.services.len() as isize - 1;
+        let current = self.table.selected().unwrap_or(0) as isize;
+        self.table.select(Some((current + step).clamp(0, last) as usize));
+        self.logs.clear();
+    }
+}
+
+/// What a key asks for
+enum Action {
+    Quit,
+    Restart(String),
+    Stop(String),
+    Refresh,
+    None,
+}
+
+fn on_key(dashboard: &mut Dashboard, code: KeyCode, modifiers: KeyModifiers) -> Action {
+    match code {
+        KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+        KeyCode::Down | KeyCode::Char('j') => {
+            dashboard.select_next(1);
+            Action::Refresh
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            dashboard.select_next(-1);
+            Action::Refresh
+        }
+        KeyCode::Char('l') => {
+            dashboard.show_logs = !dashboard.show_logs;
+            Action::Refresh
+        }
+        KeyCode::Char('r') => dashboard.selected_service().map_or(Action::None, Action::Restart),
+        KeyCode::Char('s') => dashboard.selected_service().map_or(Action::None, Action::Stop),
+        _ => Action::None,
+    }
+}
+
+/// Run the dashboard until the user quits
+pub async fn run(engine: &mut Engine, interval: u64) -> Result<()> {
+    let interval = Duration::from_secs(interval.max(1));
+    let mut dashboard = Dashboard::new(engine.project_name());
+
+    let mut terminal = ratatui::init();
+    OPEN.store(true, Ordering::Relaxed);
+    let result = event_loop(engine, &mut terminal, &mut dashboard, interval).await;
+    OPEN.store(false, Ordering::Relaxed);
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    engine: &mut Engine,
+    terminal: &mut DefaultTerminal,
+    dashboard: &mut Dashboard,
+    interval: Duration,
+) -> Result<()> {
+    // Terminal events are read on a plain thread that ends once the loop drops the receiver
+    let (keys, mut keys_rx) = mpsc::channel(16);
+    std::thread::spawn(move || {
+        while !keys.is_closed() {
+            match event::poll(Duration::from_millis(100)) {
+                Ok(true) => match event::read() {
+                    Ok(event) => {
+                        if keys.blocking_send(event).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                },
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                match engine.service_infos().await {
+                    Ok(services) => dashboard.update(services, interval),
+                    Err(e) => dashboard.status = Some(format!("Error fetching stats: {}", e)),
+                }
+                refresh_logs(engine, dashboard).await;
+            }
+            event = keys_rx.recv() => {
+                let Some(event) = event else { break };
+                let Event::Key(key) = event else {
+                    terminal.draw(|frame| draw(frame, dashboard))?;
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match on_key(dashboard, key.code, key.modifiers) {
+                    Action::Quit => break,
+                    Action::Restart(service) => {
+                        dashboard.status = Some(match engine.restart_service(&service).await {
+                            Ok(()) => format!("Restarted {}", service),
+                            Err(e) => format!("Failed to restart {}: {:#}", service, e),
+                        });
+                    }
+                    Action::Stop(service) => {
+                        dashboard.status = Some(match engine.stop_service(&service).await {
+                            Ok(()) => format!("Stopped {}", service),
+                            Err(e) => format!("Failed to stop {}: {:#}", service, e),
+                        });
+                    }
+                    Action::Refresh => refresh_logs(engine, dashboard).await,
+                    Action::None => continue,
+                }
+            }
+        }
+        terminal.draw(|frame| draw(frame, dashboard))?;
+    }
+    Ok(())
+}
+
+async fn refresh_logs(engine: &Engine, dashboard: &mut Dashboard) {
+    if !dashboard.show_logs {
+        return;
+    }
+    if let Some(service) = dashboard.selected_service() {
+        dashboard.logs = engine.get_logs_as_string(&service, LOG_TAIL, &LogFilter::new()).await
+            .unwrap_or_else(|e| format!("Failed to read logs: {:#}", e));
+    }
+}
+
+fn draw(frame: &mut Frame, dashboard: &mut Dashboard) {
+    let mut constraints = vec![Constraint::Min(5), Constraint::Length(5)];
+    if dashboard.show_logs {
+        constraints.push(Constraint::Percentage(40));
+    }
+    constraints.push(Constraint::Length(1));
+    let areas = Layout::vertical(constraints).split(frame.area());
+
+    draw_table(frame, areas[0], dashboard);
+    draw_sparklines(frame, areas[1], dashboard);
+    if dashboard.show_logs {
+        draw_logs(frame, areas[2], dashboard);
+    }
+
+    let help = " ↑↓ select  r restart  s stop  l logs  q quit ";
+    let mut footer = vec![Span::styled(help, Style::default().fg(Color::Black).bg(Color::Cyan))];
+    if let Some(status) = &dashboard.status {
+        footer.push(Span::raw(format!("  {}", status)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(footer)), areas[areas.len() - 1]);
+}
+
+fn draw_table(frame: &mut Frame, area: Rect, dashboard: &mut Dashboard) {
+    let rows = dashboard.services.iter().map(|info| {
+        let history = dashboard.history.get(&info.name);
+        let rate = |series: Option<&VecDeque<u64>>| series.and_then(|s| s.back()).map(|r| format!("{}/s", format_bytes(*r)));
+        let (cpu, memory) = match &info.stats {
+            Some(stats) => (format!("{:.2}%", stats.cpu), format_bytes(stats.memory.usage)),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let status_color = if info.status.starts_with("Up") { Color::Green } else { Color::Red };
+        Row::new(vec![
+            Span::raw(info.name.clone()),
+            Span::styled(info.status.clone(), Style::default().fg(status_color)),
+            Span::raw(cpu),
+            Span::raw(memory),
+            Span::raw(rate(history.map(|h| &h.rx)).unwrap_or_else(|| "-".to_string())),
+            Span::raw(rate(history.map(|h| &h.tx)).unwrap_or_else(|| "-".to_string())),
+        ])
+    });
+
+    let widths = [
+        Constraint::Percentage(26),
+        Constraint::Percentage(24),
+        Constraint::Percentage(10),
+        Constraint::Percentage(14),
+        Constraint::Percentage(13),
+        Constraint::Percentage(13),
+    ];
+    let table = Table::new(rows, widths)
+        .header(Row::new(["SERVICE", "STATUS", "CPU", "MEMORY", "NET IN", "NET OUT"])
+            .style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(format!(" 📊 {} ", dashboard.project)))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, area, &mut dashboard.table);
+}
+
+fn draw_sparklines(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(area);
+    let history = dashboard.selected().and_then(|info| dashboard.history.get(&info.name));
+    let last = |series: &VecDeque<u64>| series.back().copied().unwrap_or(0);
+
+    let (cpu, memory, network) = match history {
+        Some(history) => (
+            format!(" CPU {:.2}% ", last(&history.cpu) as f64 / 100.0),
+            format!(" Memory {} ", format_bytes(last(&history.memory))),
+            format!(" Network in {}/s ", format_bytes(last(&history.rx))),
+        ),
+        None => (" CPU ".to_string(), " Memory ".to_string(), " Network in ".to_string()),
+    };
+    let series = |pick: fn(&History) -> &VecDeque<u64>, width: u16| -> Vec<u64> {
+        let values = history.map(pick).cloned().unwrap_or_default();
+        // Show the most recent samples that fit between the borders
+        let skip = values.len().saturating_sub(width.saturating_sub(2) as usize);
+        values.into_iter().skip(skip).collect()
+    };
+
+    for (index, (title, pick, color)) in [
+        (cpu, (|h: &History| &h.cpu) as fn(&History) -> &VecDeque<u64>, Color::Yellow),
+        (memory, |h: &History| &h.memory, Color::Magenta),
+        (network, |h: &History| &h.rx, Color::Blue),
+    ].into_iter().enumerate() {
+        let data = series(pick, areas[index].width);
+        let sparkline = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(&data)
+            .style(Style::default().fg(color));
+        frame.render_widget(sparkline, areas[index]);
+    }
+}
+
+fn draw_logs(frame: &mut Frame, area: Rect, dashboard: &Dashboard) {
+    let title = match dashboard.selected_service() {
+        Some(service) => format!(" Logs: {} ", service),
+        None => " Logs ".to_string(),
+    };
+    // Only the tail fits; lines are not wrapped so the newest stay at the bottom
+    let height = area.height.saturating_sub(2) as usize;
+    let lines: Vec<&str> = dashboard.logs.lines().collect();
+    let tail = lines[lines.len().saturating_sub(height)..].join("\n");
+    let paragraph = Paragraph::new(tail)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::overview::{MemoryStats, NetworkStats, ServiceStats};
+
+    fn info(name: &str, cpu: f64, rx: u64) -> ServiceInfo {
+        ServiceInfo {
+            name: name.to_string(),
+            image: "postgres:16".to_string(),
+            status: "Up 2 minutes".to_string(),
+            port: None,
+            stats: Some(ServiceStats {
+                cpu,
+                memory: MemoryStats { percentage: 1.0, usage: 1024, limit: 0 },
+                network: NetworkStats { rx, tx: 0 },
+            }),
+        }
+    }
+
+    #[test]
+    fn test_samples_build_history_and_keep_selection() {
+        let mut dashboard = Dashboard::new("shop");
+        let interval = Duration::from_secs(2);
+        dashboard.update(vec![info("shop_web", 1.0, 100), info("shop_api", 2.5, 0)], interval);
+        assert_eq!(dashboard.selected_service().as_deref(), Some("api"));
+
+        dashboard.select_next(1);
+        dashboard.update(vec![info("shop_web", 3.0, 500), info("shop_api", 2.5, 0)], interval);
+        assert_eq!(dashboard.selected_service().as_deref(), Some("web"));
+
+        let web = &dashboard.history["shop_web"];
+        assert_eq!(web.cpu, [100, 300]);
+        assert_eq!(web.rx, [0, 200]);
+
+        // A container that went away loses its history and the selection moves to one that exists
+        dashboard.update(vec![info("shop_api", 2.5, 0)], interval);
+        assert!(!dashboard.history.contains_key("shop_web"));
+        assert_eq!(dashboard.selected_service().as_deref(), Some("api"));
+        assert!(matches!(on_key(&mut dashboard, KeyCode::Char('s'), KeyModifiers::NONE), Action::Stop(service) if service == "api"));
+    }
+}
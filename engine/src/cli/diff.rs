@@ -0,0 +1,96 @@
+//! Line diffs of files the CLI is about to rewrite, e.g. zero.yml by `zero init --auto`
+
+use colored::Colorize;
+
+/// A line of a diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Lines of `old` and `new` in order, marked kept, removed or added along their longest common subsequence
+pub fn lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    diff
+}
+
+/// Whether a diff changes anything
+pub fn has_changes(diff: &[DiffLine]) -> bool {
+    diff.iter().any(|line| !matches!(line, DiffLine::Same(_)))
+}
+
+/// Colored `+`/`-` lines, with `context` unchanged lines around each change and `…` for the rest
+pub fn render(diff: &[DiffLine], context: usize) -> String {
+    let near_change = |index: usize| {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(diff.len());
+        diff[start..end].iter().any(|line| !matches!(line, DiffLine::Same(_)))
+    };
+
+    let mut output = Vec::new();
+    let mut skipped = false;
+    for (index, line) in diff.iter().enumerate() {
+        match line {
+            DiffLine::Removed(text) => output.push(format!("- {}", text).red().to_string()),
+            DiffLine::Added(text) => output.push(format!("+ {}", text).green().to_string()),
+            DiffLine::Same(text) if near_change(index) => output.push(format!("  {}", text).dimmed().to_string()),
+            DiffLine::Same(_) => {
+                if !skipped {
+                    output.push("  …".dimmed().to_string());
+                }
+                skipped = true;
+                continue;
+            }
+        }
+        skipped = false;
+    }
+    output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        let diff = lines("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(diff, vec![DiffLine::Same("a"), DiffLine::Removed("b"), DiffLine::Same("c"), DiffLine::Added("d")]);
+        assert!(has_changes(&diff));
+        assert!(!has_changes(&lines("a\nb", "a\nb\n")));
+
+        colored::control::set_override(false);
+        assert_eq!(render(&lines("1\n2\n3\n4\n5\n", "1\n2\n3\n4\nfive\n"), 1), "  …\n  4\n- 5\n+ five");
+    }
+}
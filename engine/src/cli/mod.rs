@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+
+pub mod dashboard;
+pub mod diff;
+pub mod terminal;
 
 #[derive(Parser)]
 #[command(name = "zc")]
@@ -16,6 +20,53 @@ pub struct Cli {
     /// Project directory (defaults to current directory)
     #[arg(short, long, global = true)]
     pub project_dir: Option<String>,
+
+    /// Config files to stack instead of zero.yml and zero.override.yml, later ones win
+    #[arg(long = "config-file", global = true)]
+    pub config_files: Vec<String>,
+
+    /// Docker context whose engine to use, e.g. one on a remote machine
+    #[arg(long, global = true)]
+    pub context: Option<String>,
+}
+
+/// How commands print their results, as `--output` takes it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored text for people
+    #[default]
+    Text,
+    /// JSON on stdout for scripts and editors; logs go to stderr
+    Json,
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+}
+
+/// Where `zero up` and `zero down` run services, as `--runtime` takes it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RuntimeTarget {
+    /// The detected Docker or Podman engine
+    #[default]
+    Docker,
+    /// The cluster of the kubeconfig context
+    #[value(alias = "k8s")]
+    Kubernetes,
+    /// A local minikube cluster, which also builds the images of services with `build:`
+    Minikube,
+}
+
+impl From<RuntimeTarget> for crate::runtime::ContainerRuntime {
+    fn from(target: RuntimeTarget) -> Self {
+        match target {
+            RuntimeTarget::Docker => Self::Docker,
+            RuntimeTarget::Kubernetes => Self::Kubernetes,
+            RuntimeTarget::Minikube => Self::Minikube,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -29,17 +80,41 @@ pub enum Commands {
         /// Template to use (node, python, rust, go, java, fullstack)
         #[arg(short, long)]
         template: Option<String>,
+
+        /// Propose a zero.yml from the project's manifests, shown as a diff before writing
+        #[arg(long, conflicts_with = "template")]
+        auto: bool,
+
+        /// Write the proposed zero.yml without asking
+        #[arg(short, long, requires = "auto")]
+        yes: bool,
     },
 
     /// Start the development environment
     Up {
-        /// Rebuild containers before starting
+        /// Rebuild images of services with `build:` before starting
         #[arg(short, long)]
         build: bool,
 
         /// Detach and run in background
         #[arg(short, long)]
         detach: bool,
+
+        /// Wait until every service is healthy (also `wait: true` in zero.yml)
+        #[arg(short, long)]
+        wait: bool,
+
+        /// Seconds to wait for services to become healthy
+        #[arg(long, default_value = "120")]
+        wait_timeout: u64,
+
+        /// When to pull images
+        #[arg(long, value_enum, default_value_t)]
+        pull: crate::orchestrator::PullPolicy,
+
+        /// Where services run
+        #[arg(long, value_enum, default_value_t)]
+        runtime: RuntimeTarget,
     },
 
     /// Stop the development environment
@@ -47,6 +122,10 @@ pub enum Commands {
         /// Remove volumes as well
         #[arg(short, long)]
         volumes: bool,
+
+        /// Where services run
+        #[arg(long, value_enum, default_value_t)]
+        runtime: RuntimeTarget,
     },
 
     /// Start a specific service
@@ -65,20 +144,44 @@ pub enum Commands {
     BuildEnv,
 
     /// Check system requirements and configuration
-    Doctor,
+    Doctor {
+        /// Fix what can be fixed automatically: start the runtime, create the network, free ports, ignore credentials
+        #[arg(long)]
+        fix: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
+    },
 
     /// View logs from services
     Logs {
-        /// Service name to view logs for
-        service: Option<String>,
+        /// Services to view logs for
+        services: Vec<String>,
+
+        /// View logs for every service in zero.yml
+        #[arg(short, long)]
+        all: bool,
 
         /// Follow log output
         #[arg(short, long)]
         follow: bool,
 
-        /// Number of lines to show
+        /// Number of lines to show per service
         #[arg(short, long, default_value = "100")]
         tail: usize,
+
+        /// Only show lines matching a regex (or literal substring)
+        #[arg(long)]
+        grep: Option<String>,
+
+        /// Only show logs since a time (e.g. 10m, 2h, 2024-01-01T00:00:00Z)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Minimum log level to show (error, warn, info, debug)
+        #[arg(long)]
+        level: Option<String>,
     },
 
     /// Manage cloud emulation
@@ -106,20 +209,49 @@ pub enum Commands {
         command: Vec<String>,
     },
 
-    /// Monitor resource usage
+    /// Monitor resource usage in a live dashboard with logs, restart and stop
     Monitor {
         /// Refresh interval in seconds
         #[arg(short, long, default_value = "2")]
         interval: u64,
+
+        /// Serve Prometheus metrics on an address such as :9464 (localhost) or 0.0.0.0:9464 instead of printing them
+        #[arg(long)]
+        serve: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
     },
 
-    /// List all running services
-    Ps,
+    /// List running services with their health, uptime and ports, and their web UIs
+    #[command(alias = "status")]
+    Ps {
+        /// Include stopped containers of the project
+        #[arg(short, long)]
+        all: bool,
+
+        /// Refresh the list every two seconds
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
+    },
 
     /// Restart services
     Restart {
         /// Specific services to restart (restart all if none specified)
         services: Vec<String>,
+
+        /// Restart one service at a time, waiting for health in between
+        #[arg(short, long)]
+        rolling: bool,
+
+        /// Health timeout per service in seconds when rolling
+        #[arg(short, long, default_value = "60")]
+        timeout: u64,
     },
 
     /// View environment variables
@@ -127,6 +259,10 @@ pub enum Commands {
         /// Export format (shell, json, yaml)
         #[arg(short, long, default_value = "shell")]
         format: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
     },
 
     /// Generate configuration files
@@ -147,16 +283,121 @@ pub enum Commands {
         /// Timeout in seconds when waiting
         #[arg(short, long, default_value = "60")]
         timeout: u64,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
     },
 
     /// Backup database services
     Backup {
         /// Service to backup
+        service: Option<String>,
+
+        /// Backup every database service
+        #[arg(short, long)]
+        all: bool,
+
+        /// Output directory for backup (defaults to backups.output or ./backups)
+        #[arg(short = 'o', long, visible_alias = "output")]
+        dir: Option<String>,
+
+        /// Keep only the newest N backups per service
+        #[arg(short, long)]
+        keep: Option<usize>,
+
+        /// Run scheduled backups from the `backups:` section until interrupted
+        #[arg(long, conflicts_with_all = ["service", "all", "dir", "keep"])]
+        daemon: bool,
+    },
+
+    /// Expose a service on a temporary public URL
+    Expose {
+        /// Service to expose
         service: String,
 
-        /// Output directory for backup
-        #[arg(short, long, default_value = "./backups")]
-        output: String,
+        /// Tunnel provider (cloudflared, ngrok, bore); auto-detected if omitted
+        #[arg(long)]
+        provider: Option<String>,
+    },
+
+    /// Share a service on a public URL until `zero share --stop` or `zero down`
+    Share {
+        /// Service to share; lists the shared services if omitted
+        service: Option<String>,
+
+        /// Tunnel provider (cloudflared, ngrok, bore); auto-detected if omitted
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// Close the service's tunnel, or every tunnel if no service is given
+        #[arg(long)]
+        stop: bool,
+    },
+
+    /// Serve services as <service>.localhost, over https:// with `proxy.tls`
+    Proxy {
+        #[command(subcommand)]
+        action: Option<ProxyCommands>,
+    },
+
+    /// Open a service's web UI (admin tools, consoles, Grafana) in the browser
+    Open {
+        /// Service or UI to open, e.g. minio, mail or postgres-admin; lists the UIs if omitted
+        target: Option<String>,
+    },
+
+    /// Start the environment and rebuild app containers as sources and zero.yml change
+    Dev {
+        /// Additional glob patterns or directory names to ignore
+        #[arg(long)]
+        ignore: Vec<String>,
+
+        /// Milliseconds to wait for changes to settle before rebuilding
+        #[arg(long, default_value = "500")]
+        debounce: u64,
+    },
+
+    /// Rerun a command or restart a service whenever source files change
+    Watch {
+        /// Command to run on change (e.g. `zero watch -- cargo test`)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+
+        /// Paths to watch (defaults to the project directory)
+        #[arg(short = 'w', long = "path")]
+        paths: Vec<String>,
+
+        /// Additional glob patterns or directory names to ignore
+        #[arg(long)]
+        ignore: Vec<String>,
+
+        /// Restart this service on change instead of running a command
+        #[arg(long)]
+        restart: Option<String>,
+
+        /// Milliseconds to wait for changes to settle before rerunning
+        #[arg(long, default_value = "300")]
+        debounce: u64,
+    },
+
+    /// Check zero.yml
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+
+    /// Upgrade zero.yml to the current schema version
+    UpgradeConfig {
+        /// Show the changes without writing zero.yml
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Manage generated credentials
+    Secrets {
+        #[command(subcommand)]
+        action: SecretsCommands,
     },
 
     /// Restore database services
@@ -173,8 +414,16 @@ pub enum Commands {
 pub enum CloudCommands {
     /// Start cloud emulation (LocalStack, etc.)
     Start {
-        /// Cloud provider (aws, azure, gcp)
-        provider: String,
+        /// Cloud providers (aws, azure, gcp); in a project, those in zero.yml when omitted
+        providers: Vec<String>,
+
+        /// Wait until the emulator is ready for requests; in a project it always does, before seeding
+        #[arg(short, long)]
+        wait: bool,
+
+        /// Timeout in seconds when waiting
+        #[arg(short, long, default_value = "120")]
+        timeout: u64,
     },
 
     /// Stop cloud emulation
@@ -185,12 +434,91 @@ pub enum CloudCommands {
 
     /// Open cloud UI in browser
     Ui,
+
+    /// Save the data of the project's emulators (LocalStack state, Azurite's workspace) to a tarball
+    Snapshot {
+        /// Snapshot name, written to ./cloud-snapshots/<name>.tar.gz
+        name: String,
+    },
+
+    /// Replace the emulators' data with a snapshot and start them again
+    Restore {
+        /// Snapshot name, or the path of a snapshot file shared by a teammate
+        name: String,
+
+        /// Timeout in seconds when waiting for the emulators
+        #[arg(short, long, default_value = "120")]
+        timeout: u64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Validate zero.yml and flag risky patterns; exits non-zero on errors
+    Lint {
+        /// Fail on warnings too
+        #[arg(long)]
+        strict: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
+    },
+
+    /// Set a value in zero.yml, keeping its comments and layout
+    Set {
+        /// Dotted key (e.g. services.postgres.version)
+        key: String,
+
+        /// New value, as YAML (e.g. 16, auto, "[a, b]")
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SecretsCommands {
+    /// List stored credentials with their values masked
+    List,
+
+    /// Print the value of a credential
+    Get {
+        /// Credential key (e.g. postgres_POSTGRES_PASSWORD)
+        key: String,
+    },
+
+    /// Set a credential and restart the containers using it
+    ///
+    /// The value is read from a hidden prompt, or from stdin when piped, so it
+    /// stays out of the shell history and the process list.
+    Set {
+        /// Credential key
+        key: String,
+    },
+
+    /// Replace a credential with a generated one and restart the containers using it
+    Rotate {
+        /// Credential key
+        key: String,
+    },
+
+    /// Print every credential in .env format, unmasked
+    Reveal,
+}
+
+#[derive(Subcommand)]
+pub enum ProxyCommands {
+    /// Install the local CA in the system and browser trust stores
+    Trust,
 }
 
 #[derive(Subcommand)]
 pub enum GenerateCommands {
-    /// Generate Dockerfile
-    Dockerfile,
+    /// Generate a multi-stage Dockerfile for the primary language
+    Dockerfile {
+        /// Language to build for instead of the first of zero.yml (node, python, go, rust, java)
+        #[arg(long)]
+        language: Option<String>,
+    },
 
     /// Generate docker-compose.yml
     Compose,
@@ -201,6 +529,12 @@ pub enum GenerateCommands {
     /// Generate GitHub Actions workflow
     GithubActions,
 
+    /// Generate flake.nix with a dev shell of the language toolchains and `nix run .#up`
+    Nix,
+
+    /// Generate Terraform (or OpenTofu) for the cloud services, targeting the emulators by default
+    Terraform,
+
     /// Generate all configuration files
     All,
 }
@@ -209,4 +543,56 @@ impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+}
+
+impl Commands {
+    /// Output format the command was asked for; text for commands without `--output`
+    pub fn output(&self) -> OutputFormat {
+        match self {
+            Commands::Doctor { output, .. }
+            | Commands::Monitor { output, .. }
+            | Commands::Ps { output, .. }
+            | Commands::Env { output, .. }
+            | Commands::Health { output, .. }
+            | Commands::Config { action: ConfigCommands::Lint { output, .. } } => *output,
+            _ => OutputFormat::Text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_is_validated() {
+        let cli = Cli::try_parse_from(["zc", "ps", "--output", "json"]).unwrap();
+        assert!(cli.command.output().is_json());
+        let cli = Cli::try_parse_from(["zc", "config", "lint"]).unwrap();
+        assert_eq!(cli.command.output(), OutputFormat::Text);
+        assert!(Cli::try_parse_from(["zc", "ps", "--output", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_pull_policy_is_validated() {
+        let cli = Cli::try_parse_from(["zc", "up", "--pull", "always"]).unwrap();
+        let Commands::Up { pull, .. } = cli.command else { panic!("expected up") };
+        assert_eq!(pull, crate::orchestrator::PullPolicy::Always);
+        assert!(Cli::try_parse_from(["zc", "up", "--pull", "never"]).is_err());
+    }
+
+    #[test]
+    fn test_runtime_is_validated() {
+        let cli = Cli::try_parse_from(["zc", "up", "--runtime", "k8s"]).unwrap();
+        let Commands::Up { runtime, .. } = cli.command else { panic!("expected up") };
+        assert_eq!(runtime, RuntimeTarget::Kubernetes);
+        assert!(Cli::try_parse_from(["zc", "up", "--runtime", "colima"]).is_err());
+    }
+
+    #[test]
+    fn test_backup_output_is_an_alias_of_dir() {
+        let cli = Cli::try_parse_from(["zc", "backup", "postgres", "--output", "./dumps"]).unwrap();
+        let Commands::Backup { dir, .. } = cli.command else { panic!("expected backup") };
+        assert_eq!(dir.as_deref(), Some("./dumps"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,195 @@
+//! Attaching the local terminal to an interactive exec session
+//!
+//! The terminal is switched to raw mode so keystrokes such as Ctrl+C and the
+//! arrow keys reach the program in the container instead of this process, and
+//! window size changes are forwarded to the session's TTY.
+
+use anyhow::Result;
+use futures::StreamExt;
+use std::io::{Read, Write};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+use crate::orchestrator::ExecSession;
+
+/// Columns and rows of the local terminal, with a classic fallback
+pub fn size() -> (u16, u16) {
+    let (rows, cols) = console::Term::stdout().size();
+    if rows == 0 || cols == 0 { (80, 24) } else { (cols, rows) }
+}
+
+/// Pump a session until the command exits and return its exit code
+///
+/// Raw mode is only enabled when stdin is a terminal; piped input is forwarded as is.
+pub async fn attach(session: ExecSession) -> Result<i64> {
+    let ExecSession { mut output, mut input, control } = session;
+    let _raw = RawMode::enable()?;
+
+    // A plain thread, unlike tokio's stdin, does not keep the runtime alive after the session ends
+    let (keys, mut keys_rx) = mpsc::channel::<Vec<u8>>(32);
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin();
+        let mut buffer = [0u8; 1024];
+        while let Ok(n) = stdin.read(&mut buffer) {
+            if n == 0 || keys.blocking_send(buffer[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let writer = tokio::spawn(async move {
+        while let Some(data) = keys_rx.recv().await {
+            if input.write_all(&data).await.is_err() || input.flush().await.is_err() {
+                break;
+            }
+        }
+        // End of input, e.g. piped stdin, closes the command's stdin too
+        let _ = input.shutdown().await;
+    });
+
+    let resize_control = control.clone();
+    let resizer = tokio::spawn(async move {
+        let mut last = size();
+        loop {
+            window_changed().await;
+            let current = size();
+            if current != last {
+                last = current;
+                let _ = resize_control.resize(current.0, current.1).await;
+            }
+        }
+    });
+
+    let mut stdout = std::io::stdout();
+    while let Some(chunk) = output.next().await {
+        stdout.write_all(&chunk?)?;
+        stdout.flush()?;
+    }
+
+    writer.abort();
+    resizer.abort();
+    Ok(control.exit_code().await?.unwrap_or(0))
+}
+
+#[cfg(unix)]
+async fn window_changed() {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::window_change()) {
+        Ok(mut signal) => {
+            signal.recv().await;
+        }
+        Err(_) => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn window_changed() {
+    // No resize signal; poll the console size instead
+    tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+}
+
+/// Puts the terminal in raw mode until dropped
+struct RawMode {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+    #[cfg(windows)]
+    original: Option<(u32, u32)>,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    fn enable() -> Result<Self> {
+        if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            return Ok(Self { original: None });
+        }
+
+        // SAFETY: termios is plain data and the calls only touch stdin's terminal settings
+        unsafe {
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            let mut raw = original;
+            libc::cfmakeraw(&mut raw);
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            Ok(Self { original: Some(original) })
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(original) = &self.original {
+            // SAFETY: restores the settings read in `enable`
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod console_api {
+    pub type Handle = *mut std::ffi::c_void;
+
+    pub const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+    pub const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    pub const ENABLE_PROCESSED_INPUT: u32 = 0x0001;
+    pub const ENABLE_LINE_INPUT: u32 = 0x0002;
+    pub const ENABLE_ECHO_INPUT: u32 = 0x0004;
+    pub const ENABLE_VIRTUAL_TERMINAL_INPUT: u32 = 0x0200;
+    pub const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetStdHandle(handle: u32) -> Handle;
+        pub fn GetConsoleMode(handle: Handle, mode: *mut u32) -> i32;
+        pub fn SetConsoleMode(handle: Handle, mode: u32) -> i32;
+    }
+}
+
+#[cfg(windows)]
+impl RawMode {
+    fn enable() -> Result<Self> {
+        use console_api::*;
+
+        if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            return Ok(Self { original: None });
+        }
+
+        // SAFETY: the handles are the process's own console handles
+        unsafe {
+            let (input, output) = (GetStdHandle(STD_INPUT_HANDLE), GetStdHandle(STD_OUTPUT_HANDLE));
+            let (mut input_mode, mut output_mode) = (0, 0);
+            if GetConsoleMode(input, &mut input_mode) == 0 || GetConsoleMode(output, &mut output_mode) == 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            let raw_input = (input_mode & !(ENABLE_PROCESSED_INPUT | ENABLE_LINE_INPUT | ENABLE_ECHO_INPUT))
+                | ENABLE_VIRTUAL_TERMINAL_INPUT;
+            if SetConsoleMode(input, raw_input) == 0
+                || SetConsoleMode(output, output_mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) == 0
+            {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            Ok(Self { original: Some((input_mode, output_mode)) })
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        use console_api::*;
+
+        if let Some((input_mode, output_mode)) = self.original {
+            // SAFETY: restores the modes read in `enable`
+            unsafe {
+                SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), input_mode);
+                SetConsoleMode(GetStdHandle(STD_OUTPUT_HANDLE), output_mode);
+            }
+        }
+    }
+}
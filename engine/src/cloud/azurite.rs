@@ -1,45 +1,81 @@
 // Azurite - Azure Storage Emulator
 
+use anyhow::Result;
+
+use crate::config::CloudConfig;
+
 pub const DEFAULT_SERVICES: &[&str] = &[
     "blob",
     "queue",
     "table",
 ];
 
-pub const DEFAULT_IMAGE: &str = "mcr.microsoft.com/azure-storage/azurite:latest";
+pub const DEFAULT_IMAGE: &str = "mcr.microsoft.com/azure-storage/azurite";
+/// Container name within the project, as `{project}_azurite`
+pub const CONTAINER: &str = "azurite";
 
 // Azurite default ports
 pub const BLOB_PORT: u16 = 10000;
 pub const QUEUE_PORT: u16 = 10001;
 pub const TABLE_PORT: u16 = 10002;
 
-pub fn get_blob_endpoint() -> String {
-    format!("http://127.0.0.1:{}", BLOB_PORT)
+/// Highest `azure.port`, leaving room for the queue and table ports after it
+pub const MAX_PORT: u16 = u16::MAX - 2;
+
+/// Volume holding Azurite's workspace, so data survives restarts; one per project, as `{project}_azurite-data`
+pub const DATA_VOLUME: &str = "azurite-data";
+/// Where the image's default command keeps the workspace
+pub const DATA_DIR: &str = "/data";
+
+/// Host port of the blob service: `azure.port`, else the default; queue and table take the next two
+pub fn port(cloud: Option<&CloudConfig>) -> u16 {
+    cloud.and_then(|cloud| cloud.azure.as_ref()).and_then(|azure| azure.port).unwrap_or(BLOB_PORT)
+}
+
+/// Image to run: `azure.image` and `azure.version`, else the default
+pub fn image(cloud: Option<&CloudConfig>) -> String {
+    let azure = cloud.and_then(|cloud| cloud.azure.as_ref());
+    super::image_ref(azure.and_then(|azure| azure.image.as_deref()), DEFAULT_IMAGE, azure.and_then(|azure| azure.version.as_deref()))
+}
+
+/// Container ports of the blob, queue and table services and the host ports they are published on
+pub fn port_bindings(blob_port: u16) -> Result<[(u16, u16); 3]> {
+    match (blob_port.checked_add(1), blob_port.checked_add(2)) {
+        (Some(queue_port), Some(table_port)) => Ok([(BLOB_PORT, blob_port), (QUEUE_PORT, queue_port), (TABLE_PORT, table_port)]),
+        _ => anyhow::bail!("azure.port {} leaves no room for the queue and table ports; use at most {}", blob_port, MAX_PORT),
+    }
+}
+
+pub fn get_blob_endpoint(blob_port: u16) -> String {
+    format!("http://127.0.0.1:{}", blob_port)
 }
 
-pub fn get_queue_endpoint() -> String {
-    format!("http://127.0.0.1:{}", QUEUE_PORT)
+/// Queue endpoint, on the host port [`port_bindings`] gives the queue service
+pub fn get_queue_endpoint(blob_port: u16) -> Result<String> {
+    Ok(format!("http://127.0.0.1:{}", port_bindings(blob_port)?[1].1))
 }
 
-pub fn get_table_endpoint() -> String {
-    format!("http://127.0.0.1:{}", TABLE_PORT)
+/// Table endpoint, on the host port [`port_bindings`] gives the table service
+pub fn get_table_endpoint(blob_port: u16) -> Result<String> {
+    Ok(format!("http://127.0.0.1:{}", port_bindings(blob_port)?[2].1))
 }
 
-pub fn get_connection_string() -> String {
-    format!(
+pub fn get_connection_string(blob_port: u16) -> Result<String> {
+    Ok(format!(
         "DefaultEndpointsProtocol=http;AccountName=devstoreaccount1;\
         AccountKey=Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==;\
         BlobEndpoint={}/devstoreaccount1;\
         QueueEndpoint={}/devstoreaccount1;\
         TableEndpoint={}/devstoreaccount1;",
-        get_blob_endpoint(),
-        get_queue_endpoint(),
-        get_table_endpoint()
-    )
+        get_blob_endpoint(blob_port),
+        get_queue_endpoint(blob_port)?,
+        get_table_endpoint(blob_port)?
+    ))
 }
 
-pub fn get_azure_config_snippet() -> String {
-    format!(
+pub fn get_azure_config_snippet(blob_port: u16) -> Result<String> {
+    let connection_string = get_connection_string(blob_port)?;
+    Ok(format!(
         r#"
 # Azure Storage Configuration for Azurite
 export AZURE_STORAGE_CONNECTION_STRING="{}"
@@ -54,10 +90,10 @@ export AZURE_STORAGE_CONNECTION_STRING="{}"
 # Queue Storage: {}
 # Table Storage: {}
 "#,
-        get_connection_string(),
-        get_connection_string(),
-        get_blob_endpoint(),
-        get_queue_endpoint(),
-        get_table_endpoint()
-    )
+        connection_string,
+        connection_string,
+        get_blob_endpoint(blob_port),
+        get_queue_endpoint(blob_port)?,
+        get_table_endpoint(blob_port)?
+    ))
 }
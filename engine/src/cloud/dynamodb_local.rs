@@ -0,0 +1,63 @@
+// DynamoDB Local - Amazon's standalone DynamoDB emulator, run for `aws.flavor: dynamodb-local`
+
+use crate::config::CloudConfig;
+
+pub const DEFAULT_PORT: u16 = 8000;
+pub const DEFAULT_IMAGE: &str = "amazon/dynamodb-local";
+/// Container name within the project, as `{project}_dynamodb-local`
+pub const CONTAINER: &str = "dynamodb-local";
+
+/// Arguments after the image's `java` entrypoint; one database for every
+/// region and access key, as clients pointed at it use arbitrary ones
+pub const COMMAND: &[&str] = &["-jar", "DynamoDBLocal.jar", "-sharedDb", "-inMemory"];
+
+/// Host port DynamoDB Local is published on: `aws.port`, else the default
+pub fn port(cloud: Option<&CloudConfig>) -> u16 {
+    cloud.and_then(|cloud| cloud.aws.as_ref()).and_then(|aws| aws.port).unwrap_or(DEFAULT_PORT)
+}
+
+/// Image to run: `aws.image` and `aws.version`, else the default
+pub fn image(cloud: Option<&CloudConfig>) -> String {
+    let aws = cloud.and_then(|cloud| cloud.aws.as_ref());
+    super::image_ref(aws.and_then(|aws| aws.image.as_deref()), DEFAULT_IMAGE, aws.and_then(|aws| aws.version.as_deref()))
+}
+
+pub fn get_endpoint_url(port: u16) -> String {
+    format!("http://localhost:{}", port)
+}
+
+/// `curl` arguments for a DynamoDB API call made inside the container.
+///
+/// DynamoDB Local requires a SigV4 `Authorization` header but does not check
+/// the signature, so a fixed one is enough.
+pub fn api_call(target: &str, body: &str) -> Vec<String> {
+    [
+        "curl", "-sf", "-X", "POST",
+        "-H", "Content-Type: application/x-amz-json-1.0",
+        "-H", &format!("X-Amz-Target: DynamoDB_20120810.{}", target),
+        "-H", "Authorization: AWS4-HMAC-SHA256 Credential=test/20240101/us-east-1/dynamodb/aws4_request, SignedHeaders=host, Signature=0",
+        "-d", body,
+        &format!("http://localhost:{}", DEFAULT_PORT),
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+pub fn get_config_snippet(region: &str, port: u16) -> String {
+    format!(
+        r#"
+# AWS Configuration for DynamoDB Local
+export AWS_ACCESS_KEY_ID=test
+export AWS_SECRET_ACCESS_KEY=test
+export AWS_DEFAULT_REGION={region}
+export AWS_ENDPOINT_URL_DYNAMODB={endpoint}
+
+# Or in your code (Python boto3):
+# import boto3
+# dynamodb = boto3.client('dynamodb', endpoint_url='{endpoint}', region_name='{region}')
+"#,
+        region = region,
+        endpoint = get_endpoint_url(port),
+    )
+}
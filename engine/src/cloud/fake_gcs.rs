@@ -0,0 +1,54 @@
+// fake-gcs-server - Cloud Storage emulator, run for `gcp.flavor: fake-gcs`
+
+use crate::config::CloudConfig;
+
+pub const DEFAULT_PORT: u16 = 4443;
+pub const DEFAULT_IMAGE: &str = "fsouza/fake-gcs-server";
+/// Container name within the project, as `{project}_fake-gcs`
+pub const CONTAINER: &str = "fake-gcs";
+
+/// Host port fake-gcs-server is published on: `gcp.port`, else the default
+pub fn port(cloud: Option<&CloudConfig>) -> u16 {
+    cloud.and_then(|cloud| cloud.gcp.as_ref()).and_then(|gcp| gcp.port).unwrap_or(DEFAULT_PORT)
+}
+
+/// Image to run: `gcp.image` and `gcp.version`, else the default
+pub fn image(cloud: Option<&CloudConfig>) -> String {
+    let gcp = cloud.and_then(|cloud| cloud.gcp.as_ref());
+    super::image_ref(gcp.and_then(|gcp| gcp.image.as_deref()), DEFAULT_IMAGE, gcp.and_then(|gcp| gcp.version.as_deref()))
+}
+
+/// Arguments after the image's entrypoint. It serves plain HTTP, and the URLs
+/// it hands out (e.g. for resumable uploads) point at the host port.
+pub fn command(port: u16) -> Vec<String> {
+    vec![
+        "-scheme".to_string(),
+        "http".to_string(),
+        "-port".to_string(),
+        DEFAULT_PORT.to_string(),
+        "-public-host".to_string(),
+        format!("localhost:{}", port),
+        "-external-url".to_string(),
+        get_endpoint_url(port),
+    ]
+}
+
+pub fn get_endpoint_url(port: u16) -> String {
+    format!("http://localhost:{}", port)
+}
+
+pub fn get_config_snippet(port: u16) -> String {
+    format!(
+        r#"
+# GCP Cloud Storage Configuration for fake-gcs-server
+export STORAGE_EMULATOR_HOST={endpoint}
+
+# Or in your code (Python):
+# from google.auth.credentials import AnonymousCredentials
+# from google.cloud import storage
+# client = storage.Client(credentials=AnonymousCredentials(), project='test-project',
+#                         client_options={{'api_endpoint': '{endpoint}'}})
+"#,
+        endpoint = get_endpoint_url(port),
+    )
+}
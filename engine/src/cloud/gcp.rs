@@ -1,5 +1,13 @@
 // GCP Cloud SDK Emulators
 
+use crate::config::CloudConfig;
+
+pub const DEFAULT_IMAGE: &str = "gcr.io/google.com/cloudsdktool/google-cloud-cli";
+
+/// Container names within the project, as `{project}_gcp-firestore`
+pub const FIRESTORE_CONTAINER: &str = "gcp-firestore";
+pub const PUBSUB_CONTAINER: &str = "gcp-pubsub";
+
 pub const DEFAULT_SERVICES: &[&str] = &[
     "firestore",
     "pubsub",
@@ -15,8 +23,19 @@ pub const BIGTABLE_PORT: u16 = 8086;
 pub const DATASTORE_PORT: u16 = 8081;
 pub const SPANNER_PORT: u16 = 9010;
 
-pub fn get_firestore_endpoint() -> String {
-    format!("localhost:{}", FIRESTORE_PORT)
+/// Host port of the Firestore emulator: `gcp.port`, else the default
+pub fn port(cloud: Option<&CloudConfig>) -> u16 {
+    cloud.and_then(|cloud| cloud.gcp.as_ref()).and_then(|gcp| gcp.port).unwrap_or(FIRESTORE_PORT)
+}
+
+/// Image to run: `gcp.image` and `gcp.version`, else the default
+pub fn image(cloud: Option<&CloudConfig>) -> String {
+    let gcp = cloud.and_then(|cloud| cloud.gcp.as_ref());
+    super::image_ref(gcp.and_then(|gcp| gcp.image.as_deref()), DEFAULT_IMAGE, gcp.and_then(|gcp| gcp.version.as_deref()))
+}
+
+pub fn get_firestore_endpoint(port: u16) -> String {
+    format!("localhost:{}", port)
 }
 
 pub fn get_pubsub_endpoint() -> String {
@@ -35,7 +54,7 @@ pub fn get_spanner_endpoint() -> String {
     format!("localhost:{}", SPANNER_PORT)
 }
 
-pub fn get_gcp_config_snippet() -> String {
+pub fn get_gcp_config_snippet(firestore_port: u16) -> String {
     format!(
         r#"
 # GCP Emulator Configuration
@@ -59,13 +78,13 @@ export GOOGLE_APPLICATION_CREDENTIALS=/path/to/service-account-key.json
 # Datastore: {}
 # Spanner: {}
 "#,
-        get_firestore_endpoint(),
+        get_firestore_endpoint(firestore_port),
         get_pubsub_endpoint(),
         get_bigtable_endpoint(),
         get_datastore_endpoint(),
         get_spanner_endpoint(),
-        get_firestore_endpoint(),
-        get_firestore_endpoint(),
+        get_firestore_endpoint(firestore_port),
+        get_firestore_endpoint(firestore_port),
         get_pubsub_endpoint(),
         get_bigtable_endpoint(),
         get_datastore_endpoint(),
@@ -1,5 +1,7 @@
 // LocalStack-specific utilities and helpers
 
+use crate::config::CloudConfig;
+
 pub const DEFAULT_SERVICES: &[&str] = &[
     "s3",
     "dynamodb",
@@ -18,30 +20,126 @@ pub const DEFAULT_SERVICES: &[&str] = &[
 ];
 
 pub const DEFAULT_PORT: u16 = 4566;
-pub const DEFAULT_IMAGE: &str = "localstack/localstack:latest";
+pub const DEFAULT_IMAGE: &str = "localstack/localstack";
+/// Image run instead of the default one when an auth token is set
+pub const PRO_IMAGE: &str = "localstack/localstack-pro";
+pub const DEFAULT_REGION: &str = "us-east-1";
+/// Container name within the project, as `{project}_localstack`
+pub const CONTAINER: &str = "localstack";
+
+/// Host variable passed through to LocalStack to activate Pro features
+pub const AUTH_TOKEN_VAR: &str = "LOCALSTACK_AUTH_TOKEN";
+
+/// Volume holding LocalStack's working directory; one per project, as `{project}_localstack-data`
+pub const DATA_VOLUME: &str = "localstack-data";
+/// LocalStack's working directory inside the container
+pub const DATA_DIR: &str = "/var/lib/localstack";
+/// Where LocalStack persists the state of its services, within [`DATA_DIR`]
+pub const STATE_DIR: &str = "/var/lib/localstack/state";
+
+/// Auth token from the host environment, if set
+pub fn auth_token() -> Option<String> {
+    std::env::var(AUTH_TOKEN_VAR).ok().filter(|token| !token.is_empty())
+}
+
+/// Host port LocalStack is published on: `aws.port`, else the default
+pub fn port(cloud: Option<&CloudConfig>) -> u16 {
+    cloud.and_then(|cloud| cloud.aws.as_ref()).and_then(|aws| aws.port).unwrap_or(DEFAULT_PORT)
+}
+
+/// Image to run: `aws.image` and `aws.version`, else the default or, with an auth token, the Pro image
+pub fn image(cloud: Option<&CloudConfig>, auth_token: Option<&str>) -> String {
+    let aws = cloud.and_then(|cloud| cloud.aws.as_ref());
+    let default = if auth_token.is_some() { PRO_IMAGE } else { DEFAULT_IMAGE };
+    super::image_ref(aws.and_then(|aws| aws.image.as_deref()), default, aws.and_then(|aws| aws.version.as_deref()))
+}
+
+/// Services to start: `aws.services`, else the `localstack:` list, else the defaults; empty means all of them
+pub fn services(cloud: Option<&CloudConfig>) -> Vec<String> {
+    let declared = cloud.and_then(|cloud| match (&cloud.aws, &cloud.localstack) {
+        (Some(aws), _) if !aws.services.is_empty() => Some(aws.services.clone()),
+        (_, Some(list)) if list.trim() == "full" => Some(Vec::new()),
+        (_, Some(list)) => Some(list.split(',').map(str::trim).filter(|service| !service.is_empty()).map(str::to_string).collect()),
+        _ => None,
+    });
+    declared.unwrap_or_else(|| DEFAULT_SERVICES.iter().map(|service| service.to_string()).collect())
+}
+
+/// Region clients and the emulator default to, from `aws.region`
+pub fn region(cloud: Option<&CloudConfig>) -> &str {
+    cloud.and_then(|cloud| cloud.aws.as_ref())
+        .and_then(|aws| aws.region.as_deref())
+        .unwrap_or(DEFAULT_REGION)
+}
+
+/// Environment of the LocalStack container
+pub fn container_env(services: &[String], region: &str, auth_token: Option<&str>) -> Vec<String> {
+    let mut env = vec![
+        format!("AWS_DEFAULT_REGION={}", region),
+        "DEBUG=1".to_string(),
+        // Keep service state in STATE_DIR across restarts (a LocalStack Pro feature)
+        "PERSISTENCE=1".to_string(),
+    ];
+    if !services.is_empty() {
+        env.insert(0, format!("SERVICES={}", services.join(",")));
+    }
+    if let Some(token) = auth_token {
+        env.push(format!("{}={}", AUTH_TOKEN_VAR, token));
+    }
+    env
+}
 
-pub fn get_endpoint_url() -> String {
-    format!("http://localhost:{}", DEFAULT_PORT)
+pub fn get_endpoint_url(port: u16) -> String {
+    format!("http://localhost:{}", port)
 }
 
-pub fn get_aws_config_snippet() -> String {
+pub fn get_aws_config_snippet(region: &str, port: u16) -> String {
     format!(
         r#"
 # AWS Configuration for LocalStack
 export AWS_ACCESS_KEY_ID=test
 export AWS_SECRET_ACCESS_KEY=test
-export AWS_DEFAULT_REGION=us-east-1
-export AWS_ENDPOINT_URL={}
+export AWS_DEFAULT_REGION={region}
+export AWS_ENDPOINT_URL={endpoint}
 
 # Or in your code:
 # aws_config = {{
-#     'endpoint_url': '{}',
+#     'endpoint_url': '{endpoint}',
 #     'aws_access_key_id': 'test',
 #     'aws_secret_access_key': 'test',
-#     'region_name': 'us-east-1'
+#     'region_name': '{region}'
 # }}
 "#,
-        get_endpoint_url(),
-        get_endpoint_url()
+        region = region,
+        endpoint = get_endpoint_url(port),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_services_and_region_from_config() {
+        let cloud: CloudConfig = serde_yaml::from_str("aws:\n  services: [s3, sqs]\n  region: eu-west-1\n").unwrap();
+        assert_eq!(services(Some(&cloud)), ["s3", "sqs"]);
+        assert_eq!(region(Some(&cloud)), "eu-west-1");
+        assert_eq!(container_env(&services(Some(&cloud)), region(Some(&cloud)), None)[..2],
+            ["SERVICES=s3,sqs", "AWS_DEFAULT_REGION=eu-west-1"]);
+
+        let full: CloudConfig = serde_yaml::from_str("localstack: full\n").unwrap();
+        assert!(services(Some(&full)).is_empty());
+        assert!(!container_env(&[], DEFAULT_REGION, None).iter().any(|var| var.starts_with("SERVICES=")));
+        assert_eq!(services(None).len(), DEFAULT_SERVICES.len());
+        assert_eq!(region(None), DEFAULT_REGION);
+    }
+
+    #[test]
+    fn test_image_and_port() {
+        let pinned: CloudConfig = serde_yaml::from_str("aws:\n  services: [s3]\n  port: 4567\n  version: \"3.8\"\n").unwrap();
+        assert_eq!(port(Some(&pinned)), 4567);
+        assert_eq!(image(Some(&pinned), None), "localstack/localstack:3.8");
+        assert_eq!(image(None, Some("token")), "localstack/localstack-pro:latest");
+        assert_eq!(container_env(&[], DEFAULT_REGION, Some("token")).last().unwrap(), "LOCALSTACK_AUTH_TOKEN=token");
+    }
+}
@@ -1,34 +1,228 @@
 use anyhow::{Context, Result};
 use bollard::Docker;
-use bollard::container::{Config, CreateContainerOptions, StartContainerOptions};
+use bollard::container::{Config, CreateContainerOptions, NetworkingConfig, StartContainerOptions};
+use bollard::models::EndpointSettings;
 use bollard::image::CreateImageOptions;
+use bollard::volume::CreateVolumeOptions;
 use futures::StreamExt;
+use serde::Serialize;
 use tracing::{info, error};
 
+use crate::config::{AwsFlavor, CloudConfig, GcpFlavor};
+use crate::orchestrator::PROJECT_LABEL;
+
 pub mod localstack;
 pub mod azurite;
 pub mod gcp;
+pub mod dynamodb_local;
+pub mod fake_gcs;
+pub mod readiness;
+pub mod resources;
+pub mod seed;
+pub mod snapshot;
+
+pub use resources::CloudResource;
+
+/// Canonical name of a provider given as any of the names `CloudEmulator::new` accepts
+pub fn canonical_provider(provider: &str) -> Result<&'static str> {
+    match provider {
+        "localstack" | "aws" => Ok("aws"),
+        "azure" | "azurite" => Ok("azure"),
+        "gcp" | "google" => Ok("gcp"),
+        _ => anyhow::bail!("Unsupported cloud provider: {}. Supported: aws, azure, gcp", provider),
+    }
+}
+
+/// Image reference from a configured image and version, falling back to `default`.
+/// A version replaces any tag the image carries; without either the tag is `latest`.
+pub fn image_ref(image: Option<&str>, default: &str, version: Option<&str>) -> String {
+    let image = image.unwrap_or(default);
+    // A colon after the last slash starts a tag; one before it belongs to a registry port
+    let tag_start = image.rfind(':').filter(|colon| !image[*colon..].contains('/'));
+    match (version, tag_start) {
+        (Some(version), Some(colon)) => format!("{}:{}", &image[..colon], version),
+        (Some(version), None) => format!("{}:{}", image, version),
+        (None, Some(_)) => image.to_string(),
+        (None, None) => format!("{}:latest", image),
+    }
+}
+
+/// Name of an emulator container of a project, `{project}_{container}` like the project's services
+pub fn container_name(project: &str, container: &str) -> String {
+    format!("{}_{}", project, container)
+}
+
+/// Name of an emulator data volume of a project, `{project}_{volume}` so it is listed with the project's volumes
+pub fn volume_name(project: &str, volume: &str) -> String {
+    format!("{}_{}", project, volume)
+}
+
+/// Providers with a block in zero.yml's `cloud:`, in the order they start
+pub fn declared_providers(cloud: &CloudConfig) -> Vec<&'static str> {
+    let mut providers = Vec::new();
+    if cloud.aws.is_some() || cloud.localstack.is_some() {
+        providers.push("aws");
+    }
+    if cloud.azure.is_some() {
+        providers.push("azure");
+    }
+    if cloud.gcp.is_some() {
+        providers.push("gcp");
+    }
+    providers
+}
+
+/// State of one provider's emulator
+#[derive(Debug, Clone, Serialize)]
+pub struct CloudStatus {
+    pub provider: String,
+    pub running: bool,
+    pub endpoint: String,
+    /// Name service containers reach it by on the project network
+    pub hostname: String,
+    /// Services the emulator was asked to start; empty for all of them
+    pub services: Vec<String>,
+}
+
+/// Project emulators run for when none is given with [`CloudEmulator::for_project`]
+pub const DEFAULT_PROJECT: &str = "zeroconfig-project";
 
 pub struct CloudEmulator {
     docker: Docker,
     provider: String,
+    project: String,
+    config: Option<CloudConfig>,
+    network: Option<String>,
 }
 
 impl CloudEmulator {
     pub async fn new(provider: String) -> Result<Self> {
-        let docker = Docker::connect_with_local_defaults()
-            .context("Failed to connect to Docker")?;
+        Self::on_engine(provider, crate::orchestrator::docker_client::connect()?).await
+    }
 
+    /// Emulator on the engine a client is connected to, such as [`Engine::docker`](crate::core::Engine::docker)
+    pub async fn on_engine(provider: String, docker: Docker) -> Result<Self> {
         docker.ping().await
             .context("Docker is not running or not accessible")?;
 
-        Ok(Self { docker, provider })
+        Ok(Self { docker, provider, project: DEFAULT_PROJECT.to_string(), config: None, network: None })
+    }
+
+    /// Run the emulator for a project: its containers are named and labelled
+    /// after the project like its services, so projects do not replace each other's
+    pub fn for_project(mut self, project: &str) -> Self {
+        self.project = project.to_string();
+        self
+    }
+
+    /// Start the services and region declared in zero.yml's `cloud:` instead of the defaults
+    pub fn with_config(mut self, config: Option<&CloudConfig>) -> Self {
+        self.config = config.cloned();
+        self
+    }
+
+    /// Join a project's network, where its services reach the emulator as [`hostname`](Self::hostname)
+    pub fn on_network(mut self, network: &str) -> Self {
+        self.network = Some(network.to_string());
+        self
+    }
+
+    /// Emulator `cloud.aws.flavor` asks for
+    pub fn aws_flavor(&self) -> AwsFlavor {
+        self.config.as_ref().and_then(|config| config.aws.as_ref()).map(|aws| aws.flavor).unwrap_or_default()
+    }
+
+    /// Emulator `cloud.gcp.flavor` asks for
+    pub fn gcp_flavor(&self) -> GcpFlavor {
+        self.config.as_ref().and_then(|config| config.gcp.as_ref()).map(|gcp| gcp.flavor).unwrap_or_default()
+    }
+
+    /// Network alias of the emulator's primary container
+    pub fn hostname(&self) -> &'static str {
+        match self.provider.as_str() {
+            "azure" | "azurite" => "azurite",
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => "gcs",
+            "gcp" | "google" => "firestore",
+            _ if self.aws_flavor() == AwsFlavor::DynamodbLocal => "dynamodb",
+            _ => "localstack",
+        }
+    }
+
+    /// Host port of the emulator's primary service, `cloud.<provider>.port` or its default
+    pub fn port(&self) -> u16 {
+        let config = self.config.as_ref();
+        match self.provider.as_str() {
+            "azure" | "azurite" => azurite::port(config),
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => fake_gcs::port(config),
+            "gcp" | "google" => gcp::port(config),
+            _ if self.aws_flavor() == AwsFlavor::DynamodbLocal => dynamodb_local::port(config),
+            _ => localstack::port(config),
+        }
+    }
+
+    /// URL or address applications on the host connect to
+    pub fn endpoint(&self) -> String {
+        match self.provider.as_str() {
+            "azure" | "azurite" => azurite::get_blob_endpoint(self.port()),
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => fake_gcs::get_endpoint_url(self.port()),
+            "gcp" | "google" => gcp::get_firestore_endpoint(self.port()),
+            _ if self.aws_flavor() == AwsFlavor::DynamodbLocal => dynamodb_local::get_endpoint_url(self.port()),
+            _ => localstack::get_endpoint_url(self.port()),
+        }
+    }
+
+    /// Environment for reaching the emulator from the host
+    pub fn config_snippet(&self) -> Result<String> {
+        let config = self.config.as_ref();
+        Ok(match self.provider.as_str() {
+            "azure" | "azurite" => azurite::get_azure_config_snippet(self.port())?,
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => fake_gcs::get_config_snippet(self.port()),
+            "gcp" | "google" => gcp::get_gcp_config_snippet(self.port()),
+            _ if self.aws_flavor() == AwsFlavor::DynamodbLocal => dynamodb_local::get_config_snippet(localstack::region(config), self.port()),
+            _ => localstack::get_aws_config_snippet(localstack::region(config), self.port()),
+        })
+    }
+
+    /// Name of one of the emulator's containers in its project
+    fn container_name(&self, container: &str) -> String {
+        container_name(&self.project, container)
+    }
+
+    /// Create one of the emulator's data volumes, labelled with its project, and return its name
+    async fn data_volume(&self, volume: &str) -> Result<String> {
+        let name = volume_name(&self.project, volume);
+        self.docker
+            .create_volume(CreateVolumeOptions {
+                name: name.clone(),
+                labels: std::collections::HashMap::from([(PROJECT_LABEL.to_string(), self.project.clone())]),
+                ..Default::default()
+            })
+            .await
+            .with_context(|| format!("Failed to create volume {}", name))?;
+        Ok(name)
+    }
+
+    /// Label a container and join it to the project network, if any
+    fn attach_to_project(&self, config: &mut Config<String>) {
+        config.labels = Some(std::collections::HashMap::from([(PROJECT_LABEL.to_string(), self.project.clone())]));
+        let Some(network) = &self.network else { return };
+        if let Some(host_config) = config.host_config.as_mut() {
+            host_config.network_mode = Some(network.clone());
+        }
+        config.networking_config = Some(NetworkingConfig {
+            endpoints_config: std::collections::HashMap::from([(
+                network.clone(),
+                EndpointSettings { aliases: Some(vec![self.hostname().to_string()]), ..Default::default() },
+            )]),
+        });
     }
 
     pub async fn start(&self) -> Result<()> {
         match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() == AwsFlavor::DynamodbLocal => self.start_dynamodb_local().await,
             "localstack" | "aws" => self.start_localstack().await,
             "azure" | "azurite" => self.start_azurite().await,
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => self.start_fake_gcs().await,
             "gcp" | "google" => self.start_gcp_emulators().await,
             _ => anyhow::bail!("Unsupported cloud provider: {}. Supported: aws, azure, gcp", self.provider),
         }
@@ -37,13 +231,14 @@ impl CloudEmulator {
     async fn start_localstack(&self) -> Result<()> {
         info!("Starting LocalStack...");
 
-        let image = "localstack/localstack:latest";
+        let auth_token = localstack::auth_token();
+        let image = localstack::image(self.config.as_ref(), auth_token.as_deref());
 
         // Pull image
         info!("Pulling image: {}", image);
         let mut stream = self.docker.create_image(
             Some(CreateImageOptions {
-                from_image: image,
+                from_image: image.as_str(),
                 ..Default::default()
             }),
             None,
@@ -58,19 +253,19 @@ impl CloudEmulator {
         }
 
         // Create container
-        let container_name = "zeroconfig-localstack";
+        let container_name = self.container_name(localstack::CONTAINER);
+        let container_name = container_name.as_str();
 
         // Stop and remove existing container if it exists
         let _ = self.docker.stop_container(container_name, None).await;
         let _ = self.docker.remove_container(container_name, None).await;
 
-        let config = Config {
-            image: Some(image.to_string()),
-            env: Some(vec![
-                "SERVICES=s3,dynamodb,sqs,sns,lambda,apigateway,cloudformation".to_string(),
-                "DEBUG=1".to_string(),
-                "DATA_DIR=/tmp/localstack/data".to_string(),
-            ]),
+        let services = localstack::services(self.config.as_ref());
+        let region = localstack::region(self.config.as_ref());
+        let data_volume = self.data_volume(localstack::DATA_VOLUME).await?;
+        let mut config = Config {
+            image: Some(image.clone()),
+            env: Some(localstack::container_env(&services, region, auth_token.as_deref())),
             exposed_ports: Some({
                 let mut map = std::collections::HashMap::new();
                 map.insert("4566/tcp".to_string(), std::collections::HashMap::new());
@@ -84,15 +279,17 @@ impl CloudEmulator {
                         "4566/tcp".to_string(),
                         Some(vec![bollard::models::PortBinding {
                             host_ip: Some("0.0.0.0".to_string()),
-                            host_port: Some("4566".to_string()),
+                            host_port: Some(self.port().to_string()),
                         }]),
                     );
                     map
                 }),
+                binds: Some(vec![format!("{}:{}", data_volume, localstack::DATA_DIR)]),
                 ..Default::default()
             }),
             ..Default::default()
         };
+        self.attach_to_project(&mut config);
 
         self.docker
             .create_container(
@@ -110,8 +307,12 @@ impl CloudEmulator {
             .await?;
 
         info!("LocalStack started successfully");
-        println!("✅ LocalStack is running on http://localhost:4566");
-        println!("   Available services: S3, DynamoDB, SQS, SNS, Lambda, API Gateway, CloudFormation");
+        println!("✅ LocalStack is running on {} in {}", self.endpoint(), region);
+        if services.is_empty() {
+            println!("   Available services: all");
+        } else {
+            println!("   Available services: {}", services.join(", "));
+        }
 
         Ok(())
     }
@@ -119,13 +320,14 @@ impl CloudEmulator {
     async fn start_azurite(&self) -> Result<()> {
         info!("Starting Azurite (Azure Storage Emulator)...");
 
-        let image = azurite::DEFAULT_IMAGE;
+        let image = azurite::image(self.config.as_ref());
+        let port_bindings = azurite::port_bindings(self.port())?;
 
         // Pull image
         info!("Pulling image: {}", image);
         let mut stream = self.docker.create_image(
             Some(CreateImageOptions {
-                from_image: image,
+                from_image: image.as_str(),
                 ..Default::default()
             }),
             None,
@@ -140,14 +342,16 @@ impl CloudEmulator {
         }
 
         // Create container
-        let container_name = "zeroconfig-azurite";
+        let container_name = self.container_name(azurite::CONTAINER);
+        let container_name = container_name.as_str();
 
         // Stop and remove existing container if it exists
         let _ = self.docker.stop_container(container_name, None).await;
         let _ = self.docker.remove_container(container_name, None).await;
 
-        let config = Config {
-            image: Some(image.to_string()),
+        let data_volume = self.data_volume(azurite::DATA_VOLUME).await?;
+        let mut config = Config {
+            image: Some(image.clone()),
             exposed_ports: Some({
                 let mut map = std::collections::HashMap::new();
                 map.insert(format!("{}/tcp", azurite::BLOB_PORT), std::collections::HashMap::new());
@@ -158,21 +362,23 @@ impl CloudEmulator {
             host_config: Some(bollard::models::HostConfig {
                 port_bindings: Some({
                     let mut map = std::collections::HashMap::new();
-                    for port in &[azurite::BLOB_PORT, azurite::QUEUE_PORT, azurite::TABLE_PORT] {
+                    for (port, host_port) in port_bindings {
                         map.insert(
                             format!("{}/tcp", port),
                             Some(vec![bollard::models::PortBinding {
                                 host_ip: Some("0.0.0.0".to_string()),
-                                host_port: Some(port.to_string()),
+                                host_port: Some(host_port.to_string()),
                             }]),
                         );
                     }
                     map
                 }),
+                binds: Some(vec![format!("{}:{}", data_volume, azurite::DATA_DIR)]),
                 ..Default::default()
             }),
             ..Default::default()
         };
+        self.attach_to_project(&mut config);
 
         self.docker
             .create_container(
@@ -191,9 +397,10 @@ impl CloudEmulator {
 
         info!("Azurite started successfully");
         println!("✅ Azurite (Azure Storage Emulator) is running");
-        println!("   Blob Storage: {}", azurite::get_blob_endpoint());
-        println!("   Queue Storage: {}", azurite::get_queue_endpoint());
-        println!("   Table Storage: {}", azurite::get_table_endpoint());
+        println!("   Blob Storage: {}", azurite::get_blob_endpoint(self.port()));
+        println!("   Queue Storage: {}", azurite::get_queue_endpoint(self.port())?);
+        println!("   Table Storage: {}", azurite::get_table_endpoint(self.port())?);
+        println!("   Data volume: {}", data_volume);
 
         Ok(())
     }
@@ -203,13 +410,13 @@ impl CloudEmulator {
 
         // For GCP, we'll start individual emulators for each service
         // Starting with Firestore emulator
-        let image = "gcr.io/google.com/cloudsdktool/google-cloud-cli:latest";
+        let image = gcp::image(self.config.as_ref());
 
         // Pull image
         info!("Pulling image: {}", image);
         let mut stream = self.docker.create_image(
             Some(CreateImageOptions {
-                from_image: image,
+                from_image: image.as_str(),
                 ..Default::default()
             }),
             None,
@@ -224,14 +431,15 @@ impl CloudEmulator {
         }
 
         // Create Firestore emulator container
-        let container_name = "zeroconfig-gcp-firestore";
+        let container_name = self.container_name(gcp::FIRESTORE_CONTAINER);
+        let container_name = container_name.as_str();
 
         // Stop and remove existing container if it exists
         let _ = self.docker.stop_container(container_name, None).await;
         let _ = self.docker.remove_container(container_name, None).await;
 
-        let config = Config {
-            image: Some(image.to_string()),
+        let mut config = Config {
+            image: Some(image.clone()),
             cmd: Some(vec![
                 "gcloud".to_string(),
                 "emulators".to_string(),
@@ -251,7 +459,7 @@ impl CloudEmulator {
                         "8080/tcp".to_string(),
                         Some(vec![bollard::models::PortBinding {
                             host_ip: Some("0.0.0.0".to_string()),
-                            host_port: Some("8080".to_string()),
+                            host_port: Some(self.port().to_string()),
                         }]),
                     );
                     map
@@ -260,6 +468,7 @@ impl CloudEmulator {
             }),
             ..Default::default()
         };
+        self.attach_to_project(&mut config);
 
         self.docker
             .create_container(
@@ -277,40 +486,168 @@ impl CloudEmulator {
             .await?;
 
         info!("GCP emulators started successfully");
-        println!("✅ GCP Firestore Emulator is running on {}", gcp::get_firestore_endpoint());
+        println!("✅ GCP Firestore Emulator is running on {}", self.endpoint());
         println!("   Use FIRESTORE_EMULATOR_HOST environment variable");
 
         Ok(())
     }
 
+    /// Start one of the single-service emulators: pull its image, replace any
+    /// previous container, and publish `container_port` on [`port`](Self::port)
+    async fn run_single_container(&self, container: &str, image: &str, cmd: Vec<String>, container_port: u16) -> Result<()> {
+        self.pull(image).await;
+
+        let container_name = self.container_name(container);
+        let container_name = container_name.as_str();
+
+        let _ = self.docker.stop_container(container_name, None).await;
+        let _ = self.docker.remove_container(container_name, None).await;
+
+        let port_key = format!("{}/tcp", container_port);
+        let mut config = Config {
+            image: Some(image.to_string()),
+            cmd: Some(cmd),
+            exposed_ports: Some(std::collections::HashMap::from([(port_key.clone(), std::collections::HashMap::new())])),
+            host_config: Some(bollard::models::HostConfig {
+                port_bindings: Some(std::collections::HashMap::from([(
+                    port_key,
+                    Some(vec![bollard::models::PortBinding {
+                        host_ip: Some("0.0.0.0".to_string()),
+                        host_port: Some(self.port().to_string()),
+                    }]),
+                )])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.attach_to_project(&mut config);
+
+        self.docker
+            .create_container(
+                Some(CreateContainerOptions {
+                    name: container_name,
+                    ..Default::default()
+                }),
+                config,
+            )
+            .await?;
+        self.docker
+            .start_container(container_name, None::<StartContainerOptions<String>>)
+            .await?;
+        Ok(())
+    }
+
+    /// Pull an image, logging rather than failing on errors so a local copy can still be used
+    pub(super) async fn pull(&self, image: &str) {
+        info!("Pulling image: {}", image);
+        let mut stream = self.docker.create_image(
+            Some(CreateImageOptions {
+                from_image: image,
+                ..Default::default()
+            }),
+            None,
+            None,
+        );
+        while let Some(info) = stream.next().await {
+            if let Err(e) = info {
+                error!("Error pulling image: {}", e);
+            }
+        }
+    }
+
+    async fn start_dynamodb_local(&self) -> Result<()> {
+        info!("Starting DynamoDB Local...");
+
+        let image = dynamodb_local::image(self.config.as_ref());
+        let cmd = dynamodb_local::COMMAND.iter().map(|arg| arg.to_string()).collect();
+        self.run_single_container(dynamodb_local::CONTAINER, &image, cmd, dynamodb_local::DEFAULT_PORT).await?;
+
+        info!("DynamoDB Local started successfully");
+        println!("✅ DynamoDB Local is running on {}", self.endpoint());
+        println!("   Available services: dynamodb");
+
+        Ok(())
+    }
+
+    async fn start_fake_gcs(&self) -> Result<()> {
+        info!("Starting fake-gcs-server...");
+
+        let image = fake_gcs::image(self.config.as_ref());
+        self.run_single_container(fake_gcs::CONTAINER, &image, fake_gcs::command(self.port()), fake_gcs::DEFAULT_PORT).await?;
+
+        info!("fake-gcs-server started successfully");
+        println!("✅ fake-gcs-server (Cloud Storage) is running on {}", self.endpoint());
+        println!("   Use STORAGE_EMULATOR_HOST environment variable");
+
+        Ok(())
+    }
+
+    /// Stop the provider's emulator, whichever flavor of it runs
     pub async fn stop(&self) -> Result<()> {
         info!("Stopping cloud emulation...");
 
-        let container_names = match self.provider.as_str() {
-            "localstack" | "aws" => vec!["zeroconfig-localstack"],
-            "azure" | "azurite" => vec!["zeroconfig-azurite"],
-            "gcp" | "google" => vec!["zeroconfig-gcp-firestore", "zeroconfig-gcp-pubsub"],
+        let containers: &[&str] = match self.provider.as_str() {
+            "localstack" | "aws" => &[localstack::CONTAINER, dynamodb_local::CONTAINER],
+            "azure" | "azurite" => &[azurite::CONTAINER],
+            "gcp" | "google" => &[gcp::FIRESTORE_CONTAINER, gcp::PUBSUB_CONTAINER, fake_gcs::CONTAINER],
             _ => return Ok(()),
         };
 
-        for container_name in container_names {
-            let _ = self.docker.stop_container(container_name, None).await;
-            let _ = self.docker.remove_container(container_name, None).await;
+        for container in containers {
+            let container_name = self.container_name(container);
+            let _ = self.docker.stop_container(&container_name, None).await;
+            let _ = self.docker.remove_container(&container_name, None).await;
         }
 
         info!("Cloud emulation stopped");
         Ok(())
     }
 
-    pub async fn status(&self) -> Result<()> {
-        let (container_name, endpoint) = match self.provider.as_str() {
-            "localstack" | "aws" => ("zeroconfig-localstack", "http://localhost:4566".to_string()),
-            "azure" | "azurite" => ("zeroconfig-azurite", azurite::get_blob_endpoint()),
-            "gcp" | "google" => ("zeroconfig-gcp-firestore", gcp::get_firestore_endpoint()),
-            _ => return Ok(()),
+    /// Containers run for the provider, primary container first
+    fn containers(&self) -> Result<Vec<String>> {
+        let containers: &[&str] = match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() == AwsFlavor::DynamodbLocal => &[dynamodb_local::CONTAINER],
+            "localstack" | "aws" => &[localstack::CONTAINER],
+            "azure" | "azurite" => &[azurite::CONTAINER],
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => &[fake_gcs::CONTAINER],
+            "gcp" | "google" => &[gcp::FIRESTORE_CONTAINER, gcp::PUBSUB_CONTAINER],
+            _ => anyhow::bail!("Unsupported cloud provider: {}", self.provider),
         };
+        Ok(containers.iter().map(|container| self.container_name(container)).collect())
+    }
 
-        match self.docker.inspect_container(container_name, None).await {
+    /// Name of the emulator's primary container
+    fn primary_container(&self) -> Result<String> {
+        Ok(self.containers()?.swap_remove(0))
+    }
+
+    /// Whether the emulator runs, where, and with which services
+    pub async fn provider_status(&self) -> Result<CloudStatus> {
+        let services = match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() == AwsFlavor::DynamodbLocal => vec!["dynamodb".to_string()],
+            "localstack" | "aws" => localstack::services(self.config.as_ref()),
+            "azure" | "azurite" => self.config.as_ref()
+                .and_then(|config| config.azure.as_ref())
+                .map_or_else(|| azurite::DEFAULT_SERVICES.iter().map(|service| service.to_string()).collect(),
+                    |azure| azure.services.clone()),
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => vec!["storage".to_string()],
+            "gcp" | "google" => vec!["firestore".to_string()],
+            _ => anyhow::bail!("Unsupported cloud provider: {}", self.provider),
+        };
+        Ok(CloudStatus {
+            provider: self.provider.clone(),
+            running: self.is_running().await?,
+            endpoint: self.endpoint(),
+            hostname: self.hostname().to_string(),
+            services,
+        })
+    }
+
+    pub async fn status(&self) -> Result<()> {
+        let Ok(container_name) = self.primary_container() else { return Ok(()) };
+        let endpoint = self.endpoint();
+
+        match self.docker.inspect_container(&container_name, None).await {
             Ok(info) => {
                 let status = info.state
                     .and_then(|s| s.status)
@@ -334,19 +671,26 @@ impl CloudEmulator {
 
     pub async fn ui(&self) -> Result<()> {
         let url = match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() == AwsFlavor::DynamodbLocal => {
+                println!("DynamoDB Local has no UI; point a DynamoDB client at {}", self.endpoint());
+                return Ok(());
+            },
             "localstack" | "aws" => {
                 println!("Opening LocalStack UI...");
                 println!("Dashboard: https://app.localstack.cloud");
-                "http://localhost:4566/_localstack/health"
+                format!("{}/_localstack/health", self.endpoint())
             },
             "azure" | "azurite" => {
                 println!("Opening Azure Storage Explorer...");
-                println!("Connect using: {}", azurite::get_connection_string());
-                "https://azure.microsoft.com/en-us/products/storage/storage-explorer/"
+                println!("Connect using: {}", azurite::get_connection_string(self.port())?);
+                "https://azure.microsoft.com/en-us/products/storage/storage-explorer/".to_string()
             },
             "gcp" | "google" => {
                 println!("GCP Emulator Information:");
-                println!("Firestore Emulator: {}", gcp::get_firestore_endpoint());
+                match self.gcp_flavor() {
+                    GcpFlavor::FakeGcs => println!("Cloud Storage (fake-gcs-server): {}", self.endpoint()),
+                    GcpFlavor::Sdk => println!("Firestore Emulator: {}", self.endpoint()),
+                }
                 return Ok(());
             },
             _ => return Ok(()),
@@ -356,24 +700,54 @@ impl CloudEmulator {
         #[cfg(target_os = "windows")]
         {
             let _ = std::process::Command::new("cmd")
-                .args(&["/C", "start", url])
+                .args(&["/C", "start", url.as_str()])
                 .spawn();
         }
 
         #[cfg(target_os = "macos")]
         {
             let _ = std::process::Command::new("open")
-                .arg(url)
+                .arg(&url)
                 .spawn();
         }
 
         #[cfg(target_os = "linux")]
         {
             let _ = std::process::Command::new("xdg-open")
-                .arg(url)
+                .arg(&url)
                 .spawn();
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_ref() {
+        assert_eq!(image_ref(None, localstack::DEFAULT_IMAGE, None), "localstack/localstack:latest");
+        assert_eq!(image_ref(None, localstack::DEFAULT_IMAGE, Some("3.8")), "localstack/localstack:3.8");
+        assert_eq!(image_ref(Some("registry:5000/localstack:3.4"), localstack::DEFAULT_IMAGE, None), "registry:5000/localstack:3.4");
+        assert_eq!(image_ref(Some("registry:5000/localstack:3.4"), localstack::DEFAULT_IMAGE, Some("3.8")), "registry:5000/localstack:3.8");
+        assert_eq!(image_ref(Some("registry:5000/localstack"), localstack::DEFAULT_IMAGE, None), "registry:5000/localstack:latest");
+    }
+
+    #[test]
+    fn test_container_name() {
+        assert_eq!(container_name("shop", localstack::CONTAINER), "shop_localstack");
+        assert_ne!(container_name("shop", azurite::CONTAINER), container_name("blog", azurite::CONTAINER));
+        assert_eq!(volume_name("shop", azurite::DATA_VOLUME), "shop_azurite-data");
+    }
+
+    #[test]
+    fn test_azurite_port_bindings() {
+        assert_eq!(azurite::port_bindings(20000).unwrap()[2], (azurite::TABLE_PORT, 20002));
+        assert!(azurite::port_bindings(azurite::MAX_PORT).is_ok());
+        assert!(azurite::port_bindings(azurite::MAX_PORT + 1).is_err());
+        assert_eq!(azurite::get_table_endpoint(20000).unwrap(), "http://127.0.0.1:20002");
+        assert!(azurite::get_queue_endpoint(u16::MAX).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Whether an emulator is ready for requests, not only started
+//!
+//! LocalStack takes many seconds after its container starts to bring its
+//! services up. Each provider is probed from inside its own container:
+//! LocalStack's `/_localstack/health` must list the requested services as
+//! available, Azurite must accept connections on all three of its ports, and
+//! the Firestore emulator must answer the ping on the port it serves gRPC on.
+//! DynamoDB Local must answer an API call, and fake-gcs-server must list buckets.
+
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use super::{azurite, dynamodb_local, fake_gcs, gcp, localstack, CloudEmulator};
+use crate::config::{AwsFlavor, GcpFlavor};
+
+impl CloudEmulator {
+    /// Whether the emulator's container runs and answers its readiness probe
+    pub async fn is_running(&self) -> Result<bool> {
+        if !self.container_running().await? {
+            return Ok(false);
+        }
+        let container = &self.primary_container()?;
+        Ok(match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() == AwsFlavor::DynamodbLocal => {
+                self.exec_in(container, dynamodb_local::api_call("ListTables", "{}")).await.is_ok()
+            }
+            "localstack" | "aws" => {
+                let probe = format!("curl -fs http://localhost:{}/_localstack/health", localstack::DEFAULT_PORT);
+                match self.exec_in(container, vec!["sh".to_string(), "-c".to_string(), probe]).await {
+                    Ok(health) => localstack_ready(&health, &localstack::services(self.config.as_ref())),
+                    Err(_) => false,
+                }
+            }
+            "azure" | "azurite" => {
+                let ports = [azurite::BLOB_PORT, azurite::QUEUE_PORT, azurite::TABLE_PORT];
+                let script = format!(
+                    "const net = require('net'); let open = 0; for (const port of {:?}) \
+                     net.connect(port, '127.0.0.1').on('connect', () => ++open === 3 && process.exit(0)).on('error', () => process.exit(1));",
+                    ports,
+                );
+                self.exec_in(container, vec!["node".to_string(), "-e".to_string(), script]).await.is_ok()
+            }
+            "gcp" | "google" if self.gcp_flavor() == GcpFlavor::FakeGcs => {
+                // The image is Alpine-based, with BusyBox wget but no curl
+                let probe = format!("wget -q -O /dev/null http://localhost:{}/storage/v1/b", fake_gcs::DEFAULT_PORT);
+                self.exec_in(container, vec!["sh".to_string(), "-c".to_string(), probe]).await.is_ok()
+            }
+            "gcp" | "google" => {
+                let probe = format!("curl -fs http://localhost:{}/ | grep -q Ok", gcp::FIRESTORE_PORT);
+                self.exec_in(container, vec!["sh".to_string(), "-c".to_string(), probe]).await.is_ok()
+            }
+            _ => false,
+        })
+    }
+
+    /// Wait until [`is_running`](Self::is_running), failing after `timeout`
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<()> {
+        let started = Instant::now();
+        while !self.is_running().await? {
+            if started.elapsed() > timeout {
+                anyhow::bail!("{} emulator did not become ready within {}s", self.provider, timeout.as_secs());
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        Ok(())
+    }
+
+    /// Whether the emulator's primary container is running, ready or not
+    pub(super) async fn container_running(&self) -> Result<bool> {
+        let container_name = &self.primary_container()?;
+
+        match self.docker.inspect_container(container_name, None).await {
+            Ok(info) => Ok(info.state.and_then(|s| s.running).unwrap_or(false)),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+/// Whether LocalStack's health report lists every requested service, or all
+/// of them when none were requested, as usable
+fn localstack_ready(health: &str, services: &[String]) -> bool {
+    let Ok(health) = serde_json::from_str::<serde_json::Value>(health) else {
+        return false;
+    };
+    let Some(states) = health.get("services").and_then(|services| services.as_object()) else {
+        return false;
+    };
+    let usable = |state: Option<&serde_json::Value>| matches!(state.and_then(|state| state.as_str()), Some("available" | "running"));
+    if services.is_empty() {
+        !states.is_empty() && states.values().all(|state| usable(Some(state)) || state == "disabled")
+    } else {
+        services.iter().all(|service| usable(states.get(service)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localstack_ready() {
+        let health = r#"{"services": {"s3": "running", "sqs": "available", "lambda": "initializing"}, "edition": "community"}"#;
+        assert!(localstack_ready(health, &["s3".to_string(), "sqs".to_string()]));
+        assert!(!localstack_ready(health, &["lambda".to_string()]));
+        assert!(!localstack_ready(health, &["dynamodb".to_string()]));
+        assert!(!localstack_ready(health, &[]));
+        assert!(!localstack_ready("starting", &[]));
+    }
+}
@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use bollard::container::{LogOutput, LogsOptions};
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use super::CloudEmulator;
+use crate::config::AwsFlavor;
+
+/// A resource created inside a cloud emulator, such as an S3 bucket or a queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudResource {
+    /// Emulated service the resource belongs to, e.g. `s3` or `blob`
+    pub service: String,
+    pub name: String,
+}
+
+/// `awslocal` listings run inside the LocalStack container: (service, command)
+const LOCALSTACK_LISTINGS: &[(&str, &str)] = &[
+    ("s3", "awslocal s3api list-buckets --query 'Buckets[].Name' --output json"),
+    ("sqs", "awslocal sqs list-queues --query 'QueueUrls' --output json"),
+    ("sns", "awslocal sns list-topics --query 'Topics[].TopicArn' --output json"),
+    ("dynamodb", "awslocal dynamodb list-tables --query 'TableNames' --output json"),
+];
+
+/// Node script run inside the Azurite container to list blob containers,
+/// queues or tables with the well-known development account key.
+///
+/// Usage: `node -e <script> <blob|queue|table> <port>`; prints a JSON array of names.
+const AZURITE_LIST_SCRIPT: &str = r#"
+const http = require('http');
+const crypto = require('crypto');
+const [service, port] = process.argv.slice(1);
+const account = 'devstoreaccount1';
+const key = Buffer.from('Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==', 'base64');
+const date = new Date().toUTCString();
+const version = '2021-10-04';
+const table = service === 'table';
+const path = table ? `/${account}/Tables` : `/${account}/?comp=list`;
+const toSign = table
+  ? `${date}\n/${account}/${account}/Tables`
+  : `GET\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:${date}\nx-ms-version:${version}\n/${account}/${account}/\ncomp:list`;
+const signature = crypto.createHmac('sha256', key).update(toSign, 'utf8').digest('base64');
+const headers = {
+  'x-ms-date': date,
+  'x-ms-version': version,
+  'Authorization': `${table ? 'SharedKeyLite' : 'SharedKey'} ${account}:${signature}`,
+};
+if (table) headers['Accept'] = 'application/json;odata=nometadata';
+http.get({ host: '127.0.0.1', port, path, headers }, (res) => {
+  let body = '';
+  res.on('data', (chunk) => body += chunk);
+  res.on('end', () => {
+    if (res.statusCode >= 400) { console.error(`HTTP ${res.statusCode}: ${body}`); process.exit(1); }
+    const names = table
+      ? JSON.parse(body).value.map((t) => t.TableName)
+      : [...body.matchAll(/<Name>([^<]*)<\/Name>/g)].map((m) => m[1]);
+    console.log(JSON.stringify(names));
+  });
+}).on('error', (e) => { console.error(e.message); process.exit(1); });
+"#;
+
+impl CloudEmulator {
+    /// List the resources that exist in the emulator
+    ///
+    /// Services that are disabled or not ready are skipped rather than failing
+    /// the whole listing.
+    pub async fn list_resources(&self) -> Result<Vec<CloudResource>> {
+        let container = &self.primary_container()?;
+        if !self.is_running().await? {
+            anyhow::bail!("{} emulator is not running", self.provider);
+        }
+
+        let listings: Vec<(&str, Vec<String>)> = match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() != AwsFlavor::Localstack => {
+                anyhow::bail!("Resource listing is not supported for DynamoDB Local yet")
+            }
+            "localstack" | "aws" => LOCALSTACK_LISTINGS
+                .iter()
+                .map(|(service, command)| (*service, vec!["sh".to_string(), "-c".to_string(), command.to_string()]))
+                .collect(),
+            "azure" | "azurite" => [
+                ("blob", super::azurite::BLOB_PORT),
+                ("queue", super::azurite::QUEUE_PORT),
+                ("table", super::azurite::TABLE_PORT),
+            ]
+            .iter()
+            .map(|(service, port)| {
+                let command = vec![
+                    "node".to_string(),
+                    "-e".to_string(),
+                    AZURITE_LIST_SCRIPT.to_string(),
+                    service.to_string(),
+                    port.to_string(),
+                ];
+                (*service, command)
+            })
+            .collect(),
+            _ => anyhow::bail!("Resource listing is not supported for {} yet", self.provider),
+        };
+
+        let mut resources = Vec::new();
+        for (service, command) in listings {
+            let Ok(output) = self.exec_in(container, command).await else {
+                continue;
+            };
+            resources.extend(parse_names(&output).into_iter().map(|name| CloudResource {
+                service: service.to_string(),
+                name,
+            }));
+        }
+
+        Ok(resources)
+    }
+
+    /// Stream the emulator's container logs, prefixed with the container name
+    /// when the provider runs more than one
+    pub async fn log_stream(&self, follow: bool, tail: usize) -> Result<BoxStream<'static, Result<String>>> {
+        // Not every provider starts all of its containers
+        let mut containers = Vec::new();
+        for container in self.containers()? {
+            if self.docker.inspect_container(&container, None).await.is_ok() {
+                containers.push(container);
+            }
+        }
+        if containers.is_empty() {
+            anyhow::bail!("{} emulator is not running", self.provider);
+        }
+        let prefixed = containers.len() > 1;
+
+        let streams = containers.into_iter().map(|container| {
+            let options = LogsOptions::<String> {
+                follow,
+                stdout: true,
+                stderr: true,
+                tail: tail.to_string(),
+                ..Default::default()
+            };
+            let label = container.strip_prefix(&format!("{}_", self.project)).unwrap_or(&container).to_string();
+            self.docker.logs(&container, Some(options)).map(move |chunk| {
+                let line = chunk.map_err(|e| anyhow::anyhow!("Docker log error: {}", e))?.to_string();
+                Ok(if prefixed { format!("[{}] {}", label, line) } else { line })
+            })
+        });
+
+        Ok(futures::stream::select_all(streams).boxed())
+    }
+
+    /// Run a command in an emulator container and return stdout, failing on a non-zero exit
+    pub(super) async fn exec_in(&self, container: &str, command: Vec<String>) -> Result<String> {
+        let exec = self.docker
+            .create_exec(container, CreateExecOptions {
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                cmd: Some(command),
+                ..Default::default()
+            })
+            .await
+            .context("Failed to create exec")?;
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        if let StartExecResults::Attached { mut output, .. } = self.docker.start_exec(&exec.id, None).await? {
+            while let Some(chunk) = output.next().await {
+                match chunk? {
+                    LogOutput::StdOut { message } => stdout.push_str(&String::from_utf8_lossy(&message)),
+                    LogOutput::StdErr { message } => stderr.push_str(&String::from_utf8_lossy(&message)),
+                    _ => {}
+                }
+            }
+        }
+
+        let inspect = self.docker.inspect_exec(&exec.id).await?;
+        if let Some(code) = inspect.exit_code.filter(|code| *code != 0) {
+            anyhow::bail!("Command exited with code {}: {}", code, stderr.trim());
+        }
+
+        Ok(stdout)
+    }
+}
+
+/// Parse a JSON array of names, treating `null` (AWS CLI for "none") as empty
+fn parse_names(output: &str) -> Vec<String> {
+    let names: Option<Vec<String>> = serde_json::from_str(output.trim()).unwrap_or_default();
+    let mut names = names.unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(parse_names("[\"uploads\", \"avatars\"]\n"), vec!["avatars", "uploads"]);
+        assert!(parse_names("null\n").is_empty());
+        assert!(parse_names("not json").is_empty());
+    }
+}
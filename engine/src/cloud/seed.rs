@@ -0,0 +1,261 @@
+//! Creating the resources zero.yml declares inside a running emulator
+//!
+//! Like the resource listing, seeding runs inside the emulator's own container,
+//! `awslocal` for LocalStack, `curl` for DynamoDB Local and a signed REST call
+//! from Node for Azurite, so
+//! nothing beyond Docker is needed on the host. Every command leaves an
+//! existing resource alone, which makes seeding safe to repeat on each
+//! `zero up`.
+
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tracing::info;
+
+use super::{azurite, dynamodb_local, CloudEmulator, CloudResource};
+use crate::config::{AwsFlavor, AwsResources, AzureResources, DynamoTable};
+
+/// How long seeding waits for the emulator to become ready
+const READY_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Node script run inside the Azurite container to create a blob container,
+/// queue or table with the well-known development account key.
+///
+/// Usage: `node -e <script> <blob|queue|table> <port> <name>`; one that exists already is left alone.
+const AZURITE_CREATE_SCRIPT: &str = r#"
+const http = require('http');
+const crypto = require('crypto');
+const [service, port, name] = process.argv.slice(1);
+const account = 'devstoreaccount1';
+const key = Buffer.from('Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==', 'base64');
+const date = new Date().toUTCString();
+const version = '2021-10-04';
+const table = service === 'table';
+const body = table ? JSON.stringify({ TableName: name }) : '';
+const path = table ? `/${account}/Tables` : `/${account}/${name}` + (service === 'blob' ? '?restype=container' : '');
+const toSign = table
+  ? `${date}\n/${account}/${account}/Tables`
+  : `PUT\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:${date}\nx-ms-version:${version}\n/${account}/${account}/${name}`
+    + (service === 'blob' ? '\nrestype:container' : '');
+const signature = crypto.createHmac('sha256', key).update(toSign, 'utf8').digest('base64');
+const headers = {
+  'x-ms-date': date,
+  'x-ms-version': version,
+  'Content-Length': Buffer.byteLength(body),
+  'Authorization': `${table ? 'SharedKeyLite' : 'SharedKey'} ${account}:${signature}`,
+};
+if (table) Object.assign(headers, { 'Content-Type': 'application/json', 'Accept': 'application/json;odata=nometadata' });
+const req = http.request({ host: '127.0.0.1', port, path, method: table ? 'POST' : 'PUT', headers }, (res) => {
+  let text = '';
+  res.on('data', (chunk) => text += chunk);
+  res.on('end', () => {
+    if (res.statusCode >= 400 && res.statusCode !== 409) { console.error(`HTTP ${res.statusCode}: ${text}`); process.exit(1); }
+  });
+});
+req.on('error', (e) => { console.error(e.message); process.exit(1); });
+req.end(body);
+"#;
+
+impl CloudEmulator {
+    /// Create the resources declared for the provider, returning those it made sure exist
+    pub async fn seed(&self) -> Result<Vec<CloudResource>> {
+        let config = self.config.as_ref();
+        let commands = match self.provider.as_str() {
+            "localstack" | "aws" => match config.and_then(|config| config.aws.as_ref()) {
+                Some(aws) if aws.flavor == AwsFlavor::DynamodbLocal => dynamodb_local_commands(&aws.resources)?,
+                Some(aws) => localstack_commands(&aws.resources)?,
+                None => Vec::new(),
+            },
+            "azure" | "azurite" => match config.and_then(|config| config.azure.as_ref()) {
+                Some(azure) => azurite_commands(&azure.resources),
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        if commands.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let container = &self.primary_container()?;
+        self.wait_until_ready(READY_TIMEOUT).await?;
+
+        let mut seeded = Vec::new();
+        for (resource, command) in commands {
+            info!("Creating {} {}", resource.service, resource.name);
+            self.exec_in(container, command).await
+                .with_context(|| format!("Failed to create {} {}", resource.service, resource.name))?;
+            seeded.push(resource);
+        }
+
+        // Confirm through a fresh listing that each one is there
+        if matches!(self.provider.as_str(), "azure" | "azurite") {
+            let existing = self.list_resources().await?;
+            if let Some(missing) = seeded.iter().find(|resource| !existing.contains(resource)) {
+                anyhow::bail!("{} {} was created but is not listed by Azurite", missing.service, missing.name);
+            }
+        }
+        Ok(seeded)
+    }
+}
+
+/// Node invocations creating each blob container, queue and table
+fn azurite_commands(resources: &AzureResources) -> Vec<(CloudResource, Vec<String>)> {
+    let services = [
+        ("blob", azurite::BLOB_PORT, &resources.containers),
+        ("queue", azurite::QUEUE_PORT, &resources.queues),
+        ("table", azurite::TABLE_PORT, &resources.tables),
+    ];
+    services.into_iter()
+        .flat_map(|(service, port, names)| names.iter().map(move |name| {
+            let resource = CloudResource { service: service.to_string(), name: name.clone() };
+            let command = vec![
+                "node".to_string(),
+                "-e".to_string(),
+                AZURITE_CREATE_SCRIPT.to_string(),
+                service.to_string(),
+                port.to_string(),
+                name.clone(),
+            ];
+            (resource, command)
+        }))
+        .collect()
+}
+
+/// `awslocal` commands creating each resource unless it exists
+fn localstack_commands(resources: &AwsResources) -> Result<Vec<(CloudResource, Vec<String>)>> {
+    let resource = |service: &str, name: &str| CloudResource { service: service.to_string(), name: name.to_string() };
+    let mut commands = Vec::new();
+
+    for bucket in &resources.buckets {
+        let bucket_arg = quote(bucket);
+        commands.push((resource("s3", bucket), format!(
+            "awslocal s3api head-bucket --bucket {bucket} >/dev/null 2>&1 || awslocal s3 mb s3://{bucket}",
+            bucket = bucket_arg,
+        )));
+    }
+    for queue in &resources.queues {
+        let fifo = if queue.ends_with(".fifo") { " --attributes FifoQueue=true" } else { "" };
+        commands.push((resource("sqs", queue), format!("awslocal sqs create-queue --queue-name {}{}", quote(queue), fifo)));
+    }
+    for topic in &resources.topics {
+        let fifo = if topic.ends_with(".fifo") { " --attributes FifoTopic=true" } else { "" };
+        commands.push((resource("sns", topic), format!("awslocal sns create-topic --name {}{}", quote(topic), fifo)));
+    }
+    for table in &resources.tables {
+        commands.push((resource("dynamodb", &table.name), create_table(table)?));
+    }
+
+    // The commands print what they created; only failures matter
+    Ok(commands.into_iter()
+        .map(|(resource, command)| (resource, vec!["sh".to_string(), "-c".to_string(), format!("{{ {}; }} >/dev/null", command)]))
+        .collect())
+}
+
+/// DynamoDB API calls creating each table unless it exists; DynamoDB Local
+/// has no other resources
+fn dynamodb_local_commands(resources: &AwsResources) -> Result<Vec<(CloudResource, Vec<String>)>> {
+    let mut commands = Vec::new();
+    for table in &resources.tables {
+        let mut definitions = Vec::new();
+        let mut schema = Vec::new();
+        for (attribute, attribute_type, key_type) in table_keys(table)? {
+            definitions.push(serde_json::json!({ "AttributeName": attribute, "AttributeType": attribute_type }));
+            schema.push(serde_json::json!({ "AttributeName": attribute, "KeyType": key_type }));
+        }
+        let create = serde_json::json!({
+            "TableName": table.name,
+            "AttributeDefinitions": definitions,
+            "KeySchema": schema,
+            "BillingMode": "PAY_PER_REQUEST",
+        });
+        let describe = serde_json::json!({ "TableName": table.name });
+
+        let call = |target: &str, body: &serde_json::Value| {
+            dynamodb_local::api_call(target, &body.to_string()).iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")
+        };
+        let command = format!("{} >/dev/null || {} >/dev/null", call("DescribeTable", &describe), call("CreateTable", &create));
+        commands.push((
+            CloudResource { service: "dynamodb".to_string(), name: table.name.clone() },
+            vec!["sh".to_string(), "-c".to_string(), command],
+        ));
+    }
+    Ok(commands)
+}
+
+/// Attribute name, attribute type and key type of each key of a table, hash key first
+fn table_keys(table: &DynamoTable) -> Result<Vec<(&str, &str, &'static str)>> {
+    let mut keys = Vec::new();
+    for (key, key_type) in [(Some(&table.partition_key), "HASH"), (table.sort_key.as_ref(), "RANGE")] {
+        let Some(key) = key else { continue };
+        let (attribute, attribute_type) = DynamoTable::key_attribute(key)
+            .with_context(|| format!("Invalid key of table '{}'", table.name))?;
+        keys.push((attribute, attribute_type, key_type));
+    }
+    Ok(keys)
+}
+
+fn create_table(table: &DynamoTable) -> Result<String> {
+    let mut definitions = Vec::new();
+    let mut schema = Vec::new();
+    for (attribute, attribute_type, key_type) in table_keys(table)? {
+        definitions.push(quote(&format!("AttributeName={},AttributeType={}", attribute, attribute_type)));
+        schema.push(quote(&format!("AttributeName={},KeyType={}", attribute, key_type)));
+    }
+
+    let name = quote(&table.name);
+    Ok(format!(
+        "awslocal dynamodb describe-table --table-name {name} >/dev/null 2>&1 || \
+         awslocal dynamodb create-table --table-name {name} --attribute-definitions {definitions} \
+         --key-schema {schema} --billing-mode PAY_PER_REQUEST",
+        name = name,
+        definitions = definitions.join(" "),
+        schema = schema.join(" "),
+    ))
+}
+
+/// Single-quote a value for `sh -c`
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localstack_commands() {
+        let resources: AwsResources = serde_yaml::from_str(
+            "buckets: [uploads]\nqueues: [jobs.fifo]\ntables:\n  - name: orders\n    partition_key: id\n    sort_key: created_at:N\n",
+        ).unwrap();
+        let commands = localstack_commands(&resources).unwrap();
+        let names: Vec<(&str, &str)> = commands.iter().map(|(resource, _)| (resource.service.as_str(), resource.name.as_str())).collect();
+        assert_eq!(names, [("s3", "uploads"), ("sqs", "jobs.fifo"), ("dynamodb", "orders")]);
+
+        assert_eq!(commands[1].1[2], "{ awslocal sqs create-queue --queue-name 'jobs.fifo' --attributes FifoQueue=true; } >/dev/null");
+        assert!(commands[2].1[2].contains(
+            "--attribute-definitions 'AttributeName=id,AttributeType=S' 'AttributeName=created_at,AttributeType=N' \
+             --key-schema 'AttributeName=id,KeyType=HASH' 'AttributeName=created_at,KeyType=RANGE'"
+        ));
+
+        let invalid: AwsResources = serde_yaml::from_str("tables:\n  - name: orders\n    partition_key: id:X\n").unwrap();
+        assert!(localstack_commands(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_dynamodb_local_commands() {
+        let resources: AwsResources = serde_yaml::from_str("tables:\n  - name: orders\n    partition_key: id\n").unwrap();
+        let commands = dynamodb_local_commands(&resources).unwrap();
+        assert_eq!(commands.len(), 1);
+        let command = &commands[0].1[2];
+        assert!(command.contains("'X-Amz-Target: DynamoDB_20120810.DescribeTable'"));
+        assert!(command.contains(r#"'{"AttributeDefinitions":[{"AttributeName":"id","AttributeType":"S"}],"BillingMode":"PAY_PER_REQUEST","KeySchema":[{"AttributeName":"id","KeyType":"HASH"}],"TableName":"orders"}'"#));
+    }
+
+    #[test]
+    fn test_azurite_commands() {
+        let resources: AzureResources = serde_yaml::from_str("containers: [uploads]\ntables: [orders]\n").unwrap();
+        let commands = azurite_commands(&resources);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].0, CloudResource { service: "blob".to_string(), name: "uploads".to_string() });
+        assert_eq!(commands[1].1[3..], ["table", "10002", "orders"]);
+    }
+}
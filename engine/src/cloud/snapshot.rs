@@ -0,0 +1,260 @@
+//! Snapshots of the data kept by cloud emulators
+//!
+//! LocalStack and Azurite keep their data in a named volume per project. A
+//! snapshot is a gzipped tar holding a manifest and, per provider, the archive
+//! Docker exports of the project's data directory. The emulator is stopped
+//! while its data is exported so everything it holds in memory is flushed
+//! first. Restoring replaces the project's volume with the archive through a
+//! container that is created but never started, so teammates can load a
+//! snapshot before their emulators have ever run. Volumes of other projects
+//! are never read or replaced.
+
+use anyhow::{Context, Result};
+use bollard::container::{
+    Config, CreateContainerOptions, DownloadFromContainerOptions, RemoveContainerOptions, StartContainerOptions,
+    UploadToContainerOptions,
+};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::{azurite, localstack, CloudEmulator};
+use crate::config::AwsFlavor;
+use crate::orchestrator::PROJECT_LABEL;
+use crate::orchestrator::sidecar::{tar_entry, tar_finish};
+
+/// Directory snapshots are written to and looked up in by name
+pub const DEFAULT_DIR: &str = "./cloud-snapshots";
+
+const EXTENSION: &str = "tar.gz";
+const MANIFEST_FILE: &str = "snapshot.json";
+
+/// A provider and the archive of its data directory
+pub type ProviderArchive = (String, Vec<u8>);
+
+/// What a snapshot holds, stored alongside the data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Providers with data in the snapshot
+    pub providers: Vec<String>,
+}
+
+/// Where an emulator keeps the data a snapshot captures
+struct DataLocation {
+    /// Volume within the project, see [`volume_name`](super::volume_name)
+    volume: &'static str,
+    /// Where the volume is mounted
+    mount: &'static str,
+    /// Directory exported, within `mount`
+    path: &'static str,
+}
+
+impl CloudEmulator {
+    /// Whether the emulator keeps data in a volume that snapshots capture
+    pub fn keeps_data(&self) -> bool {
+        self.data_location().is_some()
+    }
+
+    /// LocalStack only persists its state with an auth token (LocalStack Pro)
+    fn data_location(&self) -> Option<DataLocation> {
+        match self.provider.as_str() {
+            "localstack" | "aws" if self.aws_flavor() == AwsFlavor::Localstack && localstack::auth_token().is_some() => Some(DataLocation {
+                volume: localstack::DATA_VOLUME,
+                mount: localstack::DATA_DIR,
+                path: localstack::STATE_DIR,
+            }),
+            "azure" | "azurite" => Some(DataLocation {
+                volume: azurite::DATA_VOLUME,
+                mount: azurite::DATA_DIR,
+                path: azurite::DATA_DIR,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Image the emulator's primary container runs
+    fn image(&self) -> String {
+        match self.provider.as_str() {
+            "azure" | "azurite" => azurite::image(self.config.as_ref()),
+            _ => localstack::image(self.config.as_ref(), localstack::auth_token().as_deref()),
+        }
+    }
+
+    /// Archive of the emulator's data directory, exported while the emulator is
+    /// briefly stopped
+    pub async fn export_data(&self) -> Result<Vec<u8>> {
+        let location = self.data_location()
+            .with_context(|| format!("The {} emulator keeps no data to snapshot", self.provider))?;
+        let container = &self.primary_container()?;
+        if !self.container_running().await? {
+            anyhow::bail!("The {} emulator is not running; start it with zero cloud start {}", self.provider, self.provider);
+        }
+
+        self.docker.stop_container(container, None).await
+            .with_context(|| format!("Failed to stop {}", container))?;
+        let mut archive = Vec::new();
+        let mut stream = self.docker.download_from_container(container, Some(DownloadFromContainerOptions { path: location.path }));
+        let mut downloaded = Ok(());
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(bytes) => archive.extend_from_slice(&bytes),
+                Err(e) => {
+                    downloaded = Err(e);
+                    break;
+                }
+            }
+        }
+        self.docker.start_container(container, None::<StartContainerOptions<String>>).await
+            .with_context(|| format!("Failed to restart {}", container))?;
+
+        downloaded.with_context(|| format!("Failed to export {} from {}", location.path, container))?;
+        Ok(archive)
+    }
+
+    /// Replace the emulator's data volume with an archive from [`export_data`](Self::export_data).
+    /// The emulator must be stopped; it loads the data when started again.
+    pub async fn import_data(&self, archive: Vec<u8>) -> Result<()> {
+        let location = self.data_location()
+            .with_context(|| format!("The {} emulator keeps no data to restore", self.provider))?;
+        if self.container_running().await? {
+            anyhow::bail!("Stop the {} emulator before restoring its data", self.provider);
+        }
+
+        let volume = super::volume_name(&self.project, location.volume);
+        if let Ok(existing) = self.docker.inspect_volume(&volume).await {
+            match existing.labels.get(PROJECT_LABEL) {
+                Some(project) if *project != self.project => {
+                    anyhow::bail!("Volume {} belongs to project {}, not {}; not replacing it", volume, project, self.project)
+                }
+                _ => {}
+            }
+            self.docker.remove_volume(&volume, None).await
+                .with_context(|| format!("Failed to remove volume {}", volume))?;
+        }
+        let volume = self.data_volume(location.volume).await?;
+
+        let image = self.image();
+        self.pull(&image).await;
+        let loader = format!("{}-restore", self.primary_container()?);
+        let _ = self.docker.remove_container(&loader, None).await;
+        let config = Config {
+            image: Some(image),
+            labels: Some(std::collections::HashMap::from([(PROJECT_LABEL.to_string(), self.project.clone())])),
+            host_config: Some(bollard::models::HostConfig {
+                binds: Some(vec![format!("{}:{}", volume, location.mount)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        self.docker
+            .create_container(Some(CreateContainerOptions { name: loader.as_str(), ..Default::default() }), config)
+            .await
+            .with_context(|| format!("Failed to create {}", loader))?;
+
+        // The archive's entries start with the exported directory's own name
+        let parent = Path::new(location.path).parent().and_then(Path::to_str).unwrap_or("/");
+        let uploaded = self.docker
+            .upload_to_container(&loader, Some(UploadToContainerOptions { path: parent, ..Default::default() }), archive.into())
+            .await
+            .with_context(|| format!("Failed to copy the snapshot into {}", volume));
+        let _ = self.docker
+            .remove_container(&loader, Some(RemoveContainerOptions { force: true, ..Default::default() }))
+            .await;
+        uploaded
+    }
+}
+
+/// Path of a snapshot given by name, or by its file path as shared by a teammate
+pub fn snapshot_path(name: &str) -> PathBuf {
+    if name.ends_with(EXTENSION) || name.contains(std::path::MAIN_SEPARATOR) || name.contains('/') {
+        PathBuf::from(name)
+    } else {
+        Path::new(DEFAULT_DIR).join(format!("{}.{}", name, EXTENSION))
+    }
+}
+
+/// Write a snapshot holding each provider's data archive
+pub fn write_snapshot(path: &Path, manifest: &SnapshotManifest, archives: &[ProviderArchive]) -> Result<()> {
+    let mut tar = Vec::new();
+    tar_entry(&mut tar, MANIFEST_FILE, 0o644, 0, &serde_json::to_vec_pretty(manifest)?)?;
+    for (provider, archive) in archives {
+        tar_entry(&mut tar, &format!("{}.tar", provider), 0o644, 0, archive)?;
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&tar_finish(tar))?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, encoder.finish()?).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read a snapshot back into its manifest and each provider's data archive
+pub fn read_snapshot(path: &Path) -> Result<(SnapshotManifest, Vec<ProviderArchive>)> {
+    let compressed = std::fs::read(path).with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+    let mut tar = Vec::new();
+    GzDecoder::new(compressed.as_slice()).read_to_end(&mut tar)
+        .with_context(|| format!("{} is not a cloud snapshot", path.display()))?;
+
+    let mut manifest = None;
+    let mut archives = Vec::new();
+    for (name, content) in tar_files(&tar)? {
+        if name == MANIFEST_FILE {
+            manifest = Some(serde_json::from_slice::<SnapshotManifest>(content).context("Invalid snapshot manifest")?);
+        } else if let Some(provider) = name.strip_suffix(".tar") {
+            archives.push((provider.to_string(), content.to_vec()));
+        }
+    }
+    let manifest = manifest.with_context(|| format!("{} has no {}", path.display(), MANIFEST_FILE))?;
+    Ok((manifest, archives))
+}
+
+/// Names and contents of the files in an archive written by [`tar_entry`]
+fn tar_files(tar: &[u8]) -> Result<Vec<(String, &[u8])>> {
+    let mut files = Vec::new();
+    let mut offset = 0;
+    while offset + 512 <= tar.len() && tar[offset..offset + 512].iter().any(|&byte| byte != 0) {
+        let header = &tar[offset..offset + 512];
+        let field = |range: std::ops::Range<usize>| String::from_utf8_lossy(&header[range]).trim_end_matches('\0').to_string();
+        let size = usize::from_str_radix(field(124..135).trim(), 8).context("Corrupt snapshot archive")?;
+        let start = offset + 512;
+        let end = start.checked_add(size).context("Corrupt snapshot archive")?;
+        let content = tar.get(start..end).context("Truncated snapshot archive")?;
+        files.push((field(0..100), content));
+        offset = size.div_ceil(512).checked_mul(512)
+            .and_then(|padded| start.checked_add(padded))
+            .context("Corrupt snapshot archive")?;
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshots").join("seeded.tar.gz");
+        let manifest = SnapshotManifest {
+            name: "seeded".to_string(),
+            created_at: Utc::now(),
+            providers: vec!["aws".to_string(), "azure".to_string()],
+        };
+        let archives = vec![("aws".to_string(), vec![7u8; 700]), ("azure".to_string(), b"data/".to_vec())];
+        write_snapshot(&path, &manifest, &archives).unwrap();
+
+        let (read, read_archives) = read_snapshot(&path).unwrap();
+        assert_eq!(read.providers, manifest.providers);
+        assert_eq!(read_archives, archives);
+
+        assert_eq!(snapshot_path("seeded"), Path::new(DEFAULT_DIR).join("seeded.tar.gz"));
+        assert_eq!(snapshot_path("shared/seeded.tar.gz"), PathBuf::from("shared/seeded.tar.gz"));
+    }
+}
@@ -1,12 +1,17 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
+use futures::StreamExt;
 use tracing::{info, warn};
-use crate::cli::{CloudCommands, GenerateCommands};
-use crate::config::ZeroConfig;
+use crate::cli::{terminal, CloudCommands, GenerateCommands, OutputFormat, ProxyCommands, SecretsCommands};
+use crate::config::{edit, lint, migrate, Severity, ZeroConfig};
 use crate::core::Engine;
-use crate::{cloud, runtime, secrets, generators, health};
+use crate::doctor::{self, CheckCategory, CheckStatus, DoctorAction, DoctorReport};
+use crate::logs::LogFilter;
+use crate::orchestrator::{ContainerOrchestrator, KubernetesOrchestrator};
+use crate::runtime::ContainerRuntime;
+use crate::{backup, cloud, runtime, secrets, generators, health, metrics, proxy, tunnels};
 
-pub async fn init(name: Option<String>, template: Option<String>) -> Result<()> {
+pub async fn init(name: Option<String>, template: Option<String>, auto: bool, yes: bool) -> Result<()> {
     println!("{}", "🚀 Initializing ZeroConfig project...".cyan().bold());
 
     let project_name = name.unwrap_or_else(|| {
@@ -18,6 +23,10 @@ pub async fn init(name: Option<String>, template: Option<String>) -> Result<()>
 
     println!("Project name: {}", project_name.green());
 
+    if auto {
+        return init_auto(&project_name, yes);
+    }
+
     // Generate zero.yml based on template
     let config_content = generate_template(template.as_deref());
 
@@ -33,7 +42,14 @@ pub async fn init(name: Option<String>, template: Option<String>) -> Result<()>
     Ok(())
 }
 
-pub async fn up(build: bool, detach: bool) -> Result<()> {
+pub async fn up(
+    build: bool,
+    detach: bool,
+    wait: bool,
+    wait_timeout: std::time::Duration,
+    pull: crate::orchestrator::PullPolicy,
+    runtime: ContainerRuntime,
+) -> Result<()> {
     println!("{}", "🚀 Starting development environment...".cyan().bold());
 
     let config = match ZeroConfig::discover()? {
@@ -46,11 +62,22 @@ pub async fn up(build: bool, detach: bool) -> Result<()> {
 
     config.validate()?;
 
+    if runtime.is_kubernetes_compatible() {
+        return up_kubernetes(config, runtime, detach, wait_timeout).await;
+    }
+    if runtime != ContainerRuntime::Docker {
+        anyhow::bail!("zero up runs on docker, kubernetes or minikube, not {}", runtime.name());
+    }
+
     let project_name = config.metadata.name
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
     let mut engine = Engine::new(project_name, config).await?;
+    engine.set_pull_policy(pull);
+    if std::io::IsTerminal::is_terminal(&std::io::stderr()) {
+        engine.show_pull_progress(indicatif::MultiProgress::new());
+    }
 
     if build {
         println!("{}", "🔨 Building environment...".yellow());
@@ -60,11 +87,41 @@ pub async fn up(build: bool, detach: bool) -> Result<()> {
     println!("{}", "🔄 Starting services...".yellow());
     engine.start().await?;
 
+    if wait || engine.config().wait {
+        println!("{}", "⏳ Waiting for services to become healthy...".yellow());
+        wait_until_healthy(&engine, wait_timeout).await?;
+    }
+
     println!("{}", "✅ Environment is ready!".green().bold());
 
+    for status in engine.cloud_status().await.unwrap_or_default() {
+        println!("☁️  {} emulator: {}", status.provider, status.endpoint.cyan());
+    }
+    if engine.config().observability {
+        if let Ok(port) = engine.get_host_port("grafana").await {
+            println!("📈 Grafana: {}", format!("http://localhost:{}", port).cyan());
+        }
+    }
+
     if !detach {
+        let metrics_address = engine.config().metrics_address().map(|address| metrics::parse_address(&address)).transpose()?;
+        if let Some(address) = metrics_address {
+            println!("📊 Metrics: {}", format!("http://{}/metrics", address).cyan());
+        }
         println!("\nPress {} to stop", "Ctrl+C".yellow());
-        tokio::signal::ctrl_c().await?;
+        // The environment keeps running if metrics cannot be served, e.g. with the port taken
+        let serve_metrics = async {
+            if let Some(address) = metrics_address {
+                if let Err(e) = metrics::serve(&engine, address).await {
+                    warn!("{:#}", e);
+                }
+            }
+            std::future::pending::<()>().await
+        };
+        tokio::select! {
+            _ = serve_metrics => {}
+            result = tokio::signal::ctrl_c() => result?,
+        }
         println!("\n{}", "🛑 Stopping services...".yellow());
         engine.stop().await?;
     }
@@ -72,7 +129,149 @@ pub async fn up(build: bool, detach: bool) -> Result<()> {
     Ok(())
 }
 
-pub async fn down(_volumes: bool) -> Result<()> {
+/// Deploy the services to a cluster, port-forwarding them until Ctrl+C unless detached
+async fn up_kubernetes(config: ZeroConfig, runtime: ContainerRuntime, detach: bool, timeout: std::time::Duration) -> Result<()> {
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+    let mut cluster = KubernetesOrchestrator::new(project_name, &config, runtime).await?;
+
+    println!("{} {}", "☸️  Deploying to namespace".yellow(), cluster.namespace().cyan());
+    cluster.build_images(&config).await?;
+    let manifests = cluster.manifests(&config).await?;
+    cluster.apply(&manifests).await?;
+
+    println!("{}", "⏳ Waiting for deployments to roll out...".yellow());
+    cluster.wait_ready(&config, timeout).await?;
+    println!("{}", "✅ Environment is ready!".green().bold());
+
+    let forwards = cluster.forwards(&config)?;
+    if detach {
+        println!("\nReach services from the host with:");
+        for forward in &forwards {
+            println!("  {}", cluster.port_forward_command(forward).cyan());
+        }
+        return Ok(());
+    }
+
+    let mut children = Vec::new();
+    for forward in &forwards {
+        children.push(cluster.port_forward(forward)?);
+        println!("  {} → {}", forward.service.bold(), format!("localhost:{}", forward.host_port).cyan());
+    }
+
+    println!("\nPress {} to stop", "Ctrl+C".yellow());
+    tokio::signal::ctrl_c().await?;
+    drop(children);
+    println!("\n{}", "🛑 Stopping services...".yellow());
+    cluster.delete(false).await
+}
+
+/// Poll every service until it is healthy, with a spinner per service
+///
+/// On timeout the last log lines of each unhealthy service are printed.
+async fn wait_until_healthy(engine: &Engine, timeout: std::time::Duration) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    let interactive = std::io::stdout().is_terminal();
+    let start = std::time::Instant::now();
+
+    let services = engine.config().start_order()?;
+    let mut status: Vec<ServiceHealth> = services.into_iter().map(|service| (service, None, false)).collect();
+
+    let health_checker = engine.health_checker();
+    let mut frames = FRAMES.iter().cycle();
+    let check = |service: String| {
+        let health_checker = &health_checker;
+        async move {
+            let check = engine.check_health_with(health_checker, &service).await;
+            if !interactive && check.as_ref().is_ok_and(|status| status.is_healthy) {
+                println!("  {} {} is healthy ({:.1}s)", "✅".green(), service, start.elapsed().as_secs_f32());
+            }
+            check
+        }
+    };
+    let show = |status: &[ServiceHealth], last_round: bool| -> Result<()> {
+        if !interactive {
+            return Ok(());
+        }
+        let frame = frames.next().unwrap_or(&FRAMES[0]);
+        for (service, last, healthy) in status {
+            let line = match (healthy, last) {
+                (true, _) => format!("  ✅ {}", service.green()),
+                (false, Some(last)) => format!("  {} {} {}", frame.cyan(), service, last.status_message.dimmed()),
+                (false, None) => format!("  {} {} {}", frame.cyan(), service, "starting".dimmed()),
+            };
+            println!("\x1b[2K{}", line);
+        }
+        if !last_round {
+            print!("\x1b[{}A", status.len());
+            std::io::stdout().flush()?;
+        }
+        Ok(())
+    };
+    poll_health(&mut status, timeout, std::time::Duration::from_millis(500), check, show).await?;
+
+    let unhealthy: Vec<_> = status.iter().filter(|(_, _, healthy)| !*healthy).collect();
+    if unhealthy.is_empty() {
+        return Ok(());
+    }
+
+    for (service, last, _) in &unhealthy {
+        let reason = last.as_ref().map_or("no status", |last| last.status_message.as_str());
+        println!("\n{} {} is not healthy: {}", "❌".red(), service.bold(), reason);
+        match engine.get_logs_as_string(service, 20, &LogFilter::new()).await {
+            Ok(logs) => {
+                for line in logs.lines() {
+                    println!("    {}", line.dimmed());
+                }
+            }
+            Err(e) => println!("    {}", format!("(no logs: {})", e).dimmed()),
+        }
+    }
+
+    let names: Vec<&str> = unhealthy.iter().map(|(service, _, _)| service.as_str()).collect();
+    anyhow::bail!("Timed out after {}s waiting for {} to become healthy", timeout.as_secs(), names.join(", "))
+}
+
+/// A service, its last health status and whether it became healthy
+type ServiceHealth = (String, Option<health::HealthStatus>, bool);
+
+/// Check the services that are not healthy yet every `interval`, until all are or `timeout` passes
+///
+/// `show` gets the status after each round and whether it was the last.
+async fn poll_health<F, Fut>(
+    status: &mut [ServiceHealth],
+    timeout: std::time::Duration,
+    interval: std::time::Duration,
+    mut check: F,
+    mut show: impl FnMut(&[ServiceHealth], bool) -> Result<()>,
+) -> Result<()>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<health::HealthStatus>>,
+{
+    let start = std::time::Instant::now();
+    loop {
+        for (service, last, healthy) in status.iter_mut().filter(|(_, _, healthy)| !*healthy) {
+            let check = check(service.clone()).await;
+            *healthy = check.as_ref().is_ok_and(|status| status.is_healthy);
+            if let Ok(check) = check {
+                *last = Some(check);
+            }
+        }
+
+        let done = status.iter().all(|(_, _, healthy)| *healthy) || start.elapsed() > timeout;
+        show(status, done)?;
+        if done {
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+pub async fn down(volumes: bool, runtime: ContainerRuntime) -> Result<()> {
     println!("{}", "🛑 Stopping development environment...".yellow().bold());
 
     let config = match ZeroConfig::discover()? {
@@ -87,7 +286,13 @@ pub async fn down(_volumes: bool) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
-    let engine = Engine::new(project_name, config).await?;
+    if runtime.is_kubernetes_compatible() {
+        KubernetesOrchestrator::new(project_name, &config, runtime).await?.delete(volumes).await?;
+        println!("{}", "✅ Environment stopped".green());
+        return Ok(());
+    }
+
+    let mut engine = Engine::new(project_name, config).await?;
     engine.stop().await?;
 
     println!("{}", "✅ Environment stopped".green());
@@ -149,7 +354,7 @@ pub async fn stop_service(service: String) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
-    let engine = Engine::new(project_name, config).await?;
+    let mut engine = Engine::new(project_name, config).await?;
 
     // Stop only the specified service
     engine.stop_service(&service).await?;
@@ -184,105 +389,120 @@ pub async fn build_env() -> Result<()> {
     Ok(())
 }
 
-pub async fn doctor() -> Result<()> {
-    println!("{}", "🩺 Running system diagnostics...".cyan().bold());
-    println!();
+pub async fn doctor(fix: bool, output: OutputFormat) -> Result<()> {
+    let json = output.is_json();
+    if !json {
+        println!("{}", "🩺 Running system diagnostics...".cyan().bold());
+        println!();
+    }
 
-    let config = match ZeroConfig::discover()? {
-        Some(cfg) => cfg,
-        None => {
-            println!("{}", "No zero.yml found in current directory or parents".yellow());
-            return Ok(());
-        }
-    };
+    let current_dir = std::env::current_dir()
+        .context("Failed to get current directory")?;
+    let mut checks = doctor::run(&current_dir).await;
+    if !json {
+        print_checks(&checks).await;
+    }
 
-    // Check container runtimes (Docker, Podman, etc.)
-    println!("Checking container runtimes...");
-    let mut container_mgr = runtime::ContainerRuntimeManager::new();
-    let container_runtime_ok = match container_mgr.detect_runtimes().await {
-        Ok(()) => {
-            let available = container_mgr.get_available_runtimes();
-            if available.is_empty() {
-                println!("  {} No container runtime found", "✗".red());
-                println!("    Install Docker: https://docs.docker.com/get-docker/");
-                println!("    Or Podman: https://podman.io/getting-started/installation");
-                false
-            } else {
-                let mut has_running = false;
-                for rt in available {
-                    let status = container_mgr.get_runtime_status(*rt).await;
-                    if status.is_ready() {
-                        let version = status.version.as_deref().unwrap_or("unknown");
-                        println!("  {} {} ({})", "✓".green(), rt.name(), version);
-                        has_running = true;
-                    } else if status.installed {
-                        println!("  {} {} (installed but not running)", "⚠".yellow(), rt.name());
+    let mut fixed = Vec::new();
+    let mut failed = Vec::new();
+    if fix {
+        if !json {
+            println!();
+        }
+        for check in checks.iter().filter(|check| check.status != CheckStatus::Pass) {
+            let Some(action) = &check.action else { continue };
+            match doctor::fix::apply(action).await {
+                Ok(Some(done)) => {
+                    if !json {
+                        println!("{} {}", "🔧".green(), done);
                     }
+                    fixed.push(done);
                 }
-
-                // Show preferred runtime
-                if let Ok(preferred) = container_mgr.get_preferred_runtime() {
-                    println!("  {} Using {} as primary runtime", "→".cyan(), preferred.name());
+                Ok(None) => {}
+                Err(e) => {
+                    let error = format!("{}: {:#}", check.title, e);
+                    if !json {
+                        println!("{} {}", "❌".red(), error);
+                    }
+                    failed.push(error);
                 }
-                has_running
             }
         }
-        Err(_) => {
-            println!("  {} No container runtime found", "✗".red());
-            println!("    Install Docker: https://docs.docker.com/get-docker/");
-            println!("    Or Podman: https://podman.io/getting-started/installation");
-            false
-        }
-    };
 
-    // Check language runtimes
-    println!("\nChecking language runtimes...");
-    let mut runtime_mgr = runtime::RuntimeManager::new();
-    for (name, version) in config.get_runtimes() {
-        match runtime_mgr.check_runtime(&name, &version).await {
-            Ok(info) => {
-                if info.is_compatible {
-                    println!("  {} {} (v{})", "✓".green(), name, info.installed_version.as_deref().unwrap_or("unknown"));
-                } else {
-                    let installed_str = info.installed_version.as_deref().unwrap_or("not installed");
-                    println!("  {} {} (required: {}, installed: {})",
-                        "✗".red(), name, version, installed_str);
-                    if let Some(cmd) = info.install_command {
-                        println!("    Install: {}", cmd.yellow());
-                    }
-                }
+        if !fixed.is_empty() {
+            checks = doctor::run(&current_dir).await;
+            if !json {
+                println!();
+                println!("{}", "🩺 Checking again...".cyan().bold());
+                println!();
+                print_checks(&checks).await;
             }
-            Err(e) => println!("  {} {} check failed: {}", "✗".red(), name, e),
+        } else if !json {
+            println!("{}", "Nothing could be fixed automatically".yellow());
         }
     }
 
+    if json {
+        return print_json(&DoctorReport {
+            status: doctor::overall_status(&checks),
+            checks,
+            fixed,
+            failed,
+        });
+    }
+
     println!();
-    let all_ok = container_runtime_ok && runtime_mgr.all_compatible();
-    if all_ok {
-        println!("{}", "✅ All checks passed!".green().bold());
-    } else {
-        println!("{}", "⚠️  Some checks failed".yellow().bold());
-        if !container_runtime_ok {
-            println!("    - No container runtime available (Docker/Podman required)");
-        }
-        if !runtime_mgr.all_compatible() {
-            println!("    - Some language runtimes are missing or incompatible");
-        }
+    match doctor::overall_status(&checks) {
+        CheckStatus::Pass => println!("{}", "✅ All checks passed!".green().bold()),
+        CheckStatus::Warn => println!("{}", "⚠️  Some checks need attention".yellow().bold()),
+        CheckStatus::Fail => println!("{}", "⚠️  Some checks failed".yellow().bold()),
     }
 
     Ok(())
 }
 
-pub async fn logs(service: Option<String>, follow: bool, tail: usize) -> Result<()> {
-    let service_name = match service {
-        Some(s) => s,
-        None => {
-            println!("{}", "Error: Service name is required".red());
-            println!("Usage: zero logs <service> [--follow] [--tail <lines>]");
-            return Ok(());
+async fn print_checks(checks: &[doctor::DoctorCheck]) {
+    let mut category = None;
+    for check in checks {
+        if category != Some(check.category) {
+            if category.is_some() {
+                println!();
+            }
+            println!("{}", match check.category {
+                CheckCategory::Runtime => "Container runtime",
+                CheckCategory::Daemon => "Docker API",
+                CheckCategory::Disk => "Disk space",
+                CheckCategory::Config => "Configuration",
+                CheckCategory::Languages => "Language runtimes",
+                CheckCategory::Ports => "Ports",
+                CheckCategory::Images => "Images",
+                CheckCategory::Resources => "Resources",
+            }.bold());
+            category = Some(check.category);
         }
-    };
 
+        let icon = match check.status {
+            CheckStatus::Pass => "✓".green(),
+            CheckStatus::Warn => "⚠".yellow(),
+            CheckStatus::Fail => "✗".red(),
+        };
+        println!("  {} {}: {}", icon, check.title, check.message);
+
+        match &check.action {
+            Some(DoctorAction::InstallRuntime { runtime, version }) => {
+                println!("    Install: {}", runtime::installer::install_hint(runtime, version).await.yellow());
+            }
+            Some(DoctorAction::StartRuntime { runtime }) => println!("    Start {} or run {}", runtime, "zero doctor --fix".cyan()),
+            Some(DoctorAction::RunCommand { command }) => println!("    Run: {}", command.yellow()),
+            Some(DoctorAction::CreateNetwork { .. }) => println!("    Run {} or {}", "zero doctor --fix".cyan(), "zero up".cyan()),
+            Some(DoctorAction::StopContainer { container }) => println!("    Stop {} or run {}", container.yellow(), "zero doctor --fix".cyan()),
+            Some(DoctorAction::IgnoreFile { gitignore, entry }) => println!("    Add {} to {} or run {}", entry.yellow(), gitignore, "zero doctor --fix".cyan()),
+            Some(DoctorAction::EditConfig { .. }) | None => {}
+        }
+    }
+}
+
+pub async fn logs(services: Vec<String>, all: bool, follow: bool, tail: usize, filter: LogFilter) -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
         None => {
@@ -291,39 +511,139 @@ pub async fn logs(service: Option<String>, follow: bool, tail: usize) -> Result<
         }
     };
 
+    let services = if all {
+        let mut names: Vec<String> = config.services.keys().cloned().collect();
+        names.sort();
+        names
+    } else {
+        services
+    };
+
+    if services.is_empty() {
+        println!("{}", "Error: At least one service name (or --all) is required".red());
+        println!("Usage: zero logs <service>... [--all] [--follow] [--tail <lines>] [--grep <pattern>] [--since <time>] [--level <level>]");
+        return Ok(());
+    }
+
     let project_name = config.metadata.name
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
-    println!("{}", format!("📜 Viewing logs for service: {}", service_name).cyan().bold());
+    println!("{}", format!("📜 Viewing logs for: {}", services.join(", ")).cyan().bold());
 
     let engine = Engine::new(project_name, config).await?;
-    engine.get_logs(&service_name, follow, tail).await?;
+
+    if let [service_name] = services.as_slice() {
+        let mut stream = Box::pin(engine.log_stream(service_name, follow, tail, filter.clone()).await?);
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(lines) => print!("{}", filter.highlight(&lines)),
+                Err(e) => {
+                    warn!("Error reading logs: {}", e);
+                    break;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Merge all services into one stream, prefixing each line with its service
+    const PREFIX_COLORS: &[colored::Color] = &[
+        colored::Color::Cyan,
+        colored::Color::Yellow,
+        colored::Color::Green,
+        colored::Color::Magenta,
+        colored::Color::Blue,
+    ];
+    let width = services.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut streams = Vec::new();
+
+    for (index, service_name) in services.iter().enumerate() {
+        let prefix = format!("{:width$}", service_name, width = width)
+            .color(PREFIX_COLORS[index % PREFIX_COLORS.len()])
+            .to_string();
+
+        match engine.log_stream(service_name, follow, tail, filter.clone()).await {
+            Ok(stream) => {
+                // Highlight before prefixing so the service names are never marked as matches
+                let filter = filter.clone();
+                let stream = stream.map(move |chunk| chunk.map(|lines| crate::logs::prefix_lines(&prefix, &filter.highlight(&lines))));
+                streams.push(Box::pin(stream));
+            }
+            Err(e) => println!("{}", format!("⚠️  Skipping {}: {}", service_name, e).yellow()),
+        }
+    }
+
+    let mut merged = futures::stream::select_all(streams);
+    while let Some(chunk) = merged.next().await {
+        match chunk {
+            Ok(lines) => print!("{}", lines),
+            Err(e) => warn!("Error reading logs: {}", e),
+        }
+    }
 
     Ok(())
 }
 
 pub async fn cloud(action: CloudCommands) -> Result<()> {
     match action {
-        CloudCommands::Start { provider } => {
+        CloudCommands::Start { providers, wait, timeout } => {
+            let timeout = std::time::Duration::from_secs(timeout);
+
+            // In a project the emulators join its network and state, with the services and region of zero.yml
+            if let Some(config) = ZeroConfig::discover()? {
+                let providers = match providers.is_empty() {
+                    true => config.cloud.as_ref().map(cloud::declared_providers).unwrap_or_default()
+                        .into_iter().map(str::to_string).collect(),
+                    false => providers,
+                };
+                if providers.is_empty() {
+                    anyhow::bail!("No cloud providers in zero.yml; name one, e.g. zero cloud start aws");
+                }
+                println!("{}", format!("☁️  Starting {} cloud emulation...", providers.join(", ")).cyan().bold());
+
+                let cloud_config = config.cloud.clone();
+                let project_name = config.metadata.name.clone().unwrap_or_else(|| "zeroconfig-project".to_string());
+                let mut engine = Engine::new(project_name, config).await?;
+                let statuses = engine.start_cloud_providers(&providers, timeout).await?;
+
+                println!("{}", "✅ Cloud emulation started successfully".green());
+                for status in &statuses {
+                    println!("  {} {}: {} (on the project network as {})",
+                        "•".blue(), status.provider, status.endpoint.cyan(), status.hostname.green());
+                }
+                for status in &statuses {
+                    let emulator = engine.cloud_emulator(status.provider.clone()).await?.with_config(cloud_config.as_ref());
+                    println!("\n{}", emulator.config_snippet()?);
+                }
+                return Ok(());
+            }
+
+            let [provider] = providers.as_slice() else {
+                anyhow::bail!("Name one cloud provider outside a project, e.g. zero cloud start aws");
+            };
             println!("{}", format!("☁️  Starting {} cloud emulation...", provider).cyan().bold());
 
             let emulator = cloud::CloudEmulator::new(provider.clone()).await?;
             emulator.start().await?;
+            if wait {
+                println!("{}", "⏳ Waiting for the emulator to become ready...".yellow());
+                emulator.wait_until_ready(timeout).await?;
+            }
 
             println!("{}", format!("✅ {} emulation started successfully", provider).green());
-
-            // Show provider-specific configuration
-            match provider.as_str() {
-                "localstack" | "aws" => println!("\n{}", cloud::localstack::get_aws_config_snippet()),
-                "azure" | "azurite" => println!("\n{}", cloud::azurite::get_azure_config_snippet()),
-                "gcp" | "google" => println!("\n{}", cloud::gcp::get_gcp_config_snippet()),
-                _ => {}
-            }
+            println!("\n{}", emulator.config_snippet()?);
         }
         CloudCommands::Stop => {
             println!("{}", "☁️  Stopping cloud emulation...".cyan().bold());
 
+            if let Some(config) = ZeroConfig::discover()? {
+                let project_name = config.metadata.name.clone().unwrap_or_else(|| "zeroconfig-project".to_string());
+                Engine::new(project_name, config).await?.stop_cloud().await;
+                println!("{}", "✅ Cloud emulation stopped".green());
+                return Ok(());
+            }
+
             // Stop all known emulators
             let mut stopped_count = 0;
             for provider in &["localstack", "azurite", "gcp"] {
@@ -349,26 +669,107 @@ pub async fn cloud(action: CloudCommands) -> Result<()> {
         }
         CloudCommands::Status => {
             println!("{}", "☁️  Cloud emulation status:".cyan().bold());
-            println!();
 
-            // Check status of all emulators
-            for provider in &["localstack", "azurite", "gcp"] {
-                let emulator = cloud::CloudEmulator::new(provider.to_string()).await?;
-                let _ = emulator.status().await;
-                println!();
+            let mut statuses = Vec::new();
+            if let Some(config) = ZeroConfig::discover()? {
+                let project_name = config.metadata.name.clone().unwrap_or_else(|| "zeroconfig-project".to_string());
+                statuses = Engine::new(project_name, config).await?.cloud_status().await?;
+            }
+            // Outside a project, or in one without emulators, show every provider
+            if statuses.is_empty() {
+                for provider in ["aws", "azure", "gcp"] {
+                    statuses.push(cloud::CloudEmulator::new(provider.to_string()).await?.provider_status().await?);
+                }
             }
+            print_cloud_status(&statuses);
         }
         CloudCommands::Ui => {
             println!("{}", "☁️  Opening cloud UI...".cyan().bold());
 
             // Default to LocalStack, but can be extended
-            let emulator = cloud::CloudEmulator::new("localstack".to_string()).await?;
+            let config = ZeroConfig::discover()?;
+            let host = config.as_ref().and_then(|config| config.runtime.host.as_deref());
+            let docker = crate::orchestrator::docker_client::connect_with_host(host)?;
+            let project_name = config.as_ref().and_then(|config| config.metadata.name.clone())
+                .unwrap_or_else(|| "zeroconfig-project".to_string());
+            let emulator = cloud::CloudEmulator::on_engine("localstack".to_string(), docker).await?
+                .for_project(&project_name)
+                .with_config(config.as_ref().and_then(|config| config.cloud.as_ref()));
             emulator.ui().await?;
         }
+        CloudCommands::Snapshot { name } => {
+            let Some(config) = ZeroConfig::discover()? else {
+                anyhow::bail!("No zero.yml found; snapshots cover a project's emulators");
+            };
+            let path = cloud::snapshot::snapshot_path(&name);
+            println!("{}", format!("📸 Snapshotting cloud emulators to {}...", path.display()).cyan().bold());
+
+            let project_name = config.metadata.name.clone().unwrap_or_else(|| "zeroconfig-project".to_string());
+            let providers = Engine::new(project_name, config).await?.snapshot_cloud(&name, &path).await?;
+
+            println!("{}", format!("✅ Snapshot {} saved ({})", name, providers.join(", ")).green());
+            println!("   Share {} and restore it with: zero cloud restore {}", path.display(), path.display());
+        }
+        CloudCommands::Restore { name, timeout } => {
+            let Some(config) = ZeroConfig::discover()? else {
+                anyhow::bail!("No zero.yml found; snapshots cover a project's emulators");
+            };
+            let path = cloud::snapshot::snapshot_path(&name);
+            println!("{}", format!("📦 Restoring cloud emulators from {}...", path.display()).cyan().bold());
+
+            let project_name = config.metadata.name.clone().unwrap_or_else(|| "zeroconfig-project".to_string());
+            let mut engine = Engine::new(project_name, config).await?;
+            let statuses = engine.restore_cloud(&path, std::time::Duration::from_secs(timeout)).await?;
+
+            println!("{}", "✅ Cloud emulators restored".green());
+            for status in &statuses {
+                println!("  {} {}: {}", "•".blue(), status.provider, status.endpoint.cyan());
+            }
+        }
     }
     Ok(())
 }
 
+fn print_cloud_status(statuses: &[cloud::CloudStatus]) {
+    let header = ["PROVIDER", "STATUS", "ENDPOINT", "HOSTNAME", "SERVICES"];
+    let rows: Vec<[String; 5]> = statuses.iter()
+        .map(|status| [
+            status.provider.clone(),
+            if status.running { "ready".to_string() } else { "stopped".to_string() },
+            status.endpoint.clone(),
+            status.hostname.clone(),
+            if status.services.is_empty() { "all".to_string() } else { status.services.join(",") },
+        ])
+        .collect();
+
+    print_table(header, &rows, |column, text, cell| match (column, text) {
+        (0, _) | (1, "ready") => cell.green().to_string(),
+        (1, _) => cell.red().to_string(),
+        _ => cell,
+    });
+}
+
+/// Print aligned columns under a bold header, `color` styling each padded cell by column and text
+fn print_table<const N: usize>(header: [&str; N], rows: &[[String; N]], color: impl Fn(usize, &str, String) -> String) {
+    // Pad before coloring, as escape codes would count towards the width
+    let mut widths = header.map(str::len);
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let pad = |cell: &str, width: usize| format!("{:<width$}", cell, width = width);
+
+    let header: Vec<String> = header.iter().zip(widths).map(|(cell, width)| pad(cell, width)).collect();
+    println!("  {}", header.join("  ").bold());
+    for row in rows {
+        let cells: Vec<String> = row.iter().zip(widths).enumerate()
+            .map(|(column, (cell, width))| color(column, cell, pad(cell, width)))
+            .collect();
+        println!("  {}", cells.join("  ").trim_end());
+    }
+}
+
 pub async fn shell(service: String, shell: String) -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
@@ -385,7 +786,12 @@ pub async fn shell(service: String, shell: String) -> Result<()> {
     println!("{}", format!("🐚 Opening {} shell in service: {}", shell, service).cyan().bold());
 
     let engine = Engine::new(project_name, config).await?;
-    engine.open_shell(&service, &shell).await?;
+    let (cols, rows) = terminal::size();
+    let session = engine.open_shell(&service, &shell, cols, rows).await?;
+    let code = terminal::attach(session).await?;
+    if code != 0 {
+        std::process::exit(code as i32);
+    }
 
     Ok(())
 }
@@ -403,18 +809,55 @@ pub async fn exec(service: String, command: Vec<String>) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
-    println!("{}", format!("⚡ Executing command in service: {}", service).cyan().bold());
-    println!("Command: {}", command.join(" "));
-
     let engine = Engine::new(project_name, config).await?;
-    engine.exec_command(&service, command).await?;
+
+    // With a terminal on both ends the command gets a TTY, so `zero exec db psql` works like a shell
+    use std::io::IsTerminal;
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let code = if interactive {
+        println!("{}", format!("⚡ Executing command in service: {}", service).cyan().bold());
+        println!("Command: {}", command.join(" "));
+        let (cols, rows) = terminal::size();
+        let session = engine.open_exec_session(&service, command, cols, rows).await?;
+        terminal::attach(session).await?
+    } else {
+        engine.exec_command(&service, command).await?
+    };
+    if code != 0 {
+        std::process::exit(code as i32);
+    }
 
     Ok(())
 }
 
-pub async fn monitor(interval: u64) -> Result<()> {
+pub async fn monitor_serve(address: String) -> Result<()> {
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found".red());
+            return Ok(());
+        }
+    };
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    let address = metrics::parse_address(&address)?;
+    let engine = Engine::new(project_name, config).await?;
+    println!("{}", format!("📊 Serving Prometheus metrics at http://{}/metrics", address).cyan().bold());
+    println!("{}", "Press Ctrl+C to stop".yellow());
+
+    tokio::select! {
+        result = metrics::serve(&engine, address) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+pub async fn monitor(interval: u64, output: OutputFormat) -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
+        None if output.is_json() => anyhow::bail!("No zero.yml found"),
         None => {
             println!("{}", "Error: No zero.yml found".red());
             return Ok(());
@@ -425,6 +868,27 @@ pub async fn monitor(interval: u64) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
+    if output.is_json() {
+        // One JSON object per line and sample, for consumers that read the stream as it comes
+        let engine = Engine::new(project_name, config).await?;
+        loop {
+            match engine.service_stats().await {
+                Ok(services) => println!("{}", serde_json::json!({
+                    "timestamp": chrono::Local::now().to_rfc3339(),
+                    "services": services,
+                })),
+                Err(e) => eprintln!("Error fetching stats: {}", e),
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+        }
+    }
+
+    if std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        let mut engine = Engine::new(project_name, config).await?;
+        return crate::cli::dashboard::run(&mut engine, interval).await;
+    }
+
+    // Without a terminal to draw on, print a table per sample
     println!("{}", format!("📊 Monitoring resources (interval: {}s)", interval).cyan().bold());
     println!("{}", "Press Ctrl+C to stop".yellow());
     println!();
@@ -434,9 +898,6 @@ pub async fn monitor(interval: u64) -> Result<()> {
     loop {
         match engine.get_all_stats().await {
             Ok(stats) => {
-                // Clear screen (platform independent)
-                print!("\x1B[2J\x1B[1;1H");
-
                 println!("{}", "📊 Container Resource Usage".cyan().bold());
                 println!("{}", "─".repeat(80));
                 println!("{:25} {:>12} {:>12} {:>12} {:>12}",
@@ -500,6 +961,12 @@ fn calculate_cpu_percent(stats: &bollard::container::Stats) -> f64 {
     }
 }
 
+/// Print a result for `--output json`
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
 fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
@@ -513,12 +980,12 @@ fn format_bytes(bytes: u64) -> String {
     format!("{:.2} {}", size, UNITS[unit_index])
 }
 
-pub async fn ps() -> Result<()> {
-    println!("{}", "📦 Running services:".cyan().bold());
-
+pub async fn ps(all: bool, watch: bool, output: OutputFormat) -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
+        None if output.is_json() => anyhow::bail!("No zero.yml found"),
         None => {
+            println!("{}", "📦 Running services:".cyan().bold());
             println!("No configuration found");
             return Ok(());
         }
@@ -529,22 +996,83 @@ pub async fn ps() -> Result<()> {
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
     let engine = Engine::new(project_name, config).await?;
-    let containers = engine.list_services().await?;
+    if !watch {
+        return print_ps(&engine, all, false, output).await;
+    }
+
+    loop {
+        if !output.is_json() {
+            print!("\x1B[2J\x1B[1;1H");
+        }
+        print_ps(&engine, all, true, output).await?;
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(2)) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+async fn print_ps(engine: &Engine, all: bool, watch: bool, output: OutputFormat) -> Result<()> {
+    use crate::core::ps::{format_ports, format_uptime, short_digest};
+
+    let listings = engine.service_listings(all).await?;
+    if output.is_json() {
+        let value = serde_json::json!({
+            "project": engine.project_name(),
+            "services": listings,
+            "web_uis": engine.web_uis().await,
+        });
+        // Watching prints one object per line, for consumers that read the stream as it comes
+        if watch {
+            println!("{}", value);
+            return Ok(());
+        }
+        return print_json(&value);
+    }
+
+    let title = if all { "📦 Services:" } else { "📦 Running services:" };
+    println!("{}", title.cyan().bold());
 
-    if containers.is_empty() {
+    if listings.is_empty() {
         println!("No services running");
     } else {
-        for container in containers {
-            let name = container.names.and_then(|n| n.first().cloned()).unwrap_or_default();
-            let status = container.status.unwrap_or_default();
-            println!("  {} - {}", name.green(), status);
-        }
+        let header = ["SERVICE", "STATE", "HEALTH", "UPTIME", "IMAGE", "DIGEST", "PORTS"];
+        let rows: Vec<[String; 7]> = listings.iter()
+            .map(|listing| [
+                listing.service.clone(),
+                listing.state.clone(),
+                match &listing.health {
+                    Some(health) if health.is_healthy => "healthy".to_string(),
+                    Some(health) if health.status_message == "Starting" => "starting".to_string(),
+                    Some(_) => "unhealthy".to_string(),
+                    None => "-".to_string(),
+                },
+                listing.uptime_seconds.map(format_uptime).unwrap_or_else(|| "-".to_string()),
+                listing.image.clone(),
+                listing.image_digest.as_deref().map(short_digest).unwrap_or_else(|| "-".to_string()),
+                format_ports(&listing.ports),
+            ])
+            .collect();
+
+        print_table(header, &rows, |column, text, cell| match (column, text) {
+            (0, _) => cell.green().to_string(),
+            (1, "running") | (2, "healthy") => cell.green().to_string(),
+            (1, _) | (2, "unhealthy") => cell.red().to_string(),
+            (2, "starting") => cell.yellow().to_string(),
+            _ => cell,
+        });
+    }
+
+    let uis = engine.web_uis().await;
+    if !uis.is_empty() {
+        println!("\n{}", "🖥️  Web UIs:".cyan().bold());
+        print_web_uis(&uis);
     }
 
     Ok(())
 }
 
-pub async fn restart(services: Vec<String>) -> Result<()> {
+pub async fn restart(services: Vec<String>, rolling: bool, timeout: u64) -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
         None => {
@@ -557,9 +1085,21 @@ pub async fn restart(services: Vec<String>) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
+    let order = if rolling { rolling_order(&config, &services)? } else { Vec::new() };
     let engine = Engine::new(project_name, config).await?;
 
-    if services.is_empty() {
+    if rolling {
+        println!("{}", format!("🔄 Rolling restart: {}", order.join(" → ")).cyan().bold());
+        let timeout = std::time::Duration::from_secs(timeout);
+        let engine = &engine;
+        roll(&order, |service| async move {
+            engine.restart_service(&service).await?;
+            engine.wait_for_healthy(&service, timeout).await
+                .with_context(|| format!("{} did not become healthy", service))
+        }).await?;
+
+        println!("{}", "✅ Rolling restart complete".green());
+    } else if services.is_empty() {
         println!("{}", "🔄 Restarting all services...".cyan().bold());
         engine.restart_all().await?;
         println!("{}", "✅ All services restarted successfully".green());
@@ -574,20 +1114,71 @@ pub async fn restart(services: Vec<String>) -> Result<()> {
     Ok(())
 }
 
-pub async fn env(format: String) -> Result<()> {
+/// Services of a rolling restart, dependencies before their dependents
+///
+/// Every service is restarted when none are given.
+fn rolling_order(config: &ZeroConfig, services: &[String]) -> Result<Vec<String>> {
+    if let Some(unknown) = services.iter().find(|service| !config.services.contains_key(*service)) {
+        anyhow::bail!("Service {} is not defined in zero.yml", unknown);
+    }
+    Ok(config.start_order()?
+        .into_iter()
+        .filter(|service| services.is_empty() || services.contains(service))
+        .collect())
+}
+
+/// Restart services one at a time, moving on only once each is healthy again
+///
+/// The first service that fails stops the roll, leaving the rest untouched.
+async fn roll<F, Fut>(services: &[String], mut restart: F) -> Result<()>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<health::HealthStatus>>,
+{
+    for (index, service) in services.iter().enumerate() {
+        println!("[{}/{}] Restarting {}...", index + 1, services.len(), service.yellow());
+        match restart(service.clone()).await {
+            Ok(status) => println!("  {}", health::format_health_status(&status)),
+            Err(e) => {
+                let remaining = &services[index + 1..];
+                let untouched = if remaining.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({} left as they were)", remaining.join(", "))
+                };
+                anyhow::bail!("Rolling restart stopped at {}{}: {:#}", service, untouched, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn env(format: String, output: OutputFormat) -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
+        None if output.is_json() => anyhow::bail!("No zero.yml found"),
         None => {
             println!("{}", "Error: No zero.yml found".red());
             return Ok(());
         }
     };
 
-    println!("{}", format!("🔐 Environment variables (format: {})", format).cyan().bold());
-
     let mut env_manager = secrets::EnvManager::new();
     env_manager.process_env_vars(&config.env);
 
+    // Connection strings of the last `zero up`, with their real ports and credentials
+    let state = crate::core::ProjectState::load(&config.root.clone().unwrap_or_default())?;
+    env_manager.process_env_vars(&state.connection_env());
+    // Language runtimes provided by `zero up`, so `eval "$(zero env)"` activates them
+    env_manager.process_env_vars(&state.toolchain_env());
+
+    if output.is_json() {
+        println!("{}", env_manager.export_json()?);
+        return Ok(());
+    }
+
+    println!("{}", format!("🔐 Environment variables (format: {})", format).cyan().bold());
+
     match format.as_str() {
         "shell" | "bash" => {
             println!("{}", env_manager.export_shell());
@@ -628,9 +1219,9 @@ pub async fn generate(target: GenerateCommands) -> Result<()> {
     let output_dir = std::env::current_dir()?;
 
     match target {
-        GenerateCommands::Dockerfile => {
+        GenerateCommands::Dockerfile { language } => {
             println!("{}", "📄 Generating Dockerfile...".cyan().bold());
-            generators::dockerfile::generate(&config, &output_dir)?;
+            generators::dockerfile::generate_for(&config, &output_dir, language.as_deref())?;
         }
         GenerateCommands::Compose => {
             println!("{}", "📄 Generating docker-compose.yml...".cyan().bold());
@@ -644,6 +1235,14 @@ pub async fn generate(target: GenerateCommands) -> Result<()> {
             println!("{}", "📄 Generating GitHub Actions workflow...".cyan().bold());
             generators::github_actions::generate(&config, &output_dir)?;
         }
+        GenerateCommands::Nix => {
+            println!("{}", "📄 Generating flake.nix...".cyan().bold());
+            generators::nix::generate(&config, &output_dir)?;
+        }
+        GenerateCommands::Terraform => {
+            println!("{}", "📄 Generating Terraform for cloud resources...".cyan().bold());
+            generators::terraform::generate(&config, &output_dir)?;
+        }
         GenerateCommands::All => {
             println!("{}", "📄 Generating all configuration files...".cyan().bold());
             generators::generate_all(&config, &output_dir)?;
@@ -654,6 +1253,65 @@ pub async fn generate(target: GenerateCommands) -> Result<()> {
     Ok(())
 }
 
+/// `zero init --auto`: detect the stack, show the proposed zero.yml as a diff and write it once confirmed
+fn init_auto(project_name: &str, yes: bool) -> Result<()> {
+    let dir = std::env::current_dir()?;
+    let stack = crate::runtime::detect_project_stack(&dir)?;
+
+    println!("\n{}", "🔍 Detected".cyan().bold());
+    if stack.languages.is_empty() && stack.services.is_empty() {
+        println!("  Nothing recognizable; proposing an empty project");
+    }
+    for language in &stack.languages {
+        let version = language.version.as_deref().map(|version| format!(" {}", version)).unwrap_or_default();
+        println!("  {} {}{} ({})", "•".blue(), language.name.green(), version, language.source.dimmed());
+    }
+    if !stack.frameworks.is_empty() {
+        println!("  {} {}", "•".blue(), stack.frameworks.join(", "));
+    }
+    for service in &stack.services {
+        println!("  {} {} ({})", "•".blue(), service.name.green(), service.reason.dimmed());
+    }
+
+    let proposed = stack.to_config(project_name);
+    let path = dir.join("zero.yml");
+    let current = match path.exists() {
+        true => std::fs::read_to_string(&path).context("Failed to read zero.yml")?,
+        false => String::new(),
+    };
+    let diff = crate::cli::diff::lines(&current, &proposed);
+    if !crate::cli::diff::has_changes(&diff) {
+        println!("\n{}", "✅ zero.yml already matches the detected stack".green());
+        return Ok(());
+    }
+
+    let title = if current.is_empty() { "📝 Proposed zero.yml" } else { "📝 Changes to zero.yml" };
+    println!("\n{}", title.cyan().bold());
+    println!("{}\n", crate::cli::diff::render(&diff, 3));
+
+    if !yes {
+        if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+            println!("{}", format!("Not writing zero.yml; run {} to accept", "zero init --auto --yes".cyan()).yellow());
+            return Ok(());
+        }
+        print!("Write zero.yml? [y/N] ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("{}", "zero.yml left unchanged".yellow());
+            return Ok(());
+        }
+    }
+
+    std::fs::write(&path, proposed).context("Failed to write zero.yml")?;
+    println!("{}", "✅ Wrote zero.yml".green());
+    println!("\nNext steps:");
+    println!("  1. Run {} to check the configuration", "zero config lint".cyan());
+    println!("  2. Run {} to start your environment", "zero up".cyan());
+    Ok(())
+}
+
 fn generate_template(template: Option<&str>) -> String {
     match template {
         Some("node") => include_str!("../templates/node.yml"),
@@ -668,9 +1326,11 @@ fn generate_template(template: Option<&str>) -> String {
     }.to_string()
 }
 
-pub async fn health(service: Option<String>, wait: bool, timeout: u64) -> Result<()> {
+pub async fn health(service: Option<String>, wait: bool, timeout: u64, output: OutputFormat) -> Result<()> {
+    let json = output.is_json();
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
+        None if json => anyhow::bail!("No zero.yml found"),
         None => {
             println!("{}", "Error: No zero.yml found".red());
             return Ok(());
@@ -681,77 +1341,159 @@ pub async fn health(service: Option<String>, wait: bool, timeout: u64) -> Result
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
-    println!("{}", "💚 Health Check".cyan().bold());
-    println!("{}", "─".repeat(80));
+    if !json {
+        println!("{}", "💚 Health Check".cyan().bold());
+        println!("{}", "─".repeat(80));
+    }
 
     let engine = Engine::new(project_name.clone(), config).await?;
-    let health_checker = health::HealthChecker::new().await?;
+    let health_checker = engine.health_checker();
+    let containers = engine.list_services().await?;
 
     if let Some(service_name) = service {
         // Check specific service
-        let containers = engine.list_services().await?;
-
-        for container in containers {
-            if let Some(names) = container.names {
-                for name in names {
-                    let container_name = name.trim_start_matches('/');
-                    if container_name.contains(&service_name) {
-                        let container_id = container.id.as_deref().unwrap_or("");
-
-                        if wait {
-                            println!("Waiting for {} to become healthy (timeout: {}s)...", service_name, timeout);
-                            match health_checker.wait_for_healthy(
-                                container_id,
-                                &service_name,
-                                std::time::Duration::from_secs(timeout),
-                            ).await {
-                                Ok(status) => {
-                                    println!("{}", health::format_health_status(&status));
-                                    println!("{}", "✅ Service is healthy!".green().bold());
-                                }
-                                Err(e) => {
-                                    println!("{}", format!("❌ Health check failed: {}", e).red().bold());
-                                    return Ok(());
-                                }
-                            }
-                        } else {
-                            let status = health_checker.check_container(container_id, &service_name).await?;
-                            println!("{}", health::format_health_status(&status));
-                        }
+        let container = containers.iter().find(|container| {
+            container.names.iter().flatten().any(|name| name.trim_start_matches('/').contains(&service_name))
+        });
+        let Some(container) = container else {
+            if json {
+                anyhow::bail!("Service '{}' not found", service_name);
+            }
+            println!("{}", format!("Service '{}' not found", service_name).yellow());
+            return Ok(());
+        };
+        let container_id = container.id.as_deref().unwrap_or("");
 
-                        return Ok(());
-                    }
+        if wait {
+            if !json {
+                println!("Waiting for {} to become healthy (timeout: {}s)...", service_name, timeout);
+            }
+            match health_checker.wait_for_healthy(
+                container_id,
+                &service_name,
+                std::time::Duration::from_secs(timeout),
+            ).await {
+                Ok(status) if json => print_json(&status)?,
+                Ok(status) => {
+                    println!("{}", health::format_health_status(&status));
+                    println!("{}", "✅ Service is healthy!".green().bold());
                 }
+                Err(e) if json => return Err(e),
+                Err(e) => println!("{}", format!("❌ Health check failed: {}", e).red().bold()),
+            }
+        } else {
+            let status = health_checker.check_container(container_id, &service_name).await?;
+            if json {
+                print_json(&status)?;
+            } else {
+                println!("{}", health::format_health_status(&status));
             }
         }
-
-        println!("{}", format!("Service '{}' not found", service_name).yellow());
     } else {
         // Check all services
-        let containers = engine.list_services().await?;
-
-        for container in containers {
-            if let Some(names) = container.names {
-                if let Some(name) = names.first() {
-                    let container_name = name.trim_start_matches('/');
-                    if container_name.starts_with(&project_name) {
-                        let container_id = container.id.as_deref().unwrap_or("");
-                        let status = health_checker.check_container(container_id, container_name).await?;
-                        println!("{}", health::format_health_status(&status));
-                    }
-                }
+        let mut statuses = Vec::new();
+        for container in &containers {
+            let Some(name) = container.names.iter().flatten().next() else { continue };
+            let container_name = name.trim_start_matches('/');
+            if container_name.starts_with(&project_name) {
+                let container_id = container.id.as_deref().unwrap_or("");
+                statuses.push(health_checker.check_container(container_id, container_name).await?);
             }
         }
 
+        if json {
+            return print_json(&statuses);
+        }
+        for status in &statuses {
+            println!("{}", health::format_health_status(status));
+        }
         println!("{}", "─".repeat(80));
     }
 
     Ok(())
 }
 
-pub async fn backup(service: String, output: String) -> Result<()> {
-    println!("{}", format!("💾 Backing up service: {}", service).cyan().bold());
+pub async fn backup(service: Option<String>, all: bool, output: Option<String>, keep: Option<usize>) -> Result<()> {
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found".red());
+            return Ok(());
+        }
+    };
 
+    let services = match (service, all) {
+        (_, true) => backup::database_services(&config),
+        (Some(service), false) => vec![service],
+        (None, false) => {
+            println!("{}", "Error: Specify a service or --all".red());
+            println!("Usage: zero backup <service> | zero backup --all | zero backup --daemon");
+            return Ok(());
+        }
+    };
+
+    if services.is_empty() {
+        println!("{}", "No database services found in zero.yml".yellow());
+        return Ok(());
+    }
+
+    let output_dir = backup::output_dir(&config, output.as_deref());
+    let keep = keep.or_else(|| config.backups.as_ref().and_then(|b| b.retention));
+    let upload = config.backups.as_ref().and_then(|b| b.upload.clone());
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    let engine = Engine::new(project_name, config).await?;
+
+    // One failing service must not keep the others from being backed up
+    let mut failures = Vec::new();
+    for service in &services {
+        println!("{}", format!("💾 Backing up service: {}", service).cyan().bold());
+
+        if backup::BackupKind::for_service(service).is_none() {
+            println!("{}", format!("Backup not supported for service type: {}", service).yellow());
+            continue;
+        }
+
+        println!("Executing backup command...");
+        let backup_file = match backup::backup_service(&engine, service, &output_dir).await {
+            Ok(file) => file,
+            Err(e) => {
+                println!("{}", format!("❌ Backup of {} failed: {:#}", service, e).red());
+                failures.push(service.as_str());
+                continue;
+            }
+        };
+        println!("{}", format!("✅ Backup saved to: {}", backup_file.display()).green());
+
+        if let Some(upload) = &upload {
+            match backup::upload_backup(upload, &backup_file).await {
+                Ok(uri) => println!("{}", format!("☁️  Uploaded to: {}", uri).green()),
+                Err(e) => println!("{}", format!("⚠️  Upload failed: {}", e).yellow()),
+            }
+        }
+
+        if let Some(keep) = keep {
+            for removed in backup::apply_retention(&output_dir, engine.project_name(), service, keep)? {
+                println!("{}", format!("🗑  Removed old backup: {}", removed.display()).dimmed());
+            }
+        }
+
+        if services.len() == 1 {
+            println!("\nTo restore this backup, run:");
+            println!("  {}", format!("zero restore {} {}", service, backup_file.display()).cyan());
+        }
+    }
+
+    if !failures.is_empty() {
+        anyhow::bail!("{} of {} backups failed: {}", failures.len(), services.len(), failures.join(", "));
+    }
+    Ok(())
+}
+
+pub async fn backup_daemon() -> Result<()> {
     let config = match ZeroConfig::discover()? {
         Some(cfg) => cfg,
         None => {
@@ -760,59 +1502,251 @@ pub async fn backup(service: String, output: String) -> Result<()> {
         }
     };
 
+    let backups_config = config.backups.clone().unwrap_or_default();
+    let schedule = match backups_config.schedule.as_deref() {
+        Some(expr) => backup::CronSchedule::parse(expr)?,
+        None => {
+            println!("{}", "Error: No backups.schedule configured in zero.yml".red());
+            return Ok(());
+        }
+    };
+
+    let services = if backups_config.services.is_empty() {
+        backup::database_services(&config)
+    } else {
+        backups_config.services.clone()
+    };
+    let output_dir = backup::output_dir(&config, None);
+
     let project_name = config.metadata.name
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
     let engine = Engine::new(project_name, config).await?;
 
-    // Create backup directory
-    std::fs::create_dir_all(&output)?;
-
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-
-    // Execute backup command based on service type
-    let (backup_command, file_extension) = if service.contains("postgres") {
-        (vec![
-            "pg_dump".to_string(),
-            "-U".to_string(),
-            "postgres".to_string(),
-            "-d".to_string(),
-            "postgres".to_string(),
-        ], "sql")
-    } else if service.contains("mysql") {
-        (vec![
-            "mysqldump".to_string(),
-            "-u".to_string(),
-            "root".to_string(),
-            "--all-databases".to_string(),
-        ], "sql")
-    } else if service.contains("mongo") {
-        (vec![
-            "mongodump".to_string(),
-            "--archive".to_string(),
-        ], "archive")
+    println!("{}", "⏰ Backup daemon started".cyan().bold());
+    println!("  Schedule: {}", backups_config.schedule.as_deref().unwrap_or_default().yellow());
+    println!("  Services: {}", services.join(", "));
+    println!("  Output:   {}", output_dir.display());
+    println!("Press {} to stop", "Ctrl+C".yellow());
+
+    loop {
+        let now = chrono::Local::now().naive_local();
+        let next = schedule
+            .next_after(&now)
+            .context("Backup schedule never fires")?;
+        println!("{}", format!("Next backup at {}", next.format("%Y-%m-%d %H:%M")).dimmed());
+
+        let wait = (next - now).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n{}", "🛑 Backup daemon stopped".yellow());
+                return Ok(());
+            }
+        }
+
+        for service in &services {
+            match backup::backup_service(&engine, service, &output_dir).await {
+                Ok(file) => {
+                    info!("Scheduled backup of {} saved to {}", service, file.display());
+                    println!("{} {} → {}", "✅".green(), service, file.display());
+
+                    if let Some(upload) = &backups_config.upload {
+                        match backup::upload_backup(upload, &file).await {
+                            Ok(uri) => println!("  {} {}", "☁️ ".green(), uri),
+                            Err(e) => {
+                                warn!("Upload of {} backup failed: {}", service, e);
+                                println!("  {} upload failed: {}", "✗".red(), e);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("Scheduled backup of {} failed: {}", service, e);
+                    println!("{} {} backup failed: {}", "✗".red(), service, e);
+                }
+            }
+
+            if let Some(keep) = backups_config.retention {
+                if let Err(e) = backup::apply_retention(&output_dir, engine.project_name(), service, keep) {
+                    warn!("Failed to apply retention for {}: {}", service, e);
+                }
+            }
+        }
+    }
+}
+
+pub async fn config_lint(strict: bool, output: OutputFormat) -> Result<()> {
+    let config = ZeroConfig::discover()?.context("No zero.yml found")?;
+    let mut problems = config.report();
+    problems.extend(lint::lint(&config));
+    problems.sort_by_key(|problem| std::cmp::Reverse(problem.severity));
+
+    let failing = problems.iter()
+        .filter(|problem| problem.is_error() || (strict && problem.severity == Severity::Warning))
+        .count();
+
+    if output.is_json() {
+        print_json(&problems)?;
     } else {
-        println!("{}", format!("Backup not supported for service type: {}", service).yellow());
+        println!("{}", "🔍 Linting configuration...".cyan().bold());
+        println!();
+        for problem in &problems {
+            let (icon, label) = match problem.severity {
+                Severity::Error => ("❌", "error".red()),
+                Severity::Warning => ("⚠️ ", "warning".yellow()),
+                Severity::Info => ("ℹ️ ", "info".blue()),
+            };
+            let rule = problem.rule.as_deref().unwrap_or("config");
+            let mut located = problem.clone();
+            located.suggestion = None;
+            println!("{} {} [{}] {}", icon, label, rule.bold(), located);
+            if let Some(suggestion) = &problem.suggestion {
+                println!("   {}", suggestion.dimmed());
+            }
+        }
+        if problems.is_empty() {
+            println!("{}", "✅ No problems found".green());
+        } else {
+            let count = |severity| problems.iter().filter(|problem| problem.severity == severity).count();
+            println!();
+            println!("{} errors, {} warnings, {} notes",
+                count(Severity::Error), count(Severity::Warning), count(Severity::Info));
+        }
+    }
+
+    if failing > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+pub async fn config_set(key: String, value: String) -> Result<()> {
+    let path = ZeroConfig::find_file_in(std::env::current_dir()?).context("No zero.yml found")?;
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let edited = edit::set_in(&content, &key, &value)?;
+    std::fs::write(&path, edited)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("✅ Set {} to {} in {}", key.bold(), value.cyan(), path.display());
+    Ok(())
+}
+
+pub async fn upgrade_config(dry_run: bool) -> Result<()> {
+    let path = ZeroConfig::find_file_in(std::env::current_dir()?).context("No zero.yml found")?;
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let upgrade = migrate::upgrade(&content)?;
+    if !upgrade.is_needed() {
+        println!("{}", format!("✅ {} is already at schema version {}", path.display(), upgrade.to).green());
+        return Ok(());
+    }
+
+    println!("{}", format!("⬆️  Upgrading {} from schema version {} to {}", path.display(), upgrade.from, upgrade.to).cyan().bold());
+    for change in &upgrade.changes {
+        println!("  {} {}", "•".blue(), change);
+    }
+    let diff = crate::cli::diff::lines(&content, &upgrade.content);
+    println!("\n{}\n", crate::cli::diff::render(&diff, 3));
+
+    if dry_run {
+        println!("{}", "Dry run; zero.yml left unchanged".yellow());
         return Ok(());
+    }
+    std::fs::write(&path, &upgrade.content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("✅ Upgraded {}", path.display());
+    Ok(())
+}
+
+pub async fn secrets(action: SecretsCommands) -> Result<()> {
+    let config = ZeroConfig::discover()?;
+    let backend = config.as_ref().map(|config| config.secrets.backend).unwrap_or_default();
+    // The project's credentials live next to zero.yml, wherever in the project this runs
+    let root = match config.as_ref().and_then(|config| config.root.clone()).filter(|root| !root.as_os_str().is_empty()) {
+        Some(root) => root,
+        None => std::env::current_dir()?,
+    };
+    let mut store = secrets::CredentialStore::with_backend(root, backend);
+    store.load()?;
+
+    let (key, set) = match action {
+        SecretsCommands::List => {
+            let mut keys: Vec<&String> = store.get_all().keys().collect();
+            keys.sort();
+            if keys.is_empty() {
+                println!("{}", "No credentials stored yet; they are generated by `zero up`".yellow());
+            } else {
+                println!("{}", format!("🔐 Credentials in {}", store.location()).cyan().bold());
+                for key in keys {
+                    println!("  {} = {}", key.green(), secrets::MASK.dimmed());
+                }
+            }
+            return Ok(());
+        }
+        SecretsCommands::Get { key } => {
+            let value = store.get(&key).with_context(|| format!("No credential named {}", key))?;
+            println!("{}", value);
+            return Ok(());
+        }
+        SecretsCommands::Reveal => {
+            let mut credentials: Vec<(&String, &String)> = store.get_all().iter().collect();
+            credentials.sort();
+            for (key, value) in credentials {
+                println!("{}={}", key, value);
+            }
+            return Ok(());
+        }
+        SecretsCommands::Set { key } => (key, true),
+        SecretsCommands::Rotate { key } => (key, false),
     };
 
-    println!("Executing backup command...");
-    
-    // Use exec_command_with_output to capture the backup data
-    let backup_data = engine.exec_command_with_output(&service, backup_command).await?;
-    
-    // Write backup data to file
-    let final_backup_file = format!("{}/{}_{}.{}", output, service, timestamp, file_extension);
-    std::fs::write(&final_backup_file, backup_data)?;
+    if store.get(&key).is_none() {
+        anyhow::bail!("No credential named {}; see `zero secrets list`", key);
+    }
+    let value = if set { Some(read_secret_value(&key)?) } else { None };
+    let config = config.context("No zero.yml found")?;
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+    let mut engine = Engine::new(project_name, config).await?;
 
-    println!("{}", format!("✅ Backup saved to: {}", final_backup_file).green());
-    println!("\nTo restore this backup, run:");
-    println!("  {}", format!("zero restore {} --file {}", service, final_backup_file).cyan());
+    println!("{}", format!("🔑 Updating {}...", key).cyan().bold());
+    let restarted = match value {
+        Some(value) => engine.set_secret(&key, value).await?,
+        None => engine.rotate_secret(&key).await?,
+    };
 
+    println!("{}", format!("✅ {} updated in {}", key, store.location()).green());
+    if restarted.is_empty() {
+        println!("{}", "Services pick up the new value when they next start".dimmed());
+    } else {
+        println!("{} {}", "🔄 Restarted:".cyan(), restarted.join(", ").yellow());
+    }
     Ok(())
 }
 
+/// New value of a credential: typed at a hidden prompt, or the first line of piped stdin
+fn read_secret_value(key: &str) -> Result<String> {
+    let value = if std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        let term = console::Term::stderr();
+        term.write_str(&format!("New value for {}: ", key))?;
+        term.read_secure_line()?
+    } else {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    };
+    if value.is_empty() {
+        anyhow::bail!("No value given for {}", key);
+    }
+    Ok(value)
+}
+
 pub async fn restore(service: String, file: String) -> Result<()> {
     println!("{}", format!("♻️  Restoring service: {}", service).cyan().bold());
     println!("{}", format!("From file: {}", file).dimmed());
@@ -834,55 +1768,516 @@ pub async fn restore(service: String, file: String) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "zeroconfig-project".to_string());
 
+    if backup::BackupKind::for_service(&service).is_none() {
+        println!("{}", format!("Restore not supported for service type: {}", service).yellow());
+        return Ok(());
+    }
+
+    let engine = Engine::new(project_name, config).await?;
+
+    let (kind, verification) = backup::restore_service(&engine, &service, std::path::Path::new(&file)).await?;
+    match verification {
+        backup::Verification::Verified => println!("{}", "🔒 Checksum verified against manifest".green()),
+        backup::Verification::Unknown => println!("{}", "⚠️  Backup not in manifest; checksum not verified".yellow()),
+    }
+    println!("{}", format!("✅ {} restore completed successfully", kind.name()).green());
+
+    Ok(())
+}
+
+pub async fn expose(service: String, provider: Option<String>) -> Result<()> {
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found".red());
+            return Ok(());
+        }
+    };
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    let provider = match provider {
+        Some(name) => tunnels::TunnelProvider::from_name(&name)?,
+        None => tunnels::TunnelProvider::detect()?,
+    };
+
     let engine = Engine::new(project_name, config).await?;
+    let port = engine.get_host_port(&service).await?;
+
+    println!("{}", format!("🌐 Exposing {} (localhost:{}) via {}...", service, port, provider.name()).cyan().bold());
+
+    let tunnel = tunnels::Tunnel::open(provider, port).await?;
+
+    println!("{}", "✅ Tunnel is ready!".green().bold());
+    println!("  Public URL: {}", tunnel.public_url().green());
+    println!("  Local:      http://localhost:{}", tunnel.local_port());
+    println!("\nPress {} to close the tunnel", "Ctrl+C".yellow());
+
+    tokio::signal::ctrl_c().await?;
+    println!("\n{}", "🛑 Closing tunnel...".yellow());
+    tunnel.close().await?;
 
-    // Read backup file content
-    let backup_content = std::fs::read_to_string(&file)
-        .context(format!("Failed to read backup file: {}", file))?;
-
-    // Execute restore command based on service type
-    // We use base64 encoding to safely pass SQL content to the container
-    use base64::{Engine as Base64Engine, engine::general_purpose};
-    let encoded_content = general_purpose::STANDARD.encode(backup_content.as_bytes());
-
-    if service.contains("postgres") {
-        println!("Restoring PostgreSQL database...");
-
-        // Use base64 to safely pass SQL content
-        let restore_cmd = vec![
-            "sh".to_string(),
-            "-c".to_string(),
-            format!("echo '{}' | base64 -d | psql -U zeroconfig -d zeroconfig", encoded_content),
-        ];
-
-        engine.exec_command(&service, restore_cmd).await?;
-    } else if service.contains("mysql") {
-        println!("Restoring MySQL database...");
-
-        let restore_cmd = vec![
-            "sh".to_string(),
-            "-c".to_string(),
-            format!("echo '{}' | base64 -d | mysql -u root", encoded_content),
-        ];
-
-        engine.exec_command(&service, restore_cmd).await?;
-    } else if service.contains("mongo") {
-        println!("Restoring MongoDB database...");
-
-        // For MongoDB, read the binary archive and use mongorestore
-        let restore_cmd = vec![
-            "sh".to_string(),
-            "-c".to_string(),
-            format!("echo '{}' | base64 -d | mongorestore --archive", encoded_content),
-        ];
-
-        engine.exec_command(&service, restore_cmd).await?;
+    Ok(())
+}
+
+pub async fn share(service: Option<String>, provider: Option<String>, stop: bool) -> Result<()> {
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found".red());
+            return Ok(());
+        }
+    };
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    let mut engine = Engine::new(project_name, config).await?;
+
+    if stop {
+        let closed = engine.unshare(service.as_deref())?;
+        if closed.is_empty() {
+            println!("{}", "No shared services to stop".yellow());
+        }
+        for service in closed {
+            println!("{} {}", "🛑 Stopped sharing".yellow(), service);
+        }
+        return Ok(());
+    }
+
+    let Some(service) = service else {
+        let shares = engine.shares();
+        println!("{}", "🌐 Shared services:".cyan().bold());
+        if shares.is_empty() {
+            println!("None; share one with `zero share <service>`");
+        }
+        for (service, tunnel) in shares {
+            println!("  {} → {} (localhost:{}, {})", service.green(), tunnel.public_url, tunnel.local_port, tunnel.provider);
+        }
+        return Ok(());
+    };
+
+    let provider = match provider {
+        Some(name) => tunnels::TunnelProvider::from_name(&name)?,
+        None => tunnels::TunnelProvider::detect()?,
+    };
+
+    println!("{}", format!("🌐 Sharing {} via {}...", service, provider.name()).cyan().bold());
+    let tunnel = engine.share(&service, provider).await?;
+
+    println!("{}", "✅ Service is shared!".green().bold());
+    println!("  Public URL: {}", tunnel.public_url.green());
+    println!("  Local:      http://localhost:{}", tunnel.local_port);
+    println!("\nStop it with {} or {}", format!("zero share --stop {}", service).yellow(), "zero down".yellow());
+
+    Ok(())
+}
+
+pub async fn proxy(action: Option<ProxyCommands>) -> Result<()> {
+    if let Some(ProxyCommands::Trust) = action {
+        let ca = proxy::ca::LocalCa::load_or_create(&proxy::ca::ca_dir()?)?;
+        println!("{}", format!("🔐 Trusting the local CA at {}", ca.cert_path().display()).cyan().bold());
+        for command in proxy::ca::trust(&ca.cert_path())? {
+            println!("  {} {}", "✓".green(), command);
+        }
+        println!("{}", "✅ Browsers will accept https://*.localhost once restarted".green().bold());
+        return Ok(());
+    }
+
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found".red());
+            return Ok(());
+        }
+    };
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    let mut services: Vec<String> = config.services.keys().cloned().collect();
+    services.sort();
+    let orchestrator = ContainerOrchestrator::connect(project_name, config.runtime.host.as_deref()).await?;
+    let proxy = std::sync::Arc::new(proxy::Proxy::new(config.proxy.clone(), services.clone(), orchestrator)?);
+
+    println!("{}", "🔀 Proxy is ready:".cyan().bold());
+    for service in &services {
+        println!("  {} → {}", service, proxy.url(service).green());
+    }
+    if let Some(cert) = proxy.ca_cert_path() {
+        println!("\nCertificates are signed by {}", cert.display());
+        println!("Run {} once if browsers do not trust it yet", "zero proxy trust".yellow());
+    }
+    println!("\nPress {} to stop the proxy", "Ctrl+C".yellow());
+
+    tokio::select! {
+        result = proxy.serve() => result?,
+        _ = tokio::signal::ctrl_c() => println!("\n{}", "🛑 Stopping proxy...".yellow()),
+    }
+    Ok(())
+}
+
+pub async fn open(target: Option<String>) -> Result<()> {
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found".red());
+            return Ok(());
+        }
+    };
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    let engine = Engine::new(project_name, config).await?;
+    let uis = engine.web_uis().await;
+
+    let Some(target) = target else {
+        println!("{}", "🖥️  Web UIs:".cyan().bold());
+        if uis.is_empty() {
+            println!("No web UIs running");
+        }
+        print_web_uis(&uis);
+        return Ok(());
+    };
+
+    // `mail`, `mailhog` and `mailpit` all open the inbox, whichever catcher the project runs
+    let is_mail = matches!(target.as_str(), "mail" | "mailhog" | "mailpit");
+    let ui = uis.iter()
+        .find(|ui| ui.name == target)
+        .or_else(|| uis.iter().find(|ui| is_mail && crate::services::MailpitService::handles(&ui.name)))
+        .with_context(|| format!("No web UI running for '{}'", target))?;
+
+    println!("{}", format!("🌐 Opening {} at {}", ui.title, ui.url).cyan().bold());
+    open_browser(&ui.url)
+}
+
+fn print_web_uis(uis: &[crate::core::WebUi]) {
+    for ui in uis {
+        println!("  {} - {} {}", ui.name.green(), ui.title, ui.url.cyan());
+        if let (Some(username), Some(password)) = (&ui.username, &ui.password) {
+            println!("    login: {} / {}", username, password);
+        }
+    }
+}
+
+fn open_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", url]);
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = std::process::Command::new("open");
+        command.arg(url);
+        command
     } else {
-        println!("{}", format!("Restore not supported for service type: {}", service).yellow());
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(url);
+        command
+    };
+    command.spawn().context("Failed to open the browser")?;
+    Ok(())
+}
+
+pub async fn dev(ignore: Vec<String>, debounce: u64) -> Result<()> {
+    println!("{}", "🛠️  Starting dev mode...".cyan().bold());
+
+    let config = match ZeroConfig::discover()? {
+        Some(cfg) => cfg,
+        None => {
+            println!("{}", "Error: No zero.yml found in current directory or parents".red());
+            return Ok(());
+        }
+    };
+    config.validate()?;
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+    let mut engine = Engine::new(project_name, config).await?;
+
+    engine.build().await?;
+    engine.start().await?;
+
+    let project_dir = engine.project_dir();
+    let mut watcher = crate::watch::FileWatcher::new(std::slice::from_ref(&project_dir), &ignore)?
+        .with_debounce(std::time::Duration::from_millis(debounce));
+
+    println!("{}", format!("👀 Watching {} for changes", project_dir.display()).cyan().bold());
+    println!("Press {} to stop\n", "Ctrl+C".yellow());
+
+    loop {
+        let changed = tokio::select! {
+            changed = watcher.next_change() => changed,
+            _ = tokio::signal::ctrl_c() => None,
+        };
+        let Some(changed) = changed else { break };
+
+        print_changed(&changed);
+        match engine.apply_changes(&changed).await {
+            Ok(update) if update.is_empty() => println!("{}", "Nothing to rebuild".dimmed()),
+            Ok(update) => {
+                for service in &update.rebuilt {
+                    println!("{}", format!("✅ Rebuilt and restarted {}", service).green());
+                }
+                for service in &update.restarted {
+                    println!("{}", format!("✅ Restarted {}", service).green());
+                }
+                for service in &update.stopped {
+                    println!("{}", format!("🛑 Stopped {}, no longer in zero.yml", service).yellow());
+                }
+            }
+            Err(e) => println!("{}", format!("❌ {:#}", e).red()),
+        }
+        watcher.drain();
+    }
+
+    println!("\n{}", "🛑 Stopping services...".yellow());
+    engine.stop().await?;
+    Ok(())
+}
+
+pub async fn watch(
+    command: Vec<String>,
+    paths: Vec<String>,
+    ignore: Vec<String>,
+    restart: Option<String>,
+    debounce: u64,
+) -> Result<()> {
+    if command.is_empty() && restart.is_none() {
+        println!("{}", "Error: Provide a command to run or --restart <service>".red());
+        println!("  Example: {}", "zero watch -- cargo test".cyan());
         return Ok(());
     }
 
-    println!("{}", "✅ Restore completed successfully".green());
+    let paths: Vec<std::path::PathBuf> = if paths.is_empty() {
+        vec![std::env::current_dir()?]
+    } else {
+        paths.into_iter().map(Into::into).collect()
+    };
+
+    // Restarting a service needs the project engine; plain commands do not
+    let engine = match &restart {
+        Some(_) => {
+            let config = match ZeroConfig::discover()? {
+                Some(cfg) => cfg,
+                None => {
+                    println!("{}", "Error: No zero.yml found".red());
+                    return Ok(());
+                }
+            };
+            let project_name = config.metadata.name
+                .clone()
+                .unwrap_or_else(|| "zeroconfig-project".to_string());
+            Some(Engine::new(project_name, config).await?)
+        }
+        None => None,
+    };
+
+    let mut watcher = crate::watch::FileWatcher::new(&paths, &ignore)?
+        .with_debounce(std::time::Duration::from_millis(debounce));
+
+    let action = match &restart {
+        Some(service) => format!("restart {}", service),
+        None => command.join(" "),
+    };
+    let watched: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+    println!("{}", format!("👀 Watching {} — will {}", watched.join(", "), action).cyan().bold());
+    println!("Press {} to stop\n", "Ctrl+C".yellow());
+
+    if let (Some(engine), Some(service)) = (&engine, &restart) {
+        loop {
+            let changed = tokio::select! {
+                changed = watcher.next_change() => changed,
+                _ = tokio::signal::ctrl_c() => None,
+            };
+            let Some(changed) = changed else { break };
+
+            print_changed(&changed);
+            println!("{}", format!("🔄 Restarting {}...", service).cyan());
+            match engine.restart_service(service).await {
+                Ok(()) => println!("{}", format!("✅ Service {} restarted", service).green()),
+                Err(e) => println!("{}", format!("❌ Failed to restart {}: {}", service, e).red()),
+            }
+            watcher.drain();
+        }
+    } else {
+        let command_line = command.join(" ");
+
+        loop {
+            println!("{}", format!("▶ {}", command_line).cyan().bold());
+            let mut child = watch_process(&command_line).spawn()
+                .with_context(|| format!("Failed to run: {}", command_line))?;
+
+            // A change while the command is still running restarts it
+            tokio::select! {
+                status = child.wait() => {
+                    let status = status?;
+                    if status.success() {
+                        println!("{}", "✅ Command succeeded".green());
+                    } else {
+                        println!("{}", format!("❌ Command failed ({})", status).red());
+                    }
+                }
+                changed = watcher.next_change() => {
+                    let _ = child.kill().await;
+                    let Some(changed) = changed else { break };
+                    print_changed(&changed);
+                    continue;
+                }
+                _ = tokio::signal::ctrl_c() => {
+                    let _ = child.kill().await;
+                    break;
+                }
+            }
+
+            let changed = tokio::select! {
+                changed = watcher.next_change() => changed,
+                _ = tokio::signal::ctrl_c() => None,
+            };
+            let Some(changed) = changed else { break };
+            print_changed(&changed);
+        }
+    }
 
+    println!("\n{}", "🛑 Stopped watching".yellow());
     Ok(())
 }
+
+fn watch_process(command_line: &str) -> tokio::process::Command {
+    let mut process = if cfg!(windows) {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    process.arg(command_line).kill_on_drop(true);
+    process
+}
+
+fn print_changed(changed: &[std::path::PathBuf]) {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let names: Vec<String> = changed
+        .iter()
+        .take(3)
+        .map(|p| p.strip_prefix(&cwd).unwrap_or(p).display().to_string())
+        .collect();
+    let more = changed.len().saturating_sub(names.len());
+    let suffix = if more > 0 { format!(" (+{} more)", more) } else { String::new() };
+    println!("\n{}", format!("📝 Changed: {}{}", names.join(", "), suffix).yellow());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy(service: &str) -> health::HealthStatus {
+        health::HealthStatus {
+            service_name: service.to_string(),
+            is_healthy: true,
+            status_message: "healthy".to_string(),
+            response_time_ms: 0,
+            last_check: std::time::SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_rolling_order_restarts_dependencies_first() {
+        let config = ZeroConfig::from_str(r#"
+services:
+  api:
+    port: auto
+    depends_on: [postgres, redis]
+  postgres:
+    port: auto
+  redis:
+    port: auto
+"#).unwrap();
+
+        assert_eq!(rolling_order(&config, &[]).unwrap(), vec!["postgres", "redis", "api"]);
+        let asked = vec!["api".to_string(), "postgres".to_string()];
+        assert_eq!(rolling_order(&config, &asked).unwrap(), vec!["postgres", "api"]);
+        assert!(rolling_order(&config, &["mysql".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_roll_stops_at_the_first_unhealthy_service() {
+        let services: Vec<String> = ["postgres", "redis", "api"].iter().map(|s| s.to_string()).collect();
+
+        let mut restarted = Vec::new();
+        let error = roll(&services, |service| {
+            restarted.push(service.clone());
+            async move {
+                if service == "redis" {
+                    anyhow::bail!("redis did not become healthy");
+                }
+                Ok(healthy(&service))
+            }
+        }).await.unwrap_err();
+        assert_eq!(restarted, vec!["postgres", "redis"]);
+        assert!(error.to_string().contains("stopped at redis (api left as they were)"));
+        assert!(error.to_string().contains("redis did not become healthy"));
+
+        let mut restarted = Vec::new();
+        roll(&services, |service| {
+            restarted.push(service.clone());
+            async move { Ok(healthy(&service)) }
+        }).await.unwrap();
+        assert_eq!(restarted, services);
+    }
+
+    fn status(services: &[&str]) -> Vec<ServiceHealth> {
+        services.iter().map(|service| (service.to_string(), None, false)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_poll_health_stops_once_all_are_healthy() {
+        let mut status = status(&["postgres", "redis"]);
+        let mut checks = Vec::new();
+        let mut rounds = Vec::new();
+        poll_health(&mut status, std::time::Duration::from_secs(60), std::time::Duration::ZERO, |service| {
+            checks.push(service.clone());
+            let round = checks.len();
+            async move {
+                // redis comes up on its second check
+                let mut status = healthy(&service);
+                status.is_healthy = service != "redis" || round > 2;
+                Ok(status)
+            }
+        }, |_, last_round| {
+            rounds.push(last_round);
+            Ok(())
+        }).await.unwrap();
+
+        assert_eq!(checks, vec!["postgres", "redis", "redis"]);
+        assert_eq!(rounds, vec![false, true]);
+        assert!(status.iter().all(|(_, last, healthy)| *healthy && last.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_poll_health_gives_up_after_the_timeout() {
+        let mut status = status(&["postgres", "api"]);
+        let mut rounds = 0;
+        poll_health(&mut status, std::time::Duration::from_millis(20), std::time::Duration::from_millis(5), |service| async move {
+            if service == "api" {
+                anyhow::bail!("no container");
+            }
+            Ok(healthy(&service))
+        }, |_, _| {
+            rounds += 1;
+            Ok(())
+        }).await.unwrap();
+
+        assert!(rounds > 1);
+        assert!(status[0].2);
+        assert_eq!((status[1].1.is_none(), status[1].2), (true, false));
+    }
+}
@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{schema, ZeroConfig};
+
+/// How serious a configuration problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A single problem found in a zero.yml, located by YAML path and position
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Config file the problem is in, when several are stacked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Dotted path to the offending key, e.g. `services.postgres.version`
+    pub path: String,
+    /// 1-based line, when the key could be found in the source
+    pub line: Option<usize>,
+    /// 1-based column, when the key could be found in the source
+    pub column: Option<usize>,
+    pub severity: Severity,
+    /// Id of the lint rule that reported it, e.g. `ZC001`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    pub message: String,
+    /// How to fix it, e.g. ``did you mean `postgres`?``
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, path, message)
+    }
+
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, path, message)
+    }
+
+    pub fn new(severity: Severity, path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            path: path.into(),
+            line: None,
+            column: None,
+            severity,
+            rule: None,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Option<String>) -> Self {
+        self.suggestion = suggestion;
+        self
+    }
+
+    /// Point the diagnostic at its key in a config file's source
+    pub fn locate_in(&mut self, content: &str) -> bool {
+        match locate(content, &self.path) {
+            Some((line, column)) => {
+                self.line = Some(line);
+                self.column = Some(column);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, "{}:{}:", line, column)?;
+        }
+        if self.file.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse and validate zero.yml content, returning every problem with its position
+pub fn check(content: &str) -> Vec<Diagnostic> {
+    let config: ZeroConfig = match serde_yaml::from_str(content) {
+        Ok(config) => config,
+        Err(e) => {
+            let mut diagnostic = Diagnostic::error("", e.to_string());
+            if let Some(location) = e.location() {
+                diagnostic.line = Some(location.line());
+                diagnostic.column = Some(location.column());
+            }
+            return vec![diagnostic];
+        }
+    };
+
+    let value: serde_yaml::Value = serde_yaml::from_str(content).unwrap_or_default();
+    config
+        .diagnostics()
+        .into_iter()
+        .chain(unknown_keys(&value))
+        .map(|mut diagnostic| {
+            diagnostic.locate_in(content);
+            diagnostic
+        })
+        .collect()
+}
+
+/// Keys the schema does not know, which serde would silently ignore
+pub fn unknown_keys(value: &serde_yaml::Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_keys(value, &schema::json_schema(), "", &mut diagnostics);
+    diagnostics
+}
+
+fn check_keys(value: &serde_yaml::Value, schema: &serde_json::Value, path: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(mapping) = value.as_mapping() else { return };
+    let properties = schema.get("properties").and_then(serde_json::Value::as_object);
+
+    for (key, child) in mapping {
+        let Some(key) = key.as_str() else { continue };
+        let child_path = if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+        match (properties.and_then(|properties| properties.get(key)), schema.get("additionalProperties")) {
+            (Some(child_schema), _) => check_keys(child, child_schema, &child_path, diagnostics),
+            (None, Some(serde_json::Value::Bool(false))) => {
+                let known = properties.into_iter().flat_map(|properties| properties.keys().map(String::as_str));
+                diagnostics.push(
+                    Diagnostic::warning(child_path, format!("Unknown key '{}' is ignored", key))
+                        .with_suggestion(did_you_mean(key, known)),
+                );
+            }
+            (None, Some(child_schema)) if child_schema.is_object() => check_keys(child, child_schema, &child_path, diagnostics),
+            _ => {}
+        }
+    }
+}
+
+/// Suggest the candidate closest to a misspelled name, if one is close enough
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let threshold = (name.chars().count() / 3).clamp(1, 3);
+    candidates.into_iter()
+        .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance > 0 && *distance <= threshold)
+        .min()
+        .map(|(_, candidate)| format!("did you mean `{}`?", candidate))
+}
+
+/// Find the line and column of a dotted key path in YAML source
+///
+/// Falls back to the deepest enclosing key that exists, so problems with
+/// missing keys still point somewhere useful.
+pub fn locate(content: &str, path: &str) -> Option<(usize, usize)> {
+    if path.is_empty() {
+        return None;
+    }
+
+    let target: Vec<&str> = path.split('.').collect();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut best: Option<(usize, (usize, usize))> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+            continue;
+        }
+
+        let Some((key, _)) = trimmed.split_once(':') else { continue };
+        let key = key.trim().trim_matches('"').trim_matches('\'');
+        let indent = line.len() - trimmed.len();
+
+        while stack.last().map(|(i, _)| *i >= indent).unwrap_or(false) {
+            stack.pop();
+        }
+        stack.push((indent, key.to_string()));
+
+        let depth = stack.len();
+        let matches_prefix = depth <= target.len()
+            && stack.iter().zip(&target).all(|((_, key), part)| key == part);
+
+        if matches_prefix {
+            let position = (index + 1, indent + 1);
+            if depth == target.len() {
+                return Some(position);
+            }
+            if best.map(|(d, _)| depth > d).unwrap_or(true) {
+                best = Some((depth, position));
+            }
+        }
+    }
+
+    best.map(|(_, position)| position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_nested_key() {
+        let yaml = "services:\n  postgres:\n    version: \"\"\n  redis:\n    version: \"7\"\n";
+        assert_eq!(locate(yaml, "services.postgres.version"), Some((3, 5)));
+        assert_eq!(locate(yaml, "services.redis"), Some((4, 3)));
+        // Missing keys fall back to the closest parent
+        assert_eq!(locate(yaml, "services.redis.port"), Some((4, 3)));
+        assert_eq!(locate(yaml, "backups.schedule"), None);
+    }
+
+    #[test]
+    fn test_check_reports_validation_errors_with_position() {
+        let yaml = "services:\n  postgres:\n    version: \"\"\n";
+        let diagnostics = check(yaml);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "services.postgres.version");
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert!(diagnostics[0].is_error());
+    }
+
+    #[test]
+    fn test_check_suggests_fixes_for_typos() {
+        let yaml = "servics:\n  redis: {}\nservices:\n  postgres:\n    verison: \"16\"\n  api:\n    build: {}\n    depends_on: [postgress]\n";
+        let diagnostics = check(yaml);
+
+        let unknown = diagnostics.iter().find(|d| d.path == "servics").unwrap();
+        assert_eq!(unknown.suggestion.as_deref(), Some("did you mean `services`?"));
+        assert_eq!(unknown.line, Some(1));
+        let key = diagnostics.iter().find(|d| d.path == "services.postgres.verison").unwrap();
+        assert_eq!(key.to_string(), "5:5: services.postgres.verison: Unknown key 'verison' is ignored (did you mean `version`?)");
+        let dependency = diagnostics.iter().find(|d| d.path == "services.api.depends_on").unwrap();
+        assert_eq!(dependency.suggestion.as_deref(), Some("did you mean `postgres`?"));
+        assert!(diagnostics.iter().any(|d| d.path == "services.postgres.version" && d.is_error()));
+    }
+
+    #[test]
+    fn test_check_reports_yaml_syntax_errors() {
+        let diagnostics = check("services:\n  postgres: [\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].line.is_some());
+    }
+}
@@ -0,0 +1,449 @@
+//! Editing zero.yml in place, keeping comments, ordering and formatting
+//!
+//! Keys are addressed by dotted paths such as `services.postgres.version`.
+//! Setting or removing one rewrites only that key's lines; the rest of the file
+//! stays as written. Paths lead through block mappings (a `key:` per line), the
+//! way zero.yml is written; a flow value in the way (`{ a: 1 }`) is rewritten as
+//! a block. New keys go after their last sibling, in the file's indentation.
+
+use anyhow::{Context, Result};
+use serde_yaml::{Mapping, Value};
+
+use super::{ServiceConfig, ZeroConfig};
+
+/// Text of a config file, edited key by key
+pub struct ConfigEditor {
+    lines: Vec<String>,
+    trailing_newline: bool,
+}
+
+/// A `key:` line and the lines of its value
+struct Entry {
+    key: String,
+    line: usize,
+    indent: usize,
+    /// Line after the last one of the value, trailing blank lines and comments excluded
+    end: usize,
+    /// Byte offset just past the colon
+    colon: usize,
+}
+
+impl ConfigEditor {
+    pub fn new(content: &str) -> Self {
+        Self {
+            lines: content.lines().map(str::to_string).collect(),
+            trailing_newline: content.is_empty() || content.ends_with('\n'),
+        }
+    }
+
+    /// The edited text
+    pub fn content(&self) -> String {
+        let mut content = self.lines.join("\n");
+        if self.trailing_newline && !content.is_empty() {
+            content.push('\n');
+        }
+        content
+    }
+
+    /// Value at a dotted path, if it is set
+    pub fn get(&self, path: &str) -> Result<Option<Value>> {
+        let document: Value = serde_yaml::from_str(&self.content()).context("Failed to parse zero.yml")?;
+        Ok(path.split('.').try_fold(&document, |value, key| value.get(key)).cloned())
+    }
+
+    /// Set the value at a dotted path, creating the mappings leading to it
+    pub fn set(&mut self, path: &str, value: &Value) -> Result<()> {
+        let keys = split_path(path)?;
+        let mut range = (0, self.lines.len());
+        let mut parent_indent = None;
+
+        for (depth, key) in keys.iter().enumerate() {
+            let entries = self.entries(range);
+            let Some(entry) = entries.iter().find(|entry| entry.key == *key) else {
+                // Missing from here on: add the rest of the path as nested mappings
+                let nested = keys[depth + 1..].iter().rev().fold(value.clone(), |value, key| {
+                    Value::Mapping(Mapping::from_iter([(Value::String(key.to_string()), value)]))
+                });
+                let indent = entries.first()
+                    .map(|entry| entry.indent)
+                    .unwrap_or_else(|| parent_indent.map_or(0, |indent| indent + self.indent_unit()));
+                self.insert(&entries, range.0, key, &nested, indent);
+                return Ok(());
+            };
+
+            if depth == keys.len() - 1 {
+                self.replace(entry, value);
+                return Ok(());
+            }
+            if !self.inline_value(entry).0.is_empty() {
+                // A scalar or flow value in the way; rewrite the whole of it
+                let mut current = self.get(&keys[..=depth].join("."))?.unwrap_or(Value::Null);
+                set_nested(&mut current, &keys[depth + 1..], value.clone());
+                self.replace(entry, &current);
+                return Ok(());
+            }
+            range = (entry.line + 1, entry.end);
+            parent_indent = Some(entry.indent);
+        }
+        Ok(())
+    }
+
+    /// Remove the key at a dotted path with its value; `false` when it is not set
+    pub fn remove(&mut self, path: &str) -> Result<bool> {
+        let keys = split_path(path)?;
+        let mut range = (0, self.lines.len());
+        for (depth, key) in keys.iter().enumerate() {
+            let Some(entry) = self.entries(range).into_iter().find(|entry| entry.key == *key) else { return Ok(false) };
+            if depth < keys.len() - 1 {
+                range = (entry.line + 1, entry.end);
+                continue;
+            }
+
+            let mut start = entry.line;
+            // Don't leave two blank lines where the key was
+            let blank = |line: Option<&String>| line.is_none_or(|line| line.trim().is_empty());
+            if start > 0 && blank(self.lines.get(start - 1)) && blank(self.lines.get(entry.end)) {
+                start -= 1;
+            }
+            self.lines.drain(start..entry.end);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Set a top-level key, adding it above the others when it is missing
+    pub fn set_first(&mut self, key: &str, value: &Value) -> Result<()> {
+        let entries = self.entries((0, self.lines.len()));
+        if entries.iter().any(|entry| entry.key == key) || is_block(value) {
+            return self.set(key, value);
+        }
+        let at = entries.first().map_or(self.lines.len(), |first| first.line);
+        let line = format!("{}: {}", render_scalar(&Value::String(key.to_string()), None), render_scalar(value, None));
+        self.lines.splice(at..at, [line, String::new()]);
+        Ok(())
+    }
+
+    /// Add a service, or replace the one of that name
+    pub fn add_service(&mut self, name: &str, service: &ServiceConfig) -> Result<()> {
+        self.set(&format!("services.{}", name), &serde_yaml::to_value(service)?)
+    }
+
+    /// Set the image tag of a service
+    pub fn set_version(&mut self, service: &str, version: &str) -> Result<()> {
+        self.set(&format!("services.{}.version", service), &Value::String(version.to_string()))
+    }
+
+    /// Pin the host port of a service
+    pub fn set_port(&mut self, service: &str, port: u16) -> Result<()> {
+        self.set(&format!("services.{}.port", service), &Value::Number(port.into()))
+    }
+
+    /// Keys at the shallowest indentation within a range of lines
+    fn entries(&self, (start, end): (usize, usize)) -> Vec<Entry> {
+        let Some(indent) = (start..end).find(|&i| is_content(&self.lines[i])).map(|i| indentation(&self.lines[i])) else {
+            return Vec::new();
+        };
+
+        let mut entries = Vec::new();
+        for i in start..end {
+            let line = &self.lines[i];
+            if !is_content(line) || indentation(line) != indent {
+                continue;
+            }
+            let Some((key, colon)) = parse_key(line) else { continue };
+
+            let mut last = i;
+            for (j, line) in self.lines.iter().enumerate().take(end).skip(i + 1) {
+                let trimmed = line.trim_start();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                let child = indentation(line) > indent
+                    // Sequences may sit at the indentation of their key
+                    || (indentation(line) == indent && (trimmed == "-" || trimmed.starts_with("- ")));
+                if !child {
+                    break;
+                }
+                last = j;
+            }
+            entries.push(Entry { key, line: i, indent, end: last + 1, colon });
+        }
+        entries
+    }
+
+    /// Value written on a key's line and the comment after it, with its leading spaces
+    fn inline_value(&self, entry: &Entry) -> (String, String) {
+        let rest = &self.lines[entry.line][entry.colon..];
+        let (value, comment) = split_comment(rest);
+        (value.trim().to_string(), comment.to_string())
+    }
+
+    /// Replace the value of a key, on its line when both values fit there
+    fn replace(&mut self, entry: &Entry, value: &Value) {
+        let (previous, comment) = self.inline_value(entry);
+        let prefix = self.lines[entry.line][..entry.colon].to_string();
+        let mut lines = match (is_block(value), previous.starts_with(['|', '>'])) {
+            (false, false) if !previous.is_empty() => {
+                vec![format!("{} {}{}", prefix, render_scalar(value, Some(&previous)), comment)]
+            }
+            (false, _) => vec![format!("{} {}{}", prefix, render_scalar(value, None), comment)],
+            (true, _) => {
+                let mut lines = vec![format!("{}{}", prefix, if previous.is_empty() { comment } else { String::new() })];
+                render_block(value, entry.indent + self.indent_unit(), &mut lines);
+                lines
+            }
+        };
+        self.lines.splice(entry.line..entry.end, lines.drain(..));
+    }
+
+    /// Add a key after its last sibling, or at `start` when it has none
+    fn insert(&mut self, siblings: &[Entry], start: usize, key: &str, value: &Value, indent: usize) {
+        let mut lines = Vec::new();
+        // Siblings set apart by blank lines, like the services of zero.yml, get one too
+        let spaced = match siblings {
+            [.., before, last] => last.line > before.end && self.lines[last.line - 1].trim().is_empty(),
+            _ => false,
+        };
+        if spaced {
+            lines.push(String::new());
+        }
+
+        let key = render_scalar(&Value::String(key.to_string()), None);
+        if is_block(value) {
+            lines.push(format!("{}{}:", " ".repeat(indent), key));
+            render_block(value, indent + self.indent_unit(), &mut lines);
+        } else {
+            lines.push(format!("{}{}: {}", " ".repeat(indent), key, render_scalar(value, None)));
+        }
+
+        let at = siblings.last().map_or(start, |last| last.end);
+        self.lines.splice(at..at, lines);
+    }
+
+    /// Spaces each nesting level adds, as the file indents its first nested line
+    fn indent_unit(&self) -> usize {
+        self.lines.iter()
+            .filter(|line| is_content(line))
+            .map(|line| indentation(line))
+            .find(|&indent| indent > 0)
+            .unwrap_or(2)
+    }
+}
+
+/// Set `key` of zero.yml's text to `raw`, read as YAML (`16`, `true`, `[a, b]`), or as
+/// a string where zero.yml needs one; fails when the result is no valid zero.yml
+pub fn set_in(content: &str, key: &str, raw: &str) -> Result<String> {
+    let parsed: Value = serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    let mut candidates = vec![parsed.clone()];
+    if !matches!(parsed, Value::String(_)) {
+        candidates.push(Value::String(raw.to_string()));
+    }
+
+    let mut error = None;
+    for value in candidates {
+        let mut editor = ConfigEditor::new(content);
+        editor.set(key, &value)?;
+        let edited = editor.content();
+        match ZeroConfig::from_str(&edited) {
+            Ok(_) => return Ok(edited),
+            Err(e) => error = error.or(Some(e)),
+        }
+    }
+    let error = error.map(|e| format!("{:#}", e)).unwrap_or_default();
+    anyhow::bail!("Setting {} to {} leaves zero.yml invalid: {}", key, raw, error)
+}
+
+fn split_path(path: &str) -> Result<Vec<&str>> {
+    let keys: Vec<&str> = path.split('.').collect();
+    if keys.iter().any(|key| key.is_empty()) {
+        anyhow::bail!("Invalid key '{}'; use a dotted path such as services.postgres.version", path);
+    }
+    Ok(keys)
+}
+
+/// Set a path below `value`, turning whatever is in the way into mappings
+fn set_nested(value: &mut Value, keys: &[&str], new: Value) {
+    let Some((key, rest)) = keys.split_first() else {
+        *value = new;
+        return;
+    };
+    if !value.is_mapping() {
+        *value = Value::Mapping(Mapping::new());
+    }
+    if let Value::Mapping(mapping) = value {
+        let child = mapping.entry(Value::String(key.to_string())).or_insert(Value::Null);
+        set_nested(child, rest, new);
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether a line holds YAML rather than nothing or a comment
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// Key of a `key: value` line, unquoted, and the byte offset past its colon
+fn parse_key(line: &str) -> Option<(String, usize)> {
+    let start = indentation(line);
+    let text = &line[start..];
+    let (key, colon) = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = text[1..].find(quote)? + 1;
+            (text[1..close].to_string(), close + 1)
+        }
+        '-' | '#' | '{' | '[' => return None,
+        _ => {
+            let colon = text.char_indices()
+                .find(|&(i, c)| c == ':' && text[i + 1..].chars().next().is_none_or(char::is_whitespace))?
+                .0;
+            (text[..colon].trim_end().to_string(), colon)
+        }
+    };
+    text[colon..].starts_with(':').then(|| (key, start + colon + 1))
+}
+
+/// Split `value  # comment` where the `#` is outside quotes
+fn split_comment(text: &str) -> (&str, &str) {
+    let (mut single, mut double) = (false, false);
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '\'' if !double => single = !single,
+            '"' if !single => double = !double,
+            '#' if !single && !double && previous.is_whitespace() => {
+                let value = text[..i].trim_end();
+                return (value, &text[value.len()..]);
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    (text, "")
+}
+
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Mapping(mapping) => !mapping.is_empty(),
+        Value::Sequence(sequence) => !sequence.is_empty(),
+        _ => false,
+    }
+}
+
+/// A scalar as zero.yml writes it: plain when YAML allows, else double-quoted,
+/// or in the quotes of the value it replaces
+fn render_scalar(value: &Value, previous: Option<&str>) -> String {
+    let quoted = previous.is_some_and(|previous| previous.starts_with(['\'', '"']));
+    if let (true, Value::Number(_) | Value::Bool(_)) = (quoted, value) {
+        return render_scalar(&Value::String(render_scalar(value, None)), previous);
+    }
+    match value {
+        Value::String(string) if previous.is_some_and(|previous| previous.starts_with('\'')) => {
+            format!("'{}'", string.replace('\'', "''"))
+        }
+        Value::String(string) if previous.is_some_and(|previous| previous.starts_with('"')) => {
+            serde_json::to_string(string).unwrap_or_default()
+        }
+        Value::String(string) => {
+            let plain = serde_yaml::to_string(value).unwrap_or_default();
+            match plain.starts_with(['\'', '"']) || plain.trim_end().contains('\n') {
+                true => serde_json::to_string(string).unwrap_or_default(),
+                false => plain.trim_end().to_string(),
+            }
+        }
+        Value::Mapping(_) => "{}".to_string(),
+        Value::Sequence(_) => "[]".to_string(),
+        _ => serde_yaml::to_string(value).unwrap_or_default().trim_end().to_string(),
+    }
+}
+
+/// Lines of a non-empty mapping or sequence nested at `indent`
+fn render_block(value: &Value, indent: usize, lines: &mut Vec<String>) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let key = render_scalar(key, None);
+                if is_block(value) {
+                    lines.push(format!("{}{}:", pad, key));
+                    render_block(value, indent + 2, lines);
+                } else {
+                    lines.push(format!("{}{}: {}", pad, key, render_scalar(value, None)));
+                }
+            }
+        }
+        Value::Sequence(sequence) => {
+            for item in sequence {
+                if is_block(item) {
+                    // The item's first line goes on the dash
+                    let first = lines.len();
+                    render_block(item, indent + 2, lines);
+                    lines[first] = format!("{}- {}", pad, lines[first].trim_start());
+                } else {
+                    lines.push(format!("{}- {}", pad, render_scalar(item, None)));
+                }
+            }
+        }
+        _ => lines.push(format!("{}{}", pad, render_scalar(value, None))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+# Shop backend
+metadata:
+  name: shop
+
+services:
+  postgres:
+    version: \"15\"   # bump with care
+    port: auto
+
+  redis:
+    version: latest
+
+env:
+  NODE_ENV: development
+";
+
+    #[test]
+    fn test_edits_keep_the_rest_of_the_file() {
+        let mut editor = ConfigEditor::new(CONFIG);
+        editor.set_version("postgres", "16").unwrap();
+        editor.set_port("redis", 6380).unwrap();
+        editor.set("env.LOG_LEVEL", &Value::String("debug".to_string())).unwrap();
+        let service: ServiceConfig = serde_yaml::from_str("version: \"7\"\nenvironment:\n  MEILI_ENV: development\n").unwrap();
+        editor.add_service("meilisearch", &service).unwrap();
+
+        let edited = editor.content();
+        assert!(edited.starts_with("# Shop backend\nmetadata:\n  name: shop\n\nservices:\n"));
+        assert!(edited.contains("    version: \"16\"   # bump with care\n    port: auto\n"));
+        assert!(edited.contains("  redis:\n    version: latest\n    port: 6380\n\n  meilisearch:\n    version: \"7\"\n"));
+        assert!(edited.contains("    environment:\n      MEILI_ENV: development\n"));
+        assert!(edited.ends_with("env:\n  NODE_ENV: development\n  LOG_LEVEL: debug\n"));
+
+        let config = ZeroConfig::from_str(&edited).unwrap();
+        assert_eq!(config.services["postgres"].version, "16");
+
+        assert!(editor.remove("services.meilisearch").unwrap());
+        assert!(!editor.remove("services.mysql").unwrap());
+        assert_eq!(editor.get("services.redis.port").unwrap(), Some(Value::Number(6380.into())));
+    }
+
+    #[test]
+    fn test_set_in_reads_values_as_zero_yml_needs_them() {
+        let edited = set_in(CONFIG, "services.postgres.version", "16").unwrap();
+        assert!(edited.contains("    version: \"16\"   # bump with care\n"));
+
+        let edited = set_in(CONFIG, "services.postgres.port", "5433").unwrap();
+        assert!(edited.contains("    port: 5433\n"));
+
+        assert!(set_in(CONFIG, "services.postgres.environment", "5").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Best-practice rules for zero.yml, run by `zero config lint`
+//!
+//! Unlike validation, these flag configurations that work but are risky:
+//! moving image tags, secrets committed in zero.yml and the like.
+
+use super::diagnostics::{Diagnostic, Severity};
+use super::migrate::CURRENT_VERSION;
+use super::schema::{AUTO_GENERATE, KNOWN_SERVICES};
+use super::{PortConfig, PortValue, ZeroConfig};
+use crate::secrets::{is_secret_key, vault};
+
+/// A lint rule, reported by its id
+pub struct Rule {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+}
+
+pub const LATEST_TAG: Rule = Rule {
+    id: "ZC001",
+    name: "latest-tag",
+    severity: Severity::Warning,
+    description: "`latest` changes under you; pin a version so everyone runs the same image",
+};
+
+pub const PRIVILEGED_PORT: Rule = Rule {
+    id: "ZC002",
+    name: "privileged-port",
+    severity: Severity::Warning,
+    description: "Host ports below 1024 need root on Linux and clash with system services",
+};
+
+pub const MISSING_HEALTHCHECK: Rule = Rule {
+    id: "ZC003",
+    name: "missing-healthcheck",
+    severity: Severity::Info,
+    description: "Without a healthcheck, dependents and --wait only know the container started",
+};
+
+pub const LITERAL_SECRET: Rule = Rule {
+    id: "ZC004",
+    name: "literal-secret",
+    severity: Severity::Error,
+    description: "Secrets written in zero.yml end up in version control; use a vault: reference, or auto-generate under env:",
+};
+
+pub const ABSOLUTE_VOLUME: Rule = Rule {
+    id: "ZC005",
+    name: "absolute-volume",
+    severity: Severity::Warning,
+    description: "Absolute host paths only exist on one machine; use a path relative to zero.yml or a named volume",
+};
+
+pub const OUTDATED_SCHEMA: Rule = Rule {
+    id: "ZC006",
+    name: "outdated-schema",
+    severity: Severity::Info,
+    description: "Run `zero upgrade-config` to move zero.yml to the current schema and record its version",
+};
+
+/// Every rule, in id order
+pub const RULES: [&Rule; 6] = [&LATEST_TAG, &PRIVILEGED_PORT, &MISSING_HEALTHCHECK, &LITERAL_SECRET, &ABSOLUTE_VOLUME, &OUTDATED_SCHEMA];
+
+impl Rule {
+    fn diagnostic(&self, path: impl Into<String>, message: impl Into<String>) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(self.severity, path, message);
+        diagnostic.rule = Some(self.id.to_string());
+        diagnostic.with_suggestion(Some(self.description.to_string()))
+    }
+}
+
+/// Run every rule over a configuration, located in the files it was loaded from
+pub fn lint(config: &ZeroConfig) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut services: Vec<_> = config.services.iter().collect();
+    services.sort_by_key(|(service, _)| *service);
+
+    for (service, service_config) in services {
+        let path = format!("services.{}", service);
+
+        if service_config.build.is_none() && is_latest(&service_config.version) {
+            diagnostics.push(LATEST_TAG.diagnostic(
+                format!("{}.version", path),
+                format!("Service '{}' runs the '{}' tag", service, service_config.version),
+            ));
+        }
+
+        let mut host_ports: Vec<u16> = service_config.ports.iter().filter_map(|mapping| mapping.host).collect();
+        match &service_config.port {
+            PortValue::Fixed(port) => host_ports.push(*port),
+            PortValue::Range(range) => host_ports.push(range.min),
+            PortValue::Auto(_) => {}
+        }
+        if let Some(port) = host_ports.into_iter().filter(|port| *port < 1024).min() {
+            diagnostics.push(PRIVILEGED_PORT.diagnostic(
+                format!("{}.port", path),
+                format!("Service '{}' publishes host port {}", service, port),
+            ));
+        }
+
+        let is_app = service_config.build.is_some() || !KNOWN_SERVICES.iter().any(|known| known.name == service);
+        if is_app && service_config.healthcheck.is_none() {
+            diagnostics.push(MISSING_HEALTHCHECK.diagnostic(
+                path.clone(),
+                format!("Service '{}' has no healthcheck", service),
+            ));
+        }
+
+        diagnostics.extend(literal_secrets(&format!("{}.environment", path), &service_config.environment));
+
+        for volume in &service_config.volumes {
+            if let Some(host) = host_path(volume).filter(|host| is_absolute(host)) {
+                diagnostics.push(ABSOLUTE_VOLUME.diagnostic(
+                    format!("{}.volumes", path),
+                    format!("Service '{}' mounts the absolute path {}", service, host),
+                ));
+            }
+        }
+    }
+
+    if let PortConfig::Manual(ports) = &config.ports {
+        let mut ports: Vec<_> = ports.iter().filter(|(_, port)| **port < 1024).collect();
+        ports.sort();
+        for (service, port) in ports {
+            diagnostics.push(PRIVILEGED_PORT.diagnostic(
+                format!("ports.{}", service),
+                format!("Service '{}' gets host port {}", service, port),
+            ));
+        }
+    }
+
+    diagnostics.extend(literal_secrets("env", &config.env));
+
+    let version = config.version.unwrap_or(0);
+    if version < CURRENT_VERSION {
+        diagnostics.push(OUTDATED_SCHEMA.diagnostic(
+            "version",
+            format!("zero.yml is schema version {}; the current one is {}", version, CURRENT_VERSION),
+        ));
+    }
+    config.locate(&mut diagnostics);
+    diagnostics
+}
+
+fn literal_secrets(path: &str, env: &std::collections::HashMap<String, String>) -> Vec<Diagnostic> {
+    let mut keys: Vec<&String> = env.iter()
+        .filter(|(key, value)| is_secret_key(key) && is_literal(value))
+        .map(|(key, _)| key)
+        .collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|key| LITERAL_SECRET.diagnostic(format!("{}.{}", path, key), format!("{} is set to a literal value", key)))
+        .collect()
+}
+
+fn is_latest(version: &str) -> bool {
+    version == "latest" || version.ends_with(":latest")
+}
+
+/// Whether a value is written out rather than generated or referenced
+fn is_literal(value: &str) -> bool {
+    !(value.is_empty() || value == AUTO_GENERATE || value.starts_with(vault::PREFIX) || value.starts_with("${"))
+}
+
+/// Host side of a `host:container[:mode]` volume, `None` for anonymous volumes
+fn host_path(volume: &str) -> Option<&str> {
+    // Windows drive letters carry a colon of their own
+    let skip = if volume.as_bytes().get(1) == Some(&b':') { 2 } else { 0 };
+    volume[skip..].find(':').map(|end| &volume[..skip + end])
+}
+
+fn is_absolute(path: &str) -> bool {
+    path.starts_with('/') || path.starts_with('~') || path.as_bytes().get(1) == Some(&b':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_rules() {
+        let yaml = r#"
+services:
+  postgres:
+    version: latest
+    port: 80
+    volumes: ["/var/lib/pg:/var/lib/postgresql/data", "./init:/docker-entrypoint-initdb.d"]
+    environment:
+      POSTGRES_PASSWORD: hunter2
+  api:
+    build: {}
+    environment:
+      API_TOKEN: auto-generate
+      DB_PASSWORD: "vault:secret/data/api#DB_PASSWORD"
+env:
+  JWT_SECRET: changeme
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let rules: Vec<(String, String)> = lint(&config).into_iter()
+            .map(|diagnostic| (diagnostic.rule.unwrap(), diagnostic.path))
+            .collect();
+
+        assert_eq!(rules, vec![
+            ("ZC003".to_string(), "services.api".to_string()),
+            ("ZC001".to_string(), "services.postgres.version".to_string()),
+            ("ZC002".to_string(), "services.postgres.port".to_string()),
+            ("ZC004".to_string(), "services.postgres.environment.POSTGRES_PASSWORD".to_string()),
+            ("ZC005".to_string(), "services.postgres.volumes".to_string()),
+            ("ZC004".to_string(), "env.JWT_SECRET".to_string()),
+            ("ZC006".to_string(), "version".to_string()),
+        ]);
+        assert_eq!(host_path(r"C:\data:/data"), Some(r"C:\data"));
+        assert_eq!(host_path("pgdata:/data"), Some("pgdata"));
+        assert_eq!(host_path("/data"), None);
+    }
+}
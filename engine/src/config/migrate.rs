@@ -0,0 +1,190 @@
+//! Upgrading zero.yml to the current schema version
+//!
+//! zero.yml records the schema it is written against in `version:`; files
+//! without one are version 0. Each migration moves a file one version up by
+//! editing its text, so comments and layout survive, and describes what it
+//! changed. `zero upgrade-config` applies the ones a file is missing.
+
+use anyhow::{Context, Result};
+use serde_yaml::Value;
+
+use super::edit::ConfigEditor;
+use super::ZeroConfig;
+
+/// Schema version of zero.yml this build writes and reads
+pub const CURRENT_VERSION: u32 = 1;
+
+/// A change to zero.yml from one schema version to the next
+struct Migration {
+    /// Version the migration upgrades to
+    to: u32,
+    /// Apply it, returning a line describing each change
+    apply: fn(&mut ConfigEditor) -> Result<Vec<String>>,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration { to: 1, apply: service_ports }];
+
+/// Result of upgrading a config file
+#[derive(Debug)]
+pub struct Upgrade {
+    pub from: u32,
+    pub to: u32,
+    /// What each migration changed, in order
+    pub changes: Vec<String>,
+    pub content: String,
+}
+
+impl Upgrade {
+    pub fn is_needed(&self) -> bool {
+        self.from != self.to
+    }
+}
+
+/// Schema version a config file declares, 0 without `version:`
+pub fn version_of(content: &str) -> Result<u32> {
+    let document: Value = serde_yaml::from_str(content).context("Failed to parse zero.yml")?;
+    match document.get("version") {
+        None | Some(Value::Null) => Ok(0),
+        Some(version) => version.as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .with_context(|| format!("version: must be a schema version number such as {}", CURRENT_VERSION)),
+    }
+}
+
+/// Upgrade the text of a config file to [`CURRENT_VERSION`]
+pub fn upgrade(content: &str) -> Result<Upgrade> {
+    let from = version_of(content)?;
+    if from > CURRENT_VERSION {
+        anyhow::bail!(
+            "zero.yml is schema version {}, newer than the {} this zero understands; upgrade zero instead",
+            from, CURRENT_VERSION
+        );
+    }
+
+    let mut editor = ConfigEditor::new(content);
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|migration| migration.to > from) {
+        changes.extend((migration.apply)(&mut editor)
+            .with_context(|| format!("Failed to upgrade zero.yml to version {}", migration.to))?);
+    }
+    if from < CURRENT_VERSION {
+        editor.set_first("version", &Value::Number(CURRENT_VERSION.into()))?;
+        changes.push(format!("Set version: {}", CURRENT_VERSION));
+    }
+
+    let content = editor.content();
+    ZeroConfig::from_str(&content).context("The upgraded zero.yml does not parse; it was left unchanged")?;
+    Ok(Upgrade { from, to: CURRENT_VERSION, changes, content })
+}
+
+/// Version 1: the top-level `ports:` map moves into each service's `port:`
+///
+/// Entries for services that are not defined, or that set a `port:` of their
+/// own, stay in `ports:` rather than creating a service or changing a port.
+fn service_ports(editor: &mut ConfigEditor) -> Result<Vec<String>> {
+    let mut changes = Vec::new();
+
+    let Some(Value::Mapping(ports)) = editor.get("ports")? else {
+        return Ok(changes);
+    };
+    let services = match editor.get("services")? {
+        Some(Value::Mapping(services)) => services,
+        _ => Default::default(),
+    };
+
+    let mut kept = 0;
+    for (service, port) in ports {
+        let Some(service) = service.as_str() else {
+            kept += 1;
+            continue;
+        };
+        match services.get(service) {
+            None => {
+                kept += 1;
+                changes.push(format!("Left ports.{} in place: there is no service {}", service, service));
+            }
+            Some(definition) if definition.get("port").is_some() => {
+                kept += 1;
+                changes.push(format!("Left ports.{} in place: services.{}.port is already set", service, service));
+            }
+            Some(_) => {
+                editor.set(&format!("services.{}.port", service), &port)?;
+                editor.remove(&format!("ports.{}", service))?;
+                changes.push(format!("Moved ports.{} to services.{}.port", service, service));
+            }
+        }
+    }
+    if kept == 0 {
+        editor.remove("ports")?;
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_from_unversioned() {
+        let content = "\
+# Orders service
+metadata:
+  name: orders
+
+services:
+  postgresql:
+    version: \"16\"  # matches production
+  api:
+    build:
+      context: .
+    depends_on: [postgresql]
+
+ports:
+  postgresql: 5433
+";
+        let upgrade = upgrade(content).unwrap();
+        assert_eq!((upgrade.from, upgrade.to), (0, CURRENT_VERSION));
+        assert_eq!(upgrade.changes, ["Moved ports.postgresql to services.postgresql.port", "Set version: 1"]);
+        assert!(upgrade.content.starts_with("# Orders service\nversion: 1\n\nmetadata:\n"));
+        assert!(upgrade.content.contains("  postgresql:\n    version: \"16\"  # matches production\n    port: 5433\n"));
+        assert!(!upgrade.content.contains("ports:"));
+
+        // Aliases keep their names, and with them their containers, volumes and credentials
+        let config = ZeroConfig::from_str(&upgrade.content).unwrap();
+        assert_eq!(config.services["api"].depends_on, ["postgresql"]);
+
+        let again = super::upgrade(&upgrade.content).unwrap();
+        assert!(!again.is_needed() && again.changes.is_empty());
+        assert!(super::upgrade("version: 99\n").is_err());
+    }
+
+    #[test]
+    fn test_upgrade_leaves_ports_of_undefined_services() {
+        let content = "services:\n  redis: {}\n\nports:\n  redis: 6380\n  grafana: 3001\n";
+        let upgrade = upgrade(content).unwrap();
+        assert_eq!(upgrade.changes, [
+            "Moved ports.redis to services.redis.port",
+            "Left ports.grafana in place: there is no service grafana",
+            "Set version: 1",
+        ]);
+
+        let config = ZeroConfig::from_str(&upgrade.content).unwrap();
+        assert!(!config.services.contains_key("grafana"));
+        assert!(matches!(config.services["redis"].port, crate::config::PortValue::Fixed(6380)));
+        assert!(matches!(&config.ports, crate::config::PortConfig::Manual(ports) if ports.len() == 1 && ports["grafana"] == 3001));
+    }
+
+    #[test]
+    fn test_upgrade_keeps_service_port() {
+        let content = "services:\n  postgres:\n    port: 5434\n\nports:\n  postgres: 5433\n";
+        let upgrade = upgrade(content).unwrap();
+        assert_eq!(upgrade.changes, [
+            "Left ports.postgres in place: services.postgres.port is already set",
+            "Set version: 1",
+        ]);
+
+        let config = ZeroConfig::from_str(&upgrade.content).unwrap();
+        assert!(matches!(config.services["postgres"].port, crate::config::PortValue::Fixed(5434)));
+    }
+}
@@ -1,11 +1,27 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use anyhow::{Context, Result};
+use crate::cloud::azurite;
+use crate::services::elasticsearch::{self, ElasticsearchService};
+
+pub mod diagnostics;
+pub mod edit;
+pub mod lint;
+pub mod migrate;
+pub mod overlay;
+pub mod schema;
+
+pub use diagnostics::{Diagnostic, Severity};
 
 /// Main ZeroConfig configuration structure parsed from zero.yml
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZeroConfig {
+    /// Schema version the file is written against; `zero upgrade-config` brings older ones up to date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
     /// Programming language runtimes and their versions
     #[serde(default)]
     pub languages: HashMap<String, String>,
@@ -26,30 +42,423 @@ pub struct ZeroConfig {
     #[serde(default)]
     pub ports: PortConfig,
 
-    /// Startup commands to run after environment is ready
+    /// Commands run once every service is healthy, on the host or in a service's container
     #[serde(default)]
-    pub startup: Vec<String>,
+    pub startup: Vec<StartupCommand>,
+
+    /// Commands run on the host around `zero up` and `zero down`
+    #[serde(default)]
+    pub hooks: ProjectHooks,
 
     /// Optional project metadata
     #[serde(default)]
     pub metadata: ProjectMetadata,
+
+    /// Scheduled backup configuration
+    #[serde(default)]
+    pub backups: Option<BackupsConfig>,
+
+    /// Run Prometheus, Grafana and Jaeger with dashboards for the project's services and its apps' traces
+    #[serde(default)]
+    pub observability: bool,
+
+    /// Address `zero up` serves Prometheus metrics on while attached: `:9464` on
+    /// localhost, `0.0.0.0:9464` on every interface
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<String>,
+
+    /// Make `zero up` wait until every service is healthy, like `--wait`
+    #[serde(default)]
+    pub wait: bool,
+
+    /// Where generated credentials are kept
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+
+    /// Cluster `zero up --runtime kubernetes` deploys to
+    #[serde(default)]
+    pub kubernetes: KubernetesConfig,
+
+    /// Container engine to run services on
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
+
+    /// `zero proxy`, serving services as `<service>.localhost`
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+
+    /// Directory zero.yml was loaded from; relative paths in it resolve against it
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
+
+    /// Files the configuration was merged from, base first
+    #[serde(skip)]
+    pub sources: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceConfig {
+    /// Image tag to run; not needed for services with `build:`
+    #[serde(default)]
     pub version: String,
 
+    /// Build the service's image from a local Dockerfile instead of pulling one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildConfig>,
+
     #[serde(default)]
     pub port: PortValue,
 
-    #[serde(default)]
+    /// Port the service listens on inside its container; defaults to the
+    /// service's well-known port, else the first port its image exposes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_port: Option<u16>,
+
+    /// More ports to publish, as `9001` (any free host port) or `"19001:9001"`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<PortMapping>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub environment: HashMap<String, String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub volumes: Vec<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub command: Option<String>,
+
+    /// Services that must be healthy before this one starts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Kafka topics to create once the broker is up, as `name` or `name:partitions`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+
+    /// MinIO buckets to create once the server is up
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buckets: Vec<String>,
+
+    /// RabbitMQ definitions export (vhosts, users, queues, exchanges) to import
+    /// once the broker is up, relative to zero.yml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definitions: Option<String>,
+
+    /// Keycloak realm export (JSON) to import once the server is up, relative to zero.yml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realm: Option<String>,
+
+    /// Run the database's web admin tool (pgAdmin, Adminer, Redis Insight or mongo-express)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub admin_ui: bool,
+
+    /// Path Prometheus scrapes the service's metrics from with `observability: true`, e.g. `/metrics`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_path: Option<String>,
+
+    /// Run Kibana (OpenSearch Dashboards for OpenSearch) against this Elasticsearch
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kibana: bool,
+
+    /// Container memory limit, e.g. `512m` or `2g`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+
+    /// Health check run by the container engine, replacing the built-in one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<HealthcheckConfig>,
+
+    /// When the container engine restarts the container, e.g. after Docker Desktop restarts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart: Option<RestartPolicy>,
+
+    /// SQL files, scripts and commands run in the container, in order, once it is healthy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub init: Vec<InitStep>,
+
+    /// Project networks to join besides the default one, e.g. to keep a backend apart
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<String>,
+
+    /// More DNS names the service answers to on its networks, besides its service name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+/// Name of the project network every service joins
+pub const DEFAULT_NETWORK: &str = "default";
+
+/// A step of a service's `init:`, as a file path or `{ file | run, on_failure }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum InitStep {
+    File(String),
+    Step(InitStepConfig),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InitStepConfig {
+    /// SQL file or script, relative to zero.yml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+
+    /// Shell command to run in the container
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<String>,
+
+    /// Whether a failing step stops the start or is only reported
+    #[serde(default)]
+    pub on_failure: OnFailure,
+}
+
+/// What a failing hook does to the rest of the start
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    #[default]
+    Fail,
+    Continue,
+}
+
+impl InitStep {
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            InitStep::File(file) => Some(file),
+            InitStep::Step(step) => step.file.as_deref(),
+        }
+    }
+
+    pub fn run(&self) -> Option<&str> {
+        match self {
+            InitStep::File(_) => None,
+            InitStep::Step(step) => step.run.as_deref(),
+        }
+    }
+
+    pub fn on_failure(&self) -> OnFailure {
+        match self {
+            InitStep::File(_) => OnFailure::Fail,
+            InitStep::Step(step) => step.on_failure,
+        }
+    }
+}
+
+/// A `startup:` command, as a host command line or `{ run, service }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StartupCommand {
+    Host(String),
+    Command(StartupCommandConfig),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartupCommandConfig {
+    /// Shell command line
+    pub run: String,
+
+    /// Service whose container runs the command, instead of the host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+}
+
+impl StartupCommand {
+    pub fn run(&self) -> &str {
+        match self {
+            StartupCommand::Host(run) => run,
+            StartupCommand::Command(command) => &command.run,
+        }
+    }
+
+    pub fn service(&self) -> Option<&str> {
+        match self {
+            StartupCommand::Host(_) => None,
+            StartupCommand::Command(command) => command.service.as_deref(),
+        }
+    }
+}
+
+impl std::fmt::Display for StartupCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.service() {
+            Some(service) => write!(f, "`{}` in {}", self.run(), service),
+            None => write!(f, "`{}`", self.run()),
+        }
+    }
+}
+
+impl std::fmt::Display for InitStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.file(), self.run()) {
+            (Some(file), _) => write!(f, "{}", file),
+            (None, Some(run)) => write!(f, "`{}`", run),
+            (None, None) => write!(f, "empty step"),
+        }
+    }
+}
+
+/// `restart:` of a service, as in Compose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    No,
+    Always,
+    OnFailure,
+    UnlessStopped,
+}
+
+impl std::fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            RestartPolicy::No => "no",
+            RestartPolicy::Always => "always",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::UnlessStopped => "unless-stopped",
+        };
+        f.write_str(name)
+    }
+}
+
+/// `build:` of an application service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildConfig {
+    /// Build context, relative to zero.yml
+    #[serde(default = "default_build_context")]
+    pub context: String,
+
+    /// Dockerfile, relative to the context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<String>,
+
+    /// Build arguments (`ARG` in the Dockerfile)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub args: HashMap<String, String>,
+}
+
+fn default_build_context() -> String {
+    ".".to_string()
+}
+
+impl BuildConfig {
+    pub fn dockerfile(&self) -> &str {
+        self.dockerfile.as_deref().unwrap_or("Dockerfile")
+    }
+}
+
+/// `healthcheck:` of a service, mirroring Compose's
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthcheckConfig {
+    /// Shell command (`pg_isready -U app`) or exec form (`[pg_isready, -U, app]`);
+    /// exit code 0 means healthy
+    pub command: HealthcheckCommand,
+
+    /// Time between checks, e.g. `10s`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+
+    /// Time a check may take before it counts as failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+
+    /// Failed checks in a row before the service is unhealthy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+
+    /// Grace period after start during which failures don't count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HealthcheckCommand {
+    Shell(String),
+    Exec(Vec<String>),
+}
+
+impl HealthcheckConfig {
+    /// Arguments to run the check with `docker exec`
+    pub fn exec_command(&self) -> Vec<String> {
+        match &self.command {
+            HealthcheckCommand::Shell(command) => vec!["sh".to_string(), "-c".to_string(), command.clone()],
+            HealthcheckCommand::Exec(args) => args.clone(),
+        }
+    }
+
+    /// The check in Docker's `Test` form: `CMD-SHELL` or `CMD` followed by the command
+    pub fn test(&self) -> Vec<String> {
+        match &self.command {
+            HealthcheckCommand::Shell(command) => vec!["CMD-SHELL".to_string(), command.clone()],
+            HealthcheckCommand::Exec(args) => std::iter::once("CMD".to_string()).chain(args.iter().cloned()).collect(),
+        }
+    }
+
+    pub fn interval(&self) -> Result<Option<Duration>> {
+        self.interval.as_deref().map(parse_duration).transpose()
+    }
+
+    pub fn timeout(&self) -> Result<Option<Duration>> {
+        self.timeout.as_deref().map(parse_duration).transpose()
+    }
+
+    pub fn start_period(&self) -> Result<Option<Duration>> {
+        self.start_period.as_deref().map(parse_duration).transpose()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.exec_command().last().is_none_or(|arg| arg.trim().is_empty()) {
+            anyhow::bail!("healthcheck command is empty");
+        }
+        self.interval()?;
+        self.timeout()?;
+        self.start_period()?;
+        Ok(())
+    }
+}
+
+impl ServiceConfig {
+    /// Memory limit in bytes, if `memory:` is set
+    pub fn memory_limit(&self) -> Result<Option<u64>> {
+        self.memory.as_deref().map(parse_memory).transpose()
+    }
+}
+
+/// Whether Docker accepts a name for a network or DNS alias
+fn is_dns_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|first| first.is_ascii_alphanumeric())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Parse a Compose-style duration such as `500ms`, `10s`, `2m`, `1h` or plain seconds
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (number, unit) = value.find(|c: char| !c.is_ascii_digit()).map_or((value, ""), |index| value.split_at(index));
+    let number: u64 = number.parse()
+        .with_context(|| format!("Invalid duration '{}', expected e.g. 10s or 1m", value))?;
+    let seconds = match unit {
+        "ms" => return Ok(Duration::from_millis(number)),
+        "" | "s" => Some(number),
+        "m" => number.checked_mul(60),
+        "h" => number.checked_mul(60 * 60),
+        _ => anyhow::bail!("Invalid duration unit in '{}', use ms, s, m or h", value),
+    };
+    seconds.map(Duration::from_secs)
+        .with_context(|| format!("Duration '{}' is too long", value))
+}
+
+/// Parse a Docker-style memory size such as `512m`, `2g` or `1073741824`
+pub fn parse_memory(value: &str) -> Result<u64> {
+    let value = value.trim().to_lowercase();
+    let value = value.strip_suffix('b').unwrap_or(&value);
+    let (number, multiplier) = match value.chars().last() {
+        Some('k') => (&value[..value.len() - 1], 1024),
+        Some('m') => (&value[..value.len() - 1], 1024 * 1024),
+        Some('g') => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    let number: u64 = number.trim().parse()
+        .with_context(|| format!("Invalid memory size '{}', expected e.g. 512m or 2g", value))?;
+    number.checked_mul(multiplier)
+        .with_context(|| format!("Memory size '{}' is too large", value))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +481,70 @@ pub struct PortRange {
     pub max: u16,
 }
 
+/// A container port published on the host, fixed or on any free host port
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PortMappingValue", into = "PortMappingValue")]
+pub struct PortMapping {
+    pub host: Option<u16>,
+    pub container: u16,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum PortMappingValue {
+    Port(u16),
+    Mapping(String),
+}
+
+impl std::str::FromStr for PortMapping {
+    type Err = anyhow::Error;
+
+    /// Parse `container` or `host:container`, with an optional `/tcp`
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let value = value.strip_suffix("/tcp").unwrap_or(value);
+        let parse = |port: &str| -> Result<u16> {
+            match port.trim().parse::<u16>() {
+                Ok(port) if port > 0 => Ok(port),
+                _ => anyhow::bail!("Invalid port mapping '{}', expected e.g. 9001 or \"19001:9001\"", value),
+            }
+        };
+        match value.split_once(':') {
+            Some((host, container)) => Ok(PortMapping { host: Some(parse(host)?), container: parse(container)? }),
+            None => Ok(PortMapping { host: None, container: parse(value)? }),
+        }
+    }
+}
+
+impl std::fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.host {
+            Some(host) => write!(f, "{}:{}", host, self.container),
+            None => write!(f, "{}", self.container),
+        }
+    }
+}
+
+impl TryFrom<PortMappingValue> for PortMapping {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PortMappingValue) -> Result<Self> {
+        match value {
+            PortMappingValue::Port(port) => port.to_string().parse(),
+            PortMappingValue::Mapping(mapping) => mapping.parse(),
+        }
+    }
+}
+
+impl From<PortMapping> for PortMappingValue {
+    fn from(mapping: PortMapping) -> Self {
+        match mapping.host {
+            Some(_) => PortMappingValue::Mapping(mapping.to_string()),
+            None => PortMappingValue::Port(mapping.container),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PortConfig {
@@ -104,17 +577,289 @@ pub struct CloudConfig {
 pub struct AwsConfig {
     pub services: Vec<String>,
     pub region: Option<String>,
+
+    /// Emulator to run; DynamoDB Local for projects that only use DynamoDB
+    #[serde(default, skip_serializing_if = "AwsFlavor::is_default")]
+    pub flavor: AwsFlavor,
+
+    /// Host port of the emulator, instead of 4566 (8000 for DynamoDB Local)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Image to run instead of the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Tag of the image, instead of `latest`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Created in LocalStack once it is ready
+    #[serde(default, skip_serializing_if = "AwsResources::is_empty")]
+    pub resources: AwsResources,
+}
+
+/// Emulator run for `cloud.aws`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AwsFlavor {
+    #[default]
+    Localstack,
+    /// amazon/dynamodb-local, emulating DynamoDB only
+    DynamodbLocal,
+}
+
+impl AwsFlavor {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AwsResources {
+    /// S3 bucket names
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buckets: Vec<String>,
+
+    /// SQS queue names; a `.fifo` suffix makes a FIFO queue
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queues: Vec<String>,
+
+    /// SNS topic names; a `.fifo` suffix makes a FIFO topic
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<DynamoTable>,
+}
+
+impl AwsResources {
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty() && self.queues.is_empty() && self.topics.is_empty() && self.tables.is_empty()
+    }
+}
+
+/// A DynamoDB table, billed per request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamoTable {
+    pub name: String,
+
+    /// Hash key as `attribute` or `attribute:TYPE`, TYPE being S (the default), N or B
+    pub partition_key: String,
+
+    /// Range key, in the same form
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_key: Option<String>,
+}
+
+impl DynamoTable {
+    /// Attribute name and type of a key given as `attribute[:TYPE]`
+    pub fn key_attribute(key: &str) -> Result<(&str, &str)> {
+        let (name, kind) = key.split_once(':').unwrap_or((key, "S"));
+        if name.is_empty() {
+            anyhow::bail!("Key '{}' has no attribute name", key);
+        }
+        if !matches!(kind, "S" | "N" | "B") {
+            anyhow::bail!("Key '{}' has type '{}'; use S, N or B", key, kind);
+        }
+        Ok((name, kind))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AzureConfig {
     pub services: Vec<String>,
+
+    /// Host port of the blob service, instead of 10000; queue and table take the next two
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Image to run instead of the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Tag of the image, instead of `latest`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Created in Azurite once it is ready
+    #[serde(default, skip_serializing_if = "AzureResources::is_empty")]
+    pub resources: AzureResources,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AzureResources {
+    /// Blob container names
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub containers: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queues: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<String>,
+}
+
+impl AzureResources {
+    pub fn is_empty(&self) -> bool {
+        self.containers.is_empty() && self.queues.is_empty() && self.tables.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GcpConfig {
     pub services: Vec<String>,
     pub project: Option<String>,
+
+    /// Emulator to run; fake-gcs-server for projects that only use Cloud Storage
+    #[serde(default, skip_serializing_if = "GcpFlavor::is_default")]
+    pub flavor: GcpFlavor,
+
+    /// Host port of the emulator, instead of 8080 (4443 for fake-gcs-server)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Image to run instead of the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Tag of the image, instead of `latest`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Emulator run for `cloud.gcp`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GcpFlavor {
+    /// The Cloud SDK's Firestore emulator
+    #[default]
+    Sdk,
+    /// fsouza/fake-gcs-server, emulating Cloud Storage only
+    FakeGcs,
+}
+
+impl GcpFlavor {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectHooks {
+    /// Before any service starts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_up: Vec<String>,
+
+    /// Once every service has started, e.g. migrations and seed scripts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_up: Vec<String>,
+
+    /// Before the services stop
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_down: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RuntimeConfig {
+    /// Engine address such as `ssh://dev-box` or `tcp://10.0.0.5:2375`; `DOCKER_HOST` and `--context` take precedence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProxyConfig {
+    /// Serve https:// with certificates from a local CA instead of plain http://
+    #[serde(default)]
+    pub tls: bool,
+
+    /// Port to listen on; defaults to 8443 with TLS and 8088 without
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Services that speak HTTP/2 themselves (h2c), so browsers may use it with them over TLS
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub http2: Vec<String>,
+}
+
+impl ProxyConfig {
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(if self.tls { 8443 } else { 8088 })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct KubernetesConfig {
+    /// kubeconfig context; defaults to the current one, or the minikube profile's with `--runtime minikube`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+
+    /// minikube profile images are built in with `--runtime minikube`; defaults to minikube's active one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minikube_profile: Option<String>,
+
+    /// Namespace of the project's resources; defaults to `zero-<project>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SecretsConfig {
+    /// Store for generated credentials
+    #[serde(default)]
+    pub backend: SecretsBackend,
+}
+
+/// `secrets.backend:`, where the credential store persists generated credentials
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretsBackend {
+    /// `.zeroconfig.env` next to zero.yml
+    #[default]
+    File,
+    /// macOS Keychain, Windows Credential Manager or the Secret Service on Linux
+    Keychain,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BackupsConfig {
+    /// Cron-style schedule (e.g. "0 2 * * *" or "@daily") for `zero backup --daemon`
+    #[serde(default)]
+    pub schedule: Option<String>,
+
+    /// Number of backups to keep per service
+    #[serde(default)]
+    pub retention: Option<usize>,
+
+    /// Directory backups are written to
+    #[serde(default)]
+    pub output: Option<String>,
+
+    /// Services to back up (defaults to every database service)
+    #[serde(default)]
+    pub services: Vec<String>,
+
+    /// Remote storage that new backups are copied to
+    #[serde(default)]
+    pub upload: Option<BackupUploadConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupUploadConfig {
+    /// Destination such as `s3://bucket/path`
+    pub target: String,
+
+    /// Custom S3 endpoint for MinIO or LocalStack (e.g. "http://localhost:9000")
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// AWS region of the bucket
+    #[serde(default)]
+    pub region: Option<String>,
+
+    /// AWS CLI profile holding the credentials
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -131,41 +876,380 @@ impl ZeroConfig {
         let content = std::fs::read_to_string(path.as_ref())
             .context("Failed to read zero.yml")?;
 
-        Self::from_str(&content)
+        let mut config = Self::from_str(&content)?;
+        config.root = path.as_ref().parent().map(Path::to_path_buf);
+        config.sources = vec![path.as_ref().to_path_buf()];
+        Ok(config)
+    }
+
+    /// Load zero.yml with the zero.override.yml next to it merged over it
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut files = vec![path.clone()];
+        files.extend(overlay::override_file(&path));
+        Self::from_files(&files)
+    }
+
+    /// Load a stack of config files, each merged over the ones before it
+    ///
+    /// Relative paths in the result resolve against the first file's directory.
+    pub fn from_files(paths: &[PathBuf]) -> Result<Self> {
+        let first = paths.first().context("No config file given")?;
+        let mut merged = serde_yaml::Value::Null;
+        for path in paths {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let value: serde_yaml::Value = serde_yaml::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            overlay::merge(&mut merged, value);
+        }
+
+        let mut config: Self = serde_yaml::from_value(merged).with_context(|| match paths.len() {
+            1 => format!("Failed to parse {}", first.display()),
+            _ => "Failed to parse the merged config files".to_string(),
+        })?;
+        config.root = first.parent().map(Path::to_path_buf);
+        config.sources = paths.to_vec();
+        Ok(config)
+    }
+
+    /// Resolve a path from zero.yml against the directory it was loaded from
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(path),
+            None => PathBuf::from(path),
+        }
     }
 
     /// Parse configuration from YAML string
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(content: &str) -> Result<Self> {
         serde_yaml::from_str(content)
             .context("Failed to parse zero.yml")
     }
 
-    /// Validate the configuration
+    /// Validate the configuration, reporting every error at once
+    ///
+    /// Warnings, such as unknown keys, are logged.
     pub fn validate(&self) -> Result<()> {
+        let (errors, warnings): (Vec<Diagnostic>, Vec<Diagnostic>) = self.report()
+            .into_iter()
+            .partition(Diagnostic::is_error);
+        for warning in &warnings {
+            tracing::warn!("{}", warning);
+        }
+
+        match errors.as_slice() {
+            [] => Ok(()),
+            [error] => anyhow::bail!("{}", error),
+            errors => anyhow::bail!(
+                "{} problems in the configuration:\n{}",
+                errors.len(),
+                errors.iter().map(|error| format!("  {}", error)).collect::<Vec<_>>().join("\n")
+            ),
+        }
+    }
+
+    /// Every problem in the configuration, located in the files it was loaded from
+    ///
+    /// A problem is attributed to the last file mentioning its key, as that one wins the merge.
+    pub fn report(&self) -> Vec<Diagnostic> {
+        let mut report = self.diagnostics();
+        self.locate(&mut report);
+
+        for (file, content) in &self.read_sources() {
+            let value: serde_yaml::Value = serde_yaml::from_str(content).unwrap_or_default();
+            report.extend(diagnostics::unknown_keys(&value).into_iter().map(|mut diagnostic| {
+                diagnostic.locate_in(content);
+                diagnostic.file = Some(file.clone());
+                diagnostic
+            }));
+        }
+        report
+    }
+
+    /// Point diagnostics at their keys in the files the configuration was loaded from
+    pub fn locate(&self, diagnostics: &mut [Diagnostic]) {
+        let sources = self.read_sources();
+        for diagnostic in diagnostics {
+            for (file, content) in sources.iter().rev() {
+                if diagnostic.locate_in(content) {
+                    diagnostic.file = Some(file.clone());
+                    break;
+                }
+            }
+        }
+    }
+
+    fn read_sources(&self) -> Vec<(String, String)> {
+        self.sources.iter()
+            .filter_map(|path| Some((path.display().to_string(), std::fs::read_to_string(path).ok()?)))
+            .collect()
+    }
+
+    /// Collect every validation problem in the configuration
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if let Some(version) = self.version.filter(|version| *version > migrate::CURRENT_VERSION) {
+            diagnostics.push(Diagnostic::error(
+                "version",
+                format!("Schema version {} is newer than the {} this zero understands; upgrade zero", version, migrate::CURRENT_VERSION),
+            ));
+        }
+
         // Validate language versions
         for (lang, version) in &self.languages {
             if version.is_empty() {
-                anyhow::bail!("Language '{}' has empty version", lang);
+                diagnostics.push(Diagnostic::error(
+                    format!("languages.{}", lang),
+                    format!("Language '{}' has empty version", lang),
+                ));
             }
         }
 
         // Validate service configurations
         for (service, config) in &self.services {
-            if config.version.is_empty() {
-                anyhow::bail!("Service '{}' has empty version", service);
+            if config.version.is_empty() && config.build.is_none() {
+                diagnostics.push(Diagnostic::error(
+                    format!("services.{}.version", service),
+                    format!("Service '{}' has empty version", service),
+                ));
+            }
+            match config.memory_limit() {
+                Err(e) => diagnostics.push(Diagnostic::error(
+                    format!("services.{}.memory", service),
+                    format!("Service '{}': {}", service, e),
+                )),
+                Ok(Some(limit)) if ElasticsearchService::handles(service) && limit < elasticsearch::MIN_MEMORY => {
+                    diagnostics.push(Diagnostic::error(
+                        format!("services.{}.memory", service),
+                        format!(
+                            "Service '{}' needs at least {}m of memory for its JVM heap",
+                            service,
+                            elasticsearch::MIN_MEMORY / (1024 * 1024)
+                        ),
+                    ))
+                }
+                Ok(_) => {}
+            }
+            if let Some(Err(e)) = config.healthcheck.as_ref().map(HealthcheckConfig::validate) {
+                diagnostics.push(Diagnostic::error(
+                    format!("services.{}.healthcheck", service),
+                    format!("Service '{}': {}", service, e),
+                ));
+            }
+            if config.kibana && !matches!(service.as_str(), "elasticsearch" | "opensearch") {
+                diagnostics.push(Diagnostic::warning(
+                    format!("services.{}.kibana", service),
+                    format!("Service '{}' is not Elasticsearch or OpenSearch; kibana is ignored", service),
+                ));
+            }
+            if let Some(path) = &config.metrics_path {
+                if !path.starts_with('/') {
+                    diagnostics.push(Diagnostic::error(
+                        format!("services.{}.metrics_path", service),
+                        format!("Metrics path '{}' of '{}' must start with /", path, service),
+                    ));
+                } else if !self.observability {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("services.{}.metrics_path", service),
+                        format!("Service '{}' sets metrics_path but observability is off; nothing scrapes it", service),
+                    ));
+                }
+            }
+            if let Some(realm) = &config.realm {
+                if service != "keycloak" {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("services.{}.realm", service),
+                        format!("Service '{}' is not Keycloak; realm is ignored", service),
+                    ));
+                } else if self.root.is_some() && !self.resolve_path(realm).is_file() {
+                    diagnostics.push(Diagnostic::error(
+                        format!("services.{}.realm", service),
+                        format!("Realm export '{}' of '{}' does not exist", realm, service),
+                    ));
+                }
+            }
+            if config.container_port == Some(0) {
+                diagnostics.push(Diagnostic::error(
+                    format!("services.{}.container_port", service),
+                    format!("Service '{}' has container_port 0", service),
+                ));
+            }
+            let mut published: Vec<u16> = config.ports.iter().map(|mapping| mapping.container).collect();
+            published.sort_unstable();
+            if let Some(port) = published.windows(2).find(|pair| pair[0] == pair[1]).map(|pair| pair[0]) {
+                diagnostics.push(Diagnostic::error(
+                    format!("services.{}.ports", service),
+                    format!("Service '{}' publishes container port {} more than once", service, port),
+                ));
+            }
+            if config.container_port.is_none() && !schema::KNOWN_SERVICES.iter().any(|known| known.name == service) {
+                let known = schema::KNOWN_SERVICES.iter().map(|known| known.name);
+                diagnostics.push(Diagnostic::warning(
+                    format!("services.{}.container_port", service),
+                    format!("Service '{}' has no container_port; the first port its image exposes is used", service),
+                ).with_suggestion(if config.build.is_none() { diagnostics::did_you_mean(service, known) } else { None }));
+            }
+            for (index, step) in config.init.iter().enumerate() {
+                let path = format!("services.{}.init", service);
+                if step.file().is_some() == step.run().is_some() {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("Init step {} of '{}' needs exactly one of file or run", index + 1, service),
+                    ));
+                } else if let Some(file) = step.file().filter(|file| self.root.is_some() && !self.resolve_path(file).is_file()) {
+                    diagnostics.push(Diagnostic::error(
+                        path,
+                        format!("Init file '{}' of '{}' does not exist", file, service),
+                    ));
+                }
+            }
+            for name in config.networks.iter().chain(&config.aliases).filter(|name| !is_dns_name(name)) {
+                diagnostics.push(Diagnostic::error(
+                    format!("services.{}", service),
+                    format!("'{}' of '{}' is not a valid network or DNS name; use letters, digits, '-', '_' and '.'", name, service),
+                ));
+            }
+            for alias in &config.aliases {
+                // Each clash is reported once, by the service sorting first
+                let other = if alias != service && self.services.contains_key(alias) {
+                    Some(alias)
+                } else {
+                    let mut others = self.services.iter()
+                        .filter(|(other, other_config)| *other > service && other_config.aliases.contains(alias))
+                        .map(|(other, _)| other);
+                    others.next()
+                };
+                if let Some(other) = other {
+                    diagnostics.push(Diagnostic::error(
+                        format!("services.{}.aliases", service),
+                        format!("Alias '{}' of '{}' is also a name of '{}'", alias, service, other),
+                    ));
+                }
+            }
+            for dependency in config.depends_on.iter().filter(|dependency| !self.services.contains_key(*dependency)) {
+                let services = self.services.keys().map(String::as_str);
+                diagnostics.push(Diagnostic::error(
+                    format!("services.{}.depends_on", service),
+                    format!("Service '{}' depends on '{}', which is not in services", service, dependency),
+                ).with_suggestion(diagnostics::did_you_mean(dependency, services)));
+            }
+        }
+
+        for command in &self.startup {
+            if let Some(service) = command.service().filter(|service| !self.services.contains_key(*service)) {
+                let services = self.services.keys().map(String::as_str);
+                diagnostics.push(Diagnostic::error(
+                    "startup",
+                    format!("Startup command {} runs in '{}', which is not in services", command, service),
+                ).with_suggestion(diagnostics::did_you_mean(service, services)));
+            }
+        }
+
+        if let Some(Err(e)) = self.metrics.as_deref().map(crate::metrics::parse_address) {
+            diagnostics.push(Diagnostic::error("metrics", e.to_string()));
+        }
+
+        for service in self.proxy.http2.iter().filter(|service| !self.services.contains_key(*service)) {
+            let services = self.services.keys().map(String::as_str);
+            diagnostics.push(Diagnostic::error(
+                "proxy.http2",
+                format!("'{}' is not in services", service),
+            ).with_suggestion(diagnostics::did_you_mean(service, services)));
+        }
+
+        // Validate the dependency graph once every dependency exists
+        if !diagnostics.iter().any(|d| d.path.ends_with(".depends_on")) {
+            if let Err(e) = self.start_order() {
+                diagnostics.push(Diagnostic::error("services", e.to_string()));
+            }
+        }
+
+        // Validate backup schedule
+        if let Some(schedule) = self.backups.as_ref().and_then(|b| b.schedule.as_deref()) {
+            if let Err(e) = crate::backup::CronSchedule::parse(schedule) {
+                diagnostics.push(Diagnostic::error(
+                    "backups.schedule",
+                    format!("Invalid backups.schedule '{}': {:#}", schedule, e),
+                ));
+            }
+        }
+
+        // Validate backup upload target
+        if let Some(upload) = self.backups.as_ref().and_then(|b| b.upload.as_ref()) {
+            if let Err(e) = crate::backup::S3Target::parse(&upload.target) {
+                diagnostics.push(Diagnostic::error(
+                    "backups.upload.target",
+                    format!("Invalid backups.upload.target '{}': {:#}", upload.target, e),
+                ));
+            }
+        }
+
+        // DynamoDB Local has nothing but DynamoDB to create resources in
+        if let Some(aws) = self.cloud.as_ref().and_then(|cloud| cloud.aws.as_ref()) {
+            if aws.flavor == AwsFlavor::DynamodbLocal {
+                let resources = &aws.resources;
+                for (field, declared) in [("buckets", &resources.buckets), ("queues", &resources.queues), ("topics", &resources.topics)] {
+                    if !declared.is_empty() {
+                        diagnostics.push(Diagnostic::error(
+                            format!("cloud.aws.resources.{}", field),
+                            format!("DynamoDB Local only emulates DynamoDB; use flavor: localstack for {}", field),
+                        ));
+                    }
+                }
+                if let Some(service) = aws.services.iter().find(|service| *service != "dynamodb") {
+                    diagnostics.push(Diagnostic::warning(
+                        "cloud.aws.services",
+                        format!("DynamoDB Local only emulates DynamoDB; '{}' will not be available", service),
+                    ));
+                }
+            }
+        }
+        if let Some(port) = self.cloud.as_ref().and_then(|cloud| cloud.azure.as_ref()).and_then(|azure| azure.port) {
+            if port > azurite::MAX_PORT {
+                diagnostics.push(Diagnostic::error(
+                    "cloud.azure.port",
+                    format!("Port {} leaves no room for the queue and table ports; use at most {}", port, azurite::MAX_PORT),
+                ));
+            }
+        }
+        if let Some(gcp) = self.cloud.as_ref().and_then(|cloud| cloud.gcp.as_ref()) {
+            if let Some(service) = gcp.services.iter().find(|service| gcp.flavor == GcpFlavor::FakeGcs && *service != "storage") {
+                diagnostics.push(Diagnostic::warning(
+                    "cloud.gcp.services",
+                    format!("fake-gcs-server only emulates Cloud Storage; '{}' will not be available", service),
+                ));
+            }
+        }
+
+        // Validate DynamoDB key schemas
+        let tables = self.cloud.as_ref().and_then(|cloud| cloud.aws.as_ref()).map(|aws| aws.resources.tables.as_slice());
+        for (index, table) in tables.unwrap_or_default().iter().enumerate() {
+            for (field, key) in [("partition_key", Some(&table.partition_key)), ("sort_key", table.sort_key.as_ref())] {
+                if let Some(Err(e)) = key.map(|key| DynamoTable::key_attribute(key)) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("cloud.aws.resources.tables.{}.{}", index, field),
+                        format!("Table '{}': {}", table.name, e),
+                    ));
+                }
             }
         }
 
         // Validate port ranges
         if let PortConfig::Manual(ports) = &self.ports {
             for (service, port) in ports {
-                if *port == 0 || *port > 65535 {
-                    anyhow::bail!("Invalid port {} for service '{}'", port, service);
+                if *port == 0 {
+                    diagnostics.push(Diagnostic::error(
+                        format!("ports.{}", service),
+                        format!("Invalid port {} for service '{}'", port, service),
+                    ));
                 }
             }
         }
 
-        Ok(())
+        diagnostics
     }
 
     /// Find zero.yml in current directory or parent directories
@@ -176,25 +1260,36 @@ impl ZeroConfig {
     }
 
     /// Find zero.yml in specified directory or parent directories
+    ///
+    /// The files in `ZERO_CONFIG_FILE`, relative to `path`, replace the search.
     pub fn discover_in<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        if let Some(files) = overlay::files_from_env() {
+            let files: Vec<PathBuf> = files.into_iter().map(|file| path.as_ref().join(file)).collect();
+            return Self::from_files(&files).map(Some);
+        }
+        match Self::find_file_in(path) {
+            Some(config_path) => Ok(Some(Self::load(config_path)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Path of the zero.yml (or zero.yaml) in a directory or its parents
+    pub fn find_file_in<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
         let mut dir = path.as_ref();
 
         loop {
             let config_path = dir.join("zero.yml");
             if config_path.exists() {
-                return Ok(Some(Self::from_file(config_path)?));
+                return Some(config_path);
             }
 
             // Check for alternate name
             let alt_path = dir.join("zero.yaml");
             if alt_path.exists() {
-                return Ok(Some(Self::from_file(alt_path)?));
+                return Some(alt_path);
             }
 
-            match dir.parent() {
-                Some(parent) => dir = parent,
-                None => return Ok(None),
-            }
+            dir = dir.parent()?;
         }
     }
 
@@ -205,12 +1300,55 @@ impl ZeroConfig {
             .collect()
     }
 
+    /// Networks declared by services besides the default one, sorted
+    pub fn networks(&self) -> Vec<String> {
+        let mut networks: Vec<String> = self.services.values()
+            .flat_map(|service| service.networks.iter())
+            .filter(|network| network.as_str() != DEFAULT_NETWORK)
+            .cloned()
+            .collect();
+        networks.sort();
+        networks.dedup();
+        networks
+    }
+
+    /// Where `zero up` serves metrics: `metrics:`, else the port Prometheus scrapes with `observability:`,
+    /// on every interface as the Prometheus container reaches it through the Docker host gateway
+    pub fn metrics_address(&self) -> Option<String> {
+        self.metrics.clone()
+            .or_else(|| self.observability.then(|| format!("0.0.0.0:{}", crate::services::observability::ENGINE_METRICS_PORT)))
+    }
+
     /// Get all services to provision
     pub fn get_services(&self) -> Vec<(String, ServiceConfig)> {
         self.services.iter()
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect()
     }
+
+    /// Service names with every service after its `depends_on`, otherwise alphabetical
+    pub fn start_order(&self) -> Result<Vec<String>> {
+        let mut pending: Vec<&String> = self.services.keys().collect();
+        pending.sort();
+        let mut order: Vec<String> = Vec::with_capacity(pending.len());
+
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|service| {
+                self.services[*service].depends_on.iter().all(|dependency| {
+                    order.contains(dependency) || !self.services.contains_key(dependency)
+                })
+            });
+            match ready {
+                Some(index) => order.push(pending.remove(index).clone()),
+                None => {
+                    let cycle: Vec<&str> = pending.iter().map(|service| service.as_str()).collect();
+                    anyhow::bail!("depends_on forms a cycle among: {}", cycle.join(", "));
+                }
+            }
+        }
+
+        Ok(order)
+    }
 }
 
 #[cfg(test)]
@@ -237,4 +1375,256 @@ env:
         assert_eq!(config.languages.get("node"), Some(&"20".to_string()));
         assert_eq!(config.services.len(), 1);
     }
+
+    #[test]
+    fn test_parse_backups_section() {
+        let yaml = r#"
+services:
+  postgres:
+    version: "16"
+
+backups:
+  schedule: "0 2 * * *"
+  retention: 7
+        "#;
+
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let backups = config.backups.as_ref().unwrap();
+        assert_eq!(backups.retention, Some(7));
+        assert!(config.validate().is_ok());
+
+        let invalid = ZeroConfig::from_str("backups:\n  schedule: \"every day\"\n").unwrap();
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_backup_upload() {
+        let yaml = r#"
+backups:
+  upload:
+    target: s3://team-backups/shop
+    endpoint: http://localhost:9000
+        "#;
+
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let upload = config.backups.as_ref().and_then(|b| b.upload.as_ref()).unwrap();
+        assert_eq!(upload.endpoint.as_deref(), Some("http://localhost:9000"));
+        assert!(config.validate().is_ok());
+
+        let invalid = ZeroConfig::from_str("backups:\n  upload:\n    target: /mnt/backups\n").unwrap();
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_secrets_backend() {
+        let config = ZeroConfig::from_str("secrets:\n  backend: keychain\n").unwrap();
+        assert_eq!(config.secrets.backend, SecretsBackend::Keychain);
+        assert_eq!(ZeroConfig::from_str("services: {}\n").unwrap().secrets.backend, SecretsBackend::File);
+        assert!(ZeroConfig::from_str("secrets:\n  backend: vault-ish\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_cloud_flavors() {
+        let config = ZeroConfig::from_str(
+            "cloud:\n  aws:\n    services: [dynamodb, s3]\n    flavor: dynamodb-local\n    resources:\n      buckets: [uploads]\n  gcp:\n    services: [storage]\n    flavor: fake-gcs\n",
+        ).unwrap();
+        let cloud = config.cloud.as_ref().unwrap();
+        assert_eq!(cloud.aws.as_ref().unwrap().flavor, AwsFlavor::DynamodbLocal);
+        assert_eq!(cloud.gcp.as_ref().unwrap().flavor, GcpFlavor::FakeGcs);
+
+        let paths: Vec<String> = config.diagnostics().into_iter().map(|d| d.path).collect();
+        assert_eq!(paths, ["cloud.aws.resources.buckets", "cloud.aws.services"]);
+        assert!(ZeroConfig::from_str("cloud:\n  aws:\n    services: [s3]\n    flavor: moto\n").is_err());
+
+        let azure = ZeroConfig::from_str("cloud:\n  azure:\n    services: [blob]\n    port: 65534\n").unwrap();
+        assert!(azure.validate().unwrap_err().to_string().contains("cloud.azure.port"));
+        let azure = ZeroConfig::from_str("cloud:\n  azure:\n    services: [blob]\n    port: 65533\n").unwrap();
+        assert!(azure.validate().is_ok());
+    }
+
+    #[test]
+    fn test_load_merges_override_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("zero.yml"), "services:\n  postgres:\n    version: \"15\"\n    port: 5432\n").unwrap();
+        std::fs::write(dir.join("zero.override.yml"), "services:\n  postgres:\n    version: \"16\"\n").unwrap();
+
+        let config = ZeroConfig::load(dir.join("zero.yml")).unwrap();
+        let postgres = &config.services["postgres"];
+        assert_eq!(postgres.version, "16");
+        assert!(matches!(postgres.port, PortValue::Fixed(5432)));
+        assert_eq!(config.root.as_deref(), Some(dir));
+    }
+
+    #[test]
+    fn test_parse_service_memory() {
+        assert_eq!(parse_memory("512m").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(parse_memory("2GB").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert_eq!(parse_memory("1048576").unwrap(), 1024 * 1024);
+        assert!(parse_memory("99999999999999g").is_err());
+
+        let invalid = ZeroConfig::from_str("services:\n  elasticsearch:\n    version: \"8\"\n    memory: lots\n").unwrap();
+        assert!(invalid.validate().is_err());
+        let small = ZeroConfig::from_str("services:\n  elasticsearch:\n    version: \"8\"\n    memory: 256m\n").unwrap();
+        assert!(small.validate().unwrap_err().to_string().contains("at least 512m"));
+    }
+
+    #[test]
+    fn test_parse_service_ports() {
+        let yaml = "services:\n  api:\n    version: \"1\"\n    container_port: 3000\n    ports: [9229, \"19001:9001\"]\n";
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let api = &config.services["api"];
+        assert_eq!(api.container_port, Some(3000));
+        assert_eq!(api.ports, vec![
+            PortMapping { host: None, container: 9229 },
+            PortMapping { host: Some(19001), container: 9001 },
+        ]);
+        assert_eq!(serde_yaml::to_string(&api.ports).unwrap(), "- 9229\n- 19001:9001\n");
+        assert!(config.diagnostics().is_empty());
+
+        assert!(ZeroConfig::from_str("services:\n  api:\n    version: \"1\"\n    ports: [\"http:80\"]\n").is_err());
+        let custom = ZeroConfig::from_str("services:\n  api:\n    version: \"1\"\n").unwrap();
+        assert!(!custom.diagnostics()[0].is_error());
+
+        let app = ZeroConfig::from_str("services:\n  app:\n    build:\n      args: { NODE_ENV: development }\n    container_port: 3000\n").unwrap();
+        let build = app.services["app"].build.as_ref().unwrap();
+        assert_eq!((build.context.as_str(), build.dockerfile()), (".", "Dockerfile"));
+        assert!(app.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_healthcheck() {
+        let yaml = r#"
+services:
+  postgres:
+    version: "16"
+    healthcheck:
+      command: pg_isready -U app
+      interval: 5s
+      retries: 10
+      start_period: 1m
+  redis:
+    version: "7"
+    healthcheck:
+      command: [redis-cli, ping]
+      timeout: 500ms
+    restart: unless-stopped
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let postgres = config.services["postgres"].healthcheck.as_ref().unwrap();
+        assert_eq!(postgres.test(), vec!["CMD-SHELL", "pg_isready -U app"]);
+        assert_eq!(postgres.exec_command(), vec!["sh", "-c", "pg_isready -U app"]);
+        assert_eq!(postgres.interval().unwrap(), Some(Duration::from_secs(5)));
+        assert_eq!(postgres.start_period().unwrap(), Some(Duration::from_secs(60)));
+
+        assert_eq!(config.services["postgres"].restart, None);
+
+        let redis = config.services["redis"].healthcheck.as_ref().unwrap();
+        assert_eq!(redis.test(), vec!["CMD", "redis-cli", "ping"]);
+        assert_eq!(redis.timeout().unwrap(), Some(Duration::from_millis(500)));
+        assert_eq!(config.services["redis"].restart, Some(RestartPolicy::UnlessStopped));
+        assert!(config.validate().is_ok());
+
+        let invalid = ZeroConfig::from_str(&yaml.replace("5s", "soon")).unwrap();
+        assert_eq!(invalid.diagnostics()[0].path, "services.postgres.healthcheck");
+
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert!(parse_duration("9999999999999999999h").is_err());
+        assert!(parse_duration(&format!("{}h", u64::MAX / 60)).is_err());
+    }
+
+    #[test]
+    fn test_parse_init_steps() {
+        let yaml = r#"
+services:
+  postgres:
+    version: "16"
+    init:
+      - db/schema.sql
+      - file: db/seed.sql
+        on_failure: continue
+      - run: psql -U zeroconfig -c 'select 1'
+      - {}
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let init = &config.services["postgres"].init;
+        assert_eq!(init[0].file(), Some("db/schema.sql"));
+        assert_eq!(init[1].on_failure(), OnFailure::Continue);
+        assert_eq!(init[2].run(), Some("psql -U zeroconfig -c 'select 1'"));
+
+        let errors: Vec<String> = config.diagnostics().into_iter().map(|d| d.message).collect();
+        assert_eq!(errors, vec!["Init step 4 of 'postgres' needs exactly one of file or run"]);
+    }
+
+    #[test]
+    fn test_networks_and_aliases() {
+        let yaml = r#"
+services:
+  postgres:
+    version: "16"
+    networks: [backend]
+    aliases: [db]
+  redis:
+    version: "7"
+    networks: [backend, default]
+    aliases: [cache, postgres, "bad name"]
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        assert_eq!(config.networks(), vec!["backend"]);
+        assert_eq!(config.services["postgres"].aliases, vec!["db"]);
+
+        let mut errors: Vec<String> = config.diagnostics().into_iter().map(|d| d.message).collect();
+        errors.sort();
+        assert_eq!(errors, vec![
+            "'bad name' of 'redis' is not a valid network or DNS name; use letters, digits, '-', '_' and '.'",
+            "Alias 'postgres' of 'redis' is also a name of 'postgres'",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_startup_commands() {
+        let yaml = r#"
+services:
+  api:
+    build: {}
+    healthcheck: { command: "true" }
+startup:
+  - npm run migrate
+  - run: bin/seed
+    service: api
+  - run: bin/warm-cache
+    service: apii
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        assert_eq!(config.startup[0], StartupCommand::Host("npm run migrate".to_string()));
+        assert_eq!((config.startup[1].run(), config.startup[1].service()), ("bin/seed", Some("api")));
+
+        let errors: Vec<Diagnostic> = config.diagnostics().into_iter().filter(|d| d.path == "startup").collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggestion.as_deref(), Some("did you mean `api`?"));
+    }
+
+    #[test]
+    fn test_start_order_follows_depends_on() {
+        let yaml = r#"
+services:
+  api:
+    version: "1"
+    depends_on: [postgres, redis]
+  postgres:
+    version: "15"
+  redis:
+    version: "7"
+    depends_on: [postgres]
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        assert_eq!(config.start_order().unwrap(), vec!["postgres", "redis", "api"]);
+        assert!(config.validate().is_ok());
+
+        let cycle = ZeroConfig::from_str(&yaml.replace("    version: \"15\"", "    version: \"15\"\n    depends_on: [api]")).unwrap();
+        assert!(cycle.start_order().unwrap_err().to_string().contains("api, postgres, redis"));
+
+        let unknown = ZeroConfig::from_str("services:\n  api:\n    version: \"1\"\n    container_port: 3000\n    depends_on: [mysql]\n").unwrap();
+        assert_eq!(unknown.diagnostics()[0].path, "services.api.depends_on");
+    }
 }
@@ -0,0 +1,89 @@
+//! Stacking config files over zero.yml
+//!
+//! `zero.override.yml` next to zero.yml is merged over it automatically; it is
+//! meant for local tweaks and stays out of version control. `--config-file`
+//! (or `ZERO_CONFIG_FILE`, separated like `PATH`) names the whole stack instead.
+//!
+//! Each file is merged over the ones before it:
+//! - maps merge key by key, recursively
+//! - lists and scalars replace what was there
+//! - `null` removes the key, e.g. a service the override does not want
+
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+/// Environment variable listing the config files to stack, as `--config-file` sets it
+pub const CONFIG_FILES_ENV: &str = "ZERO_CONFIG_FILE";
+
+/// Local overlay picked up next to zero.yml
+const OVERRIDE_FILES: [&str; 2] = ["zero.override.yml", "zero.override.yaml"];
+
+/// Merge `overlay` into `base`
+pub fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                if value.is_null() {
+                    base.remove(&key);
+                } else if let Some(existing) = base.get_mut(&key) {
+                    merge(existing, value);
+                } else {
+                    base.insert(key, value);
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// The override file next to a base config, if there is one
+pub fn override_file(base: &Path) -> Option<PathBuf> {
+    let dir = base.parent()?;
+    OVERRIDE_FILES.iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Config files named by `ZERO_CONFIG_FILE`, if set
+pub fn files_from_env() -> Option<Vec<PathBuf>> {
+    let value = std::env::var_os(CONFIG_FILES_ENV).filter(|value| !value.is_empty())?;
+    Some(std::env::split_paths(&value).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_semantics() {
+        let mut base: Value = serde_yaml::from_str(r#"
+services:
+  postgres:
+    version: "15"
+    port: 5432
+    volumes: [pg:/data]
+  redis:
+    version: "7"
+startup: [npm install]
+"#).unwrap();
+        let overlay: Value = serde_yaml::from_str(r#"
+services:
+  postgres:
+    port: 6543
+    volumes: [./pg:/data]
+  redis: null
+startup: [pnpm install]
+"#).unwrap();
+
+        merge(&mut base, overlay);
+        let expected: Value = serde_yaml::from_str(r#"
+services:
+  postgres:
+    version: "15"
+    port: 6543
+    volumes: [./pg:/data]
+startup: [pnpm install]
+"#).unwrap();
+        assert_eq!(base, expected);
+    }
+}
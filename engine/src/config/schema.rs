@@ -0,0 +1,573 @@
+use serde_json::{json, Map, Value};
+
+use crate::cloud::{azurite, gcp, localstack};
+
+/// A service zero.yml knows how to provision without extra configuration
+pub struct KnownService {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default_port: u16,
+    /// Image tags offered as completions, newest first
+    pub versions: &'static [&'static str],
+}
+
+pub const KNOWN_SERVICES: &[KnownService] = &[
+    KnownService { name: "postgres", description: "PostgreSQL database", default_port: 5432, versions: &["16", "15", "14", "latest"] },
+    KnownService { name: "timescaledb", description: "PostgreSQL with the TimescaleDB extension", default_port: 5432, versions: &["latest-pg16", "latest-pg15"] },
+    KnownService { name: "mysql", description: "MySQL database", default_port: 3306, versions: &["8", "8.0", "latest"] },
+    KnownService { name: "mongodb", description: "MongoDB document database", default_port: 27017, versions: &["7", "6", "latest"] },
+    KnownService { name: "redis", description: "Redis cache and message broker", default_port: 6379, versions: &["7", "6", "latest"] },
+    KnownService { name: "influxdb", description: "InfluxDB 2 time-series database with an initial org, bucket and token", default_port: 8086, versions: &["2.7", "2", "latest"] },
+    KnownService { name: "rabbitmq", description: "RabbitMQ message queue with the management UI", default_port: 5672, versions: &["3", "3-management", "latest"] },
+    KnownService { name: "kafka", description: "Apache Kafka (Confluent image)", default_port: 9092, versions: &["7.6.0", "latest"] },
+    KnownService { name: "elasticsearch", description: "Elasticsearch search engine", default_port: 9200, versions: &["8.13.0", "7.17.20"] },
+    KnownService { name: "opensearch", description: "OpenSearch search engine", default_port: 9200, versions: &["2.13.0", "latest"] },
+    KnownService { name: "meilisearch", description: "Meilisearch search engine, much lighter than Elasticsearch", default_port: 7700, versions: &["v1.9", "latest"] },
+    KnownService { name: "typesense", description: "Typesense search engine", default_port: 8108, versions: &["27.1", "latest"] },
+    KnownService { name: "minio", description: "MinIO S3-compatible object storage with its web console", default_port: 9000, versions: &["latest"] },
+    KnownService { name: "mailpit", description: "Mailpit mail catcher with a web inbox", default_port: 1025, versions: &["latest", "v1.20"] },
+    KnownService { name: "mailhog", description: "MailHog mail catcher (prefer mailpit)", default_port: 1025, versions: &["latest"] },
+    KnownService { name: "jaeger", description: "Jaeger tracing with OTLP ingest and its web UI", default_port: 4318, versions: &["1.57", "latest"] },
+    KnownService { name: "otel-collector", description: "OpenTelemetry collector (contrib distribution)", default_port: 4318, versions: &["0.104.0", "latest"] },
+    KnownService { name: "keycloak", description: "Keycloak identity provider for local OIDC, with realm import", default_port: 8080, versions: &["26.0", "25.0", "latest"] },
+    KnownService { name: "nats", description: "NATS messaging with JetStream", default_port: 4222, versions: &["2.10", "latest"] },
+    KnownService { name: "clickhouse", description: "ClickHouse analytics database (HTTP interface)", default_port: 8123, versions: &["24.8", "latest"] },
+    KnownService { name: "cassandra", description: "Apache Cassandra, as a single node", default_port: 9042, versions: &["5.0", "4.1", "latest"] },
+    KnownService { name: "memcached", description: "Memcached cache", default_port: 11211, versions: &["1.6", "latest"] },
+    KnownService { name: "localstack", description: "LocalStack AWS emulator", default_port: 4566, versions: &["latest"] },
+    KnownService { name: "consul", description: "HashiCorp Consul service discovery", default_port: 8500, versions: &["1.18", "latest"] },
+];
+
+/// Language runtimes and the versions offered as completions
+pub const KNOWN_LANGUAGES: &[(&str, &[&str])] = &[
+    ("node", &["22", "20", "18", "latest"]),
+    ("python", &["3.12", "3.11", "3.10"]),
+    ("go", &["1.22", "1.21"]),
+    ("rust", &["stable", "beta", "nightly"]),
+    ("java", &["21", "17", "11"]),
+    ("dotnet", &["8", "6"]),
+    ("ruby", &["3.3", "3.2"]),
+    ("php", &["8.3", "8.2"]),
+];
+
+/// Template names accepted by `zero init --template`
+pub const TEMPLATES: &[&str] = &[
+    "default", "node", "python", "rust", "go", "fullstack", "database", "redis", "microservices",
+];
+
+/// Value that makes `zero` generate a secret or connection string for an env var
+pub const AUTO_GENERATE: &str = "auto-generate";
+
+/// JSON Schema (draft-07) for zero.yml, for editor autocompletion and hover docs
+pub fn json_schema() -> Value {
+    let auto_port = json!({
+        "oneOf": [
+            { "const": "auto", "description": "Pick a free host port automatically" },
+            { "type": "integer", "minimum": 1, "maximum": 65535, "description": "Fixed host port" },
+            {
+                "type": "object",
+                "description": "Pick a free host port within a range",
+                "properties": {
+                    "min": { "type": "integer", "minimum": 1, "maximum": 65535 },
+                    "max": { "type": "integer", "minimum": 1, "maximum": 65535 }
+                },
+                "required": ["min", "max"],
+                "additionalProperties": false
+            }
+        ],
+        "default": "auto"
+    });
+
+    let service = |description: &str, versions: &[&str], default_port: Option<u16>| {
+        let mut port = auto_port.clone();
+        if let Some(default_port) = default_port {
+            port["description"] = json!(format!("Host port; the container listens on {}", default_port));
+        }
+        json!({
+            "type": "object",
+            "description": description,
+            "properties": {
+                "version": {
+                    "type": "string",
+                    "description": "Image tag to run",
+                    "examples": versions
+                },
+                "port": port,
+                "build": {
+                    "type": "object",
+                    "description": "Build the image from a local Dockerfile instead of pulling one",
+                    "properties": {
+                        "context": { "type": "string", "description": "Build context, relative to zero.yml", "default": "." },
+                        "dockerfile": { "type": "string", "description": "Dockerfile, relative to the context", "default": "Dockerfile" },
+                        "args": {
+                            "type": "object",
+                            "description": "Build arguments (ARG in the Dockerfile)",
+                            "additionalProperties": { "type": "string" }
+                        }
+                    },
+                    "additionalProperties": false
+                },
+                "container_port": {
+                    "type": "integer",
+                    "description": "Port the service listens on inside its container; defaults to its well-known port, else the first port its image exposes",
+                    "minimum": 1,
+                    "maximum": 65535
+                },
+                "ports": {
+                    "type": "array",
+                    "description": "More container ports to publish, as `9001` (any free host port) or `\"19001:9001\"`",
+                    "items": {
+                        "anyOf": [
+                            { "type": "integer", "minimum": 1, "maximum": 65535 },
+                            { "type": "string", "pattern": "^\\d+(:\\d+)?(/tcp)?$" }
+                        ]
+                    }
+                },
+                "environment": {
+                    "type": "object",
+                    "description": "Environment variables passed to the container; `vault:<path>#<field>` values are read from Vault at start",
+                    "additionalProperties": { "type": "string", "examples": ["vault:secret/data/myapp#DB_PASSWORD"] }
+                },
+                "volumes": {
+                    "type": "array",
+                    "description": "Volume mounts in `host:container` form",
+                    "items": { "type": "string" }
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Command overriding the image default"
+                },
+                "depends_on": {
+                    "type": "array",
+                    "description": "Services that must be healthy before this one starts",
+                    "items": { "type": "string" }
+                },
+                "buckets": {
+                    "type": "array",
+                    "description": "MinIO buckets created once the server is up",
+                    "items": { "type": "string", "pattern": "^[a-z0-9][a-z0-9.-]{1,61}[a-z0-9]$" }
+                },
+                "definitions": {
+                    "type": "string",
+                    "description": "RabbitMQ definitions export (vhosts, users, queues, exchanges) imported once the broker is up, relative to zero.yml"
+                },
+                "admin_ui": {
+                    "type": "boolean",
+                    "description": "Run the database's web admin tool (pgAdmin, Adminer, Redis Insight or mongo-express), preconfigured with its credentials",
+                    "default": false
+                },
+                "realm": {
+                    "type": "string",
+                    "description": "Keycloak realm export (JSON) imported once the server is up, unless the realm exists; relative to zero.yml"
+                },
+                "metrics_path": {
+                    "type": "string",
+                    "description": "Path the observability stack's Prometheus scrapes the service's metrics from, on its container port",
+                    "pattern": "^/"
+                },
+                "kibana": {
+                    "type": "boolean",
+                    "description": "Run Kibana, or OpenSearch Dashboards for OpenSearch, of the same version and connected to this service",
+                    "default": false
+                },
+                "healthcheck": {
+                    "type": "object",
+                    "description": "Health check run by the container engine; the service is healthy while the command exits with 0",
+                    "required": ["command"],
+                    "properties": {
+                        "command": {
+                            "description": "Shell command, or the command and its arguments as a list",
+                            "anyOf": [
+                                { "type": "string" },
+                                { "type": "array", "items": { "type": "string" }, "minItems": 1 }
+                            ]
+                        },
+                        "interval": { "type": "string", "description": "Time between checks, e.g. 10s", "pattern": "^\\d+(ms|s|m|h)?$" },
+                        "timeout": { "type": "string", "description": "Time a check may take, e.g. 5s", "pattern": "^\\d+(ms|s|m|h)?$" },
+                        "retries": { "type": "integer", "description": "Failed checks in a row before the service is unhealthy", "minimum": 0 },
+                        "start_period": { "type": "string", "description": "Grace period after start, e.g. 30s", "pattern": "^\\d+(ms|s|m|h)?$" }
+                    },
+                    "additionalProperties": false
+                },
+                "init": {
+                    "type": "array",
+                    "description": "SQL files, scripts and commands run in the container, in order, once it is healthy",
+                    "items": {
+                        "oneOf": [
+                            { "type": "string", "description": "SQL file or script, relative to zero.yml" },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "file": { "type": "string", "description": "SQL file or script, relative to zero.yml" },
+                                    "run": { "type": "string", "description": "Shell command to run in the container" },
+                                    "on_failure": { "type": "string", "enum": ["fail", "continue"], "default": "fail" }
+                                },
+                                "additionalProperties": false
+                            }
+                        ]
+                    }
+                },
+                "networks": {
+                    "type": "array",
+                    "description": "Project networks to join besides the default one",
+                    "items": { "type": "string", "pattern": "^[a-zA-Z0-9][a-zA-Z0-9_.-]*$" },
+                    "uniqueItems": true
+                },
+                "aliases": {
+                    "type": "array",
+                    "description": "More DNS names the service answers to on its networks, e.g. db or cache",
+                    "items": { "type": "string", "pattern": "^[a-zA-Z0-9][a-zA-Z0-9_.-]*$" },
+                    "uniqueItems": true
+                },
+                "restart": {
+                    "type": "string",
+                    "description": "When the container engine restarts the container, e.g. after Docker Desktop restarts",
+                    "enum": ["no", "always", "on-failure", "unless-stopped"]
+                },
+                "memory": {
+                    "type": "string",
+                    "description": "Container memory limit, e.g. 512m or 2g; search engines size their heap from it",
+                    "pattern": "^[0-9]+\\s*([kKmMgG][bB]?|[bB])?$"
+                },
+                "topics": {
+                    "type": "array",
+                    "description": "Kafka topics created once the broker is up, as name or name:partitions",
+                    "items": { "type": "string", "pattern": "^[A-Za-z0-9._-]+(:[0-9]+)?$" }
+                }
+            },
+            "anyOf": [{ "required": ["version"] }, { "required": ["build"] }],
+            "additionalProperties": false,
+            "defaultSnippets": [{ "body": { "version": versions.first().copied().unwrap_or("latest"), "port": "auto" } }]
+        })
+    };
+
+    let services: Map<String, Value> = KNOWN_SERVICES.iter()
+        .map(|known| (known.name.to_string(), service(known.description, known.versions, Some(known.default_port))))
+        .collect();
+
+    let languages: Map<String, Value> = KNOWN_LANGUAGES.iter()
+        .map(|(name, versions)| (name.to_string(), json!({ "type": "string", "examples": versions })))
+        .collect();
+
+    let cloud_services = |description: &str, services: &[&str]| json!({
+        "type": "array",
+        "description": description,
+        "items": { "type": "string", "enum": services },
+        "uniqueItems": true
+    });
+
+    // Emulator sections are built apart; the rest is already at the json! recursion limit
+    let cloud_provider = |description: &str, default_image: &str, mut properties: Value| {
+        if let Value::Object(properties) = &mut properties {
+            properties.insert("image".to_string(), json!({
+                "type": "string",
+                "description": format!("Image to run instead of {}", default_image)
+            }));
+            properties.insert("version".to_string(), json!({
+                "type": "string",
+                "description": "Tag of the image, instead of latest",
+                "examples": ["latest"]
+            }));
+        }
+        json!({
+            "type": "object",
+            "description": description,
+            "properties": properties,
+            "required": ["services"]
+        })
+    };
+
+    let aws_resources = json!({
+        "type": "object",
+        "description": "Resources created in LocalStack once it is ready",
+        "properties": {
+            "buckets": { "type": "array", "description": "S3 bucket names", "items": { "type": "string" } },
+            "queues": { "type": "array", "description": "SQS queue names; a .fifo suffix makes a FIFO queue", "items": { "type": "string" } },
+            "topics": { "type": "array", "description": "SNS topic names; a .fifo suffix makes a FIFO topic", "items": { "type": "string" } },
+            "tables": {
+                "type": "array",
+                "description": "DynamoDB tables, billed per request",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "partition_key": { "type": "string", "description": "attribute or attribute:TYPE (S, N or B)", "examples": ["id", "id:S"] },
+                        "sort_key": { "type": "string", "examples": ["created_at:N"] }
+                    },
+                    "required": ["name", "partition_key"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "additionalProperties": false
+    });
+
+    let azure_resources = json!({
+        "type": "object",
+        "description": "Resources created in Azurite once it is ready",
+        "properties": {
+            "containers": { "type": "array", "description": "Blob container names", "items": { "type": "string" } },
+            "queues": { "type": "array", "description": "Queue names", "items": { "type": "string" } },
+            "tables": { "type": "array", "description": "Table names", "items": { "type": "string" } }
+        },
+        "additionalProperties": false
+    });
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "zero.yml",
+        "description": "ZeroConfig project configuration",
+        "type": "object",
+        "properties": {
+            "version": {
+                "type": "integer",
+                "description": "Schema version zero.yml is written against; `zero upgrade-config` brings older files up to date",
+                "minimum": 0,
+                "maximum": super::migrate::CURRENT_VERSION
+            },
+            "metadata": {
+                "type": "object",
+                "description": "Optional project metadata",
+                "properties": {
+                    "name": { "type": "string" },
+                    "description": { "type": "string" },
+                    "version": { "type": "string" },
+                    "team": { "type": "string" }
+                },
+                "additionalProperties": false
+            },
+            "languages": {
+                "type": "object",
+                "description": "Programming language runtimes and their versions",
+                "properties": languages,
+                "additionalProperties": { "type": "string", "minLength": 1 }
+            },
+            "services": {
+                "type": "object",
+                "description": "Services to provision (databases, queues, caches, etc.). Unknown names run the image of the same name",
+                "properties": services,
+                "additionalProperties": service("Custom service running the image of the same name", &["latest"], None)
+            },
+            "cloud": {
+                "type": "object",
+                "description": "Cloud emulation configuration",
+                "properties": {
+                    "localstack": {
+                        "type": "string",
+                        "description": "Start LocalStack with every service (`full`) or a comma-separated list",
+                        "examples": ["full"]
+                    },
+                    "aws": cloud_provider("AWS services emulated by LocalStack", localstack::DEFAULT_IMAGE, json!({
+                        "services": cloud_services("AWS services to start", localstack::DEFAULT_SERVICES),
+                        "region": { "type": "string", "examples": ["us-east-1", "eu-west-1"] },
+                        "flavor": {
+                            "type": "string",
+                            "enum": ["localstack", "dynamodb-local"],
+                            "default": "localstack",
+                            "description": "Emulator to run; dynamodb-local starts the much smaller amazon/dynamodb-local when only DynamoDB is needed"
+                        },
+                        "port": { "type": "integer", "minimum": 1, "maximum": 65535, "description": "Host port, instead of 4566 (8000 for dynamodb-local)" },
+                        "resources": aws_resources
+                    })),
+                    "azure": cloud_provider("Azure Storage services emulated by Azurite", azurite::DEFAULT_IMAGE, json!({
+                        "services": cloud_services("Azure Storage services to start", azurite::DEFAULT_SERVICES),
+                        "port": {
+                            "type": "integer",
+                            "minimum": 1,
+                            "maximum": azurite::MAX_PORT,
+                            "description": "Host port of the blob service, instead of 10000; queue and table take the next two"
+                        },
+                        "resources": azure_resources
+                    })),
+                    "gcp": cloud_provider("Google Cloud SDK emulators", gcp::DEFAULT_IMAGE, json!({
+                        "services": cloud_services("GCP emulators to start", gcp::DEFAULT_SERVICES),
+                        "project": { "type": "string", "description": "GCP project id used by the emulators" },
+                        "flavor": {
+                            "type": "string",
+                            "enum": ["sdk", "fake-gcs"],
+                            "default": "sdk",
+                            "description": "Emulator to run; fake-gcs starts the much smaller fsouza/fake-gcs-server when only Cloud Storage is needed"
+                        },
+                        "port": { "type": "integer", "minimum": 1, "maximum": 65535, "description": "Host port of the emulator, instead of 8080 (4443 for fake-gcs)" }
+                    }))
+                },
+                "additionalProperties": false
+            },
+            "env": {
+                "type": "object",
+                "description": "Environment variables written to .env",
+                "additionalProperties": {
+                    "type": "string",
+                    "examples": [AUTO_GENERATE],
+                    "description": "Use `auto-generate` for generated secrets and service connection strings"
+                }
+            },
+            "ports": {
+                "description": "Port configuration",
+                "oneOf": [
+                    { "const": "auto", "description": "Pick free host ports automatically" },
+                    {
+                        "type": "object",
+                        "description": "Fixed host port per service",
+                        "additionalProperties": { "type": "integer", "minimum": 1, "maximum": 65535 }
+                    }
+                ],
+                "default": "auto"
+            },
+            "startup": {
+                "type": "array",
+                "description": "Commands run in order once every service is healthy; a failing command stops `zero up`",
+                "items": {
+                    "oneOf": [
+                        { "type": "string", "description": "Shell command run on the host from the directory of zero.yml" },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "run": { "type": "string", "description": "Shell command line" },
+                                "service": { "type": "string", "description": "Service whose container runs the command instead of the host" }
+                            },
+                            "required": ["run"],
+                            "additionalProperties": false
+                        }
+                    ]
+                }
+            },
+            "hooks": {
+                "type": "object",
+                "description": "Commands run on the host with the project's environment, e.g. migrations after `zero up`",
+                "properties": {
+                    "pre_up": { "type": "array", "description": "Before any service starts", "items": { "type": "string" } },
+                    "post_up": { "type": "array", "description": "Once every service has started", "items": { "type": "string" } },
+                    "pre_down": { "type": "array", "description": "Before the services stop", "items": { "type": "string" } }
+                },
+                "additionalProperties": false
+            },
+            "observability": {
+                "type": "boolean",
+                "description": "Run Prometheus, Grafana and Jaeger, scraping the project's containers and services with metrics_path and receiving the apps' traces",
+                "default": false
+            },
+            "metrics": {
+                "type": "string",
+                "description": "Address `zero up` serves Prometheus metrics of the containers on while attached: :9464 on localhost, 0.0.0.0:9464 on every interface",
+                "examples": [":9464", "0.0.0.0:9464"]
+            },
+            "wait": {
+                "type": "boolean",
+                "description": "Make `zero up` wait until every service is healthy, like `--wait`",
+                "default": false
+            },
+            "runtime": {
+                "type": "object",
+                "description": "Container engine the services run on",
+                "properties": {
+                    "host": {
+                        "type": "string",
+                        "description": "Engine on another machine, e.g. ssh://dev-box or tcp://10.0.0.5:2375; DOCKER_HOST and --context take precedence",
+                        "pattern": "^(ssh|tcp|unix|npipe)://"
+                    }
+                },
+                "additionalProperties": false
+            },
+            "proxy": {
+                "type": "object",
+                "description": "`zero proxy`, serving each service as <service>.localhost",
+                "properties": {
+                    "tls": { "type": "boolean", "default": false, "description": "Serve https:// with certificates from a local CA; trust it once with `zero proxy trust`" },
+                    "port": { "type": "integer", "minimum": 1, "maximum": 65535, "description": "Port to listen on; defaults to 8443 with TLS and 8088 without" },
+                    "http2": { "type": "array", "items": { "type": "string" }, "description": "Services that speak HTTP/2 themselves (h2c)" }
+                },
+                "additionalProperties": false
+            },
+            "kubernetes": {
+                "type": "object",
+                "description": "Cluster `zero up --runtime kubernetes` deploys the services to",
+                "properties": {
+                    "context": { "type": "string", "description": "kubeconfig context; defaults to the current one, or the minikube profile's with --runtime minikube" },
+                    "minikube_profile": { "type": "string", "description": "minikube profile images are built in with --runtime minikube; defaults to minikube's active one" },
+                    "namespace": { "type": "string", "description": "Namespace of the project's resources; defaults to zero-<project>" }
+                },
+                "additionalProperties": false
+            },
+            "secrets": {
+                "type": "object",
+                "description": "Where generated credentials are kept",
+                "properties": {
+                    "backend": {
+                        "type": "string",
+                        "enum": ["file", "keychain"],
+                        "default": "file",
+                        "description": "`file` writes .zeroconfig.env; `keychain` uses the macOS Keychain, Windows Credential Manager or the Secret Service"
+                    }
+                },
+                "additionalProperties": false
+            },
+            "backups": {
+                "type": "object",
+                "description": "Scheduled backup configuration",
+                "properties": {
+                    "schedule": {
+                        "type": "string",
+                        "description": "Cron-style schedule for `zero backup --daemon`",
+                        "examples": ["@daily", "0 2 * * *"]
+                    },
+                    "retention": { "type": "integer", "minimum": 1, "description": "Number of backups to keep per service" },
+                    "output": { "type": "string", "description": "Directory backups are written to" },
+                    "services": {
+                        "type": "array",
+                        "description": "Services to back up (defaults to every database service)",
+                        "items": { "type": "string", "examples": ["postgres", "mysql", "mongodb", "redis"] }
+                    },
+                    "upload": {
+                        "type": "object",
+                        "description": "Remote storage that new backups are copied to",
+                        "properties": {
+                            "target": { "type": "string", "pattern": "^s3://", "description": "Destination such as `s3://bucket/path`" },
+                            "endpoint": { "type": "string", "description": "Custom S3 endpoint for MinIO or LocalStack" },
+                            "region": { "type": "string" },
+                            "profile": { "type": "string", "description": "AWS CLI profile holding the credentials" }
+                        },
+                        "required": ["target"],
+                        "additionalProperties": false
+                    }
+                },
+                "additionalProperties": false
+            }
+        },
+        "additionalProperties": false,
+        "x-zeroconfig-templates": TEMPLATES
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_covers_bundled_templates() {
+        let schema = json_schema();
+        let templates = [
+            include_str!("../../templates/default.yml"),
+            include_str!("../../templates/node.yml"),
+            include_str!("../../templates/python.yml"),
+            include_str!("../../templates/rust.yml"),
+            include_str!("../../templates/go.yml"),
+            include_str!("../../templates/fullstack.yml"),
+            include_str!("../../templates/database.yml"),
+            include_str!("../../templates/redis.yml"),
+            include_str!("../../templates/microservices.yml"),
+        ];
+
+        for template in templates {
+            let config: serde_yaml::Value = serde_yaml::from_str(template).unwrap();
+            for (key, _) in config.as_mapping().unwrap() {
+                let key = key.as_str().unwrap();
+                assert!(schema["properties"].get(key).is_some(), "schema is missing '{}'", key);
+            }
+            for (service, _) in config["services"].as_mapping().into_iter().flatten() {
+                let service = service.as_str().unwrap();
+                assert!(KNOWN_SERVICES.iter().any(|known| known.name == service), "unknown service '{}'", service);
+            }
+        }
+    }
+}
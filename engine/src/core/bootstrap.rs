@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{info, instrument};
+
+use super::Engine;
+use crate::config::ServiceConfig;
+use crate::orchestrator::SidecarFile;
+use crate::services::{keycloak, rabbitmq, KafkaService, KeycloakService, MinioService, RabbitMqService};
+
+/// Times the first bootstrap command is tried while a service starts, two seconds apart
+const ATTEMPTS: u32 = 30;
+
+impl Engine {
+    /// Create the resources zero.yml declares for a service that just started
+    ///
+    /// Kafka `topics:` and MinIO `buckets:` are created once the service
+    /// accepts requests; existing ones are left alone. A RabbitMQ
+    /// `definitions:` file is imported, merging with what the broker has, and
+    /// a Keycloak `realm:` export is imported unless the realm exists.
+    #[instrument(skip(self, config))]
+    pub(crate) async fn bootstrap(&self, service_name: &str, config: &ServiceConfig) -> Result<()> {
+        match service_name {
+            "kafka" if !config.topics.is_empty() => {
+                for topic in &config.topics {
+                    self.exec_until_ready(service_name, KafkaService::create_topic_command(topic)).await
+                        .map_err(|e| e.context(format!("Failed to create Kafka topic '{}'", topic)))?;
+                    info!("Kafka topic '{}' is ready", topic);
+                }
+            }
+            "minio" if !config.buckets.is_empty() => {
+                let env: HashMap<String, String> = self.orchestrator.container_env(service_name).await?.into_iter().collect();
+                let access_key = env.get("MINIO_ROOT_USER").cloned().unwrap_or_default();
+                let secret_key = env.get("MINIO_ROOT_PASSWORD").cloned().unwrap_or_default();
+                self.exec_until_ready(service_name, MinioService::alias_command(&access_key, &secret_key)).await
+                    .map_err(|e| e.context("MinIO did not accept its credentials"))?;
+
+                for bucket in &config.buckets {
+                    self.orchestrator.exec_command_bytes(service_name, MinioService::create_bucket_command(bucket)).await
+                        .map_err(|e| e.context(format!("Failed to create MinIO bucket '{}'", bucket)))?;
+                    info!("MinIO bucket '{}' is ready", bucket);
+                }
+            }
+            "rabbitmq" => {
+                let Some(definitions) = &config.definitions else { return Ok(()) };
+                let path = self.config.resolve_path(definitions);
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read RabbitMQ definitions {}", path.display()))?;
+
+                self.orchestrator.copy_files(service_name, &[SidecarFile::new(rabbitmq::DEFINITIONS_PATH, content)]).await?;
+                self.exec_until_ready(service_name, RabbitMqService::import_definitions_command()).await
+                    .map_err(|e| e.context(format!("Failed to import RabbitMQ definitions {}", path.display())))?;
+                info!("Imported RabbitMQ definitions from {}", path.display());
+            }
+            "keycloak" => {
+                let Some(realm) = &config.realm else { return Ok(()) };
+                let path = self.config.resolve_path(realm);
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read Keycloak realm {}", path.display()))?;
+                let name = KeycloakService::realm_name(&content)
+                    .with_context(|| format!("{} is not a realm export with a \"realm\" name", path.display()))?;
+                let env: HashMap<String, String> = self.orchestrator.container_env(service_name).await?.into_iter().collect();
+                let password = env.get("KC_BOOTSTRAP_ADMIN_PASSWORD").cloned().unwrap_or_default();
+
+                self.orchestrator.copy_files(service_name, &[SidecarFile::new(keycloak::REALM_PATH, content)]).await?;
+                self.exec_until_ready(service_name, KeycloakService::login_command(&password)).await
+                    .map_err(|e| e.context("Keycloak did not accept its admin credentials"))?;
+                self.orchestrator.exec_command_bytes(service_name, KeycloakService::import_realm_command(&name)).await
+                    .map_err(|e| e.context(format!("Failed to import Keycloak realm {}", path.display())))?;
+                info!("Keycloak realm '{}' is ready", name);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Run a command in a service's container until it succeeds or the attempts run out
+    async fn exec_until_ready(&self, service_name: &str, command: Vec<String>) -> Result<()> {
+        let mut attempt = 1;
+        while let Err(e) = self.orchestrator.exec_command_bytes(service_name, command.clone()).await {
+            if attempt == ATTEMPTS {
+                return Err(e);
+            }
+            attempt += 1;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//! Cloud emulators of the project
+//!
+//! `zero up` starts one emulator per provider in zero.yml's `cloud:` block,
+//! all at once, with the services and region zero.yml asks for. Each joins the
+//! project network, so services reach it by hostname, and is recorded in the
+//! project state. The engine waits until they are ready and creates the
+//! declared resources before the project's own services start, so they can
+//! use them from their first request; `zero down` stops the emulators again.
+//! The data the emulators keep can be snapshotted and restored as a tarball.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::state::CloudState;
+use super::Engine;
+use crate::cloud::snapshot::{self, SnapshotManifest};
+use crate::cloud::{canonical_provider, declared_providers, CloudEmulator, CloudStatus};
+
+/// How long `zero up` waits for an emulator to become ready
+pub const READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+impl Engine {
+    /// Emulator of a provider on the project's container engine
+    pub async fn cloud_emulator(&self, provider: String) -> Result<CloudEmulator> {
+        Ok(CloudEmulator::on_engine(provider, self.docker()).await?.for_project(&self.project_name))
+    }
+
+    /// Start the emulator of every provider in `cloud:`
+    pub(crate) async fn start_cloud(&mut self) -> Result<()> {
+        let Some(cloud) = &self.config.cloud else { return Ok(()) };
+        let providers: Vec<String> = declared_providers(cloud).into_iter().map(str::to_string).collect();
+        self.start_cloud_providers(&providers, READY_TIMEOUT).await?;
+        Ok(())
+    }
+
+    /// Start the emulators of `providers` side by side on the project network,
+    /// waiting up to `timeout` for each to become ready before seeding it
+    pub async fn start_cloud_providers(&mut self, providers: &[String], timeout: Duration) -> Result<Vec<CloudStatus>> {
+        let mut emulators = Vec::new();
+        for provider in providers {
+            let provider = canonical_provider(provider)?;
+            if emulators.iter().any(|(started, _)| *started == provider) {
+                continue;
+            }
+            let emulator = self.cloud_emulator(provider.to_string()).await?
+                .with_config(self.config.cloud.as_ref())
+                .on_network(self.orchestrator.network_name());
+            emulators.push((provider, emulator));
+        }
+        self.orchestrator.create_network().await?;
+
+        let started = futures::future::try_join_all(emulators.iter().map(|(provider, emulator)| async move {
+            info!("Starting {} cloud emulator", provider);
+            emulator.start().await?;
+            emulator.wait_until_ready(timeout).await?;
+            let seeded = emulator.seed().await?;
+            if !seeded.is_empty() {
+                info!("Created {} resources in the {} emulator", seeded.len(), provider);
+            }
+            emulator.provider_status().await
+        })).await?;
+
+        for status in &started {
+            self.state.cloud.insert(status.provider.clone(), CloudState {
+                endpoint: status.endpoint.clone(),
+                hostname: status.hostname.clone(),
+                last_started: Some(Utc::now()),
+            });
+        }
+        self.save_state();
+        Ok(started)
+    }
+
+    /// Stop the emulators of `cloud:` and any others started for the project
+    pub async fn stop_cloud(&mut self) {
+        for provider in self.cloud_providers() {
+            let stopped = match self.cloud_emulator(provider.clone()).await {
+                Ok(emulator) => emulator.stop().await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = stopped {
+                warn!("Failed to stop {} cloud emulator: {:#}", provider, e);
+            }
+        }
+        if !self.state.cloud.is_empty() {
+            self.state.cloud.clear();
+            self.save_state();
+        }
+    }
+
+    /// Status of the emulators of `cloud:` and any others started for the project
+    pub async fn cloud_status(&self) -> Result<Vec<CloudStatus>> {
+        let mut statuses = Vec::new();
+        for provider in self.cloud_providers() {
+            let emulator = self.cloud_emulator(provider).await?.with_config(self.config.cloud.as_ref());
+            statuses.push(emulator.provider_status().await?);
+        }
+        Ok(statuses)
+    }
+
+    /// Snapshot the data of the project's running emulators to `path`,
+    /// returning the providers captured
+    pub async fn snapshot_cloud(&self, name: &str, path: &Path) -> Result<Vec<String>> {
+        let mut archives = Vec::new();
+        for provider in self.cloud_providers() {
+            let emulator = self.cloud_emulator(provider.clone()).await?.with_config(self.config.cloud.as_ref());
+            if !emulator.keeps_data() {
+                warn!("Skipping the {} emulator, which keeps no data to snapshot", provider);
+                continue;
+            }
+            if !emulator.is_running().await? {
+                warn!("Skipping the {} emulator, which is not running", provider);
+                continue;
+            }
+            info!("Exporting {} emulator data", provider);
+            archives.push((provider, emulator.export_data().await?));
+        }
+        if archives.is_empty() {
+            anyhow::bail!("No running emulator keeps data to snapshot; start them with zero cloud start");
+        }
+
+        let manifest = SnapshotManifest {
+            name: name.to_string(),
+            created_at: Utc::now(),
+            providers: archives.iter().map(|(provider, _)| provider.clone()).collect(),
+        };
+        snapshot::write_snapshot(path, &manifest, &archives)?;
+        Ok(manifest.providers)
+    }
+
+    /// Replace the data of the emulators in a snapshot with its contents and
+    /// start them again, waiting up to `timeout` for each
+    pub async fn restore_cloud(&mut self, path: &Path, timeout: Duration) -> Result<Vec<CloudStatus>> {
+        let (manifest, archives) = snapshot::read_snapshot(path)?;
+        info!("Restoring cloud snapshot {} from {}", manifest.name, manifest.created_at);
+
+        for (provider, archive) in archives {
+            let provider = canonical_provider(&provider)
+                .with_context(|| format!("Snapshot {} holds data of an unknown provider", path.display()))?;
+            let emulator = self.cloud_emulator(provider.to_string()).await?.with_config(self.config.cloud.as_ref());
+            emulator.stop().await?;
+            emulator.import_data(archive).await
+                .with_context(|| format!("Failed to restore the {} emulator", provider))?;
+        }
+        self.start_cloud_providers(&manifest.providers, timeout).await
+    }
+
+    /// Providers declared in zero.yml or recorded in the project state, declared ones first
+    fn cloud_providers(&self) -> Vec<String> {
+        let mut providers: Vec<String> = self.config.cloud.as_ref()
+            .map(declared_providers)
+            .unwrap_or_default()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        for provider in self.state.cloud.keys() {
+            if !providers.contains(provider) {
+                providers.push(provider.clone());
+            }
+        }
+        providers
+    }
+}
@@ -0,0 +1,148 @@
+//! Changing generated credentials of a project
+//!
+//! Credentials are stored as `{service}_{VARIABLE}`. A new value
+//! is applied inside the running service first, so its data stays reachable,
+//! then stored, and finally the service and every container that gets its
+//! connection string are recreated with it.
+
+use anyhow::{Context, Result};
+use tracing::info;
+
+use super::Engine;
+use crate::secrets::SecretGenerator;
+
+/// How a running service takes a new value of one of its credentials
+#[derive(Debug, PartialEq)]
+enum Rotation {
+    /// Run a command in the container, then restart it
+    Exec(Vec<String>),
+    /// The value is read at startup, so a restart is enough
+    Restart,
+    /// The value is only used when the data directory is initialized
+    InitOnly,
+}
+
+impl Engine {
+    /// Service a stored credential belongs to, from its `{service}_` prefix
+    pub fn credential_owner(&self, key: &str) -> Option<String> {
+        self.config.services.keys()
+            .filter(|service| key.strip_prefix(service.as_str()).is_some_and(|rest| rest.starts_with('_')))
+            .max_by_key(|service| service.len())
+            .cloned()
+    }
+
+    /// Replace a credential with a newly generated value
+    ///
+    /// Returns the restarted services.
+    pub async fn rotate_secret(&mut self, key: &str) -> Result<Vec<String>> {
+        self.set_secret(key, SecretGenerator::generate_for_key(key)).await
+    }
+
+    /// Replace a credential, changing it in its running service and restarting the containers using it
+    ///
+    /// Returns the restarted services; none when the owning service is not running,
+    /// as it picks up the value when it next starts.
+    pub async fn set_secret(&mut self, key: &str, value: String) -> Result<Vec<String>> {
+        let Some(service) = self.credential_owner(key) else {
+            self.orchestrator.set_credential(key, value).await?;
+            return Ok(Vec::new());
+        };
+        let variable = &key[service.len() + 1..];
+        let config = self.config.services[&service].clone();
+        if config.environment.contains_key(variable) {
+            anyhow::bail!("{} of {} is set in zero.yml; change it there instead", variable, service);
+        }
+
+        if !self.orchestrator.is_running(&service).await {
+            self.orchestrator.set_credential(key, value).await?;
+            return Ok(Vec::new());
+        }
+
+        let env = self.orchestrator.service_env(&service, &config).await;
+        match rotation(&service, variable, &env, &value) {
+            Rotation::Exec(command) => {
+                info!("Changing {} inside {}", variable, service);
+                self.orchestrator.exec_command_bytes(&service, command).await
+                    .with_context(|| format!("Failed to change {} in {}", variable, service))?;
+            }
+            Rotation::Restart => {}
+            Rotation::InitOnly => anyhow::bail!(
+                "{} is only read when {} first creates its data; remove its volume to change it",
+                variable, service
+            ),
+        }
+        self.orchestrator.set_credential(key, value).await?;
+
+        let mut restart = Vec::new();
+        for candidate in self.config.start_order()? {
+            let affected = candidate == service || self.connects_to(&candidate, &service);
+            if affected && self.orchestrator.is_running(&candidate).await {
+                restart.push(candidate);
+            }
+        }
+        for candidate in &restart {
+            info!("Restarting {} with the new {}", candidate, variable);
+            self.start_service(candidate).await?;
+        }
+
+        Ok(restart)
+    }
+}
+
+/// How `service` takes a new value of `variable`, given the environment it runs with
+fn rotation(service: &str, variable: &str, env: &std::collections::HashMap<String, String>, value: &str) -> Rotation {
+    let get = |key: &str, default: &str| env.get(key).cloned().unwrap_or_else(|| default.to_string());
+    let sql_string = |text: &str| format!("'{}'", text.replace('\'', "''"));
+
+    match (service, variable) {
+        ("postgres" | "timescaledb", "POSTGRES_PASSWORD") => {
+            let user = get("POSTGRES_USER", "postgres");
+            let database = get("POSTGRES_DB", &user);
+            Rotation::Exec(vec![
+                "psql".into(), "-U".into(), user.clone(), "-d".into(), database, "-c".into(),
+                format!("ALTER USER \"{}\" WITH PASSWORD {}", user.replace('"', "\"\""), sql_string(value)),
+            ])
+        }
+        ("mysql", "MYSQL_ROOT_PASSWORD") => Rotation::Exec(vec![
+            "mysql".into(), "-uroot".into(), format!("-p{}", get("MYSQL_ROOT_PASSWORD", "")), "-e".into(),
+            format!(
+                "ALTER USER 'root'@'%' IDENTIFIED BY {0}; ALTER USER 'root'@'localhost' IDENTIFIED BY {0};",
+                sql_string(value)
+            ),
+        ]),
+        ("mongodb" | "mongo", "MONGO_INITDB_ROOT_PASSWORD") => {
+            let user = get("MONGO_INITDB_ROOT_USERNAME", "root");
+            let script = format!(
+                "db.getSiblingDB('admin').changeUserPassword({}, {})",
+                serde_json::Value::from(user.as_str()),
+                serde_json::Value::from(value)
+            );
+            Rotation::Exec(vec![
+                "mongosh".into(), "--quiet".into(), "-u".into(), user, "-p".into(), get("MONGO_INITDB_ROOT_PASSWORD", ""),
+                "--authenticationDatabase".into(), "admin".into(), "--eval".into(), script,
+            ])
+        }
+        ("rabbitmq", "RABBITMQ_DEFAULT_PASS") => Rotation::Exec(vec![
+            "rabbitmqctl".into(), "change_password".into(), get("RABBITMQ_DEFAULT_USER", "guest"), value.into(),
+        ]),
+        ("influxdb", _) if variable.starts_with("DOCKER_INFLUXDB_INIT_") => Rotation::InitOnly,
+        _ => Rotation::Restart,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_rotation_commands() {
+        let env = HashMap::from([("POSTGRES_USER".to_string(), "zeroconfig".to_string())]);
+        let Rotation::Exec(command) = rotation("postgres", "POSTGRES_PASSWORD", &env, "it's") else { panic!() };
+        assert_eq!(command[..5], ["psql", "-U", "zeroconfig", "-d", "zeroconfig"]);
+        assert_eq!(command[6], "ALTER USER \"zeroconfig\" WITH PASSWORD 'it''s'");
+
+        assert_eq!(rotation("influxdb", "DOCKER_INFLUXDB_INIT_PASSWORD", &env, "x"), Rotation::InitOnly);
+        assert_eq!(rotation("minio", "MINIO_ROOT_PASSWORD", &env, "x"), Rotation::Restart);
+    }
+}
@@ -0,0 +1,176 @@
+//! Dev mode: keep app containers in step with the source tree and zero.yml
+//!
+//! `zero dev` feeds bursts of file changes from a [`FileWatcher`] into
+//! [`Engine::apply_changes`], which rebuilds the services whose build context
+//! changed and restarts the services whose configuration changed.
+//!
+//! [`FileWatcher`]: crate::watch::FileWatcher
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::Engine;
+use crate::config::ZeroConfig;
+use crate::watch::is_config_file;
+
+/// What the engine did about a burst of changes
+#[derive(Debug, Default, PartialEq)]
+pub struct DevUpdate {
+    /// Services rebuilt from their Dockerfile and restarted
+    pub rebuilt: Vec<String>,
+    /// Services restarted because zero.yml changed them or a service they depend on
+    pub restarted: Vec<String>,
+    /// Services removed from zero.yml and stopped
+    pub stopped: Vec<String>,
+}
+
+impl DevUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.rebuilt.is_empty() && self.restarted.is_empty() && self.stopped.is_empty()
+    }
+}
+
+impl Engine {
+    /// Rebuild and restart what a burst of changed paths affects
+    ///
+    /// A config that no longer validates is rejected and the running services
+    /// are left alone.
+    pub async fn apply_changes(&mut self, changed: &[PathBuf]) -> Result<DevUpdate> {
+        let mut update = DevUpdate::default();
+
+        if changed.iter().any(|path| is_config_file(path)) {
+            let (restarted, stopped) = self.reload_config().await?;
+            update.restarted = restarted;
+            update.stopped = stopped;
+        }
+
+        for service in self.services_built_from(changed) {
+            if update.restarted.contains(&service) {
+                continue;
+            }
+            info!("Rebuilding {} after source changes", service);
+            let config = self.config.services[&service].clone();
+            self.build_image(&service, &config, true).await?;
+            self.start_service(&service).await?;
+            update.rebuilt.push(service);
+        }
+
+        Ok(update)
+    }
+
+    /// Services with `build:` whose context contains one of the paths
+    fn services_built_from(&self, changed: &[PathBuf]) -> Vec<String> {
+        let mut services: Vec<String> = self.config.services.iter()
+            .filter_map(|(service, config)| {
+                let context = canonical(&self.config.resolve_path(&config.build.as_ref()?.context));
+                let affected = changed.iter()
+                    .filter(|path| !is_config_file(path))
+                    .any(|path| canonical(path).starts_with(&context));
+                affected.then(|| service.clone())
+            })
+            .collect();
+        services.sort();
+        services
+    }
+
+    /// Load zero.yml again and restart what changed, with the services depending on it
+    ///
+    /// Returns the restarted and the stopped services.
+    async fn reload_config(&mut self) -> Result<(Vec<String>, Vec<String>)> {
+        let config = ZeroConfig::discover_in(self.project_dir())?
+            .context("zero.yml is gone")?;
+        config.validate().context("Keeping the running services; zero.yml is invalid")?;
+
+        let changed = changed_services(&self.config, &config);
+        let stopped: Vec<String> = self.config.services.keys()
+            .filter(|service| !config.services.contains_key(*service))
+            .cloned()
+            .collect();
+        let affected = with_dependents(&config, &changed);
+        let restart: Vec<String> = config.start_order()?.into_iter()
+            .filter(|service| affected.contains(service))
+            .collect();
+
+        for service in stopped.iter().chain(&restart) {
+            // Not running yet is fine, e.g. a service that was just added
+            let _ = self.orchestrator.stop_service(service).await;
+            self.allocated_ports.remove(service);
+        }
+        self.record_stopped(&stopped);
+        self.config = config;
+
+        for service in &restart {
+            info!("Restarting {} after a zero.yml change", service);
+            self.start_service(service).await?;
+        }
+
+        Ok((restart, stopped))
+    }
+}
+
+/// Services that are new in `new` or configured differently than in `old`
+fn changed_services(old: &ZeroConfig, new: &ZeroConfig) -> BTreeSet<String> {
+    new.services.iter()
+        .filter(|(service, config)| {
+            let before = old.services.get(*service).and_then(|config| serde_json::to_value(config).ok());
+            before != serde_json::to_value(config).ok()
+        })
+        .map(|(service, _)| service.clone())
+        .collect()
+}
+
+/// The services plus every service that depends on one of them, directly or not
+fn with_dependents(config: &ZeroConfig, services: &BTreeSet<String>) -> BTreeSet<String> {
+    let mut affected = services.clone();
+    loop {
+        let more: Vec<String> = config.services.iter()
+            .filter(|(service, config)| {
+                !affected.contains(*service) && config.depends_on.iter().any(|dependency| affected.contains(dependency))
+            })
+            .map(|(service, _)| service.clone())
+            .collect();
+        if more.is_empty() {
+            return affected;
+        }
+        affected.extend(more);
+    }
+}
+
+/// Compare paths whether or not they exist anymore, e.g. deleted sources
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_change_restarts_dependents() {
+        let yaml = r#"
+services:
+  postgres:
+    version: "15"
+  redis:
+    version: "7"
+  api:
+    build: {}
+    depends_on: [postgres]
+  worker:
+    build: {}
+    depends_on: [api]
+"#;
+        let old = ZeroConfig::from_str(yaml).unwrap();
+        let new = ZeroConfig::from_str(&yaml.replace("\"15\"", "\"16\"")).unwrap();
+
+        let changed = changed_services(&old, &new);
+        assert_eq!(changed, BTreeSet::from(["postgres".to_string()]));
+        assert_eq!(
+            with_dependents(&new, &changed),
+            BTreeSet::from(["api".to_string(), "postgres".to_string(), "worker".to_string()])
+        );
+        assert!(changed_services(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,251 @@
+use bollard::models::ContainerSummary;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ZeroConfig;
+
+/// Topology of a project: the app, its services and cloud emulators
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    /// Image or version the node runs, when known
+    pub version: Option<String>,
+    /// Published host port, filled in from the running container
+    pub port: Option<u16>,
+    pub status: NodeStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    App,
+    Service,
+    Emulator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeStatus {
+    Running,
+    Stopped,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+    /// Environment variable carrying the connection, for `connection` edges
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// The app reaches the target through an injected connection string
+    Connection,
+    /// The source starts once the target is healthy (`depends_on`)
+    DependsOn,
+}
+
+/// Node id used for the project's own application
+pub const APP_NODE: &str = "app";
+
+impl ServiceGraph {
+    /// Build the static graph described by a configuration; every status starts as `Unknown`
+    pub fn from_config(project_name: &str, config: &ZeroConfig) -> Self {
+        let mut graph = Self::default();
+
+        graph.nodes.push(GraphNode {
+            id: APP_NODE.to_string(),
+            kind: NodeKind::App,
+            label: project_name.to_string(),
+            version: None,
+            port: None,
+            status: NodeStatus::Unknown,
+        });
+
+        let mut services: Vec<_> = config.services.iter().collect();
+        services.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, service) in services {
+            graph.nodes.push(GraphNode {
+                id: name.clone(),
+                kind: NodeKind::Service,
+                label: name.clone(),
+                version: Some(service.version.clone()),
+                port: None,
+                status: NodeStatus::Unknown,
+            });
+
+            if crate::generators::envfile::connection_string(name).is_some() {
+                graph.connect(name, format!("{}_URL", name.to_uppercase()));
+            }
+            for dependency in &service.depends_on {
+                graph.edges.push(GraphEdge {
+                    from: name.clone(),
+                    to: dependency.clone(),
+                    kind: EdgeKind::DependsOn,
+                    label: None,
+                });
+            }
+        }
+
+        for provider in cloud_providers(config) {
+            graph.nodes.push(GraphNode {
+                id: provider.to_string(),
+                kind: NodeKind::Emulator,
+                label: emulator_label(provider).to_string(),
+                version: None,
+                port: None,
+                status: NodeStatus::Unknown,
+            });
+        }
+
+        // Explicit env entries that point at a service or emulator host
+        let mut env: Vec<_> = config.env.iter().collect();
+        env.sort();
+        for (key, value) in env {
+            let targets: Vec<String> = graph.nodes
+                .iter()
+                .filter(|node| node.kind != NodeKind::App && references_host(value, &node.id))
+                .map(|node| node.id.clone())
+                .collect();
+            for target in targets {
+                graph.connect(&target, key.clone());
+            }
+        }
+
+        graph
+    }
+
+    /// Fill in live status and ports from the project's containers
+    ///
+    /// Services without a container are reported as stopped.
+    pub fn apply_containers(&mut self, project_name: &str, containers: &[ContainerSummary]) {
+        let prefix = format!("{}_", project_name);
+
+        for node in self.nodes.iter_mut().filter(|node| node.kind == NodeKind::Service) {
+            let container = containers.iter().find(|container| {
+                container.names.iter().flatten().any(|name| {
+                    name.trim_start_matches('/').strip_prefix(&prefix) == Some(node.id.as_str())
+                })
+            });
+
+            let Some(container) = container else {
+                node.status = NodeStatus::Stopped;
+                continue;
+            };
+
+            node.status = match container.state.as_deref() {
+                Some("running") => NodeStatus::Running,
+                Some(_) => NodeStatus::Stopped,
+                None => NodeStatus::Unknown,
+            };
+            node.port = container.ports
+                .iter()
+                .flatten()
+                .find_map(|port| port.public_port);
+            if let Some(image) = &container.image {
+                node.version = Some(image.clone());
+            }
+        }
+    }
+
+    /// Set the status of a single node
+    pub fn set_status(&mut self, id: &str, status: NodeStatus) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| node.id == id) {
+            node.status = status;
+        }
+    }
+
+    fn connect(&mut self, target: &str, label: String) {
+        let edge = GraphEdge {
+            from: APP_NODE.to_string(),
+            to: target.to_string(),
+            kind: EdgeKind::Connection,
+            label: Some(label),
+        };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+}
+
+/// Cloud providers enabled in a configuration, as accepted by `CloudEmulator::new`
+pub fn cloud_providers(config: &ZeroConfig) -> Vec<&'static str> {
+    config.cloud.as_ref().map(crate::cloud::declared_providers).unwrap_or_default()
+}
+
+fn emulator_label(provider: &str) -> &'static str {
+    match provider {
+        "aws" => "LocalStack",
+        "azure" => "Azurite",
+        _ => "GCP Emulators",
+    }
+}
+
+/// Whether a connection string or URL uses `host` as its hostname
+fn references_host(value: &str, host: &str) -> bool {
+    ["//", "@"].iter().any(|before| {
+        value.match_indices(&format!("{}{}", before, host)).any(|(index, matched)| {
+            let rest = &value[index + matched.len()..];
+            rest.is_empty() || rest.starts_with([':', '/', '?'])
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_from_config() {
+        let config = ZeroConfig::from_str(r#"
+services:
+  postgres:
+    version: "15"
+  search:
+    version: "8"
+cloud:
+  aws:
+    services: [s3]
+env:
+  SEARCH_HOST: "http://search:9200"
+  UNRELATED: "http://searcher:9200"
+"#).unwrap();
+
+        let graph = ServiceGraph::from_config("shop", &config);
+        let ids: Vec<_> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(ids, vec!["app", "postgres", "search", "aws"]);
+
+        let labels: Vec<_> = graph.edges.iter().map(|edge| (edge.to.as_str(), edge.label.as_deref())).collect();
+        assert_eq!(labels, vec![("postgres", Some("POSTGRES_URL")), ("search", Some("SEARCH_HOST"))]);
+    }
+
+    #[test]
+    fn test_apply_containers_sets_status() {
+        let config = ZeroConfig::from_str("services:\n  postgres:\n    version: \"15\"\n  redis:\n    version: \"7\"\n").unwrap();
+        let mut graph = ServiceGraph::from_config("shop", &config);
+
+        let containers = vec![ContainerSummary {
+            names: Some(vec!["/shop_postgres".to_string()]),
+            state: Some("running".to_string()),
+            ..Default::default()
+        }];
+        graph.apply_containers("shop", &containers);
+
+        let status = |id: &str| graph.nodes.iter().find(|node| node.id == id).unwrap().status;
+        assert_eq!(status("postgres"), NodeStatus::Running);
+        assert_eq!(status("redis"), NodeStatus::Stopped);
+        assert_eq!(status("app"), NodeStatus::Unknown);
+    }
+}
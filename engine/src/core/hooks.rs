@@ -0,0 +1,209 @@
+//! Hooks run as the project comes up and goes down
+//!
+//! A service's `init:` steps run in its container, in order, once it reports
+//! healthy. SQL files go to the database's client, other files run as shell
+//! scripts, and `run:` steps as shell commands. Containers are recreated on
+//! every start, so steps must cope with data kept in volumes, e.g. with
+//! `CREATE TABLE IF NOT EXISTS`.
+//!
+//! Project `hooks:` and `startup:` commands run on the host from the directory
+//! of zero.yml, with the variables of `env:`, the services' connection strings
+//! and the provided language runtimes set. Startup commands wait for every
+//! service to be healthy and may run in a service's container instead.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, instrument, warn};
+
+use super::{Engine, DEPENDENCY_TIMEOUT};
+use crate::config::{InitStep, OnFailure, ServiceConfig, StartupCommand};
+use crate::secrets::EnvManager;
+
+/// When project hooks run, as named under `hooks:`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreUp,
+    PostUp,
+    PreDown,
+}
+
+impl HookStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookStage::PreUp => "pre_up",
+            HookStage::PostUp => "post_up",
+            HookStage::PreDown => "pre_down",
+        }
+    }
+}
+
+impl Engine {
+    /// Run a service's `init:` steps once it is healthy
+    ///
+    /// A failing step stops the start unless it has `on_failure: continue`.
+    #[instrument(skip(self, config))]
+    pub(crate) async fn run_init(&self, service_name: &str, config: &ServiceConfig) -> Result<()> {
+        if config.init.is_empty() {
+            return Ok(());
+        }
+        self.wait_for_healthy(service_name, DEPENDENCY_TIMEOUT).await
+            .with_context(|| format!("{} did not become healthy before its init steps", service_name))?;
+
+        for (index, step) in config.init.iter().enumerate() {
+            info!("Running init step {} of {}: {}", index + 1, service_name, step);
+            let result = self.run_init_step(service_name, step).await
+                .with_context(|| format!("Init step {} of {} ({}) failed", index + 1, service_name, step));
+            match (result, step.on_failure()) {
+                (Ok(()), _) => {}
+                (Err(e), OnFailure::Continue) => warn!("{:#}", e),
+                (Err(e), OnFailure::Fail) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the project hooks of a stage on the host, in order, stopping at the first failure
+    #[instrument(skip_all, fields(stage = stage.name()))]
+    pub async fn run_hooks(&self, stage: HookStage) -> Result<()> {
+        let hooks = &self.config.hooks;
+        let commands = match stage {
+            HookStage::PreUp => &hooks.pre_up,
+            HookStage::PostUp => &hooks.post_up,
+            HookStage::PreDown => &hooks.pre_down,
+        };
+        if commands.is_empty() {
+            return Ok(());
+        }
+
+        let env = self.host_env();
+        for command in commands {
+            info!("Running {} hook: {}", stage.name(), command);
+            run_on_host(command, &self.project_dir(), &env).await
+                .with_context(|| format!("{} hook `{}` failed", stage.name(), command))?;
+        }
+        Ok(())
+    }
+
+    /// Run the `startup:` commands in order once every service is healthy, stopping at the first failure
+    #[instrument(skip_all)]
+    pub async fn run_startup(&self) -> Result<()> {
+        if self.config.startup.is_empty() {
+            return Ok(());
+        }
+        for service_name in self.config.start_order()? {
+            self.wait_for_healthy(&service_name, DEPENDENCY_TIMEOUT).await
+                .with_context(|| format!("{} did not become healthy before the startup commands", service_name))?;
+        }
+
+        let env = self.host_env();
+        for (index, command) in self.config.startup.iter().enumerate() {
+            info!("Running startup command {}: {}", index + 1, command);
+            self.run_startup_command(command, &env).await
+                .with_context(|| format!("Startup command {} ({}) failed", index + 1, command))?;
+        }
+        Ok(())
+    }
+
+    async fn run_startup_command(&self, command: &StartupCommand, env: &HashMap<String, String>) -> Result<()> {
+        let Some(service_name) = command.service() else {
+            return run_on_host(command.run(), &self.project_dir(), env).await;
+        };
+        let shell = vec!["sh".to_string(), "-c".to_string(), command.run().to_string()];
+        match self.orchestrator.exec_command(service_name, shell).await? {
+            0 => Ok(()),
+            code => anyhow::bail!("Exited with code {}", code),
+        }
+    }
+
+    /// Environment of commands run on the host: `env:`, the connection strings of the last start
+    /// and the provided language runtimes
+    fn host_env(&self) -> HashMap<String, String> {
+        let mut manager = EnvManager::new();
+        manager.process_env_vars(&self.config.env);
+        manager.process_env_vars(&self.state.connection_env());
+        manager.process_env_vars(&self.state.toolchain_env());
+        manager.get_env_vars().clone()
+    }
+
+    async fn run_init_step(&self, service_name: &str, step: &InitStep) -> Result<()> {
+        if let Some(run) = step.run() {
+            let command = vec!["sh".to_string(), "-c".to_string(), run.to_string()];
+            self.orchestrator.exec_command_bytes(service_name, command).await?;
+            return Ok(());
+        }
+
+        let file = step.file().context("Init step has neither file nor run")?;
+        let path = self.config.resolve_path(file);
+        let content = std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let command = vec!["sh".to_string(), "-c".to_string(), file_command(service_name, &path).to_string()];
+        self.orchestrator.exec_command_with_input(service_name, command, &content).await?;
+        Ok(())
+    }
+}
+
+/// Run a command line through the host's shell, its output going to ours
+async fn run_on_host(command_line: &str, dir: &Path, env: &HashMap<String, String>) -> Result<()> {
+    let mut process = if cfg!(windows) {
+        let mut process = tokio::process::Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let mut process = tokio::process::Command::new("sh");
+        process.arg("-c");
+        process
+    };
+    let status = process.arg(command_line)
+        .current_dir(dir)
+        .envs(env)
+        .kill_on_drop(true)
+        .status()
+        .await
+        .context("Failed to start the shell")?;
+
+    if !status.success() {
+        match status.code() {
+            Some(code) => anyhow::bail!("Exited with code {}", code),
+            None => anyhow::bail!("Killed by a signal"),
+        }
+    }
+    Ok(())
+}
+
+/// Shell command that runs a file fed on stdin, using the credentials the container was started with
+fn file_command(service_name: &str, path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+    match (service_name, extension) {
+        ("postgres" | "timescaledb", "sql") => r#"psql -v ON_ERROR_STOP=1 -U "$POSTGRES_USER" -d "$POSTGRES_DB""#,
+        ("mysql" | "mariadb", "sql") => r#"mysql -uroot -p"$MYSQL_ROOT_PASSWORD" "$MYSQL_DATABASE""#,
+        ("mongodb" | "mongo", "js") => {
+            r#"mongosh --quiet -u "$MONGO_INITDB_ROOT_USERNAME" -p "$MONGO_INITDB_ROOT_PASSWORD" --authenticationDatabase admin"#
+        }
+        ("redis", "redis" | "txt") => "redis-cli",
+        _ => "sh -s",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_command_by_service_and_extension() {
+        assert!(file_command("postgres", Path::new("db/schema.sql")).starts_with("psql -v ON_ERROR_STOP=1"));
+        assert!(file_command("mysql", Path::new("seed.sql")).starts_with("mysql -uroot"));
+        assert_eq!(file_command("postgres", Path::new("scripts/seed.sh")), "sh -s");
+        assert_eq!(file_command("api", Path::new("seed.sql")), "sh -s");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_on_host_passes_env_and_exit_code() {
+        let env = HashMap::from([("DATABASE_URL".to_string(), "postgres://localhost/app".to_string())]);
+        let dir = std::env::temp_dir();
+        run_on_host(r#"test "$DATABASE_URL" = postgres://localhost/app"#, &dir, &env).await.unwrap();
+
+        let error = run_on_host("exit 3", &dir, &env).await.unwrap_err();
+        assert_eq!(error.to_string(), "Exited with code 3");
+    }
+}
@@ -1,9 +1,40 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use bollard::models::ContainerSummary;
-use tracing::info;
-
-use crate::config::ZeroConfig;
-use crate::orchestrator::ContainerOrchestrator;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, info_span, instrument, warn, Instrument};
+
+use crate::config::{SecretsBackend, ServiceConfig, ZeroConfig};
+use crate::health::{HealthChecker, HealthStatus};
+use crate::logs::LogFilter;
+use crate::orchestrator::{ContainerOrchestrator, PullPolicy, ResourceUsage, ServiceInfo, ServiceStats};
+use crate::runtime::provisioner;
+use crate::services::ElasticsearchService;
+
+pub mod bootstrap;
+pub mod cloud;
+pub mod credentials;
+pub mod dev;
+pub mod env;
+pub mod graph;
+pub mod hooks;
+pub mod ports;
+pub mod preflight;
+pub mod ps;
+pub mod share;
+pub mod sidecars;
+pub mod state;
+
+pub use dev::DevUpdate;
+pub use env::{ResolvedEnv, ServiceEnv};
+pub use graph::{GraphEdge, GraphNode, NodeStatus, ServiceGraph};
+pub use hooks::HookStage;
+pub use ps::ServiceListing;
+pub use sidecars::WebUi;
+pub use state::{CloudState, ProjectState, ServiceState};
+
+/// How long a service waits for each of its `depends_on` to become healthy
+const DEPENDENCY_TIMEOUT: Duration = Duration::from_secs(120);
 
 /// Main ZeroConfig engine that orchestrates the environment
 pub struct Engine {
@@ -11,58 +42,142 @@ pub struct Engine {
     config: ZeroConfig,
     orchestrator: ContainerOrchestrator,
     allocated_ports: std::collections::HashMap<String, u16>,
+    state: ProjectState,
 }
 
 impl Engine {
     pub async fn new(project_name: String, config: ZeroConfig) -> Result<Self> {
         info!("Initializing ZeroConfig engine for project: {}", project_name);
 
-        let orchestrator = ContainerOrchestrator::new(project_name.clone()).await?;
+        let orchestrator = ContainerOrchestrator::connect(project_name.clone(), config.runtime.host.as_deref()).await?;
 
-        Ok(Self {
+        let mut engine = Self {
             project_name,
             config,
             orchestrator,
             allocated_ports: std::collections::HashMap::new(),
-        })
+            state: ProjectState::default(),
+        };
+        // Credentials live next to zero.yml, wherever in the project the engine starts
+        if engine.config.root.is_some() || engine.config.secrets.backend != SecretsBackend::File {
+            engine.orchestrator.set_credential_store(engine.project_dir(), engine.config.secrets.backend).await?;
+        }
+        engine.state = ProjectState::load(&engine.project_dir()).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable project state: {:#}", e);
+            ProjectState::default()
+        });
+
+        Ok(engine)
+    }
+
+    /// Name of the project this engine manages
+    pub fn project_name(&self) -> &str {
+        &self.project_name
+    }
+
+    /// Configuration the engine was created from
+    pub fn config(&self) -> &ZeroConfig {
+        &self.config
     }
 
     pub async fn build(&mut self) -> Result<()> {
         info!("Building environment...");
 
-        // Create network
-        self.orchestrator.create_network().await?;
+        // Create networks
+        self.orchestrator.create_networks(&self.config.networks()).await?;
 
         // Allocate ports for services
-        self.allocate_ports()?;
+        self.allocate_ports().await?;
+
+        // Rebuild application images from their Dockerfiles
+        for (service_name, service_config) in self.config.get_services() {
+            self.build_image(&service_name, &service_config, true).await?;
+        }
 
         info!("Environment built successfully");
         Ok(())
     }
 
+    #[instrument(name = "up", skip_all, fields(project = %self.project_name))]
     pub async fn start(&mut self) -> Result<()> {
+        if !self.config.languages.is_empty() {
+            info!("Providing language runtimes...");
+            let toolchains = provisioner::provision_all(&self.config.languages, &self.project_dir())
+                .instrument(info_span!("provision_languages"))
+                .await?;
+            self.record_toolchains(toolchains);
+        }
+        self.run_hooks(HookStage::PreUp).await?;
         info!("Starting services...");
 
-        for (service_name, service_config) in self.config.get_services() {
-            let port = self.allocated_ports.get(&service_name).copied().unwrap_or(8080);
+        if self.allocated_ports.is_empty() {
+            self.allocate_ports().await?;
+        }
+        self.pull_images().await?;
+        self.orchestrator.create_networks(&self.config.networks()).await?;
 
-            info!("Starting service: {} on port {}", service_name, port);
+        // Before the apps, so the collectors receive their traces from the first request
+        if self.config.observability {
+            info!("Starting observability stack");
+            self.start_observability().await?;
+        }
+        if self.config.cloud.is_some() {
+            info!("Starting cloud emulators");
+            self.start_cloud().await?;
+        }
 
-            self.orchestrator
-                .start_service(&service_name, &service_config, port)
-                .await?;
+        for service_name in self.config.start_order()? {
+            let service_config = self.config.services[&service_name].clone();
+            let port = self.allocated_ports[&service_name];
+            let span = info_span!("service", service = %service_name, port);
+
+            async {
+                self.wait_for_dependencies(&service_name, &service_config).await?;
+                self.build_image(&service_name, &service_config, false).await?;
+                info!("Starting service: {} on port {}", service_name, port);
+
+                let container_config = self.container_config(&service_name, &service_config).await?;
+                self.orchestrator
+                    .start_service(&service_name, &container_config, port)
+                    .await?;
+                self.record_started(&service_name, port).await;
+                self.bootstrap(&service_name, &service_config).await?;
+                self.run_init(&service_name, &service_config).await
+            }.instrument(span).await?;
+        }
+
+        for (service_name, service_config) in self.config.get_services() {
+            if service_config.admin_ui {
+                self.start_admin_ui(&service_name).await?;
+            }
+            if service_config.kibana && ElasticsearchService::handles(&service_name) {
+                self.start_dashboards(&service_name, &service_config.version).await?;
+            }
         }
 
         info!("All services started");
-        Ok(())
+        self.run_startup().await?;
+        self.run_hooks(HookStage::PostUp).await
     }
 
-    pub async fn stop(&self) -> Result<()> {
+    #[instrument(name = "down", skip_all, fields(project = %self.project_name))]
+    pub async fn stop(&mut self) -> Result<()> {
+        // A failing hook must not keep the services running
+        if let Err(e) = self.run_hooks(HookStage::PreDown).await {
+            warn!("{:#}", e);
+        }
+        if let Err(e) = self.unshare(None) {
+            warn!("{:#}", e);
+        }
         info!("Stopping all services...");
         self.orchestrator.stop_all().await?;
+        self.stop_cloud().await;
+        let services: Vec<String> = self.config.services.keys().cloned().collect();
+        self.record_stopped(&services);
         Ok(())
     }
 
+    #[instrument(skip(self))]
     pub async fn start_service(&mut self, service_name: &str) -> Result<()> {
         info!("Starting service: {}", service_name);
 
@@ -75,24 +190,84 @@ impl Engine {
             .map(|(_, config)| config)
             .ok_or_else(|| anyhow::anyhow!("Service '{}' not found in configuration", service_name))?;
 
-        // Get allocated port or use default
-        let port = self.allocated_ports.get(service_name).copied().unwrap_or_else(|| {
-            // Allocate port if not already allocated
-            let port = 5000 + self.allocated_ports.len() as u16;
-            port
-        });
+        if !self.allocated_ports.contains_key(service_name) {
+            self.allocate_ports().await?;
+        }
+        let port = self.allocated_ports[service_name];
+
+        self.wait_for_dependencies(service_name, &service_config).await?;
+        self.build_image(service_name, &service_config, false).await?;
 
+        let container_config = self.container_config(service_name, &service_config).await?;
         self.orchestrator
-            .start_service(service_name, &service_config, port)
+            .start_service(service_name, &container_config, port)
             .await?;
+        self.record_started(service_name, port).await;
+        self.bootstrap(service_name, &service_config).await?;
+        self.run_init(service_name, &service_config).await?;
+        if service_config.admin_ui {
+            self.start_admin_ui(service_name).await?;
+        }
+        if service_config.kibana && ElasticsearchService::handles(service_name) {
+            self.start_dashboards(service_name, &service_config.version).await?;
+        }
 
         info!("Service '{}' started on port {}", service_name, port);
         Ok(())
     }
 
-    pub async fn stop_service(&self, service_name: &str) -> Result<()> {
+    /// Pull the images of all services at once, ahead of starting them in order
+    #[instrument(skip_all)]
+    async fn pull_images(&self) -> Result<()> {
+        let pulls = self.config.services.iter()
+            .filter(|(_, config)| config.build.is_none())
+            .map(|(service, config)| {
+                let image = self.orchestrator.get_service_image(service, &config.version);
+                async move { self.orchestrator.pull_image(&image).await }
+            });
+        futures::future::try_join_all(pulls).await?;
+        Ok(())
+    }
+
+    /// When images are pulled; missing ones only by default
+    pub fn set_pull_policy(&mut self, policy: PullPolicy) {
+        self.orchestrator.set_pull_policy(policy);
+    }
+
+    /// Draw image pulls as progress bars
+    pub fn show_pull_progress(&mut self, progress: indicatif::MultiProgress) {
+        self.orchestrator.show_pull_progress(progress);
+    }
+
+    /// Build the image of a service with `build:` when it is missing, or always with `rebuild`
+    #[instrument(skip(self, config))]
+    async fn build_image(&self, service_name: &str, config: &ServiceConfig, rebuild: bool) -> Result<()> {
+        let Some(build) = &config.build else { return Ok(()) };
+        if !rebuild && self.orchestrator.image_exists(&self.orchestrator.built_image(service_name)).await {
+            return Ok(());
+        }
+
+        info!("Building image of {}", service_name);
+        let context = self.config.resolve_path(&build.context);
+        self.orchestrator.build_image(service_name, build, &context).await?;
+        Ok(())
+    }
+
+    /// Wait until every service in `depends_on` is healthy
+    #[instrument(skip_all)]
+    async fn wait_for_dependencies(&self, service_name: &str, config: &ServiceConfig) -> Result<()> {
+        for dependency in &config.depends_on {
+            info!("{} waits for {} to become healthy", service_name, dependency);
+            self.wait_for_healthy(dependency, DEPENDENCY_TIMEOUT).await
+                .with_context(|| format!("Dependency '{}' of '{}' is not healthy", dependency, service_name))?;
+        }
+        Ok(())
+    }
+
+    pub async fn stop_service(&mut self, service_name: &str) -> Result<()> {
         info!("Stopping service: {}", service_name);
         self.orchestrator.stop_service(service_name).await?;
+        self.record_stopped(&[service_name.to_string()]);
         Ok(())
     }
 
@@ -100,19 +275,76 @@ impl Engine {
         self.orchestrator.list_containers().await
     }
 
-    pub async fn get_logs(&self, service: &str, follow: bool, tail: usize) -> Result<()> {
-        self.orchestrator.get_logs(service, follow, tail).await
+    /// Topology of the project with live service and emulator status
+    pub async fn service_graph(&self) -> Result<ServiceGraph> {
+        let mut graph = ServiceGraph::from_config(&self.project_name, &self.config);
+
+        let containers = self.orchestrator.list_containers().await?;
+        graph.apply_containers(&self.project_name, &containers);
+
+        for provider in graph::cloud_providers(&self.config) {
+            let running = match self.cloud_emulator(provider.to_string()).await {
+                Ok(emulator) => emulator.with_config(self.config.cloud.as_ref()).is_running().await.unwrap_or(false),
+                Err(_) => false,
+            };
+            let status = if running { NodeStatus::Running } else { NodeStatus::Stopped };
+            graph.set_status(provider, status);
+        }
+
+        Ok(graph)
+    }
+
+    pub async fn inspect_service(&self, service: &str) -> Result<crate::orchestrator::ServiceDetails> {
+        self.orchestrator.inspect_service(service).await
+    }
+
+    pub async fn list_volumes(&self) -> Result<Vec<crate::orchestrator::ProjectVolume>> {
+        self.orchestrator.list_volumes().await
+    }
+
+    pub async fn inspect_volume(&self, name: &str) -> Result<bollard::models::Volume> {
+        self.orchestrator.inspect_volume(name).await
+    }
+
+    pub async fn browse_volume(&self, name: &str, path: &str) -> Result<Vec<crate::orchestrator::VolumeEntry>> {
+        self.orchestrator.browse_volume(name, path).await
+    }
+
+    pub async fn delete_volume(&self, name: &str) -> Result<Vec<String>> {
+        self.orchestrator.delete_volume(name).await
     }
 
-    pub async fn get_logs_as_string(&self, service: &str, tail: usize) -> Result<String> {
-        self.orchestrator.get_logs_as_string(service, tail).await
+    pub async fn get_logs_as_string(&self, service: &str, tail: usize, filter: &LogFilter) -> Result<String> {
+        self.orchestrator.get_logs_as_string(service, tail, filter).await
     }
 
-    pub async fn stream_logs(&self, service: &str, tail: usize) -> Result<impl futures::Stream<Item = Result<String>>> {
-        self.orchestrator.stream_logs(service, tail).await
+    pub async fn stream_logs(&self, service: &str, tail: usize, filter: LogFilter) -> Result<impl futures::Stream<Item = Result<String>>> {
+        self.orchestrator.stream_logs(service, tail, filter).await
     }
 
-    pub async fn exec_command(&self, service: &str, command: Vec<String>) -> Result<()> {
+    pub async fn logs_between(&self, service: &str, since: i64, until: i64) -> Result<String> {
+        self.orchestrator.logs_between(service, since, until).await
+    }
+
+    pub async fn search_logs(&self, service: &str, search: &crate::logs::LogSearch) -> Result<crate::logs::LogPage> {
+        self.orchestrator.search_logs(service, search).await
+    }
+
+    pub async fn get_host_port(&self, service: &str) -> Result<u16> {
+        self.orchestrator.get_host_port(service).await
+    }
+
+    pub async fn log_stream(
+        &self,
+        service: &str,
+        follow: bool,
+        tail: usize,
+        filter: LogFilter,
+    ) -> Result<impl futures::Stream<Item = Result<String>>> {
+        self.orchestrator.log_stream(service, follow, tail, filter).await
+    }
+
+    pub async fn exec_command(&self, service: &str, command: Vec<String>) -> Result<i64> {
         self.orchestrator.exec_command(service, command).await
     }
 
@@ -120,8 +352,41 @@ impl Engine {
         self.orchestrator.exec_command_with_output(service, command).await
     }
 
-    pub async fn open_shell(&self, service: &str, shell: &str) -> Result<()> {
-        self.orchestrator.open_shell(service, shell).await
+    pub async fn exec_command_bytes(&self, service: &str, command: Vec<String>) -> Result<Vec<u8>> {
+        self.orchestrator.exec_command_bytes(service, command).await
+    }
+
+    /// A credential from the project's credential store
+    pub async fn credential(&self, key: &str) -> Option<String> {
+        self.orchestrator.credential(key).await
+    }
+
+    pub async fn exec_command_with_input(&self, service: &str, command: Vec<String>, input: &[u8]) -> Result<Vec<u8>> {
+        self.orchestrator.exec_command_with_input(service, command, input).await
+    }
+
+    pub async fn exec_command_with_env(&self, service: &str, command: Vec<String>, env: Vec<String>, input: Option<&[u8]>) -> Result<Vec<u8>> {
+        self.orchestrator.exec_command_with_env(service, command, env, input).await
+    }
+
+    pub async fn exec_command_stream(
+        &self,
+        service: &str,
+        command: Vec<String>,
+    ) -> Result<(futures::stream::BoxStream<'static, Result<crate::orchestrator::ExecOutput>>, crate::orchestrator::ExecControl)> {
+        self.orchestrator.exec_command_stream(service, command).await
+    }
+
+    pub fn service_events(&self) -> futures::stream::BoxStream<'static, Result<crate::health::ServiceEvent>> {
+        self.orchestrator.service_events()
+    }
+
+    pub async fn open_exec_session(&self, service: &str, command: Vec<String>, cols: u16, rows: u16) -> Result<crate::orchestrator::ExecSession> {
+        self.orchestrator.open_exec_session(service, command, cols, rows).await
+    }
+
+    pub async fn open_shell(&self, service: &str, shell: &str, cols: u16, rows: u16) -> Result<crate::orchestrator::ExecSession> {
+        self.orchestrator.open_shell(service, shell, cols, rows).await
     }
 
     pub async fn restart_service(&self, service: &str) -> Result<()> {
@@ -132,6 +397,29 @@ impl Engine {
         self.orchestrator.restart_all().await
     }
 
+    /// Health checker for the project's containers, with the `healthcheck:`s of zero.yml
+    pub fn health_checker(&self) -> HealthChecker {
+        HealthChecker::new(self.docker()).with_healthchecks(&self.config)
+    }
+
+    /// Check a service once, with its `healthcheck:` from zero.yml when the container has none
+    pub async fn check_health(&self, service: &str) -> Result<HealthStatus> {
+        self.check_health_with(&self.health_checker(), service).await
+    }
+
+    /// Like [`check_health`](Self::check_health), with a checker reused across checks
+    pub async fn check_health_with(&self, health_checker: &HealthChecker, service: &str) -> Result<HealthStatus> {
+        let container_id = self.orchestrator.get_container_id(service).await?;
+        health_checker.check_container(&container_id, service).await
+    }
+
+    /// Wait until a service's container reports healthy
+    #[instrument(skip(self))]
+    pub async fn wait_for_healthy(&self, service: &str, timeout: Duration) -> Result<HealthStatus> {
+        let container_id = self.orchestrator.get_container_id(service).await?;
+        self.health_checker().wait_for_healthy(&container_id, service, timeout).await
+    }
+
     pub async fn get_container_stats(&self, service: &str) -> Result<bollard::container::Stats> {
         self.orchestrator.get_container_stats(service).await
     }
@@ -140,14 +428,30 @@ impl Engine {
         self.orchestrator.get_all_stats().await
     }
 
-    fn allocate_ports(&mut self) -> Result<()> {
-        let mut port = 5000;
+    /// Docker connection of the engine, e.g. to sample stats without holding the engine
+    pub fn docker(&self) -> bollard::Docker {
+        self.orchestrator.docker()
+    }
 
-        for (service_name, _) in self.config.get_services() {
-            self.allocated_ports.insert(service_name, port);
-            port += 1;
-        }
+    /// Resource usage of every running container, by container name
+    pub async fn service_stats(&self) -> Result<BTreeMap<String, ServiceStats>> {
+        Ok(self.get_all_stats().await?
+            .into_iter()
+            .map(|(name, stats)| (name, ResourceUsage::from_stats(&stats).into()))
+            .collect())
+    }
 
-        Ok(())
+    /// The project's containers with their resource usage, when stats are available
+    pub async fn service_infos(&self) -> Result<Vec<ServiceInfo>> {
+        let containers = self.list_services().await?;
+        let stats = self.service_stats().await.unwrap_or_default();
+
+        Ok(containers.iter()
+            .map(|container| {
+                let mut info = ServiceInfo::from_container(container, None);
+                info.stats = stats.get(&info.name).copied();
+                info
+            })
+            .collect())
     }
 }
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+
+use super::Engine;
+use crate::config::schema::KNOWN_SERVICES;
+use crate::config::{PortConfig, PortValue, ZeroConfig};
+use crate::validation::InputValidator;
+
+/// First port tried for services without a well-known port
+const FALLBACK_PORT: u16 = 5000;
+
+impl Engine {
+    /// Pick the host port of every service, failing when a fixed port is taken
+    ///
+    /// Running services keep the port they have.
+    pub(crate) async fn allocate_ports(&mut self) -> Result<()> {
+        let mut running = HashMap::new();
+        for service in self.config.services.keys() {
+            if let Ok(port) = self.orchestrator.get_host_port(service).await {
+                running.insert(service.clone(), port);
+            }
+        }
+
+        self.allocated_ports = plan_ports(&self.config, &running, &self.state.ports(), true)?;
+        Ok(())
+    }
+
+    /// Host ports services will be published on, once allocated
+    ///
+    /// Before allocation this is a best guess: fixed ports are reported even
+    /// when taken, so preflight can flag them.
+    pub(crate) fn port_plan(&self) -> HashMap<String, u16> {
+        if !self.allocated_ports.is_empty() {
+            return self.allocated_ports.clone();
+        }
+        plan_ports(&self.config, &HashMap::new(), &self.state.ports(), false).unwrap_or_default()
+    }
+}
+
+/// Choose a host port per service
+///
+/// `port:` pins (and top-level `ports:`) are used as is. Other services get
+/// their `previous` port while it is free, else ranges get their first free
+/// port and everything else the first free port from its well-known one.
+/// With `strict`, a taken pin or a full range is an error.
+pub(crate) fn plan_ports(
+    config: &ZeroConfig,
+    running: &HashMap<String, u16>,
+    previous: &HashMap<String, u16>,
+    strict: bool,
+) -> Result<HashMap<String, u16>> {
+    let mut services: Vec<&String> = config.services.keys().collect();
+    services.sort();
+
+    let mut plan = HashMap::new();
+    let mut taken: HashSet<u16> = running.values().copied().collect();
+
+    // Pins first, so automatic ports stay clear of them
+    for service in &services {
+        if let Some(&port) = running.get(*service) {
+            plan.insert(service.to_string(), port);
+        } else if let Some(port) = fixed_port(config, service) {
+            if strict && (taken.contains(&port) || !InputValidator::is_port_available(port)) {
+                anyhow::bail!("Port {} for service '{}' is already in use; free it or change the port in zero.yml", port, service);
+            }
+            taken.insert(port);
+            plan.insert(service.to_string(), port);
+        }
+    }
+
+    for service in services {
+        if plan.contains_key(service) {
+            continue;
+        }
+        let range = match &config.services[service].port {
+            PortValue::Range(range) => Some(range.min..=range.max),
+            _ => None,
+        };
+        let reusable = previous.get(service).copied().filter(|port| {
+            !taken.contains(port)
+                && InputValidator::is_port_available(*port)
+                && range.as_ref().is_none_or(|range| range.contains(port))
+        });
+
+        let port = match (reusable, range) {
+            (Some(port), _) => port,
+            (None, Some(range)) => match free_port(range.clone(), &taken) {
+                Some(port) => port,
+                None if strict => anyhow::bail!("No free port in {}-{} for service '{}'", range.start(), range.end(), service),
+                None => *range.start(),
+            },
+            (None, None) => {
+                let start = KNOWN_SERVICES.iter()
+                    .find(|known| known.name == service.as_str())
+                    .map_or(FALLBACK_PORT, |known| known.default_port);
+                free_port(start..=u16::MAX, &taken)
+                    .ok_or_else(|| anyhow::anyhow!("No free port for service '{}'", service))?
+            }
+        };
+        taken.insert(port);
+        plan.insert(service.clone(), port);
+    }
+
+    Ok(plan)
+}
+
+/// Port a service is pinned to by `port: 5432` or the top-level `ports:` map
+fn fixed_port(config: &ZeroConfig, service: &str) -> Option<u16> {
+    if let PortValue::Fixed(port) = config.services.get(service)?.port {
+        return Some(port);
+    }
+    match &config.ports {
+        PortConfig::Manual(ports) => ports.get(service).copied(),
+        PortConfig::Auto(_) => None,
+    }
+}
+
+/// First port in a range that is neither planned nor bound by another process
+fn free_port(range: std::ops::RangeInclusive<u16>, taken: &HashSet<u16>) -> Option<u16> {
+    let mut start = *range.start();
+    while let Some(port) = InputValidator::find_available_port(start) {
+        if port > *range.end() {
+            return None;
+        }
+        if !taken.contains(&port) {
+            return Some(port);
+        }
+        start = port.checked_add(1)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_plan_honors_pins_and_skips_bound_ports() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let busy = listener.local_addr().unwrap().port();
+
+        let yaml = format!(
+            "services:\n  api:\n    version: \"1\"\n    port: {{ min: {busy}, max: {max} }}\n  cache:\n    version: \"1\"\n    port: {pin}\n",
+            busy = busy, max = busy + 50, pin = busy + 1,
+        );
+        let config = ZeroConfig::from_str(&yaml).unwrap();
+        let plan = plan_ports(&config, &HashMap::new(), &HashMap::new(), true).unwrap();
+        assert_eq!(plan["cache"], busy + 1);
+        assert!(plan["api"] > busy + 1 && plan["api"] <= busy + 50);
+
+        let taken = ZeroConfig::from_str(&format!("services:\n  cache:\n    version: \"1\"\n    port: {}\n", busy)).unwrap();
+        assert!(plan_ports(&taken, &HashMap::new(), &HashMap::new(), true).unwrap_err().to_string().contains("already in use"));
+        assert_eq!(plan_ports(&taken, &HashMap::new(), &HashMap::new(), false).unwrap()["cache"], busy);
+        assert_eq!(plan_ports(&taken, &HashMap::from([("cache".to_string(), busy)]), &HashMap::new(), true).unwrap()["cache"], busy);
+
+        // A previous port is reused while it is free
+        let auto = ZeroConfig::from_str("services:\n  cache:\n    version: \"1\"\n").unwrap();
+        let previous = HashMap::from([("cache".to_string(), busy + 2)]);
+        assert_eq!(plan_ports(&auto, &HashMap::new(), &previous, true).unwrap()["cache"], busy + 2);
+    }
+}
@@ -0,0 +1,156 @@
+use std::net::TcpListener;
+use std::path::Path;
+
+use super::Engine;
+use crate::doctor::{self, CheckCategory, CheckStatus, DoctorAction, DoctorCheck};
+use crate::config::ServiceConfig;
+use crate::orchestrator::ImageAvailability;
+use crate::services::{elasticsearch, ElasticsearchService};
+
+/// Share of Docker's memory the services may take before a warning is reported
+const MEMORY_WARN_RATIO: f64 = 0.8;
+
+const MB: u64 = 1024 * 1024;
+
+impl Engine {
+    /// Find problems that would make starting services fail, without starting anything
+    ///
+    /// Checks host port conflicts, image availability, free disk space for
+    /// Docker's data and whether the services fit in Docker's memory.
+    /// An empty `services` list means every service in zero.yml.
+    pub async fn preflight(&self, services: &[String]) -> Vec<DoctorCheck> {
+        let mut selected: Vec<(String, ServiceConfig)> = self.config.get_services()
+            .into_iter()
+            .filter(|(name, _)| services.is_empty() || services.contains(name))
+            .collect();
+        selected.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut checks = Vec::new();
+        let mut running = Vec::new();
+        let ports = self.port_plan();
+
+        for (service, _) in &selected {
+            let Some(&port) = ports.get(service) else { continue };
+            let id = format!("port.{}", port);
+            let title = format!("Port {} ({})", port, service);
+
+            // A running container of this service already owns its port
+            if self.orchestrator.get_host_port(service).await.ok() == Some(port) {
+                running.push(service.clone());
+                checks.push(DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Pass, "Already running"));
+            } else if TcpListener::bind(("127.0.0.1", port)).is_ok() {
+                checks.push(DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Pass, "Free"));
+            } else {
+                checks.push(DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Fail, "In use by another process")
+                    .with_action(DoctorAction::RunCommand { command: port_owner_command(port) }));
+            }
+        }
+
+        for (service, config) in &selected {
+            if let Some(build) = &config.build {
+                let dockerfile = self.config.resolve_path(&build.context).join(build.dockerfile());
+                let id = format!("image.{}", service);
+                let title = format!("Image {}", self.orchestrator.built_image(service));
+                checks.push(if dockerfile.is_file() {
+                    DoctorCheck::new(id, CheckCategory::Images, title, CheckStatus::Pass, format!("Built from {}", dockerfile.display()))
+                } else {
+                    DoctorCheck::new(id, CheckCategory::Images, title, CheckStatus::Fail, format!("{} not found", dockerfile.display()))
+                        .with_action(DoctorAction::EditConfig { path: None })
+                });
+                continue;
+            }
+
+            let image = self.orchestrator.get_service_image(service, &config.version);
+            let id = format!("image.{}", service);
+            let title = format!("Image {}", image);
+            let check = match self.orchestrator.image_availability(&image).await {
+                ImageAvailability::Local => DoctorCheck::new(id, CheckCategory::Images, title, CheckStatus::Pass, "Present locally"),
+                ImageAvailability::Remote => DoctorCheck::new(id, CheckCategory::Images, title, CheckStatus::Pass, "Will be pulled on start"),
+                ImageAvailability::Missing(reason) => DoctorCheck::new(id, CheckCategory::Images, title, CheckStatus::Fail, format!("Not found in the registry: {}", reason))
+                    .with_action(DoctorAction::EditConfig { path: None }),
+                ImageAvailability::Unknown(reason) => DoctorCheck::new(id, CheckCategory::Images, title, CheckStatus::Warn, format!("Not present locally and the registry could not be checked: {}", reason)),
+            };
+            checks.push(check);
+        }
+
+        let data_root = self.orchestrator.data_root().await;
+        let disk_path = data_root.as_deref().map(Path::new).filter(|path| path.exists()).unwrap_or(Path::new("."));
+        checks.push(doctor::check_disk_space(disk_path));
+
+        let needed: u64 = selected.iter()
+            .filter(|(service, _)| !running.contains(service))
+            .map(|(service, config)| estimated_memory(service, config))
+            .sum();
+        let check = match self.orchestrator.resource_limits().await {
+            Ok((total, cpus)) if total > 0 => {
+                let status = memory_status(needed, total);
+                let message = format!(
+                    "Services need about {} MB; Docker has {} MB and {} CPUs",
+                    needed / MB, total / MB, cpus,
+                );
+                DoctorCheck::new("resources.memory", CheckCategory::Resources, "Memory budget", status, message)
+            }
+            Ok(_) => DoctorCheck::new("resources.memory", CheckCategory::Resources, "Memory budget", CheckStatus::Warn, "Docker did not report its memory"),
+            Err(e) => DoctorCheck::new("resources.memory", CheckCategory::Resources, "Memory budget", CheckStatus::Warn, format!("{:#}", e)),
+        };
+        checks.push(check);
+
+        checks
+    }
+}
+
+/// Rough memory a service needs to start and idle, in bytes
+///
+/// A `memory:` limit in zero.yml is taken as the service's need.
+fn estimated_memory(service: &str, config: &ServiceConfig) -> u64 {
+    if let Ok(Some(limit)) = config.memory_limit() {
+        return limit;
+    }
+    if ElasticsearchService::handles(service) {
+        return elasticsearch::DEFAULT_MEMORY;
+    }
+
+    let megabytes = match service {
+        "kafka" | "localstack" | "cassandra" => 1024,
+        "mongodb" | "mongo" | "mysql" | "influxdb" | "keycloak" | "clickhouse" => 512,
+        "redis" | "mailpit" | "mailhog" | "memcached" | "nats" => 64,
+        _ => 256,
+    };
+    megabytes * MB
+}
+
+fn memory_status(needed: u64, total: u64) -> CheckStatus {
+    if needed > total {
+        CheckStatus::Fail
+    } else if needed as f64 > total as f64 * MEMORY_WARN_RATIO {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    }
+}
+
+/// Shell command that shows which process holds a port
+fn port_owner_command(port: u16) -> String {
+    if cfg!(windows) {
+        format!("netstat -ano | findstr :{}", port)
+    } else {
+        format!("lsof -i :{}", port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ZeroConfig;
+
+    #[test]
+    fn test_memory_budget() {
+        let config = ZeroConfig::from_str("services:\n  elasticsearch:\n    version: \"8\"\n    memory: 2g\n  postgres:\n    version: \"16\"\n").unwrap();
+        let needed = estimated_memory("elasticsearch", &config.services["elasticsearch"])
+            + estimated_memory("postgres", &config.services["postgres"]);
+        assert_eq!(needed, 2304 * MB);
+        assert_eq!(memory_status(needed, 8192 * MB), CheckStatus::Pass);
+        assert_eq!(memory_status(needed, 2560 * MB), CheckStatus::Warn);
+        assert_eq!(memory_status(needed, 2048 * MB), CheckStatus::Fail);
+    }
+}
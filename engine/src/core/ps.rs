@@ -0,0 +1,146 @@
+//! What `zero ps` lists for each of the project's containers
+//!
+//! Besides Docker's summary, every row carries the image digest, published
+//! ports and start time from inspecting the container, and, while it runs, the
+//! result of a health check.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::Engine;
+use crate::health::HealthStatus;
+use crate::orchestrator::inspect::PortDetails;
+
+/// A project container as `zero ps` lists it
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceListing {
+    /// Container name
+    pub name: String,
+    pub service: String,
+    pub image: String,
+    /// Repository digest the image resolved to, or the local image id
+    pub image_digest: Option<String>,
+    /// Docker's state, e.g. `running` or `exited`
+    pub state: String,
+    /// Docker's description of the state, e.g. `Up 2 minutes`
+    pub status: String,
+    pub health: Option<HealthStatus>,
+    pub started_at: Option<String>,
+    /// Seconds since the container started, while it runs
+    pub uptime_seconds: Option<u64>,
+    pub ports: Vec<PortDetails>,
+}
+
+impl ServiceListing {
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+impl Engine {
+    /// The project's running containers, or all of them, with health, uptime and ports
+    pub async fn service_listings(&self, all: bool) -> Result<Vec<ServiceListing>> {
+        let prefix = format!("{}_", self.project_name);
+        let containers = self.list_services().await?;
+
+        let listings = containers.into_iter()
+            .filter(|container| all || container.state.as_deref() == Some("running"))
+            .filter_map(|container| {
+                let name = container.names.iter().flatten().next()?.trim_start_matches('/').to_string();
+                let service = name.strip_prefix(&prefix)?.to_string();
+                Some(async move {
+                    let state = container.state.unwrap_or_default();
+                    let details = self.inspect_service(&service).await.ok();
+                    let health = match state.as_str() {
+                        "running" => self.check_health(&service).await.ok(),
+                        _ => None,
+                    };
+                    let started_at = details.as_ref().and_then(|details| details.started_at.clone());
+                    let uptime_seconds = match state.as_str() {
+                        "running" => started_at.as_deref().and_then(uptime_seconds),
+                        _ => None,
+                    };
+
+                    ServiceListing {
+                        image: container.image.unwrap_or_default(),
+                        image_digest: details.as_ref().and_then(|details| details.image_digest.clone()),
+                        status: container.status.unwrap_or_default(),
+                        ports: details.map(|details| details.ports).unwrap_or_default(),
+                        name,
+                        service,
+                        state,
+                        health,
+                        started_at,
+                        uptime_seconds,
+                    }
+                })
+            });
+
+        let mut listings = futures::future::join_all(listings).await;
+        listings.sort_by(|a, b| a.service.cmp(&b.service));
+        Ok(listings)
+    }
+}
+
+fn uptime_seconds(started_at: &str) -> Option<u64> {
+    let started = chrono::DateTime::parse_from_rfc3339(started_at).ok()?;
+    u64::try_from((chrono::Utc::now() - started.with_timezone(&chrono::Utc)).num_seconds()).ok()
+}
+
+/// Uptime in its two largest units, e.g. `3h 12m`
+pub fn format_uptime(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", seconds),
+        (0, 0, minutes) => format!("{}m", minutes),
+        (0, hours, minutes) => format!("{}h {}m", hours, minutes),
+        (days, hours, _) => format!("{}d {}h", days, hours),
+    }
+}
+
+/// A digest cut to the 12 hex digits Docker shows, e.g. `sha256:0123456789ab`
+pub fn short_digest(digest: &str) -> String {
+    let digest = digest.rsplit('@').next().unwrap_or(digest);
+    match digest.split_once(':') {
+        Some((algorithm, hex)) => format!("{}:{}", algorithm, &hex[..hex.len().min(12)]),
+        None => digest[..digest.len().min(12)].to_string(),
+    }
+}
+
+/// Ports as `docker ps` shows them, e.g. `0.0.0.0:5432->5432/tcp`
+pub fn format_ports(ports: &[PortDetails]) -> String {
+    ports.iter()
+        .map(|port| match port.host_port {
+            Some(host_port) => format!(
+                "{}:{}->{}",
+                port.host_ip.as_deref().filter(|ip| !ip.is_empty()).unwrap_or("0.0.0.0"),
+                host_port,
+                port.container_port,
+            ),
+            None => port.container_port.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(format_uptime(42), "42s");
+        assert_eq!(format_uptime(12 * 60 + 5), "12m");
+        assert_eq!(format_uptime(3 * 3600 + 12 * 60), "3h 12m");
+        assert_eq!(format_uptime(2 * 86_400 + 4 * 3600 + 59), "2d 4h");
+
+        assert_eq!(short_digest("postgres@sha256:0123456789abcdef0123"), "sha256:0123456789ab");
+        assert_eq!(short_digest("sha256:abc"), "sha256:abc");
+
+        let ports = vec![
+            PortDetails { container_port: "5432/tcp".to_string(), host_ip: Some("127.0.0.1".to_string()), host_port: Some(15432) },
+            PortDetails { container_port: "8080/tcp".to_string(), host_ip: None, host_port: None },
+        ];
+        assert_eq!(format_ports(&ports), "127.0.0.1:15432->5432/tcp, 8080/tcp");
+    }
+}
@@ -0,0 +1,60 @@
+//! Public tunnels to services, left running by `zero share`
+//!
+//! Unlike `zero expose`, which holds its tunnel until Ctrl+C, shared tunnels
+//! run in the background and are recorded in the project state, so `zero share`
+//! can list them and `zero share --stop` or `zero down` close them.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use super::state::STATE_DIR;
+use super::Engine;
+use crate::tunnels::{SharedTunnel, Tunnel, TunnelProvider};
+
+impl Engine {
+    /// Open a public tunnel to a service's host port, reusing the one already running
+    pub async fn share(&mut self, service: &str, provider: TunnelProvider) -> Result<SharedTunnel> {
+        if let Some(tunnel) = self.state.shares.get(service).filter(|tunnel| tunnel.is_running()) {
+            return Ok(tunnel.clone());
+        }
+
+        let port = self.get_host_port(service).await?;
+        let log = self.project_dir().join(STATE_DIR).join("tunnels").join(format!("{}.log", service));
+        let tunnel = Tunnel::open_detached(provider, port, &log).await?;
+        self.state.shares.insert(service.to_string(), tunnel.clone());
+        self.save_state();
+        Ok(tunnel)
+    }
+
+    /// Tunnels still running, by service
+    pub fn shares(&self) -> BTreeMap<String, SharedTunnel> {
+        self.state.shares.iter()
+            .filter(|(_, tunnel)| tunnel.is_running())
+            .map(|(service, tunnel)| (service.clone(), tunnel.clone()))
+            .collect()
+    }
+
+    /// Close the tunnel of a service, or every tunnel, returning the services that were shared
+    pub fn unshare(&mut self, service: Option<&str>) -> Result<Vec<String>> {
+        let services: Vec<String> = self.state.shares.keys()
+            .filter(|shared| service.is_none_or(|service| *shared == service))
+            .cloned()
+            .collect();
+        if services.is_empty() {
+            return Ok(services);
+        }
+
+        // Tunnels that fail to stop stay recorded, so a later attempt can close them
+        let mut result = Ok(());
+        for shared in &services {
+            match self.state.shares[shared].stop() {
+                Ok(()) => {
+                    self.state.shares.remove(shared);
+                }
+                Err(e) => result = Err(e),
+            }
+        }
+        self.save_state();
+        result.map(|()| services)
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::TcpListener;
+use tracing::{info, warn};
+
+use super::Engine;
+use crate::orchestrator::default_container_port;
+use crate::services::observability::{self, ScrapeTarget, StackPorts, GRAFANA_PORT, PROMETHEUS_PORT};
+use crate::services::elasticsearch::DASHBOARDS_PORT;
+use crate::services::{web_ui, ElasticsearchService, JaegerService};
+
+/// A browser UI of the running project, e.g. pgAdmin or the MinIO console
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebUi {
+    /// Service or sidecar serving it, as accepted by `zero open`
+    pub name: String,
+    pub title: String,
+    pub url: String,
+    /// Login, for UIs that ask for one
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Engine {
+    /// Start cAdvisor, Prometheus, Grafana and Jaeger for `observability: true`
+    pub(crate) async fn start_observability(&self) -> Result<()> {
+        let mut services: Vec<String> = self.config.services.keys().cloned().collect();
+        services.sort();
+
+        let mut targets = Vec::new();
+        for service in &services {
+            let config = &self.config.services[service];
+            let Some(path) = &config.metrics_path else { continue };
+            match config.container_port.or_else(|| default_container_port(service)) {
+                Some(port) => targets.push(ScrapeTarget { service: service.clone(), port, path: path.clone() }),
+                None => warn!("Set container_port for service '{}' so Prometheus can scrape its metrics", service),
+            }
+        }
+
+        let mut ports = StackPorts {
+            prometheus: self.sidecar_port("prometheus", Some(PROMETHEUS_PORT)).await?,
+            grafana: self.sidecar_port("grafana", Some(GRAFANA_PORT)).await?,
+            jaeger: Vec::new(),
+        };
+        if self.observability_jaeger() {
+            for container_port in [JaegerService::default_port(), JaegerService::grpc_port(), JaegerService::ui_port()] {
+                ports.jaeger.push((container_port, self.sidecar_extra_port("jaeger", container_port).await?));
+            }
+        }
+
+        for sidecar in observability::sidecars(&self.project_name, &services, &targets, &ports) {
+            self.orchestrator.start_sidecar(&sidecar).await?;
+        }
+
+        let host = self.orchestrator.published_host();
+        info!("Prometheus on http://{}:{}, Grafana on http://{}:{}", host, ports.prometheus, host, ports.grafana);
+        if let Some((_, port)) = ports.jaeger.iter().find(|(container_port, _)| *container_port == JaegerService::ui_port()) {
+            info!("Jaeger on http://{}:{}", host, port);
+        }
+        Ok(())
+    }
+
+    /// Whether `observability: true` runs a Jaeger, the project having no tracing service of its own
+    pub(crate) fn observability_jaeger(&self) -> bool {
+        self.config.observability && !self.config.services.keys().any(|service| JaegerService::handles(service))
+    }
+
+    /// Start the admin tool of a database service with `admin_ui: true`
+    ///
+    /// Must run after the database, whose container holds the credentials.
+    pub(crate) async fn start_admin_ui(&self, service: &str) -> Result<()> {
+        let name = web_ui::admin_sidecar_name(service);
+        let env: HashMap<String, String> = self.orchestrator.container_env(service).await?.into_iter().collect();
+        let port = self.sidecar_port(&name, None).await?;
+
+        let Some(sidecar) = web_ui::admin_sidecar(&self.project_name, service, &env, port) else {
+            warn!("No admin UI is available for service '{}'", service);
+            return Ok(());
+        };
+        self.orchestrator.start_sidecar(&sidecar).await?;
+
+        info!("Admin UI for {} on http://{}:{}", service, self.orchestrator.published_host(), port);
+        Ok(())
+    }
+
+    /// Start Kibana, or OpenSearch Dashboards, for a search service with `kibana: true`
+    pub(crate) async fn start_dashboards(&self, service: &str, version: &str) -> Result<()> {
+        let name = ElasticsearchService::dashboards_name(service);
+        let port = self.sidecar_port(name, Some(DASHBOARDS_PORT)).await?;
+        let sidecar = ElasticsearchService::dashboards_sidecar(&self.project_name, service, version, port);
+        self.orchestrator.start_sidecar(&sidecar).await?;
+
+        info!("{} for {} on http://{}:{}", name, service, self.orchestrator.published_host(), port);
+        Ok(())
+    }
+
+    /// Browser UIs of the project that are running, sorted by name
+    pub async fn web_uis(&self) -> Vec<WebUi> {
+        let mut names: Vec<String> = self.config.services.keys().cloned().collect();
+        names.extend(self.config.services.iter()
+            .filter(|(_, config)| config.admin_ui)
+            .map(|(service, _)| web_ui::admin_sidecar_name(service)));
+        names.extend(self.config.services.iter()
+            .filter(|(service, config)| config.kibana && ElasticsearchService::handles(service))
+            .map(|(service, _)| ElasticsearchService::dashboards_name(service).to_string()));
+        if self.config.observability {
+            names.extend(["grafana", "prometheus"].map(String::from));
+        }
+        if self.observability_jaeger() {
+            names.push("jaeger".to_string());
+        }
+        names.sort();
+
+        let mut uis = Vec::new();
+        for name in names {
+            let Some((container_port, title)) = web_ui::web_ui(&name) else { continue };
+            let Ok(port) = self.orchestrator.get_published_port(&name, container_port).await else { continue };
+            let env: HashMap<String, String> = self.orchestrator.container_env(&name).await
+                .map(|env| env.into_iter().collect())
+                .unwrap_or_default();
+            let (username, password) = web_ui::credentials(&name, &env).unzip();
+
+            uis.push(WebUi {
+                title: title.to_string(),
+                url: format!("http://{}:{}", self.orchestrator.published_host(), port),
+                username,
+                password,
+                name,
+            });
+        }
+        uis
+    }
+
+    /// Host port for a sidecar: the one it already has, the preferred one if free, or any free one
+    async fn sidecar_port(&self, name: &str, preferred: Option<u16>) -> Result<u16> {
+        if let Ok(port) = self.orchestrator.get_host_port(name).await {
+            return Ok(port);
+        }
+        if let Some(port) = preferred.filter(|port| TcpListener::bind(("127.0.0.1", *port)).is_ok()) {
+            return Ok(port);
+        }
+        portpicker::pick_unused_port().with_context(|| format!("No free host port for {}", name))
+    }
+
+    /// Host port for one of a sidecar's container ports: the one it is published on, the same number if free, or any free one
+    async fn sidecar_extra_port(&self, name: &str, container_port: u16) -> Result<u16> {
+        if let Ok(port) = self.orchestrator.get_published_port(name, container_port).await {
+            return Ok(port);
+        }
+        if TcpListener::bind(("127.0.0.1", container_port)).is_ok() {
+            return Ok(container_port);
+        }
+        portpicker::pick_unused_port().with_context(|| format!("No free host port for {}", name))
+    }
+}
@@ -0,0 +1,121 @@
+//! Repairs `zero doctor --fix` can make without asking
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::DoctorAction;
+use crate::orchestrator::{docker_client, ContainerOrchestrator};
+
+/// How long a started runtime gets to bring up its API
+const START_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Apply the fix for a check, returning what was done
+///
+/// Returns `None` for actions that need the user, like installing a runtime or editing zero.yml.
+pub async fn apply(action: &DoctorAction) -> Result<Option<String>> {
+    match action {
+        DoctorAction::StartRuntime { runtime } => start_runtime(runtime).await.map(Some),
+        DoctorAction::CreateNetwork { project } => {
+            ContainerOrchestrator::new(project.clone()).await?.create_network().await?;
+            Ok(Some(format!("Created the network of {}", project)))
+        }
+        DoctorAction::StopContainer { container } => {
+            docker_client::connect()?
+                .stop_container(container, None)
+                .await
+                .with_context(|| format!("Failed to stop {}", container))?;
+            Ok(Some(format!("Stopped {}", container)))
+        }
+        DoctorAction::IgnoreFile { gitignore, entry } => {
+            ignore_file(Path::new(gitignore), entry)?;
+            Ok(Some(format!("Added {} to {}", entry, gitignore)))
+        }
+        DoctorAction::InstallRuntime { .. } | DoctorAction::RunCommand { .. } | DoctorAction::EditConfig { .. } => Ok(None),
+    }
+}
+
+/// Start a container runtime and wait until its API answers
+async fn start_runtime(runtime: &str) -> Result<String> {
+    let mut errors = Vec::new();
+
+    for command in start_commands(runtime, std::env::consts::OS) {
+        let status = tokio::process::Command::new(command[0])
+            .args(&command[1..])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await;
+        match status {
+            Ok(status) if status.success() => {
+                wait_for_api().await?;
+                return Ok(format!("Started {} with `{}`", runtime, command.join(" ")));
+            }
+            Ok(status) => errors.push(format!("`{}` exited with {}", command.join(" "), status)),
+            Err(e) => errors.push(format!("`{}`: {}", command.join(" "), e)),
+        }
+    }
+
+    anyhow::bail!("Could not start {}: {}", runtime, errors.join("; "))
+}
+
+/// Commands that start a runtime on an OS, tried in order
+fn start_commands(runtime: &str, os: &str) -> Vec<Vec<&'static str>> {
+    match (runtime, os) {
+        ("colima", _) => vec![vec!["colima", "start"]],
+        ("docker", "macos") => vec![vec!["open", "-a", "Docker"], vec!["colima", "start"]],
+        ("docker", "windows") => vec![vec!["cmd", "/C", "start", "", r"C:\Program Files\Docker\Docker\Docker Desktop.exe"]],
+        ("docker", _) => vec![vec!["systemctl", "start", "docker"], vec!["systemctl", "--user", "start", "docker"]],
+        ("podman", "linux") => vec![vec!["systemctl", "--user", "start", "podman.socket"]],
+        ("podman", _) => vec![vec!["podman", "machine", "start"]],
+        _ => vec![],
+    }
+}
+
+async fn wait_for_api() -> Result<()> {
+    let start = Instant::now();
+    loop {
+        if let Ok(docker) = docker_client::connect() {
+            if docker.ping().await.is_ok() {
+                return Ok(());
+            }
+        }
+        if start.elapsed() > START_TIMEOUT {
+            anyhow::bail!("The runtime started but its API did not answer within {}s", START_TIMEOUT.as_secs());
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Append an entry to a .gitignore, creating it if needed
+fn ignore_file(gitignore: &Path, entry: &str) -> Result<()> {
+    let mut content = std::fs::read_to_string(gitignore).unwrap_or_default();
+    if content.lines().any(|line| line.trim() == entry) {
+        return Ok(());
+    }
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(entry);
+    content.push('\n');
+    std::fs::write(gitignore, content).with_context(|| format!("Failed to write {}", gitignore.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_commands_and_ignore_file() {
+        assert_eq!(start_commands("docker", "macos")[1], vec!["colima", "start"]);
+        assert_eq!(start_commands("podman", "linux")[0], vec!["systemctl", "--user", "start", "podman.socket"]);
+        assert!(start_commands("kubectl", "linux").is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let gitignore = dir.path().join(".gitignore");
+        std::fs::write(&gitignore, "target").unwrap();
+        ignore_file(&gitignore, ".zeroconfig.env").unwrap();
+        ignore_file(&gitignore, ".zeroconfig.env").unwrap();
+        assert_eq!(std::fs::read_to_string(&gitignore).unwrap(), "target\n.zeroconfig.env\n");
+    }
+}
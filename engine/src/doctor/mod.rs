@@ -0,0 +1,450 @@
+use bollard::container::ListContainersOptions;
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+use std::net::TcpListener;
+use std::path::Path;
+
+use crate::config::{diagnostics, PortConfig, PortValue, Severity, ZeroConfig};
+use crate::orchestrator::{PROJECT_LABEL, SERVICE_LABEL};
+use crate::runtime::{ContainerRuntime, ContainerRuntimeManager, RuntimeManager};
+use crate::secrets::CREDENTIALS_FILE;
+
+pub mod fix;
+
+/// Free disk space below which a warning is reported
+const DISK_WARN_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+/// Free disk space below which images and volumes are likely to fail
+const DISK_FAIL_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Outcome of a single doctor check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckCategory {
+    Runtime,
+    Daemon,
+    Disk,
+    Config,
+    Languages,
+    Ports,
+    Images,
+    Resources,
+}
+
+/// What the user (or the desktop app) can do to fix a failing check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DoctorAction {
+    /// Install a runtime, e.g. with the `install_runtime` command
+    InstallRuntime { runtime: String, version: String },
+    /// Start an installed container runtime
+    StartRuntime { runtime: String },
+    /// Run a shell command
+    RunCommand { command: String },
+    /// Open zero.yml to fix reported problems
+    EditConfig { path: Option<String> },
+    /// Create the project's Docker network
+    CreateNetwork { project: String },
+    /// Stop a container of another zeroconfig project holding a port
+    StopContainer { container: String },
+    /// Add an entry to a .gitignore
+    IgnoreFile { gitignore: String, entry: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    /// Stable identifier, e.g. `runtime` or `language.node`
+    pub id: String,
+    pub category: CheckCategory,
+    pub title: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub action: Option<DoctorAction>,
+}
+
+impl DoctorCheck {
+    pub(crate) fn new(id: impl Into<String>, category: CheckCategory, title: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            category,
+            title: title.into(),
+            status,
+            message: message.into(),
+            action: None,
+        }
+    }
+
+    pub(crate) fn with_action(mut self, action: DoctorAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+}
+
+/// Result of `zero doctor`, as `--output json` prints it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// Worst status among the checks
+    pub status: CheckStatus,
+    pub checks: Vec<DoctorCheck>,
+    /// What `--fix` repaired; the checks are from after the repairs
+    pub fixed: Vec<String>,
+    /// Fixes that were tried and failed
+    pub failed: Vec<String>,
+}
+
+/// The worst status among the checks, or `Pass` when there are none
+pub fn overall_status(checks: &[DoctorCheck]) -> CheckStatus {
+    checks.iter().map(|check| check.status).max().unwrap_or(CheckStatus::Pass)
+}
+
+/// Run every onboarding check for a project directory
+///
+/// Config, language and port checks only run when a zero.yml is found in
+/// `project_dir` or its parents.
+pub async fn run(project_dir: &Path) -> Vec<DoctorCheck> {
+    let daemon = check_daemon().await;
+    let docker = match daemon.status {
+        CheckStatus::Pass => crate::orchestrator::docker_client::connect().ok(),
+        _ => None,
+    };
+    let mut checks = vec![
+        check_container_runtime().await,
+        daemon,
+        check_disk_space(project_dir),
+    ];
+
+    let config_path = ZeroConfig::find_file_in(project_dir);
+    let (config_check, config) = check_config(config_path.as_deref());
+    checks.push(config_check);
+
+    if let Some(config) = config {
+        let project = config.metadata.name.clone().unwrap_or_else(|| "zeroconfig-project".to_string());
+        checks.extend(check_languages(&config).await);
+        checks.extend(check_ports(&config, &project, docker.as_ref()).await);
+        if let Some(docker) = &docker {
+            checks.push(check_network(docker, &project).await);
+        }
+    }
+
+    let root = config_path.as_deref().and_then(Path::parent).unwrap_or(project_dir);
+    checks.extend(check_credentials_ignored(root));
+
+    checks
+}
+
+async fn check_container_runtime() -> DoctorCheck {
+    let manager = ContainerRuntimeManager::new();
+    let mut installed = Vec::new();
+
+    for runtime in [ContainerRuntime::Docker, ContainerRuntime::Podman] {
+        let status = manager.get_runtime_status(runtime).await;
+        if status.is_ready() {
+            let version = status.version.as_deref().unwrap_or("version unknown");
+            return DoctorCheck::new("runtime", CheckCategory::Runtime, "Container runtime", CheckStatus::Pass, format!("{} ({})", runtime.name(), version));
+        }
+        if status.installed {
+            installed.push(runtime);
+        }
+    }
+
+    match installed.first() {
+        Some(runtime) => DoctorCheck::new("runtime", CheckCategory::Runtime, "Container runtime", CheckStatus::Warn, format!("{} is installed but not running", runtime.name()))
+            .with_action(DoctorAction::StartRuntime { runtime: runtime.command().to_string() }),
+        None => DoctorCheck::new("runtime", CheckCategory::Runtime, "Container runtime", CheckStatus::Fail, "No container runtime found (Docker or Podman is required)")
+            .with_action(DoctorAction::InstallRuntime { runtime: "docker".to_string(), version: "latest".to_string() }),
+    }
+}
+
+async fn check_daemon() -> DoctorCheck {
+    let ping = match crate::orchestrator::docker_client::connect() {
+        Ok(docker) => docker.ping().await.map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+
+    match ping {
+        Ok(_) => DoctorCheck::new("daemon", CheckCategory::Daemon, "Docker API", CheckStatus::Pass, "Docker API is reachable"),
+        Err(e) => DoctorCheck::new("daemon", CheckCategory::Daemon, "Docker API", CheckStatus::Fail, format!("Cannot reach the Docker API: {}", e))
+            .with_action(DoctorAction::StartRuntime { runtime: "docker".to_string() }),
+    }
+}
+
+pub(crate) fn check_disk_space(project_dir: &Path) -> DoctorCheck {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let project_dir = project_dir.canonicalize().unwrap_or_else(|_| project_dir.to_path_buf());
+
+    // The disk with the longest mount point containing the project holds it
+    let Some(disk) = disks.list().iter()
+        .filter(|disk| project_dir.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    else {
+        return DoctorCheck::new("disk", CheckCategory::Disk, "Disk space", CheckStatus::Warn, "Could not determine free disk space");
+    };
+
+    let available = disk.available_space();
+    let status = disk_status(available);
+    let message = format!("{:.1} GB free on {}", available as f64 / 1e9, disk.mount_point().display());
+    let check = DoctorCheck::new("disk", CheckCategory::Disk, "Disk space", status, message);
+
+    if status == CheckStatus::Pass {
+        check
+    } else {
+        check.with_action(DoctorAction::RunCommand { command: "docker system prune".to_string() })
+    }
+}
+
+fn disk_status(available: u64) -> CheckStatus {
+    if available < DISK_FAIL_BYTES {
+        CheckStatus::Fail
+    } else if available < DISK_WARN_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    }
+}
+
+fn check_config(path: Option<&Path>) -> (DoctorCheck, Option<ZeroConfig>) {
+    let Some(path) = path else {
+        let check = DoctorCheck::new("config", CheckCategory::Config, "zero.yml", CheckStatus::Warn, "No zero.yml found in this folder or its parents")
+            .with_action(DoctorAction::RunCommand { command: "zero init".to_string() });
+        return (check, None);
+    };
+
+    let edit = DoctorAction::EditConfig { path: Some(path.display().to_string()) };
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            let check = DoctorCheck::new("config", CheckCategory::Config, "zero.yml", CheckStatus::Fail, format!("Failed to read {}: {}", path.display(), e));
+            return (check, None);
+        }
+    };
+
+    let problems = diagnostics::check(&content);
+    let status = if problems.iter().any(|problem| problem.severity == Severity::Error) {
+        CheckStatus::Fail
+    } else if !problems.is_empty() {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+
+    let check = match problems.first() {
+        None => DoctorCheck::new("config", CheckCategory::Config, "zero.yml", status, format!("{} is valid", path.display())),
+        Some(first) => {
+            let more = match problems.len() {
+                1 => String::new(),
+                count => format!(" (and {} more)", count - 1),
+            };
+            DoctorCheck::new("config", CheckCategory::Config, "zero.yml", status, format!("{}{}", first, more)).with_action(edit)
+        }
+    };
+
+    let config = (status != CheckStatus::Fail).then(|| ZeroConfig::from_str(&content).ok()).flatten();
+    (check, config)
+}
+
+async fn check_languages(config: &ZeroConfig) -> Vec<DoctorCheck> {
+    let mut manager = RuntimeManager::new();
+    let mut runtimes = config.get_runtimes();
+    runtimes.sort();
+
+    let mut checks = Vec::new();
+    for (name, version) in runtimes {
+        let id = format!("language.{}", name);
+        let title = format!("{} {}", name, version);
+        let install = DoctorAction::InstallRuntime { runtime: name.clone(), version: version.clone() };
+
+        let check = match manager.check_runtime(&name, &version).await {
+            Ok(info) if info.is_compatible => {
+                let installed = info.installed_version.as_deref().unwrap_or("unknown");
+                DoctorCheck::new(id, CheckCategory::Languages, title, CheckStatus::Pass, format!("v{} installed", installed))
+            }
+            Ok(info) => {
+                let message = match info.installed_version {
+                    Some(installed) => format!("v{} installed, {} required", installed, version),
+                    None => "Not installed".to_string(),
+                };
+                DoctorCheck::new(id, CheckCategory::Languages, title, CheckStatus::Fail, message).with_action(install)
+            }
+            Err(e) => DoctorCheck::new(id, CheckCategory::Languages, title, CheckStatus::Fail, format!("Check failed: {}", e)).with_action(install),
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+async fn check_ports(config: &ZeroConfig, project: &str, docker: Option<&Docker>) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    for (service, port) in fixed_ports(config) {
+        let id = format!("port.{}", port);
+        let title = format!("Port {} ({})", port, service);
+        if TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            checks.push(DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Pass, "Free"));
+            continue;
+        }
+
+        let check = match port_owner(docker, port).await {
+            Some(owner) if owner.project == project && owner.service == service => {
+                DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Pass, "Already running")
+            }
+            Some(owner) => DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Fail, format!("Held by {} of project {}", owner.container, owner.project))
+                .with_action(DoctorAction::StopContainer { container: owner.container }),
+            None => DoctorCheck::new(id, CheckCategory::Ports, title, CheckStatus::Warn, "In use by another process; stop whatever holds it or use `port: auto`")
+                .with_action(DoctorAction::EditConfig { path: None }),
+        };
+        checks.push(check);
+    }
+
+    checks
+}
+
+/// Running zeroconfig container publishing a host port
+struct PortOwner {
+    container: String,
+    project: String,
+    service: String,
+}
+
+async fn port_owner(docker: Option<&Docker>, port: u16) -> Option<PortOwner> {
+    let options = ListContainersOptions::<String> {
+        filters: [("label".to_string(), vec![PROJECT_LABEL.to_string()])].into(),
+        ..Default::default()
+    };
+    let containers = docker?.list_containers(Some(options)).await.ok()?;
+
+    containers.into_iter()
+        .find(|container| container.ports.iter().flatten().any(|published| published.public_port == Some(port)))
+        .map(|container| {
+            let labels = container.labels.unwrap_or_default();
+            PortOwner {
+                container: container.names.into_iter().flatten().next()
+                    .map(|name| name.trim_start_matches('/').to_string())
+                    .unwrap_or_default(),
+                project: labels.get(PROJECT_LABEL).cloned().unwrap_or_default(),
+                service: labels.get(SERVICE_LABEL).cloned().unwrap_or_default(),
+            }
+        })
+}
+
+async fn check_network(docker: &Docker, project: &str) -> DoctorCheck {
+    let network = format!("zeroconfig_{}", project);
+    let title = format!("Network {}", network);
+    match docker.inspect_network::<String>(&network, None).await {
+        Ok(_) => DoctorCheck::new("network", CheckCategory::Daemon, title, CheckStatus::Pass, "Exists"),
+        Err(_) => DoctorCheck::new("network", CheckCategory::Daemon, title, CheckStatus::Warn, "Missing; services cannot reach each other by name until it is created")
+            .with_action(DoctorAction::CreateNetwork { project: project.to_string() }),
+    }
+}
+
+/// Generated credentials must not end up in version control
+fn check_credentials_ignored(root: &Path) -> Option<DoctorCheck> {
+    if !root.join(CREDENTIALS_FILE).exists() {
+        return None;
+    }
+
+    let gitignore = root.join(".gitignore");
+    let ignored = std::fs::read_to_string(&gitignore).unwrap_or_default().lines()
+        .map(|line| line.trim().trim_start_matches('/'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .any(|pattern| globset::Glob::new(pattern).is_ok_and(|glob| glob.compile_matcher().is_match(CREDENTIALS_FILE)));
+
+    let title = format!("{} ignored by git", CREDENTIALS_FILE);
+    Some(if ignored {
+        DoctorCheck::new("gitignore", CheckCategory::Config, title, CheckStatus::Pass, "Listed in .gitignore")
+    } else {
+        DoctorCheck::new("gitignore", CheckCategory::Config, title, CheckStatus::Warn, "Holds generated credentials but is not in .gitignore")
+            .with_action(DoctorAction::IgnoreFile {
+                gitignore: gitignore.display().to_string(),
+                entry: CREDENTIALS_FILE.to_string(),
+            })
+    })
+}
+
+/// Host ports pinned in zero.yml, as (service, port)
+fn fixed_ports(config: &ZeroConfig) -> Vec<(String, u16)> {
+    let mut ports: Vec<(String, u16)> = config.services.iter()
+        .filter_map(|(service, service_config)| match service_config.port {
+            PortValue::Fixed(port) => Some((service.clone(), port)),
+            _ => None,
+        })
+        .collect();
+
+    if let PortConfig::Manual(manual) = &config.ports {
+        ports.extend(manual.iter().map(|(service, port)| (service.clone(), *port)));
+    }
+
+    ports.sort_by_key(|(_, port)| *port);
+    ports.dedup_by_key(|(_, port)| *port);
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_status_thresholds() {
+        assert_eq!(disk_status(DISK_FAIL_BYTES - 1), CheckStatus::Fail);
+        assert_eq!(disk_status(DISK_WARN_BYTES - 1), CheckStatus::Warn);
+        assert_eq!(disk_status(DISK_WARN_BYTES), CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_fixed_ports_and_overall_status() {
+        let config = ZeroConfig::from_str(r#"
+services:
+  postgres:
+    version: "16"
+    port: 5432
+  redis:
+    version: "7"
+    port: auto
+ports:
+  postgres: 5432
+  mailpit: 8025
+"#).unwrap();
+        assert_eq!(fixed_ports(&config), vec![("postgres".to_string(), 5432), ("mailpit".to_string(), 8025)]);
+
+        let checks = vec![
+            DoctorCheck::new("a", CheckCategory::Disk, "A", CheckStatus::Pass, ""),
+            DoctorCheck::new("b", CheckCategory::Ports, "B", CheckStatus::Warn, ""),
+        ];
+        assert_eq!(overall_status(&checks), CheckStatus::Warn);
+        assert_eq!(overall_status(&[]), CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let check = DoctorCheck::new("runtime", CheckCategory::Runtime, "Container runtime", CheckStatus::Fail, "Docker is not running")
+            .with_action(DoctorAction::StartRuntime { runtime: "docker".to_string() });
+        let report = DoctorReport {
+            status: CheckStatus::Fail,
+            checks: vec![check],
+            fixed: vec![],
+            failed: vec!["network".to_string()],
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "status": "fail",
+            "checks": [{
+                "id": "runtime",
+                "category": "runtime",
+                "title": "Container runtime",
+                "status": "fail",
+                "message": "Docker is not running",
+                "action": { "type": "start_runtime", "runtime": "docker" },
+            }],
+            "fixed": [],
+            "failed": ["network"],
+        }));
+    }
+}
@@ -1,94 +1,286 @@
+//! Multi-stage Dockerfile for the project's primary language
+//!
+//! A builder stage installs dependencies, with BuildKit cache mounts for the
+//! package manager, and builds the app; a slim runtime stage copies the result
+//! and runs it as a non-root user, with a health check that the app's port
+//! accepts connections.
+
 use anyhow::Result;
+use std::fmt::Write as _;
 use std::fs;
 use std::path::Path;
 
 use crate::config::ZeroConfig;
 
+/// Languages with a tailored build, in the order the primary one is picked from `languages:`
+pub const LANGUAGES: &[&str] = &["node", "python", "go", "rust", "java"];
+
+/// What the health check and `EXPOSE` assume the app listens on, unless `env: PORT` says otherwise
+fn default_port(language: &str) -> u16 {
+    match language {
+        "node" => 3000,
+        "python" => 8000,
+        _ => 8080,
+    }
+}
+
 pub fn generate(config: &ZeroConfig, output_dir: &Path) -> Result<()> {
-    let mut dockerfile = String::new();
+    generate_for(config, output_dir, None)
+}
 
-    // Determine base image from primary language
-    let (base_image, package_manager_install) = if config.languages.contains_key("node") {
-        let version = config.languages.get("node").unwrap();
-        (format!("FROM node:{}-alpine", version), "RUN npm install")
-    } else if config.languages.contains_key("python") {
-        let version = config.languages.get("python").unwrap();
-        (format!("FROM python:{}-slim", version), "RUN pip install -r requirements.txt")
-    } else if config.languages.contains_key("go") {
-        let version = config.languages.get("go").unwrap();
-        (format!("FROM golang:{}-alpine", version), "RUN go mod download")
-    } else if config.languages.contains_key("rust") {
-        ("FROM rust:latest".to_string(), "RUN cargo build --release")
-    } else {
-        ("FROM ubuntu:22.04".to_string(), "# No package manager install")
+/// Write the Dockerfile, for `language` instead of the primary one of zero.yml when given
+pub fn generate_for(config: &ZeroConfig, output_dir: &Path, language: Option<&str>) -> Result<()> {
+    let dockerfile = render(config, language, output_dir)?;
+
+    let output_path = output_dir.join("Dockerfile");
+    fs::write(&output_path, dockerfile)?;
+
+    println!("✅ Generated: {}", output_path.display());
+    Ok(())
+}
+
+/// Dockerfile for a project in `project_dir`, whose manifests name the built binary where needed
+pub fn render(config: &ZeroConfig, language: Option<&str>, project_dir: &Path) -> Result<String> {
+    let language = match language {
+        Some(language) if LANGUAGES.contains(&language) => Some(language),
+        Some(language) => anyhow::bail!("No Dockerfile template for '{}'; choose one of {}", language, LANGUAGES.join(", ")),
+        None => LANGUAGES.iter().copied().find(|language| config.languages.contains_key(*language)),
     };
+    let version = language.and_then(|language| config.languages.get(language)).map(String::as_str);
+    let port = config.env.get("PORT")
+        .and_then(|port| port.parse().ok())
+        .unwrap_or_else(|| default_port(language.unwrap_or_default()));
 
-    dockerfile.push_str(&format!("# Generated by ZeroConfig\n"));
+    let mut dockerfile = String::new();
+    dockerfile.push_str("# syntax=docker/dockerfile:1\n");
+    dockerfile.push_str("# Generated by ZeroConfig\n");
     dockerfile.push_str(&format!("# Project: {}\n\n",
         config.metadata.name.as_deref().unwrap_or("zeroconfig-project")));
 
-    dockerfile.push_str(&base_image);
-    dockerfile.push_str("\n\n");
-
-    // Set working directory
-    dockerfile.push_str("WORKDIR /app\n\n");
-
-    // Copy dependency files first (for layer caching)
-    if config.languages.contains_key("node") {
-        dockerfile.push_str("# Copy package files\n");
-        dockerfile.push_str("COPY package*.json ./\n\n");
-    } else if config.languages.contains_key("python") {
-        dockerfile.push_str("# Copy requirements\n");
-        dockerfile.push_str("COPY requirements.txt ./\n\n");
-    } else if config.languages.contains_key("go") {
-        dockerfile.push_str("# Copy go mod files\n");
-        dockerfile.push_str("COPY go.mod go.sum ./\n\n");
-    } else if config.languages.contains_key("rust") {
-        dockerfile.push_str("# Copy Cargo files\n");
-        dockerfile.push_str("COPY Cargo.toml Cargo.lock ./\n\n");
-    }
+    let stages = match language {
+        Some("node") => node(version.unwrap_or("20")),
+        Some("python") => python(version.unwrap_or("3.12")),
+        Some("go") => go(version.unwrap_or("1.22")),
+        Some("rust") => rust(version.unwrap_or("stable"), &rust_binary(config, project_dir)),
+        Some("java") => java(version.unwrap_or("21")),
+        _ => generic(),
+    };
+    dockerfile.push_str(&stages.build);
+    dockerfile.push('\n');
+    dockerfile.push_str(&stages.runtime);
 
-    // Install dependencies
-    dockerfile.push_str("# Install dependencies\n");
-    dockerfile.push_str(&package_manager_install);
-    dockerfile.push_str("\n\n");
-
-    // Copy application code
-    dockerfile.push_str("# Copy application code\n");
-    dockerfile.push_str("COPY . .\n\n");
-
-    // Add environment variables
-    if !config.env.is_empty() {
-        dockerfile.push_str("# Environment variables\n");
-        for (key, value) in &config.env {
-            if !value.contains("auto-generate") {
-                dockerfile.push_str(&format!("ENV {}={}\n", key, value));
-            }
+    // Values of `env:` the app reads at runtime; generated secrets stay out of the image
+    let mut env: Vec<(&String, &String)> = config.env.iter()
+        .filter(|(_, value)| !value.contains("auto-generate"))
+        .collect();
+    env.sort();
+    if !env.is_empty() {
+        dockerfile.push_str("\n# Environment variables\n");
+        for (key, value) in env {
+            let _ = writeln!(dockerfile, "ENV {}={}", key, value);
         }
-        dockerfile.push_str("\n");
     }
 
-    // Expose ports
-    dockerfile.push_str("# Expose ports\n");
-    dockerfile.push_str("EXPOSE 3000\n\n");
-
-    // Add startup command
-    if let Some(startup) = config.startup.first() {
-        dockerfile.push_str("# Startup command\n");
-        dockerfile.push_str(&format!("CMD [\"{}\"]\n", startup));
-    } else if config.languages.contains_key("node") {
-        dockerfile.push_str("CMD [\"npm\", \"start\"]\n");
-    } else if config.languages.contains_key("python") {
-        dockerfile.push_str("CMD [\"python\", \"main.py\"]\n");
-    } else if config.languages.contains_key("go") {
-        dockerfile.push_str("CMD [\"./app\"]\n");
-    } else if config.languages.contains_key("rust") {
-        dockerfile.push_str("CMD [\"./target/release/app\"]\n");
+    let _ = write!(
+        dockerfile,
+        "\nEXPOSE {port}\nHEALTHCHECK --interval=30s --timeout=5s --start-period=10s --retries=3 \\\n  CMD {check}\n\nCMD {command}\n",
+        port = port,
+        check = stages.health_check.replace("{port}", &port.to_string()),
+        command = stages.command,
+    );
+    Ok(dockerfile)
+}
+
+/// The stages of a language's Dockerfile, the runtime stage ending before `ENV` and `EXPOSE`
+struct Stages {
+    build: String,
+    runtime: String,
+    /// Command exiting with 0 once the app accepts connections on `{port}`
+    health_check: &'static str,
+    /// Exec form of the app's command
+    command: String,
+}
+
+fn node(version: &str) -> Stages {
+    Stages {
+        build: format!(
+            r#"FROM node:{version}-alpine AS build
+WORKDIR /app
+COPY package*.json ./
+RUN --mount=type=cache,target=/root/.npm if [ -f package-lock.json ]; then npm ci; else npm install; fi
+COPY . .
+RUN npm run build --if-present && npm prune --omit=dev
+"#
+        ),
+        runtime: format!(
+            r#"FROM node:{version}-alpine AS runtime
+ENV NODE_ENV=production
+WORKDIR /app
+COPY --from=build --chown=node:node /app ./
+USER node
+"#
+        ),
+        health_check: r#"node -e "require('net').connect({port}, 'localhost').on('connect', () => process.exit(0)).on('error', () => process.exit(1))""#,
+        command: r#"["npm", "start"]"#.to_string(),
     }
+}
 
-    let output_path = output_dir.join("Dockerfile");
-    fs::write(&output_path, dockerfile)?;
+fn python(version: &str) -> Stages {
+    Stages {
+        build: format!(
+            r#"FROM python:{version}-slim AS build
+WORKDIR /app
+RUN python -m venv /opt/venv
+ENV PATH=/opt/venv/bin:$PATH
+COPY requirements.txt ./
+RUN --mount=type=cache,target=/root/.cache/pip pip install -r requirements.txt
+"#
+        ),
+        runtime: format!(
+            r#"FROM python:{version}-slim AS runtime
+ENV PATH=/opt/venv/bin:$PATH PYTHONUNBUFFERED=1
+WORKDIR /app
+RUN useradd --create-home --uid 10001 app
+COPY --from=build /opt/venv /opt/venv
+COPY --chown=app:app . .
+USER app
+"#
+        ),
+        health_check: r#"python -c "import socket; socket.create_connection(('localhost', {port}), 2)""#,
+        command: r#"["python", "main.py"]"#.to_string(),
+    }
+}
 
-    println!("✅ Generated: {}", output_path.display());
-    Ok(())
+fn go(version: &str) -> Stages {
+    Stages {
+        build: format!(
+            r#"FROM golang:{version}-alpine AS build
+WORKDIR /src
+COPY go.mod go.sum* ./
+RUN --mount=type=cache,target=/go/pkg/mod go mod download
+COPY . .
+RUN --mount=type=cache,target=/go/pkg/mod --mount=type=cache,target=/root/.cache/go-build \
+    CGO_ENABLED=0 go build -o /out/app .
+"#
+        ),
+        runtime: r#"FROM alpine:3.20 AS runtime
+RUN adduser -D -u 10001 app
+COPY --from=build /out/app /usr/local/bin/app
+USER app
+"#
+        .to_string(),
+        health_check: "nc -z localhost {port}",
+        command: r#"["app"]"#.to_string(),
+    }
+}
+
+fn rust(version: &str, binary: &str) -> Stages {
+    // `stable` and friends are channels, not image tags
+    let image = match version.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("rust:{}-slim", version),
+        _ => "rust:slim".to_string(),
+    };
+    Stages {
+        build: format!(
+            r#"FROM {image} AS build
+WORKDIR /src
+COPY . .
+RUN --mount=type=cache,target=/usr/local/cargo/registry --mount=type=cache,target=/src/target \
+    cargo build --release && cp target/release/{binary} /app
+"#
+        ),
+        runtime: r#"FROM debian:bookworm-slim AS runtime
+RUN useradd --create-home --uid 10001 app
+COPY --from=build /app /usr/local/bin/app
+USER app
+"#
+        .to_string(),
+        health_check: "bash -c 'exec 3<>/dev/tcp/localhost/{port}'",
+        command: r#"["app"]"#.to_string(),
+    }
+}
+
+fn java(version: &str) -> Stages {
+    Stages {
+        build: format!(
+            r#"FROM maven:3.9-eclipse-temurin-{version} AS build
+WORKDIR /src
+COPY pom.xml ./
+RUN --mount=type=cache,target=/root/.m2 mvn -q dependency:go-offline
+COPY src ./src
+RUN --mount=type=cache,target=/root/.m2 mvn -q package -DskipTests && cp target/*.jar /app.jar
+"#
+        ),
+        runtime: format!(
+            r#"FROM eclipse-temurin:{version}-jre AS runtime
+RUN useradd --create-home --uid 10001 app
+COPY --from=build /app.jar /app/app.jar
+USER app
+"#
+        ),
+        health_check: "bash -c 'exec 3<>/dev/tcp/localhost/{port}'",
+        command: r#"["java", "-jar", "/app/app.jar"]"#.to_string(),
+    }
+}
+
+/// No known language: a single slim stage to fill in
+fn generic() -> Stages {
+    Stages {
+        build: "# No language in zero.yml; add build steps here, or pass --language\n".to_string(),
+        runtime: r#"FROM debian:bookworm-slim AS runtime
+RUN useradd --create-home --uid 10001 app
+WORKDIR /app
+COPY --chown=app:app . .
+USER app
+"#
+        .to_string(),
+        health_check: "bash -c 'exec 3<>/dev/tcp/localhost/{port}'",
+        command: r#"["./start.sh"]"#.to_string(),
+    }
+}
+
+/// Binary `cargo build` produces: the package name from Cargo.toml, else the project's
+fn rust_binary(config: &ZeroConfig, project_dir: &Path) -> String {
+    fs::read_to_string(project_dir.join("Cargo.toml")).ok()
+        .and_then(|manifest| toml::from_str::<toml::Value>(&manifest).ok())
+        .and_then(|manifest| manifest.get("package")?.get("name")?.as_str().map(str::to_string))
+        .or_else(|| config.metadata.name.clone())
+        .unwrap_or_else(|| "app".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Templates of `zero init` and the Dockerfile each produces, checked in under snapshots/
+    const SNAPSHOTS: &[(&str, &str, &str)] = &[
+        ("default", include_str!("../../templates/default.yml"), include_str!("snapshots/default.Dockerfile")),
+        ("node", include_str!("../../templates/node.yml"), include_str!("snapshots/node.Dockerfile")),
+        ("python", include_str!("../../templates/python.yml"), include_str!("snapshots/python.Dockerfile")),
+        ("go", include_str!("../../templates/go.yml"), include_str!("snapshots/go.Dockerfile")),
+        ("rust", include_str!("../../templates/rust.yml"), include_str!("snapshots/rust.Dockerfile")),
+        ("fullstack", include_str!("../../templates/fullstack.yml"), include_str!("snapshots/fullstack.Dockerfile")),
+        ("database", include_str!("../../templates/database.yml"), include_str!("snapshots/database.Dockerfile")),
+        ("redis", include_str!("../../templates/redis.yml"), include_str!("snapshots/redis.Dockerfile")),
+        ("microservices", include_str!("../../templates/microservices.yml"), include_str!("snapshots/microservices.Dockerfile")),
+    ];
+
+    #[test]
+    fn test_template_snapshots() {
+        let no_manifests = Path::new("/nonexistent");
+        for (template, config, expected) in SNAPSHOTS {
+            let config = ZeroConfig::from_str(config).unwrap();
+            let dockerfile = render(&config, None, no_manifests).unwrap();
+            assert_eq!(&dockerfile, expected, "Dockerfile of the {} template changed", template);
+        }
+    }
+
+    #[test]
+    fn test_language_override() {
+        let config = ZeroConfig::from_str(include_str!("../../templates/node.yml")).unwrap();
+        let dockerfile = render(&config, Some("java"), Path::new("/nonexistent")).unwrap();
+        assert!(dockerfile.contains("FROM maven:3.9-eclipse-temurin-21 AS build\n"));
+        assert!(dockerfile.contains("\nUSER app\n"));
+        assert!(render(&config, Some("cobol"), Path::new("/nonexistent")).is_err());
+    }
 }
@@ -54,7 +54,7 @@ pub fn generate(config: &ZeroConfig, output_dir: &Path) -> Result<()> {
             workflow.push_str("          --health-retries 5\n");
         }
 
-        workflow.push_str("\n");
+        workflow.push('\n');
     }
 
     workflow.push_str("    steps:\n");
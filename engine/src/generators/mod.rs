@@ -8,6 +8,8 @@ pub mod dockerfile;
 pub mod compose;
 pub mod envfile;
 pub mod github_actions;
+pub mod nix;
+pub mod terraform;
 
 /// Generate all configuration files
 pub fn generate_all(config: &ZeroConfig, output_dir: &Path) -> Result<()> {
@@ -15,6 +17,9 @@ pub fn generate_all(config: &ZeroConfig, output_dir: &Path) -> Result<()> {
     compose::generate(config, output_dir)?;
     envfile::generate(config, output_dir)?;
     github_actions::generate(config, output_dir)?;
+    if terraform::has_cloud_services(config) {
+        terraform::generate(config, output_dir)?;
+    }
     Ok(())
 }
 
@@ -0,0 +1,138 @@
+//! flake.nix with a developer shell of the project's language toolchains
+//!
+//! `nix develop` enters a shell with the runtimes from `languages:` and the
+//! Docker CLI; `nix run .#up` and `nix run .#down` start and stop the
+//! services through `zero`, which must be on the PATH.
+
+use anyhow::Result;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::config::ZeroConfig;
+
+pub fn generate(config: &ZeroConfig, output_dir: &Path) -> Result<()> {
+    let output_path = output_dir.join("flake.nix");
+    fs::write(&output_path, render(config))?;
+
+    println!("✅ Generated: {}", output_path.display());
+    Ok(())
+}
+
+pub fn render(config: &ZeroConfig) -> String {
+    let project = config.metadata.name.as_deref().unwrap_or("zeroconfig-project");
+
+    let mut languages: Vec<(&String, &String)> = config.languages.iter().collect();
+    languages.sort();
+    let mut packages = Vec::new();
+    for (language, version) in languages {
+        for package in toolchain_packages(language, version) {
+            packages.push(format!("{}  # {} {}", package, language, version));
+        }
+    }
+    packages.push("pkgs.docker-client".to_string());
+
+    let mut flake = String::new();
+    let _ = writeln!(flake, "# Generated by ZeroConfig\n# Project: {}", project);
+    let _ = write!(
+        flake,
+        r#"{{
+  description = "{project} development environment";
+
+  inputs = {{
+    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+    flake-utils.url = "github:numtide/flake-utils";
+  }};
+
+  outputs = {{ self, nixpkgs, flake-utils }}:
+    flake-utils.lib.eachDefaultSystem (system:
+      let
+        pkgs = import nixpkgs {{ inherit system; }};
+
+        # Runs a zero command from the project root; zero itself is not in nixpkgs
+        zero = command: pkgs.writeShellApplication {{
+          name = "zero-${{command}}";
+          text = ''
+            cd "$(git rev-parse --show-toplevel 2>/dev/null || pwd)"
+            exec zero ${{command}} "$@"
+          '';
+        }};
+      in
+      {{
+        devShells.default = pkgs.mkShell {{
+          packages = [
+{packages}
+          ];
+
+          shellHook = ''
+            echo "{project}: run 'zero up' (or 'nix run .#up') to start the services"
+          '';
+        }};
+
+        apps.up = {{
+          type = "app";
+          program = "${{zero "up"}}/bin/zero-up";
+        }};
+
+        apps.down = {{
+          type = "app";
+          program = "${{zero "down"}}/bin/zero-down";
+        }};
+      }});
+}}
+"#,
+        project = project,
+        packages = packages.iter().map(|package| format!("            {}", package)).collect::<Vec<_>>().join("\n"),
+    );
+    flake
+}
+
+/// nixpkgs attributes providing a language at a version, falling back to the unversioned package
+fn toolchain_packages(language: &str, version: &str) -> Vec<String> {
+    let major = version.split('.').next().unwrap_or(version);
+    let major_minor = |separator: &str| {
+        let parts: Vec<&str> = version.split('.').take(2).collect();
+        match parts.as_slice() {
+            [major, minor] if parts.iter().all(|part| part.chars().all(|c| c.is_ascii_digit())) => {
+                Some(format!("{}{}{}", major, separator, minor))
+            }
+            _ => None,
+        }
+    };
+    let numeric = !major.is_empty() && major.chars().all(|c| c.is_ascii_digit());
+
+    let packages = match language {
+        "node" | "nodejs" if numeric => vec![format!("nodejs_{}", major)],
+        "node" | "nodejs" => vec!["nodejs".to_string()],
+        "python" => vec![major_minor("").map_or("python3".to_string(), |version| format!("python{}", version))],
+        "go" | "golang" => vec![major_minor("_").map_or("go".to_string(), |version| format!("go_{}", version))],
+        // Channels and exact versions need an overlay such as rust-overlay; nixpkgs ships one stable toolchain
+        "rust" => ["cargo", "rustc", "rustfmt", "clippy", "rust-analyzer"].map(String::from).to_vec(),
+        "java" if numeric => vec![format!("jdk{}", major)],
+        "java" => vec!["jdk".to_string()],
+        "dotnet" if numeric => vec![format!("dotnet-sdk_{}", major)],
+        "dotnet" => vec!["dotnet-sdk".to_string()],
+        "ruby" => vec![major_minor("_").map_or("ruby".to_string(), |version| format!("ruby_{}", version))],
+        "php" => vec![major_minor("").map_or("php".to_string(), |version| format!("php{}", version))],
+        other => vec![other.to_string()],
+    };
+    packages.into_iter().map(|package| format!("pkgs.{}", package)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toolchains_and_apps() {
+        assert_eq!(toolchain_packages("node", "20"), vec!["pkgs.nodejs_20"]);
+        assert_eq!(toolchain_packages("python", "3.11"), vec!["pkgs.python311"]);
+        assert_eq!(toolchain_packages("go", "1.22"), vec!["pkgs.go_1_22"]);
+        assert_eq!(toolchain_packages("node", "latest"), vec!["pkgs.nodejs"]);
+
+        let config = ZeroConfig::from_str("metadata:\n  name: shop\nlanguages:\n  node: \"20\"\n").unwrap();
+        let flake = render(&config);
+        assert!(flake.contains("            pkgs.nodejs_20  # node 20\n            pkgs.docker-client\n"));
+        assert!(flake.contains("program = \"${zero \"up\"}/bin/zero-up\";"));
+    }
+}
@@ -0,0 +1,326 @@
+//! Terraform (or OpenTofu) for the cloud resources zero.yml emulates
+//!
+//! Every service under `cloud.aws/azure/gcp` becomes a resource block. With
+//! `use_emulators` (the default) the AWS provider talks to LocalStack and the
+//! Pub/Sub provider to its emulator, so `terraform apply` creates the same
+//! resources locally that it later creates in the real cloud with
+//! `-var use_emulators=false`. Resources of emulators Terraform cannot reach
+//! are only created in the real cloud.
+
+use anyhow::Result;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::cloud::{dynamodb_local, gcp, localstack};
+use crate::config::{AwsFlavor, CloudConfig, ZeroConfig};
+
+/// Region resources are created in unless `cloud.aws.region` says otherwise
+const DEFAULT_REGION: &str = "us-east-1";
+
+pub fn generate(config: &ZeroConfig, output_dir: &Path) -> Result<()> {
+    let project = config.metadata.name.as_deref().unwrap_or("zeroconfig-project");
+    let Some(content) = config.cloud.as_ref().and_then(|cloud| render(project, cloud)) else {
+        anyhow::bail!("zero.yml lists no cloud services; add them under cloud.aws, cloud.azure or cloud.gcp");
+    };
+
+    let dir = output_dir.join("terraform");
+    super::ensure_dir(&dir)?;
+    let output_path = dir.join("main.tf");
+    fs::write(&output_path, content)?;
+
+    println!("✅ Generated: {}", output_path.display());
+    Ok(())
+}
+
+/// Whether zero.yml lists cloud services to generate resources for
+pub fn has_cloud_services(config: &ZeroConfig) -> bool {
+    config.cloud.as_ref().is_some_and(|cloud| {
+        cloud.aws.as_ref().is_some_and(|aws| !aws.services.is_empty())
+            || cloud.azure.as_ref().is_some_and(|azure| !azure.services.is_empty())
+            || cloud.gcp.as_ref().is_some_and(|gcp| !gcp.services.is_empty())
+    })
+}
+
+/// main.tf for the cloud services of a project, `None` when it lists none
+pub fn render(project: &str, cloud: &CloudConfig) -> Option<String> {
+    let aws = cloud.aws.as_ref().map(|aws| aws.services.as_slice()).unwrap_or_default();
+    let azure = cloud.azure.as_ref().map(|azure| azure.services.as_slice()).unwrap_or_default();
+    let gcp_services = cloud.gcp.as_ref().map(|gcp| gcp.services.as_slice()).unwrap_or_default();
+    if aws.is_empty() && azure.is_empty() && gcp_services.is_empty() {
+        return None;
+    }
+
+    let name = resource_name(project);
+    let mut tf = String::new();
+    let _ = writeln!(tf, "# Generated by ZeroConfig\n# Project: {}\n", project);
+
+    tf.push_str("terraform {\n  required_providers {\n");
+    if !aws.is_empty() {
+        tf.push_str("    aws = {\n      source  = \"hashicorp/aws\"\n      version = \"~> 5.0\"\n    }\n");
+    }
+    if !azure.is_empty() {
+        tf.push_str("    azurerm = {\n      source  = \"hashicorp/azurerm\"\n      version = \"~> 4.0\"\n    }\n");
+    }
+    if !gcp_services.is_empty() {
+        tf.push_str("    google = {\n      source  = \"hashicorp/google\"\n      version = \"~> 6.0\"\n    }\n");
+    }
+    tf.push_str("  }\n}\n\n");
+
+    tf.push_str(concat!(
+        "variable \"use_emulators\" {\n",
+        "  description = \"Create the resources in the emulators zero up starts; false targets the real clouds\"\n",
+        "  type        = bool\n",
+        "  default     = true\n",
+        "}\n",
+    ));
+
+    if !aws.is_empty() {
+        let region = cloud.aws.as_ref().and_then(|aws| aws.region.as_deref()).unwrap_or(DEFAULT_REGION);
+        let port = match cloud.aws.as_ref().map(|aws| aws.flavor) {
+            Some(AwsFlavor::DynamodbLocal) => dynamodb_local::port(Some(cloud)),
+            _ => localstack::port(Some(cloud)),
+        };
+        render_aws(&mut tf, &name, region, port, aws);
+    }
+    if !azure.is_empty() {
+        render_azure(&mut tf, &name, azure);
+    }
+    if !gcp_services.is_empty() {
+        let gcp_project = cloud.gcp.as_ref().and_then(|gcp| gcp.project.as_deref()).unwrap_or(&name);
+        render_gcp(&mut tf, &name, gcp_project, gcp_services);
+    }
+    Some(tf)
+}
+
+fn render_aws(tf: &mut String, name: &str, region: &str, port: u16, services: &[String]) {
+    let _ = write!(
+        tf,
+        concat!(
+            "\nvariable \"localstack_endpoint\" {{\n",
+            "  description = \"LocalStack's edge endpoint, used while use_emulators is true\"\n",
+            "  type        = string\n",
+            "  default     = \"{endpoint}\"\n",
+            "}}\n\n",
+            "locals {{\n",
+            "  localstack = var.use_emulators ? var.localstack_endpoint : null\n",
+            "}}\n\n",
+            "provider \"aws\" {{\n",
+            "  region = \"{region}\"\n\n",
+            "  # LocalStack accepts any credentials; the real cloud uses the usual AWS credential chain\n",
+            "  access_key                  = var.use_emulators ? \"test\" : null\n",
+            "  secret_key                  = var.use_emulators ? \"test\" : null\n",
+            "  skip_credentials_validation = var.use_emulators\n",
+            "  skip_metadata_api_check     = var.use_emulators\n",
+            "  skip_requesting_account_id  = var.use_emulators\n",
+            "  s3_use_path_style           = var.use_emulators\n\n",
+            "  endpoints {{\n",
+        ),
+        endpoint = localstack::get_endpoint_url(port),
+        region = region,
+    );
+    for service in services.iter().filter(|service| aws_resource(service, name).is_some()) {
+        let _ = writeln!(tf, "    {} = local.localstack", service);
+    }
+    tf.push_str("  }\n}\n");
+
+    for service in services {
+        match aws_resource(service, name) {
+            Some(resource) => {
+                tf.push('\n');
+                tf.push_str(&resource);
+            }
+            None => {
+                let _ = writeln!(tf, "\n# {}: no resource is generated for this service; declare it here", service);
+            }
+        }
+    }
+}
+
+/// Resource block for an AWS service LocalStack emulates
+fn aws_resource(service: &str, name: &str) -> Option<String> {
+    let resource = match service {
+        "s3" => format!("resource \"aws_s3_bucket\" \"main\" {{\n  bucket = \"{}\"\n}}\n", name),
+        "sqs" => format!("resource \"aws_sqs_queue\" \"main\" {{\n  name = \"{}\"\n}}\n", name),
+        "sns" => format!("resource \"aws_sns_topic\" \"main\" {{\n  name = \"{}\"\n}}\n", name),
+        "dynamodb" => format!(
+            concat!(
+                "resource \"aws_dynamodb_table\" \"main\" {{\n",
+                "  name         = \"{}\"\n",
+                "  billing_mode = \"PAY_PER_REQUEST\"\n",
+                "  hash_key     = \"id\"\n\n",
+                "  attribute {{\n",
+                "    name = \"id\"\n",
+                "    type = \"S\"\n",
+                "  }}\n",
+                "}}\n",
+            ),
+            name,
+        ),
+        "kinesis" => format!("resource \"aws_kinesis_stream\" \"main\" {{\n  name        = \"{}\"\n  shard_count = 1\n}}\n", name),
+        "secretsmanager" => format!("resource \"aws_secretsmanager_secret\" \"main\" {{\n  name = \"{}\"\n}}\n", name),
+        "ssm" => format!(
+            "resource \"aws_ssm_parameter\" \"main\" {{\n  name  = \"/{}/example\"\n  type  = \"String\"\n  value = \"changeme\"\n}}\n",
+            name,
+        ),
+        "ecr" => format!("resource \"aws_ecr_repository\" \"main\" {{\n  name = \"{}\"\n}}\n", name),
+        _ => return None,
+    };
+    Some(resource)
+}
+
+/// Azurite emulates a fixed development account the azurerm provider cannot manage, so these are real-cloud only
+fn render_azure(tf: &mut String, name: &str, services: &[String]) {
+    let account: String = name.chars().filter(char::is_ascii_alphanumeric).take(24).collect();
+    let _ = write!(
+        tf,
+        concat!(
+            "\n# Azurite serves a fixed development account with the same APIs; these are created in Azure only\n",
+            "variable \"azure_location\" {{\n",
+            "  type    = string\n",
+            "  default = \"westeurope\"\n",
+            "}}\n\n",
+            "provider \"azurerm\" {{\n",
+            "  features {{}}\n",
+            "}}\n\n",
+            "resource \"azurerm_resource_group\" \"main\" {{\n",
+            "  count    = var.use_emulators ? 0 : 1\n",
+            "  name     = \"{name}\"\n",
+            "  location = var.azure_location\n",
+            "}}\n\n",
+            "resource \"azurerm_storage_account\" \"main\" {{\n",
+            "  count                    = var.use_emulators ? 0 : 1\n",
+            "  name                     = \"{account}\"\n",
+            "  resource_group_name      = azurerm_resource_group.main[0].name\n",
+            "  location                 = azurerm_resource_group.main[0].location\n",
+            "  account_tier             = \"Standard\"\n",
+            "  account_replication_type = \"LRS\"\n",
+            "}}\n",
+        ),
+        name = name,
+        account = account,
+    );
+
+    for service in services {
+        let resource = match service.as_str() {
+            "blob" => "azurerm_storage_container",
+            "queue" => "azurerm_storage_queue",
+            "table" => "azurerm_storage_table",
+            _ => {
+                let _ = writeln!(tf, "\n# {}: no resource is generated for this service; declare it here", service);
+                continue;
+            }
+        };
+        let name_key = if service == "table" { name.replace('-', "") } else { name.to_string() };
+        let account_ref = match service.as_str() {
+            "table" => "storage_account_name = azurerm_storage_account.main[0].name",
+            _ => "storage_account_id   = azurerm_storage_account.main[0].id",
+        };
+        let _ = write!(
+            tf,
+            "\nresource \"{}\" \"main\" {{\n  count                = var.use_emulators ? 0 : 1\n  name                 = \"{}\"\n  {}\n}}\n",
+            resource, name_key, account_ref,
+        );
+    }
+}
+
+/// Pub/Sub goes through its emulator's REST API; the other emulators have no endpoint the provider can use
+fn render_gcp(tf: &mut String, name: &str, project: &str, services: &[String]) {
+    let _ = write!(
+        tf,
+        concat!(
+            "\nvariable \"gcp_project\" {{\n",
+            "  type    = string\n",
+            "  default = \"{project}\"\n",
+            "}}\n\n",
+            "provider \"google\" {{\n",
+            "  project = var.gcp_project\n",
+        ),
+        project = project,
+    );
+    if services.iter().any(|service| service == "pubsub") {
+        let _ = writeln!(
+            tf,
+            "  pubsub_custom_endpoint = var.use_emulators ? \"http://localhost:{}/v1/\" : null",
+            gcp::PUBSUB_PORT,
+        );
+        tf.push_str("  access_token           = var.use_emulators ? \"emulator\" : null\n");
+    }
+    tf.push_str("}\n");
+
+    for service in services {
+        match service.as_str() {
+            "pubsub" => {
+                let _ = write!(
+                    tf,
+                    concat!(
+                        "\nresource \"google_pubsub_topic\" \"main\" {{\n",
+                        "  name = \"{name}\"\n",
+                        "}}\n\n",
+                        "resource \"google_pubsub_subscription\" \"main\" {{\n",
+                        "  name  = \"{name}\"\n",
+                        "  topic = google_pubsub_topic.main.id\n",
+                        "}}\n",
+                    ),
+                    name = name,
+                );
+            }
+            "firestore" => {
+                let _ = write!(
+                    tf,
+                    concat!(
+                        "\n# The Firestore emulator needs no database; this creates the real one only\n",
+                        "resource \"google_firestore_database\" \"main\" {{\n",
+                        "  count       = var.use_emulators ? 0 : 1\n",
+                        "  name        = \"(default)\"\n",
+                        "  location_id = \"nam5\"\n",
+                        "  type        = \"FIRESTORE_NATIVE\"\n",
+                        "}}\n",
+                    ),
+                );
+            }
+            _ => {
+                let _ = writeln!(tf, "\n# {}: no resource is generated for this service; declare it here", service);
+            }
+        }
+    }
+}
+
+/// A project name as cloud resource names accept it: lowercase letters, digits and dashes
+fn resource_name(project: &str) -> String {
+    let name: String = project.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    match name.trim_matches('-') {
+        "" => "zeroconfig".to_string(),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AwsConfig, GcpConfig};
+
+    #[test]
+    fn test_render_aws_and_gcp() {
+        let cloud = CloudConfig {
+            localstack: None,
+            aws: Some(AwsConfig { services: vec!["s3".to_string(), "dynamodb".to_string(), "lambda".to_string()], region: Some("eu-west-1".to_string()), flavor: Default::default(), port: None, image: None, version: None, resources: Default::default() }),
+            azure: None,
+            gcp: Some(GcpConfig { services: vec!["pubsub".to_string()], project: None, flavor: Default::default(), port: None, image: None, version: None }),
+        };
+        let tf = render("My Shop", &cloud).unwrap();
+
+        assert!(tf.contains("  region = \"eu-west-1\"\n"));
+        assert!(tf.contains("    s3 = local.localstack\n    dynamodb = local.localstack\n  }\n"));
+        assert!(tf.contains("resource \"aws_s3_bucket\" \"main\" {\n  bucket = \"my-shop\"\n}\n"));
+        assert!(tf.contains("# lambda: no resource is generated"));
+        assert!(tf.contains("pubsub_custom_endpoint = var.use_emulators ? \"http://localhost:8085/v1/\" : null"));
+        assert!(!tf.contains("azurerm"));
+
+        let empty = CloudConfig { localstack: Some("full".to_string()), aws: None, azure: None, gcp: None };
+        assert!(render("shop", &empty).is_none());
+    }
+}
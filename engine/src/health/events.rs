@@ -0,0 +1,147 @@
+use bollard::models::EventMessage;
+use std::collections::HashSet;
+
+/// A lifecycle or health change of a project service, derived from Docker events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEvent {
+    pub service: String,
+    pub kind: ServiceEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceEventKind {
+    Started,
+    Died { exit_code: Option<i64> },
+    OutOfMemory,
+    Healthy,
+    Unhealthy,
+}
+
+impl ServiceEventKind {
+    /// Whether this event means the service stopped working
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Died { .. } | Self::OutOfMemory | Self::Unhealthy)
+    }
+}
+
+impl ServiceEvent {
+    /// Docker event actions worth subscribing to
+    pub const DOCKER_ACTIONS: &'static [&'static str] = &["start", "die", "oom", "health_status"];
+
+    /// Convert a Docker event into a service event for the given project
+    ///
+    /// Returns `None` for events from other projects or actions we do not track.
+    pub fn from_docker(message: &EventMessage, project_name: &str) -> Option<Self> {
+        let attributes = message.actor.as_ref()?.attributes.as_ref()?;
+        let container = attributes.get("name")?;
+        let service = container.strip_prefix(&format!("{}_", project_name))?.to_string();
+
+        let kind = match message.action.as_deref()?.trim() {
+            "start" => ServiceEventKind::Started,
+            "die" => ServiceEventKind::Died {
+                exit_code: attributes.get("exitCode").and_then(|code| code.parse().ok()),
+            },
+            "oom" => ServiceEventKind::OutOfMemory,
+            "health_status: healthy" => ServiceEventKind::Healthy,
+            "health_status: unhealthy" => ServiceEventKind::Unhealthy,
+            _ => return None,
+        };
+
+        Some(Self { service, kind })
+    }
+
+    /// Human-readable summary, e.g. for desktop notifications
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            ServiceEventKind::Started => format!("{} started", self.service),
+            ServiceEventKind::Died { exit_code: Some(code) } => format!("{} exited with code {}", self.service, code),
+            ServiceEventKind::Died { exit_code: None } => format!("{} stopped unexpectedly", self.service),
+            ServiceEventKind::OutOfMemory => format!("{} ran out of memory", self.service),
+            ServiceEventKind::Healthy => format!("{} is healthy", self.service),
+            ServiceEventKind::Unhealthy => format!("{} is unhealthy", self.service),
+        }
+    }
+}
+
+/// Notable transitions worth surfacing to the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transition {
+    Failed(ServiceEvent),
+    Recovered(ServiceEvent),
+}
+
+/// Tracks failed services so that only failures and recoveries are reported
+#[derive(Debug, Default)]
+pub struct FailureTracker {
+    failed: HashSet<String>,
+}
+
+impl FailureTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed an event and get the transition it represents, if any
+    pub fn observe(&mut self, event: ServiceEvent) -> Option<Transition> {
+        if event.kind.is_failure() {
+            // A crash is usually followed by `die`; report only the first failure
+            if self.failed.insert(event.service.clone()) {
+                return Some(Transition::Failed(event));
+            }
+            // OOM kills arrive before `die` and are the more useful message
+            return None;
+        }
+
+        if self.failed.remove(&event.service) {
+            return Some(Transition::Recovered(event));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::EventActor;
+    use std::collections::HashMap;
+
+    fn docker_event(action: &str, name: &str) -> EventMessage {
+        EventMessage {
+            action: Some(action.to_string()),
+            actor: Some(EventActor {
+                id: Some("abc".to_string()),
+                attributes: Some(HashMap::from([
+                    ("name".to_string(), name.to_string()),
+                    ("exitCode".to_string(), "137".to_string()),
+                ])),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_from_docker_event() {
+        let event = ServiceEvent::from_docker(&docker_event("die", "shop_postgres"), "shop").unwrap();
+        assert_eq!(event.service, "postgres");
+        assert_eq!(event.kind, ServiceEventKind::Died { exit_code: Some(137) });
+
+        let healthy = ServiceEvent::from_docker(&docker_event("health_status: healthy", "shop_redis"), "shop").unwrap();
+        assert_eq!(healthy.kind, ServiceEventKind::Healthy);
+
+        assert!(ServiceEvent::from_docker(&docker_event("die", "other_postgres"), "shop").is_none());
+        assert!(ServiceEvent::from_docker(&docker_event("exec_start", "shop_postgres"), "shop").is_none());
+    }
+
+    #[test]
+    fn test_failure_tracker_reports_transitions_once() {
+        let mut tracker = FailureTracker::new();
+        let event = |kind| ServiceEvent { service: "postgres".to_string(), kind };
+
+        assert!(tracker.observe(event(ServiceEventKind::Started)).is_none());
+        assert!(matches!(tracker.observe(event(ServiceEventKind::OutOfMemory)), Some(Transition::Failed(_))));
+        assert!(tracker.observe(event(ServiceEventKind::Died { exit_code: Some(137) })).is_none());
+        assert!(matches!(tracker.observe(event(ServiceEventKind::Started)), Some(Transition::Recovered(_))));
+        assert!(tracker.observe(event(ServiceEventKind::Healthy)).is_none());
+    }
+}
@@ -2,10 +2,22 @@ use anyhow::Result;
 use bollard::Docker;
 use bollard::exec::{CreateExecOptions, StartExecResults};
 use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use tracing::info;
 
-#[derive(Debug, Clone)]
+pub mod events;
+
+pub use events::{FailureTracker, ServiceEvent, ServiceEventKind, Transition};
+
+use crate::config::ZeroConfig;
+use crate::services::{
+    CassandraService, ClickHouseService, ElasticsearchService, KeycloakService, MailpitService, MeilisearchService,
+    MemcachedService, NatsService, TypesenseService,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthStatus {
     pub service_name: String,
     pub is_healthy: bool,
@@ -16,12 +28,27 @@ pub struct HealthStatus {
 
 pub struct HealthChecker {
     docker: Docker,
+    /// `healthcheck:` commands from zero.yml, by service
+    healthchecks: HashMap<String, Vec<String>>,
 }
 
 impl HealthChecker {
-    pub async fn new() -> Result<Self> {
-        let docker = Docker::connect_with_local_defaults()?;
-        Ok(Self { docker })
+    /// Check containers of the engine a client is connected to, such as [`Engine::docker`](crate::core::Engine::docker)
+    pub fn new(docker: Docker) -> Self {
+        Self { docker, healthchecks: HashMap::new() }
+    }
+
+    /// Check services with the `healthcheck:` of zero.yml when Docker reports no health for
+    /// their container
+    ///
+    /// Containers created from zero.yml carry the healthcheck themselves, so Docker's
+    /// status is used and the command is not run again; this covers containers created
+    /// before the healthcheck was added.
+    pub fn with_healthchecks(mut self, config: &ZeroConfig) -> Self {
+        self.healthchecks = config.services.iter()
+            .filter_map(|(service, config)| Some((service.clone(), config.healthcheck.as_ref()?.exec_command())))
+            .collect();
+        self
     }
 
     /// Check health of a specific container
@@ -94,9 +121,16 @@ impl HealthChecker {
         }
     }
 
-    /// Perform service-specific health check
+    /// Perform service-specific health check, for containers without a Docker health status
     async fn perform_service_health_check(&self, container_id: &str, service_name: &str) -> Result<String> {
-        let health_command = self.get_health_command(service_name);
+        if let Some(command) = self.healthchecks.get(service_name) {
+            return self.run_configured_check(container_id, command).await;
+        }
+        // HTTP probes print nothing useful on failure, so these pass by exit code alone
+        if let Some(command) = exit_code_command(service_name) {
+            return self.run_configured_check(container_id, &command).await;
+        }
+        let health_command = output_command(service_name);
 
         if health_command.is_empty() {
             return Ok("Running (no health check available)".to_string());
@@ -121,7 +155,10 @@ impl HealthChecker {
                     }
                 }
 
-                if result.contains("accepting connections") || result.contains("ready") {
+                // pg_isready and friends, then Meilisearch's and Typesense's /health bodies
+                if result.contains("accepting connections") || result.contains("ready")
+                    || result.contains("\"available\"") || result.contains("\"ok\":true")
+                {
                     Ok("Healthy".to_string())
                 } else {
                     Ok("Running".to_string())
@@ -131,39 +168,31 @@ impl HealthChecker {
         }
     }
 
-    /// Get health check command for a service
-    fn get_health_command(&self, service_name: &str) -> Vec<String> {
-        match service_name {
-            s if s.contains("postgres") => vec![
-                "pg_isready".to_string(),
-                "-U".to_string(),
-                "postgres".to_string(),
-            ],
-            s if s.contains("redis") => vec![
-                "redis-cli".to_string(),
-                "ping".to_string(),
-            ],
-            s if s.contains("mongo") => vec![
-                "mongosh".to_string(),
-                "--eval".to_string(),
-                "db.adminCommand('ping')".to_string(),
-            ],
-            s if s.contains("mysql") => vec![
-                "mysqladmin".to_string(),
-                "ping".to_string(),
-                "-h".to_string(),
-                "localhost".to_string(),
-            ],
-            s if s.contains("rabbitmq") => vec![
-                "rabbitmq-diagnostics".to_string(),
-                "ping".to_string(),
-            ],
-            s if s.contains("elasticsearch") => vec![
-                "curl".to_string(),
-                "-f".to_string(),
-                "http://localhost:9200/_cluster/health".to_string(),
-            ],
-            _ => vec![],
+    /// Run a `healthcheck:` command, which passes when it exits with 0
+    async fn run_configured_check(&self, container_id: &str, command: &[String]) -> Result<String> {
+        let exec_config = CreateExecOptions {
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            cmd: Some(command.iter().map(|s| s.as_str()).collect()),
+            ..Default::default()
+        };
+
+        let exec = self.docker.create_exec(container_id, exec_config).await?;
+        let mut result = String::new();
+        if let StartExecResults::Attached { mut output, .. } = self.docker.start_exec(&exec.id, None).await? {
+            while let Some(Ok(chunk)) = output.next().await {
+                result.push_str(&chunk.to_string());
+            }
+        }
+
+        match self.docker.inspect_exec(&exec.id).await?.exit_code {
+            Some(0) => Ok("Healthy".to_string()),
+            code => anyhow::bail!(
+                "`{}` exited with {}: {}",
+                command.join(" "),
+                code.map_or("no status".to_string(), |code| code.to_string()),
+                result.trim()
+            ),
         }
     }
 
@@ -193,6 +222,65 @@ impl HealthChecker {
     }
 }
 
+/// Health check of a service that passes when the command exits with 0
+fn exit_code_command(service_name: &str) -> Option<Vec<String>> {
+    match service_name {
+        s if s.ends_with("kibana") || s.ends_with("dashboards") => Some(ElasticsearchService::dashboards_health_check_command()),
+        s if s.contains("elasticsearch") || s.contains("opensearch") => Some(ElasticsearchService::health_check_command()),
+        s if s.contains("mailpit") || s.contains("mailhog") => Some(MailpitService::health_check_command()),
+        s if s.contains("keycloak") => Some(KeycloakService::health_check_command()),
+        s if s.contains("nats") => Some(NatsService::health_check_command()),
+        s if s.contains("clickhouse") => Some(ClickHouseService::health_check_command()),
+        s if s.contains("cassandra") => Some(CassandraService::health_check_command()),
+        s if s.contains("memcached") => Some(MemcachedService::health_check_command()),
+        _ => None,
+    }
+}
+
+/// Health check of a service whose output tells whether it is ready, e.g. `accepting connections`
+fn output_command(service_name: &str) -> Vec<String> {
+    match service_name {
+        s if s.contains("postgres") || s.contains("timescale") => vec![
+            "pg_isready".to_string(),
+            "-U".to_string(),
+            "postgres".to_string(),
+        ],
+        s if s.contains("redis") => vec![
+            "redis-cli".to_string(),
+            "ping".to_string(),
+        ],
+        s if s.contains("mongo") => vec![
+            "mongosh".to_string(),
+            "--eval".to_string(),
+            "db.adminCommand('ping')".to_string(),
+        ],
+        s if s.contains("mysql") => vec![
+            "mysqladmin".to_string(),
+            "ping".to_string(),
+            "-h".to_string(),
+            "localhost".to_string(),
+        ],
+        s if s.contains("influx") => vec![
+            "influx".to_string(),
+            "ping".to_string(),
+        ],
+        s if s.contains("meilisearch") => MeilisearchService::health_check_command(),
+        s if s.contains("typesense") => TypesenseService::health_check_command(),
+        s if s.contains("rabbitmq") => vec![
+            "rabbitmq-diagnostics".to_string(),
+            "ping".to_string(),
+        ],
+        _ => vec![],
+    }
+}
+
+/// Built-in health check of a well-known service, as a command that exits with 0 once it is ready
+///
+/// What `zero generate compose` gives services without a `healthcheck:`.
+pub fn builtin_check_command(service_name: &str) -> Option<Vec<String>> {
+    exit_code_command(service_name).or_else(|| Some(output_command(service_name)).filter(|command| !command.is_empty()))
+}
+
 /// Format health status for display
 pub fn format_health_status(status: &HealthStatus) -> String {
     let health_indicator = if status.is_healthy { "✓" } else { "✗" };
@@ -5,14 +5,22 @@ pub mod orchestrator;
 pub mod runtime;
 pub mod services;
 pub mod secrets;
+pub mod telemetry;
 pub mod generators;
 pub mod cloud;
 pub mod health;
+pub mod logs;
+pub mod metrics;
 pub mod validation;
+pub mod proxy;
+pub mod tunnels;
+pub mod watch;
+pub mod doctor;
 
 // Re-export common types
 pub use config::ZeroConfig;
 pub use self::core::Engine;
 
+pub mod backup;
 pub mod commands;
 
@@ -0,0 +1,56 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::core::Engine;
+
+/// What to include when writing service logs to a file
+#[derive(Debug, Clone, Default)]
+pub struct LogExport {
+    pub services: Vec<String>,
+    /// Unix timestamps bounding the export (see [`super::parse_since`]); 0 means unbounded
+    pub since: i64,
+    pub until: i64,
+    /// Write one `{service}.log` per service into a ZIP archive instead of a single text file
+    pub zip: bool,
+}
+
+/// Write the selected services' logs to `path` and return the number of bytes written
+///
+/// Lines keep the runtime's timestamps so exports from several services can be
+/// correlated.
+pub async fn export_logs(engine: &Engine, export: &LogExport, path: &Path) -> Result<u64> {
+    if export.services.is_empty() {
+        anyhow::bail!("No services selected for export");
+    }
+
+    let mut files = Vec::new();
+    for service in &export.services {
+        let logs = engine.logs_between(service, export.since, export.until).await
+            .with_context(|| format!("Failed to read logs for {}", service))?;
+        files.push((format!("{}.log", service), logs.into_bytes()));
+    }
+
+    let content = if export.zip {
+        super::zip::build(&files)?
+    } else {
+        let mut combined = Vec::new();
+        for (name, logs) in &files {
+            combined.extend_from_slice(format!("===== {} =====\n", name.trim_end_matches(".log")).as_bytes());
+            combined.extend_from_slice(logs);
+            if !logs.ends_with(b"\n") {
+                combined.push(b'\n');
+            }
+            combined.push(b'\n');
+        }
+        combined
+    };
+
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    std::fs::write(path, &content)
+        .with_context(|| format!("Failed to write log export: {}", path.display()))?;
+
+    Ok(content.len() as u64)
+}
@@ -0,0 +1,483 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+pub mod export;
+mod zip;
+
+pub use export::{export_logs, LogExport};
+
+/// Log severity detected from a log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Parse a level from its CLI name
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "debug" | "trace" => Ok(Self::Debug),
+            "info" => Ok(Self::Info),
+            "warn" | "warning" => Ok(Self::Warn),
+            "error" | "err" => Ok(Self::Error),
+            _ => anyhow::bail!("Unknown log level: {}. Supported: error, warn, info, debug", name),
+        }
+    }
+
+    /// Best-effort level detection for a log line
+    ///
+    /// Lines without a recognizable marker are treated as `Info`.
+    pub fn detect(line: &str) -> Self {
+        let lower = line.to_lowercase();
+
+        if ["error", "fatal", "panic", "critical", "exception"].iter().any(|m| lower.contains(m)) {
+            Self::Error
+        } else if lower.contains("warn") {
+            Self::Warn
+        } else if lower.contains("debug") || lower.contains("trace") {
+            Self::Debug
+        } else {
+            Self::Info
+        }
+    }
+}
+
+/// Filter applied to log lines before they leave the engine
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pattern: Option<Regex>,
+    since: Option<i64>,
+    min_level: Option<LogLevel>,
+}
+
+impl LogFilter {
+    /// Create an empty filter that matches every line
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep lines matching a pattern
+    ///
+    /// The pattern is used as a regex when valid, otherwise as a literal substring.
+    pub fn with_grep(mut self, pattern: &str) -> Self {
+        let regex = Regex::new(pattern)
+            .unwrap_or_else(|_| Regex::new(&regex::escape(pattern)).expect("escaped pattern is valid"));
+        self.pattern = Some(regex);
+        self
+    }
+
+    /// Only keep lines logged after a point in time (see [`parse_since`])
+    pub fn with_since(mut self, since: &str) -> Result<Self> {
+        self.since = Some(parse_since(since)?);
+        Ok(self)
+    }
+
+    /// Only keep lines at or above a severity
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    /// Build a filter from optional CLI arguments
+    pub fn from_args(grep: Option<&str>, since: Option<&str>, level: Option<&str>) -> Result<Self> {
+        let mut filter = Self::new();
+        if let Some(pattern) = grep {
+            filter = filter.with_grep(pattern);
+        }
+        if let Some(since) = since {
+            filter = filter.with_since(since)?;
+        }
+        if let Some(level) = level {
+            filter = filter.with_level(LogLevel::from_name(level)?);
+        }
+        Ok(filter)
+    }
+
+    /// Unix timestamp to pass to the container runtime's `since` option (0 = no limit)
+    pub fn since_timestamp(&self) -> i64 {
+        self.since.unwrap_or(0)
+    }
+
+    /// Check if a single log line passes the filter
+    pub fn matches(&self, line: &str) -> bool {
+        if let Some(min_level) = self.min_level {
+            if LogLevel::detect(line) < min_level {
+                return false;
+            }
+        }
+
+        match &self.pattern {
+            Some(pattern) => pattern.is_match(line),
+            None => true,
+        }
+    }
+
+    /// Keep only the matching lines of a log chunk
+    pub fn apply(&self, chunk: &str) -> Option<String> {
+        if self.pattern.is_none() && self.min_level.is_none() {
+            return Some(chunk.to_string());
+        }
+
+        let kept: Vec<&str> = chunk.lines().filter(|line| self.matches(line)).collect();
+        if kept.is_empty() {
+            None
+        } else {
+            Some(format!("{}\n", kept.join("\n")))
+        }
+    }
+
+    /// Colorize a log chunk by severity, marking what the grep pattern matched
+    pub fn highlight(&self, chunk: &str) -> String {
+        chunk
+            .lines()
+            .map(|line| {
+                let level = LogLevel::detect(line);
+                self.split_matches(line)
+                    .into_iter()
+                    .map(|(text, matched)| match (matched, level) {
+                        (true, _) => text.black().on_yellow().bold().to_string(),
+                        (false, LogLevel::Error) => text.red().to_string(),
+                        (false, LogLevel::Warn) => text.yellow().to_string(),
+                        (false, _) => text.to_string(),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + if chunk.ends_with('\n') { "\n" } else { "" }
+    }
+
+    /// Split a line into the parts the grep pattern matched and the rest
+    fn split_matches<'a>(&self, line: &'a str) -> Vec<(&'a str, bool)> {
+        let Some(pattern) = &self.pattern else {
+            return vec![(line, false)];
+        };
+
+        let mut parts = Vec::new();
+        let mut end = 0;
+        for found in pattern.find_iter(line).filter(|found| !found.is_empty()) {
+            if found.start() > end {
+                parts.push((&line[end..found.start()], false));
+            }
+            parts.push((found.as_str(), true));
+            end = found.end();
+        }
+        if end < line.len() || parts.is_empty() {
+            parts.push((&line[end..], false));
+        }
+        parts
+    }
+}
+
+/// A log line returned by a search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// RFC 3339 timestamp recorded by the container runtime
+    pub timestamp: Option<String>,
+    pub level: LogLevel,
+    pub line: String,
+}
+
+/// One page of search results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogPage {
+    pub entries: Vec<LogEntry>,
+    /// Pass back as the cursor to fetch the next page; `None` once the range is exhausted
+    pub next_cursor: Option<String>,
+}
+
+/// A paginated search over a service's log history
+///
+/// Pages are returned oldest first. The cursor is the timestamp of the last
+/// entry of the previous page and how many lines carry that timestamp up to
+/// it, so lines logged within the same instant are neither lost nor repeated.
+#[derive(Debug, Clone)]
+pub struct LogSearch {
+    filter: LogFilter,
+    until: Option<i64>,
+    limit: usize,
+    cursor: Option<LogPosition>,
+    /// Position of the last line passed to [`accept`](Self::accept)
+    position: Option<LogPosition>,
+}
+
+/// A line's timestamp and its place among the lines sharing it, counting from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LogPosition {
+    time: DateTime<FixedOffset>,
+    offset: usize,
+}
+
+impl LogSearch {
+    pub fn new(filter: LogFilter, limit: usize) -> Self {
+        Self {
+            filter,
+            until: None,
+            limit: limit.max(1),
+            cursor: None,
+            position: None,
+        }
+    }
+
+    /// Only search lines logged before a point in time (see [`parse_since`])
+    pub fn with_until(mut self, until: &str) -> Result<Self> {
+        self.until = Some(parse_since(until)?);
+        Ok(self)
+    }
+
+    /// Continue after a cursor returned in a previous [`LogPage`]
+    pub fn with_cursor(mut self, cursor: &str) -> Result<Self> {
+        let (time, offset) = cursor.split_once('#').unwrap_or((cursor, "0"));
+        let time = DateTime::parse_from_rfc3339(time)
+            .with_context(|| format!("Invalid log cursor: {}", cursor))?;
+        let offset = offset.parse().with_context(|| format!("Invalid log cursor: {}", cursor))?;
+        self.cursor = Some(LogPosition { time, offset });
+        Ok(self)
+    }
+
+    /// Cursor continuing after the last line passed to [`accept`](Self::accept)
+    pub fn cursor(&self) -> Option<String> {
+        self.position.map(|position| {
+            format!("{}#{}", position.time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true), position.offset)
+        })
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Unix timestamp to pass to the container runtime's `since` option
+    pub fn since_timestamp(&self) -> i64 {
+        let cursor = self.cursor.map(|cursor| cursor.time.timestamp()).unwrap_or(0);
+        self.filter.since_timestamp().max(cursor)
+    }
+
+    /// Unix timestamp to pass to the container runtime's `until` option (0 = no limit)
+    pub fn until_timestamp(&self) -> i64 {
+        self.until.unwrap_or(0)
+    }
+
+    /// Turn a timestamped log line into an entry if it belongs in the results
+    ///
+    /// Lines must be passed in the order the runtime returns them.
+    pub fn accept(&mut self, raw: &str) -> Option<LogEntry> {
+        let (timestamp, line) = split_timestamp(raw);
+
+        if let Some((_, time)) = timestamp {
+            let offset = match self.position {
+                Some(last) if last.time == time => last.offset + 1,
+                _ => 1,
+            };
+            let position = LogPosition { time, offset };
+            self.position = Some(position);
+
+            // The runtime's `since` has one-second granularity, so skip what the cursor already covered
+            if let Some(cursor) = self.cursor {
+                if (position.time, position.offset) <= (cursor.time, cursor.offset) {
+                    return None;
+                }
+            }
+        }
+
+        if !self.filter.matches(line) {
+            return None;
+        }
+
+        Some(LogEntry {
+            timestamp: timestamp.map(|(text, _)| text.to_string()),
+            level: LogLevel::detect(line),
+            line: line.to_string(),
+        })
+    }
+}
+
+/// Split the RFC 3339 timestamp that `docker logs --timestamps` puts in front of a line
+fn split_timestamp(raw: &str) -> (Option<(&str, DateTime<FixedOffset>)>, &str) {
+    if let Some((prefix, rest)) = raw.split_once(' ') {
+        if let Ok(time) = DateTime::parse_from_rfc3339(prefix) {
+            return (Some((prefix, time)), rest);
+        }
+    }
+    (None, raw)
+}
+
+/// Parse a `--since` value into a unix timestamp
+///
+/// Accepts relative durations (`30s`, `10m`, `2h`, `1d`), RFC 3339 timestamps,
+/// and raw unix timestamps.
+pub fn parse_since(value: &str) -> Result<i64> {
+    let value = value.trim();
+    let now = chrono::Utc::now().timestamp();
+
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Ok(timestamp);
+    }
+
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.timestamp());
+    }
+
+    let invalid = || format!("Invalid --since value: {}. Use e.g. 30s, 10m, 2h, 1d or an RFC 3339 timestamp", value);
+    let (amount, unit) = value.split_at(value.char_indices().last().map_or(0, |(index, _)| index));
+    let amount: i64 = amount.parse().with_context(invalid)?;
+
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => anyhow::bail!("Invalid --since unit '{}'. Use s, m, h or d", unit),
+    };
+
+    amount.checked_mul(scale)
+        .and_then(|seconds| now.checked_sub(seconds))
+        .with_context(invalid)
+}
+
+/// Colorize a log chunk by detected severity for terminal output
+pub fn highlight(chunk: &str) -> String {
+    LogFilter::new().highlight(chunk)
+}
+
+/// Prefix every line of a log chunk, as used when merging several services
+pub fn prefix_lines(prefix: &str, chunk: &str) -> String {
+    chunk
+        .lines()
+        .map(|line| format!("{} | {}\n", prefix, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_level() {
+        assert_eq!(LogLevel::detect("2024-01-01 ERROR: connection refused"), LogLevel::Error);
+        assert_eq!(LogLevel::detect("WARNING:  could not open file"), LogLevel::Warn);
+        assert_eq!(LogLevel::detect("Ready to accept connections"), LogLevel::Info);
+    }
+
+    #[test]
+    fn test_filter_by_level() {
+        let filter = LogFilter::new().with_level(LogLevel::Warn);
+        assert!(filter.matches("FATAL: role does not exist"));
+        assert!(filter.matches("[warn] slow query"));
+        assert!(!filter.matches("database system is ready"));
+    }
+
+    #[test]
+    fn test_filter_grep_regex_and_literal() {
+        let regex = LogFilter::new().with_grep("conn(ection)? refused");
+        assert!(regex.matches("connection refused"));
+        assert!(!regex.matches("connected"));
+
+        // Invalid regex falls back to a literal match
+        let literal = LogFilter::new().with_grep("value[");
+        assert!(literal.matches("bad value[0]"));
+    }
+
+    #[test]
+    fn test_apply_keeps_matching_lines() {
+        let filter = LogFilter::new().with_grep("GET");
+        let chunk = "GET /health\nPOST /login\nGET /users\n";
+        assert_eq!(filter.apply(chunk), Some("GET /health\nGET /users\n".to_string()));
+        assert_eq!(filter.apply("POST /login\n"), None);
+    }
+
+    #[test]
+    fn test_log_search_pages_after_cursor() {
+        let mut search = LogSearch::new(LogFilter::new().with_grep("GET"), 10)
+            .with_cursor("2024-01-01T00:00:01.5Z#1")
+            .unwrap();
+        assert_eq!(search.since_timestamp(), 1_704_067_201);
+
+        // Lines the previous page already covered are skipped
+        assert!(search.accept("2024-01-01T00:00:01.200000000Z GET /old").is_none());
+        assert!(search.accept("2024-01-01T00:00:02.000000000Z POST /login").is_none());
+
+        let entry = search.accept("2024-01-01T00:00:02.000000000Z GET /users error").unwrap();
+        assert_eq!(entry.timestamp.as_deref(), Some("2024-01-01T00:00:02.000000000Z"));
+        assert_eq!(entry.line, "GET /users error");
+        assert_eq!(entry.level, LogLevel::Error);
+
+        assert!(LogSearch::new(LogFilter::new(), 10).with_cursor("yesterday").is_err());
+        assert!(LogSearch::new(LogFilter::new(), 10).with_cursor("2024-01-01T00:00:01Z#x").is_err());
+    }
+
+    #[test]
+    fn test_log_search_pages_through_shared_timestamp() {
+        let lines = [
+            "2024-01-01T00:00:01.000000000Z GET /a",
+            "2024-01-01T00:00:02.000000000Z GET /b",
+            "2024-01-01T00:00:02.000000000Z POST /c",
+            "2024-01-01T00:00:02.000000000Z GET /d",
+            "2024-01-01T00:00:02.000000000Z GET /e",
+            "2024-01-01T00:00:03.000000000Z GET /f",
+        ];
+
+        // Page through two entries at a time, as the orchestrator does
+        let mut seen = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut search = LogSearch::new(LogFilter::new().with_grep("GET"), 2);
+            if let Some(cursor) = &cursor {
+                search = search.with_cursor(cursor).unwrap();
+            }
+            let mut page = Vec::new();
+            let mut next = None;
+            let mut last = None;
+            for raw in lines {
+                let Some(entry) = search.accept(raw) else { continue };
+                if page.len() == search.limit() {
+                    next = last;
+                    break;
+                }
+                page.push(entry.line);
+                last = search.cursor();
+            }
+            seen.extend(page);
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(seen, vec!["GET /a", "GET /b", "GET /d", "GET /e", "GET /f"]);
+    }
+
+    #[test]
+    fn test_split_matches() {
+        let filter = LogFilter::new().with_grep("time(out)?");
+        assert_eq!(
+            filter.split_matches("timeout after 5s, retrying in time"),
+            vec![("timeout", true), (" after 5s, retrying in ", false), ("time", true)]
+        );
+        assert_eq!(LogFilter::new().split_matches("ready"), vec![("ready", false)]);
+        assert_eq!(LogFilter::new().with_grep("x*").split_matches("ready"), vec![("ready", false)]);
+    }
+
+    #[test]
+    fn test_prefix_lines() {
+        assert_eq!(prefix_lines("redis   ", "a\nb\n"), "redis    | a\nredis    | b\n");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = chrono::Utc::now().timestamp();
+        let ten_minutes_ago = parse_since("10m").unwrap();
+        assert!((now - 600 - ten_minutes_ago).abs() <= 1);
+        assert_eq!(parse_since("1700000000").unwrap(), 1_700_000_000);
+        assert_eq!(parse_since("2024-01-01T00:00:00Z").unwrap(), 1_704_067_200);
+        assert!(parse_since("10x").is_err());
+        assert!(parse_since("10é").is_err());
+        assert!(parse_since("999999999999999999d").is_err());
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::Result;
+use chrono::{Datelike, Timelike};
+use flate2::write::DeflateEncoder;
+use std::io::Write;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+/// General purpose flag marking file names as UTF-8
+const UTF8_FLAG: u16 = 0x0800;
+const DEFLATE: u16 = 8;
+
+/// Build a deflate-compressed ZIP archive from in-memory files
+///
+/// Only what log exports need: no ZIP64, so entries must stay under 4 GiB.
+pub fn build(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>> {
+    let (time, date) = dos_timestamp(chrono::Local::now().naive_local());
+    let mut archive = Vec::new();
+    let mut central = Vec::new();
+
+    for (name, data) in files {
+        let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+
+        let crc = crc32fast::hash(data);
+        let offset = u32::try_from(archive.len())?;
+        let compressed_size = u32::try_from(compressed.len())?;
+        let size = u32::try_from(data.len())?;
+        let name_len = u16::try_from(name.len())?;
+
+        put_u32(&mut archive, LOCAL_HEADER_SIGNATURE);
+        for value in [VERSION, UTF8_FLAG, DEFLATE, time, date] {
+            put_u16(&mut archive, value);
+        }
+        for value in [crc, compressed_size, size] {
+            put_u32(&mut archive, value);
+        }
+        put_u16(&mut archive, name_len);
+        put_u16(&mut archive, 0);
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(&compressed);
+
+        put_u32(&mut central, CENTRAL_HEADER_SIGNATURE);
+        for value in [VERSION, VERSION, UTF8_FLAG, DEFLATE, time, date] {
+            put_u16(&mut central, value);
+        }
+        for value in [crc, compressed_size, size] {
+            put_u32(&mut central, value);
+        }
+        // Name length, extra, comment, disk number and internal attributes
+        for value in [name_len, 0, 0, 0, 0] {
+            put_u16(&mut central, value);
+        }
+        put_u32(&mut central, 0);
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = u32::try_from(archive.len())?;
+    let central_size = u32::try_from(central.len())?;
+    let entries = u16::try_from(files.len())?;
+    archive.extend_from_slice(&central);
+
+    put_u32(&mut archive, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+    for value in [0, 0, entries, entries] {
+        put_u16(&mut archive, value);
+    }
+    put_u32(&mut archive, central_size);
+    put_u32(&mut archive, central_offset);
+    put_u16(&mut archive, 0);
+
+    Ok(archive)
+}
+
+/// MS-DOS time and date fields; the format cannot represent years before 1980
+fn dos_timestamp(now: chrono::NaiveDateTime) -> (u16, u16) {
+    let time = (now.hour() << 11) | (now.minute() << 5) | (now.second() / 2);
+    let date = ((now.year().max(1980) - 1980) as u32) << 9 | (now.month() << 5) | now.day();
+    (time as u16, date as u16)
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_build_archive() {
+        let content = b"postgres | ready to accept connections\n".repeat(20);
+        let archive = build(&[("postgres.log".to_string(), content.clone())]).unwrap();
+
+        assert_eq!(&archive[..4], &LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        assert_eq!(&archive[archive.len() - 22..archive.len() - 18], &END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+
+        // Data starts after the 30-byte local header and the file name
+        let compressed_size = u32::from_le_bytes(archive[18..22].try_into().unwrap()) as usize;
+        let data = &archive[30 + "postgres.log".len()..][..compressed_size];
+        let mut decoded = Vec::new();
+        DeflateDecoder::new(data).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, content);
+        assert_eq!(u32::from_le_bytes(archive[14..18].try_into().unwrap()), crc32fast::hash(&content));
+    }
+}
@@ -1,43 +1,87 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
-use zeroconfig::cli::{Cli, Commands};
+use tracing_subscriber::filter::{filter_fn, EnvFilter, FilterExt};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::prelude::*;
+use zeroconfig::cli::{Cli, Commands, ConfigCommands};
 use zeroconfig::commands;
-use tracing_subscriber;
+use zeroconfig::telemetry::Telemetry;
 
-#[tokio::main]
-async fn main() {
-    if let Err(e) = run().await {
+fn main() {
+    let cli = Cli::parse_args();
+
+    // The environment is set up before the runtime starts threads that read it
+    let result = prepare(&cli).and_then(|()| {
+        tokio::runtime::Runtime::new()
+            .context("Failed to start the async runtime")?
+            .block_on(run(cli))
+    });
+    if let Err(e) = result {
         eprintln!("{} {}", "Error:".red().bold(), e);
         std::process::exit(1);
     }
 }
 
-async fn run() -> Result<()> {
-    let cli = Cli::parse_args();
+/// Apply the global options that work through the process environment
+fn prepare(cli: &Cli) -> Result<()> {
+    // --verbose wins over RUST_LOG, which in turn wins over the default
+    if cli.verbose {
+        std::env::set_var("RUST_LOG", "debug");
+    } else if std::env::var_os("RUST_LOG").is_none() {
+        std::env::set_var("RUST_LOG", "info");
+    }
 
-    // Initialize logging
-    let log_level = if cli.verbose { "debug" } else { "info" };
-    std::env::set_var("RUST_LOG", log_level);
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(false)
-        .init();
+    // Stack config files; made absolute first so they are found from the project directory
+    if !cli.config_files.is_empty() {
+        let current_dir = std::env::current_dir()?;
+        let files = cli.config_files.iter().map(|file| current_dir.join(file));
+        let files = std::env::join_paths(files).context("Invalid --config-file path")?;
+        std::env::set_var(zeroconfig::config::overlay::CONFIG_FILES_ENV, files);
+    }
+
+    // A docker context stands in for DOCKER_HOST, as with the docker CLI
+    let context = cli.context.clone().or_else(|| std::env::var("DOCKER_CONTEXT").ok());
+    if let Some(context) = context.filter(|context| context != "default") {
+        let host = zeroconfig::orchestrator::docker_client::context_host(&context)?;
+        std::env::set_var("DOCKER_HOST", host);
+    }
 
     // Change to project directory if specified
     if let Some(ref dir) = cli.project_dir {
         std::env::set_current_dir(dir)
             .context("Failed to change to project directory")?;
     }
+    Ok(())
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    // Initialize logging; JSON output keeps stdout for the JSON
+    let writer = if cli.command.output().is_json() { BoxMakeWriter::new(std::io::stderr) } else { BoxMakeWriter::new(std::io::stdout) };
+    // Spans are only for trace export; logs stay a plain list of events
+    let logs = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_writer(writer)
+        .with_filter(EnvFilter::from_default_env().and(filter_fn(|metadata| {
+            // The monitor dashboard draws over the whole terminal, so events would garble it
+            metadata.is_event() && !zeroconfig::cli::dashboard::is_open()
+        })));
+    let telemetry = Telemetry::from_env()?;
+    tracing_subscriber::registry()
+        .with(logs)
+        .with(telemetry.as_ref().map(|telemetry| telemetry.layer()))
+        .init();
 
     match cli.command {
-        Commands::Init { name, template } => {
-            commands::init(name, template).await?;
+        Commands::Init { name, template, auto, yes } => {
+            commands::init(name, template, auto, yes).await?;
         }
-        Commands::Up { build, detach } => {
-            commands::up(build, detach).await?;
+        Commands::Up { build, detach, wait, wait_timeout, pull, runtime } => {
+            let wait_timeout = std::time::Duration::from_secs(wait_timeout);
+            commands::up(build, detach, wait, wait_timeout, pull, runtime.into()).await?;
         }
-        Commands::Down { volumes } => {
-            commands::down(volumes).await?;
+        Commands::Down { volumes, runtime } => {
+            commands::down(volumes, runtime.into()).await?;
         }
         Commands::Start { service } => {
             commands::start_service(service).await?;
@@ -48,11 +92,12 @@ async fn run() -> Result<()> {
         Commands::BuildEnv => {
             commands::build_env().await?;
         }
-        Commands::Doctor => {
-            commands::doctor().await?;
+        Commands::Doctor { fix, output } => {
+            commands::doctor(fix, output).await?;
         }
-        Commands::Logs { service, follow, tail } => {
-            commands::logs(service, follow, tail).await?;
+        Commands::Logs { services, all, follow, tail, grep, since, level } => {
+            let filter = zeroconfig::logs::LogFilter::from_args(grep.as_deref(), since.as_deref(), level.as_deref())?;
+            commands::logs(services, all, follow, tail, filter).await?;
         }
         Commands::Cloud { action } => {
             commands::cloud(action).await?;
@@ -63,30 +108,66 @@ async fn run() -> Result<()> {
         Commands::Exec { service, command } => {
             commands::exec(service, command).await?;
         }
-        Commands::Monitor { interval } => {
-            commands::monitor(interval).await?;
+        Commands::Monitor { serve: Some(address), .. } => {
+            commands::monitor_serve(address).await?;
         }
-        Commands::Ps => {
-            commands::ps().await?;
+        Commands::Monitor { interval, serve: None, output } => {
+            commands::monitor(interval, output).await?;
         }
-        Commands::Restart { services } => {
-            commands::restart(services).await?;
+        Commands::Ps { all, watch, output } => {
+            commands::ps(all, watch, output).await?;
         }
-        Commands::Env { format } => {
-            commands::env(format).await?;
+        Commands::Restart { services, rolling, timeout } => {
+            commands::restart(services, rolling, timeout).await?;
+        }
+        Commands::Env { format, output } => {
+            commands::env(format, output).await?;
         }
         Commands::Generate { target } => {
             commands::generate(target).await?;
         }
-        Commands::Health { service, wait, timeout } => {
-            commands::health(service, wait, timeout).await?;
+        Commands::Health { service, wait, timeout, output } => {
+            commands::health(service, wait, timeout, output).await?;
+        }
+        Commands::Backup { daemon: true, .. } => {
+            commands::backup_daemon().await?;
+        }
+        Commands::Backup { service, all, dir, keep, daemon: false } => {
+            commands::backup(service, all, dir, keep).await?;
+        }
+        Commands::Config { action: ConfigCommands::Lint { strict, output } } => {
+            commands::config_lint(strict, output).await?;
+        }
+        Commands::Config { action: ConfigCommands::Set { key, value } } => {
+            commands::config_set(key, value).await?;
         }
-        Commands::Backup { service, output } => {
-            commands::backup(service, output).await?;
+        Commands::UpgradeConfig { dry_run } => {
+            commands::upgrade_config(dry_run).await?;
+        }
+        Commands::Secrets { action } => {
+            commands::secrets(action).await?;
         }
         Commands::Restore { service, file } => {
             commands::restore(service, file).await?;
         }
+        Commands::Expose { service, provider } => {
+            commands::expose(service, provider).await?;
+        }
+        Commands::Share { service, provider, stop } => {
+            commands::share(service, provider, stop).await?;
+        }
+        Commands::Proxy { action } => {
+            commands::proxy(action).await?;
+        }
+        Commands::Open { target } => {
+            commands::open(target).await?;
+        }
+        Commands::Dev { ignore, debounce } => {
+            commands::dev(ignore, debounce).await?;
+        }
+        Commands::Watch { command, paths, ignore, restart, debounce } => {
+            commands::watch(command, paths, ignore, restart, debounce).await?;
+        }
     }
 
     Ok(())
@@ -0,0 +1,191 @@
+//! Prometheus endpoint for the project's containers
+//!
+//! `zero monitor --serve :9464`, or `metrics:` in zero.yml while `zero up`
+//! runs, serves the container stats the engine already collects at
+//! `/metrics`. Series use cAdvisor's names and `name` label, so dashboards
+//! built for cAdvisor work unchanged, plus `project` and `service` labels.
+
+use anyhow::{Context, Result};
+use bollard::container::{MemoryStatsStats, Stats};
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, warn};
+
+use crate::core::Engine;
+use crate::orchestrator::overview::cpu_percent;
+
+/// Content type of the Prometheus text format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How long a scraper gets to send its request and read the answer
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What is exported for one container
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContainerMetrics {
+    /// Container name, as cAdvisor's `name` label
+    pub name: String,
+    pub cpu_seconds: f64,
+    pub cpu_percent: f64,
+    pub memory_usage: u64,
+    /// Memory in use minus reclaimable page cache, what the kernel counts against limits
+    pub memory_working_set: u64,
+    pub memory_limit: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+impl ContainerMetrics {
+    pub fn from_stats(name: &str, stats: &Stats) -> Self {
+        let usage = stats.memory_stats.usage.unwrap_or(0);
+        let inactive_file = match stats.memory_stats.stats {
+            Some(MemoryStatsStats::V1(v1)) => v1.total_inactive_file,
+            Some(MemoryStatsStats::V2(v2)) => v2.inactive_file,
+            None => 0,
+        };
+        let (rx_bytes, tx_bytes) = stats.networks.iter()
+            .flat_map(|networks| networks.values())
+            .fold((0, 0), |(rx, tx), network| (rx + network.rx_bytes, tx + network.tx_bytes));
+
+        Self {
+            name: name.to_string(),
+            cpu_seconds: stats.cpu_stats.cpu_usage.total_usage as f64 / 1e9,
+            cpu_percent: cpu_percent(stats),
+            memory_usage: usage,
+            memory_working_set: usage.saturating_sub(inactive_file),
+            memory_limit: stats.memory_stats.limit.unwrap_or(0),
+            rx_bytes,
+            tx_bytes,
+        }
+    }
+}
+
+/// Address to listen on; `:9464` or a bare port listens on localhost only, as
+/// the metrics are served without authentication. `0.0.0.0:9464` listens on
+/// every interface, e.g. for a Prometheus in a container.
+pub fn parse_address(value: &str) -> Result<SocketAddr> {
+    let value = value.trim();
+    let port = value.strip_prefix(':').unwrap_or(value);
+    if let Ok(port) = port.parse::<u16>() {
+        return Ok(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port));
+    }
+    value.parse().with_context(|| format!("'{}' is not an address like :9464 or 0.0.0.0:9464", value))
+}
+
+/// The metrics of a project's containers in the Prometheus text format
+pub fn render(project: &str, containers: &[ContainerMetrics]) -> String {
+    type Value = fn(&ContainerMetrics) -> String;
+    let families: [(&str, &str, &str, Value); 7] = [
+        ("container_cpu_usage_seconds_total", "counter", "CPU time consumed, in seconds", |m| m.cpu_seconds.to_string()),
+        ("zeroconfig_container_cpu_percent", "gauge", "CPU use over the last sample, in percent of one core", |m| format!("{:.2}", m.cpu_percent)),
+        ("container_memory_usage_bytes", "gauge", "Memory in use, including page cache", |m| m.memory_usage.to_string()),
+        ("container_memory_working_set_bytes", "gauge", "Memory in use, without reclaimable page cache", |m| m.memory_working_set.to_string()),
+        ("container_spec_memory_limit_bytes", "gauge", "Memory limit", |m| m.memory_limit.to_string()),
+        ("container_network_receive_bytes_total", "counter", "Bytes received over all networks", |m| m.rx_bytes.to_string()),
+        ("container_network_transmit_bytes_total", "counter", "Bytes sent over all networks", |m| m.tx_bytes.to_string()),
+    ];
+
+    let prefix = format!("{}_", project);
+    let mut text = String::new();
+    for (metric, kind, help, value) in families {
+        let _ = writeln!(text, "# HELP {} {}", metric, help);
+        let _ = writeln!(text, "# TYPE {} {}", metric, kind);
+        for container in containers {
+            let service = container.name.strip_prefix(&prefix).unwrap_or(&container.name);
+            let _ = writeln!(
+                text,
+                "{}{{name=\"{}\",project=\"{}\",service=\"{}\"}} {}",
+                metric, escape(&container.name), escape(project), escape(service), value(container),
+            );
+        }
+    }
+    text
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+/// Answer scrapes of `/metrics` until the future is dropped
+///
+/// Scrapes are served one at a time, each collecting fresh stats.
+pub async fn serve(engine: &Engine, address: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(address).await
+        .with_context(|| format!("Failed to serve metrics on {}", address))?;
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        match tokio::time::timeout(SCRAPE_TIMEOUT, answer(engine, stream)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => debug!("Metrics request from {} failed: {:#}", peer, e),
+            Err(_) => debug!("Metrics request from {} timed out", peer),
+        }
+    }
+}
+
+async fn answer(engine: &Engine, mut stream: TcpStream) -> Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        anyhow::ensure!(read > 0 && request.len() < 16 * 1024, "Incomplete request");
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let path = request.split_whitespace().nth(1).unwrap_or_default();
+    let (status, content_type, body) = match path.split('?').next() {
+        Some("/metrics") => {
+            let containers: Vec<ContainerMetrics> = match engine.get_all_stats().await {
+                Ok(stats) => stats.iter().map(|(name, stats)| ContainerMetrics::from_stats(name, stats)).collect(),
+                Err(e) => {
+                    warn!("Failed to collect container stats: {:#}", e);
+                    Vec::new()
+                }
+            };
+            ("200 OK", CONTENT_TYPE, render(engine.project_name(), &containers))
+        }
+        _ => ("404 Not Found", "text/plain", "Metrics are served at /metrics\n".to_string()),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len(),
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_parse_address() {
+        let postgres = ContainerMetrics {
+            name: "shop_postgres".to_string(),
+            cpu_seconds: 12.5,
+            cpu_percent: 2.5,
+            memory_usage: 2048,
+            memory_working_set: 1024,
+            ..Default::default()
+        };
+        let text = render("shop", &[postgres]);
+        assert!(text.starts_with("# HELP container_cpu_usage_seconds_total CPU time consumed, in seconds\n# TYPE container_cpu_usage_seconds_total counter\n"));
+        assert!(text.contains("\ncontainer_cpu_usage_seconds_total{name=\"shop_postgres\",project=\"shop\",service=\"postgres\"} 12.5\n"));
+        assert!(text.contains("zeroconfig_container_cpu_percent{name=\"shop_postgres\",project=\"shop\",service=\"postgres\"} 2.50\n"));
+        assert!(text.contains("container_memory_working_set_bytes{name=\"shop_postgres\",project=\"shop\",service=\"postgres\"} 1024\n"));
+
+        assert_eq!(parse_address(":9464").unwrap(), "127.0.0.1:9464".parse().unwrap());
+        assert_eq!(parse_address("9464").unwrap(), "127.0.0.1:9464".parse().unwrap());
+        assert_eq!(parse_address("0.0.0.0:9464").unwrap(), "0.0.0.0:9464".parse().unwrap());
+        assert_eq!(parse_address("127.0.0.1:9000").unwrap(), "127.0.0.1:9000".parse().unwrap());
+        assert!(parse_address("localhost").is_err());
+    }
+}
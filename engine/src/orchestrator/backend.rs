@@ -0,0 +1,169 @@
+//! Container engines the orchestrator can drive
+//!
+//! Docker and Podman both serve the Docker Engine API, so a backend only
+//! decides where to find that API and smooths over the engines' differences.
+
+use anyhow::Result;
+use bollard::Docker;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::docker_client;
+use crate::runtime::{ContainerRuntime, ContainerRuntimeManager};
+
+/// A container engine serving the Docker Engine API
+pub trait ContainerBackend: Send + Sync {
+    fn runtime(&self) -> ContainerRuntime;
+
+    /// Environment variable that points at the engine's API, honored first
+    fn host_var(&self) -> &'static str;
+
+    /// Local API endpoints, in the order they are tried
+    fn endpoints(&self) -> Vec<String>;
+
+    /// Image reference the engine resolves the same way Docker would
+    fn qualify_image(&self, image: &str) -> String {
+        image.to_string()
+    }
+
+    /// Connect to the engine; the connection is made on first request
+    fn connect(&self) -> Result<Docker> {
+        if let Ok(host) = std::env::var(self.host_var()) {
+            return docker_client::connect_to(&host);
+        }
+        match self.endpoints().into_iter().find(|endpoint| docker_client::endpoint_exists(endpoint)) {
+            Some(endpoint) => docker_client::connect_to(&endpoint),
+            None => anyhow::bail!("{} is not running: none of {} exist", self.runtime().name(), self.endpoints().join(", ")),
+        }
+    }
+}
+
+pub struct DockerBackend;
+
+impl ContainerBackend for DockerBackend {
+    fn runtime(&self) -> ContainerRuntime {
+        ContainerRuntime::Docker
+    }
+
+    fn host_var(&self) -> &'static str {
+        "DOCKER_HOST"
+    }
+
+    fn endpoints(&self) -> Vec<String> {
+        if cfg!(windows) {
+            return vec!["npipe:////./pipe/docker_engine".to_string()];
+        }
+        let mut endpoints = vec!["unix:///var/run/docker.sock".to_string()];
+        if let Some(home) = std::env::var_os("HOME") {
+            // Docker Desktop on macOS without the privileged socket link
+            endpoints.push(unix_endpoint(PathBuf::from(home).join(".docker/run/docker.sock")));
+        }
+        endpoints
+    }
+}
+
+/// Podman through its Docker-compatible API service (`podman system service`,
+/// or the socket `podman machine` forwards)
+pub struct PodmanBackend;
+
+impl ContainerBackend for PodmanBackend {
+    fn runtime(&self) -> ContainerRuntime {
+        ContainerRuntime::Podman
+    }
+
+    /// Podman's own variable, e.g. `unix:///run/user/1000/podman/podman.sock` or `tcp://host:8888`
+    fn host_var(&self) -> &'static str {
+        "CONTAINER_HOST"
+    }
+
+    fn endpoints(&self) -> Vec<String> {
+        if cfg!(windows) {
+            return vec!["npipe:////./pipe/podman-machine-default".to_string()];
+        }
+        let mut endpoints = Vec::new();
+        if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+            endpoints.push(unix_endpoint(PathBuf::from(runtime_dir).join("podman/podman.sock")));
+        }
+        endpoints.push("unix:///run/podman/podman.sock".to_string());
+        if let Some(home) = std::env::var_os("HOME") {
+            // `podman machine` on macOS
+            let machine = PathBuf::from(home).join(".local/share/containers/podman/machine");
+            endpoints.push(unix_endpoint(machine.join("podman.sock")));
+            endpoints.push(unix_endpoint(machine.join("podman-machine-default/podman.sock")));
+        }
+        endpoints
+    }
+
+    /// Fully qualify short names, which Podman may refuse to resolve without a TTY
+    fn qualify_image(&self, image: &str) -> String {
+        let first = image.split('/').next().unwrap_or_default();
+        let has_registry = image.contains('/') && (first.contains('.') || first.contains(':') || first == "localhost");
+        match (has_registry, image.contains('/')) {
+            (true, _) => image.to_string(),
+            (false, true) => format!("docker.io/{}", image),
+            (false, false) => format!("docker.io/library/{}", image),
+        }
+    }
+}
+
+fn unix_endpoint(path: PathBuf) -> String {
+    format!("unix://{}", path.display())
+}
+
+/// Backend for a runtime; everything but Podman is reached like Docker
+pub fn backend_for(runtime: ContainerRuntime) -> Arc<dyn ContainerBackend> {
+    match runtime {
+        ContainerRuntime::Podman => Arc::new(PodmanBackend),
+        _ => Arc::new(DockerBackend),
+    }
+}
+
+/// Pick the backend to use
+///
+/// `DOCKER_HOST` or `CONTAINER_HOST` decide when set, then whichever engine
+/// alone has an API endpoint. With both or neither, the preferred runtime of
+/// [`ContainerRuntimeManager`] decides, Docker when no runtime CLI is found.
+pub async fn select() -> Arc<dyn ContainerBackend> {
+    if std::env::var_os("DOCKER_HOST").is_some() {
+        return Arc::new(DockerBackend);
+    }
+    if std::env::var_os("CONTAINER_HOST").is_some() {
+        return Arc::new(PodmanBackend);
+    }
+
+    let listening = |backend: &dyn ContainerBackend| {
+        backend.endpoints().iter().any(|endpoint| docker_client::endpoint_exists(endpoint))
+    };
+    let backend = match (listening(&DockerBackend), listening(&PodmanBackend)) {
+        (true, false) => backend_for(ContainerRuntime::Docker),
+        (false, true) => backend_for(ContainerRuntime::Podman),
+        _ => {
+            let mut manager = ContainerRuntimeManager::new();
+            match manager.detect_runtimes().await.and_then(|_| manager.get_preferred_runtime()) {
+                Ok(runtime) => backend_for(runtime),
+                Err(e) => {
+                    debug!("No container runtime detected: {}", e);
+                    backend_for(ContainerRuntime::Docker)
+                }
+            }
+        }
+    };
+    debug!("Using the {} backend", backend.runtime().name());
+    backend
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_podman_qualifies_short_names() {
+        assert_eq!(PodmanBackend.qualify_image("postgres:15"), "docker.io/library/postgres:15");
+        assert_eq!(PodmanBackend.qualify_image("minio/minio:latest"), "docker.io/minio/minio:latest");
+        assert_eq!(PodmanBackend.qualify_image("gcr.io/cadvisor/cadvisor:v0.49.1"), "gcr.io/cadvisor/cadvisor:v0.49.1");
+        assert_eq!(PodmanBackend.qualify_image("localhost/app:dev"), "localhost/app:dev");
+        assert_eq!(DockerBackend.qualify_image("postgres:15"), "postgres:15");
+        assert!(PodmanBackend.endpoints().iter().all(|endpoint| endpoint.contains("podman")));
+    }
+}
@@ -0,0 +1,213 @@
+//! Building application images from a local Dockerfile
+//!
+//! The build context is packed into a tar archive, minus whatever
+//! `.dockerignore` excludes, and sent to the engine's build API.
+
+use anyhow::{Context, Result};
+use bollard::image::BuildImageOptions;
+use futures::StreamExt;
+use globset::{GlobBuilder, GlobMatcher};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, instrument};
+
+use super::sidecar::{tar_entry, tar_finish, tar_symlink};
+use super::{ContainerOrchestrator, PROJECT_LABEL, SERVICE_LABEL};
+use crate::config::BuildConfig;
+
+/// Tag of the image built for a service of a project
+pub fn built_image_name(project_name: &str, service_name: &str) -> String {
+    let name: String = format!("{}-{}", project_name, service_name)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+        .collect();
+    format!("zeroconfig/{}:dev", name)
+}
+
+impl ContainerOrchestrator {
+    /// Tag of the image built for a service with `build:`
+    pub fn built_image(&self, service_name: &str) -> String {
+        built_image_name(&self.project_name, service_name)
+    }
+
+    /// Whether an image is present locally
+    pub async fn image_exists(&self, image: &str) -> bool {
+        self.docker.inspect_image(image).await.is_ok()
+    }
+
+    /// Build a service's image from its context directory, printing the build output
+    #[instrument(name = "docker_build", skip(self, build))]
+    pub async fn build_image(&self, service_name: &str, build: &BuildConfig, context: &Path) -> Result<String> {
+        let image = self.built_image(service_name);
+        let archive = context_archive(context, build.dockerfile())
+            .with_context(|| format!("Failed to pack the build context {}", context.display()))?;
+        info!("Building {} from {} ({} KB context)", image, context.display(), archive.len() / 1024);
+
+        let options = BuildImageOptions {
+            dockerfile: build.dockerfile().to_string(),
+            t: image.clone(),
+            buildargs: build.args.clone(),
+            rm: true,
+            labels: HashMap::from([
+                (PROJECT_LABEL.to_string(), self.project_name.clone()),
+                (SERVICE_LABEL.to_string(), service_name.to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        let mut stream = self.docker.build_image(options, None, Some(archive.into()));
+        while let Some(output) = stream.next().await {
+            let output = output.with_context(|| format!("Failed to build the image of {}", service_name))?;
+            if let Some(error) = output.error {
+                anyhow::bail!("Building the image of {} failed: {}", service_name, error.trim());
+            }
+            if let Some(line) = output.stream.as_deref().map(str::trim_end).filter(|line| !line.is_empty()) {
+                info!("{}", line);
+            }
+        }
+
+        Ok(image)
+    }
+}
+
+/// Pack a build context, skipping what `.dockerignore` excludes
+///
+/// The Dockerfile is always sent, as Docker does.
+fn context_archive(dir: &Path, dockerfile: &str) -> Result<Vec<u8>> {
+    let ignore = DockerIgnore::load(dir)?;
+    let mut archive = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let mut entries: Vec<_> = std::fs::read_dir(&current)
+            .with_context(|| format!("Failed to read {}", current.display()))?
+            .collect::<std::io::Result<_>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let relative = path.strip_prefix(dir)?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if ignore.is_ignored(&relative) && relative != dockerfile {
+                continue;
+            }
+
+            // Symlinks are sent as links, like the docker CLI does for the context
+            let metadata = std::fs::symlink_metadata(&path)?;
+            if metadata.is_symlink() {
+                let target = std::fs::read_link(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+                tar_symlink(&mut archive, &relative, &target.to_string_lossy())?;
+            } else if metadata.is_dir() {
+                pending.push(path);
+            } else if metadata.is_file() {
+                let content = std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+                tar_entry(&mut archive, &relative, file_mode(&metadata), 0, &content)?;
+            }
+        }
+    }
+
+    Ok(tar_finish(archive))
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &std::fs::Metadata) -> u32 {
+    0o644
+}
+
+/// Patterns of a `.dockerignore`; the last matching one decides, `!` re-includes
+struct DockerIgnore {
+    rules: Vec<(GlobMatcher, bool)>,
+}
+
+impl DockerIgnore {
+    fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(".dockerignore");
+        let content = if path.exists() { std::fs::read_to_string(&path)? } else { String::new() };
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let (pattern, include) = match line.strip_prefix('!') {
+                Some(pattern) => (pattern.trim(), true),
+                None => (line, false),
+            };
+            let pattern = pattern.trim_start_matches("./").trim_start_matches('/').trim_end_matches('/');
+            // A pattern matching a directory excludes everything in it
+            for glob in [pattern.to_string(), format!("{}/**", pattern)] {
+                let matcher = GlobBuilder::new(&glob)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| format!("Invalid .dockerignore pattern: {}", line))?
+                    .compile_matcher();
+                rules.push((matcher, include));
+            }
+        }
+        Ok(Self { rules })
+    }
+
+    fn is_ignored(&self, path: &str) -> bool {
+        self.rules.iter()
+            .rev()
+            .find(|(matcher, _)| matcher.is_match(path))
+            .is_some_and(|(_, include)| !include)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_archive_honors_dockerignore() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("node_modules/react")).unwrap();
+        std::fs::write(dir.join("Dockerfile"), "FROM node:20\n").unwrap();
+        std::fs::write(dir.join(".dockerignore"), "# deps\nnode_modules\n*.log\n!keep.log\nDockerfile\n").unwrap();
+        std::fs::write(dir.join("src/index.js"), "console.log('hi')\n").unwrap();
+        std::fs::write(dir.join("node_modules/react/index.js"), "").unwrap();
+        std::fs::write(dir.join("debug.log"), "").unwrap();
+        std::fs::write(dir.join("keep.log"), "").unwrap();
+
+        let archive = context_archive(dir, "Dockerfile").unwrap();
+        let names: Vec<String> = archive.chunks(512)
+            .filter(|block| &block[257..262] == b"ustar")
+            .map(|block| String::from_utf8_lossy(&block[..100]).trim_end_matches('\0').to_string())
+            .collect();
+        assert_eq!(names, vec![".dockerignore", "Dockerfile", "keep.log", "src/index.js"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_context_archive_keeps_symlinks() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/index.js"), "").unwrap();
+        // A link back up the tree would loop forever if followed
+        std::os::unix::fs::symlink("..", dir.join("src/parent")).unwrap();
+
+        let archive = context_archive(dir, "Dockerfile").unwrap();
+        let links: Vec<_> = archive.chunks(512)
+            .filter(|block| &block[257..262] == b"ustar" && block[156] == b'2')
+            .map(|block| (
+                String::from_utf8_lossy(&block[..100]).trim_end_matches('\0').to_string(),
+                String::from_utf8_lossy(&block[157..257]).trim_end_matches('\0').to_string(),
+            ))
+            .collect();
+        assert_eq!(links, vec![("src/parent".to_string(), "..".to_string())]);
+    }
+}
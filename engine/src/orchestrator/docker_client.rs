@@ -1,2 +1,243 @@
-// Docker client utilities module
-// This module provides helper functions for Docker operations
+//! Connecting to the container engine's API
+//!
+//! Everything talks to Docker (or Podman's Docker-compatible API) through
+//! bollard, so no `docker` CLI is needed. `DOCKER_HOST` (or Podman's
+//! `CONTAINER_HOST`) wins when set, including `npipe://` addresses; otherwise
+//! the first local endpoint that exists is used, Docker's before Podman's.
+//!
+//! Engines on other machines are reached at `tcp://host:2375` or, like the
+//! docker CLI does, at `ssh://[user@]host`: an `ssh` process forwards a local
+//! port to the remote engine's socket for as long as we run. `--context`
+//! picks the address of a `docker context`.
+
+use anyhow::{Context, Result};
+use bollard::{Docker, API_DEFAULT_VERSION};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::backend::{ContainerBackend, DockerBackend, PodmanBackend};
+
+/// Request timeout in seconds, bollard's default
+const TIMEOUT: u64 = 120;
+
+/// Socket of the engine on the far side of an `ssh://` address without a path
+const REMOTE_SOCKET: &str = "/var/run/docker.sock";
+
+/// How long an SSH tunnel may take to come up
+const TUNNEL_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Open SSH tunnels by address, with the local endpoint they serve
+static TUNNELS: Mutex<Option<HashMap<String, (String, Child)>>> = Mutex::new(None);
+
+/// `runtime.host` of zero.yml, unless `DOCKER_HOST` or `CONTAINER_HOST` already pick the engine
+pub fn configured_host(host: Option<&str>) -> Option<&str> {
+    host.filter(|_| std::env::var_os("DOCKER_HOST").is_none() && std::env::var_os("CONTAINER_HOST").is_none())
+}
+
+/// Connect to the engine at `runtime.host` of zero.yml, else as [`connect`] does
+pub fn connect_with_host(host: Option<&str>) -> Result<Docker> {
+    match configured_host(host) {
+        Some(host) => connect_to(host),
+        None => connect(),
+    }
+}
+
+/// Connect to the container engine; the connection is made on first request
+pub fn connect() -> Result<Docker> {
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        return connect_to(&host);
+    }
+    if let Ok(host) = std::env::var("CONTAINER_HOST") {
+        return connect_to(&host);
+    }
+
+    match local_endpoints().into_iter().find(|endpoint| endpoint_exists(endpoint)) {
+        Some(endpoint) => connect_to(&endpoint),
+        None => Docker::connect_with_local_defaults().context("Failed to connect to Docker"),
+    }
+}
+
+/// Connect to an endpoint such as `unix:///var/run/docker.sock`, `npipe:////./pipe/docker_engine`,
+/// `tcp://host:2375` or `ssh://user@host`
+pub fn connect_to(endpoint: &str) -> Result<Docker> {
+    let docker = match endpoint.split_once("://").map(|(scheme, _)| scheme) {
+        Some("unix") | Some("npipe") => Docker::connect_with_local(endpoint, TIMEOUT, API_DEFAULT_VERSION),
+        Some("tcp") | Some("http") if std::env::var_os("DOCKER_TLS_VERIFY").is_some_and(|verify| !verify.is_empty()) => {
+            anyhow::bail!("TLS connections to {} are not supported; reach the engine over ssh:// instead", endpoint)
+        }
+        Some("tcp") | Some("http") => Docker::connect_with_http(endpoint, TIMEOUT, API_DEFAULT_VERSION),
+        Some("ssh") => Docker::connect_with_http(&ssh_tunnel(endpoint)?, TIMEOUT, API_DEFAULT_VERSION),
+        _ => anyhow::bail!("Unsupported container engine address '{}'", endpoint),
+    };
+    docker.with_context(|| format!("Failed to connect to {}", endpoint))
+}
+
+/// Host that published ports are reached on: the remote machine of `DOCKER_HOST`, else localhost
+pub fn published_host() -> String {
+    published_host_at(None)
+}
+
+/// Host that published ports of the engine at `host` are reached on, falling back to [`published_host`]
+pub fn published_host_at(host: Option<&str>) -> String {
+    host.map(str::to_string).or_else(|| std::env::var("DOCKER_HOST").ok())
+        .and_then(|endpoint| remote_host(&endpoint))
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// Machine of a `tcp://` or `ssh://` address, `None` for local sockets
+fn remote_host(endpoint: &str) -> Option<String> {
+    let (scheme, rest) = endpoint.split_once("://")?;
+    if !matches!(scheme, "tcp" | "http" | "ssh") {
+        return None;
+    }
+    let authority = rest.split('/').next()?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next()?,
+        None => host.split(':').next()?,
+    };
+    Some(host.to_string()).filter(|host| !matches!(host.as_str(), "" | "localhost" | "127.0.0.1" | "::1"))
+}
+
+/// Destination, port and engine socket of an `ssh://[user@]host[:port][/socket]` address
+fn parse_ssh(endpoint: &str) -> Result<(String, Option<String>, String)> {
+    let rest = endpoint.strip_prefix("ssh://").context("Not an ssh:// address")?;
+    let (authority, socket) = match rest.find('/') {
+        Some(slash) => (&rest[..slash], &rest[slash..]),
+        None => (rest, REMOTE_SOCKET),
+    };
+    let (destination, port) = match authority.rsplit_once(':') {
+        Some((destination, port)) if port.parse::<u16>().is_ok() => (destination, Some(port.to_string())),
+        _ => (authority, None),
+    };
+    if destination.is_empty() {
+        anyhow::bail!("No host in {}", endpoint);
+    }
+    Ok((destination.to_string(), port, socket.to_string()))
+}
+
+/// Local address forwarding to the engine behind an `ssh://` address, opening the tunnel once
+///
+/// The remote side runs `cat`, which ends when our end of its stdin closes, so
+/// the tunnel goes away with this process however it exits.
+fn ssh_tunnel(endpoint: &str) -> Result<String> {
+    let mut tunnels = TUNNELS.lock().unwrap_or_else(|e| e.into_inner());
+    let tunnels = tunnels.get_or_insert_with(HashMap::new);
+    if let Some((local, child)) = tunnels.get_mut(endpoint) {
+        if child.try_wait().ok().flatten().is_none() {
+            return Ok(local.clone());
+        }
+    }
+
+    let (destination, port, socket) = parse_ssh(endpoint)?;
+    let local_port = portpicker::pick_unused_port().context("No free local port for the SSH tunnel")?;
+    let mut command = Command::new("ssh");
+    command.args(["-o", "ExitOnForwardFailure=yes", "-o", "BatchMode=yes", "-o", "ServerAliveInterval=30"])
+        .arg("-L")
+        .arg(format!("127.0.0.1:{}:{}", local_port, socket));
+    if let Some(port) = port {
+        command.args(["-p", &port]);
+    }
+    let mut child = command.arg(&destination)
+        .arg("cat > /dev/null")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("ssh is needed to reach a container engine at an ssh:// address")?;
+
+    let start = Instant::now();
+    while TcpStream::connect(("127.0.0.1", local_port)).is_err() {
+        if let Some(status) = child.try_wait()? {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                let _ = pipe.read_to_string(&mut stderr);
+            }
+            anyhow::bail!("ssh to {} exited with {}: {}", destination, status, stderr.trim());
+        }
+        if start.elapsed() > TUNNEL_TIMEOUT {
+            let _ = child.kill();
+            anyhow::bail!("Timed out opening an SSH tunnel to {}", destination);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    let local = format!("tcp://127.0.0.1:{}", local_port);
+    tunnels.insert(endpoint.to_string(), (local.clone(), child));
+    Ok(local)
+}
+
+/// Address of the engine a `docker context` points at
+pub fn context_host(name: &str) -> Result<String> {
+    let config_dir = std::env::var_os("DOCKER_CONFIG").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(|home| PathBuf::from(home).join(".docker")))
+        .context("No home directory to find docker contexts in")?;
+    let contexts = config_dir.join("contexts").join("meta");
+
+    for entry in std::fs::read_dir(&contexts).into_iter().flatten().flatten() {
+        let Ok(content) = std::fs::read_to_string(entry.path().join("meta.json")) else { continue };
+        let Ok(meta) = serde_json::from_str::<serde_json::Value>(&content) else { continue };
+        if meta["Name"] == name {
+            return meta["Endpoints"]["docker"]["Host"].as_str()
+                .map(str::to_string)
+                .with_context(|| format!("Docker context {} has no engine address", name));
+        }
+    }
+    anyhow::bail!("No docker context named {}; see `docker context ls`", name)
+}
+
+/// Local API endpoints of every backend, in the order they are tried
+pub fn local_endpoints() -> Vec<String> {
+    let mut endpoints = DockerBackend.endpoints();
+    endpoints.extend(PodmanBackend.endpoints());
+    endpoints
+}
+
+/// Whether a socket or named pipe is there to connect to
+pub fn endpoint_exists(endpoint: &str) -> bool {
+    match endpoint.split_once("://") {
+        Some(("unix", path)) => PathBuf::from(path).exists(),
+        // `//./pipe/name` is `\\.\pipe\name`, which Windows reports as existing while the engine listens
+        Some(("npipe", path)) => PathBuf::from(path.replace('/', "\\")).exists(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_endpoints() {
+        let endpoints = local_endpoints();
+        if cfg!(windows) {
+            assert_eq!(endpoints[0], "npipe:////./pipe/docker_engine");
+            assert!(endpoints[1].ends_with("podman-machine-default"));
+        } else {
+            assert_eq!(endpoints[0], "unix:///var/run/docker.sock");
+            assert!(endpoints.iter().any(|endpoint| endpoint.ends_with("podman.sock")));
+        }
+        assert!(!endpoint_exists("unix:///nonexistent/docker.sock"));
+        assert!(!endpoint_exists("tcp://localhost:2375"));
+        assert!(connect_to("ftp://dev-box").is_err());
+    }
+
+    #[test]
+    fn test_remote_addresses() {
+        assert_eq!(parse_ssh("ssh://dev-box").unwrap(), ("dev-box".to_string(), None, REMOTE_SOCKET.to_string()));
+        assert_eq!(
+            parse_ssh("ssh://me@dev-box:2222/run/user/1000/docker.sock").unwrap(),
+            ("me@dev-box".to_string(), Some("2222".to_string()), "/run/user/1000/docker.sock".to_string())
+        );
+        assert_eq!(remote_host("ssh://me@dev-box:2222").as_deref(), Some("dev-box"));
+        assert_eq!(remote_host("tcp://10.0.0.5:2375").as_deref(), Some("10.0.0.5"));
+        assert_eq!(remote_host("tcp://127.0.0.1:2375"), None);
+        assert_eq!(remote_host("unix:///var/run/docker.sock"), None);
+        assert_eq!(published_host_at(Some("ssh://me@dev-box")), "dev-box");
+    }
+}
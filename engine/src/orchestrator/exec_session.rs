@@ -0,0 +1,58 @@
+use anyhow::Result;
+use bollard::Docker;
+use bollard::exec::ResizeExecOptions;
+use futures::stream::BoxStream;
+use std::pin::Pin;
+use tokio::io::AsyncWrite;
+
+/// An interactive exec running with a TTY inside a service container
+///
+/// `output` yields raw terminal bytes and `input` accepts keystrokes, which
+/// makes it suitable for driving an in-app terminal emulator.
+pub struct ExecSession {
+    pub output: BoxStream<'static, Result<Vec<u8>>>,
+    pub input: Pin<Box<dyn AsyncWrite + Send>>,
+    pub control: ExecControl,
+}
+
+/// A chunk of output from a non-interactive exec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecOutput {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+}
+
+/// Cloneable handle for resizing and inspecting an exec session
+#[derive(Clone)]
+pub struct ExecControl {
+    docker: Docker,
+    exec_id: String,
+}
+
+impl ExecControl {
+    pub(crate) fn new(docker: Docker, exec_id: String) -> Self {
+        Self { docker, exec_id }
+    }
+
+    /// Docker's identifier for the exec instance
+    pub fn id(&self) -> &str {
+        &self.exec_id
+    }
+
+    /// Resize the session's TTY
+    pub async fn resize(&self, cols: u16, rows: u16) -> Result<()> {
+        self.docker
+            .resize_exec(&self.exec_id, ResizeExecOptions { width: cols, height: rows })
+            .await?;
+        Ok(())
+    }
+
+    /// Exit code of the command, or `None` while it is still running
+    pub async fn exit_code(&self) -> Result<Option<i64>> {
+        let inspect = self.docker.inspect_exec(&self.exec_id).await?;
+        if inspect.running.unwrap_or(false) {
+            return Ok(None);
+        }
+        Ok(inspect.exit_code)
+    }
+}
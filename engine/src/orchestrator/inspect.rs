@@ -0,0 +1,260 @@
+use anyhow::{Context, Result};
+use bollard::models::ContainerInspectResponse;
+use serde::{Deserialize, Serialize};
+
+use super::ContainerOrchestrator;
+use crate::secrets::mask_env_value;
+
+/// Everything worth knowing about a service's container, with secrets masked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceDetails {
+    pub service: String,
+    pub container_id: String,
+    pub container_name: String,
+    /// Image reference the container was created from, e.g. `postgres:15`
+    pub image: String,
+    /// Repository digest the image resolved to, or the local image id
+    pub image_digest: Option<String>,
+    pub command: Vec<String>,
+    pub status: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub exit_code: Option<i64>,
+    pub oom_killed: bool,
+    pub restart_count: i64,
+    pub env: Vec<EnvVar>,
+    pub mounts: Vec<MountDetails>,
+    pub networks: Vec<NetworkDetails>,
+    pub ports: Vec<PortDetails>,
+    pub health: Option<HealthDetails>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+    /// Whether `value` had secrets replaced
+    pub masked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountDetails {
+    /// `volume`, `bind` or `tmpfs`
+    pub kind: String,
+    /// Volume name or host path
+    pub source: Option<String>,
+    pub destination: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkDetails {
+    pub name: String,
+    pub ip_address: Option<String>,
+    pub gateway: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortDetails {
+    /// Container port with protocol, e.g. `5432/tcp`
+    pub container_port: String,
+    pub host_ip: Option<String>,
+    pub host_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthDetails {
+    pub status: Option<String>,
+    pub failing_streak: i64,
+    pub log: Vec<HealthCheckRun>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheckRun {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub exit_code: Option<i64>,
+    pub output: String,
+}
+
+impl ContainerOrchestrator {
+    /// Inspect a service's container in full
+    pub async fn inspect_service(&self, service_name: &str) -> Result<ServiceDetails> {
+        let container_id = self.get_container_id(service_name).await?;
+        let inspect = self.docker.inspect_container(&container_id, None).await
+            .with_context(|| format!("Failed to inspect service '{}'", service_name))?;
+
+        let mut details = ServiceDetails::from_inspect(service_name, inspect);
+
+        // The container only knows the image id; the registry digest lives on the image
+        if let Some(image_id) = details.image_digest.clone() {
+            if let Ok(image) = self.docker.inspect_image(&image_id).await {
+                if let Some(digest) = image.repo_digests.into_iter().flatten().next() {
+                    details.image_digest = Some(digest);
+                }
+            }
+        }
+
+        Ok(details)
+    }
+
+    /// Environment of a service's container as Docker reports it, secrets included
+    pub async fn container_env(&self, service_name: &str) -> Result<Vec<(String, String)>> {
+        let container_id = self.get_container_id(service_name).await?;
+        let inspect = self.docker.inspect_container(&container_id, None).await
+            .with_context(|| format!("Failed to inspect service '{}'", service_name))?;
+
+        Ok(inspect.config.and_then(|config| config.env).into_iter().flatten()
+            .map(|entry| match entry.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (entry, String::new()),
+            })
+            .collect())
+    }
+}
+
+impl ServiceDetails {
+    /// Convert Docker's inspect response, masking secret environment values
+    pub fn from_inspect(service: &str, inspect: ContainerInspectResponse) -> Self {
+        let config = inspect.config.unwrap_or_default();
+        let state = inspect.state.unwrap_or_default();
+
+        let env = config.env.into_iter().flatten()
+            .map(|entry| {
+                let (key, value) = entry.split_once('=').unwrap_or((entry.as_str(), ""));
+                let masked_value = mask_env_value(key, value);
+                EnvVar {
+                    key: key.to_string(),
+                    masked: masked_value != value,
+                    value: masked_value,
+                }
+            })
+            .collect();
+
+        let mounts = inspect.mounts.into_iter().flatten()
+            .map(|mount| MountDetails {
+                kind: mount.typ.map(|typ| typ.to_string()).unwrap_or_default(),
+                source: mount.name.or(mount.source),
+                destination: mount.destination.unwrap_or_default(),
+                read_only: mount.rw == Some(false),
+            })
+            .collect();
+
+        let network_settings = inspect.network_settings.unwrap_or_default();
+
+        let mut networks: Vec<NetworkDetails> = network_settings.networks.into_iter().flatten()
+            .map(|(name, endpoint)| NetworkDetails {
+                name,
+                ip_address: endpoint.ip_address.filter(|ip| !ip.is_empty()),
+                gateway: endpoint.gateway.filter(|gateway| !gateway.is_empty()),
+                aliases: endpoint.aliases.unwrap_or_default(),
+            })
+            .collect();
+        networks.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut ports: Vec<PortDetails> = Vec::new();
+        for (container_port, bindings) in network_settings.ports.into_iter().flatten() {
+            match bindings {
+                Some(bindings) if !bindings.is_empty() => {
+                    ports.extend(bindings.into_iter().map(|binding| PortDetails {
+                        container_port: container_port.clone(),
+                        host_ip: binding.host_ip,
+                        host_port: binding.host_port.and_then(|port| port.parse().ok()),
+                    }));
+                }
+                _ => ports.push(PortDetails {
+                    container_port,
+                    host_ip: None,
+                    host_port: None,
+                }),
+            }
+        }
+        ports.sort_by(|a, b| a.container_port.cmp(&b.container_port));
+
+        let health = state.health.map(|health| HealthDetails {
+            status: health.status.map(|status| status.to_string()),
+            failing_streak: health.failing_streak.unwrap_or(0),
+            log: health.log.into_iter().flatten()
+                .map(|run| HealthCheckRun {
+                    start: run.start,
+                    end: run.end,
+                    exit_code: run.exit_code,
+                    output: run.output.unwrap_or_default(),
+                })
+                .collect(),
+        });
+
+        let mut command = config.entrypoint.unwrap_or_default();
+        command.extend(config.cmd.unwrap_or_default());
+
+        Self {
+            service: service.to_string(),
+            container_id: inspect.id.unwrap_or_default(),
+            container_name: inspect.name.unwrap_or_default().trim_start_matches('/').to_string(),
+            image: config.image.unwrap_or_default(),
+            image_digest: inspect.image,
+            command,
+            status: state.status.map(|status| status.to_string()),
+            started_at: state.started_at,
+            finished_at: state.finished_at,
+            exit_code: state.exit_code,
+            oom_killed: state.oom_killed.unwrap_or(false),
+            restart_count: inspect.restart_count.unwrap_or(0),
+            env,
+            mounts,
+            networks,
+            ports,
+            health,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::{ContainerConfig, ContainerState, PortBinding};
+    use bollard::models::NetworkSettings;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_from_inspect_masks_secrets() {
+        let inspect = ContainerInspectResponse {
+            id: Some("abc123".to_string()),
+            name: Some("/shop_postgres".to_string()),
+            image: Some("sha256:deadbeef".to_string()),
+            restart_count: Some(2),
+            config: Some(ContainerConfig {
+                image: Some("postgres:15".to_string()),
+                env: Some(vec![
+                    "POSTGRES_PASSWORD=hunter2".to_string(),
+                    "PGDATA=/var/lib/postgresql/data".to_string(),
+                ]),
+                ..Default::default()
+            }),
+            state: Some(ContainerState {
+                running: Some(true),
+                ..Default::default()
+            }),
+            network_settings: Some(NetworkSettings {
+                ports: Some(HashMap::from([(
+                    "5432/tcp".to_string(),
+                    Some(vec![PortBinding {
+                        host_ip: Some("0.0.0.0".to_string()),
+                        host_port: Some("5000".to_string()),
+                    }]),
+                )])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let details = ServiceDetails::from_inspect("postgres", inspect);
+        assert_eq!(details.container_name, "shop_postgres");
+        assert_eq!(details.restart_count, 2);
+        assert_eq!(details.env[0].value, crate::secrets::MASK);
+        assert!(details.env[0].masked);
+        assert!(!details.env[1].masked);
+        assert_eq!(details.ports[0].host_port, Some(5000));
+    }
+}
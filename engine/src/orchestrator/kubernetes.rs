@@ -0,0 +1,437 @@
+//! Running a project's services on Kubernetes instead of a container engine
+//!
+//! Each service becomes a Deployment with a ClusterIP Service of the same
+//! name, so services reach each other by name as on the Docker network. Its
+//! environment goes into a Secret and named volumes become
+//! PersistentVolumeClaims; bind mounts of host paths are skipped. Everything
+//! lives in one namespace per project, `zero-<project>` unless zero.yml names
+//! one, in the current kubeconfig context or `minikube`. Manifests are
+//! applied with `kubectl`, and `zero up` port-forwards each service to its
+//! host port while it runs.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+use tracing::{info, warn};
+
+use super::build::built_image_name;
+use super::{default_command, default_container_port, service_env_vars, service_image};
+use crate::config::{HealthcheckConfig, ServiceConfig, ZeroConfig};
+use crate::runtime::ContainerRuntime;
+use crate::secrets::vault::VaultClient;
+use crate::secrets::CredentialStore;
+
+/// Label selecting every resource of a project
+const PART_OF_LABEL: &str = "app.kubernetes.io/part-of";
+
+/// Label selecting the pods of a service
+const NAME_LABEL: &str = "app.kubernetes.io/name";
+
+/// Size requested for each named volume
+const VOLUME_SIZE: &str = "1Gi";
+
+/// Deploys a project's services to a Kubernetes cluster through `kubectl`
+pub struct KubernetesOrchestrator {
+    project_name: String,
+    namespace: String,
+    context: Option<String>,
+    minikube: bool,
+    /// `kubernetes.minikube_profile`, passed to minikube as `-p`
+    profile: Option<String>,
+    credential_store: CredentialStore,
+}
+
+/// A service port forwarded to the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forward {
+    pub service: String,
+    pub host_port: u16,
+    pub container_port: u16,
+}
+
+impl KubernetesOrchestrator {
+    /// Orchestrator for the cluster of `kubernetes:` in zero.yml, checking it answers
+    pub async fn new(project_name: String, config: &ZeroConfig, runtime: ContainerRuntime) -> Result<Self> {
+        let minikube = runtime == ContainerRuntime::Minikube;
+        let profile = config.kubernetes.minikube_profile.clone().filter(|_| minikube);
+        // minikube names the kubeconfig context after the profile
+        let context = config.kubernetes.context.clone()
+            .or_else(|| minikube.then(|| profile.clone().unwrap_or_else(|| "minikube".to_string())));
+        let namespace = config.kubernetes.namespace.clone()
+            .unwrap_or_else(|| resource_name(&format!("zero-{}", project_name)));
+
+        let credential_store = CredentialStore::for_project(config)?;
+
+        let orchestrator = Self { project_name, namespace, context, minikube, profile, credential_store };
+        let mut cluster_info = orchestrator.kubectl();
+        cluster_info.arg("cluster-info");
+        run(cluster_info, None).await.with_context(|| match minikube {
+            true => "minikube is not running; start it with `minikube start`".to_string(),
+            false => format!("No Kubernetes cluster answers in context {}", orchestrator.context.as_deref().unwrap_or("(current)")),
+        })?;
+        Ok(orchestrator)
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Build the images of services with `build:` inside minikube, where the cluster finds them
+    pub async fn build_images(&self, config: &ZeroConfig) -> Result<()> {
+        for (service_name, service_config) in sorted(&config.services) {
+            let Some(build) = &service_config.build else { continue };
+            if !self.minikube {
+                anyhow::bail!(
+                    "Service '{}' builds its image, which only works with --runtime minikube; push it to a registry and use image: instead",
+                    service_name
+                );
+            }
+            let image = built_image_name(&self.project_name, service_name);
+            info!("Building {} in minikube", image);
+
+            let mut command = Command::new("minikube");
+            command.args(["image", "build", "-t", &image, "-f", build.dockerfile()]);
+            if let Some(profile) = &self.profile {
+                command.args(["-p", profile]);
+            }
+            for (key, value) in &build.args {
+                command.arg(format!("--build-opt=build-arg={}={}", key, value));
+            }
+            command.arg(config.resolve_path(&build.context));
+            let status = command.status().await.context("Failed to run minikube")?;
+            if !status.success() {
+                anyhow::bail!("Building the image of {} failed", service_name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Namespace and resources of every service, in start order
+    pub async fn manifests(&mut self, config: &ZeroConfig) -> Result<Vec<Value>> {
+        let mut manifests = vec![json!({
+            "apiVersion": "v1",
+            "kind": "Namespace",
+            "metadata": { "name": self.namespace, "labels": { PART_OF_LABEL: resource_name(&self.project_name) } },
+        })];
+
+        let mut vault = VaultClient::from_env();
+        for service_name in config.start_order()? {
+            let service_config = &config.services[&service_name];
+            let image = match service_config.build {
+                Some(_) => built_image_name(&self.project_name, &service_name),
+                None => service_image(&service_name, &service_config.version),
+            };
+
+            let mut env: HashMap<String, String> = service_env_vars(&service_name, &mut self.credential_store)
+                .into_iter()
+                .filter_map(|var| var.split_once('=').map(|(key, value)| (key.to_string(), value.to_string())))
+                .collect();
+            env.extend(service_config.environment.clone());
+            vault.resolve(&mut env).await.with_context(|| format!("Failed to resolve the environment of {}", service_name))?;
+
+            manifests.extend(service_manifests(&self.project_name, &service_name, service_config, &image, &env)?);
+        }
+
+        for manifest in manifests.iter_mut().skip(1) {
+            manifest["metadata"]["namespace"] = json!(self.namespace);
+        }
+        Ok(manifests)
+    }
+
+    /// Create or update resources with `kubectl apply`
+    pub async fn apply(&self, manifests: &[Value]) -> Result<()> {
+        let mut documents = Vec::new();
+        for manifest in manifests {
+            documents.push(serde_yaml::to_string(manifest)?);
+        }
+        let mut command = self.kubectl();
+        command.args(["apply", "-f", "-"]);
+        let output = run(command, Some(documents.join("---\n").as_bytes())).await.context("kubectl apply failed")?;
+        for line in output.lines() {
+            info!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Wait for every Deployment to roll out, in start order
+    pub async fn wait_ready(&self, config: &ZeroConfig, timeout: Duration) -> Result<()> {
+        for service_name in config.start_order()? {
+            let mut command = self.kubectl();
+            command.args(["-n", &self.namespace, "rollout", "status"])
+                .arg(format!("deployment/{}", resource_name(&service_name)))
+                .arg(format!("--timeout={}s", timeout.as_secs()));
+            run(command, None).await.with_context(|| format!("{} did not become ready", service_name))?;
+            info!("{} is ready", service_name);
+        }
+        Ok(())
+    }
+
+    /// Host port each service is forwarded to, chosen like the container engine's
+    pub fn forwards(&self, config: &ZeroConfig) -> Result<Vec<Forward>> {
+        let ports = crate::core::ports::plan_ports(config, &HashMap::new(), &HashMap::new(), true)?;
+        let mut forwards = Vec::new();
+        for (service_name, service_config) in sorted(&config.services) {
+            forwards.push(Forward {
+                service: service_name.clone(),
+                host_port: ports[service_name],
+                container_port: container_port(service_name, service_config)?,
+            });
+        }
+        Ok(forwards)
+    }
+
+    /// `kubectl port-forward` of a service, stopped when the returned child is dropped
+    pub fn port_forward(&self, forward: &Forward) -> Result<Child> {
+        let mut command = self.kubectl();
+        command.args(["-n", &self.namespace, "port-forward"])
+            .arg(format!("service/{}", resource_name(&forward.service)))
+            .arg(format!("{}:{}", forward.host_port, forward.container_port))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .kill_on_drop(true);
+        command.spawn().context("Failed to run kubectl port-forward")
+    }
+
+    /// The command forwarding a service, for users to run themselves
+    pub fn port_forward_command(&self, forward: &Forward) -> String {
+        let context = self.context.as_ref().map(|context| format!("--context {} ", context)).unwrap_or_default();
+        format!(
+            "kubectl {}-n {} port-forward service/{} {}:{}",
+            context, self.namespace, resource_name(&forward.service), forward.host_port, forward.container_port
+        )
+    }
+
+    /// Remove the project's workloads; with `volumes`, the whole namespace and its data
+    pub async fn delete(&self, volumes: bool) -> Result<()> {
+        let mut command = self.kubectl();
+        if volumes {
+            command.args(["delete", "namespace", &self.namespace, "--ignore-not-found"]);
+        } else {
+            command.args(["-n", &self.namespace, "delete", "deployment,service,secret", "--ignore-not-found", "-l"])
+                .arg(format!("{}={}", PART_OF_LABEL, resource_name(&self.project_name)));
+        }
+        run(command, None).await.context("kubectl delete failed")?;
+        Ok(())
+    }
+
+    fn kubectl(&self) -> Command {
+        let mut command = Command::new("kubectl");
+        if let Some(context) = &self.context {
+            command.args(["--context", context]);
+        }
+        command
+    }
+}
+
+/// Secret, PersistentVolumeClaims, Deployment and Service of one service
+fn service_manifests(
+    project_name: &str,
+    service_name: &str,
+    config: &ServiceConfig,
+    image: &str,
+    env: &HashMap<String, String>,
+) -> Result<Vec<Value>> {
+    let name = resource_name(service_name);
+    let labels = json!({ NAME_LABEL: name, PART_OF_LABEL: resource_name(project_name) });
+    let port = container_port(service_name, config)?;
+    let secret_name = format!("{}-env", name);
+
+    let env: BTreeMap<&String, &String> = env.iter().collect();
+    let mut manifests = vec![json!({
+        "apiVersion": "v1",
+        "kind": "Secret",
+        "metadata": { "name": secret_name, "labels": labels },
+        "type": "Opaque",
+        "stringData": env,
+    })];
+
+    let mut volumes = Vec::new();
+    let mut mounts = Vec::new();
+    for (index, volume) in config.volumes.iter().enumerate() {
+        let parts: Vec<&str> = volume.split(':').collect();
+        let (source, target, mode) = match parts.as_slice() {
+            [target] => (None, *target, None),
+            [source, target] => (Some(*source), *target, None),
+            [source, target, mode, ..] => (Some(*source), *target, Some(*mode)),
+            [] => continue,
+        };
+        let volume_name = match source {
+            Some(source) if source.starts_with(['.', '/', '~']) || source.as_bytes().get(1) == Some(&b':') => {
+                warn!("Skipping {} of {}: host paths are not mounted into the cluster", volume, service_name);
+                continue;
+            }
+            Some(source) => {
+                let claim = resource_name(&format!("{}-{}", name, source));
+                manifests.push(json!({
+                    "apiVersion": "v1",
+                    "kind": "PersistentVolumeClaim",
+                    "metadata": { "name": claim, "labels": labels },
+                    "spec": { "accessModes": ["ReadWriteOnce"], "resources": { "requests": { "storage": VOLUME_SIZE } } },
+                }));
+                volumes.push(json!({ "name": claim, "persistentVolumeClaim": { "claimName": claim } }));
+                claim
+            }
+            None => {
+                let scratch = format!("scratch-{}", index);
+                volumes.push(json!({ "name": scratch, "emptyDir": {} }));
+                scratch
+            }
+        };
+        mounts.push(json!({ "name": volume_name, "mountPath": target, "readOnly": mode == Some("ro") }));
+    }
+
+    let mut ports = vec![port];
+    ports.extend(config.ports.iter().map(|mapping| mapping.container).filter(|extra| *extra != port));
+
+    let mut container = json!({
+        "name": name,
+        "image": image,
+        "imagePullPolicy": if config.build.is_some() { "Never" } else { "IfNotPresent" },
+        "envFrom": [{ "secretRef": { "name": secret_name } }],
+        "ports": ports.iter().map(|port| json!({ "containerPort": port })).collect::<Vec<_>>(),
+        "volumeMounts": mounts,
+        "readinessProbe": match &config.healthcheck {
+            Some(healthcheck) => probe(healthcheck)?,
+            None => json!({ "tcpSocket": { "port": port }, "periodSeconds": 5 }),
+        },
+    });
+    let args = match &config.command {
+        Some(command) => Some(vec![command.to_string()]),
+        None => default_command(service_name),
+    };
+    if let Some(args) = args {
+        container["args"] = json!(args);
+    }
+    if let Some(limit) = config.memory_limit()? {
+        container["resources"] = json!({ "limits": { "memory": limit.to_string() } });
+    }
+
+    manifests.push(json!({
+        "apiVersion": "apps/v1",
+        "kind": "Deployment",
+        "metadata": { "name": name, "labels": labels },
+        "spec": {
+            "replicas": 1,
+            // Volumes are ReadWriteOnce, so the old pod goes before the new one starts
+            "strategy": { "type": "Recreate" },
+            "selector": { "matchLabels": { NAME_LABEL: name } },
+            "template": {
+                "metadata": { "labels": labels },
+                "spec": { "containers": [container], "volumes": volumes },
+            },
+        },
+    }));
+    // Aliases get Services of their own selecting the same pod, so their DNS names resolve too
+    let service_ports: Vec<Value> = ports.iter()
+        .map(|port| json!({ "name": format!("p{}", port), "port": port, "targetPort": port }))
+        .collect();
+    for dns_name in std::iter::once(name.clone()).chain(config.aliases.iter().map(|alias| resource_name(alias))) {
+        manifests.push(json!({
+            "apiVersion": "v1",
+            "kind": "Service",
+            "metadata": { "name": dns_name, "labels": labels },
+            "spec": {
+                "selector": { NAME_LABEL: name },
+                "ports": service_ports,
+            },
+        }));
+    }
+    Ok(manifests)
+}
+
+/// Readiness probe running a `healthcheck:`
+fn probe(healthcheck: &HealthcheckConfig) -> Result<Value> {
+    let mut probe = json!({ "exec": { "command": healthcheck.exec_command() } });
+    if let Some(interval) = healthcheck.interval()? {
+        probe["periodSeconds"] = json!(interval.as_secs().max(1));
+    }
+    if let Some(timeout) = healthcheck.timeout()? {
+        probe["timeoutSeconds"] = json!(timeout.as_secs().max(1));
+    }
+    if let Some(start_period) = healthcheck.start_period()? {
+        probe["initialDelaySeconds"] = json!(start_period.as_secs());
+    }
+    if let Some(retries) = healthcheck.retries {
+        probe["failureThreshold"] = json!(retries);
+    }
+    Ok(probe)
+}
+
+fn container_port(service_name: &str, config: &ServiceConfig) -> Result<u16> {
+    config.container_port.or_else(|| default_container_port(service_name))
+        .with_context(|| format!("Set container_port for service '{}' in zero.yml to run it on Kubernetes", service_name))
+}
+
+/// A name Kubernetes accepts: lowercase alphanumerics and dashes
+fn resource_name(name: &str) -> String {
+    let name: String = name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    name.trim_matches('-').chars().take(63).collect()
+}
+
+fn sorted(services: &HashMap<String, ServiceConfig>) -> Vec<(&String, &ServiceConfig)> {
+    let mut services: Vec<_> = services.iter().collect();
+    services.sort_by_key(|(name, _)| *name);
+    services
+}
+
+/// Run a command, with `input` on its stdin, and return its stdout
+async fn run(mut command: Command, input: Option<&[u8]>) -> Result<String> {
+    let mut child = command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("kubectl is needed to run services on Kubernetes")?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input).await?;
+    }
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_manifests() {
+        let yaml = r#"
+services:
+  postgres:
+    version: "16"
+    volumes: ["pgdata:/var/lib/postgresql/data", "./init:/docker-entrypoint-initdb.d"]
+    healthcheck:
+      command: pg_isready -U zeroconfig
+      interval: 5s
+    aliases: [db]
+"#;
+        let config = ZeroConfig::from_str(yaml).unwrap();
+        let env = HashMap::from([("POSTGRES_PASSWORD".to_string(), "secret".to_string())]);
+        let manifests = service_manifests("My App", "postgres", &config.services["postgres"], "postgres:16", &env).unwrap();
+
+        let kinds: Vec<&str> = manifests.iter().map(|manifest| manifest["kind"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["Secret", "PersistentVolumeClaim", "Deployment", "Service", "Service"]);
+        assert_eq!(manifests[0]["stringData"]["POSTGRES_PASSWORD"], "secret");
+        assert_eq!(manifests[1]["metadata"]["name"], "postgres-pgdata");
+
+        let container = &manifests[2]["spec"]["template"]["spec"]["containers"][0];
+        assert_eq!(container["ports"][0]["containerPort"], 5432);
+        assert_eq!(container["volumeMounts"].as_array().unwrap().len(), 1);
+        assert_eq!(container["readinessProbe"]["exec"]["command"][2], "pg_isready -U zeroconfig");
+        assert_eq!(container["readinessProbe"]["periodSeconds"], 5);
+        assert_eq!(manifests[3]["metadata"]["labels"][PART_OF_LABEL], "my-app");
+        assert_eq!(manifests[4]["metadata"]["name"], "db");
+        assert_eq!(manifests[4]["spec"]["selector"][NAME_LABEL], "postgres");
+    }
+}
@@ -1,35 +1,100 @@
 use anyhow::{Context, Result};
 use bollard::Docker;
-use bollard::container::{Config, CreateContainerOptions, StartContainerOptions, StopContainerOptions, LogsOptions};
+use bollard::container::{Config, CreateContainerOptions, NetworkingConfig, StartContainerOptions, StopContainerOptions, LogsOptions};
 use bollard::exec::{CreateExecOptions, StartExecResults};
-use bollard::image::CreateImageOptions;
-use bollard::models::{ContainerSummary, HostConfig, PortBinding};
+use bollard::models::{ContainerSummary, EndpointSettings, HealthConfig, HostConfig, PortBinding, RestartPolicyNameEnum};
+use bollard::network::ConnectNetworkOptions;
 use futures::StreamExt;
 use std::collections::HashMap;
-use tracing::{info, warn, error};
+use tracing::{info, info_span, instrument, warn, error, Instrument};
 
+pub mod backend;
+pub mod build;
 pub mod docker_client;
+pub mod exec_session;
+pub mod inspect;
+pub mod kubernetes;
+pub mod overview;
+pub mod pull;
 pub mod service_templates;
-
-use crate::config::ServiceConfig;
+pub mod sidecar;
+pub mod volumes;
+
+pub use backend::{ContainerBackend, DockerBackend, PodmanBackend};
+pub use exec_session::{ExecControl, ExecOutput, ExecSession};
+pub use inspect::{EnvVar, ServiceDetails};
+pub use kubernetes::KubernetesOrchestrator;
+pub use overview::{KnownProject, MemoryStats, NetworkStats, ProjectHealth, ProjectSummary, ResourceUsage, ServiceInfo, ServiceStats};
+pub use pull::PullPolicy;
+pub use sidecar::{Sidecar, SidecarFile};
+pub use volumes::{EntryKind, ProjectVolume, VolumeEntry, VolumeMount};
+
+use crate::config::schema::KNOWN_SERVICES;
+use crate::config::{HealthcheckConfig, RestartPolicy, ServiceConfig};
+use crate::logs::{LogFilter, LogPage, LogSearch};
+use crate::services::{
+    CassandraService, ClickHouseService, ElasticsearchService, InfluxDbService, JaegerService, KafkaService, KeycloakService,
+    MailpitService, MeilisearchService, MemcachedService, MinioService, NatsService, PostgresService, RabbitMqService,
+    TypesenseService,
+};
+
+/// Label recording which project created a container
+pub const PROJECT_LABEL: &str = "dev.zeroconfig.project";
+
+/// Label recording which service of the project a container runs
+pub const SERVICE_LABEL: &str = "dev.zeroconfig.service";
+
+/// Label recording the container port of a service's main port
+pub const PORT_LABEL: &str = "dev.zeroconfig.port";
+
+/// Where a service image can be found before starting it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageAvailability {
+    Local,
+    /// Not pulled yet, but the registry has it
+    Remote,
+    /// The registry does not know the image, or refuses access to it
+    Missing(String),
+    /// The registry could not be reached
+    Unknown(String),
+}
 
 /// Container orchestrator that manages Docker containers for services
 pub struct ContainerOrchestrator {
     docker: Docker,
+    backend: std::sync::Arc<dyn ContainerBackend>,
+    /// Engine address from zero.yml in use, when not picked by the environment
+    host: Option<String>,
     project_name: String,
     network_name: String,
     credential_store: std::sync::Arc<tokio::sync::Mutex<crate::secrets::CredentialStore>>,
+    pull_policy: PullPolicy,
+    pulls: pull::Pulls,
+    progress: Option<indicatif::MultiProgress>,
 }
 
 impl ContainerOrchestrator {
     /// Create a new container orchestrator
     pub async fn new(project_name: String) -> Result<Self> {
-        let docker = Docker::connect_with_local_defaults()
-            .context("Failed to connect to Docker")?;
+        Self::connect(project_name, None).await
+    }
+
+    /// Create a container orchestrator for the engine at `host` (`runtime.host` of zero.yml)
+    /// unless `DOCKER_HOST` or `CONTAINER_HOST` pick one
+    pub async fn connect(project_name: String, host: Option<&str>) -> Result<Self> {
+        let host = docker_client::configured_host(host);
+        let (backend, docker) = match host {
+            Some(host) => (backend::backend_for(crate::runtime::ContainerRuntime::Docker), docker_client::connect_to(host)?),
+            None => {
+                let backend = backend::select().await;
+                let docker = backend.connect()?;
+                (backend, docker)
+            }
+        };
 
         // Verify Docker is running
         docker.ping().await
-            .context("Docker is not running or not accessible")?;
+            .with_context(|| format!("{} is not running or not accessible", backend.runtime().name()))?;
 
         let network_name = format!("zeroconfig_{}", project_name);
 
@@ -40,67 +105,100 @@ impl ContainerOrchestrator {
 
         Ok(Self {
             docker,
+            backend,
+            host: host.map(str::to_string),
             project_name,
             network_name,
             credential_store: std::sync::Arc::new(tokio::sync::Mutex::new(credential_store)),
+            pull_policy: PullPolicy::default(),
+            pulls: pull::Pulls::default(),
+            progress: None,
         })
     }
 
+    /// Docker name of the project's default network
+    pub fn network_name(&self) -> &str {
+        &self.network_name
+    }
+
     /// Create Docker network for the project
     pub async fn create_network(&self) -> Result<()> {
+        self.ensure_network(&self.network_name).await
+    }
+
+    /// Create the project's default network and the ones services declare
+    pub async fn create_networks(&self, networks: &[String]) -> Result<()> {
+        self.create_network().await?;
+        for network in networks {
+            self.ensure_network(&self.network_for(network)).await?;
+        }
+        Ok(())
+    }
+
+    /// Docker name of a project network from zero.yml
+    fn network_for(&self, network: &str) -> String {
+        if network == crate::config::DEFAULT_NETWORK {
+            self.network_name.clone()
+        } else {
+            format!("{}_{}", self.network_name, network)
+        }
+    }
+
+    async fn ensure_network(&self, name: &str) -> Result<()> {
         use bollard::network::CreateNetworkOptions;
 
         let config = CreateNetworkOptions {
-            name: self.network_name.clone(),
+            name: name.to_string(),
             check_duplicate: true,
             driver: "bridge".to_string(),
+            labels: HashMap::from([(PROJECT_LABEL.to_string(), self.project_name.clone())]),
             ..Default::default()
         };
 
         match self.docker.create_network(config).await {
             Ok(_) => {
-                info!("Created network: {}", self.network_name);
+                info!("Created network: {}", name);
                 Ok(())
             }
             Err(e) if e.to_string().contains("already exists") => {
-                info!("Network {} already exists", self.network_name);
+                info!("Network {} already exists", name);
                 Ok(())
             }
-            Err(e) => Err(e).context("Failed to create Docker network"),
+            Err(e) => Err(e).with_context(|| format!("Failed to create Docker network {}", name)),
         }
     }
 
-    /// Pull Docker image if not present
-    pub async fn pull_image(&self, image: &str) -> Result<()> {
-        info!("Pulling image: {}", image);
-
-        let options = Some(CreateImageOptions {
-            from_image: image,
-            ..Default::default()
-        });
-
-        let mut stream = self.docker.create_image(options, None, None);
+    /// Whether an image is present locally, or failing that, in its registry
+    pub async fn image_availability(&self, image: &str) -> ImageAvailability {
+        if self.docker.inspect_image(image).await.is_ok() {
+            return ImageAvailability::Local;
+        }
 
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(info) => {
-                    if let Some(status) = info.status {
-                        if status.contains("Download") || status.contains("Pull") {
-                            info!("{}", status);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Error pulling image: {}", e);
-                    return Err(e).context("Failed to pull Docker image");
-                }
+        match self.docker.inspect_registry_image(image, None).await {
+            Ok(_) => ImageAvailability::Remote,
+            Err(bollard::errors::Error::DockerResponseServerError { status_code: 401 | 403 | 404, message }) => {
+                ImageAvailability::Missing(message)
             }
+            Err(e) => ImageAvailability::Unknown(e.to_string()),
         }
+    }
 
-        Ok(())
+    /// Docker's total memory in bytes and CPU count, which bound what services can use
+    pub async fn resource_limits(&self) -> Result<(u64, u64)> {
+        let info = self.docker.info().await.context("Failed to query Docker info")?;
+        Ok((
+            info.mem_total.unwrap_or(0).max(0) as u64,
+            info.ncpu.unwrap_or(0).max(0) as u64,
+        ))
+    }
+
+    /// Docker's data directory, where images and volumes take up disk space
+    pub async fn data_root(&self) -> Option<String> {
+        self.docker.info().await.ok()?.docker_root_dir
     }
 
     /// Create and start a service container
+    #[instrument(name = "start_container", skip(self, config))]
     pub async fn start_service(
         &self,
         service_name: &str,
@@ -108,14 +206,19 @@ impl ContainerOrchestrator {
         port: u16,
     ) -> Result<String> {
         let container_name = format!("{}_{}", self.project_name, service_name);
-        let image = self.get_service_image(service_name, &config.version);
 
-        // Pull image first
-        self.pull_image(&image).await?;
+        // Pull image first; images from `build:` are built by the engine beforehand
+        let image = if config.build.is_some() {
+            self.built_image(service_name)
+        } else {
+            let image = self.get_service_image(service_name, &config.version);
+            self.pull_image(&image).await?;
+            image
+        };
 
         // Prepare port bindings
         let mut port_bindings = HashMap::new();
-        let container_port = self.get_default_port(service_name);
+        let container_port = self.container_port(service_name, config, &image).await?;
 
         port_bindings.insert(
             format!("{}/tcp", container_port),
@@ -125,6 +228,37 @@ impl ContainerOrchestrator {
             }]),
         );
 
+        // Ports next to the main one (web UIs, OTLP/gRPC) get whichever host port is free
+        for extra_port in extra_ports(service_name) {
+            let host_port = portpicker::pick_unused_port()
+                .with_context(|| format!("No free host port for port {} of {}", extra_port, service_name))?;
+            port_bindings.insert(
+                format!("{}/tcp", extra_port),
+                Some(vec![PortBinding {
+                    host_ip: Some("0.0.0.0".to_string()),
+                    host_port: Some(host_port.to_string()),
+                }]),
+            );
+            info!("Port {} of {} on host port {}", extra_port, container_name, host_port);
+        }
+
+        // `ports:` from zero.yml, on their own host port or any free one
+        for mapping in &config.ports {
+            let host_port = match mapping.host {
+                Some(host_port) => host_port,
+                None => portpicker::pick_unused_port()
+                    .with_context(|| format!("No free host port for port {} of {}", mapping.container, service_name))?,
+            };
+            port_bindings.insert(
+                format!("{}/tcp", mapping.container),
+                Some(vec![PortBinding {
+                    host_ip: Some("0.0.0.0".to_string()),
+                    host_port: Some(host_port.to_string()),
+                }]),
+            );
+            info!("Port {} of {} on host port {}", mapping.container, container_name, host_port);
+        }
+
         // Prepare environment variables
         let mut env_vars: Vec<String> = config
             .environment
@@ -133,7 +267,29 @@ impl ContainerOrchestrator {
             .collect();
 
         // Add service-specific environment variables
-        env_vars.extend(self.get_service_env_vars(service_name));
+        env_vars.extend(self.get_service_env_vars(service_name).await);
+
+        let mut memory_limit = config.memory_limit()?;
+
+        // Kafka advertises its host port, so its listeners depend on the allocation
+        if service_name == "kafka" {
+            let cluster_id = KafkaService::cluster_id(&container_name);
+            env_vars.extend(unless_overridden(KafkaService::default_env_vars(&container_name, port, &cluster_id), config));
+        }
+
+        if MailpitService::handles(service_name) {
+            env_vars.extend(unless_overridden(MailpitService::default_env_vars(service_name), config));
+        }
+
+        if JaegerService::handles(service_name) {
+            env_vars.extend(unless_overridden(JaegerService::default_env_vars(service_name), config));
+        }
+
+        // Search engines size their heap from the memory limit, so they always get one
+        if ElasticsearchService::handles(service_name) {
+            let limit = *memory_limit.get_or_insert(crate::services::elasticsearch::DEFAULT_MEMORY);
+            env_vars.extend(unless_overridden(ElasticsearchService::default_env_vars(service_name, limit), config));
+        }
 
         // Prepare volumes
         let volumes: Vec<String> = config.volumes.clone();
@@ -142,6 +298,8 @@ impl ContainerOrchestrator {
         let host_config = HostConfig {
             port_bindings: Some(port_bindings),
             network_mode: Some(self.network_name.clone()),
+            memory: memory_limit.map(|limit| limit as i64),
+            restart_policy: config.restart.map(restart_policy),
             binds: if volumes.is_empty() {
                 None
             } else {
@@ -154,7 +312,19 @@ impl ContainerOrchestrator {
             image: Some(image.clone()),
             env: Some(env_vars),
             host_config: Some(host_config),
-            cmd: config.command.as_ref().map(|c| vec![c.to_string()]),
+            cmd: match &config.command {
+                Some(command) => Some(vec![command.to_string()]),
+                None => default_command(service_name),
+            },
+            labels: Some(HashMap::from([
+                (PROJECT_LABEL.to_string(), self.project_name.clone()),
+                (SERVICE_LABEL.to_string(), service_name.to_string()),
+                (PORT_LABEL.to_string(), container_port.to_string()),
+            ])),
+            healthcheck: config.healthcheck.as_ref().map(health_config).transpose()?,
+            networking_config: Some(NetworkingConfig {
+                endpoints_config: HashMap::from([(self.network_name.clone(), endpoint_settings(service_name, config))]),
+            }),
             ..Default::default()
         };
 
@@ -172,12 +342,24 @@ impl ContainerOrchestrator {
         let container = self
             .docker
             .create_container(Some(options), container_config)
+            .instrument(info_span!("create"))
             .await
             .context("Failed to create container")?;
 
+        // Containers are created on one network; the others are joined before starting
+        for network in config.networks.iter().filter(|network| network.as_str() != crate::config::DEFAULT_NETWORK) {
+            let options = ConnectNetworkOptions {
+                container: container_name.clone(),
+                endpoint_config: endpoint_settings(service_name, config),
+            };
+            self.docker.connect_network(&self.network_for(network), options).await
+                .with_context(|| format!("Failed to connect {} to network {}", container_name, network))?;
+        }
+
         // Start container
         self.docker
             .start_container(&container_name, None::<StartContainerOptions<String>>)
+            .instrument(info_span!("start"))
             .await
             .context("Failed to start container")?;
 
@@ -244,106 +426,70 @@ impl ContainerOrchestrator {
             .context("Failed to list containers")
     }
 
-    /// Get Docker image for a service
-    fn get_service_image(&self, service_name: &str, version: &str) -> String {
-        match service_name {
-            "postgres" => format!("postgres:{}", version),
-            "redis" => format!("redis:{}", version),
-            "mongodb" | "mongo" => format!("mongo:{}", version),
-            "mysql" => format!("mysql:{}", version),
-            "kafka" => format!("confluentinc/cp-kafka:{}", version),
-            "rabbitmq" => format!("rabbitmq:{}-management", version),
-            "elasticsearch" => format!("elasticsearch:{}", version),
-            "minio" => format!("minio/minio:{}", version),
-            "localstack" => format!("localstack/localstack:{}", version),
-            _ => format!("{}:{}", service_name, version),
-        }
+    /// Get Docker image for a service, as the backend resolves it
+    pub fn get_service_image(&self, service_name: &str, version: &str) -> String {
+        self.backend.qualify_image(&service_image(service_name, version))
     }
 
-    /// Get default port for a service
-    fn get_default_port(&self, service_name: &str) -> u16 {
-        match service_name {
-            "postgres" => 5432,
-            "redis" => 6379,
-            "mongodb" | "mongo" => 27017,
-            "mysql" => 3306,
-            "kafka" => 9092,
-            "rabbitmq" => 5672,
-            "elasticsearch" => 9200,
-            "minio" => 9000,
-            "localstack" => 4566,
-            _ => 8080,
+    /// Port a service listens on in its container: `container_port:` from
+    /// zero.yml, its well-known port or else the lowest port its image exposes
+    async fn container_port(&self, service_name: &str, config: &ServiceConfig, image: &str) -> Result<u16> {
+        if let Some(port) = config.container_port.or_else(|| default_container_port(service_name)) {
+            return Ok(port);
         }
+
+        let inspect = self.docker.inspect_image(image).await
+            .with_context(|| format!("Failed to inspect image {}", image))?;
+        inspect.config
+            .and_then(|config| config.exposed_ports)
+            .and_then(|ports| ports.keys().filter_map(|port| port.split('/').next()?.parse::<u16>().ok()).min())
+            .ok_or_else(|| anyhow::anyhow!(
+                "Image {} exposes no port; set container_port for service '{}' in zero.yml", image, service_name
+            ))
     }
 
     /// Get service-specific environment variables with generated secrets
-    fn get_service_env_vars(&self, service_name: &str) -> Vec<String> {
-        use crate::secrets::SecretGenerator;
-
+    async fn get_service_env_vars(&self, service_name: &str) -> Vec<String> {
         // Use credential store to persist passwords across restarts
-        let credential_store = self.credential_store.clone();
-        let service_name_owned = service_name.to_string();
-        
-        // Block on async operation (we're in a sync context)
-        let runtime = tokio::runtime::Handle::current();
-        let env_vars = runtime.block_on(async move {
-            let mut store = credential_store.lock().await;
-            
-            match service_name_owned.as_str() {
-                "postgres" => {
-                    let password = store.get_or_generate(
-                        &format!("{}_POSTGRES_PASSWORD", service_name_owned),
-                        || SecretGenerator::generate_db_password()
-                    );
-                    let _ = store.save(); // Save credentials to file
-                    info!("Using persisted password for postgres service");
-                    vec![
-                        format!("POSTGRES_PASSWORD={}", password),
-                        "POSTGRES_USER=zeroconfig".to_string(),
-                        "POSTGRES_DB=zeroconfig".to_string(),
-                    ]
-                }
-                "mysql" => {
-                    let password = store.get_or_generate(
-                        &format!("{}_MYSQL_ROOT_PASSWORD", service_name_owned),
-                        || SecretGenerator::generate_db_password()
-                    );
-                    let _ = store.save();
-                    info!("Using persisted password for mysql service");
-                    vec![
-                        format!("MYSQL_ROOT_PASSWORD={}", password),
-                        "MYSQL_DATABASE=zeroconfig".to_string(),
-                    ]
-                }
-                "mongodb" | "mongo" => {
-                    let password = store.get_or_generate(
-                        &format!("{}_MONGO_INITDB_ROOT_PASSWORD", service_name_owned),
-                        || SecretGenerator::generate_db_password()
-                    );
-                    let _ = store.save();
-                    info!("Using persisted password for mongodb service");
-                    vec![
-                        "MONGO_INITDB_ROOT_USERNAME=zeroconfig".to_string(),
-                        format!("MONGO_INITDB_ROOT_PASSWORD={}", password),
-                    ]
-                }
-                "rabbitmq" => {
-                    let password = store.get_or_generate(
-                        &format!("{}_RABBITMQ_DEFAULT_PASS", service_name_owned),
-                        || SecretGenerator::generate_db_password()
-                    );
-                    let _ = store.save();
-                    info!("Using persisted password for rabbitmq service");
-                    vec![
-                        "RABBITMQ_DEFAULT_USER=zeroconfig".to_string(),
-                        format!("RABBITMQ_DEFAULT_PASS={}", password),
-                    ]
-                }
-                _ => vec![],
-            }
-        });
-        
-        env_vars
+        service_env_vars(service_name, &mut *self.credential_store.lock().await)
+    }
+
+    /// Environment a service's container is started with: generated credentials, overridden by zero.yml
+    pub async fn service_env(&self, service_name: &str, config: &ServiceConfig) -> HashMap<String, String> {
+        let mut env: HashMap<String, String> = self.get_service_env_vars(service_name).await
+            .into_iter()
+            .filter_map(|var| var.split_once('=').map(|(key, value)| (key.to_string(), value.to_string())))
+            .collect();
+        env.extend(config.environment.clone());
+        env
+    }
+
+    /// A stored credential, e.g. `redis_PASSWORD`
+    pub async fn credential(&self, key: &str) -> Option<String> {
+        self.credential_store.lock().await.get(key).cloned()
+    }
+
+    /// Replace a stored credential and write the credentials file
+    ///
+    /// Containers keep the old value until they are started again.
+    pub async fn set_credential(&self, key: &str, value: String) -> Result<()> {
+        let mut store = self.credential_store.lock().await;
+        store.set(key.to_string(), value);
+        store.save().with_context(|| format!("Failed to write the credentials to {}", store.location()))
+    }
+
+    /// Keep credentials in `secrets.backend` of the project in `root`, loading them from it
+    pub async fn set_credential_store(&self, root: std::path::PathBuf, backend: crate::config::SecretsBackend) -> Result<()> {
+        let mut store = crate::secrets::CredentialStore::with_backend(root, backend);
+        store.load().context("Failed to load the project's credentials")?;
+        *self.credential_store.lock().await = store;
+        Ok(())
+    }
+
+    /// Whether a service's container exists and is running
+    pub async fn is_running(&self, service_name: &str) -> bool {
+        self.find_container(service_name).await
+            .is_ok_and(|container| container.state.as_deref() == Some("running"))
     }
 
     /// Stop all project containers
@@ -366,98 +512,178 @@ impl ContainerOrchestrator {
 
     /// Get container ID by service name
     pub async fn get_container_id(&self, service_name: &str) -> Result<String> {
+        let container = self.find_container(service_name).await?;
+        container.id.context("Container has no ID")
+    }
+
+    /// Find the container summary for a service
+    async fn find_container(&self, service_name: &str) -> Result<ContainerSummary> {
         let containers = self.list_containers().await?;
+        let project_container = format!("{}_{}", self.project_name, service_name);
 
         for container in containers {
-            if let Some(names) = container.names {
-                for name in names {
-                    let container_name = name.trim_start_matches('/');
-                    if container_name == service_name || container_name.ends_with(&format!("-{}", service_name)) {
-                        return Ok(container.id.context("Container has no ID")?);
-                    }
-                }
+            let matches = container.names.iter().flatten().any(|name| {
+                let container_name = name.trim_start_matches('/');
+                container_name == service_name
+                    || container_name == project_container
+                    || container_name.ends_with(&format!("-{}", service_name))
+            });
+            if matches {
+                return Ok(container);
             }
         }
 
         anyhow::bail!("Service '{}' not found or not running", service_name)
     }
 
-    /// Stream logs from a service container
-    pub async fn get_logs(&self, service_name: &str, follow: bool, tail: usize) -> Result<()> {
+    /// Get the host port a service's container is published on
+    pub async fn get_host_port(&self, service_name: &str) -> Result<u16> {
+        let container = self.find_container(service_name).await?;
+        let ports: Vec<_> = container.ports.iter().flatten().collect();
+
+        // Services publishing several ports (e.g. MinIO's console) report their main one
+        let main_port = container.labels.as_ref()
+            .and_then(|labels| labels.get(PORT_LABEL)?.parse().ok())
+            .or_else(|| default_container_port(service_name));
+        ports.iter()
+            .find(|port| Some(port.private_port) == main_port && port.public_port.is_some())
+            .or_else(|| ports.iter().find(|port| port.public_port.is_some()))
+            .and_then(|port| port.public_port)
+            .ok_or_else(|| anyhow::anyhow!("Service '{}' has no published ports", service_name))
+    }
+
+    /// Host port a container port of a service is published on
+    pub async fn get_published_port(&self, service_name: &str, container_port: u16) -> Result<u16> {
+        let container = self.find_container(service_name).await?;
+
+        container.ports.iter().flatten()
+            .find(|port| port.private_port == container_port)
+            .and_then(|port| port.public_port)
+            .ok_or_else(|| anyhow::anyhow!("Port {} of '{}' is not published", container_port, service_name))
+    }
+
+    /// A service's last `tail` log lines that pass the filter, joined
+    pub async fn get_logs_as_string(&self, service_name: &str, tail: usize, filter: &LogFilter) -> Result<String> {
+        let mut stream = Box::pin(self.log_stream(service_name, false, tail, filter.clone()).await?);
+        let mut output = String::new();
+
+        while let Some(lines) = stream.next().await {
+            match lines {
+                Ok(lines) => output.push_str(&lines),
+                Err(e) => error!("Error reading logs: {}", e),
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Follow a service's logs, starting with its last `tail` lines
+    pub async fn stream_logs(&self, service_name: &str, tail: usize, filter: LogFilter) -> Result<impl futures::Stream<Item = Result<String>>> {
+        self.log_stream(service_name, true, tail, filter).await
+    }
+
+    /// Stream logs from a service container, optionally following new output
+    pub async fn log_stream(
+        &self,
+        service_name: &str,
+        follow: bool,
+        tail: usize,
+        filter: LogFilter,
+    ) -> Result<impl futures::Stream<Item = Result<String>>> {
         let container_id = self.get_container_id(service_name).await?;
 
         let options = LogsOptions::<String> {
             follow,
             stdout: true,
             stderr: true,
+            since: filter.since_timestamp(),
             tail: tail.to_string(),
             ..Default::default()
         };
 
-        let mut stream = self.docker.logs(&container_id, Some(options));
-
-        while let Some(log) = stream.next().await {
-            match log {
-                Ok(output) => print!("{}", output),
-                Err(e) => {
-                    error!("Error reading logs: {}", e);
-                    break;
-                }
-            }
-        }
+        let stream = self.docker.logs(&container_id, Some(options));
 
-        Ok(())
+        // Filter inside the engine so non-matching lines never reach the consumer
+        Ok(stream.filter_map(move |res| {
+            let item = match res {
+                Ok(output) => filter.apply(&output.to_string()).map(Ok),
+                Err(e) => Some(Err(anyhow::anyhow!("Docker log error: {}", e))),
+            };
+            futures::future::ready(item)
+        }))
     }
 
-    /// Get logs as a string
-    pub async fn get_logs_as_string(&self, service_name: &str, tail: usize) -> Result<String> {
+    /// Read a service's complete log within a time range, with timestamps
+    ///
+    /// `since` and `until` are unix timestamps; 0 leaves that side unbounded.
+    pub async fn logs_between(&self, service_name: &str, since: i64, until: i64) -> Result<String> {
         let container_id = self.get_container_id(service_name).await?;
 
         let options = LogsOptions::<String> {
             follow: false,
             stdout: true,
             stderr: true,
-            tail: tail.to_string(),
-            ..Default::default()
+            timestamps: true,
+            since,
+            until,
+            tail: "all".to_string(),
         };
 
         let mut stream = self.docker.logs(&container_id, Some(options));
-        let mut output_string = String::new();
-
-        while let Some(log) = stream.next().await {
-            match log {
-                Ok(output) => output_string.push_str(&output.to_string()),
-                Err(e) => error!("Error reading logs: {}", e),
-            }
+        let mut output = String::new();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| anyhow::anyhow!("Docker log error: {}", e))?;
+            output.push_str(&chunk.to_string());
         }
 
-        Ok(output_string)
+        Ok(output)
     }
 
-    /// Stream logs from a service container
-    pub async fn stream_logs(&self, service_name: &str, tail: usize) -> Result<impl futures::Stream<Item = Result<String>>> {
+    /// Search a service's log history without following, one page at a time
+    ///
+    /// Reading stops as soon as the page is full, so large logs are never
+    /// loaded in full.
+    pub async fn search_logs(&self, service_name: &str, search: &LogSearch) -> Result<LogPage> {
         let container_id = self.get_container_id(service_name).await?;
 
         let options = LogsOptions::<String> {
-            follow: true,
+            follow: false,
             stdout: true,
             stderr: true,
-            tail: tail.to_string(),
-            ..Default::default()
+            timestamps: true,
+            since: search.since_timestamp(),
+            until: search.until_timestamp(),
+            tail: "all".to_string(),
         };
 
-        let stream = self.docker.logs(&container_id, Some(options));
-        
-        Ok(stream.map(|res| {
-            match res {
-                Ok(output) => Ok(output.to_string()),
-                Err(e) => Err(anyhow::anyhow!("Docker log error: {}", e))
+        let mut stream = self.docker.logs(&container_id, Some(options));
+        let mut search = search.clone();
+        let mut page = LogPage::default();
+        let mut cursor = None;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| anyhow::anyhow!("Docker log error: {}", e))?.to_string();
+            for raw in chunk.lines() {
+                let Some(entry) = search.accept(raw) else { continue };
+
+                // A further match means there is another page
+                if page.entries.len() == search.limit() {
+                    page.next_cursor = cursor;
+                    return Ok(page);
+                }
+                page.entries.push(entry);
+                cursor = search.cursor();
             }
-        }))
+        }
+
+        Ok(page)
     }
 
-    /// Execute a command in a service container
-    pub async fn exec_command(&self, service_name: &str, command: Vec<String>) -> Result<()> {
+    /// Execute a command in a service container, passing its output through, and return its exit code
+    pub async fn exec_command(&self, service_name: &str, command: Vec<String>) -> Result<i64> {
+        use bollard::container::LogOutput;
+        use std::io::Write;
+
         let container_id = self.get_container_id(service_name).await?;
 
         let exec_config = CreateExecOptions {
@@ -472,7 +698,8 @@ impl ContainerOrchestrator {
         if let StartExecResults::Attached { mut output, .. } = self.docker.start_exec(&exec.id, None).await? {
             while let Some(chunk) = output.next().await {
                 match chunk {
-                    Ok(output) => print!("{}", output),
+                    Ok(LogOutput::StdErr { message }) => std::io::stderr().write_all(&message)?,
+                    Ok(output) => std::io::stdout().write_all(&output.into_bytes())?,
                     Err(e) => {
                         error!("Error executing command: {}", e);
                         break;
@@ -481,7 +708,8 @@ impl ContainerOrchestrator {
             }
         }
 
-        Ok(())
+        let inspect = self.docker.inspect_exec(&exec.id).await?;
+        Ok(inspect.exit_code.unwrap_or(0))
     }
 
     /// Execute a command in a service container and return the output as a String
@@ -516,23 +744,188 @@ impl ContainerOrchestrator {
         Ok(output_string)
     }
 
-    /// Open an interactive shell in a service container
-    pub async fn open_shell(&self, service_name: &str, shell: &str) -> Result<()> {
+    /// Execute a command and return its raw stdout, failing on a non-zero exit code
+    ///
+    /// Unlike [`Self::exec_command_with_output`], stderr is kept out of the result so
+    /// binary output such as database dumps stays intact.
+    pub async fn exec_command_bytes(&self, service_name: &str, command: Vec<String>) -> Result<Vec<u8>> {
+        self.exec_command_with_env(service_name, command, Vec::new(), None).await
+    }
+
+    /// Execute a command with `input` written to its stdin and return its stdout
+    pub async fn exec_command_with_input(&self, service_name: &str, command: Vec<String>, input: &[u8]) -> Result<Vec<u8>> {
+        self.exec_command_with_env(service_name, command, Vec::new(), Some(input)).await
+    }
+
+    /// Execute a command with extra `KEY=value` environment, e.g. credentials that
+    /// must not appear in its arguments, optionally writing `input` to its stdin
+    pub async fn exec_command_with_env(
+        &self,
+        service_name: &str,
+        command: Vec<String>,
+        env: Vec<String>,
+        input: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        use bollard::container::LogOutput;
+        use tokio::io::AsyncWriteExt;
+
         let container_id = self.get_container_id(service_name).await?;
 
-        info!("Opening {} shell in container {}", shell, service_name);
+        let exec_config = CreateExecOptions {
+            attach_stdin: Some(input.is_some()),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            cmd: Some(command.iter().map(|s| s.as_str()).collect()),
+            env: Some(env.iter().map(|var| var.as_str()).collect()),
+            ..Default::default()
+        };
 
-        // Use docker CLI for interactive shell since Bollard doesn't support TTY properly
-        let docker_cmd = if cfg!(windows) {
-            format!("docker exec -it {} {}", container_id, shell)
-        } else {
-            format!("docker exec -it {} {}", container_id, shell)
+        let exec = self.docker.create_exec(&container_id, exec_config).await?;
+
+        let mut stdout = Vec::new();
+        let mut stderr = String::new();
+
+        if let StartExecResults::Attached { mut output, input: mut stdin } = self.docker.start_exec(&exec.id, None).await? {
+            if let Some(data) = input {
+                // Feed stdin concurrently so large outputs cannot block the writer
+                let data = data.to_vec();
+                tokio::spawn(async move {
+                    if let Err(e) = stdin.write_all(&data).await {
+                        warn!("Failed to write exec stdin: {}", e);
+                    }
+                    let _ = stdin.shutdown().await;
+                });
+            }
+
+            while let Some(chunk) = output.next().await {
+                match chunk? {
+                    LogOutput::StdOut { message } => stdout.extend_from_slice(&message),
+                    LogOutput::StdErr { message } => stderr.push_str(&String::from_utf8_lossy(&message)),
+                    _ => {}
+                }
+            }
+        }
+
+        let inspect = self.docker.inspect_exec(&exec.id).await?;
+        if let Some(code) = inspect.exit_code.filter(|code| *code != 0) {
+            anyhow::bail!(
+                "Command `{}` failed in {} (exit code {}): {}",
+                command.join(" "),
+                service_name,
+                code,
+                stderr.trim()
+            );
+        }
+
+        Ok(stdout)
+    }
+
+    /// Run a non-interactive command and stream its stdout/stderr as it is produced
+    ///
+    /// Use the returned [`ExecControl`] to read the exit code once the stream ends.
+    pub async fn exec_command_stream(
+        &self,
+        service_name: &str,
+        command: Vec<String>,
+    ) -> Result<(futures::stream::BoxStream<'static, Result<ExecOutput>>, ExecControl)> {
+        use bollard::container::LogOutput;
+
+        let container_id = self.get_container_id(service_name).await?;
+
+        let exec_config = CreateExecOptions {
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            cmd: Some(command.iter().map(|s| s.as_str()).collect()),
+            ..Default::default()
         };
 
-        println!("Running: {}", docker_cmd);
-        println!("Note: Interactive shells require running 'docker exec -it {} {}' directly", container_id, shell);
+        let exec = self.docker.create_exec(&container_id, exec_config).await?;
 
-        Ok(())
+        let StartExecResults::Attached { output, .. } = self.docker.start_exec(&exec.id, None).await? else {
+            anyhow::bail!("Exec in {} started detached", service_name);
+        };
+
+        let stream = output
+            .filter_map(|chunk| {
+                let item = match chunk {
+                    Ok(LogOutput::StdOut { message }) => Some(Ok(ExecOutput::Stdout(message.to_vec()))),
+                    Ok(LogOutput::StdErr { message }) => Some(Ok(ExecOutput::Stderr(message.to_vec()))),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e.into())),
+                };
+                futures::future::ready(item)
+            })
+            .boxed();
+
+        Ok((stream, ExecControl::new(self.docker.clone(), exec.id)))
+    }
+
+    /// Stream lifecycle and health events for this project's containers
+    pub fn service_events(&self) -> futures::stream::BoxStream<'static, Result<crate::health::ServiceEvent>> {
+        use bollard::system::EventsOptions;
+        use crate::health::ServiceEvent;
+
+        let filters = HashMap::from([
+            ("type".to_string(), vec!["container".to_string()]),
+            ("event".to_string(), ServiceEvent::DOCKER_ACTIONS.iter().map(|a| a.to_string()).collect()),
+        ]);
+
+        let project_name = self.project_name.clone();
+        self.docker
+            .events(Some(EventsOptions::<String> { filters, ..Default::default() }))
+            .filter_map(move |message| {
+                let item = match message {
+                    Ok(message) => ServiceEvent::from_docker(&message, &project_name).map(Ok),
+                    Err(e) => Some(Err(anyhow::anyhow!("Docker event error: {}", e))),
+                };
+                futures::future::ready(item)
+            })
+            .boxed()
+    }
+
+    /// Start an interactive command with a TTY and attached stdin
+    pub async fn open_exec_session(&self, service_name: &str, command: Vec<String>, cols: u16, rows: u16) -> Result<ExecSession> {
+        let container_id = self.get_container_id(service_name).await?;
+
+        let exec_config = CreateExecOptions {
+            attach_stdin: Some(true),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            tty: Some(true),
+            env: Some(vec!["TERM=xterm-256color"]),
+            cmd: Some(command.iter().map(|s| s.as_str()).collect()),
+            ..Default::default()
+        };
+
+        let exec = self.docker.create_exec(&container_id, exec_config).await?;
+
+        let StartExecResults::Attached { output, input } = self.docker.start_exec(&exec.id, None).await? else {
+            anyhow::bail!("Exec session in {} started detached", service_name);
+        };
+
+        let control = ExecControl::new(self.docker.clone(), exec.id);
+        // The TTY only accepts a size once the exec is running
+        if let Err(e) = control.resize(cols, rows).await {
+            warn!("Failed to set terminal size: {}", e);
+        }
+
+        info!("Opened exec session {} in {}", control.id(), service_name);
+
+        Ok(ExecSession {
+            output: output
+                .map(|chunk| chunk.map(|out| out.into_bytes().to_vec()).map_err(Into::into))
+                .boxed(),
+            input,
+            control,
+        })
+    }
+
+    /// Open an interactive shell in a service container
+    ///
+    /// `bash` falls back to `sh` in images that lack it, such as Alpine-based ones.
+    pub async fn open_shell(&self, service_name: &str, shell: &str, cols: u16, rows: u16) -> Result<ExecSession> {
+        info!("Opening {} shell in container {}", shell, service_name);
+        self.open_exec_session(service_name, shell_command(shell), cols, rows).await
     }
 
     /// Restart a specific service
@@ -589,23 +982,263 @@ impl ContainerOrchestrator {
 
     /// Get stats for all project containers
     pub async fn get_all_stats(&self) -> Result<Vec<(String, bollard::container::Stats)>> {
-        let containers = self.list_containers().await?;
-        let mut stats = Vec::new();
+        overview::project_stats(&self.docker, &self.project_name).await
+    }
 
-        for container in containers {
-            if let Some(names) = container.names {
-                if let Some(name) = names.first() {
-                    let service_name = name.trim_start_matches('/');
-                    if service_name.starts_with(&self.project_name) {
-                        match self.get_container_stats(service_name).await {
-                            Ok(stat) => stats.push((service_name.to_string(), stat)),
-                            Err(e) => warn!("Failed to get stats for {}: {}", service_name, e),
-                        }
-                    }
-                }
-            }
+    /// Docker connection of this orchestrator, for work that shouldn't borrow it
+    pub fn docker(&self) -> Docker {
+        self.docker.clone()
+    }
+
+    /// Host the engine's published ports are reached on
+    pub fn published_host(&self) -> String {
+        docker_client::published_host_at(self.host.as_deref())
+    }
+}
+
+/// Command starting a shell, with `bash` falling back to `sh`
+fn shell_command(shell: &str) -> Vec<String> {
+    if shell == "bash" {
+        let fallback = "if command -v bash >/dev/null 2>&1; then exec bash; else exec sh; fi";
+        vec!["sh".to_string(), "-c".to_string(), fallback.to_string()]
+    } else {
+        vec![shell.to_string()]
+    }
+}
+
+/// Docker's form of a `healthcheck:` from zero.yml; unset durations inherit the engine's defaults
+fn health_config(healthcheck: &HealthcheckConfig) -> Result<HealthConfig> {
+    let nanos = |duration: Option<std::time::Duration>| duration.map(|duration| duration.as_nanos() as i64);
+    Ok(HealthConfig {
+        test: Some(healthcheck.test()),
+        interval: nanos(healthcheck.interval()?),
+        timeout: nanos(healthcheck.timeout()?),
+        retries: healthcheck.retries.map(i64::from),
+        start_period: nanos(healthcheck.start_period()?),
+        ..Default::default()
+    })
+}
+
+/// A service's endpoint on a network, reachable by its service name and aliases
+fn endpoint_settings(service_name: &str, config: &ServiceConfig) -> EndpointSettings {
+    let aliases = std::iter::once(service_name.to_string()).chain(config.aliases.iter().cloned()).collect();
+    EndpointSettings { aliases: Some(aliases), ..Default::default() }
+}
+
+fn restart_policy(policy: RestartPolicy) -> bollard::models::RestartPolicy {
+    let name = match policy {
+        RestartPolicy::No => RestartPolicyNameEnum::NO,
+        RestartPolicy::Always => RestartPolicyNameEnum::ALWAYS,
+        RestartPolicy::OnFailure => RestartPolicyNameEnum::ON_FAILURE,
+        RestartPolicy::UnlessStopped => RestartPolicyNameEnum::UNLESS_STOPPED,
+    };
+    bollard::models::RestartPolicy { name: Some(name), maximum_retry_count: None }
+}
+
+/// Image of a service at a version, as Docker names it
+pub fn service_image(service_name: &str, version: &str) -> String {
+    match service_name {
+        "postgres" => PostgresService::default_image(version),
+        "timescaledb" => PostgresService::timescaledb_image(version),
+        "influxdb" => InfluxDbService::default_image(version),
+        "meilisearch" => MeilisearchService::default_image(version),
+        "typesense" => TypesenseService::default_image(version),
+        "redis" => format!("redis:{}", version),
+        "mongodb" | "mongo" => format!("mongo:{}", version),
+        "mysql" => format!("mysql:{}", version),
+        "kafka" => KafkaService::default_image(version),
+        "rabbitmq" => RabbitMqService::default_image(version),
+        "elasticsearch" | "opensearch" => ElasticsearchService::default_image(service_name, version),
+        "minio" => MinioService::default_image(version),
+        "mailpit" | "mailhog" => MailpitService::default_image(service_name, version),
+        "jaeger" | "otel-collector" => JaegerService::default_image(service_name, version),
+        "keycloak" => KeycloakService::default_image(version),
+        "nats" => NatsService::default_image(version),
+        "clickhouse" => ClickHouseService::default_image(version),
+        "cassandra" => CassandraService::default_image(version),
+        "memcached" => MemcachedService::default_image(version),
+        "localstack" => format!("localstack/localstack:{}", version),
+        _ => format!("{}:{}", service_name, version),
+    }
+}
+
+/// Generated credentials and settings a service starts with, as `KEY=value`
+///
+/// Credentials are kept in `store` so they survive restarts.
+pub fn service_env_vars(service_name: &str, store: &mut crate::secrets::CredentialStore) -> Vec<String> {
+    use crate::secrets::SecretGenerator;
+
+    match service_name {
+        "postgres" | "timescaledb" => {
+            let password = store.get_or_generate(
+                &format!("{}_POSTGRES_PASSWORD", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let _ = store.save(); // Save credentials to file
+            info!("Using persisted password for {} service", service_name);
+            vec![
+                format!("POSTGRES_PASSWORD={}", password),
+                "POSTGRES_USER=zeroconfig".to_string(),
+                "POSTGRES_DB=zeroconfig".to_string(),
+            ]
+        }
+        "mysql" => {
+            let password = store.get_or_generate(
+                &format!("{}_MYSQL_ROOT_PASSWORD", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let _ = store.save();
+            info!("Using persisted password for mysql service");
+            vec![
+                format!("MYSQL_ROOT_PASSWORD={}", password),
+                "MYSQL_DATABASE=zeroconfig".to_string(),
+            ]
+        }
+        "mongodb" | "mongo" => {
+            let password = store.get_or_generate(
+                &format!("{}_MONGO_INITDB_ROOT_PASSWORD", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let _ = store.save();
+            info!("Using persisted password for mongodb service");
+            vec![
+                "MONGO_INITDB_ROOT_USERNAME=zeroconfig".to_string(),
+                format!("MONGO_INITDB_ROOT_PASSWORD={}", password),
+            ]
+        }
+        "rabbitmq" => {
+            let password = store.get_or_generate(
+                &format!("{}_RABBITMQ_DEFAULT_PASS", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let _ = store.save();
+            info!("Using persisted password for rabbitmq service");
+            vec![
+                "RABBITMQ_DEFAULT_USER=zeroconfig".to_string(),
+                format!("RABBITMQ_DEFAULT_PASS={}", password),
+            ]
+        }
+        "influxdb" => {
+            let password = store.get_or_generate(
+                &format!("{}_DOCKER_INFLUXDB_INIT_PASSWORD", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let token = store.get_or_generate(
+                &format!("{}_DOCKER_INFLUXDB_INIT_ADMIN_TOKEN", service_name),
+                || SecretGenerator::generate_alphanumeric(64)
+            );
+            let _ = store.save();
+            info!("Using persisted password and token for influxdb service");
+            InfluxDbService::default_env_vars(&password, &token)
         }
+        "meilisearch" => {
+            let key = store.get_or_generate(
+                &format!("{}_{}", service_name, crate::services::meilisearch::KEY_VAR),
+                SecretGenerator::generate_api_key
+            );
+            let _ = store.save();
+            info!("Using persisted master key for meilisearch service");
+            MeilisearchService::default_env_vars(&key)
+        }
+        "typesense" => {
+            let key = store.get_or_generate(
+                &format!("{}_{}", service_name, crate::services::typesense::KEY_VAR),
+                SecretGenerator::generate_api_key
+            );
+            let _ = store.save();
+            info!("Using persisted API key for typesense service");
+            TypesenseService::default_env_vars(&key)
+        }
+        "minio" => {
+            let access_key = store.get_or_generate(
+                &format!("{}_MINIO_ROOT_USER", service_name),
+                || SecretGenerator::generate_alphanumeric(20)
+            );
+            let secret_key = store.get_or_generate(
+                &format!("{}_MINIO_ROOT_PASSWORD", service_name),
+                || SecretGenerator::generate_alphanumeric(40)
+            );
+            let _ = store.save();
+            info!("Using persisted access keys for minio service");
+            MinioService::default_env_vars(&access_key, &secret_key)
+        }
+        "keycloak" => {
+            let password = store.get_or_generate(
+                &format!("{}_KC_BOOTSTRAP_ADMIN_PASSWORD", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let _ = store.save();
+            info!("Using persisted admin password for keycloak service");
+            KeycloakService::default_env_vars(&password)
+        }
+        "clickhouse" => {
+            let password = store.get_or_generate(
+                &format!("{}_CLICKHOUSE_PASSWORD", service_name),
+                SecretGenerator::generate_db_password
+            );
+            let _ = store.save();
+            info!("Using persisted password for clickhouse service");
+            ClickHouseService::default_env_vars(&password)
+        }
+        "cassandra" => CassandraService::default_env_vars(),
+        _ => vec![],
+    }
+}
 
-        Ok(stats)
+/// Well-known container port of a service, `None` for custom services
+pub fn default_container_port(service_name: &str) -> Option<u16> {
+    let port = match service_name {
+        "postgres" | "postgresql" | "timescaledb" => PostgresService::default_port(),
+        "influxdb" => InfluxDbService::default_port(),
+        "meilisearch" => MeilisearchService::default_port(),
+        "typesense" => TypesenseService::default_port(),
+        "redis" => 6379,
+        "mongodb" | "mongo" => 27017,
+        "mysql" => 3306,
+        "kafka" => 9092,
+        "rabbitmq" => RabbitMqService::default_port(),
+        "elasticsearch" | "opensearch" => ElasticsearchService::default_port(),
+        "minio" => MinioService::default_port(),
+        "mailpit" | "mailhog" => MailpitService::default_port(),
+        "jaeger" | "otel-collector" => JaegerService::default_port(),
+        "keycloak" => KeycloakService::default_port(),
+        "nats" => NatsService::default_port(),
+        "clickhouse" => ClickHouseService::default_port(),
+        "cassandra" => CassandraService::default_port(),
+        "memcached" => MemcachedService::default_port(),
+        "localstack" => 4566,
+        known => return KNOWN_SERVICES.iter().find(|service| service.name == known).map(|service| service.default_port),
+    };
+    Some(port)
+}
+
+/// Container ports a service serves besides its main port, such as web UIs
+pub fn extra_ports(service_name: &str) -> Vec<u16> {
+    match service_name {
+        "minio" => vec![MinioService::console_port()],
+        "rabbitmq" => vec![RabbitMqService::management_port()],
+        "mailpit" | "mailhog" => vec![MailpitService::ui_port()],
+        "jaeger" | "otel-collector" => JaegerService::extra_ports(service_name),
+        "nats" => vec![NatsService::monitoring_port()],
+        "clickhouse" => vec![ClickHouseService::native_port()],
+        _ => vec![],
     }
 }
+
+/// Command a service runs unless zero.yml sets `command:`, `None` to keep the image's
+pub fn default_command(service_name: &str) -> Option<Vec<String>> {
+    match service_name {
+        "minio" => Some(MinioService::default_command()),
+        "keycloak" => Some(KeycloakService::default_command()),
+        "nats" => Some(NatsService::default_command()),
+        _ => None,
+    }
+}
+
+/// Service defaults (`KEY=value`) minus the keys zero.yml sets itself
+fn unless_overridden(defaults: Vec<String>, config: &ServiceConfig) -> impl Iterator<Item = String> + '_ {
+    defaults.into_iter().filter(|var| {
+        let key = var.split('=').next().unwrap_or_default();
+        !config.environment.contains_key(key)
+    })
+}
@@ -0,0 +1,353 @@
+use anyhow::{Context, Result};
+use bollard::container::{ListContainersOptions, Stats, StatsOptions};
+use bollard::models::ContainerSummary;
+use bollard::Docker;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::PROJECT_LABEL;
+
+/// A project the caller already knows about, e.g. from the desktop app's recent list
+#[derive(Debug, Clone)]
+pub struct KnownProject {
+    pub name: String,
+    pub path: Option<String>,
+    /// Services declared in the project's zero.yml
+    pub services: Vec<String>,
+}
+
+/// Resource and health summary of one project's containers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectSummary {
+    pub name: String,
+    pub path: Option<String>,
+    pub services_total: usize,
+    pub services_running: usize,
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+    pub health: ProjectHealth,
+    /// Services that are failing their healthcheck or declared but not running
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectHealth {
+    /// Every service is running and none is unhealthy
+    Healthy,
+    /// Some services are running, but not all of them or not all healthy
+    Degraded,
+    Stopped,
+}
+
+/// Summarize every project with containers on this machine, plus the known ones
+///
+/// Containers are attributed to a project by the `dev.zeroconfig.project` label,
+/// or by the `{project}_` name prefix for known projects started before labels
+/// were added.
+pub async fn project_summaries(known: &[KnownProject]) -> Result<Vec<ProjectSummary>> {
+    let docker = super::docker_client::connect()?;
+
+    let containers = docker
+        .list_containers(Some(ListContainersOptions::<String> {
+            all: true,
+            ..Default::default()
+        }))
+        .await
+        .context("Failed to list containers")?;
+
+    let mut projects: BTreeMap<String, Vec<(String, ContainerSummary)>> = BTreeMap::new();
+    for project in known {
+        projects.entry(project.name.clone()).or_default();
+    }
+
+    for container in containers {
+        let Some(name) = container.names.iter().flatten().next().map(|name| name.trim_start_matches('/').to_string()) else {
+            continue;
+        };
+
+        let labeled = container.labels.as_ref().and_then(|labels| labels.get(PROJECT_LABEL)).cloned();
+        let project = labeled.or_else(|| {
+            known.iter()
+                .map(|project| &project.name)
+                .find(|project| name.starts_with(&format!("{}_", project)))
+                .cloned()
+        });
+
+        if let Some(project) = project {
+            let service = name.strip_prefix(&format!("{}_", project)).unwrap_or(&name).to_string();
+            projects.entry(project).or_default().push((service, container));
+        }
+    }
+
+    // Sample every running container at once; each sample takes about a second
+    let running_ids: Vec<String> = projects.values()
+        .flatten()
+        .filter(|(_, container)| container.state.as_deref() == Some("running"))
+        .filter_map(|(_, container)| container.id.clone())
+        .collect();
+    let samples = futures::future::join_all(running_ids.iter().map(|id| sample_stats(&docker, id))).await;
+    let usage: HashMap<&String, (f64, u64)> = running_ids.iter()
+        .zip(samples)
+        .filter_map(|(id, sample)| sample.map(|sample| (id, sample)))
+        .collect();
+
+    let summaries = projects.into_iter()
+        .map(|(name, containers)| {
+            let known = known.iter().find(|project| project.name == name);
+            summarize(name, known, &containers, &usage)
+        })
+        .collect();
+
+    Ok(summaries)
+}
+
+fn summarize(
+    name: String,
+    known: Option<&KnownProject>,
+    containers: &[(String, ContainerSummary)],
+    usage: &HashMap<&String, (f64, u64)>,
+) -> ProjectSummary {
+    let mut problems = Vec::new();
+    let mut services_running = 0;
+    let mut cpu_percent = 0.0;
+    let mut memory_bytes = 0;
+
+    for (service, container) in containers {
+        if container.state.as_deref() == Some("running") {
+            services_running += 1;
+        }
+        if container.status.as_deref().is_some_and(|status| status.contains("(unhealthy)")) {
+            problems.push(service.clone());
+        }
+        if let Some((cpu, memory)) = container.id.as_ref().and_then(|id| usage.get(id)) {
+            cpu_percent += cpu;
+            memory_bytes += memory;
+        }
+    }
+
+    // Declared services without a running container count as problems too
+    let declared = known.map(|project| project.services.as_slice()).unwrap_or_default();
+    for service in declared {
+        let running = containers.iter()
+            .any(|(name, container)| name == service && container.state.as_deref() == Some("running"));
+        if !running && !problems.contains(service) {
+            problems.push(service.clone());
+        }
+    }
+    problems.sort();
+
+    let services_total = containers.len().max(declared.len());
+    let health = if services_running == 0 {
+        ProjectHealth::Stopped
+    } else if problems.is_empty() && services_running == services_total {
+        ProjectHealth::Healthy
+    } else {
+        ProjectHealth::Degraded
+    };
+
+    ProjectSummary {
+        name,
+        path: known.and_then(|project| project.path.clone()),
+        services_total,
+        services_running,
+        cpu_percent,
+        memory_bytes,
+        health,
+        problems,
+    }
+}
+
+/// CPU percentage and memory usage of a container
+async fn sample_stats(docker: &Docker, id: &str) -> Option<(f64, u64)> {
+    // Without one_shot the daemon waits for a second sample, so CPU usage is meaningful
+    let options = StatsOptions { stream: false, one_shot: false };
+    let stats = docker.stats(id, Some(options)).next().await?.ok()?;
+    Some((cpu_percent(&stats), stats.memory_stats.usage.unwrap_or(0)))
+}
+
+/// Stats of every container of a project, by container name
+pub async fn project_stats(docker: &Docker, project_name: &str) -> Result<Vec<(String, Stats)>> {
+    let containers = docker
+        .list_containers(Some(ListContainersOptions {
+            all: true,
+            filters: HashMap::from([("name".to_string(), vec![project_name.to_string()])]),
+            ..Default::default()
+        }))
+        .await
+        .context("Failed to list containers")?;
+
+    let mut stats = Vec::new();
+    for container in containers {
+        let Some(name) = container.names.iter().flatten().next().map(|name| name.trim_start_matches('/')) else {
+            continue;
+        };
+        if !name.starts_with(project_name) {
+            continue;
+        }
+        let options = StatsOptions { stream: false, one_shot: true };
+        match docker.stats(container.id.as_deref().unwrap_or(name), Some(options)).next().await {
+            Some(Ok(stat)) => stats.push((name.to_string(), stat)),
+            Some(Err(e)) => tracing::warn!("Failed to get stats for {}: {}", name, e),
+            None => tracing::warn!("Failed to get stats for {}", name),
+        }
+    }
+    Ok(stats)
+}
+
+/// Resource usage of one container, computed from a stats sample
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    pub cpu_percent: f64,
+    pub memory_usage: u64,
+    pub memory_limit: u64,
+    /// Bytes received and sent over all networks since the container started
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+impl ResourceUsage {
+    pub fn from_stats(stats: &Stats) -> Self {
+        let (rx_bytes, tx_bytes) = stats.networks.iter()
+            .flat_map(|networks| networks.values())
+            .fold((0, 0), |(rx, tx), network| (rx + network.rx_bytes, tx + network.tx_bytes));
+
+        Self {
+            cpu_percent: cpu_percent(stats),
+            memory_usage: stats.memory_stats.usage.unwrap_or(0),
+            memory_limit: stats.memory_stats.limit.unwrap_or(0),
+            rx_bytes,
+            tx_bytes,
+        }
+    }
+
+    /// Memory usage as a percentage of the limit, or 0 without a limit
+    pub fn memory_percent(&self) -> f64 {
+        if self.memory_limit > 0 {
+            (self.memory_usage as f64 / self.memory_limit as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A project container as `zero ps` and the desktop app list it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    /// Container name
+    pub name: String,
+    pub image: String,
+    pub status: String,
+    /// First published host port
+    pub port: Option<u16>,
+    pub stats: Option<ServiceStats>,
+}
+
+impl ServiceInfo {
+    pub fn from_container(container: &ContainerSummary, stats: Option<ServiceStats>) -> Self {
+        Self {
+            name: container.names.iter().flatten().next()
+                .map(|name| name.trim_start_matches('/'))
+                .unwrap_or("unknown")
+                .to_string(),
+            image: container.image.clone().unwrap_or_default(),
+            status: container.status.clone().unwrap_or_default(),
+            port: container.ports.iter().flatten().find_map(|port| port.public_port),
+            stats,
+        }
+    }
+}
+
+/// Resource usage of a container in the shape the CLI's JSON output and the desktop app share
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ServiceStats {
+    pub cpu: f64,
+    pub memory: MemoryStats,
+    pub network: NetworkStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub percentage: f64,
+    pub usage: u64,
+    pub limit: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStats {
+    pub rx: u64,
+    pub tx: u64,
+}
+
+impl From<ResourceUsage> for ServiceStats {
+    fn from(usage: ResourceUsage) -> Self {
+        Self {
+            cpu: usage.cpu_percent,
+            memory: MemoryStats {
+                percentage: usage.memory_percent(),
+                usage: usage.memory_usage,
+                limit: usage.memory_limit,
+            },
+            network: NetworkStats {
+                rx: usage.rx_bytes,
+                tx: usage.tx_bytes,
+            },
+        }
+    }
+}
+
+/// CPU usage across all cores as a percentage, from a stats sample
+pub fn cpu_percent(stats: &Stats) -> f64 {
+    let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
+        - stats.precpu_stats.cpu_usage.total_usage as f64;
+    let system_delta = stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+        - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+
+    if system_delta > 0.0 && cpu_delta > 0.0 {
+        let cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
+        (cpu_delta / system_delta) * cpus * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(state: &str, status: &str) -> ContainerSummary {
+        ContainerSummary {
+            state: Some(state.to_string()),
+            status: Some(status.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize_health() {
+        let known = KnownProject {
+            name: "shop".to_string(),
+            path: Some("/code/shop".to_string()),
+            services: vec!["postgres".to_string(), "redis".to_string()],
+        };
+        let usage = HashMap::new();
+
+        let all_up = vec![
+            ("postgres".to_string(), container("running", "Up 2 hours (healthy)")),
+            ("redis".to_string(), container("running", "Up 2 hours")),
+        ];
+        let summary = summarize("shop".to_string(), Some(&known), &all_up, &usage);
+        assert_eq!(summary.health, ProjectHealth::Healthy);
+        assert_eq!((summary.services_running, summary.services_total), (2, 2));
+
+        let redis_missing = vec![("postgres".to_string(), container("running", "Up 2 hours (unhealthy)"))];
+        let summary = summarize("shop".to_string(), Some(&known), &redis_missing, &usage);
+        assert_eq!(summary.health, ProjectHealth::Degraded);
+        assert_eq!(summary.problems, vec!["postgres", "redis"]);
+
+        let summary = summarize("shop".to_string(), Some(&known), &[], &usage);
+        assert_eq!(summary.health, ProjectHealth::Stopped);
+        assert_eq!(summary.services_total, 2);
+    }
+}
@@ -0,0 +1,196 @@
+//! Pulling images: only when missing, once per image, with optional progress bars
+
+use anyhow::{Context, Result};
+use bollard::image::CreateImageOptions;
+use bollard::models::CreateImageInfo;
+use futures::StreamExt;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+use super::ContainerOrchestrator;
+
+/// When images are pulled, as `--pull` takes it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PullPolicy {
+    /// Pull images that are not present locally
+    #[default]
+    Missing,
+    /// Pull every image once per run, picking up new versions of a tag
+    Always,
+}
+
+/// Pulls in progress or done during this run, by image
+#[derive(Default)]
+pub(super) struct Pulls {
+    images: std::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<bool>>>>,
+}
+
+impl Pulls {
+    /// Lock of an image, holding whether it was pulled during this run
+    fn lock(&self, image: &str) -> Arc<tokio::sync::Mutex<bool>> {
+        let mut images = self.images.lock().unwrap_or_else(|e| e.into_inner());
+        images.entry(image.to_string()).or_default().clone()
+    }
+}
+
+impl ContainerOrchestrator {
+    /// Pull images when starting services, instead of only missing ones
+    pub fn set_pull_policy(&mut self, policy: PullPolicy) {
+        self.pull_policy = policy;
+    }
+
+    /// Draw pull progress as bars, one per image
+    pub fn show_pull_progress(&mut self, progress: MultiProgress) {
+        self.progress = Some(progress);
+    }
+
+    /// Pull an image unless the policy says the local one will do
+    ///
+    /// Concurrent pulls of the same image wait for the first one.
+    #[instrument(skip(self))]
+    pub async fn pull_image(&self, image: &str) -> Result<()> {
+        let image = self.backend.qualify_image(image);
+        let lock = self.pulls.lock(&image);
+        let mut pulled = lock.lock().await;
+
+        if *pulled || (self.pull_policy == PullPolicy::Missing && self.image_exists(&image).await) {
+            return Ok(());
+        }
+
+        info!("Pulling image: {}", image);
+        let bar = self.progress.as_ref().map(|progress| progress.add(pull_bar(&image)));
+        let result = self.stream_pull(&image, bar.as_ref()).await;
+
+        match (&bar, &result) {
+            (Some(bar), Ok(())) => bar.finish_with_message(format!("{} pulled", image)),
+            (Some(bar), Err(_)) => bar.abandon_with_message(format!("{} failed", image)),
+            _ => {}
+        }
+        *pulled = result.is_ok();
+        result
+    }
+
+    async fn stream_pull(&self, image: &str, bar: Option<&ProgressBar>) -> Result<()> {
+        let options = Some(CreateImageOptions {
+            from_image: image,
+            ..Default::default()
+        });
+
+        let mut layers = LayerProgress::default();
+        let mut stream = self.docker.create_image(options, None, None);
+
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(info) => {
+                    if let Some(message) = info.error {
+                        anyhow::bail!("Failed to pull {}: {}", image, message);
+                    }
+                    layers.update(&info);
+                    match bar {
+                        Some(bar) => {
+                            bar.set_length(layers.total().max(1));
+                            bar.set_position(layers.current());
+                            bar.set_message(format!("{} ({}/{} layers)", image, layers.done(), layers.count()));
+                        }
+                        None => {
+                            if let Some(status) = info.status.filter(|status| status.contains("Download") || status.contains("Pull")) {
+                                info!("{}", status);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Error pulling image: {}", e);
+                    return Err(e).with_context(|| format!("Failed to pull image {}", image));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn pull_bar(image: &str) -> ProgressBar {
+    let style = ProgressStyle::with_template("{spinner:.cyan} {msg:40!} [{bar:30.cyan/blue}] {bytes}/{total_bytes}")
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("=> ");
+    let bar = ProgressBar::new(1).with_style(style).with_message(image.to_string());
+    bar.enable_steady_tick(std::time::Duration::from_millis(120));
+    bar
+}
+
+/// Bytes downloaded per layer, from the pull's status messages
+#[derive(Default)]
+struct LayerProgress {
+    layers: HashMap<String, (u64, u64, bool)>,
+}
+
+impl LayerProgress {
+    fn update(&mut self, info: &CreateImageInfo) {
+        let (Some(id), Some(status)) = (&info.id, &info.status) else { return };
+        // The first message of a pull carries the tag as its id
+        if status.starts_with("Pulling from") {
+            return;
+        }
+
+        let layer = self.layers.entry(id.clone()).or_default();
+        if let Some(detail) = &info.progress_detail {
+            if status == "Downloading" {
+                layer.0 = detail.current.unwrap_or(0).max(0) as u64;
+                layer.1 = detail.total.unwrap_or(0).max(0) as u64;
+            }
+        }
+        if matches!(status.as_str(), "Download complete" | "Pull complete" | "Already exists") {
+            layer.0 = layer.1;
+            layer.2 = true;
+        }
+    }
+
+    fn current(&self) -> u64 {
+        self.layers.values().map(|layer| layer.0).sum()
+    }
+
+    fn total(&self) -> u64 {
+        self.layers.values().map(|layer| layer.1).sum()
+    }
+
+    fn done(&self) -> usize {
+        self.layers.values().filter(|layer| layer.2).count()
+    }
+
+    fn count(&self) -> usize {
+        self.layers.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::ProgressDetail;
+
+    fn status(id: &str, status: &str, progress: Option<(i64, i64)>) -> CreateImageInfo {
+        CreateImageInfo {
+            id: Some(id.to_string()),
+            status: Some(status.to_string()),
+            progress_detail: progress.map(|(current, total)| ProgressDetail { current: Some(current), total: Some(total) }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_layer_progress() {
+        let mut layers = LayerProgress::default();
+        layers.update(&status("16", "Pulling from library/postgres", None));
+        layers.update(&status("a1", "Already exists", None));
+        layers.update(&status("b2", "Downloading", Some((512, 2048))));
+        layers.update(&status("c3", "Downloading", Some((0, 1024))));
+        assert_eq!((layers.current(), layers.total()), (512, 3072));
+        assert_eq!((layers.done(), layers.count()), (1, 3));
+
+        layers.update(&status("b2", "Download complete", None));
+        assert_eq!(layers.current(), 2048);
+        assert_eq!(layers.done(), 2);
+    }
+}
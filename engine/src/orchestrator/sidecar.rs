@@ -0,0 +1,205 @@
+use anyhow::{Context, Result};
+use bollard::container::{Config, CreateContainerOptions, StartContainerOptions, UploadToContainerOptions};
+use bollard::models::{HostConfig, PortBinding};
+use std::collections::HashMap;
+use tracing::info;
+
+use super::{ContainerOrchestrator, PROJECT_LABEL, SERVICE_LABEL};
+
+/// A supporting container zero.yml doesn't list as a service, e.g. Prometheus for `observability: true`
+#[derive(Debug, Clone, Default)]
+pub struct Sidecar {
+    /// Name within the project; the container is `{project}_{name}`
+    pub name: String,
+    pub image: String,
+    pub env: Vec<String>,
+    pub cmd: Option<Vec<String>>,
+    /// (container port, host port) pairs to publish
+    pub ports: Vec<(u16, u16)>,
+    pub binds: Vec<String>,
+    pub extra_hosts: Vec<String>,
+    pub privileged: bool,
+    /// Files written into the container before it starts
+    pub files: Vec<SidecarFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SidecarFile {
+    /// Absolute path in the container
+    pub path: String,
+    pub content: String,
+    pub mode: u32,
+    /// User id owning the file
+    pub owner: u32,
+}
+
+impl SidecarFile {
+    /// A world-readable file owned by root
+    pub fn new(path: impl Into<String>, content: impl Into<String>) -> Self {
+        Self { path: path.into(), content: content.into(), mode: 0o644, owner: 0 }
+    }
+
+    /// Make the file readable by `owner` only, as password files require
+    pub fn private(mut self, owner: u32) -> Self {
+        self.mode = 0o600;
+        self.owner = owner;
+        self
+    }
+}
+
+impl ContainerOrchestrator {
+    /// Create and start a sidecar on the project network, replacing an existing one
+    pub async fn start_sidecar(&self, sidecar: &Sidecar) -> Result<String> {
+        let container_name = format!("{}_{}", self.project_name, sidecar.name);
+        let image = self.backend.qualify_image(&sidecar.image);
+        self.pull_image(&image).await?;
+
+        let port_bindings = sidecar.ports.iter()
+            .map(|(container_port, host_port)| {
+                (
+                    format!("{}/tcp", container_port),
+                    Some(vec![PortBinding {
+                        host_ip: Some("0.0.0.0".to_string()),
+                        host_port: Some(host_port.to_string()),
+                    }]),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let config = Config {
+            image: Some(image),
+            env: Some(sidecar.env.clone()),
+            cmd: sidecar.cmd.clone(),
+            labels: Some(HashMap::from([
+                (PROJECT_LABEL.to_string(), self.project_name.clone()),
+                (SERVICE_LABEL.to_string(), sidecar.name.clone()),
+            ])),
+            host_config: Some(HostConfig {
+                port_bindings: Some(port_bindings),
+                network_mode: Some(self.network_name.clone()),
+                binds: (!sidecar.binds.is_empty()).then(|| sidecar.binds.clone()),
+                extra_hosts: (!sidecar.extra_hosts.is_empty()).then(|| sidecar.extra_hosts.clone()),
+                privileged: Some(sidecar.privileged),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        self.remove_container(&container_name).await?;
+        let container = self.docker
+            .create_container(Some(CreateContainerOptions { name: container_name.clone(), platform: None }), config)
+            .await
+            .with_context(|| format!("Failed to create container for {}", sidecar.name))?;
+
+        if !sidecar.files.is_empty() {
+            self.copy_files(&sidecar.name, &sidecar.files).await?;
+        }
+
+        self.docker
+            .start_container(&container_name, None::<StartContainerOptions<String>>)
+            .await
+            .with_context(|| format!("Failed to start {}", sidecar.name))?;
+
+        info!("Started sidecar {} ({})", container_name, sidecar.image);
+        Ok(container.id)
+    }
+
+    /// Write files into the container of a service or sidecar
+    pub async fn copy_files(&self, name: &str, files: &[SidecarFile]) -> Result<()> {
+        let options = UploadToContainerOptions { path: "/".to_string(), ..Default::default() };
+        self.docker
+            .upload_to_container(&format!("{}_{}", self.project_name, name), Some(options), tar(files)?.into())
+            .await
+            .with_context(|| format!("Failed to copy files into {}", name))
+    }
+}
+
+/// Pack files into an uncompressed ustar archive, the format Docker's archive API takes
+fn tar(files: &[SidecarFile]) -> Result<Vec<u8>> {
+    let mut archive = Vec::new();
+    for file in files {
+        tar_entry(&mut archive, &file.path, file.mode, file.owner, file.content.as_bytes())?;
+    }
+    Ok(tar_finish(archive))
+}
+
+/// Append a regular file to a ustar archive
+///
+/// Paths longer than 100 bytes are split into ustar's prefix and name at a `/`.
+pub(crate) fn tar_entry(archive: &mut Vec<u8>, path: &str, mode: u32, owner: u32, content: &[u8]) -> Result<()> {
+    tar_header(archive, path, mode, owner, content.len(), b'0', "")?;
+    archive.extend_from_slice(content);
+    archive.resize(archive.len().div_ceil(512) * 512, 0);
+    Ok(())
+}
+
+/// Append a symbolic link to `target` to a ustar archive
+pub(crate) fn tar_symlink(archive: &mut Vec<u8>, path: &str, target: &str) -> Result<()> {
+    if target.len() > 100 {
+        anyhow::bail!("Link target too long for the archive: {}", target);
+    }
+    tar_header(archive, path, 0o777, 0, 0, b'2', target)
+}
+
+fn tar_header(archive: &mut Vec<u8>, path: &str, mode: u32, owner: u32, size: usize, kind: u8, link: &str) -> Result<()> {
+    let path = path.trim_start_matches('/');
+    let (prefix, name) = match path.len() {
+        0..=100 => ("", path),
+        _ => path.char_indices()
+            .filter(|&(index, c)| c == '/' && index <= 155 && path.len() - index - 1 <= 100)
+            .map(|(index, _)| (&path[..index], &path[index + 1..]))
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Path too long for the archive: {}", path))?,
+    };
+
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(format!("{:07o}\0", mode).as_bytes());
+    header[108..116].copy_from_slice(format!("{:07o}\0", owner).as_bytes());
+    header[116..124].copy_from_slice(format!("{:07o}\0", owner).as_bytes());
+    header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+    header[136..148].copy_from_slice(b"00000000000\0");
+    header[156] = kind;
+    header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+    header[257..265].copy_from_slice(b"ustar\x0000");
+    header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+
+    // The checksum is computed with its own field filled with spaces
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    archive.extend_from_slice(&header);
+    Ok(())
+}
+
+/// End an archive with the two empty blocks ustar requires
+pub(crate) fn tar_finish(mut archive: Vec<u8>) -> Vec<u8> {
+    archive.resize(archive.len() + 1024, 0);
+    archive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tar_layout() {
+        let archive = tar(&[SidecarFile::new("/etc/prometheus/prometheus.yml", "global: {}\n")]).unwrap();
+        assert_eq!(archive.len(), 512 * 4);
+        assert!(archive.starts_with(b"etc/prometheus/prometheus.yml\0"));
+        assert_eq!(&archive[124..136], b"00000000013\0");
+        assert_eq!(&archive[512..523], b"global: {}\n");
+
+        let mut header = archive[..512].to_vec();
+        header[148..156].copy_from_slice(b"        ");
+        let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+        assert_eq!(&archive[148..154], format!("{:06o}", checksum).as_bytes());
+
+        let archive = tar(&[SidecarFile::new("/pgpass", "").private(5050)]).unwrap();
+        assert_eq!(&archive[100..108], b"0000600\0");
+        assert_eq!(&archive[108..116], b"0011672\0");
+
+        assert!(tar(&[SidecarFile::new(format!("/{}", "a".repeat(101)), "")]).is_err());
+    }
+}
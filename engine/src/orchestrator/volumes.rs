@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use bollard::container::{Config, CreateContainerOptions, ListContainersOptions, LogOutput, LogsOptions, WaitContainerOptions};
+use bollard::models::{HostConfig, MountPointTypeEnum, Volume};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::info;
+
+use super::ContainerOrchestrator;
+
+/// Image used for the short-lived container that lists volume contents
+const HELPER_IMAGE: &str = "alpine:latest";
+
+/// Where the helper container mounts the volume being browsed
+const HELPER_MOUNT: &str = "/volume";
+
+/// A Docker volume mounted by one of the project's containers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectVolume {
+    pub name: String,
+    pub driver: Option<String>,
+    /// Size on disk in bytes, when Docker reports it
+    pub size: Option<i64>,
+    pub mounts: Vec<VolumeMount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeMount {
+    pub service: String,
+    pub destination: String,
+    pub read_only: bool,
+}
+
+/// A file or directory inside a volume
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeEntry {
+    pub name: String,
+    /// Path relative to the volume root
+    pub path: String,
+    pub kind: EntryKind,
+    pub size: u64,
+    /// Modification time as a Unix timestamp
+    pub modified: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+impl ContainerOrchestrator {
+    /// List the volumes mounted by this project's containers, with their sizes
+    pub async fn list_volumes(&self) -> Result<Vec<ProjectVolume>> {
+        let prefix = format!("{}_", self.project_name);
+        let mut volumes: Vec<ProjectVolume> = Vec::new();
+
+        for container in self.list_containers().await? {
+            let Some(service) = container.names.iter().flatten()
+                .find_map(|name| name.trim_start_matches('/').strip_prefix(&prefix))
+                .map(str::to_string)
+            else {
+                continue;
+            };
+
+            for mount in container.mounts.iter().flatten() {
+                if mount.typ != Some(MountPointTypeEnum::VOLUME) {
+                    continue;
+                }
+                let Some(name) = &mount.name else { continue };
+
+                let entry = match volumes.iter_mut().find(|volume| &volume.name == name) {
+                    Some(volume) => volume,
+                    None => {
+                        volumes.push(ProjectVolume {
+                            name: name.clone(),
+                            driver: mount.driver.clone(),
+                            size: None,
+                            mounts: Vec::new(),
+                        });
+                        volumes.last_mut().expect("volume was just pushed")
+                    }
+                };
+                entry.mounts.push(VolumeMount {
+                    service: service.clone(),
+                    destination: mount.destination.clone().unwrap_or_default(),
+                    read_only: mount.rw == Some(false),
+                });
+            }
+        }
+
+        // Sizes come from `docker system df`, which can be slow; treat them as optional
+        if !volumes.is_empty() {
+            if let Ok(usage) = self.docker.df().await {
+                let sizes: HashMap<String, i64> = usage.volumes.into_iter().flatten()
+                    .filter_map(|volume| {
+                        let size = volume.usage_data.as_ref()?.size;
+                        // Docker reports -1 when the size is not available
+                        (size >= 0).then_some((volume.name, size))
+                    })
+                    .collect();
+                for volume in &mut volumes {
+                    volume.size = sizes.get(&volume.name).copied();
+                }
+            }
+        }
+
+        volumes.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(volumes)
+    }
+
+    /// Full Docker details of a volume
+    pub async fn inspect_volume(&self, name: &str) -> Result<Volume> {
+        self.docker.inspect_volume(name).await
+            .with_context(|| format!("Failed to inspect volume '{}'", name))
+    }
+
+    /// List the entries of a directory inside a volume
+    ///
+    /// The volume is mounted read-only into a throwaway helper container, so
+    /// this works whether or not the owning service is running.
+    pub async fn browse_volume(&self, name: &str, path: &str) -> Result<Vec<VolumeEntry>> {
+        let path = normalize_volume_path(path)?;
+
+        // Make sure the volume exists rather than letting Docker create an empty one
+        self.inspect_volume(name).await?;
+        self.pull_image(HELPER_IMAGE).await?;
+
+        // Not prefixed with the project name so it never shows up as a service
+        let nonce = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos())
+            .unwrap_or_default();
+        let container_name = format!("zeroconfig-volume-browser-{}-{}", std::process::id(), nonce);
+        let script = format!(
+            r#"find "{}/$VOLUME_PATH" -mindepth 1 -maxdepth 1 -exec stat -c '%F|%s|%Y|%n' {{}} +"#,
+            HELPER_MOUNT,
+        );
+
+        let config = Config {
+            image: Some(self.backend.qualify_image(HELPER_IMAGE)),
+            cmd: Some(vec!["sh".to_string(), "-c".to_string(), script]),
+            env: Some(vec![format!("VOLUME_PATH={}", path)]),
+            host_config: Some(HostConfig {
+                binds: Some(vec![format!("{}:{}:ro", name, HELPER_MOUNT)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        self.remove_container(&container_name).await?;
+        self.docker
+            .create_container(Some(CreateContainerOptions { name: container_name.clone(), platform: None }), config)
+            .await
+            .context("Failed to create volume browser container")?;
+
+        let output = self.run_helper(&container_name).await;
+        self.remove_container(&container_name).await?;
+        let (stdout, stderr, exit_code) = output?;
+
+        if exit_code != 0 {
+            anyhow::bail!("Failed to list '{}' in volume '{}': {}", path, name, stderr.trim());
+        }
+
+        Ok(parse_listing(&stdout))
+    }
+
+    /// Delete a project volume, removing the containers that use it first
+    ///
+    /// Refuses volumes that belong to other projects. Returns the services
+    /// whose containers were removed; they recreate the volume on next start.
+    pub async fn delete_volume(&self, name: &str) -> Result<Vec<String>> {
+        let volume = self.list_volumes().await?
+            .into_iter()
+            .find(|volume| volume.name == name)
+            .with_context(|| format!("Volume '{}' is not used by project '{}'", name, self.project_name))?;
+
+        let prefix = format!("{}_", self.project_name);
+        let options = Some(ListContainersOptions {
+            all: true,
+            filters: HashMap::from([("volume".to_string(), vec![name.to_string()])]),
+            ..Default::default()
+        });
+        for container in self.docker.list_containers(options).await.context("Failed to list containers")? {
+            for container_name in container.names.iter().flatten() {
+                let container_name = container_name.trim_start_matches('/');
+                if !container_name.starts_with(&prefix) {
+                    anyhow::bail!("Volume '{}' is also used by container '{}'", name, container_name);
+                }
+            }
+        }
+
+        let mut services: Vec<String> = volume.mounts.into_iter().map(|mount| mount.service).collect();
+        services.sort();
+        services.dedup();
+
+        for service in &services {
+            self.stop_service(service).await?;
+            self.remove_container(&format!("{}{}", prefix, service)).await?;
+        }
+
+        self.docker.remove_volume(name, None).await
+            .with_context(|| format!("Failed to delete volume '{}'", name))?;
+
+        info!("Deleted volume {}", name);
+        Ok(services)
+    }
+
+    /// Start a helper container, wait for it and collect stdout, stderr and exit code
+    async fn run_helper(&self, container_name: &str) -> Result<(String, String, i64)> {
+        self.docker
+            .start_container::<String>(container_name, None)
+            .await
+            .context("Failed to start helper container")?;
+
+        // A non-zero exit is reported as an error by the wait endpoint
+        let exit_code = match self.docker
+            .wait_container(container_name, None::<WaitContainerOptions<String>>)
+            .next()
+            .await
+        {
+            Some(Ok(response)) => response.status_code,
+            Some(Err(bollard::errors::Error::DockerContainerWaitError { code, .. })) => code,
+            Some(Err(e)) => return Err(e).context("Failed to wait for helper container"),
+            None => 0,
+        };
+
+        let options = Some(LogsOptions::<String> {
+            stdout: true,
+            stderr: true,
+            ..Default::default()
+        });
+        let mut logs = self.docker.logs(container_name, options);
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        while let Some(chunk) = logs.next().await {
+            match chunk.context("Failed to read helper output")? {
+                LogOutput::StdOut { message } => stdout.push_str(&String::from_utf8_lossy(&message)),
+                LogOutput::StdErr { message } => stderr.push_str(&String::from_utf8_lossy(&message)),
+                _ => {}
+            }
+        }
+
+        Ok((stdout, stderr, exit_code))
+    }
+}
+
+/// Turn a user-supplied path into a clean path relative to the volume root
+fn normalize_volume_path(path: &str) -> Result<String> {
+    let mut parts = Vec::new();
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => anyhow::bail!("Volume paths may not contain '..'"),
+            part => parts.push(part),
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+/// Parse `stat -c '%F|%s|%Y|%n'` lines from the helper container
+fn parse_listing(output: &str) -> Vec<VolumeEntry> {
+    let root = format!("{}/", HELPER_MOUNT);
+
+    let mut entries: Vec<VolumeEntry> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '|');
+            let kind = match fields.next()? {
+                "directory" => EntryKind::Directory,
+                "symbolic link" => EntryKind::Symlink,
+                kind if kind.starts_with("regular") => EntryKind::File,
+                _ => EntryKind::Other,
+            };
+            let size = fields.next()?.parse().ok()?;
+            let modified = fields.next()?.parse().ok()?;
+            let full_path = fields.next()?;
+
+            let path = full_path.strip_prefix(&root)?.trim_start_matches('/').to_string();
+            let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+
+            Some(VolumeEntry { name, path, kind, size, modified })
+        })
+        .collect();
+
+    // Directories first, then alphabetical
+    entries.sort_by(|a, b| {
+        (a.kind != EntryKind::Directory, &a.name).cmp(&(b.kind != EntryKind::Directory, &b.name))
+    });
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_volume_path() {
+        assert_eq!(normalize_volume_path("/").unwrap(), "");
+        assert_eq!(normalize_volume_path("./pgdata//base/").unwrap(), "pgdata/base");
+        assert!(normalize_volume_path("pgdata/../../etc").is_err());
+    }
+
+    #[test]
+    fn test_parse_listing() {
+        let output = "\
+regular file|42|1700000000|/volume/pgdata/PG_VERSION
+directory|4096|1700000001|/volume/pgdata/base
+symbolic link|9|1700000002|/volume/pgdata/a|b
+garbage line
+";
+        let entries = parse_listing(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "base");
+        assert_eq!(entries[0].kind, EntryKind::Directory);
+        assert_eq!(entries[1].path, "pgdata/PG_VERSION");
+        assert_eq!(entries[1].size, 42);
+        assert_eq!(entries[2].name, "a|b");
+        assert_eq!(entries[2].kind, EntryKind::Symlink);
+    }
+}
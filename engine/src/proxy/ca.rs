@@ -0,0 +1,194 @@
+//! Local certificate authority for the proxy, in the spirit of mkcert
+//!
+//! The CA's key and certificate live in the user's data directory and are
+//! shared by every project, so `zero proxy trust` only has to run once per
+//! machine. Host certificates are issued in memory as browsers ask for them.
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Duration, Utc};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose,
+};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding where the CA is kept
+pub const CA_DIR_ENV: &str = "ZERO_CA_DIR";
+
+const CA_NAME: &str = "ZeroConfig local CA";
+const CERT_FILE: &str = "rootCA.pem";
+const KEY_FILE: &str = "rootCA-key.pem";
+
+/// The CA, able to sign certificates for local hosts
+pub struct LocalCa {
+    dir: PathBuf,
+    cert: Certificate,
+    key: KeyPair,
+}
+
+/// A host certificate and its key, both DER-encoded
+pub struct HostCertificate {
+    pub chain: Vec<Vec<u8>>,
+    pub key: Vec<u8>,
+}
+
+/// Directory of the CA: `ZERO_CA_DIR`, else the user's data directory
+pub fn ca_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os(CA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
+    }
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let data_dir = match std::env::consts::OS {
+        "windows" => var("LOCALAPPDATA"),
+        "macos" => var("HOME").map(|home| home.join("Library").join("Application Support")),
+        _ => var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share"))),
+    };
+    let data_dir = data_dir.context("Cannot tell where to keep the local CA; set ZERO_CA_DIR")?;
+    Ok(data_dir.join("zeroconfig").join("ca"))
+}
+
+impl LocalCa {
+    /// Load the CA from a directory, creating it on first use
+    pub fn load_or_create(dir: &Path) -> Result<Self> {
+        let key_path = dir.join(KEY_FILE);
+        let key = if key_path.exists() {
+            let pem = std::fs::read_to_string(&key_path).with_context(|| format!("Failed to read {}", key_path.display()))?;
+            KeyPair::from_pem(&pem).with_context(|| format!("{} is not a private key", key_path.display()))?
+        } else {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+            let key = KeyPair::generate()?;
+            write_private(&key_path, &key.serialize_pem())?;
+            key
+        };
+
+        // Signing only needs the CA's name and key, so the certificate is rebuilt rather than parsed
+        let cert = ca_params().self_signed(&key)?;
+        let cert_path = dir.join(CERT_FILE);
+        if !cert_path.exists() {
+            std::fs::write(&cert_path, cert.pem()).with_context(|| format!("Failed to write {}", cert_path.display()))?;
+        }
+        Ok(Self { dir: dir.to_path_buf(), cert, key })
+    }
+
+    /// The CA certificate to install as trusted
+    pub fn cert_path(&self) -> PathBuf {
+        self.dir.join(CERT_FILE)
+    }
+
+    /// Issue a certificate for a host, valid for two years
+    pub fn issue(&self, host: &str) -> Result<HostCertificate> {
+        let mut params = CertificateParams::new(vec![host.to_string()])
+            .with_context(|| format!("'{}' is not a valid host name", host))?;
+        params.distinguished_name.push(DnType::CommonName, host);
+        params.distinguished_name.push(DnType::OrganizationName, CA_NAME);
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+        params.use_authority_key_identifier_extension = true;
+        let yesterday = Utc::now() - Duration::days(1);
+        params.not_before = rcgen::date_time_ymd(yesterday.year(), yesterday.month() as u8, yesterday.day() as u8);
+        params.not_after = rcgen::date_time_ymd(yesterday.year() + 2, yesterday.month() as u8, 1);
+
+        let key = KeyPair::generate()?;
+        let cert = params.signed_by(&key, &self.cert, &self.key)?;
+        Ok(HostCertificate {
+            chain: vec![cert.der().to_vec(), self.cert.der().to_vec()],
+            key: key.serialize_der(),
+        })
+    }
+}
+
+fn ca_params() -> CertificateParams {
+    let mut params = CertificateParams::default();
+    params.distinguished_name.push(DnType::CommonName, CA_NAME);
+    params.distinguished_name.push(DnType::OrganizationName, CA_NAME);
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign, KeyUsagePurpose::DigitalSignature];
+    params.not_before = rcgen::date_time_ymd(2024, 1, 1);
+    params.not_after = rcgen::date_time_ymd(2034, 1, 1);
+    params
+}
+
+/// Write a file only its owner can read
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Install the CA certificate in the trust stores of the OS and, where found, of browsers
+///
+/// System stores need administrator rights, so the commands may ask for a password.
+pub fn trust(cert_path: &Path) -> Result<Vec<String>> {
+    let nss_db = std::env::var_os("HOME").map(|home| Path::new(&home).join(".pki").join("nssdb")).filter(|db| db.exists());
+    let commands = trust_commands(std::env::consts::OS, cert_path, Path::exists, nss_db.as_deref());
+    anyhow::ensure!(!commands.is_empty(), "No known trust store on this system; import {} by hand", cert_path.display());
+
+    let mut installed = Vec::new();
+    for command in commands {
+        let status = std::process::Command::new(&command[0])
+            .args(&command[1..])
+            .status()
+            .with_context(|| format!("Failed to run {}", command[0]))?;
+        anyhow::ensure!(status.success(), "`{}` exited with {}", command.join(" "), status);
+        installed.push(command.join(" "));
+    }
+    Ok(installed)
+}
+
+/// Commands that trust a CA certificate on an OS, all run in order
+fn trust_commands(os: &str, cert: &Path, exists: impl Fn(&Path) -> bool, nss_db: Option<&Path>) -> Vec<Vec<String>> {
+    let cert = cert.display().to_string();
+    let command = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+    let mut commands = match os {
+        "macos" => vec![command(&["security", "add-trusted-cert", "-r", "trustRoot", "-k",
+            &format!("{}/Library/Keychains/login.keychain-db", std::env::var("HOME").unwrap_or_default()), &cert])],
+        "windows" => vec![command(&["certutil", "-user", "-addstore", "-f", "Root", &cert])],
+        _ if exists(Path::new("/usr/local/share/ca-certificates")) => vec![
+            command(&["sudo", "cp", &cert, "/usr/local/share/ca-certificates/zeroconfig-local-ca.crt"]),
+            command(&["sudo", "update-ca-certificates"]),
+        ],
+        _ if exists(Path::new("/etc/pki/ca-trust/source/anchors")) => vec![
+            command(&["sudo", "cp", &cert, "/etc/pki/ca-trust/source/anchors/zeroconfig-local-ca.pem"]),
+            command(&["sudo", "update-ca-trust"]),
+        ],
+        _ if exists(Path::new("/etc/ca-certificates/trust-source/anchors")) => vec![command(&["sudo", "trust", "anchor", &cert])],
+        _ => vec![],
+    };
+    // Chrome and Firefox on Linux read their own NSS database rather than the system store
+    if let Some(db) = nss_db.filter(|_| os == "linux") {
+        commands.push(command(&["certutil", "-d", &format!("sql:{}", db.display()), "-A", "-t", "C,,", "-n", CA_NAME, "-i", &cert]));
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ca_is_created_once_and_issues_host_certificates() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = LocalCa::load_or_create(dir.path()).unwrap();
+        let pem = std::fs::read_to_string(ca.cert_path()).unwrap();
+        let issued = ca.issue("api.localhost").unwrap();
+        assert_eq!(issued.chain.len(), 2);
+        assert!(!issued.key.is_empty());
+
+        let reloaded = LocalCa::load_or_create(dir.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(reloaded.cert_path()).unwrap(), pem);
+        assert_eq!(reloaded.key.public_key_der(), ca.key.public_key_der());
+    }
+
+    #[test]
+    fn test_trust_commands() {
+        let cert = Path::new("/ca/rootCA.pem");
+        let debian = trust_commands("linux", cert, |path| path.ends_with("ca-certificates"), Some(Path::new("/home/me/.pki/nssdb")));
+        assert_eq!(debian[1], vec!["sudo", "update-ca-certificates"]);
+        assert_eq!(debian[2][..3], ["certutil", "-d", "sql:/home/me/.pki/nssdb"]);
+        assert_eq!(trust_commands("windows", cert, |_| false, None)[0][..4], ["certutil", "-user", "-addstore", "-f"]);
+        assert!(trust_commands("freebsd", cert, |_| false, None).is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Reverse proxy giving services names under `.localhost`
+//!
+//! `zero proxy` listens on one port and sends each connection to the service
+//! its host names: `api.localhost` reaches the host port of the `api` service,
+//! as do subdomains like `tenant.api.localhost`. With `proxy.tls` connections
+//! are TLS, routed by SNI, with certificates from the local CA (see [`ca`]);
+//! otherwise they are plain HTTP, routed by the `Host` header. After routing,
+//! bytes are piped both ways, so WebSockets and streaming work unchanged.
+
+pub mod ca;
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::server::Acceptor;
+use tokio_rustls::rustls::{self, ServerConfig};
+use tokio_rustls::LazyConfigAcceptor;
+use tracing::{debug, warn};
+
+use crate::config::ProxyConfig;
+use crate::orchestrator::ContainerOrchestrator;
+use ca::LocalCa;
+
+/// Domain every service gets a name under; browsers resolve it to loopback
+pub const DOMAIN: &str = "localhost";
+
+/// Most a plain HTTP request head may take before the proxy gives up on finding its host
+const MAX_HEAD: usize = 16 * 1024;
+
+/// The proxy of a project, routing to its running services
+pub struct Proxy {
+    config: ProxyConfig,
+    services: Vec<String>,
+    orchestrator: ContainerOrchestrator,
+    ca: Option<LocalCa>,
+    tls_configs: Mutex<HashMap<String, Arc<ServerConfig>>>,
+}
+
+impl Proxy {
+    /// Proxy for services, loading the local CA when serving TLS
+    pub fn new(config: ProxyConfig, services: Vec<String>, orchestrator: ContainerOrchestrator) -> Result<Self> {
+        let ca = match config.tls {
+            true => Some(LocalCa::load_or_create(&ca::ca_dir()?)?),
+            false => None,
+        };
+        Ok(Self { config, services, orchestrator, ca, tls_configs: Mutex::new(HashMap::new()) })
+    }
+
+    /// URL a service is reached at through the proxy
+    pub fn url(&self, service: &str) -> String {
+        let scheme = if self.config.tls { "https" } else { "http" };
+        match (self.config.tls, self.config.port()) {
+            (true, 443) | (false, 80) => format!("{}://{}.{}", scheme, service, DOMAIN),
+            (_, port) => format!("{}://{}.{}:{}", scheme, service, DOMAIN, port),
+        }
+    }
+
+    /// The CA certificate browsers need to trust, when serving TLS
+    pub fn ca_cert_path(&self) -> Option<std::path::PathBuf> {
+        self.ca.as_ref().map(LocalCa::cert_path)
+    }
+
+    /// Accept connections until the task is dropped
+    pub async fn serve(self: Arc<Self>) -> Result<()> {
+        let port = self.config.port();
+        let listener = TcpListener::bind(("127.0.0.1", port)).await
+            .with_context(|| format!("Failed to listen on port {}; set proxy.port to another one", port))?;
+
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let proxy = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = proxy.handle(stream).await {
+                    debug!("Proxy connection from {} ended: {:#}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, stream: TcpStream) -> Result<()> {
+        if self.ca.is_none() {
+            return self.handle_plain(stream).await;
+        }
+
+        let start = LazyConfigAcceptor::new(Acceptor::default(), stream).await?;
+        let host = start.client_hello().server_name()
+            .context("TLS client sent no server name")?
+            .to_string();
+        let mut client = start.into_stream(self.tls_config(&host)?).await?;
+        self.forward(&host, &mut client, &[]).await
+    }
+
+    async fn handle_plain(&self, mut client: TcpStream) -> Result<()> {
+        let mut head = Vec::new();
+        let host = loop {
+            let mut buffer = [0u8; 4096];
+            let read = client.read(&mut buffer).await?;
+            anyhow::ensure!(read > 0, "Connection closed before the request head");
+            head.extend_from_slice(&buffer[..read]);
+            if let Some(host) = host_header(&head) {
+                break host;
+            }
+            if head.windows(4).any(|window| window == b"\r\n\r\n") || head.len() > MAX_HEAD {
+                respond(&mut client, "400 Bad Request", "The request has no Host header").await?;
+                return Ok(());
+            }
+        };
+        self.forward(&host, &mut client, &head).await
+    }
+
+    /// Connect a client to the service its host names, replaying what was already read
+    async fn forward<S: AsyncRead + AsyncWrite + Unpin>(&self, host: &str, client: &mut S, head: &[u8]) -> Result<()> {
+        let Some(service) = service_for_host(host, &self.services) else {
+            let message = format!("No service is named {}; try one of: {}", host, self.services.join(", "));
+            return respond(client, "404 Not Found", &message).await;
+        };
+        let port = match self.orchestrator.get_host_port(&service).await {
+            Ok(port) => port,
+            Err(e) => return respond(client, "502 Bad Gateway", &format!("{:#}", e)).await,
+        };
+        let mut upstream = match TcpStream::connect((self.orchestrator.published_host(), port)).await {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                warn!("Proxy could not reach {} on port {}: {}", service, port, e);
+                return respond(client, "502 Bad Gateway", &format!("{} is not answering on port {}", service, port)).await;
+            }
+        };
+        upstream.write_all(head).await?;
+        tokio::io::copy_bidirectional(client, &mut upstream).await?;
+        Ok(())
+    }
+
+    /// TLS settings for a host, with a certificate issued for it on first use
+    fn tls_config(&self, host: &str) -> Result<Arc<ServerConfig>> {
+        let mut configs = self.tls_configs.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(config) = configs.get(host) {
+            return Ok(config.clone());
+        }
+
+        let ca = self.ca.as_ref().context("The proxy is not serving TLS")?;
+        let issued = ca.issue(host)?;
+        let chain = issued.chain.into_iter().map(CertificateDer::from).collect();
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(issued.key));
+        let mut config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_single_cert(chain, key)?;
+
+        // Only services that speak HTTP/2 themselves may be offered it, as the bytes pass through as they are
+        let http2 = service_for_host(host, &self.services).is_some_and(|service| self.config.http2.contains(&service));
+        config.alpn_protocols = match http2 {
+            true => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            false => vec![b"http/1.1".to_vec()],
+        };
+
+        let config = Arc::new(config);
+        configs.insert(host.to_string(), config.clone());
+        Ok(config)
+    }
+}
+
+/// Service a host name routes to: the label right before `.localhost`
+pub fn service_for_host(host: &str, services: &[String]) -> Option<String> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name.to_string(),
+        _ => host,
+    };
+    let name = host.strip_suffix(DOMAIN)?.strip_suffix('.')?;
+    let service = name.rsplit('.').next()?;
+    services.iter().find(|candidate| candidate.to_ascii_lowercase() == service).cloned()
+}
+
+/// Value of the Host header in a request head, once it has been read
+fn host_header(head: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(head);
+    let (head, _) = text.split_once("\r\n\r\n")?;
+    head.lines().skip(1).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("host").then(|| value.trim().to_string())
+    })
+}
+
+/// Answer a client with a plain-text error page and close
+async fn respond<S: AsyncWrite + Unpin>(client: &mut S, status: &str, message: &str) -> Result<()> {
+    let body = format!("{}\n", message);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body,
+    );
+    client.write_all(response.as_bytes()).await?;
+    client.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routing_by_host() {
+        let services = vec!["api".to_string(), "web".to_string()];
+        assert_eq!(service_for_host("api.localhost", &services).as_deref(), Some("api"));
+        assert_eq!(service_for_host("tenant.API.localhost:8443", &services).as_deref(), Some("api"));
+        assert_eq!(service_for_host("web.localhost.", &services).as_deref(), Some("web"));
+        assert_eq!(service_for_host("localhost", &services), None);
+        assert_eq!(service_for_host("db.localhost", &services), None);
+        assert_eq!(service_for_host("api.example.com", &services), None);
+
+        assert_eq!(host_header(b"GET / HTTP/1.1\r\nhost: api.localhost:8080\r\n\r\n").as_deref(), Some("api.localhost:8080"));
+        assert_eq!(host_header(b"GET / HTTP/1.1\r\nHost: api.local"), None);
+    }
+}
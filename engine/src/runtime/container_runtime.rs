@@ -18,6 +18,21 @@ pub enum ContainerRuntime {
 }
 
 impl ContainerRuntime {
+    /// All supported runtimes, in detection order
+    pub fn all() -> &'static [ContainerRuntime] {
+        &[
+            Self::Docker,
+            Self::Podman,
+            Self::Minikube,
+            Self::Kubernetes,
+            Self::DockerCompose,
+            Self::Containerd,
+            Self::CriO,
+            Self::Nerdctl,
+            Self::Colima,
+        ]
+    }
+
     /// Get the command name for this runtime
     pub fn command(&self) -> &'static str {
         match self {
@@ -266,6 +281,18 @@ impl ContainerRuntime {
     }
 }
 
+impl std::str::FromStr for ContainerRuntime {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value.to_lowercase();
+        ContainerRuntime::all().iter().copied()
+            .find(|runtime| runtime.command() == value || runtime.name().to_lowercase() == value)
+            .or((value == "k8s").then_some(ContainerRuntime::Kubernetes))
+            .ok_or_else(|| anyhow!("Unknown runtime '{}', use docker, podman, kubernetes or minikube", value))
+    }
+}
+
 /// Container runtime manager that detects and manages available runtimes
 pub struct ContainerRuntimeManager {
     available_runtimes: Vec<ContainerRuntime>,
@@ -285,19 +312,7 @@ impl ContainerRuntimeManager {
     pub async fn detect_runtimes(&mut self) -> Result<()> {
         info!("Detecting available container runtimes...");
 
-        let all_runtimes = vec![
-            ContainerRuntime::Docker,
-            ContainerRuntime::Podman,
-            ContainerRuntime::Minikube,
-            ContainerRuntime::Kubernetes,
-            ContainerRuntime::DockerCompose,
-            ContainerRuntime::Containerd,
-            ContainerRuntime::CriO,
-            ContainerRuntime::Nerdctl,
-            ContainerRuntime::Colima,
-        ];
-
-        for runtime in all_runtimes {
+        for &runtime in ContainerRuntime::all() {
             if runtime.is_installed().await {
                 info!("Found {} - {}", runtime.name(), runtime.get_version().await.unwrap_or_else(|_| "version unknown".to_string()));
                 self.available_runtimes.push(runtime);
@@ -305,17 +320,13 @@ impl ContainerRuntimeManager {
                 // Set preferred runtime (prioritize Docker, then Podman, then others)
                 if self.preferred_runtime.is_none() {
                     match runtime {
-                        ContainerRuntime::Docker => {
-                            if runtime.is_running().await {
-                                self.preferred_runtime = Some(runtime);
-                                info!("Using {} as preferred runtime", runtime.name());
-                            }
+                        ContainerRuntime::Docker if runtime.is_running().await => {
+                            self.preferred_runtime = Some(runtime);
+                            info!("Using {} as preferred runtime", runtime.name());
                         }
-                        ContainerRuntime::Podman => {
-                            if self.preferred_runtime.is_none() && runtime.is_running().await {
-                                self.preferred_runtime = Some(runtime);
-                                info!("Using {} as preferred runtime", runtime.name());
-                            }
+                        ContainerRuntime::Podman if runtime.is_running().await => {
+                            self.preferred_runtime = Some(runtime);
+                            info!("Using {} as preferred runtime", runtime.name());
                         }
                         _ => {}
                     }
@@ -458,5 +469,9 @@ mod tests {
         assert!(ContainerRuntime::Kubernetes.is_kubernetes_compatible());
         assert!(ContainerRuntime::Minikube.is_kubernetes_compatible());
         assert!(!ContainerRuntime::Docker.is_kubernetes_compatible());
+        assert_eq!("kubernetes".parse::<ContainerRuntime>().unwrap(), ContainerRuntime::Kubernetes);
+        assert_eq!("k8s".parse::<ContainerRuntime>().unwrap(), ContainerRuntime::Kubernetes);
+        assert_eq!("Minikube".parse::<ContainerRuntime>().unwrap(), ContainerRuntime::Minikube);
+        assert!("kind".parse::<ContainerRuntime>().is_err());
     }
 }
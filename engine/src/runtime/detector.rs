@@ -1,2 +1,470 @@
-// Runtime detector module
-// This module provides runtime detection utilities
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::config::migrate::CURRENT_VERSION;
+use crate::config::schema::{KNOWN_LANGUAGES, KNOWN_SERVICES};
+
+/// What a project folder appears to be built with, used to pre-fill zero.yml
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectStack {
+    pub languages: Vec<DetectedLanguage>,
+    /// Frameworks found in dependencies, e.g. `next` or `django`
+    pub frameworks: Vec<String>,
+    pub services: Vec<DetectedService>,
+    /// Existing compose file the services were read from
+    pub compose_file: Option<String>,
+    /// Template best matching the stack (see `zero init --template`)
+    pub template: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    pub name: String,
+    /// Version pinned by the project, if any
+    pub version: Option<String>,
+    /// File the language was detected from
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedService {
+    /// zero.yml service name, e.g. `postgres`
+    pub name: String,
+    /// Why the service is suggested, e.g. `pg in package.json`
+    pub reason: String,
+}
+
+/// Dependencies that imply a backing service: (package or module path, service)
+const SERVICE_HINTS: &[(&str, &str)] = &[
+    // Node
+    ("pg", "postgres"),
+    ("postgres", "postgres"),
+    ("mysql", "mysql"),
+    ("mysql2", "mysql"),
+    ("mongodb", "mongodb"),
+    ("mongoose", "mongodb"),
+    ("redis", "redis"),
+    ("ioredis", "redis"),
+    ("bullmq", "redis"),
+    ("amqplib", "rabbitmq"),
+    ("kafkajs", "kafka"),
+    ("@elastic/elasticsearch", "elasticsearch"),
+    ("minio", "minio"),
+    ("nats", "nats"),
+    ("@clickhouse/client", "clickhouse"),
+    ("cassandra-driver", "cassandra"),
+    ("memjs", "memcached"),
+    // Python
+    ("psycopg", "postgres"),
+    ("psycopg2", "postgres"),
+    ("psycopg2-binary", "postgres"),
+    ("asyncpg", "postgres"),
+    ("pymysql", "mysql"),
+    ("mysqlclient", "mysql"),
+    ("pymongo", "mongodb"),
+    ("motor", "mongodb"),
+    ("celery", "redis"),
+    ("pika", "rabbitmq"),
+    ("kafka-python", "kafka"),
+    ("confluent-kafka", "kafka"),
+    ("elasticsearch", "elasticsearch"),
+    ("nats-py", "nats"),
+    ("clickhouse-connect", "clickhouse"),
+    ("pymemcache", "memcached"),
+    // Rust
+    ("tokio-postgres", "postgres"),
+    ("mysql_async", "mysql"),
+    ("lapin", "rabbitmq"),
+    ("rdkafka", "kafka"),
+    ("async-nats", "nats"),
+    ("clickhouse", "clickhouse"),
+    ("scylla", "cassandra"),
+    // Java (Maven artifact ids)
+    ("postgresql", "postgres"),
+    ("mysql-connector-j", "mysql"),
+    ("mysql-connector-java", "mysql"),
+    ("spring-boot-starter-data-redis", "redis"),
+    ("jedis", "redis"),
+    ("lettuce-core", "redis"),
+    ("spring-boot-starter-data-mongodb", "mongodb"),
+    ("mongodb-driver-sync", "mongodb"),
+    ("spring-boot-starter-amqp", "rabbitmq"),
+    ("kafka-clients", "kafka"),
+    ("spring-kafka", "kafka"),
+    ("spring-boot-starter-data-elasticsearch", "elasticsearch"),
+    ("spring-boot-starter-data-cassandra", "cassandra"),
+    ("java-driver-core", "cassandra"),
+    // Go
+    ("github.com/lib/pq", "postgres"),
+    ("github.com/jackc/pgx", "postgres"),
+    ("github.com/go-sql-driver/mysql", "mysql"),
+    ("go.mongodb.org/mongo-driver", "mongodb"),
+    ("github.com/redis/go-redis", "redis"),
+    ("github.com/go-redis/redis", "redis"),
+    ("github.com/rabbitmq/amqp091-go", "rabbitmq"),
+    ("github.com/segmentio/kafka-go", "kafka"),
+    ("github.com/IBM/sarama", "kafka"),
+    ("github.com/elastic/go-elasticsearch", "elasticsearch"),
+    ("github.com/minio/minio-go", "minio"),
+    ("github.com/nats-io/nats.go", "nats"),
+    ("github.com/ClickHouse/clickhouse-go", "clickhouse"),
+    ("github.com/gocql/gocql", "cassandra"),
+    ("github.com/bradfitz/gomemcache", "memcached"),
+];
+
+/// Dependencies that identify a framework: (package or module path, framework)
+const FRAMEWORK_HINTS: &[(&str, &str)] = &[
+    ("next", "next"),
+    ("react", "react"),
+    ("vue", "vue"),
+    ("svelte", "svelte"),
+    ("express", "express"),
+    ("@nestjs/core", "nestjs"),
+    ("fastify", "fastify"),
+    ("django", "django"),
+    ("flask", "flask"),
+    ("fastapi", "fastapi"),
+    ("axum", "axum"),
+    ("actix-web", "actix-web"),
+    ("rocket", "rocket"),
+    ("github.com/gin-gonic/gin", "gin"),
+    ("github.com/labstack/echo", "echo"),
+    ("github.com/gofiber/fiber", "fiber"),
+    ("spring-boot-starter-web", "spring-boot"),
+    ("spring-boot-starter-webflux", "spring-boot"),
+    ("quarkus-core", "quarkus"),
+];
+
+/// Image name prefixes in compose files: (image, service)
+const IMAGE_HINTS: &[(&str, &str)] = &[
+    ("postgres", "postgres"),
+    ("postgis/postgis", "postgres"),
+    ("mysql", "mysql"),
+    ("mariadb", "mysql"),
+    ("mongo", "mongodb"),
+    ("redis", "redis"),
+    ("rabbitmq", "rabbitmq"),
+    ("confluentinc/cp-kafka", "kafka"),
+    ("bitnami/kafka", "kafka"),
+    ("elasticsearch", "elasticsearch"),
+    ("docker.elastic.co/elasticsearch/elasticsearch", "elasticsearch"),
+    ("minio/minio", "minio"),
+    ("nats", "nats"),
+    ("clickhouse/clickhouse-server", "clickhouse"),
+    ("cassandra", "cassandra"),
+    ("memcached", "memcached"),
+    ("localstack/localstack", "localstack"),
+];
+
+const COMPOSE_FILES: &[&str] = &["docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml"];
+
+/// Inspect a project folder's manifests and suggest languages, services and a template
+pub fn detect_project_stack(dir: &Path) -> Result<ProjectStack> {
+    if !dir.is_dir() {
+        anyhow::bail!("{} is not a directory", dir.display());
+    }
+
+    let mut stack = ProjectStack::default();
+
+    if let Some(content) = read(dir, "package.json")? {
+        let manifest: serde_json::Value = serde_json::from_str(&content).context("Failed to parse package.json")?;
+        let version = read(dir, ".nvmrc")?
+            .or(read(dir, ".node-version")?)
+            .map(|version| version.trim().trim_start_matches('v').to_string())
+            .or_else(|| manifest["engines"]["node"].as_str().and_then(major_version));
+        stack.add_language("node", version, "package.json");
+
+        let dependencies = ["dependencies", "devDependencies"].iter()
+            .filter_map(|section| manifest[section].as_object())
+            .flat_map(|deps| deps.keys().cloned())
+            .collect::<Vec<_>>();
+        stack.add_dependencies(&dependencies, "package.json");
+    }
+
+    let pyproject = read(dir, "pyproject.toml")?;
+    let requirements = read(dir, "requirements.txt")?;
+    if pyproject.is_some() || requirements.is_some() {
+        let source = if pyproject.is_some() { "pyproject.toml" } else { "requirements.txt" };
+        let mut dependencies = Vec::new();
+        let mut version = read(dir, ".python-version")?.map(|version| version.trim().to_string());
+
+        if let Some(content) = &pyproject {
+            let manifest: toml::Value = toml::from_str(content).context("Failed to parse pyproject.toml")?;
+            let project = manifest.get("project");
+            if version.is_none() {
+                version = project
+                    .and_then(|project| project.get("requires-python"))
+                    .and_then(|requires| requires.as_str())
+                    .and_then(major_version);
+            }
+            dependencies.extend(project
+                .and_then(|project| project.get("dependencies"))
+                .and_then(|deps| deps.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|dep| dep.as_str())
+                .map(python_requirement_name));
+            // Poetry keeps dependencies in its own table
+            dependencies.extend(manifest.get("tool")
+                .and_then(|tool| tool.get("poetry"))
+                .and_then(|poetry| poetry.get("dependencies"))
+                .and_then(|deps| deps.as_table())
+                .into_iter()
+                .flat_map(|deps| deps.keys().cloned()));
+        }
+        if let Some(content) = &requirements {
+            dependencies.extend(content.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('-'))
+                .map(python_requirement_name));
+        }
+
+        stack.add_language("python", version, source);
+        stack.add_dependencies(&dependencies, source);
+    }
+
+    if let Some(content) = read(dir, "go.mod")? {
+        let version = content.lines()
+            .find_map(|line| line.trim().strip_prefix("go "))
+            .map(|version| version.trim().to_string());
+        stack.add_language("go", version, "go.mod");
+
+        // Module paths from `require` lines and blocks; versions follow a space
+        let dependencies: Vec<String> = content.lines()
+            .map(|line| line.trim().trim_start_matches("require").trim())
+            .filter(|line| line.contains('.') && line.contains(" v"))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .collect();
+        stack.add_dependencies(&dependencies, "go.mod");
+    }
+
+    if let Some(content) = read(dir, "Cargo.toml")? {
+        let manifest: toml::Value = toml::from_str(&content).context("Failed to parse Cargo.toml")?;
+        let version = read(dir, "rust-toolchain")?
+            .map(|version| version.trim().to_string())
+            .or_else(|| manifest.get("package")
+                .and_then(|package| package.get("rust-version"))
+                .and_then(|version| version.as_str())
+                .map(str::to_string));
+        stack.add_language("rust", version, "Cargo.toml");
+
+        let mut dependencies = Vec::new();
+        for section in ["dependencies", "dev-dependencies"] {
+            for (name, spec) in manifest.get(section).and_then(|deps| deps.as_table()).into_iter().flatten() {
+                dependencies.push(name.clone());
+                // sqlx, diesel and sea-orm pick their database with features
+                let features = spec.get("features").and_then(|features| features.as_array()).into_iter().flatten();
+                for feature in features.filter_map(|feature| feature.as_str()) {
+                    match feature {
+                        "postgres" | "sqlx-postgres" => dependencies.push("tokio-postgres".to_string()),
+                        "mysql" | "sqlx-mysql" => dependencies.push("mysql_async".to_string()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        stack.add_dependencies(&dependencies, "Cargo.toml");
+    }
+
+    if let Some(content) = read(dir, "pom.xml")? {
+        let version = ["maven.compiler.release", "java.version", "maven.compiler.source"].iter()
+            .find_map(|property| xml_values(&content, property).into_iter().next());
+        stack.add_language("java", version, "pom.xml");
+        stack.add_dependencies(&xml_values(&content, "artifactId"), "pom.xml");
+    }
+
+    for file in COMPOSE_FILES {
+        let Some(content) = read(dir, file)? else { continue };
+        let compose: serde_yaml::Value = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", file))?;
+        for (_, service) in compose["services"].as_mapping().into_iter().flatten() {
+            let Some(image) = service["image"].as_str() else { continue };
+            let name = image.split(':').next().unwrap_or(image);
+            if let Some((_, service)) = IMAGE_HINTS.iter().find(|(hint, _)| *hint == name) {
+                stack.add_service(service, format!("{} in {}", image, file));
+            }
+        }
+        stack.compose_file = Some(file.to_string());
+        break;
+    }
+
+    stack.services.sort_by(|a, b| a.name.cmp(&b.name));
+    stack.template = match stack.languages.as_slice() {
+        [] => "default",
+        [language] => match language.name.as_str() {
+            "node" | "python" | "rust" | "go" => language.name.as_str(),
+            _ => "default",
+        },
+        _ => "fullstack",
+    }
+    .to_string();
+
+    Ok(stack)
+}
+
+impl ProjectStack {
+    /// zero.yml for `zero init --auto`: the detected languages and services, each commented with where it was found
+    pub fn to_config(&self, project_name: &str) -> String {
+        let mut yaml = String::from("# Detected by zero init --auto; review before starting\n");
+        let _ = writeln!(yaml, "version: {}\n\nmetadata:\n  name: {}", CURRENT_VERSION, project_name);
+        if !self.frameworks.is_empty() {
+            let _ = writeln!(yaml, "  description: {} application", self.frameworks.join(", "));
+        }
+
+        if !self.languages.is_empty() {
+            yaml.push_str("\nlanguages:\n");
+            for language in &self.languages {
+                let version = language.version.as_deref()
+                    .or_else(|| KNOWN_LANGUAGES.iter()
+                        .find(|(name, _)| *name == language.name)
+                        .and_then(|(_, versions)| versions.first().copied()))
+                    .unwrap_or("latest");
+                let _ = writeln!(yaml, "  {}: \"{}\"  # {}", language.name, version, language.source);
+            }
+        }
+
+        if !self.services.is_empty() {
+            yaml.push_str("\nservices:\n");
+            for service in &self.services {
+                let version = KNOWN_SERVICES.iter()
+                    .find(|known| known.name == service.name)
+                    .and_then(|known| known.versions.first().copied())
+                    .unwrap_or("latest");
+                let _ = writeln!(yaml, "  # {}\n  {}:\n    version: \"{}\"\n    port: auto", service.reason, service.name, version);
+            }
+        }
+
+        yaml.push_str("\nports: auto\n");
+        yaml
+    }
+
+    fn add_language(&mut self, name: &str, version: Option<String>, source: &str) {
+        self.languages.push(DetectedLanguage {
+            name: name.to_string(),
+            version: version.filter(|version| !version.is_empty()),
+            source: source.to_string(),
+        });
+    }
+
+    fn add_dependencies(&mut self, dependencies: &[String], source: &str) {
+        for dependency in dependencies {
+            if let Some((_, service)) = SERVICE_HINTS.iter().find(|(hint, _)| matches_dependency(dependency, hint)) {
+                self.add_service(service, format!("{} in {}", dependency, source));
+            }
+            if let Some((_, framework)) = FRAMEWORK_HINTS.iter().find(|(hint, _)| matches_dependency(dependency, hint)) {
+                if !self.frameworks.iter().any(|known| known == framework) {
+                    self.frameworks.push(framework.to_string());
+                }
+            }
+        }
+    }
+
+    fn add_service(&mut self, name: &str, reason: String) {
+        if !self.services.iter().any(|service| service.name == name) {
+            self.services.push(DetectedService { name: name.to_string(), reason });
+        }
+    }
+}
+
+/// Exact package names, or Go module paths including major-version suffixes like `/v5`
+fn matches_dependency(dependency: &str, hint: &str) -> bool {
+    dependency.eq_ignore_ascii_case(hint)
+        || (hint.contains('/') && hint.contains('.') && dependency.starts_with(&format!("{}/", hint)))
+}
+
+/// `Django>=4.2` or `psycopg[binary]==3.1` -> package name
+fn python_requirement_name(requirement: &str) -> String {
+    requirement
+        .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .next()
+        .unwrap_or(requirement)
+        .to_lowercase()
+}
+
+/// `>=20.0.0` or `^3.11` -> `20` or `3.11`: the first version number mentioned
+fn major_version(range: &str) -> Option<String> {
+    let start = range.find(|c: char| c.is_ascii_digit())?;
+    let version: String = range[start..].chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    // Keep major.minor at most, e.g. 3.11.2 -> 3.11; a bare major stays as is
+    let parts: Vec<&str> = version.trim_end_matches('.').split('.').collect();
+    match parts.as_slice() {
+        [major, "0", ..] | [major] => Some(major.to_string()),
+        [major, minor, ..] => Some(format!("{}.{}", major, minor)),
+        [] => None,
+    }
+}
+
+/// Text of every `<tag>value</tag>` in an XML document, e.g. a pom.xml's artifact ids
+fn xml_values(content: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    content.split(open.as_str())
+        .skip(1)
+        .filter_map(|rest| rest.split_once(close.as_str()))
+        .map(|(value, _)| value.trim().to_string())
+        .filter(|value| !value.is_empty() && !value.starts_with("${"))
+        .collect()
+}
+
+fn read(dir: &Path, file: &str) -> Result<Option<String>> {
+    let path = dir.join(file);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(&path)
+        .map(Some)
+        .with_context(|| format!("Failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_node_and_compose_stack() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{
+            "engines": { "node": ">=20.0.0" },
+            "dependencies": { "next": "14.0.0", "pg": "8.11.0", "ioredis": "5.3.0" }
+        }"#).unwrap();
+        std::fs::write(dir.path().join("docker-compose.yml"), "services:\n  db:\n    image: postgres:16\n  search:\n    image: docker.elastic.co/elasticsearch/elasticsearch:8.13.0\n").unwrap();
+
+        let stack = detect_project_stack(dir.path()).unwrap();
+
+        assert_eq!(stack.template, "node");
+        assert_eq!(stack.languages[0].version.as_deref(), Some("20"));
+        assert_eq!(stack.frameworks, vec!["next"]);
+        let services: Vec<&str> = stack.services.iter().map(|service| service.name.as_str()).collect();
+        assert_eq!(services, vec!["elasticsearch", "postgres", "redis"]);
+        assert_eq!(stack.compose_file.as_deref(), Some("docker-compose.yml"));
+    }
+
+    #[test]
+    fn test_detect_maven_project_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("pom.xml"), "<project>\n  <properties><java.version>21</java.version></properties>\n  <dependencies>\n    <dependency><artifactId>spring-boot-starter-web</artifactId></dependency>\n    <dependency><artifactId>postgresql</artifactId></dependency>\n    <dependency><artifactId>spring-boot-starter-data-redis</artifactId></dependency>\n  </dependencies>\n</project>\n").unwrap();
+
+        let stack = detect_project_stack(dir.path()).unwrap();
+
+        assert_eq!(stack.frameworks, vec!["spring-boot"]);
+        let yaml = stack.to_config("shop");
+        assert!(yaml.contains("  java: \"21\"  # pom.xml\n"));
+        assert!(yaml.contains("  # postgresql in pom.xml\n  postgres:\n    version: \"16\"\n"));
+        let config = crate::config::ZeroConfig::from_str(&yaml).unwrap();
+        assert_eq!(config.metadata.name.as_deref(), Some("shop"));
+        assert!(config.services.contains_key("redis"));
+    }
+
+    #[test]
+    fn test_dependency_parsing_helpers() {
+        assert_eq!(python_requirement_name("psycopg[binary]>=3.1"), "psycopg");
+        assert_eq!(python_requirement_name("Django==4.2"), "django");
+        assert_eq!(major_version("^3.11.2").as_deref(), Some("3.11"));
+        assert_eq!(major_version(">=18").as_deref(), Some("18"));
+        assert!(matches_dependency("github.com/jackc/pgx/v5", "github.com/jackc/pgx"));
+        assert!(!matches_dependency("pg-promise", "pg"));
+    }
+}
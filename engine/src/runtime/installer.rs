@@ -0,0 +1,254 @@
+use anyhow::{Context, Result};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+/// A command that installs a runtime with a tool already present on the machine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallStep {
+    /// Tool doing the install, e.g. `brew` or `rustup`
+    pub tool: &'static str,
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl InstallStep {
+    fn new(tool: &'static str, args: &[&str]) -> Self {
+        Self {
+            tool,
+            program: tool.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    /// The command line as the user would type it
+    pub fn display(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Installers that may be used, in order of preference for each OS
+const CANDIDATE_TOOLS: &[&str] = &["rustup", "fnm", "pyenv", "brew", "winget"];
+
+/// Pick how to install a runtime, preferring version managers over system package managers
+///
+/// `available` lists the installer tools found on the machine (see [`available_tools`]).
+/// Version managers get a second step making the installed version the default.
+pub fn plan(name: &str, version: &str, os: &str, available: &[&str]) -> Result<Vec<InstallStep>> {
+    let has = |tool: &str| available.contains(&tool);
+    let major = version.split('.').next().unwrap_or(version);
+    let minor = version.split('.').take(2).collect::<Vec<_>>().join(".");
+    let pinned = !matches!(version, "" | "latest" | "stable");
+
+    let steps = match name {
+        "rust" if has("rustup") => {
+            let toolchain = if pinned { version } else { "stable" };
+            Some(vec![
+                InstallStep::new("rustup", &["toolchain", "install", toolchain]),
+                InstallStep::new("rustup", &["default", toolchain]),
+            ])
+        }
+        // `fnm use` only lasts for the shell it runs in, so the default is set instead
+        "node" if has("fnm") => Some(vec![
+            InstallStep::new("fnm", &["install", if pinned { version } else { "--lts" }]),
+            InstallStep::new("fnm", &["default", if pinned { version } else { "lts-latest" }]),
+        ]),
+        "python" | "python3" if has("pyenv") && pinned => Some(vec![InstallStep::new("pyenv", &["install", "--skip-existing", version])]),
+        _ => None,
+    };
+    if let Some(steps) = steps {
+        return Ok(steps);
+    }
+
+    let step = match (os, name) {
+        ("macos", _) if !has("brew") => None,
+        ("macos", "node") => Some(brew(&versioned("node", major, pinned))),
+        // Formulae are named by minor version, e.g. python@3.12
+        ("macos", "python" | "python3") => Some(brew(&versioned("python", &minor, pinned))),
+        ("macos", "go") => Some(brew("go")),
+        ("macos", "java") => Some(brew(&versioned("openjdk", major, pinned))),
+        ("macos", "ruby") => Some(brew("ruby")),
+        ("macos", "php") => Some(brew("php")),
+        ("macos", "dotnet") => Some(InstallStep::new("brew", &["install", "--cask", "dotnet-sdk"])),
+        ("macos", "docker") => Some(InstallStep::new("brew", &["install", "--cask", "docker"])),
+        ("macos", "podman") => Some(brew("podman")),
+        ("macos", "minikube") => Some(brew("minikube")),
+        ("macos", "kubectl") => Some(brew("kubectl")),
+        ("macos", "colima") => Some(brew("colima")),
+        ("windows", _) if !has("winget") => None,
+        ("windows", "node") => Some(winget("OpenJS.NodeJS.LTS")),
+        ("windows", "python" | "python3") => Some(winget(&format!("Python.Python.{}", if pinned { version } else { "3.12" }))),
+        ("windows", "go") => Some(winget("GoLang.Go")),
+        ("windows", "java") => Some(winget(&format!("EclipseAdoptium.Temurin.{}.JDK", if pinned { major } else { "21" }))),
+        ("windows", "dotnet") => Some(winget(&format!("Microsoft.DotNet.SDK.{}", if pinned { major } else { "8" }))),
+        ("windows", "docker") => Some(winget("Docker.DockerDesktop")),
+        ("windows", "podman") => Some(winget("RedHat.Podman")),
+        ("windows", "minikube") => Some(winget("Kubernetes.minikube")),
+        ("windows", "kubectl") => Some(winget("Kubernetes.kubectl")),
+        _ => None,
+    };
+
+    step.map(|step| vec![step]).with_context(|| format!(
+        "No automatic installer for {} {} on {}. Install it manually or add a version manager (fnm, pyenv, rustup) or package manager (brew, winget)",
+        name, version, os
+    ))
+}
+
+/// How to install a runtime on this machine: the commands [`install`] would run,
+/// or where to get it when no installer applies
+pub async fn install_hint(name: &str, version: &str) -> String {
+    match plan(name, version, std::env::consts::OS, &available_tools().await) {
+        Ok(steps) => steps.iter().map(InstallStep::display).collect::<Vec<_>>().join(" && "),
+        Err(_) => manual_hint(name, version),
+    }
+}
+
+/// Where to download a runtime from
+fn manual_hint(name: &str, version: &str) -> String {
+    match name {
+        "node" => format!("Visit https://nodejs.org/ or use nvm: nvm install {}", version),
+        "python" | "python3" => {
+            format!("Visit https://python.org/ or use pyenv: pyenv install {}", version)
+        }
+        "go" => format!("Visit https://golang.org/dl/ or use gvm: gvm install go{}", version),
+        "rust" => {
+            format!("Visit https://rustup.rs/ or run: rustup install {}", version)
+        }
+        "java" => {
+            format!("Visit https://adoptium.net/ or use sdkman: sdk install java {}", version)
+        }
+        "dotnet" => {
+            format!("Visit https://dotnet.microsoft.com/ or use: dotnet-install.sh --version {}", version)
+        }
+        "docker" => "Visit https://docs.docker.com/get-docker/ to install Docker Desktop".to_string(),
+        "podman" => "Visit https://podman.io/getting-started/installation to install Podman".to_string(),
+        "minikube" => "Visit https://minikube.sigs.k8s.io/docs/start/ to install Minikube".to_string(),
+        "kubectl" => "Visit https://kubernetes.io/docs/tasks/tools/ to install kubectl".to_string(),
+        "docker-compose" => "Visit https://docs.docker.com/compose/install/ to install Docker Compose".to_string(),
+        "nerdctl" => "Visit https://github.com/containerd/nerdctl to install nerdctl".to_string(),
+        "containerd" => "Visit https://containerd.io/downloads/ to install containerd".to_string(),
+        "crictl" => "Visit https://github.com/kubernetes-sigs/cri-tools to install crictl".to_string(),
+        "colima" => "Visit https://github.com/abiosoft/colima to install Colima (macOS/Linux)".to_string(),
+        _ => format!("Please install {} version {}", name, version),
+    }
+}
+
+fn brew(formula: &str) -> InstallStep {
+    InstallStep::new("brew", &["install", formula])
+}
+
+fn winget(id: &str) -> InstallStep {
+    InstallStep::new("winget", &["install", "--exact", "--silent", "--accept-package-agreements", "--accept-source-agreements", "--id", id])
+}
+
+/// `node@20` style formula names, or the plain name when no version is pinned
+fn versioned(formula: &str, version: &str, pinned: bool) -> String {
+    if pinned {
+        format!("{}@{}", formula, version)
+    } else {
+        formula.to_string()
+    }
+}
+
+/// Installer tools found on this machine
+pub async fn available_tools() -> Vec<&'static str> {
+    let mut tools = Vec::new();
+    for tool in CANDIDATE_TOOLS {
+        let found = Command::new(tool)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map(|status| status.success())
+            .unwrap_or(false);
+        if found {
+            tools.push(*tool);
+        }
+    }
+    tools
+}
+
+/// Install a runtime, passing every line of installer output to `on_output`
+pub async fn install(name: &str, version: &str, mut on_output: impl FnMut(&str)) -> Result<Vec<InstallStep>> {
+    let steps = plan(name, version, std::env::consts::OS, &available_tools().await)?;
+    for step in &steps {
+        run(step, &mut on_output).await?;
+    }
+    Ok(steps)
+}
+
+async fn run(step: &InstallStep, on_output: &mut impl FnMut(&str)) -> Result<()> {
+    on_output(&format!("$ {}", step.display()));
+
+    let mut child = Command::new(&step.program)
+        .args(&step.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", step.tool))?;
+
+    let mut stdout = BufReader::new(child.stdout.take().context("Installer stdout not captured")?).lines();
+    let mut stderr = BufReader::new(child.stderr.take().context("Installer stderr not captured")?).lines();
+    let (mut stdout_open, mut stderr_open) = (true, true);
+
+    while stdout_open || stderr_open {
+        tokio::select! {
+            line = stdout.next_line(), if stdout_open => match line? {
+                Some(line) => on_output(&line),
+                None => stdout_open = false,
+            },
+            line = stderr.next_line(), if stderr_open => match line? {
+                Some(line) => on_output(&line),
+                None => stderr_open = false,
+            },
+        }
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        anyhow::bail!("`{}` failed with {}", step.display(), status);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn displayed(steps: Vec<InstallStep>) -> Vec<String> {
+        steps.iter().map(InstallStep::display).collect()
+    }
+
+    #[test]
+    fn test_plan_prefers_version_managers() {
+        let steps = plan("node", "20", "macos", &["fnm", "brew"]).unwrap();
+        assert_eq!(displayed(steps), vec!["fnm install 20", "fnm default 20"]);
+
+        let steps = plan("node", "20", "macos", &["brew"]).unwrap();
+        assert_eq!(displayed(steps), vec!["brew install node@20"]);
+
+        let steps = plan("rust", "latest", "linux", &["rustup"]).unwrap();
+        assert_eq!(displayed(steps), vec!["rustup toolchain install stable", "rustup default stable"]);
+    }
+
+    #[test]
+    fn test_plan_names_brew_python_by_minor_version() {
+        let steps = plan("python", "3.12.1", "macos", &["brew"]).unwrap();
+        assert_eq!(displayed(steps), vec!["brew install python@3.12"]);
+        let steps = plan("python", "3.11", "macos", &["brew"]).unwrap();
+        assert_eq!(displayed(steps), vec!["brew install python@3.11"]);
+    }
+
+    #[test]
+    fn test_plan_without_installer_fails() {
+        assert!(plan("docker", "latest", "linux", &[]).is_err());
+        assert!(plan("docker", "latest", "macos", &[]).is_err());
+        assert_eq!(plan("docker", "latest", "windows", &["winget"]).unwrap()[0].tool, "winget");
+    }
+}
@@ -1,15 +1,20 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::process::Command;
 use tracing::{info, warn};
 
 pub mod detector;
 pub mod version_manager;
 pub mod container_runtime;
+pub mod installer;
+pub mod provisioner;
 
-pub use container_runtime::ContainerRuntimeManager;
+pub use container_runtime::{ContainerRuntime, ContainerRuntimeManager};
+pub use detector::{detect_project_stack, ProjectStack};
+pub use provisioner::{Method, Toolchain};
 
 /// Runtime information for a programming language/tool
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeInfo {
     pub name: String,
     pub installed_version: Option<String>,
@@ -47,7 +52,7 @@ impl RuntimeManager {
             required_version: required_version.to_string(),
             is_compatible,
             install_command: if !is_compatible {
-                Some(self.get_install_command(name, required_version))
+                Some(installer::install_hint(name, required_version).await)
             } else {
                 None
             },
@@ -182,45 +187,12 @@ impl RuntimeManager {
         installed.starts_with(required) || installed == required
     }
 
-    /// Get install command for a runtime
-    fn get_install_command(&self, name: &str, version: &str) -> String {
-        match name {
-            "node" => format!("Visit https://nodejs.org/ or use nvm: nvm install {}", version),
-            "python" | "python3" => {
-                format!("Visit https://python.org/ or use pyenv: pyenv install {}", version)
-            }
-            "go" => format!("Visit https://golang.org/dl/ or use gvm: gvm install go{}", version),
-            "rust" => {
-                format!("Visit https://rustup.rs/ or run: rustup install {}", version)
-            }
-            "java" => {
-                format!("Visit https://adoptium.net/ or use sdkman: sdk install java {}", version)
-            }
-            "dotnet" => {
-                format!("Visit https://dotnet.microsoft.com/ or use: dotnet-install.sh --version {}", version)
-            }
-            "docker" => "Visit https://docs.docker.com/get-docker/ to install Docker Desktop".to_string(),
-            "podman" => "Visit https://podman.io/getting-started/installation to install Podman".to_string(),
-            "minikube" => "Visit https://minikube.sigs.k8s.io/docs/start/ to install Minikube".to_string(),
-            "kubectl" => "Visit https://kubernetes.io/docs/tasks/tools/ to install kubectl".to_string(),
-            "docker-compose" => "Visit https://docs.docker.com/compose/install/ to install Docker Compose".to_string(),
-            "nerdctl" => "Visit https://github.com/containerd/nerdctl to install nerdctl".to_string(),
-            "containerd" => "Visit https://containerd.io/downloads/ to install containerd".to_string(),
-            "crictl" => "Visit https://github.com/kubernetes-sigs/cri-tools to install crictl".to_string(),
-            "colima" => "Visit https://github.com/abiosoft/colima to install Colima (macOS/Linux)".to_string(),
-            _ => format!("Please install {} version {}", name, version),
-        }
-    }
-
-    /// Check if Docker is installed and running
+    /// Check if the Docker (or Podman) API answers, without needing the CLI
     pub async fn check_docker(&self) -> Result<bool> {
-        let output = Command::new("docker")
-            .arg("ps")
-            .output()
-            .context("Docker is not installed or not in PATH")?;
+        let docker = crate::orchestrator::docker_client::connect()?;
 
-        if !output.status.success() {
-            warn!("Docker is installed but not running");
+        if let Err(e) = docker.ping().await {
+            warn!("Docker is not running: {}", e);
             return Ok(false);
         }
 
@@ -0,0 +1,366 @@
+//! Making the runtimes under `languages:` available to a project
+//!
+//! A runtime already on PATH in a compatible version is used as is. Otherwise
+//! the first version manager found installs it: mise, asdf, then nvm, pyenv or
+//! rustup for their own language. Without one, shims in `.zero/toolchains/bin`
+//! run the language's official image. What was provided is recorded in the
+//! project state, so `zero env` and hooks get it on PATH.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+use super::RuntimeManager;
+use crate::core::state::STATE_DIR;
+
+/// How a language runtime is provided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// Already installed in a compatible version
+    System,
+    Mise,
+    Asdf,
+    Nvm,
+    Pyenv,
+    Rustup,
+    /// Shims running the language's official image
+    Container,
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Method::System => "system",
+            Method::Mise => "mise",
+            Method::Asdf => "asdf",
+            Method::Nvm => "nvm",
+            Method::Pyenv => "pyenv",
+            Method::Rustup => "rustup",
+            Method::Container => "container",
+        })
+    }
+}
+
+/// A language runtime provided for the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Toolchain {
+    pub language: String,
+    pub version: String,
+    pub method: Method,
+    /// Directory to put first on PATH
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin_dir: Option<PathBuf>,
+    /// Variables selecting the version, e.g. `RUSTUP_TOOLCHAIN`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Version managers, in order of preference
+const MANAGERS: [Method; 5] = [Method::Mise, Method::Asdf, Method::Nvm, Method::Pyenv, Method::Rustup];
+
+/// Version managers found on this machine, in order of preference
+pub async fn available_managers() -> Vec<Method> {
+    let mut found = Vec::new();
+    for method in MANAGERS {
+        let available = match method {
+            Method::Mise => succeeds("mise", &["--version"]).await,
+            Method::Asdf => succeeds("asdf", &["version"]).await,
+            Method::Nvm => nvm_script().is_some(),
+            Method::Pyenv => succeeds("pyenv", &["--version"]).await,
+            Method::Rustup => succeeds("rustup", &["--version"]).await,
+            Method::System | Method::Container => false,
+        };
+        if available {
+            found.push(method);
+        }
+    }
+    found
+}
+
+/// How to provide a language that is not installed, given the version managers available
+pub fn choose(language: &str, available: &[Method]) -> Option<Method> {
+    available.iter().copied()
+        .find(|method| tool_name(*method, language).is_some())
+        .or_else(|| image(language, "latest").map(|_| Method::Container))
+}
+
+/// Provide every language of a project, in name order
+///
+/// Languages nothing can provide are skipped with a warning.
+pub async fn provision_all(languages: &HashMap<String, String>, project_dir: &Path) -> Result<Vec<Toolchain>> {
+    let mut languages: Vec<_> = languages.iter().collect();
+    languages.sort();
+
+    let available = available_managers().await;
+    let mut checker = RuntimeManager::new();
+    let mut toolchains = Vec::new();
+    for (language, version) in languages {
+        let installed = checker.check_runtime(language, version).await.is_ok_and(|info| info.is_compatible);
+        let method = if installed {
+            Method::System
+        } else if let Some(method) = choose(language, &available) {
+            info!("Providing {} {} with {}", language, version, method);
+            method
+        } else {
+            warn!("Nothing can provide {} {}; install it manually", language, version);
+            continue;
+        };
+        let toolchain = provision(language, version, method, project_dir).await
+            .with_context(|| format!("Failed to provide {} {} with {}", language, version, method))?;
+        toolchains.push(toolchain);
+    }
+    Ok(toolchains)
+}
+
+/// Install a language with a method and find where it lives
+pub async fn provision(language: &str, version: &str, method: Method, project_dir: &Path) -> Result<Toolchain> {
+    let mut toolchain = Toolchain {
+        language: language.to_string(),
+        version: version.to_string(),
+        method,
+        bin_dir: None,
+        env: BTreeMap::new(),
+    };
+    let unpinned = matches!(version, "" | "latest" | "stable");
+    let tool = tool_name(method, language);
+
+    match method {
+        Method::System => {}
+        Method::Mise => {
+            let spec = format!("{}@{}", tool.context("mise has no such tool")?, if unpinned { "latest" } else { version });
+            run("mise", &["install", &spec]).await?;
+            toolchain.bin_dir = Some(PathBuf::from(query("mise", &["where", &spec]).await?).join("bin"));
+        }
+        Method::Asdf => {
+            let plugin = tool.context("asdf has no such plugin")?;
+            // Fails when the plugin is already there
+            let _ = succeeds("asdf", &["plugin", "add", plugin]).await;
+            let mut latest = vec!["latest", plugin];
+            if !unpinned {
+                latest.push(version);
+            }
+            let resolved = query("asdf", &latest).await?;
+            run("asdf", &["install", plugin, &resolved]).await?;
+            toolchain.bin_dir = Some(PathBuf::from(query("asdf", &["where", plugin, &resolved]).await?).join("bin"));
+        }
+        Method::Nvm => {
+            let script = nvm_script().context("nvm is not installed")?;
+            let script = script.to_string_lossy();
+            let node = query("bash", &[
+                "-c", r#". "$0" && nvm install "$1" >&2 && nvm which "$1""#,
+                &script, if unpinned { "node" } else { version },
+            ]).await?;
+            toolchain.bin_dir = Path::new(&node).parent().map(Path::to_path_buf);
+        }
+        Method::Pyenv => {
+            let resolved = query("pyenv", &["latest", "--known", if unpinned { "3" } else { version }]).await?;
+            run("pyenv", &["install", "--skip-existing", &resolved]).await?;
+            toolchain.bin_dir = Some(PathBuf::from(query("pyenv", &["prefix", &resolved]).await?).join("bin"));
+        }
+        Method::Rustup => {
+            let channel = if version == "latest" || version.is_empty() { "stable" } else { version };
+            run("rustup", &["toolchain", "install", "--profile", "minimal", channel]).await?;
+            toolchain.env.insert("RUSTUP_TOOLCHAIN".to_string(), channel.to_string());
+        }
+        Method::Container => {
+            toolchain.bin_dir = Some(write_shims(language, version, project_dir)?);
+        }
+    }
+    Ok(toolchain)
+}
+
+/// Variables putting the toolchains first on PATH, ahead of the current PATH
+pub fn activation_env(toolchains: &[Toolchain]) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    let dirs: Vec<PathBuf> = toolchains.iter().filter_map(|toolchain| toolchain.bin_dir.clone()).collect();
+    if !dirs.is_empty() {
+        let current = std::env::var_os("PATH").unwrap_or_default();
+        if let Ok(path) = std::env::join_paths(dirs.into_iter().chain(std::env::split_paths(&current))) {
+            env.insert("PATH".to_string(), path.to_string_lossy().into_owned());
+        }
+    }
+    for toolchain in toolchains {
+        env.extend(toolchain.env.clone());
+    }
+    env
+}
+
+/// Name of a language for a version manager, `None` when it cannot install it
+fn tool_name(method: Method, language: &str) -> Option<&'static str> {
+    match (method, language) {
+        (Method::Mise, "node") => Some("node"),
+        (Method::Asdf, "node") => Some("nodejs"),
+        (Method::Mise, "go") => Some("go"),
+        (Method::Asdf, "go") => Some("golang"),
+        (Method::Mise | Method::Asdf, "python") => Some("python"),
+        (Method::Mise | Method::Asdf, "rust") => Some("rust"),
+        (Method::Mise | Method::Asdf, "java") => Some("java"),
+        (Method::Mise | Method::Asdf, "ruby") => Some("ruby"),
+        (Method::Mise | Method::Asdf, "php") => Some("php"),
+        (Method::Nvm, "node") => Some("node"),
+        (Method::Pyenv, "python") => Some("python"),
+        (Method::Rustup, "rust") => Some("rust"),
+        _ => None,
+    }
+}
+
+/// Official image of a language at a version
+fn image(language: &str, version: &str) -> Option<String> {
+    let tag = if matches!(version, "" | "latest" | "stable") { "latest" } else { version };
+    Some(match language {
+        "node" => format!("node:{}", tag),
+        "python" => format!("python:{}", tag),
+        "go" => format!("golang:{}", tag),
+        "rust" => format!("rust:{}", tag),
+        "java" => format!("eclipse-temurin:{}", tag),
+        "ruby" => format!("ruby:{}", tag),
+        "php" if tag == "latest" => "php:cli".to_string(),
+        "php" => format!("php:{}-cli", tag),
+        _ => return None,
+    })
+}
+
+/// Commands a language's shims cover
+fn shim_commands(language: &str) -> &'static [&'static str] {
+    match language {
+        "node" => &["node", "npm", "npx"],
+        "python" => &["python", "python3", "pip"],
+        "go" => &["go", "gofmt"],
+        "rust" => &["cargo", "rustc"],
+        "java" => &["java", "javac"],
+        "ruby" => &["ruby", "gem", "bundle"],
+        "php" => &["php"],
+        _ => &[],
+    }
+}
+
+/// Write shims running a language from its image, mounting the working directory
+fn write_shims(language: &str, version: &str, project_dir: &Path) -> Result<PathBuf> {
+    let image = image(language, version).with_context(|| format!("No image for {}", language))?;
+    let dir = project_dir.join(STATE_DIR).join("toolchains").join("bin");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    for command in shim_commands(language) {
+        let (name, content) = shim(command, &image);
+        let path = dir.join(name);
+        std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    Ok(dir)
+}
+
+/// File name and content of a shim for this OS
+fn shim(command: &str, image: &str) -> (String, String) {
+    if cfg!(windows) {
+        (
+            format!("{}.cmd", command),
+            format!("@docker run --rm -i -v \"%CD%\":/work -w /work {} {} %*\r\n", image, command),
+        )
+    } else {
+        (
+            command.to_string(),
+            format!(
+                "#!/bin/sh\nexec docker run --rm -i $([ -t 0 ] && echo -t) -v \"$PWD\":\"$PWD\" -w \"$PWD\" {} {} \"$@\"\n",
+                image, command
+            ),
+        )
+    }
+}
+
+/// nvm is a shell function, so it is found by its script
+fn nvm_script() -> Option<PathBuf> {
+    let dir = std::env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".nvm")))?;
+    Some(dir.join("nvm.sh")).filter(|script| script.is_file())
+}
+
+async fn succeeds(program: &str, args: &[&str]) -> bool {
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .is_ok_and(|status| status.success())
+}
+
+/// Run an install, its output going to ours
+async fn run(program: &str, args: &[&str]) -> Result<()> {
+    let status = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .status()
+        .await
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !status.success() {
+        anyhow::bail!("`{} {}` failed with {}", program, args.join(" "), status);
+    }
+    Ok(())
+}
+
+/// Last line a command prints
+async fn query(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        anyhow::bail!("`{} {}` failed: {}", program, args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().map(str::trim).rfind(|line| !line.is_empty())
+        .map(str::to_string)
+        .with_context(|| format!("`{} {}` printed nothing", program, args.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_prefers_managers_then_containers() {
+        assert_eq!(choose("node", &[Method::Nvm, Method::Rustup]), Some(Method::Nvm));
+        assert_eq!(choose("go", &[Method::Asdf]), Some(Method::Asdf));
+        assert_eq!(choose("go", &[Method::Nvm, Method::Pyenv]), Some(Method::Container));
+        assert_eq!(choose("dotnet", &[Method::Nvm]), None);
+
+        assert_eq!(image("php", "8.3").as_deref(), Some("php:8.3-cli"));
+        assert_eq!(image("rust", "stable").as_deref(), Some("rust:latest"));
+    }
+
+    #[test]
+    fn test_activation_env_puts_toolchains_first() {
+        let toolchains = vec![
+            Toolchain {
+                language: "node".to_string(),
+                version: "20".to_string(),
+                method: Method::Mise,
+                bin_dir: Some(PathBuf::from("/opt/mise/installs/node/20/bin")),
+                env: BTreeMap::new(),
+            },
+            Toolchain {
+                language: "rust".to_string(),
+                version: "1.75".to_string(),
+                method: Method::Rustup,
+                bin_dir: None,
+                env: BTreeMap::from([("RUSTUP_TOOLCHAIN".to_string(), "1.75".to_string())]),
+            },
+        ];
+        let env = activation_env(&toolchains);
+        assert!(env["PATH"].starts_with("/opt/mise/installs/node/20/bin"));
+        assert_eq!(env["RUSTUP_TOOLCHAIN"], "1.75");
+    }
+}
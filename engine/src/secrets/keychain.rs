@@ -0,0 +1,251 @@
+//! Credentials in the OS keychain
+//!
+//! Each project is one item of the `zeroconfig` service, keyed by the project
+//! directory and holding its credentials as a JSON object. The macOS Keychain
+//! is reached through `security`, the Secret Service on Linux through
+//! `secret-tool` (libsecret), and Windows Credential Manager through advapi32,
+//! where content over a credential's 2560 bytes is split across several.
+
+use anyhow::Result;
+
+/// Service name the keychain items are filed under
+const SERVICE: &str = "zeroconfig";
+
+/// Read the item of a project, `None` when there is none yet
+pub fn read(account: &str) -> Result<Option<String>> {
+    platform::read(account)
+}
+
+/// Create or replace the item of a project
+pub fn write(account: &str, content: &str) -> Result<()> {
+    platform::write(account, content)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::SERVICE;
+    use anyhow::{Context, Result};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    /// Exit code of `security` when no item matches
+    const NOT_FOUND: i32 = 44;
+
+    pub fn read(account: &str) -> Result<Option<String>> {
+        let output = Command::new("security")
+            .args(["find-generic-password", "-s", SERVICE, "-a", account, "-w"])
+            .output()
+            .context("Failed to run `security` to read the keychain")?;
+        match output.status.code() {
+            Some(0) => Ok(Some(String::from_utf8_lossy(&output.stdout).trim_end().to_string())),
+            Some(NOT_FOUND) => Ok(None),
+            _ => anyhow::bail!("Reading the keychain failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
+        }
+    }
+
+    pub fn write(account: &str, content: &str) -> Result<()> {
+        // `-w` last and without a value makes `security` prompt for the
+        // password, twice, so it never shows up in the process list
+        let mut child = Command::new("security")
+            .args(["add-generic-password", "-U", "-s", SERVICE, "-a", account, "-w"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run `security` to write the keychain")?;
+        child.stdin.take().context("security has no stdin")?
+            .write_all(format!("{}\n{}\n", content, content).as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!("Writing the keychain failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::SERVICE;
+    use anyhow::{Context, Result};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    const MISSING: &str = "secret-tool is needed for `secrets.backend: keychain`; install libsecret-tools (or libsecret)";
+
+    pub fn read(account: &str) -> Result<Option<String>> {
+        let output = Command::new("secret-tool")
+            .args(["lookup", "service", SERVICE, "account", account])
+            .output()
+            .context(MISSING)?;
+        // lookup exits with 1 and prints nothing when no item matches
+        if output.status.success() {
+            Ok(Some(String::from_utf8_lossy(&output.stdout).trim_end().to_string()))
+        } else if output.stderr.is_empty() {
+            Ok(None)
+        } else {
+            anyhow::bail!("Reading the Secret Service failed: {}", String::from_utf8_lossy(&output.stderr).trim())
+        }
+    }
+
+    pub fn write(account: &str, content: &str) -> Result<()> {
+        let label = format!("ZeroConfig credentials ({})", account);
+        let mut child = Command::new("secret-tool")
+            .args(["store", "--label", &label, "service", SERVICE, "account", account])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context(MISSING)?;
+        // The secret goes through stdin so it never shows up in the process list
+        child.stdin.take().context("secret-tool has no stdin")?.write_all(content.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!("Writing the Secret Service failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::SERVICE;
+    use anyhow::{Context, Result};
+    use std::ffi::c_void;
+
+    const CRED_TYPE_GENERIC: u32 = 1;
+    /// Kept for the user across logons on this machine only, rather than
+    /// roamed to other machines with the profile like `CRED_PERSIST_ENTERPRISE`
+    const CRED_PERSIST_LOCAL_MACHINE: u32 = 2;
+    const ERROR_NOT_FOUND: i32 = 1168;
+    /// Largest blob a credential holds, `CRED_MAX_CREDENTIAL_BLOB_SIZE`; longer
+    /// content is split across several credentials
+    const MAX_BLOB_SIZE: usize = 5 * 512;
+
+    #[repr(C)]
+    struct FileTime {
+        low: u32,
+        high: u32,
+    }
+
+    #[repr(C)]
+    struct Credential {
+        flags: u32,
+        kind: u32,
+        target_name: *mut u16,
+        comment: *mut u16,
+        last_written: FileTime,
+        blob_size: u32,
+        blob: *mut u8,
+        persist: u32,
+        attribute_count: u32,
+        attributes: *mut c_void,
+        target_alias: *mut u16,
+        user_name: *mut u16,
+    }
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn CredReadW(target: *const u16, kind: u32, flags: u32, credential: *mut *mut Credential) -> i32;
+        fn CredWriteW(credential: *const Credential, flags: u32) -> i32;
+        fn CredDeleteW(target: *const u16, kind: u32, flags: u32) -> i32;
+        fn CredFree(buffer: *mut c_void);
+    }
+
+    /// Target of one part of a project's content: `zeroconfig:<account>`, then `zeroconfig:<account>:<part>`
+    fn target(account: &str, part: usize) -> Vec<u16> {
+        let name = match part {
+            0 => format!("{}:{}", SERVICE, account),
+            _ => format!("{}:{}:{}", SERVICE, account, part),
+        };
+        name.encode_utf16().chain(Some(0)).collect()
+    }
+
+    pub fn read(account: &str) -> Result<Option<String>> {
+        let mut content = Vec::new();
+        for part in 0.. {
+            match read_part(&target(account, part))? {
+                Some(blob) => content.extend_from_slice(&blob),
+                None if part == 0 => return Ok(None),
+                None => break,
+            }
+        }
+        Ok(Some(String::from_utf8_lossy(&content).to_string()))
+    }
+
+    pub fn write(account: &str, content: &str) -> Result<()> {
+        let mut parts: Vec<&[u8]> = content.as_bytes().chunks(MAX_BLOB_SIZE).collect();
+        if parts.is_empty() {
+            parts.push(&[]);
+        }
+        for (part, blob) in parts.iter().enumerate() {
+            write_part(account, target(account, part), blob)
+                .with_context(|| format!("Writing part {} of {} of the credentials to Credential Manager failed", part + 1, parts.len()))?;
+        }
+        // Remove the parts of longer content written before
+        for part in parts.len().. {
+            if !delete_part(&target(account, part))? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Blob of one credential, `None` when there is none
+    fn read_part(target: &[u16]) -> Result<Option<Vec<u8>>> {
+        let mut credential: *mut Credential = std::ptr::null_mut();
+        // SAFETY: the target is NUL-terminated and the credential is freed with CredFree
+        unsafe {
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                let error = std::io::Error::last_os_error();
+                if error.raw_os_error() == Some(ERROR_NOT_FOUND) {
+                    return Ok(None);
+                }
+                anyhow::bail!("Reading Credential Manager failed: {}", error);
+            }
+            let blob = std::slice::from_raw_parts((*credential).blob, (*credential).blob_size as usize).to_vec();
+            CredFree(credential as *mut c_void);
+            Ok(Some(blob))
+        }
+    }
+
+    fn write_part(account: &str, mut target: Vec<u16>, blob: &[u8]) -> Result<()> {
+        let mut user: Vec<u16> = account.encode_utf16().chain(Some(0)).collect();
+        let mut blob = blob.to_vec();
+        let credential = Credential {
+            flags: 0,
+            kind: CRED_TYPE_GENERIC,
+            target_name: target.as_mut_ptr(),
+            comment: std::ptr::null_mut(),
+            last_written: FileTime { low: 0, high: 0 },
+            blob_size: blob.len() as u32,
+            blob: blob.as_mut_ptr(),
+            persist: CRED_PERSIST_LOCAL_MACHINE,
+            attribute_count: 0,
+            attributes: std::ptr::null_mut(),
+            target_alias: std::ptr::null_mut(),
+            user_name: user.as_mut_ptr(),
+        };
+        // SAFETY: every pointer refers to a buffer that outlives the call
+        unsafe {
+            if CredWriteW(&credential, 0) == 0 {
+                anyhow::bail!("{}", std::io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete one credential, returning whether there was one
+    fn delete_part(target: &[u16]) -> Result<bool> {
+        // SAFETY: the target is NUL-terminated
+        unsafe {
+            if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == 0 {
+                let error = std::io::Error::last_os_error();
+                if error.raw_os_error() == Some(ERROR_NOT_FOUND) {
+                    return Ok(false);
+                }
+                anyhow::bail!("Deleting from Credential Manager failed: {}", error);
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,145 @@
+//! Environment values read from HashiCorp Vault
+//!
+//! A value such as `vault:secret/data/myapp#DB_PASSWORD` names the API path of
+//! a secret and one of its fields. The server and token come from `VAULT_ADDR`
+//! and `VAULT_TOKEN` (or `~/.vault-token`, as the vault CLI writes it), and
+//! requests go through `curl` so TLS setups that work for the CLI work here too.
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+/// Marks an environment value as a Vault reference
+pub const PREFIX: &str = "vault:";
+
+/// Address of a dev server started with `vault server -dev`
+const DEFAULT_ADDRESS: &str = "http://127.0.0.1:8200";
+
+/// Path and field of a Vault reference, `None` for plain values
+pub fn parse_ref(value: &str) -> Option<Result<(&str, &str)>> {
+    let reference = value.strip_prefix(PREFIX)?;
+    Some(match reference.split_once('#') {
+        Some((path, field)) if !path.is_empty() && !field.is_empty() => Ok((path.trim_matches('/'), field)),
+        _ => Err(anyhow::anyhow!("Invalid Vault reference '{}', expected vault:<path>#<field>", value)),
+    })
+}
+
+/// Reads secrets from a Vault server, each path once
+pub struct VaultClient {
+    address: String,
+    token: Option<String>,
+    namespace: Option<String>,
+    secrets: HashMap<String, Map<String, Value>>,
+}
+
+impl VaultClient {
+    /// Client for the server in `VAULT_ADDR`, authenticated like the vault CLI
+    pub fn from_env() -> Self {
+        let token = std::env::var("VAULT_TOKEN").ok()
+            .or_else(|| {
+                let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+                std::fs::read_to_string(std::path::Path::new(&home).join(".vault-token")).ok()
+            })
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+
+        Self {
+            address: std::env::var("VAULT_ADDR").unwrap_or_else(|_| DEFAULT_ADDRESS.to_string()),
+            token,
+            namespace: std::env::var("VAULT_NAMESPACE").ok(),
+            secrets: HashMap::new(),
+        }
+    }
+
+    /// Replace the Vault references among environment values with the secrets they name
+    pub async fn resolve(&mut self, env: &mut HashMap<String, String>) -> Result<()> {
+        for (key, value) in env.iter_mut() {
+            let Some(reference) = parse_ref(value) else { continue };
+            let (path, field) = reference?;
+            let secret = self.read(path).await.with_context(|| format!("Failed to resolve {}", key))?;
+            *value = match secret.get(field) {
+                Some(Value::String(text)) => text.clone(),
+                Some(other) => other.to_string(),
+                None => anyhow::bail!("{}: the Vault secret {} has no field {}", key, path, field),
+            };
+        }
+        Ok(())
+    }
+
+    /// Fields of the secret at an API path
+    async fn read(&mut self, path: &str) -> Result<&Map<String, Value>> {
+        if !self.secrets.contains_key(path) {
+            let response = self.get(path).await?;
+            self.secrets.insert(path.to_string(), secret_fields(response)?);
+        }
+        Ok(&self.secrets[path])
+    }
+
+    async fn get(&self, path: &str) -> Result<Value> {
+        let token = self.token.as_deref()
+            .context("No Vault token; set VAULT_TOKEN or run `vault login`")?;
+        let url = format!("{}/v1/{}", self.address.trim_end_matches('/'), path);
+
+        // Headers are passed on stdin so the token stays out of the process list
+        let mut headers = format!("X-Vault-Token: {}\n", token);
+        if let Some(namespace) = &self.namespace {
+            headers.push_str(&format!("X-Vault-Namespace: {}\n", namespace));
+        }
+
+        let mut child = tokio::process::Command::new("curl")
+            .args(["--silent", "--show-error", "--header", "@-", "--write-out", "\n%{http_code}", &url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("curl is needed to read secrets from Vault")?;
+        let mut stdin = child.stdin.take().context("curl has no stdin")?;
+        stdin.write_all(headers.as_bytes()).await?;
+        drop(stdin);
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            anyhow::bail!("Could not reach Vault at {}: {}", self.address, String::from_utf8_lossy(&output.stderr).trim());
+        }
+        let body = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = body.rsplit_once('\n').unwrap_or(("", &body));
+        match status.trim() {
+            "200" => serde_json::from_str(body).context("Vault returned invalid JSON"),
+            "403" => anyhow::bail!("Vault denied reading {}; check the token's policies", path),
+            "404" => anyhow::bail!("No Vault secret at {}", path),
+            status => anyhow::bail!("Vault answered {} for {}: {}", status, path, body.trim()),
+        }
+    }
+}
+
+/// Fields of a read response; KV version 2 nests them under `data.data`
+fn secret_fields(response: Value) -> Result<Map<String, Value>> {
+    let Some(Value::Object(mut data)) = response.get("data").cloned() else {
+        anyhow::bail!("Vault response has no data");
+    };
+    if data.contains_key("metadata") {
+        if let Some(Value::Object(fields)) = data.remove("data") {
+            return Ok(fields);
+        }
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ref_and_kv_versions() {
+        assert_eq!(parse_ref("vault:secret/data/myapp#DB_PASSWORD").unwrap().unwrap(), ("secret/data/myapp", "DB_PASSWORD"));
+        assert!(parse_ref("vault:secret/data/myapp").unwrap().is_err());
+        assert!(parse_ref("hunter2").is_none());
+
+        let v2 = serde_json::json!({ "data": { "data": { "DB_PASSWORD": "s3cret" }, "metadata": { "version": 3 } } });
+        assert_eq!(secret_fields(v2).unwrap()["DB_PASSWORD"], "s3cret");
+        let v1 = serde_json::json!({ "data": { "DB_PASSWORD": "s3cret" } });
+        assert_eq!(secret_fields(v1).unwrap()["DB_PASSWORD"], "s3cret");
+    }
+}
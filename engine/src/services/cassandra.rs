@@ -0,0 +1,60 @@
+/// Cassandra service configuration and helpers
+///
+/// A single node with a small heap, as the default sizes the JVM by the
+/// host's memory; authentication is off, as in the image.
+pub struct CassandraService;
+
+/// Data center the node joins, which drivers must name as their local one
+pub const DATACENTER: &str = "datacenter1";
+
+impl CassandraService {
+    pub fn default_image(version: &str) -> String {
+        format!("cassandra:{}", version)
+    }
+
+    /// CQL native protocol
+    pub fn default_port() -> u16 {
+        9042
+    }
+
+    pub fn default_env_vars() -> Vec<String> {
+        vec![
+            "CASSANDRA_CLUSTER_NAME=zeroconfig".to_string(),
+            format!("CASSANDRA_DC={}", DATACENTER),
+            "CASSANDRA_ENDPOINT_SNITCH=GossipingPropertyFileSnitch".to_string(),
+            "MAX_HEAP_SIZE=512M".to_string(),
+            "HEAP_NEWSIZE=128M".to_string(),
+        ]
+    }
+
+    /// Variables the DataStax drivers and most frameworks are configured from
+    pub fn app_env_vars(host: &str, port: u16) -> Vec<(String, String)> {
+        vec![
+            ("CASSANDRA_CONTACT_POINTS".to_string(), format!("{}:{}", host, port)),
+            ("CASSANDRA_LOCAL_DATACENTER".to_string(), DATACENTER.to_string()),
+        ]
+    }
+
+    /// Passes once the node accepts CQL, which takes a while after the container starts
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "cqlsh".to_string(),
+            "-e".to_string(),
+            "DESCRIBE KEYSPACES".to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_node_env() {
+        assert!(CassandraService::default_env_vars().contains(&"MAX_HEAP_SIZE=512M".to_string()));
+        assert_eq!(
+            CassandraService::app_env_vars("shop_cassandra", 9042)[0],
+            ("CASSANDRA_CONTACT_POINTS".to_string(), "shop_cassandra:9042".to_string())
+        );
+    }
+}
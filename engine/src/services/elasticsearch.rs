@@ -0,0 +1,155 @@
+use crate::orchestrator::Sidecar;
+
+/// Elasticsearch and OpenSearch service configuration and helpers
+///
+/// Both run as a single node with security disabled, and with a heap sized to
+/// the container's memory limit so the JVM doesn't claim most of the host.
+/// With `kibana: true` their dashboards run as a sidecar of the same version.
+pub struct ElasticsearchService;
+
+/// Port Kibana and OpenSearch Dashboards serve their UI on
+pub const DASHBOARDS_PORT: u16 = 5601;
+
+/// Container memory limit when zero.yml doesn't set `memory:`
+pub const DEFAULT_MEMORY: u64 = 1024 * 1024 * 1024;
+
+/// Smallest heap the JVM is given
+const MIN_HEAP_MB: u64 = 256;
+
+/// Smallest memory limit that fits the minimum heap in half the container
+pub const MIN_MEMORY: u64 = 2 * MIN_HEAP_MB * 1024 * 1024;
+
+impl ElasticsearchService {
+    /// Whether a service name is run by this helper
+    pub fn handles(service: &str) -> bool {
+        matches!(service, "elasticsearch" | "opensearch")
+    }
+
+    pub fn default_image(service: &str, version: &str) -> String {
+        match service {
+            "opensearch" => format!("opensearchproject/opensearch:{}", version),
+            _ => format!("elasticsearch:{}", version),
+        }
+    }
+
+    pub fn default_port() -> u16 {
+        9200
+    }
+
+    /// Heap size in megabytes: half the memory limit, leaving the rest to Lucene's file cache
+    pub fn heap_megabytes(memory_limit: u64) -> u64 {
+        (memory_limit / 2 / (1024 * 1024)).max(MIN_HEAP_MB)
+    }
+
+    /// Environment for a single local node with security disabled
+    pub fn default_env_vars(service: &str, memory_limit: u64) -> Vec<String> {
+        let heap = Self::heap_megabytes(memory_limit);
+        let java_opts = format!("-Xms{}m -Xmx{}m", heap, heap);
+
+        match service {
+            "opensearch" => vec![
+                "discovery.type=single-node".to_string(),
+                "DISABLE_SECURITY_PLUGIN=true".to_string(),
+                "DISABLE_INSTALL_DEMO_CONFIG=true".to_string(),
+                format!("OPENSEARCH_JAVA_OPTS={}", java_opts),
+            ],
+            _ => vec![
+                "discovery.type=single-node".to_string(),
+                "xpack.security.enabled=false".to_string(),
+                "xpack.security.enrollment.enabled=false".to_string(),
+                format!("ES_JAVA_OPTS={}", java_opts),
+            ],
+        }
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+
+    /// Passes once the cluster is at least yellow, which is as good as a single node gets
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "curl".to_string(),
+            "-fs".to_string(),
+            "http://localhost:9200/_cluster/health?wait_for_status=yellow&timeout=5s".to_string(),
+        ]
+    }
+
+    /// Sidecar name of the dashboards for a service: `kibana` or `opensearch-dashboards`
+    pub fn dashboards_name(service: &str) -> &'static str {
+        match service {
+            "opensearch" => "opensearch-dashboards",
+            _ => "kibana",
+        }
+    }
+
+    /// Kibana, or OpenSearch Dashboards, connected to a service by its container name
+    ///
+    /// Both refuse to talk to a server of another version, so they run the service's.
+    pub fn dashboards_sidecar(project: &str, service: &str, version: &str, host_port: u16) -> Sidecar {
+        let url = format!("http://{}_{}:{}", project, service, Self::default_port());
+        let (image, env) = match service {
+            "opensearch" => (
+                format!("opensearchproject/opensearch-dashboards:{}", version),
+                vec![
+                    format!("OPENSEARCH_HOSTS=[\"{}\"]", url),
+                    "DISABLE_SECURITY_DASHBOARDS_PLUGIN=true".to_string(),
+                ],
+            ),
+            _ => (
+                format!("kibana:{}", version),
+                vec![
+                    format!("ELASTICSEARCH_HOSTS={}", url),
+                    "TELEMETRY_OPTIN=false".to_string(),
+                ],
+            ),
+        };
+
+        Sidecar {
+            name: Self::dashboards_name(service).to_string(),
+            image,
+            env,
+            ports: vec![(DASHBOARDS_PORT, host_port)],
+            ..Default::default()
+        }
+    }
+
+    /// Passes once the dashboards report their status, which they only do when ready
+    pub fn dashboards_health_check_command() -> Vec<String> {
+        vec![
+            "curl".to_string(),
+            "-fs".to_string(),
+            format!("http://localhost:{}/api/status", DASHBOARDS_PORT),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heap_follows_memory_limit() {
+        assert_eq!(ElasticsearchService::heap_megabytes(DEFAULT_MEMORY), 512);
+        assert_eq!(ElasticsearchService::heap_megabytes(256 * 1024 * 1024), MIN_HEAP_MB);
+
+        let env = ElasticsearchService::default_env_vars("elasticsearch", 4 * DEFAULT_MEMORY);
+        assert!(env.contains(&"ES_JAVA_OPTS=-Xms2048m -Xmx2048m".to_string()));
+        let env = ElasticsearchService::default_env_vars("opensearch", DEFAULT_MEMORY);
+        assert!(env.contains(&"OPENSEARCH_JAVA_OPTS=-Xms512m -Xmx512m".to_string()));
+        assert_eq!(ElasticsearchService::default_image("opensearch", "2"), "opensearchproject/opensearch:2");
+    }
+
+    #[test]
+    fn test_dashboards_match_the_service() {
+        let kibana = ElasticsearchService::dashboards_sidecar("shop", "elasticsearch", "8.13.0", 5602);
+        assert_eq!(kibana.name, "kibana");
+        assert_eq!(kibana.image, "kibana:8.13.0");
+        assert_eq!(kibana.ports, vec![(DASHBOARDS_PORT, 5602)]);
+        assert!(kibana.env.contains(&"ELASTICSEARCH_HOSTS=http://shop_elasticsearch:9200".to_string()));
+
+        let dashboards = ElasticsearchService::dashboards_sidecar("shop", "opensearch", "2", 5601);
+        assert_eq!(dashboards.name, "opensearch-dashboards");
+        assert!(dashboards.env.contains(&"OPENSEARCH_HOSTS=[\"http://shop_opensearch:9200\"]".to_string()));
+    }
+}
@@ -0,0 +1,67 @@
+/// InfluxDB 2.x service configuration and helpers
+///
+/// The image's setup mode creates the initial user, org, bucket and admin
+/// token on first start, from values kept in the credential store.
+pub struct InfluxDbService;
+
+/// Org and bucket created on first start
+pub const ORG: &str = "zeroconfig";
+pub const BUCKET: &str = "zeroconfig";
+
+impl InfluxDbService {
+    pub fn default_image(version: &str) -> String {
+        format!("influxdb:{}", version)
+    }
+
+    pub fn default_port() -> u16 {
+        8086
+    }
+
+    pub fn default_env_vars(password: &str, token: &str) -> Vec<String> {
+        vec![
+            "DOCKER_INFLUXDB_INIT_MODE=setup".to_string(),
+            "DOCKER_INFLUXDB_INIT_USERNAME=zeroconfig".to_string(),
+            format!("DOCKER_INFLUXDB_INIT_PASSWORD={}", password),
+            format!("DOCKER_INFLUXDB_INIT_ORG={}", ORG),
+            format!("DOCKER_INFLUXDB_INIT_BUCKET={}", BUCKET),
+            format!("DOCKER_INFLUXDB_INIT_ADMIN_TOKEN={}", token),
+        ]
+    }
+
+    /// Variables the InfluxDB client libraries and CLI read
+    pub fn app_env_vars(host: &str, port: u16, token: &str) -> Vec<(String, String)> {
+        vec![
+            ("INFLUX_HOST".to_string(), Self::connection_string(host, port)),
+            ("INFLUX_TOKEN".to_string(), token.to_string()),
+            ("INFLUX_ORG".to_string(), ORG.to_string()),
+            ("INFLUX_BUCKET".to_string(), BUCKET.to_string()),
+        ]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "influx".to_string(),
+            "ping".to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_env_and_app_env() {
+        let env = InfluxDbService::default_env_vars("secret", "token123");
+        assert!(env.contains(&"DOCKER_INFLUXDB_INIT_MODE=setup".to_string()));
+        assert!(env.contains(&"DOCKER_INFLUXDB_INIT_ADMIN_TOKEN=token123".to_string()));
+
+        let app = InfluxDbService::app_env_vars("localhost", 5005, "token123");
+        assert_eq!(app[0], ("INFLUX_HOST".to_string(), "http://localhost:5005".to_string()));
+        assert_eq!(app[1].1, "token123");
+    }
+}
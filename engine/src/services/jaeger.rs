@@ -0,0 +1,72 @@
+/// Jaeger (or a bare OpenTelemetry collector) tracing service configuration and helpers
+///
+/// Apps export traces over OTLP/HTTP on the main port; OTLP/gRPC and, for
+/// Jaeger, the web UI are published too.
+pub struct JaegerService;
+
+impl JaegerService {
+    /// Whether a service name is run by this helper
+    pub fn handles(service: &str) -> bool {
+        matches!(service, "jaeger" | "otel-collector")
+    }
+
+    pub fn default_image(service: &str, version: &str) -> String {
+        match service {
+            "otel-collector" => format!("otel/opentelemetry-collector-contrib:{}", version),
+            _ => format!("jaegertracing/all-in-one:{}", version),
+        }
+    }
+
+    /// OTLP over HTTP
+    pub fn default_port() -> u16 {
+        4318
+    }
+
+    pub fn grpc_port() -> u16 {
+        4317
+    }
+
+    pub fn ui_port() -> u16 {
+        16686
+    }
+
+    /// Container ports published besides the main one
+    pub fn extra_ports(service: &str) -> Vec<u16> {
+        match service {
+            "jaeger" => vec![Self::grpc_port(), Self::ui_port()],
+            _ => vec![Self::grpc_port()],
+        }
+    }
+
+    pub fn default_env_vars(service: &str) -> Vec<String> {
+        match service {
+            "jaeger" => vec!["COLLECTOR_OTLP_ENABLED=true".to_string()],
+            _ => vec![],
+        }
+    }
+
+    /// Variables every OpenTelemetry SDK reads to find the collector
+    pub fn app_env_vars(host: &str, port: u16) -> Vec<(String, String)> {
+        vec![
+            ("OTEL_EXPORTER_OTLP_ENDPOINT".to_string(), Self::connection_string(host, port)),
+            ("OTEL_EXPORTER_OTLP_PROTOCOL".to_string(), "http/protobuf".to_string()),
+        ]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otlp_endpoint_and_ports() {
+        assert_eq!(JaegerService::app_env_vars("localhost", 5004)[0].1, "http://localhost:5004");
+        assert_eq!(JaegerService::extra_ports("jaeger"), vec![4317, 16686]);
+        assert_eq!(JaegerService::extra_ports("otel-collector"), vec![4317]);
+        assert_eq!(JaegerService::default_image("otel-collector", "0.104.0"), "otel/opentelemetry-collector-contrib:0.104.0");
+    }
+}
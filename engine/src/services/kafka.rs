@@ -0,0 +1,118 @@
+/// Kafka service configuration and helpers
+///
+/// Brokers run as a single KRaft node (broker and controller in one process),
+/// so no ZooKeeper container is needed.
+pub struct KafkaService;
+
+/// Listener other containers on the project network connect to
+pub const INTERNAL_PORT: u16 = 29092;
+
+/// Listener the KRaft controller quorum uses
+pub const CONTROLLER_PORT: u16 = 29093;
+
+impl KafkaService {
+    pub fn default_image(version: &str) -> String {
+        format!("confluentinc/cp-kafka:{}", version)
+    }
+
+    /// Container port published to the host
+    pub fn default_port() -> u16 {
+        9092
+    }
+
+    /// Environment for a single-node KRaft broker
+    ///
+    /// Clients on the host reach the broker on `localhost:{host_port}`, and
+    /// containers on the project network on `{container_name}:29092`.
+    pub fn default_env_vars(container_name: &str, host_port: u16, cluster_id: &str) -> Vec<String> {
+        vec![
+            "KAFKA_NODE_ID=1".to_string(),
+            "KAFKA_PROCESS_ROLES=broker,controller".to_string(),
+            format!(
+                "KAFKA_LISTENERS=PLAINTEXT://0.0.0.0:{},CONTROLLER://0.0.0.0:{},PLAINTEXT_HOST://0.0.0.0:{}",
+                INTERNAL_PORT, CONTROLLER_PORT, Self::default_port()
+            ),
+            format!(
+                "KAFKA_ADVERTISED_LISTENERS=PLAINTEXT://{}:{},PLAINTEXT_HOST://localhost:{}",
+                container_name, INTERNAL_PORT, host_port
+            ),
+            "KAFKA_LISTENER_SECURITY_PROTOCOL_MAP=CONTROLLER:PLAINTEXT,PLAINTEXT:PLAINTEXT,PLAINTEXT_HOST:PLAINTEXT".to_string(),
+            "KAFKA_CONTROLLER_LISTENER_NAMES=CONTROLLER".to_string(),
+            "KAFKA_INTER_BROKER_LISTENER_NAME=PLAINTEXT".to_string(),
+            format!("KAFKA_CONTROLLER_QUORUM_VOTERS=1@{}:{}", container_name, CONTROLLER_PORT),
+            "KAFKA_OFFSETS_TOPIC_REPLICATION_FACTOR=1".to_string(),
+            "KAFKA_TRANSACTION_STATE_LOG_REPLICATION_FACTOR=1".to_string(),
+            "KAFKA_TRANSACTION_STATE_LOG_MIN_ISR=1".to_string(),
+            "KAFKA_GROUP_INITIAL_REBALANCE_DELAY_MS=0".to_string(),
+            format!("CLUSTER_ID={}", cluster_id),
+        ]
+    }
+
+    /// KRaft cluster id derived from the container name
+    ///
+    /// Kafka expects 16 bytes in unpadded URL-safe base64. Deriving it keeps
+    /// the id stable across restarts, so persisted log dirs stay valid.
+    pub fn cluster_id(container_name: &str) -> String {
+        use base64::{Engine as _, engine::general_purpose};
+        use sha2::{Digest, Sha256};
+
+        let digest = Sha256::digest(container_name.as_bytes());
+        general_purpose::URL_SAFE_NO_PAD.encode(&digest[..16])
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("{}:{}", host, port)
+    }
+
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "kafka-broker-api-versions".to_string(),
+            "--bootstrap-server".to_string(),
+            format!("localhost:{}", INTERNAL_PORT),
+        ]
+    }
+
+    /// Command creating a topic unless it exists
+    ///
+    /// Topics are written as `name` or `name:partitions` in zero.yml.
+    pub fn create_topic_command(topic: &str) -> Vec<String> {
+        let (name, partitions) = match topic.rsplit_once(':') {
+            Some((name, partitions)) if partitions.parse::<u32>().is_ok() => (name, partitions),
+            _ => (topic, "1"),
+        };
+
+        vec![
+            "kafka-topics".to_string(),
+            "--bootstrap-server".to_string(),
+            format!("localhost:{}", INTERNAL_PORT),
+            "--create".to_string(),
+            "--if-not-exists".to_string(),
+            "--topic".to_string(),
+            name.to_string(),
+            "--partitions".to_string(),
+            partitions.to_string(),
+            "--replication-factor".to_string(),
+            "1".to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kraft_listeners_and_topics() {
+        let env = KafkaService::default_env_vars("shop_kafka", 5003, &KafkaService::cluster_id("shop_kafka"));
+        assert!(env.contains(&"KAFKA_ADVERTISED_LISTENERS=PLAINTEXT://shop_kafka:29092,PLAINTEXT_HOST://localhost:5003".to_string()));
+        assert!(env.contains(&"KAFKA_CONTROLLER_QUORUM_VOTERS=1@shop_kafka:29093".to_string()));
+
+        let cluster_id = KafkaService::cluster_id("shop_kafka");
+        assert_eq!(cluster_id.len(), 22);
+        assert_eq!(cluster_id, KafkaService::cluster_id("shop_kafka"));
+
+        let command = KafkaService::create_topic_command("orders:3");
+        assert_eq!(&command[6..9], &["orders", "--partitions", "3"]);
+        assert_eq!(&KafkaService::create_topic_command("events")[6..9], &["events", "--partitions", "1"]);
+    }
+}
@@ -0,0 +1,135 @@
+/// Keycloak service configuration and helpers
+///
+/// Runs in development mode with an admin account whose password is kept in
+/// the credential store. A `realm:` export is imported through the admin CLI
+/// once the server is up, unless the realm already exists.
+pub struct KeycloakService;
+
+/// Where a `realm:` file is copied in the container before it is imported
+pub const REALM_PATH: &str = "/tmp/realm.json";
+
+/// Realm apps authenticate against when zero.yml imports none
+pub const DEFAULT_REALM: &str = "master";
+
+/// Username of the bootstrap admin account
+pub const ADMIN_USER: &str = "admin";
+
+/// Port the health endpoints are served on, apart from the app's
+const MANAGEMENT_PORT: u16 = 9000;
+
+const KCADM: &str = "/opt/keycloak/bin/kcadm.sh";
+
+impl KeycloakService {
+    pub fn default_image(version: &str) -> String {
+        format!("quay.io/keycloak/keycloak:{}", version)
+    }
+
+    pub fn default_port() -> u16 {
+        8080
+    }
+
+    /// Server command, used unless zero.yml sets `command:`
+    pub fn default_command() -> Vec<String> {
+        vec!["start-dev".to_string()]
+    }
+
+    /// Admin account and health endpoints
+    ///
+    /// Keycloak 26 reads the `KC_BOOTSTRAP_ADMIN_*` names, earlier versions `KEYCLOAK_ADMIN*`.
+    pub fn default_env_vars(password: &str) -> Vec<String> {
+        vec![
+            format!("KC_BOOTSTRAP_ADMIN_USERNAME={}", ADMIN_USER),
+            format!("KC_BOOTSTRAP_ADMIN_PASSWORD={}", password),
+            format!("KEYCLOAK_ADMIN={}", ADMIN_USER),
+            format!("KEYCLOAK_ADMIN_PASSWORD={}", password),
+            "KC_HEALTH_ENABLED=true".to_string(),
+        ]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+
+    /// Issuer URL OIDC clients discover the realm's endpoints from
+    pub fn issuer_url(host: &str, port: u16, realm: &str) -> String {
+        format!("{}/realms/{}", Self::connection_string(host, port), realm)
+    }
+
+    /// Variables pointing the app's OIDC client at a realm
+    pub fn app_env_vars(host: &str, port: u16, realm: &str) -> Vec<(String, String)> {
+        vec![
+            ("OIDC_ISSUER_URL".to_string(), Self::issuer_url(host, port, realm)),
+            ("KEYCLOAK_REALM".to_string(), realm.to_string()),
+        ]
+    }
+
+    /// Name of the realm in a realm export
+    pub fn realm_name(export: &str) -> Option<String> {
+        let export: serde_json::Value = serde_json::from_str(export).ok()?;
+        export.get("realm")?.as_str().map(str::to_string)
+    }
+
+    /// Log the admin CLI in, failing until the server accepts requests
+    pub fn login_command(password: &str) -> Vec<String> {
+        vec![
+            KCADM.to_string(),
+            "config".to_string(),
+            "credentials".to_string(),
+            "--server".to_string(),
+            format!("http://localhost:{}", Self::default_port()),
+            "--realm".to_string(),
+            DEFAULT_REALM.to_string(),
+            "--user".to_string(),
+            ADMIN_USER.to_string(),
+            "--password".to_string(),
+            password.to_string(),
+        ]
+    }
+
+    /// Create the realm from [`REALM_PATH`] unless it exists; run after [`Self::login_command`]
+    pub fn import_realm_command(realm: &str) -> Vec<String> {
+        let realm = realm.replace('\'', r"'\''");
+        vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            format!(
+                "{kcadm} get 'realms/{realm}' > /dev/null 2>&1 || {kcadm} create realms -f {path}",
+                kcadm = KCADM,
+                realm = realm,
+                path = REALM_PATH,
+            ),
+        ]
+    }
+
+    /// Passes once the server reports ready on its management port
+    ///
+    /// The image has neither curl nor wget, so bash speaks HTTP itself.
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            format!(
+                "exec 3<>/dev/tcp/localhost/{} && printf 'GET /health/ready HTTP/1.0\\r\\nHost: localhost\\r\\n\\r\\n' >&3 && grep -q '\"UP\"' <&3",
+                MANAGEMENT_PORT
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realm_import_and_issuer() {
+        assert_eq!(KeycloakService::realm_name(r#"{"realm": "shop", "enabled": true}"#).as_deref(), Some("shop"));
+        assert_eq!(KeycloakService::realm_name("not json"), None);
+
+        let command = KeycloakService::import_realm_command("shop");
+        assert_eq!(command[2], "/opt/keycloak/bin/kcadm.sh get 'realms/shop' > /dev/null 2>&1 || /opt/keycloak/bin/kcadm.sh create realms -f /tmp/realm.json");
+        assert_eq!(
+            KeycloakService::app_env_vars("localhost", 8081, "shop")[0],
+            ("OIDC_ISSUER_URL".to_string(), "http://localhost:8081/realms/shop".to_string())
+        );
+    }
+}
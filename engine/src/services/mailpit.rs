@@ -0,0 +1,78 @@
+/// Mailpit (or MailHog) mail-catching service configuration and helpers
+///
+/// Apps send mail over SMTP without authentication and the web UI shows
+/// every message caught.
+pub struct MailpitService;
+
+impl MailpitService {
+    /// Whether a service name is run by this helper
+    pub fn handles(service: &str) -> bool {
+        matches!(service, "mailpit" | "mailhog")
+    }
+
+    pub fn default_image(service: &str, version: &str) -> String {
+        match service {
+            "mailhog" => format!("mailhog/mailhog:{}", version),
+            _ => format!("axllent/mailpit:{}", version),
+        }
+    }
+
+    /// SMTP port
+    pub fn default_port() -> u16 {
+        1025
+    }
+
+    pub fn ui_port() -> u16 {
+        8025
+    }
+
+    /// Accept any SMTP credentials, so apps configured with a username and password work too
+    pub fn default_env_vars(service: &str) -> Vec<String> {
+        match service {
+            "mailpit" => vec![
+                "MP_SMTP_AUTH_ACCEPT_ANY=1".to_string(),
+                "MP_SMTP_AUTH_ALLOW_INSECURE=1".to_string(),
+            ],
+            _ => vec![],
+        }
+    }
+
+    /// Variables pointing the app's mailer at the service
+    pub fn app_env_vars(host: &str, port: u16) -> Vec<(String, String)> {
+        vec![
+            ("SMTP_HOST".to_string(), host.to_string()),
+            ("SMTP_PORT".to_string(), port.to_string()),
+        ]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("smtp://{}:{}", host, port)
+    }
+
+    /// Passes once the web UI answers; both images ship busybox's wget but no curl
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "wget".to_string(),
+            "-q".to_string(),
+            "--spider".to_string(),
+            format!("http://localhost:{}/", Self::ui_port()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mailhog_alias_and_app_env() {
+        assert_eq!(MailpitService::default_image("mailhog", "latest"), "mailhog/mailhog:latest");
+        assert_eq!(MailpitService::default_image("mailpit", "v1.20"), "axllent/mailpit:v1.20");
+        assert!(MailpitService::default_env_vars("mailhog").is_empty());
+        assert_eq!(MailpitService::health_check_command().last().map(String::as_str), Some("http://localhost:8025/"));
+        assert_eq!(
+            MailpitService::app_env_vars("localhost", 5002),
+            vec![("SMTP_HOST".to_string(), "localhost".to_string()), ("SMTP_PORT".to_string(), "5002".to_string())]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+/// Meilisearch service configuration and helpers
+pub struct MeilisearchService;
+
+/// Environment variable holding the master key, in the container and the credential store
+pub const KEY_VAR: &str = "MEILI_MASTER_KEY";
+
+impl MeilisearchService {
+    pub fn default_image(version: &str) -> String {
+        format!("getmeili/meilisearch:{}", version)
+    }
+
+    pub fn default_port() -> u16 {
+        7700
+    }
+
+    pub fn default_env_vars(master_key: &str) -> Vec<String> {
+        vec![
+            format!("{}={}", KEY_VAR, master_key),
+            "MEILI_ENV=development".to_string(),
+            "MEILI_NO_ANALYTICS=true".to_string(),
+        ]
+    }
+
+    /// Variables the app's Meilisearch client is configured from
+    pub fn app_env_vars(master_key: &str) -> Vec<(String, String)> {
+        vec![("MEILISEARCH_API_KEY".to_string(), master_key.to_string())]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "curl".to_string(),
+            "-f".to_string(),
+            format!("http://localhost:{}/health", Self::default_port()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_master_key_env() {
+        assert!(MeilisearchService::default_env_vars("k3y").contains(&"MEILI_MASTER_KEY=k3y".to_string()));
+        assert_eq!(MeilisearchService::health_check_command()[2], "http://localhost:7700/health");
+    }
+}
@@ -0,0 +1,29 @@
+/// Memcached service configuration and helpers
+pub struct MemcachedService;
+
+impl MemcachedService {
+    pub fn default_image(version: &str) -> String {
+        format!("memcached:{}", version)
+    }
+
+    pub fn default_port() -> u16 {
+        11211
+    }
+
+    /// Server list most clients read, e.g. Rails' and Django's
+    pub fn app_env_vars(host: &str, port: u16) -> Vec<(String, String)> {
+        vec![("MEMCACHED_SERVERS".to_string(), format!("{}:{}", host, port))]
+    }
+
+    /// Asks for stats over the text protocol; the image has bash but no nc
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            format!(
+                "exec 3<>/dev/tcp/localhost/{} && printf 'stats\\r\\nquit\\r\\n' >&3 && grep -q 'STAT pid' <&3",
+                Self::default_port()
+            ),
+        ]
+    }
+}
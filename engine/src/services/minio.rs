@@ -0,0 +1,99 @@
+/// MinIO service configuration and helpers
+///
+/// The S3 API listens on 9000 and the web console on 9001; both are published.
+pub struct MinioService;
+
+/// Alias the bucket commands register for the server inside its own container
+const ALIAS: &str = "local";
+
+/// Region MinIO answers to unless configured otherwise
+const REGION: &str = "us-east-1";
+
+impl MinioService {
+    pub fn default_image(version: &str) -> String {
+        format!("minio/minio:{}", version)
+    }
+
+    /// S3 API port
+    pub fn default_port() -> u16 {
+        9000
+    }
+
+    pub fn console_port() -> u16 {
+        9001
+    }
+
+    /// Server command, used unless zero.yml sets `command:`
+    pub fn default_command() -> Vec<String> {
+        vec![
+            "server".to_string(),
+            "/data".to_string(),
+            "--console-address".to_string(),
+            format!(":{}", Self::console_port()),
+        ]
+    }
+
+    pub fn default_env_vars(access_key: &str, secret_key: &str) -> Vec<String> {
+        vec![
+            format!("MINIO_ROOT_USER={}", access_key),
+            format!("MINIO_ROOT_PASSWORD={}", secret_key),
+        ]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+
+    /// Variables S3 SDKs read their endpoint and credentials from
+    ///
+    /// `AWS_ENDPOINT_URL_S3` is honored by the AWS SDKs and CLI themselves;
+    /// `S3_ENDPOINT` is the name most other clients and frameworks use.
+    pub fn app_env_vars(endpoint: &str, access_key: &str, secret_key: &str) -> Vec<(String, String)> {
+        vec![
+            ("S3_ENDPOINT".to_string(), endpoint.to_string()),
+            ("AWS_ENDPOINT_URL_S3".to_string(), endpoint.to_string()),
+            ("AWS_ACCESS_KEY_ID".to_string(), access_key.to_string()),
+            ("AWS_SECRET_ACCESS_KEY".to_string(), secret_key.to_string()),
+            ("AWS_REGION".to_string(), REGION.to_string()),
+        ]
+    }
+
+    /// Point the bundled `mc` client at the server, failing until it accepts requests
+    pub fn alias_command(access_key: &str, secret_key: &str) -> Vec<String> {
+        vec![
+            "mc".to_string(),
+            "alias".to_string(),
+            "set".to_string(),
+            ALIAS.to_string(),
+            format!("http://localhost:{}", Self::default_port()),
+            access_key.to_string(),
+            secret_key.to_string(),
+        ]
+    }
+
+    /// Command creating a bucket unless it exists; run after [`Self::alias_command`]
+    pub fn create_bucket_command(bucket: &str) -> Vec<String> {
+        vec![
+            "mc".to_string(),
+            "mb".to_string(),
+            "--ignore-existing".to_string(),
+            format!("{}/{}", ALIAS, bucket),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_and_bucket_commands() {
+        assert_eq!(MinioService::default_command().join(" "), "server /data --console-address :9001");
+        assert_eq!(MinioService::alias_command("key", "secret")[4], "http://localhost:9000");
+        assert_eq!(MinioService::create_bucket_command("uploads").join(" "), "mc mb --ignore-existing local/uploads");
+
+        let env = MinioService::app_env_vars("http://localhost:9000", "key", "secret");
+        assert!(env.contains(&("AWS_ENDPOINT_URL_S3".to_string(), "http://localhost:9000".to_string())));
+        assert!(env.contains(&("AWS_SECRET_ACCESS_KEY".to_string(), "secret".to_string())));
+    }
+}
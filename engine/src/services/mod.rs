@@ -2,6 +2,22 @@
 pub mod postgres;
 pub mod redis;
 pub mod mongodb;
+pub mod kafka;
+pub mod keycloak;
+pub mod rabbitmq;
+pub mod elasticsearch;
+pub mod minio;
+pub mod mailpit;
+pub mod jaeger;
+pub mod influxdb;
+pub mod meilisearch;
+pub mod typesense;
+pub mod nats;
+pub mod clickhouse;
+pub mod cassandra;
+pub mod memcached;
+pub mod observability;
+pub mod web_ui;
 
 // Re-export service types for external use
 #[allow(unused_imports)]
@@ -10,3 +26,31 @@ pub use postgres::PostgresService;
 pub use redis::RedisService;
 #[allow(unused_imports)]
 pub use mongodb::MongoDBService;
+#[allow(unused_imports)]
+pub use kafka::KafkaService;
+#[allow(unused_imports)]
+pub use keycloak::KeycloakService;
+#[allow(unused_imports)]
+pub use rabbitmq::RabbitMqService;
+#[allow(unused_imports)]
+pub use elasticsearch::ElasticsearchService;
+#[allow(unused_imports)]
+pub use minio::MinioService;
+#[allow(unused_imports)]
+pub use mailpit::MailpitService;
+#[allow(unused_imports)]
+pub use jaeger::JaegerService;
+#[allow(unused_imports)]
+pub use influxdb::InfluxDbService;
+#[allow(unused_imports)]
+pub use meilisearch::MeilisearchService;
+#[allow(unused_imports)]
+pub use typesense::TypesenseService;
+#[allow(unused_imports)]
+pub use nats::NatsService;
+#[allow(unused_imports)]
+pub use clickhouse::ClickHouseService;
+#[allow(unused_imports)]
+pub use cassandra::CassandraService;
+#[allow(unused_imports)]
+pub use memcached::MemcachedService;
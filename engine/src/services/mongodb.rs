@@ -1,5 +1,4 @@
 /// MongoDB service configuration and helpers
-
 pub struct MongoDBService;
 
 impl MongoDBService {
@@ -0,0 +1,58 @@
+/// NATS service configuration and helpers
+///
+/// Runs with JetStream enabled and the monitoring endpoint on 8222, which is
+/// published besides the client port and answers the health check.
+pub struct NatsService;
+
+impl NatsService {
+    /// The Alpine variant, as the plain image has no shell or tools to check health with
+    pub fn default_image(version: &str) -> String {
+        match version {
+            "latest" | "alpine" => "nats:alpine".to_string(),
+            version => format!("nats:{}-alpine", version),
+        }
+    }
+
+    /// Client port
+    pub fn default_port() -> u16 {
+        4222
+    }
+
+    pub fn monitoring_port() -> u16 {
+        8222
+    }
+
+    /// Server arguments, used unless zero.yml sets `command:`
+    pub fn default_command() -> Vec<String> {
+        vec![
+            "--jetstream".to_string(),
+            "--http_port".to_string(),
+            Self::monitoring_port().to_string(),
+        ]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("nats://{}:{}", host, port)
+    }
+
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "wget".to_string(),
+            "-q".to_string(),
+            "--spider".to_string(),
+            format!("http://localhost:{}/healthz", Self::monitoring_port()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alpine_image() {
+        assert_eq!(NatsService::default_image("2.10"), "nats:2.10-alpine");
+        assert_eq!(NatsService::default_image("latest"), "nats:alpine");
+        assert_eq!(NatsService::default_command().join(" "), "--jetstream --http_port 8222");
+    }
+}
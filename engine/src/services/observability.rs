@@ -0,0 +1,232 @@
+//! Prometheus, Grafana, Jaeger and cAdvisor provisioned by `observability: true`
+//!
+//! cAdvisor exports per-container metrics on the project network, Prometheus
+//! scrapes it, the engine's own metrics endpoint and every service with a
+//! `metrics_path:`, Jaeger receives the apps' traces over OTLP, and Grafana
+//! starts with both as data sources and a dashboard of the project's services.
+//! A project that runs its own `jaeger` or `otel-collector` keeps it instead.
+
+use serde_json::{json, Value};
+
+use super::JaegerService;
+use crate::orchestrator::{Sidecar, SidecarFile};
+
+pub const CADVISOR_IMAGE: &str = "gcr.io/cadvisor/cadvisor:v0.49.1";
+pub const PROMETHEUS_IMAGE: &str = "prom/prometheus:v2.53.0";
+pub const GRAFANA_IMAGE: &str = "grafana/grafana:11.1.0";
+pub const JAEGER_IMAGE: &str = "jaegertracing/all-in-one:1.57";
+
+/// Host port the engine serves its own Prometheus metrics on
+pub const ENGINE_METRICS_PORT: u16 = 9464;
+
+pub const PROMETHEUS_PORT: u16 = 9090;
+pub const GRAFANA_PORT: u16 = 3000;
+
+/// Names of the sidecars, as they appear among the project's containers; `jaeger` only
+/// when the project has no tracing service of its own
+pub const SIDECARS: &[&str] = &["cadvisor", "prometheus", "grafana", "jaeger"];
+
+/// A project service exporting Prometheus metrics of its own
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrapeTarget {
+    pub service: String,
+    /// Container port the metrics are served on
+    pub port: u16,
+    pub path: String,
+}
+
+/// Host ports the stack publishes its UIs and collectors on
+#[derive(Debug, Clone, Default)]
+pub struct StackPorts {
+    pub prometheus: u16,
+    pub grafana: u16,
+    /// (container port, host port) pairs of the stack's Jaeger; empty when the project runs its own
+    pub jaeger: Vec<(u16, u16)>,
+}
+
+/// Sidecars for a project, publishing their UIs on the given host ports
+pub fn sidecars(project: &str, services: &[String], targets: &[ScrapeTarget], ports: &StackPorts) -> Vec<Sidecar> {
+    let mut sidecars = vec![
+        Sidecar {
+            name: "cadvisor".to_string(),
+            image: CADVISOR_IMAGE.to_string(),
+            cmd: Some(vec!["--docker_only=true".to_string(), "--housekeeping_interval=10s".to_string()]),
+            binds: vec![
+                "/:/rootfs:ro".to_string(),
+                "/var/run:/var/run:ro".to_string(),
+                "/sys:/sys:ro".to_string(),
+                "/var/lib/docker/:/var/lib/docker:ro".to_string(),
+            ],
+            privileged: true,
+            ..Default::default()
+        },
+        Sidecar {
+            name: "prometheus".to_string(),
+            image: PROMETHEUS_IMAGE.to_string(),
+            ports: vec![(PROMETHEUS_PORT, ports.prometheus)],
+            // Lets Prometheus reach the engine on the host, Docker Desktop or not
+            extra_hosts: vec!["host.docker.internal:host-gateway".to_string()],
+            files: vec![SidecarFile::new("/etc/prometheus/prometheus.yml", prometheus_config(project, targets))],
+            ..Default::default()
+        },
+        Sidecar {
+            name: "grafana".to_string(),
+            image: GRAFANA_IMAGE.to_string(),
+            env: vec![
+                "GF_AUTH_ANONYMOUS_ENABLED=true".to_string(),
+                "GF_AUTH_ANONYMOUS_ORG_ROLE=Admin".to_string(),
+                "GF_AUTH_DISABLE_LOGIN_FORM=true".to_string(),
+                format!("GF_DASHBOARDS_DEFAULT_HOME_DASHBOARD_PATH=/var/lib/grafana/dashboards/{}.json", project),
+            ],
+            ports: vec![(GRAFANA_PORT, ports.grafana)],
+            files: vec![
+                SidecarFile::new("/etc/grafana/provisioning/datasources/zeroconfig.yml", datasource_config(project)),
+                SidecarFile::new("/etc/grafana/provisioning/dashboards/zeroconfig.yml", DASHBOARD_PROVIDER),
+                SidecarFile::new(format!("/var/lib/grafana/dashboards/{}.json", project), dashboard(project, services).to_string()),
+            ],
+            ..Default::default()
+        },
+    ];
+
+    if !ports.jaeger.is_empty() {
+        sidecars.push(Sidecar {
+            name: "jaeger".to_string(),
+            image: JAEGER_IMAGE.to_string(),
+            env: JaegerService::default_env_vars("jaeger"),
+            ports: ports.jaeger.clone(),
+            ..Default::default()
+        });
+    }
+    sidecars
+}
+
+/// Scrape cAdvisor for the project's containers, the engine on the host and services exporting metrics
+fn prometheus_config(project: &str, targets: &[ScrapeTarget]) -> String {
+    let mut config = format!(
+        r#"global:
+  scrape_interval: 15s
+
+scrape_configs:
+  - job_name: containers
+    static_configs:
+      - targets: ["{project}_cadvisor:8080"]
+    metric_relabel_configs:
+      - source_labels: [name]
+        regex: "{project}_.*"
+        action: keep
+  - job_name: zeroconfig
+    static_configs:
+      - targets: ["host.docker.internal:{engine_port}"]
+  - job_name: prometheus
+    static_configs:
+      - targets: ["localhost:{prometheus_port}"]
+"#,
+        project = project,
+        engine_port = ENGINE_METRICS_PORT,
+        prometheus_port = PROMETHEUS_PORT,
+    );
+    for target in targets {
+        config.push_str(&format!(
+            "  - job_name: {service}\n    metrics_path: {path}\n    static_configs:\n      - targets: [\"{project}_{service}:{port}\"]\n",
+            service = target.service,
+            path = target.path,
+            project = project,
+            port = target.port,
+        ));
+    }
+    config
+}
+
+fn datasource_config(project: &str) -> String {
+    format!(
+        r#"apiVersion: 1
+datasources:
+  - name: Prometheus
+    uid: prometheus
+    type: prometheus
+    access: proxy
+    url: http://{project}_prometheus:{prometheus_port}
+    isDefault: true
+  - name: Jaeger
+    uid: jaeger
+    type: jaeger
+    access: proxy
+    url: http://{project}_jaeger:{jaeger_port}
+"#,
+        project = project,
+        prometheus_port = PROMETHEUS_PORT,
+        jaeger_port = JaegerService::ui_port(),
+    )
+}
+
+const DASHBOARD_PROVIDER: &str = r#"apiVersion: 1
+providers:
+  - name: zeroconfig
+    type: file
+    options:
+      path: /var/lib/grafana/dashboards
+"#;
+
+/// Dashboard with CPU, memory and network panels, one series per service
+fn dashboard(project: &str, services: &[String]) -> Value {
+    let panel = |id: u32, title: &str, unit: &str, expr: &dyn Fn(&str) -> String| {
+        let targets: Vec<Value> = services.iter()
+            .map(|service| json!({
+                "datasource": { "type": "prometheus", "uid": "prometheus" },
+                "expr": expr(&format!("{}_{}", project, service)),
+                "legendFormat": service,
+            }))
+            .collect();
+        json!({
+            "id": id,
+            "type": "timeseries",
+            "title": title,
+            "gridPos": { "x": ((id - 1) % 2) * 12, "y": ((id - 1) / 2) * 8, "w": 12, "h": 8 },
+            "fieldConfig": { "defaults": { "unit": unit }, "overrides": [] },
+            "targets": targets,
+        })
+    };
+
+    json!({
+        "uid": format!("zeroconfig-{}", project),
+        "title": format!("{} services", project),
+        "schemaVersion": 39,
+        "refresh": "10s",
+        "time": { "from": "now-15m", "to": "now" },
+        "panels": [
+            // The engine exports the same series under the zeroconfig job; counting them twice would double the panels
+            panel(1, "CPU", "percent", &|name| format!("sum(rate(container_cpu_usage_seconds_total{{job=\"containers\",name=\"{}\"}}[1m])) * 100", name)),
+            panel(2, "Memory", "bytes", &|name| format!("sum(container_memory_working_set_bytes{{job=\"containers\",name=\"{}\"}})", name)),
+            panel(3, "Network received", "Bps", &|name| format!("sum(rate(container_network_receive_bytes_total{{job=\"containers\",name=\"{}\"}}[1m]))", name)),
+            panel(4, "Network sent", "Bps", &|name| format!("sum(rate(container_network_transmit_bytes_total{{job=\"containers\",name=\"{}\"}}[1m]))", name)),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_is_wired_to_the_project() {
+        let targets = [ScrapeTarget { service: "api".to_string(), port: 8080, path: "/metrics".to_string() }];
+        let ports = StackPorts { prometheus: 9091, grafana: 3001, jaeger: vec![(16686, 16686), (4318, 4318)] };
+        let sidecars = sidecars("shop", &["postgres".to_string(), "redis".to_string()], &targets, &ports);
+        let names: Vec<&str> = sidecars.iter().map(|sidecar| sidecar.name.as_str()).collect();
+        assert_eq!(names, SIDECARS);
+
+        let prometheus = &sidecars[1];
+        assert_eq!(prometheus.ports, vec![(PROMETHEUS_PORT, 9091)]);
+        assert!(prometheus.files[0].content.contains("shop_cadvisor:8080"));
+        assert!(prometheus.files[0].content.contains("regex: \"shop_.*\""));
+        assert!(prometheus.files[0].content.ends_with("  - job_name: api\n    metrics_path: /metrics\n    static_configs:\n      - targets: [\"shop_api:8080\"]\n"));
+        assert!(sidecars[2].files[0].content.contains("url: http://shop_jaeger:16686"));
+
+        let own_jaeger = StackPorts { jaeger: Vec::new(), ..ports };
+        assert_eq!(super::sidecars("shop", &[], &[], &own_jaeger).len(), 3);
+
+        let dashboard = dashboard("shop", &["postgres".to_string(), "redis".to_string()]);
+        assert_eq!(dashboard["panels"][1]["targets"][0]["expr"], "sum(container_memory_working_set_bytes{job=\"containers\",name=\"shop_postgres\"})");
+        assert_eq!(dashboard["panels"][3]["gridPos"]["y"], 8);
+    }
+}
@@ -1,12 +1,21 @@
 /// PostgreSQL service configuration and helpers
-
 pub struct PostgresService;
 
 impl PostgresService {
+    /// Whether a service name runs PostgreSQL, including the TimescaleDB flavor
+    pub fn handles(service: &str) -> bool {
+        matches!(service, "postgres" | "postgresql" | "timescaledb")
+    }
+
     pub fn default_image(version: &str) -> String {
         format!("postgres:{}", version)
     }
 
+    /// PostgreSQL with the TimescaleDB extension, tagged like `latest-pg16`
+    pub fn timescaledb_image(version: &str) -> String {
+        format!("timescale/timescaledb:{}", version)
+    }
+
     pub fn default_port() -> u16 {
         5432
     }
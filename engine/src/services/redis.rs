@@ -1,5 +1,4 @@
 /// Redis service configuration and helpers
-
 pub struct RedisService;
 
 impl RedisService {
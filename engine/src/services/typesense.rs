@@ -0,0 +1,53 @@
+/// Typesense service configuration and helpers
+pub struct TypesenseService;
+
+/// Environment variable holding the admin API key, in the container and the credential store
+pub const KEY_VAR: &str = "TYPESENSE_API_KEY";
+
+impl TypesenseService {
+    pub fn default_image(version: &str) -> String {
+        format!("typesense/typesense:{}", version)
+    }
+
+    pub fn default_port() -> u16 {
+        8108
+    }
+
+    /// The data dir must exist in the image, so it defaults to /tmp; mount a
+    /// volume and set TYPESENSE_DATA_DIR in zero.yml to keep indexes
+    pub fn default_env_vars(api_key: &str) -> Vec<String> {
+        vec![
+            format!("{}={}", KEY_VAR, api_key),
+            "TYPESENSE_DATA_DIR=/tmp".to_string(),
+            "TYPESENSE_ENABLE_CORS=true".to_string(),
+        ]
+    }
+
+    /// Variables the app's Typesense client is configured from
+    pub fn app_env_vars(api_key: &str) -> Vec<(String, String)> {
+        vec![(KEY_VAR.to_string(), api_key.to_string())]
+    }
+
+    pub fn connection_string(host: &str, port: u16) -> String {
+        format!("http://{}:{}", host, port)
+    }
+
+    pub fn health_check_command() -> Vec<String> {
+        vec![
+            "curl".to_string(),
+            "-f".to_string(),
+            format!("http://localhost:{}/health", Self::default_port()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_env() {
+        assert!(TypesenseService::default_env_vars("k3y").contains(&"TYPESENSE_API_KEY=k3y".to_string()));
+        assert_eq!(TypesenseService::app_env_vars("k3y"), vec![("TYPESENSE_API_KEY".to_string(), "k3y".to_string())]);
+    }
+}
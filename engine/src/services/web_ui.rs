@@ -0,0 +1,167 @@
+//! Browser UIs of services, and the admin tools `admin_ui: true` adds to databases
+//!
+//! Admin tools run as sidecars named `{service}-admin`, connect to the
+//! database by its container name on the project network and are configured
+//! with the credentials the database container was created with.
+
+use std::collections::HashMap;
+
+use super::PostgresService;
+use crate::orchestrator::{Sidecar, SidecarFile};
+
+/// User id pgAdmin runs as; its password file must belong to it
+const PGADMIN_UID: u32 = 5050;
+
+/// Web UI served by a service or sidecar: (container port, title)
+pub fn web_ui(service: &str) -> Option<(u16, &'static str)> {
+    let ui = match service {
+        "minio" => (super::MinioService::console_port(), "MinIO console"),
+        "rabbitmq" => (super::RabbitMqService::management_port(), "RabbitMQ management"),
+        "mailpit" | "mailhog" => (super::MailpitService::ui_port(), "Mail inbox"),
+        "jaeger" => (super::JaegerService::ui_port(), "Jaeger UI"),
+        "grafana" => (super::observability::GRAFANA_PORT, "Grafana"),
+        "prometheus" => (super::observability::PROMETHEUS_PORT, "Prometheus"),
+        "kibana" => (super::elasticsearch::DASHBOARDS_PORT, "Kibana"),
+        "keycloak" => (super::KeycloakService::default_port(), "Keycloak admin console"),
+        "opensearch-dashboards" => (super::elasticsearch::DASHBOARDS_PORT, "OpenSearch Dashboards"),
+        admin => admin.strip_suffix("-admin").and_then(admin_tool)?,
+    };
+    Some(ui)
+}
+
+/// Login of a web UI from its container's environment: (username, password)
+pub fn credentials(service: &str, env: &HashMap<String, String>) -> Option<(String, String)> {
+    let (user_key, password_key) = match service {
+        "minio" => ("MINIO_ROOT_USER", "MINIO_ROOT_PASSWORD"),
+        "rabbitmq" => ("RABBITMQ_DEFAULT_USER", "RABBITMQ_DEFAULT_PASS"),
+        "keycloak" => ("KC_BOOTSTRAP_ADMIN_USERNAME", "KC_BOOTSTRAP_ADMIN_PASSWORD"),
+        _ => return None,
+    };
+    Some((env.get(user_key)?.clone(), env.get(password_key)?.clone()))
+}
+
+/// Sidecar name of a database's admin tool
+pub fn admin_sidecar_name(service: &str) -> String {
+    format!("{}-admin", service)
+}
+
+/// Admin tool for a database: (container port, title)
+fn admin_tool(service: &str) -> Option<(u16, &'static str)> {
+    match service {
+        s if PostgresService::handles(s) => Some((80, "pgAdmin")),
+        "mysql" | "mariadb" => Some((8080, "Adminer")),
+        "redis" => Some((5540, "Redis Insight")),
+        "mongodb" | "mongo" => Some((8081, "mongo-express")),
+        _ => None,
+    }
+}
+
+/// Admin tool sidecar for a database, or `None` when there is no tool for it
+///
+/// `env` is the database container's environment, which holds its credentials.
+pub fn admin_sidecar(project: &str, service: &str, env: &HashMap<String, String>, host_port: u16) -> Option<Sidecar> {
+    let (port, _) = admin_tool(service)?;
+    let host = format!("{}_{}", project, service);
+    let get = |key: &str, default: &str| env.get(key).cloned().unwrap_or_else(|| default.to_string());
+
+    let mut sidecar = Sidecar {
+        name: admin_sidecar_name(service),
+        ports: vec![(port, host_port)],
+        ..Default::default()
+    };
+
+    match service {
+        s if PostgresService::handles(s) => {
+            let user = get("POSTGRES_USER", "postgres");
+            let database = get("POSTGRES_DB", &user);
+            let password = get("POSTGRES_PASSWORD", "");
+            let servers = serde_json::json!({
+                "Servers": {
+                    "1": {
+                        "Name": service,
+                        "Group": project,
+                        "Host": host,
+                        "Port": PostgresService::default_port(),
+                        "MaintenanceDB": database,
+                        "Username": user,
+                        "SSLMode": "prefer",
+                        "PassFile": "/pgpass",
+                    }
+                }
+            });
+
+            sidecar.image = "dpage/pgadmin4:latest".to_string();
+            sidecar.env = vec![
+                "PGADMIN_DEFAULT_EMAIL=admin@zeroconfig.dev".to_string(),
+                "PGADMIN_DEFAULT_PASSWORD=zeroconfig".to_string(),
+                // Desktop mode: no login page
+                "PGADMIN_CONFIG_SERVER_MODE=False".to_string(),
+                "PGADMIN_CONFIG_MASTER_PASSWORD_REQUIRED=False".to_string(),
+                "PGADMIN_SERVER_JSON_FILE=/pgadmin4/servers.json".to_string(),
+            ];
+            sidecar.files = vec![
+                SidecarFile::new("/pgadmin4/servers.json", servers.to_string()),
+                SidecarFile::new("/pgpass", format!("{}:{}:*:{}:{}\n", host, PostgresService::default_port(), user, password))
+                    .private(PGADMIN_UID),
+            ];
+        }
+        "mysql" | "mariadb" => {
+            sidecar.image = "adminer:latest".to_string();
+            sidecar.env = vec![format!("ADMINER_DEFAULT_SERVER={}", host)];
+        }
+        "redis" => {
+            sidecar.image = "redis/redisinsight:latest".to_string();
+            sidecar.env = vec![
+                format!("RI_REDIS_HOST={}", host),
+                "RI_REDIS_PORT=6379".to_string(),
+                format!("RI_REDIS_ALIAS={}", service),
+            ];
+        }
+        _ => {
+            let user = get("MONGO_INITDB_ROOT_USERNAME", "");
+            let password = get("MONGO_INITDB_ROOT_PASSWORD", "");
+            let credentials = if user.is_empty() { String::new() } else { format!("{}:{}@", user, password) };
+
+            sidecar.image = "mongo-express:latest".to_string();
+            sidecar.env = vec![
+                format!("ME_CONFIG_MONGODB_URL=mongodb://{}{}:27017/?authSource=admin", credentials, host),
+                "ME_CONFIG_BASICAUTH=false".to_string(),
+                "ME_CONFIG_BASICAUTH_ENABLED=false".to_string(),
+            ];
+        }
+    }
+
+    Some(sidecar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pgadmin_is_preconfigured() {
+        let env = HashMap::from([
+            ("POSTGRES_USER".to_string(), "zeroconfig".to_string()),
+            ("POSTGRES_PASSWORD".to_string(), "hunter2".to_string()),
+        ]);
+        let sidecar = admin_sidecar("shop", "postgres", &env, 5433).unwrap();
+
+        assert_eq!(sidecar.name, "postgres-admin");
+        assert_eq!(sidecar.ports, vec![(80, 5433)]);
+        assert!(sidecar.files[0].content.contains("\"Host\":\"shop_postgres\""));
+        assert_eq!(sidecar.files[1].content, "shop_postgres:5432:*:zeroconfig:hunter2\n");
+        assert_eq!(sidecar.files[1].mode, 0o600);
+
+        assert!(admin_sidecar("shop", "kafka", &env, 5433).is_none());
+        assert_eq!(web_ui("redis-admin"), Some((5540, "Redis Insight")));
+        assert_eq!(web_ui("minio"), Some((9001, "MinIO console")));
+        assert_eq!(web_ui("postgres"), None);
+
+        let env = HashMap::from([
+            ("RABBITMQ_DEFAULT_USER".to_string(), "zeroconfig".to_string()),
+            ("RABBITMQ_DEFAULT_PASS".to_string(), "hunter2".to_string()),
+        ]);
+        assert_eq!(credentials("rabbitmq", &env), Some(("zeroconfig".to_string(), "hunter2".to_string())));
+        assert_eq!(credentials("postgres-admin", &env), None);
+    }
+}
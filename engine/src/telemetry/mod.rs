@@ -0,0 +1,86 @@
+//! OpenTelemetry export of the engine's spans
+//!
+//! With `ZERO_OTEL_ENDPOINT` set to an OTLP/HTTP collector such as
+//! `http://localhost:4318`, the spans around language provisioning, hooks,
+//! pulls, builds, container creation and start, and health waits are exported,
+//! so a slow `zero up` can be read as a trace in Jaeger, Tempo or any OTLP backend.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Environment variable naming the OTLP/HTTP collector to export to
+pub const ENDPOINT_ENV: &str = "ZERO_OTEL_ENDPOINT";
+
+/// Service name the spans are reported under
+pub const SERVICE_NAME: &str = "zeroconfig";
+
+/// Exports spans while alive and flushes the remaining ones when dropped
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    /// Exporter for the collector in `ZERO_OTEL_ENDPOINT`, if set
+    pub fn from_env() -> Result<Option<Self>> {
+        let Some(endpoint) = std::env::var(ENDPOINT_ENV).ok().filter(|endpoint| !endpoint.is_empty()) else {
+            return Ok(None);
+        };
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(traces_endpoint(&endpoint))
+            .build()
+            .with_context(|| format!("Invalid {}: {}", ENDPOINT_ENV, endpoint))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+            .build();
+        Ok(Some(Self { provider }))
+    }
+
+    /// Layer exporting the engine's own spans, leaving out those of the libraries it uses
+    pub fn layer<S>(&self) -> impl Layer<S>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer()
+            .with_tracer(self.provider.tracer(SERVICE_NAME))
+            .with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), tracing::Level::INFO))
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to export traces: {}", e);
+        }
+    }
+}
+
+/// URL spans are posted to: the collector's base URL, as `OTEL_EXPORTER_OTLP_ENDPOINT` takes it, plus `/v1/traces`
+fn traces_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traces_endpoint() {
+        assert_eq!(traces_endpoint("http://localhost:4318"), "http://localhost:4318/v1/traces");
+        assert_eq!(traces_endpoint("http://collector:4318/"), "http://collector:4318/v1/traces");
+        assert_eq!(traces_endpoint("https://otlp.example.com/v1/traces"), "https://otlp.example.com/v1/traces");
+    }
+}
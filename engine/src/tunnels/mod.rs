@@ -0,0 +1,365 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tracing::{debug, info, warn};
+
+/// How long to wait for a tunnel client to report its public URL
+const URL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Supported tunneling backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunnelProvider {
+    Cloudflared,
+    Ngrok,
+    Bore,
+}
+
+impl TunnelProvider {
+    /// All providers in order of preference
+    pub fn all() -> &'static [TunnelProvider] {
+        &[Self::Cloudflared, Self::Ngrok, Self::Bore]
+    }
+
+    /// Parse a provider from its CLI name
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "cloudflared" | "cloudflare" => Ok(Self::Cloudflared),
+            "ngrok" => Ok(Self::Ngrok),
+            "bore" => Ok(Self::Bore),
+            _ => Err(anyhow!("Unsupported tunnel provider: {}. Supported: cloudflared, ngrok, bore", name)),
+        }
+    }
+
+    /// Get the command name for this provider
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::Cloudflared => "cloudflared",
+            Self::Ngrok => "ngrok",
+            Self::Bore => "bore",
+        }
+    }
+
+    /// Get provider name as string
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cloudflared => "Cloudflare Tunnel",
+            Self::Ngrok => "ngrok",
+            Self::Bore => "bore",
+        }
+    }
+
+    /// Get the arguments that expose a local port
+    pub fn tunnel_args(&self, port: u16) -> Vec<String> {
+        match self {
+            Self::Cloudflared => vec![
+                "tunnel".to_string(),
+                "--no-autoupdate".to_string(),
+                "--url".to_string(),
+                format!("http://localhost:{}", port),
+            ],
+            Self::Ngrok => vec![
+                "http".to_string(),
+                port.to_string(),
+                "--log".to_string(),
+                "stdout".to_string(),
+                "--log-format".to_string(),
+                "logfmt".to_string(),
+            ],
+            Self::Bore => vec![
+                "local".to_string(),
+                port.to_string(),
+                "--to".to_string(),
+                "bore.pub".to_string(),
+            ],
+        }
+    }
+
+    /// Get install instructions for this provider
+    pub fn install_hint(&self) -> &'static str {
+        match self {
+            Self::Cloudflared => "Visit https://developers.cloudflare.com/cloudflare-one/connections/connect-networks/downloads/ to install cloudflared",
+            Self::Ngrok => "Visit https://ngrok.com/download to install ngrok",
+            Self::Bore => "Run: cargo install bore-cli",
+        }
+    }
+
+    /// Check if this provider's client is installed
+    pub fn is_installed(&self) -> bool {
+        std::process::Command::new(self.command())
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    /// Detect the first installed provider
+    pub fn detect() -> Result<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|provider| provider.is_installed())
+            .ok_or_else(|| anyhow!("No tunnel client found. Install cloudflared, ngrok, or bore"))
+    }
+
+    /// Extract the public URL from a line of client output
+    pub fn extract_public_url(&self, line: &str) -> Option<String> {
+        match self {
+            Self::Cloudflared => find_url(line, "https://").filter(|url| url.contains("trycloudflare.com")),
+            Self::Ngrok => line
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("url="))
+                .map(|url| url.trim_matches('"').to_string()),
+            Self::Bore => line
+                .split("listening at ")
+                .nth(1)
+                .and_then(|rest| rest.split_whitespace().next())
+                .map(|addr| format!("tcp://{}", addr)),
+        }
+    }
+}
+
+/// Find the first URL with the given scheme in a line
+fn find_url(line: &str, scheme: &str) -> Option<String> {
+    let start = line.find(scheme)?;
+    let url: String = line[start..]
+        .chars()
+        .take_while(|c| !c.is_whitespace() && *c != '|' && *c != '"')
+        .collect();
+    Some(url)
+}
+
+/// A tunnel left running in the background by `zero share`, as kept in the project state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedTunnel {
+    /// Command of the tunnel client
+    pub provider: String,
+    pub public_url: String,
+    pub local_port: u16,
+    pub pid: u32,
+    pub started: DateTime<Utc>,
+}
+
+impl SharedTunnel {
+    /// Whether the tunnel client is still running, rather than another process given its pid
+    pub fn is_running(&self) -> bool {
+        self.process(|process| process.is_some())
+    }
+
+    /// Stop the tunnel client; one that already exited counts as stopped
+    pub fn stop(&self) -> Result<()> {
+        let stopped = self.process(|process| process.is_none_or(|process| process.kill()));
+        anyhow::ensure!(stopped, "Failed to stop {} (pid {})", self.provider, self.pid);
+        info!("Closed tunnel {}", self.public_url);
+        Ok(())
+    }
+
+    fn process<T>(&self, f: impl FnOnce(Option<&sysinfo::Process>) -> T) -> T {
+        let pid = sysinfo::Pid::from_u32(self.pid);
+        let mut system = sysinfo::System::new();
+        system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]));
+        let process = system.process(pid)
+            .filter(|process| process.name().to_string_lossy().starts_with(self.provider.as_str()));
+        f(process)
+    }
+}
+
+/// A running tunnel exposing a local port behind a public URL
+pub struct Tunnel {
+    provider: TunnelProvider,
+    local_port: u16,
+    public_url: String,
+    child: Child,
+}
+
+impl Tunnel {
+    /// Start a tunnel to a local port and wait for its public URL
+    pub async fn open(provider: TunnelProvider, local_port: u16) -> Result<Self> {
+        info!("Opening {} tunnel to port {}", provider.name(), local_port);
+
+        let mut child = Command::new(provider.command())
+            .args(provider.tunnel_args(local_port))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start {}. {}", provider.command(), provider.install_hint()))?;
+
+        let stdout = child.stdout.take().context("Tunnel client has no stdout")?;
+        let stderr = child.stderr.take().context("Tunnel client has no stderr")?;
+
+        // cloudflared reports on stderr, ngrok and bore on stdout. Both pipes are
+        // drained until the client exits; closing them would kill it with SIGPIPE
+        // on its next log line.
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for reader in [
+            Box::new(stdout) as Box<dyn tokio::io::AsyncRead + Unpin + Send>,
+            Box::new(stderr),
+        ] {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    debug!("{}: {}", provider.command(), line);
+                    // Nobody listens once the URL is found
+                    let _ = tx.send(line);
+                }
+            });
+        }
+        drop(tx);
+
+        let public_url = tokio::time::timeout(URL_TIMEOUT, async {
+            while let Some(line) = rx.recv().await {
+                if let Some(url) = provider.extract_public_url(&line) {
+                    return Some(url);
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten();
+
+        match public_url {
+            Some(public_url) => {
+                info!("Tunnel ready: {} -> localhost:{}", public_url, local_port);
+                Ok(Self {
+                    provider,
+                    local_port,
+                    public_url,
+                    child,
+                })
+            }
+            None => {
+                let _ = child.kill().await;
+                anyhow::bail!("{} did not report a public URL within {}s", provider.name(), URL_TIMEOUT.as_secs())
+            }
+        }
+    }
+
+    /// Start a tunnel that outlives this process, its client logging to a file, and wait for its public URL
+    pub async fn open_detached(provider: TunnelProvider, local_port: u16, log_path: &Path) -> Result<SharedTunnel> {
+        info!("Opening {} tunnel to port {} in the background", provider.name(), local_port);
+
+        if let Some(dir) = log_path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let log = std::fs::File::create(log_path).with_context(|| format!("Failed to create {}", log_path.display()))?;
+        let mut command = std::process::Command::new(provider.command());
+        command.args(provider.tunnel_args(local_port))
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log);
+        // Its own process group keeps Ctrl+C in this terminal from reaching it later
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command.spawn()
+            .with_context(|| format!("Failed to start {}. {}", provider.command(), provider.install_hint()))?;
+
+        let deadline = Instant::now() + URL_TIMEOUT;
+        loop {
+            let output = std::fs::read_to_string(log_path).unwrap_or_default();
+            if let Some(public_url) = output.lines().find_map(|line| provider.extract_public_url(line)) {
+                info!("Tunnel ready: {} -> localhost:{}", public_url, local_port);
+                return Ok(SharedTunnel {
+                    provider: provider.command().to_string(),
+                    public_url,
+                    local_port,
+                    pid: child.id(),
+                    started: Utc::now(),
+                });
+            }
+            if let Some(status) = child.try_wait()? {
+                anyhow::bail!("{} exited with {} before reporting a public URL; see {}", provider.name(), status, log_path.display());
+            }
+            if Instant::now() > deadline {
+                let _ = child.kill();
+                anyhow::bail!("{} did not report a public URL within {}s", provider.name(), URL_TIMEOUT.as_secs());
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// Public URL of the tunnel
+    pub fn public_url(&self) -> &str {
+        &self.public_url
+    }
+
+    /// Local port being exposed
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    /// Provider backing this tunnel
+    pub fn provider(&self) -> TunnelProvider {
+        self.provider
+    }
+
+    /// Tear down the tunnel
+    pub async fn close(mut self) -> Result<()> {
+        if let Err(e) = self.child.kill().await {
+            warn!("Failed to stop {} tunnel: {}", self.provider.name(), e);
+        }
+        info!("Closed tunnel {}", self.public_url);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_cloudflared_url() {
+        let line = "2024-01-01T00:00:00Z INF |  https://quiet-river-1234.trycloudflare.com                                   |";
+        assert_eq!(
+            TunnelProvider::Cloudflared.extract_public_url(line),
+            Some("https://quiet-river-1234.trycloudflare.com".to_string())
+        );
+        assert_eq!(TunnelProvider::Cloudflared.extract_public_url("INF Starting tunnel"), None);
+    }
+
+    #[test]
+    fn test_extract_ngrok_url() {
+        let line = "t=2024-01-01 lvl=info msg=\"started tunnel\" obj=tunnels name=command_line addr=http://localhost:3000 url=https://abcd.ngrok-free.app";
+        assert_eq!(
+            TunnelProvider::Ngrok.extract_public_url(line),
+            Some("https://abcd.ngrok-free.app".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_bore_url() {
+        let line = "2024-01-01T00:00:00Z  INFO bore_cli::client: listening at bore.pub:41235";
+        assert_eq!(
+            TunnelProvider::Bore.extract_public_url(line),
+            Some("tcp://bore.pub:41235".to_string())
+        );
+    }
+
+    #[test]
+    fn test_provider_from_name() {
+        assert_eq!(TunnelProvider::from_name("ngrok").unwrap(), TunnelProvider::Ngrok);
+        assert!(TunnelProvider::from_name("localtunnel").is_err());
+    }
+
+    #[test]
+    fn test_shared_tunnel_only_matches_its_client() {
+        let tunnel = SharedTunnel {
+            provider: "cloudflared".to_string(),
+            public_url: "https://quiet-river-1234.trycloudflare.com".to_string(),
+            local_port: 3000,
+            pid: std::process::id(),
+            started: Utc::now(),
+        };
+        // Our own pid belongs to the test binary, not to a tunnel client
+        assert!(!tunnel.is_running());
+        tunnel.stop().unwrap();
+    }
+}
@@ -76,12 +76,7 @@ impl InputValidator {
 
     /// Find next available port starting from a given port
     pub fn find_available_port(start_port: u16) -> Option<u16> {
-        for port in start_port..65535 {
-            if Self::is_port_available(port) {
-                return Some(port);
-            }
-        }
-        None
+        (start_port..65535).find(|&port| Self::is_port_available(port))
     }
 }
 
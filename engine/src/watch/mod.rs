@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Paths that are never interesting to watch
+pub const DEFAULT_IGNORES: &[&str] = &[
+    "**/.git/**",
+    "**/target/**",
+    "**/node_modules/**",
+    "**/.zero/**",
+    "**/backups/**",
+    "**/__pycache__/**",
+    "**/*.swp",
+    "**/*~",
+];
+
+/// Default quiet period before a burst of changes is reported
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Debounced, filtered file watcher shared by `zero watch`, dev mode and config watching
+pub struct FileWatcher {
+    // Kept alive for as long as events should be delivered
+    _watcher: RecommendedWatcher,
+    rx: mpsc::UnboundedReceiver<PathBuf>,
+    ignore: GlobSet,
+    debounce: Duration,
+}
+
+impl FileWatcher {
+    /// Watch the given paths recursively, skipping `DEFAULT_IGNORES` and extra ignore globs
+    pub fn new(paths: &[PathBuf], ignore: &[String]) -> Result<Self> {
+        Self::with_mode(paths, ignore, RecursiveMode::Recursive)
+    }
+
+    /// Watch only the direct children of the given directories
+    pub fn non_recursive(paths: &[PathBuf], ignore: &[String]) -> Result<Self> {
+        Self::with_mode(paths, ignore, RecursiveMode::NonRecursive)
+    }
+
+    fn with_mode(paths: &[PathBuf], ignore: &[String], mode: RecursiveMode) -> Result<Self> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+            Err(e) => warn!("File watch error: {}", e),
+        })
+        .context("Failed to create file watcher")?;
+
+        for path in paths {
+            watcher
+                .watch(path, mode)
+                .with_context(|| format!("Failed to watch {}", path.display()))?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            rx,
+            ignore: build_ignore_set(ignore)?,
+            debounce: DEFAULT_DEBOUNCE,
+        })
+    }
+
+    /// Change the quiet period used to group bursts of changes
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Check whether a path is excluded by the ignore patterns
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignore.is_match(path)
+    }
+
+    /// Wait for the next burst of changes and return the affected paths
+    ///
+    /// Returns `None` once the underlying watcher has shut down.
+    pub async fn next_change(&mut self) -> Option<Vec<PathBuf>> {
+        let mut changed = Vec::new();
+
+        // Wait for the first relevant event
+        loop {
+            let path = self.rx.recv().await?;
+            if !self.is_ignored(&path) {
+                changed.push(path);
+                break;
+            }
+        }
+
+        // Collect everything else until the quiet period elapses
+        while let Ok(Some(path)) = tokio::time::timeout(self.debounce, self.rx.recv()).await {
+            if !self.is_ignored(&path) && !changed.contains(&path) {
+                changed.push(path);
+            }
+        }
+
+        debug!("Detected {} changed path(s)", changed.len());
+        Some(changed)
+    }
+
+    /// Discard any events that are already queued
+    pub fn drain(&mut self) {
+        while self.rx.try_recv().is_ok() {}
+    }
+}
+
+/// Check whether a path is a project config file (`zero.yml`, `zero.yaml` or an override like `zero.override.yml`)
+pub fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with("zero.") && (name.ends_with(".yml") || name.ends_with(".yaml")))
+        .unwrap_or(false)
+}
+
+fn build_ignore_set(extra: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in DEFAULT_IGNORES.iter().copied().chain(extra.iter().map(String::as_str)) {
+        // Bare names like "dist" ignore that directory anywhere in the tree
+        let pattern = if pattern.contains('/') || pattern.contains('*') {
+            pattern.to_string()
+        } else {
+            format!("**/{}/**", pattern)
+        };
+        builder.add(Glob::new(&pattern).with_context(|| format!("Invalid ignore pattern: {}", pattern))?);
+    }
+
+    builder.build().context("Failed to build ignore patterns")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_patterns() {
+        let set = build_ignore_set(&["dist".to_string(), "*.log".to_string()]).unwrap();
+        assert!(set.is_match("/project/.git/HEAD"));
+        assert!(set.is_match("/project/node_modules/react/index.js"));
+        assert!(set.is_match("/project/dist/bundle.js"));
+        assert!(set.is_match("/project/server.log"));
+        assert!(!set.is_match("/project/src/main.rs"));
+    }
+
+    #[test]
+    fn test_is_config_file() {
+        assert!(is_config_file(Path::new("/project/zero.yml")));
+        assert!(is_config_file(Path::new("/project/zero.override.yml")));
+        assert!(is_config_file(Path::new("zero.yaml")));
+        assert!(!is_config_file(Path::new("/project/docker-compose.yml")));
+        assert!(!is_config_file(Path::new("/project/zero.yml.swp")));
+    }
+
+    #[tokio::test]
+    async fn test_detects_file_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = FileWatcher::new(&[dir.path().to_path_buf()], &[])
+            .unwrap()
+            .with_debounce(Duration::from_millis(50));
+
+        std::fs::write(dir.path().join("app.js"), "console.log(1)").unwrap();
+
+        let changed = tokio::time::timeout(Duration::from_secs(5), watcher.next_change())
+            .await
+            .expect("timed out waiting for change")
+            .unwrap();
+        assert!(changed.iter().any(|p| p.ends_with("app.js")));
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use zeroconfig::config::ZeroConfig;
+use zeroconfig::core::Engine;
+
+/// Event emitted when a project's zero.yml changes; its payload carries `project_path`
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+pub type SharedEngine = Arc<RwLock<Engine>>;
+
+/// Caches one Engine (and Docker connection) per project path
+///
+/// Engines are created on first use and dropped when the project's
+/// configuration changes, so the next command rebuilds them from disk.
+#[derive(Default)]
+pub struct EngineManager {
+    engines: Cache<RwLock<Engine>>,
+    /// Serializes starts and stops per project
+    updates: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl EngineManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the cached engine for a project, creating it if needed
+    pub async fn get(&self, project_path: &str) -> Result<SharedEngine, String> {
+        let create = async { create_engine(project_path).await.map(RwLock::new) };
+        self.engines.get_or_try_insert(cache_key(project_path), create).await
+    }
+
+    /// Run a start or stop on an engine of its own, which then replaces the cached one
+    ///
+    /// Commands reading the cached engine keep working meanwhile instead of
+    /// waiting for a start that may pull images for minutes. Updates of one
+    /// project still run one at a time.
+    pub async fn update<T>(
+        &self,
+        project_path: &str,
+        operation: impl AsyncFnOnce(&mut Engine) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let key = cache_key(project_path);
+        let lock = self.updates.lock().await.entry(key.clone()).or_default().clone();
+        let _update = lock.lock().await;
+
+        let mut engine = create_engine(project_path).await?;
+        let result = operation(&mut engine).await;
+        // Keep the ports and state the operation recorded
+        self.engines.insert(key, RwLock::new(engine)).await;
+        result
+    }
+
+    /// Engines created so far, keyed by normalized project path
+    pub async fn cached(&self) -> Vec<(String, SharedEngine)> {
+        self.engines.entries().await
+    }
+
+    /// Drop the cached engine for a project
+    pub async fn invalidate(&self, project_path: &str) {
+        self.engines.remove(&cache_key(project_path)).await;
+    }
+}
+
+/// Values created on first use and shared by key
+struct Cache<T> {
+    entries: Mutex<HashMap<String, Arc<T>>>,
+}
+
+impl<T> Default for Cache<T> {
+    fn default() -> Self {
+        Self { entries: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl<T> Cache<T> {
+    /// The cached value, or the one `create` makes
+    ///
+    /// The map is not locked while creating, so a slow creation never blocks
+    /// other keys; when two callers race, the first value inserted wins.
+    async fn get_or_try_insert(&self, key: String, create: impl Future<Output = Result<T, String>>) -> Result<Arc<T>, String> {
+        if let Some(value) = self.entries.lock().await.get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = Arc::new(create.await?);
+        Ok(self.entries.lock().await.entry(key).or_insert(value).clone())
+    }
+
+    async fn insert(&self, key: String, value: T) {
+        self.entries.lock().await.insert(key, Arc::new(value));
+    }
+
+    async fn remove(&self, key: &str) {
+        self.entries.lock().await.remove(key);
+    }
+
+    async fn entries(&self) -> Vec<(String, Arc<T>)> {
+        self.entries.lock().await
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+/// Discover, validate and connect an engine for a project
+async fn create_engine(project_path: &str) -> Result<Engine, String> {
+    let config = ZeroConfig::discover_in(project_path)
+        .map_err(|e| format!("Failed to discover config: {}", e))?
+        .ok_or_else(|| "No zero.yml found".to_string())?;
+
+    config.validate().map_err(|e| format!("Invalid config: {}", e))?;
+
+    let project_name = config.metadata.name
+        .clone()
+        .unwrap_or_else(|| "zeroconfig-project".to_string());
+
+    Engine::new(project_name, config).await
+        .map_err(|e| format!("Failed to initialize engine: {}", e))
+}
+
+/// Normalize a project path so different spellings share a cache entry
+pub fn cache_key(project_path: &str) -> String {
+    std::fs::canonicalize(project_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| project_path.replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_creates_each_key_once() {
+        let cache = Cache::default();
+        let first = cache.get_or_try_insert("a".to_string(), async { Ok(1) }).await.unwrap();
+        let second = cache.get_or_try_insert("a".to_string(), async { panic!("created twice") }).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        assert!(cache.get_or_try_insert("b".to_string(), async { Err("no zero.yml".to_string()) }).await.is_err());
+        assert_eq!(*cache.get_or_try_insert("b".to_string(), async { Ok(2) }).await.unwrap(), 2);
+
+        cache.remove("a").await;
+        assert_eq!(*cache.get_or_try_insert("a".to_string(), async { Ok(3) }).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_unlocked_while_creating() {
+        let cache = Cache::default();
+        // A racing caller inserts while this one is still creating; its value wins
+        let value = cache.get_or_try_insert("a".to_string(), async {
+            cache.insert("a".to_string(), 1).await;
+            Ok(2)
+        }).await.unwrap();
+        assert_eq!(*value, 1);
+        assert_eq!(cache.entries().await.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_key_normalizes_existing_paths() {
+        let dir = std::env::current_dir().unwrap();
+        assert_eq!(cache_key("."), cache_key(&dir.to_string_lossy()));
+        assert_eq!(cache_key("C:\\missing\\project"), "C:/missing/project");
+    }
+}
@@ -0,0 +1,168 @@
+use futures::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+use zeroconfig::orchestrator::{ExecControl, ExecSession};
+
+/// Event carrying terminal output: `{ session_id, data }`
+pub const OUTPUT_EVENT: &str = "exec-output";
+
+/// Event emitted once a session's command exits: `{ session_id, exit_code }`
+pub const EXIT_EVENT: &str = "exec-exit";
+
+/// Terminal the frontend asks for when opening a session
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecSessionRequest {
+    pub project_path: String,
+    pub service_name: String,
+    /// Shell to run, `sh` when unset
+    pub shell: Option<String>,
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+}
+
+type SessionInput = Arc<Mutex<Pin<Box<dyn AsyncWrite + Send>>>>;
+
+struct Session {
+    input: SessionInput,
+    control: ExecControl,
+    pump: tokio::task::AbortHandle,
+}
+
+/// Interactive exec sessions rendered by the in-app terminal, keyed by session id
+#[derive(Default)]
+pub struct ExecSessionManager {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl ExecSessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start forwarding a session's output as events and return its id
+    pub async fn register(&self, app: tauri::AppHandle, session: ExecSession) -> String {
+        let ExecSession { mut output, input, control } = session;
+        let session_id = control.id().to_string();
+
+        // Keep the map locked until the session is inserted so a command that
+        // exits immediately cannot be removed before it was registered
+        let mut sessions = self.sessions.lock().await;
+
+        let pump_id = session_id.clone();
+        let pump_control = control.clone();
+        let pump = tokio::spawn(async move {
+            // Hold back partial UTF-8 sequences until the rest arrives
+            let mut pending = Vec::new();
+            while let Some(Ok(chunk)) = output.next().await {
+                pending.extend_from_slice(&chunk);
+                let data = take_utf8(&mut pending);
+                if !data.is_empty() {
+                    let _ = app.emit(OUTPUT_EVENT, serde_json::json!({
+                        "session_id": pump_id,
+                        "data": data,
+                    }));
+                }
+            }
+
+            let exit_code = pump_control.exit_code().await.ok().flatten();
+            let _ = app.emit(EXIT_EVENT, serde_json::json!({
+                "session_id": pump_id,
+                "exit_code": exit_code,
+            }));
+            app.state::<ExecSessionManager>().sessions.lock().await.remove(&pump_id);
+        });
+
+        sessions.insert(session_id.clone(), Session {
+            input: Arc::new(Mutex::new(input)),
+            control,
+            pump: pump.abort_handle(),
+        });
+
+        session_id
+    }
+
+    /// Send keystrokes to a session
+    pub async fn write(&self, session_id: &str, data: &[u8]) -> Result<(), String> {
+        let input = self.sessions.lock().await
+            .get(session_id)
+            .map(|session| session.input.clone())
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+        let mut input = input.lock().await;
+        input.write_all(data).await
+            .map_err(|e| format!("Failed to write to session: {}", e))?;
+        input.flush().await
+            .map_err(|e| format!("Failed to write to session: {}", e))
+    }
+
+    /// Resize a session's terminal
+    pub async fn resize(&self, session_id: &str, cols: u16, rows: u16) -> Result<(), String> {
+        let control = self.sessions.lock().await
+            .get(session_id)
+            .map(|session| session.control.clone())
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+        control.resize(cols, rows).await
+            .map_err(|e| format!("Failed to resize session: {}", e))
+    }
+
+    /// Stop forwarding output and close the session's stdin
+    pub async fn close(&self, session_id: &str) -> Result<(), String> {
+        let session = self.sessions.lock().await
+            .remove(session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+        session.pump.abort();
+        let _ = session.input.lock().await.shutdown().await;
+        Ok(())
+    }
+}
+
+/// Drain the longest valid UTF-8 prefix from a buffer
+fn take_utf8(buffer: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(buffer) {
+        Ok(_) => buffer.len(),
+        // An incomplete sequence at the end waits for the next chunk
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        // Invalid bytes are replaced rather than stalling the terminal
+        Err(_) => buffer.len(),
+    };
+
+    let text = String::from_utf8_lossy(&buffer[..valid]).to_string();
+    buffer.drain(..valid);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_utf8_holds_back_split_sequences() {
+        // "é" is 0xC3 0xA9 and "€" is 0xE2 0x82 0xAC
+        let mut buffer = b"caf\xC3".to_vec();
+        assert_eq!(take_utf8(&mut buffer), "caf");
+        assert_eq!(buffer, vec![0xC3]);
+
+        buffer.extend_from_slice(&[0xA9, b' ', 0xE2, 0x82]);
+        assert_eq!(take_utf8(&mut buffer), "\u{e9} ");
+        assert_eq!(buffer, vec![0xE2, 0x82]);
+
+        buffer.push(0xAC);
+        assert_eq!(take_utf8(&mut buffer), "\u{20ac}");
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_take_utf8_replaces_invalid_bytes() {
+        let mut buffer = vec![b'a', 0xFF, b'b'];
+        assert_eq!(take_utf8(&mut buffer), "a\u{fffd}b");
+        assert!(buffer.is_empty());
+    }
+}
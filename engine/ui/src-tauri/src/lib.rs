@@ -1,11 +1,25 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Mutex;
-use tauri::{Emitter, State};
+use tauri::{Emitter, Listener, Manager, State};
 use futures::StreamExt;
-use zeroconfig::config::ZeroConfig;
-use zeroconfig::core::Engine;
-use zeroconfig::runtime::ContainerRuntimeManager;
+use zeroconfig::backup::{self, BackupEntry};
+use zeroconfig::config::{diagnostics, edit, Diagnostic, ZeroConfig};
+use zeroconfig::core::{ProjectState, ResolvedEnv, ServiceGraph, WebUi};
+use zeroconfig::doctor::{self, DoctorAction, DoctorCheck};
+use zeroconfig::logs::{LogFilter, LogPage, LogSearch};
+use zeroconfig::orchestrator::{KnownProject, ProjectSummary, ProjectVolume, ServiceDetails, ServiceInfo, ServiceStats, VolumeEntry};
+use zeroconfig::runtime::{installer, ContainerRuntimeManager, ProjectStack, RuntimeInfo, RuntimeManager};
+
+mod engine_manager;
+mod exec_sessions;
+mod recent_projects;
+mod stats_history;
+
+use engine_manager::EngineManager;
+use exec_sessions::{ExecSessionManager, ExecSessionRequest};
+use recent_projects::{RecentProject, RecentProjectStore};
+use stats_history::{StatsHistory, StatsSample};
 
 /// Validate shell command to prevent command injection
 fn validate_shell_command(shell: &str) -> Result<(), String> {
@@ -28,49 +42,28 @@ impl LogStreamManager {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ServiceInfo {
-    name: String,
-    image: String,
-    status: String,
-    port: Option<u16>,
-    stats: Option<ServiceStats>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ServiceStats {
-    cpu: f64,
-    memory: MemoryStats,
-    network: NetworkStats,
+struct ConfigWatchManager {
+    handles: Mutex<HashMap<String, tokio::task::AbortHandle>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MemoryStats {
-    percentage: f64,
-    usage: u64,
-    limit: u64,
+impl ConfigWatchManager {
+    fn new() -> Self {
+        Self {
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NetworkStats {
-    rx: u64,
-    tx: u64,
+struct ServiceMonitorManager {
+    handles: Mutex<HashMap<String, tokio::task::AbortHandle>>,
 }
 
-/// Helper to get initialized engine
-async fn get_engine(project_path: &str) -> Result<Engine, String> {
-    let config = ZeroConfig::discover_in(project_path)
-        .map_err(|e| format!("Failed to discover config: {}", e))?
-        .ok_or_else(|| "No zero.yml found".to_string())?;
-
-    config.validate().map_err(|e| format!("Invalid config: {}", e))?;
-
-    let project_name = config.metadata.name
-        .clone()
-        .unwrap_or_else(|| "zeroconfig-project".to_string());
-
-    Engine::new(project_name, config).await
-        .map_err(|e| format!("Failed to initialize engine: {}", e))
+impl ServiceMonitorManager {
+    fn new() -> Self {
+        Self {
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
 }
 
 #[tauri::command]
@@ -84,7 +77,7 @@ async fn init_project(project_path: String, template: Option<String>) -> Result<
     let path = std::path::Path::new(&normalized_path);
 
     if !path.exists() {
-        std::fs::create_dir_all(&path)
+        std::fs::create_dir_all(path)
             .map_err(|e| format!("Failed to create directory: {}", e))?;
     }
 
@@ -108,180 +101,269 @@ async fn init_project(project_path: String, template: Option<String>) -> Result<
 }
 
 #[tauri::command]
-async fn list_services(project_path: String) -> Result<Vec<ServiceInfo>, String> {
-    let engine = get_engine(&project_path).await?;
-    let containers = engine.list_services().await
-        .map_err(|e| format!("Failed to list services: {}", e))?;
-
-    // Try to get stats, but don't fail if we can't
-    let stats_map = engine.get_all_stats().await.ok().unwrap_or_default()
-        .into_iter()
-        .collect::<HashMap<_, _>>();
-
-    let mut services = Vec::new();
-
-    for container in containers {
-        let name = container.names.as_ref()
-            .and_then(|n| n.first())
-            .map(|n| n.trim_start_matches('/'))
-            .unwrap_or("unknown")
-            .to_string();
-        
-        let image = container.image.unwrap_or_default();
-        let status = container.status.unwrap_or_default();
-        
-        // Extract port
-        let port = container.ports.as_ref()
-            .and_then(|p| p.iter().find(|port| port.public_port.is_some()))
-            .map(|p| p.public_port.unwrap_or(0));
-
-        let stats = stats_map.get(&name).map(|stat| {
-             // Calculate CPU percentage
-            let cpu_delta = stat.cpu_stats.cpu_usage.total_usage as f64
-                - stat.precpu_stats.cpu_usage.total_usage as f64;
-            let system_delta = stat.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
-                - stat.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
-
-            let cpu_percent = if system_delta > 0.0 && cpu_delta > 0.0 {
-                let num_cpus = stat.cpu_stats.online_cpus.unwrap_or(1) as f64;
-                (cpu_delta / system_delta) * num_cpus * 100.0
-            } else {
-                0.0
-            };
-
-            // Calculate Memory percentage
-            let memory_usage = stat.memory_stats.usage.unwrap_or(0);
-            let memory_limit = stat.memory_stats.limit.unwrap_or(0);
-            let memory_percent = if memory_limit > 0 {
-                (memory_usage as f64 / memory_limit as f64) * 100.0
-            } else {
-                0.0
-            };
-
-            // Network I/O
-            let mut rx = 0;
-            let mut tx = 0;
-            if let Some(networks) = &stat.networks {
-                for net in networks.values() {
-                    rx += net.rx_bytes;
-                    tx += net.tx_bytes;
-                }
-            }
-
-            ServiceStats {
-                cpu: cpu_percent,
-                memory: MemoryStats {
-                    percentage: memory_percent,
-                    usage: memory_usage,
-                    limit: memory_limit,
-                },
-                network: NetworkStats {
-                    rx,
-                    tx,
-                },
-            }
-        });
-
-        services.push(ServiceInfo {
-            name,
-            image,
-            status,
-            port,
-            stats,
-        });
-    }
+async fn list_services(engines: State<'_, EngineManager>, project_path: String) -> Result<Vec<ServiceInfo>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.service_infos().await
+        .map_err(|e| format!("Failed to list services: {}", e))
+}
 
-    Ok(services)
+/// Check what would make starting services fail: port conflicts, missing images,
+/// disk space and memory. Without `services`, every service in zero.yml is checked.
+#[tauri::command]
+async fn preflight_start(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    services: Option<Vec<String>>,
+) -> Result<Vec<DoctorCheck>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    Ok(engine.preflight(&services.unwrap_or_default()).await)
 }
 
 #[tauri::command]
-async fn start_services(project_path: String) -> Result<String, String> {
-    let mut engine = get_engine(&project_path).await?;
-    engine.start().await.map_err(|e| format!("Failed to start services: {}", e))?;
+async fn start_services(engines: State<'_, EngineManager>, project_path: String) -> Result<String, String> {
+    engines.update(&project_path, async |engine| {
+        engine.start().await.map_err(|e| format!("Failed to start services: {}", e))
+    }).await?;
     Ok("Services started successfully".to_string())
 }
 
 #[tauri::command]
-async fn stop_services(project_path: String) -> Result<String, String> {
-    let engine = get_engine(&project_path).await?;
-    engine.stop().await.map_err(|e| format!("Failed to stop services: {}", e))?;
+async fn stop_services(engines: State<'_, EngineManager>, project_path: String) -> Result<String, String> {
+    engines.update(&project_path, async |engine| {
+        engine.stop().await.map_err(|e| format!("Failed to stop services: {}", e))
+    }).await?;
     Ok("Services stopped successfully".to_string())
 }
 
 #[tauri::command]
-async fn start_service(project_path: String, service_name: String) -> Result<String, String> {
-    let mut engine = get_engine(&project_path).await?;
-    engine.start_service(&service_name).await
-        .map_err(|e| format!("Failed to start service {}: {}", service_name, e))?;
+async fn start_service(engines: State<'_, EngineManager>, project_path: String, service_name: String) -> Result<String, String> {
+    engines.update(&project_path, async |engine| {
+        engine.start_service(&service_name).await
+            .map_err(|e| format!("Failed to start service {}: {}", service_name, e))
+    }).await?;
     Ok(format!("Service {} started", service_name))
 }
 
 #[tauri::command]
-async fn stop_service(project_path: String, service_name: String) -> Result<String, String> {
-    let engine = get_engine(&project_path).await?;
-    engine.stop_service(&service_name).await
-        .map_err(|e| format!("Failed to stop service {}: {}", service_name, e))?;
+async fn stop_service(engines: State<'_, EngineManager>, project_path: String, service_name: String) -> Result<String, String> {
+    engines.update(&project_path, async |engine| {
+        engine.stop_service(&service_name).await
+            .map_err(|e| format!("Failed to stop service {}: {}", service_name, e))
+    }).await?;
     Ok(format!("Service {} stopped", service_name))
 }
 
 #[tauri::command]
-async fn restart_service(project_path: String, service_name: String) -> Result<String, String> {
-    let engine = get_engine(&project_path).await?;
+async fn restart_service(engines: State<'_, EngineManager>, project_path: String, service_name: String) -> Result<String, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
     engine.restart_service(&service_name).await
         .map_err(|e| format!("Failed to restart service {}: {}", service_name, e))?;
     Ok(format!("Service {} restarted", service_name))
 }
 
 #[tauri::command]
-async fn get_service_logs(project_path: String, service_name: String, tail: Option<usize>) -> Result<String, String> {
-    let engine = get_engine(&project_path).await?;
-    let logs = engine.get_logs_as_string(&service_name, tail.unwrap_or(100)).await
+async fn get_service_logs(engines: State<'_, EngineManager>, project_path: String, service_name: String, tail: Option<usize>) -> Result<String, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let logs = engine.get_logs_as_string(&service_name, tail.unwrap_or(100), &LogFilter::new()).await
         .map_err(|e| format!("Failed to get logs: {}", e))?;
     Ok(logs)
 }
 
+/// Search a service's logs inside the engine and return one page of matches
+///
+/// `since`/`until` accept the same values as `zero logs --since`; pass the
+/// returned `next_cursor` back as `cursor` to load the next page.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn search_logs(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: String,
+    query: Option<String>,
+    level: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    max_results: Option<usize>,
+    cursor: Option<String>,
+) -> Result<LogPage, String> {
+    let filter = LogFilter::from_args(query.as_deref().filter(|q| !q.is_empty()), since.as_deref(), level.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    let mut search = LogSearch::new(filter, max_results.unwrap_or(500));
+    if let Some(until) = &until {
+        search = search.with_until(until).map_err(|e| e.to_string())?;
+    }
+    if let Some(cursor) = &cursor {
+        search = search.with_cursor(cursor).map_err(|e| e.to_string())?;
+    }
+
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.search_logs(&service_name, &search).await
+        .map_err(|e| format!("Failed to search logs: {}", e))
+}
+
+/// Write the selected services' logs to a file, optionally as a ZIP with one log per service
+///
+/// Returns the number of bytes written.
+#[tauri::command]
+async fn export_logs(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    services: Vec<String>,
+    file_path: String,
+    since: Option<String>,
+    until: Option<String>,
+    zip: Option<bool>,
+) -> Result<u64, String> {
+    use zeroconfig::logs::{export_logs, parse_since, LogExport};
+
+    let export = LogExport {
+        services,
+        since: since.as_deref().map(parse_since).transpose().map_err(|e| e.to_string())?.unwrap_or(0),
+        until: until.as_deref().map(parse_since).transpose().map_err(|e| e.to_string())?.unwrap_or(0),
+        zip: zip.unwrap_or(false),
+    };
+
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    export_logs(&engine, &export, std::path::Path::new(&file_path)).await
+        .map_err(|e| format!("Failed to export logs: {:#}", e))
+}
+
 // Cloud commands - reuse existing logic or implement similar to above
 #[tauri::command]
-async fn start_cloud_emulator(provider: String) -> Result<String, String> {
-    // Cloud emulator logic is in zeroconfig::cloud
-    // We can use it directly
-    use zeroconfig::cloud::CloudEmulator;
-    
-    let emulator = CloudEmulator::new(provider.clone()).await
-        .map_err(|e| format!("Failed to create emulator: {}", e))?;
-        
-    emulator.start().await
-        .map_err(|e| format!("Failed to start emulator: {}", e))?;
-        
+async fn start_cloud_emulator(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    provider: String,
+) -> Result<String, String> {
+    // Through the engine, so the emulator joins the project network and state
+    // with the services and region of the project's cloud block
+    engines.update(&project_path, async |engine| {
+        engine.start_cloud_providers(std::slice::from_ref(&provider), zeroconfig::core::cloud::READY_TIMEOUT).await
+            .map_err(|e| format!("Failed to start emulator: {}", e))
+    }).await?;
+
     Ok(format!("{} emulator started", provider))
 }
 
-#[tauri::command]
-async fn stop_cloud_emulator(provider: String) -> Result<String, String> {
-    use zeroconfig::cloud::CloudEmulator;
-    
-    let emulator = CloudEmulator::new(provider.clone()).await
+/// Emulator of a provider for a project: its containers are the project's, with
+/// the flavor, services and ports of the project's cloud block
+async fn project_emulator(
+    engines: &EngineManager,
+    project_path: &str,
+    provider: String,
+) -> Result<zeroconfig::cloud::CloudEmulator, String> {
+    let engine = engines.get(project_path).await?;
+    let engine = engine.read().await;
+    let emulator = engine.cloud_emulator(provider).await
         .map_err(|e| format!("Failed to create emulator: {}", e))?;
-        
+    Ok(emulator.with_config(engine.config().cloud.as_ref()))
+}
+
+#[tauri::command]
+async fn stop_cloud_emulator(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    provider: String,
+) -> Result<String, String> {
+    let emulator = project_emulator(&engines, &project_path, provider.clone()).await?;
+
     emulator.stop().await
         .map_err(|e| format!("Failed to stop emulator: {}", e))?;
         
     Ok(format!("{} emulator stopped", provider))
 }
 
+/// "Running" once the emulator is ready for requests, "Stopped" before
 #[tauri::command]
-async fn get_cloud_status(provider: String) -> Result<String, String> {
-    use zeroconfig::cloud::CloudEmulator;
-    
-    let emulator = CloudEmulator::new(provider.clone()).await
-        .map_err(|e| format!("Failed to create emulator: {}", e))?;
-        
+async fn get_cloud_status(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    provider: String,
+) -> Result<String, String> {
+    // Readiness depends on the services the project's cloud block asked for
+    let emulator = project_emulator(&engines, &project_path, provider).await?;
+
     let status = emulator.is_running().await
         .map_err(|e| format!("Failed to get status: {}", e))?;
         
     Ok(if status { "Running".to_string() } else { "Stopped".to_string() })
 }
 
+/// Status of the emulators of every provider in the project's cloud block
+#[tauri::command]
+async fn get_project_cloud_status(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+) -> Result<Vec<zeroconfig::cloud::CloudStatus>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.cloud_status().await
+        .map_err(|e| format!("Failed to get cloud status: {}", e))
+}
+
+/// List buckets, queues, tables and other resources inside a running emulator
+#[tauri::command]
+async fn list_cloud_resources(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    provider: String,
+) -> Result<Vec<zeroconfig::cloud::CloudResource>, String> {
+    let emulator = project_emulator(&engines, &project_path, provider).await?;
+
+    emulator.list_resources().await
+        .map_err(|e| format!("Failed to list resources: {}", e))
+}
+
+/// Stream an emulator's logs as `cloud-log-event` events
+#[tauri::command]
+async fn start_cloud_log_stream(
+    app: tauri::AppHandle,
+    state: State<'_, LogStreamManager>,
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    provider: String,
+) -> Result<(), String> {
+    // Cloud streams share the manager with service streams, under their own keys
+    let key = format!("cloud:{}", provider);
+    if let Some(handle) = state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.remove(&key) {
+        handle.abort();
+    }
+
+    let emulator = project_emulator(&engines, &project_path, provider.clone()).await?;
+    let mut stream = emulator.log_stream(true, 100).await
+        .map_err(|e| format!("Failed to start log stream: {}", e))?;
+
+    let handle = tokio::spawn(async move {
+        while let Some(Ok(line)) = stream.next().await {
+            let _ = app.emit("cloud-log-event", serde_json::json!({
+                "provider": provider,
+                "line": line
+            }));
+        }
+    });
+
+    state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.insert(key, handle.abort_handle());
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_cloud_log_stream(
+    state: State<'_, LogStreamManager>,
+    provider: String,
+) -> Result<(), String> {
+    if let Some(handle) = state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.remove(&format!("cloud:{}", provider)) {
+        handle.abort();
+    }
+    Ok(())
+}
+
 // Runtime checks
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContainerRuntimeStatus {
@@ -322,30 +404,77 @@ async fn detect_all_runtimes() -> Result<Vec<ContainerRuntimeStatus>, String> {
     Ok(statuses)
 }
 
-// Legacy checks for compatibility
+/// Run the onboarding checks (runtime, Docker API, disk space, config, languages, ports)
+///
+/// Without a project path the checks run against the home directory, so only
+/// the machine-level checks are meaningful.
 #[tauri::command]
-async fn check_docker_status() -> Result<String, String> {
-    let manager = ContainerRuntimeManager::new();
-    let status = manager.get_runtime_status(zeroconfig::runtime::ContainerRuntime::Docker).await;
-    if status.running {
-        Ok(status.version.unwrap_or_default())
-    } else {
-        Err("Docker not running".to_string())
-    }
+async fn run_doctor(app: tauri::AppHandle, project_path: Option<String>) -> Result<Vec<DoctorCheck>, String> {
+    let dir = match project_path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => app.path().home_dir().map_err(|e| format!("Failed to resolve home directory: {}", e))?,
+    };
+    Ok(doctor::run(&dir).await)
 }
 
+/// Apply the automatic fix of a doctor check, returning what was done
 #[tauri::command]
-async fn check_podman_status() -> Result<String, String> {
-    let manager = ContainerRuntimeManager::new();
-    let status = manager.get_runtime_status(zeroconfig::runtime::ContainerRuntime::Podman).await;
-    if status.running {
-        Ok(status.version.unwrap_or_default())
-    } else {
-        Err("Podman not running".to_string())
+async fn apply_doctor_fix(action: DoctorAction) -> Result<Option<String>, String> {
+    doctor::fix::apply(&action).await.map_err(|e| format!("{:#}", e))
+}
+
+/// Install a missing runtime with the machine's package or version manager
+///
+/// Emits `runtime-install-progress` with the stage (`started`, `finished` or
+/// `failed`) and `runtime-install-log` for each line of installer output, then
+/// re-checks the runtime so the detection screen can refresh.
+#[tauri::command]
+async fn install_runtime(app: tauri::AppHandle, runtime: String, version: Option<String>) -> Result<RuntimeInfo, String> {
+    let version = version.unwrap_or_else(|| "latest".to_string());
+    let emit_progress = |stage: &str, message: Option<String>| {
+        let _ = app.emit("runtime-install-progress", serde_json::json!({
+            "runtime": runtime,
+            "version": version,
+            "stage": stage,
+            "message": message,
+        }));
+    };
+
+    emit_progress("started", None);
+    let result = installer::install(&runtime, &version, |line| {
+        let _ = app.emit("runtime-install-log", serde_json::json!({
+            "runtime": runtime,
+            "line": line,
+        }));
+    }).await;
+
+    if let Err(e) = result {
+        let message = format!("Failed to install {}: {:#}", runtime, e);
+        emit_progress("failed", Some(message.clone()));
+        return Err(message);
     }
+    emit_progress("finished", None);
+
+    RuntimeManager::new()
+        .check_runtime(&runtime, &version)
+        .await
+        .map_err(|e| format!("Failed to check {}: {}", runtime, e))
+}
+
+/// Inspect a folder's manifests and suggest a template, languages and services for init
+#[tauri::command]
+fn detect_project_stack(project_path: String) -> Result<ProjectStack, String> {
+    zeroconfig::runtime::detect_project_stack(std::path::Path::new(&project_path))
+        .map_err(|e| format!("Failed to detect project stack: {:#}", e))
 }
 
 // Config commands
+/// JSON Schema for zero.yml, used by the config editor for autocomplete and hover docs
+#[tauri::command]
+fn get_config_schema() -> serde_json::Value {
+    zeroconfig::config::schema::json_schema()
+}
+
 #[tauri::command]
 async fn load_template(template_name: String) -> Result<String, String> {
     let template_str = match template_name.as_str() {
@@ -372,13 +501,27 @@ async fn list_templates() -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-async fn save_config(project_path: String, config_content: String) -> Result<String, String> {
+async fn save_config(engines: State<'_, EngineManager>, project_path: String, config_content: String) -> Result<String, String> {
     let config_path = std::path::Path::new(&project_path).join("zero.yml");
     std::fs::write(&config_path, config_content)
         .map_err(|e| format!("Failed to save configuration: {}", e))?;
+    engines.invalidate(&project_path).await;
     Ok(format!("Configuration saved to {:?}", config_path))
 }
 
+/// Set one key of zero.yml, keeping the user's comments and layout
+#[tauri::command]
+async fn set_config_value(engines: State<'_, EngineManager>, project_path: String, key: String, value: String) -> Result<String, String> {
+    let config_path = ZeroConfig::find_file_in(&project_path).ok_or("No zero.yml found")?;
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|e| format!("Failed to load configuration: {}", e))?;
+    let edited = edit::set_in(&content, &key, &value).map_err(|e| format!("{:#}", e))?;
+    std::fs::write(&config_path, edited)
+        .map_err(|e| format!("Failed to save configuration: {}", e))?;
+    engines.invalidate(&project_path).await;
+    Ok(format!("Set {} to {}", key, value))
+}
+
 #[tauri::command]
 async fn load_config(project_path: String) -> Result<String, String> {
     let config_path = std::path::Path::new(&project_path).join("zero.yml");
@@ -387,10 +530,8 @@ async fn load_config(project_path: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn validate_config(config_content: String) -> Result<String, String> {
-    ZeroConfig::from_str(&config_content)
-        .map(|_| "Configuration is valid".to_string())
-        .map_err(|e| format!("Invalid YAML: {}", e))
+async fn validate_config(config_content: String) -> Result<Vec<Diagnostic>, String> {
+    Ok(diagnostics::check(&config_content))
 }
 
 // Generators - reuse library
@@ -449,80 +590,152 @@ async fn generate_all_configs(project_path: String) -> Result<String, String> {
     Ok("All configs generated".to_string())
 }
 
+/// Nodes and edges for the project topology view
 #[tauri::command]
-async fn get_services_stats(project_path: String) -> Result<HashMap<String, ServiceStats>, String> {
-    let engine = get_engine(&project_path).await?;
-    let stats = engine.get_all_stats().await
-        .map_err(|e| format!("Failed to get stats: {}", e))?;
-
-    let mut result = HashMap::new();
-
-    for (name, stat) in stats {
-        // Calculate CPU percentage
-        let cpu_delta = stat.cpu_stats.cpu_usage.total_usage as f64
-            - stat.precpu_stats.cpu_usage.total_usage as f64;
-        let system_delta = stat.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
-            - stat.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
-
-        let cpu_percent = if system_delta > 0.0 && cpu_delta > 0.0 {
-            let num_cpus = stat.cpu_stats.online_cpus.unwrap_or(1) as f64;
-            (cpu_delta / system_delta) * num_cpus * 100.0
-        } else {
-            0.0
-        };
-
-        // Calculate Memory percentage
-        let memory_usage = stat.memory_stats.usage.unwrap_or(0);
-        let memory_limit = stat.memory_stats.limit.unwrap_or(0);
-        let memory_percent = if memory_limit > 0 {
-            (memory_usage as f64 / memory_limit as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        // Network I/O
-        let mut rx = 0;
-        let mut tx = 0;
-        if let Some(networks) = stat.networks {
-            for net in networks.values() {
-                rx += net.rx_bytes;
-                tx += net.tx_bytes;
-            }
-        }
+async fn get_service_graph(engines: State<'_, EngineManager>, project_path: String) -> Result<ServiceGraph, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.service_graph().await
+        .map_err(|e| format!("Failed to build service graph: {}", e))
+}
 
-        result.insert(name, ServiceStats {
-            cpu: cpu_percent,
-            memory: MemoryStats {
-                percentage: memory_percent,
-                usage: memory_usage,
-                limit: memory_limit,
-            },
-            network: NetworkStats {
-                rx,
-                tx,
-            },
-        });
-    }
+/// Ports, containers and connection strings recorded by the last start
+#[tauri::command]
+async fn get_project_state(engines: State<'_, EngineManager>, project_path: String) -> Result<ProjectState, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    Ok(engine.state().clone())
+}
 
-    Ok(result)
+/// Running web UIs of the project: admin tools, consoles and dashboards
+#[tauri::command]
+async fn get_web_uis(engines: State<'_, EngineManager>, project_path: String) -> Result<Vec<WebUi>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    Ok(engine.web_uis().await)
 }
 
-// Missing commands: check_minikube_status
+/// Full container details for a service, with secret env values masked
 #[tauri::command]
-async fn check_minikube_status() -> Result<String, String> {
-     let manager = ContainerRuntimeManager::new();
-    let status = manager.get_runtime_status(zeroconfig::runtime::ContainerRuntime::Minikube).await;
-    if status.running {
-        Ok(status.version.unwrap_or_default())
-    } else {
-        Err("Minikube not running".to_string())
+async fn inspect_service(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: String,
+) -> Result<ServiceDetails, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.inspect_service(&service_name).await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Environment the app and each service will receive, with secrets masked
+///
+/// Passing `reveal` asks the user to confirm in a native dialog before any
+/// secret is returned in plain text.
+#[tauri::command]
+async fn get_resolved_env(
+    app: tauri::AppHandle,
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    reveal: Option<bool>,
+) -> Result<ResolvedEnv, String> {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let mut reveal = reveal.unwrap_or(false);
+    if reveal {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        app.dialog()
+            .message("Secret values such as passwords and API keys will be shown in plain text.")
+            .title("Reveal secrets?")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom("Reveal".to_string(), "Cancel".to_string()))
+            .show(move |confirmed| {
+                let _ = tx.send(confirmed);
+            });
+        reveal = rx.await.unwrap_or(false);
     }
+
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.resolved_env(std::path::Path::new(&project_path), reveal).await
+        .map_err(|e| format!("Failed to resolve environment: {:#}", e))
+}
+
+#[tauri::command]
+async fn list_project_volumes(engines: State<'_, EngineManager>, project_path: String) -> Result<Vec<ProjectVolume>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.list_volumes().await
+        .map_err(|e| format!("Failed to list volumes: {}", e))
+}
+
+#[tauri::command]
+async fn inspect_volume(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    volume: String,
+) -> Result<serde_json::Value, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let details = engine.inspect_volume(&volume).await
+        .map_err(|e| format!("{:#}", e))?;
+    serde_json::to_value(details).map_err(|e| e.to_string())
+}
+
+/// List the files in a directory of a volume, relative to its root
+#[tauri::command]
+async fn browse_volume_path(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    volume: String,
+    path: Option<String>,
+) -> Result<Vec<VolumeEntry>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.browse_volume(&volume, path.as_deref().unwrap_or("")).await
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Delete a project volume; returns the services that were removed and need restarting
+#[tauri::command]
+async fn delete_volume(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    volume: String,
+) -> Result<Vec<String>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.delete_volume(&volume).await
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[tauri::command]
+async fn get_services_stats(engines: State<'_, EngineManager>, project_path: String) -> Result<BTreeMap<String, ServiceStats>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    engine.service_stats().await
+        .map_err(|e| format!("Failed to get stats: {}", e))
+}
+
+/// Recent stats samples for a service (keyed like `get_services_stats`)
+///
+/// `range` is in seconds and defaults to the whole 15 minute window.
+#[tauri::command]
+fn get_stats_history(
+    history: State<'_, StatsHistory>,
+    project_path: String,
+    service: String,
+    range: Option<u64>,
+) -> Vec<StatsSample> {
+    let range = range.map(std::time::Duration::from_secs).unwrap_or(stats_history::WINDOW);
+    history.range(&project_path, &service, range.min(stats_history::WINDOW))
 }
 
 #[tauri::command]
 async fn start_log_stream(
     app: tauri::AppHandle,
     state: State<'_, LogStreamManager>,
+    engines: State<'_, EngineManager>,
     project_path: String,
     service_name: String,
 ) -> Result<(), String> {
@@ -531,8 +744,9 @@ async fn start_log_stream(
         handle.abort();
     }
 
-    let engine = get_engine(&project_path).await?;
-    let mut stream = engine.stream_logs(&service_name, 100).await
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let mut stream = engine.stream_logs(&service_name, 100, LogFilter::new()).await
         .map_err(|e| format!("Failed to start log stream: {}", e))?;
 
     let service_name_clone = service_name.clone();
@@ -565,40 +779,175 @@ async fn stop_log_stream(
     Ok(())
 }
 
+/// Emit `config-changed` events whenever zero.yml or an override file changes on disk
 #[tauri::command]
-async fn open_terminal_window(service_name: String, shell: Option<String>) -> Result<(), String> {
-    // Get container ID using docker ps command directly
-    let output = std::process::Command::new("docker")
-        .args(&["ps", "--filter", &format!("name={}", service_name), "--format", "{{.ID}}"])
-        .output()
-        .map_err(|e| format!("Failed to run docker ps: {}", e))?;
-    
-    if !output.status.success() {
-        return Err("Failed to get container ID".to_string());
+async fn watch_project_config(
+    app: tauri::AppHandle,
+    state: State<'_, ConfigWatchManager>,
+    project_path: String,
+) -> Result<(), String> {
+    use zeroconfig::watch::{is_config_file, FileWatcher};
+
+    // Replace any existing watcher for this project
+    if let Some(handle) = state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.remove(&project_path) {
+        handle.abort();
     }
-    
-    let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if container_id.is_empty() {
-        return Err(format!("Container {} not found or not running", service_name));
+
+    let mut watcher = FileWatcher::non_recursive(&[std::path::PathBuf::from(&project_path)], &[])
+        .map_err(|e| format!("Failed to watch {}: {}", project_path, e))?;
+
+    let watched_path = project_path.clone();
+    let handle = tokio::spawn(async move {
+        while let Some(changed) = watcher.next_change().await {
+            let files: Vec<String> = changed
+                .iter()
+                .filter(|path| is_config_file(path))
+                .map(|path| path.display().to_string())
+                .collect();
+            if files.is_empty() {
+                continue;
+            }
+
+            let project_dir = std::path::Path::new(&watched_path);
+            let config_path = ["zero.yml", "zero.yaml"]
+                .iter()
+                .map(|name| project_dir.join(name))
+                .find(|path| path.exists());
+
+            let found: Vec<Diagnostic> = match config_path.map(std::fs::read_to_string) {
+                Some(Ok(content)) => diagnostics::check(&content),
+                Some(Err(e)) => vec![Diagnostic::error("", format!("Failed to read config: {}", e))],
+                None => vec![Diagnostic::error("", "No zero.yml found")],
+            };
+
+            let _ = app.emit(engine_manager::CONFIG_CHANGED_EVENT, serde_json::json!({
+                "project_path": watched_path,
+                "files": files,
+                "valid": !found.iter().any(Diagnostic::is_error),
+                "diagnostics": found,
+            }));
+        }
+    });
+
+    state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.insert(project_path, handle.abort_handle());
+    Ok(())
+}
+
+#[tauri::command]
+async fn unwatch_project_config(
+    state: State<'_, ConfigWatchManager>,
+    project_path: String,
+) -> Result<(), String> {
+    if let Some(handle) = state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.remove(&project_path) {
+        handle.abort();
     }
-    
+    Ok(())
+}
+
+/// Raise OS notifications when a project's services crash, run out of memory or recover
+///
+/// Every lifecycle event is also forwarded to the UI as a `service-event`.
+#[tauri::command]
+async fn start_service_notifications(
+    app: tauri::AppHandle,
+    state: State<'_, ServiceMonitorManager>,
+    engines: State<'_, EngineManager>,
+    project_path: String,
+) -> Result<(), String> {
+    use tauri_plugin_notification::NotificationExt;
+    use zeroconfig::health::{FailureTracker, Transition};
+
+    if let Some(handle) = state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.remove(&project_path) {
+        handle.abort();
+    }
+
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let project_name = engine.project_name().to_string();
+    let mut events = engine.service_events();
+
+    let handle = tokio::spawn(async move {
+        let mut tracker = FailureTracker::new();
+
+        while let Some(Ok(event)) = events.next().await {
+            let _ = app.emit("service-event", serde_json::json!({
+                "project": project_name,
+                "service": event.service,
+                "message": event.describe(),
+                "failure": event.kind.is_failure(),
+            }));
+
+            let title = match tracker.observe(event.clone()) {
+                Some(Transition::Failed(_)) => format!("⚠️ {}: service down", project_name),
+                Some(Transition::Recovered(_)) => format!("✅ {}: service recovered", project_name),
+                None => continue,
+            };
+
+            let _ = app.notification()
+                .builder()
+                .title(title)
+                .body(event.describe())
+                .show();
+        }
+    });
+
+    state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.insert(project_path, handle.abort_handle());
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_service_notifications(
+    state: State<'_, ServiceMonitorManager>,
+    project_path: String,
+) -> Result<(), String> {
+    if let Some(handle) = state.handles.lock().map_err(|_| "Failed to lock mutex".to_string())?.remove(&project_path) {
+        handle.abort();
+    }
+    Ok(())
+}
+
+/// Open a shell in a service container in the system terminal
+///
+/// The terminal runs `zero shell`, which reaches the container through the
+/// engine API, so no `docker` CLI is needed.
+#[tauri::command]
+async fn open_terminal_window(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: String,
+    shell: Option<String>,
+) -> Result<(), String> {
     let shell_cmd = shell.unwrap_or_else(|| "sh".to_string());
-    
+
     // Validate shell command to prevent injection
     validate_shell_command(&shell_cmd)?;
-    
+
+    let engine = engines.get(&project_path).await?;
+    let details = engine.read().await.inspect_service(&service_name).await
+        .map_err(|e| format!("{:#}", e))?;
+    if details.status.as_deref() != Some("running") {
+        return Err(format!("Service {} is not running", service_name));
+    }
+
+    let args = ["zero", "--project-dir", project_path.as_str(), "shell", service_name.as_str(), "--shell", shell_cmd.as_str()];
+
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("cmd")
-            .args(&["/C", "start", "cmd", "/K", &format!("docker exec -it {} {}", container_id, shell_cmd)])
+            .args(["/C", "start", "cmd", "/K"])
+            .args(args)
             .spawn()
             .map_err(|e| format!("Failed to open terminal: {}", e))?;
     }
 
     #[cfg(target_os = "macos")]
     {
+        let command_line = args.iter()
+            .map(|arg| format!("'{}'", arg.replace('\'', "'\\''")))
+            .collect::<Vec<_>>()
+            .join(" ");
         std::process::Command::new("osascript")
-            .args(&["-e", &format!("tell application \"Terminal\" to do script \"docker exec -it {} {}\"", container_id, shell_cmd)])
+            .args(["-e", &format!("tell application \"Terminal\" to do script \"{}\"", command_line.replace('\\', "\\\\").replace('"', "\\\""))])
             .spawn()
             .map_err(|e| format!("Failed to open terminal: {}", e))?;
     }
@@ -607,21 +956,11 @@ async fn open_terminal_window(service_name: String, shell: Option<String>) -> Re
     {
         // Try common terminal emulators
         let terminals = ["gnome-terminal", "konsole", "xterm", "rxvt"];
-        let mut spawned = false;
-        
-        for term in terminals {
-            let args = match term {
-                "gnome-terminal" => vec!["--", "docker", "exec", "-it", &container_id, &shell_cmd],
-                "konsole" => vec!["-e", "docker", "exec", "-it", &container_id, &shell_cmd],
-                _ => vec!["-e", &format!("docker exec -it {} {}", container_id, shell_cmd)],
-            };
-            
-            if std::process::Command::new(term).args(&args).spawn().is_ok() {
-                spawned = true;
-                break;
-            }
-        }
-        
+        let spawned = terminals.iter().any(|term| {
+            let separator = if *term == "gnome-terminal" { "--" } else { "-e" };
+            std::process::Command::new(term).arg(separator).args(args).spawn().is_ok()
+        });
+
         if !spawned {
             return Err("No supported terminal emulator found".to_string());
         }
@@ -630,43 +969,400 @@ async fn open_terminal_window(service_name: String, shell: Option<String>) -> Re
     Ok(())
 }
 
+/// Resolve the backup directory for a project, relative to the project rather than the app
+fn project_backup_dir(project_path: &str, config: &ZeroConfig) -> std::path::PathBuf {
+    let dir = backup::output_dir(config, None);
+    if dir.is_absolute() {
+        dir
+    } else {
+        std::path::Path::new(project_path).join(dir)
+    }
+}
+
+/// Emit a `backup-progress` event for the dashboard
+fn emit_backup_progress(app: &tauri::AppHandle, project_path: &str, service: &str, operation: &str, stage: serde_json::Value) {
+    let _ = app.emit("backup-progress", serde_json::json!({
+        "project_path": project_path,
+        "service": service,
+        "operation": operation,
+        "stage": stage,
+    }));
+}
+
+/// Back up a database service, reporting each step as a `backup-progress` event
+///
+/// Uploads and retention from the `backups:` section apply just like `zero backup`.
+#[tauri::command]
+async fn backup_service(
+    app: tauri::AppHandle,
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: String,
+) -> Result<BackupEntry, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let output_dir = project_backup_dir(&project_path, engine.config());
+
+    let result = backup::backup_service_with_progress(&engine, &service_name, &output_dir, |stage| {
+        emit_backup_progress(&app, &project_path, &service_name, "backup", serde_json::json!(stage));
+    }).await;
+    let backup_file = match result {
+        Ok(file) => file,
+        Err(e) => {
+            emit_backup_progress(&app, &project_path, &service_name, "backup", serde_json::json!("failed"));
+            return Err(format!("Backup failed: {:#}", e));
+        }
+    };
+
+    let backups_config = engine.config().backups.clone().unwrap_or_default();
+    if let Some(upload) = &backups_config.upload {
+        emit_backup_progress(&app, &project_path, &service_name, "backup", serde_json::json!("uploading"));
+        // Like the CLI, a failed upload leaves the local backup in place
+        if backup::upload_backup(upload, &backup_file).await.is_err() {
+            emit_backup_progress(&app, &project_path, &service_name, "backup", serde_json::json!("upload_failed"));
+        }
+    }
+    if let Some(keep) = backups_config.retention {
+        backup::apply_retention(&output_dir, engine.project_name(), &service_name, keep)
+            .map_err(|e| format!("Failed to apply retention: {:#}", e))?;
+    }
+
+    emit_backup_progress(&app, &project_path, &service_name, "backup", serde_json::json!("done"));
+
+    backup::backup_entries(&output_dir, engine.project_name(), &service_name)
+        .map_err(|e| format!("Failed to list backups: {:#}", e))?
+        .into_iter()
+        .find(|entry| entry.path == backup_file)
+        .ok_or_else(|| format!("Backup {} was not found after writing it", backup_file.display()))
+}
+
+/// List backups for one service, or every database service, newest first
+#[tauri::command]
+async fn list_backups(
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: Option<String>,
+) -> Result<Vec<BackupEntry>, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let output_dir = project_backup_dir(&project_path, engine.config());
+
+    let services = match service_name {
+        Some(service) => vec![service],
+        None => backup::database_services(engine.config()),
+    };
+
+    let mut entries = Vec::new();
+    for service in services {
+        entries.extend(
+            backup::backup_entries(&output_dir, engine.project_name(), &service)
+                .map_err(|e| format!("Failed to list backups: {:#}", e))?,
+        );
+    }
+    Ok(entries)
+}
+
+/// Restore a service from a backup file, reporting each step as a `backup-progress` event
+///
+/// Returns whether the file's checksum was verified against the manifest.
+#[tauri::command]
+async fn restore_service(
+    app: tauri::AppHandle,
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: String,
+    file: String,
+) -> Result<bool, String> {
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+
+    let result = backup::restore_service_with_progress(&engine, &service_name, std::path::Path::new(&file), |stage| {
+        emit_backup_progress(&app, &project_path, &service_name, "restore", serde_json::json!(stage));
+    }).await;
+
+    match result {
+        Ok((_, verification)) => {
+            emit_backup_progress(&app, &project_path, &service_name, "restore", serde_json::json!("done"));
+            Ok(verification == backup::Verification::Verified)
+        }
+        Err(e) => {
+            emit_backup_progress(&app, &project_path, &service_name, "restore", serde_json::json!("failed"));
+            Err(format!("Restore failed: {:#}", e))
+        }
+    }
+}
+
+/// Run a one-off command in a service, streaming output as `service-exec-output`
+/// events and finishing with a `service-exec-exit` event carrying the exit code.
+///
+/// Pass a `run_id` to correlate events from before the command returns;
+/// otherwise the Docker exec id is used and returned.
+#[tauri::command]
+async fn exec_in_service(
+    app: tauri::AppHandle,
+    engines: State<'_, EngineManager>,
+    project_path: String,
+    service_name: String,
+    command: Vec<String>,
+    run_id: Option<String>,
+) -> Result<String, String> {
+    use zeroconfig::orchestrator::ExecOutput;
+
+    if command.is_empty() {
+        return Err("Command cannot be empty".to_string());
+    }
+
+    let engine = engines.get(&project_path).await?;
+    let engine = engine.read().await;
+    let (mut output, control) = engine.exec_command_stream(&service_name, command).await
+        .map_err(|e| format!("Failed to run command in {}: {}", service_name, e))?;
+
+    let run_id = run_id.unwrap_or_else(|| control.id().to_string());
+    let event_id = run_id.clone();
+
+    tokio::spawn(async move {
+        while let Some(chunk) = output.next().await {
+            let (stream, data) = match chunk {
+                Ok(ExecOutput::Stdout(data)) => ("stdout", data),
+                Ok(ExecOutput::Stderr(data)) => ("stderr", data),
+                Err(_) => break,
+            };
+            let _ = app.emit("service-exec-output", serde_json::json!({
+                "run_id": event_id,
+                "stream": stream,
+                "data": String::from_utf8_lossy(&data),
+            }));
+        }
+
+        let exit_code = control.exit_code().await.ok().flatten();
+        let _ = app.emit("service-exec-exit", serde_json::json!({
+            "run_id": event_id,
+            "exit_code": exit_code,
+        }));
+    });
+
+    Ok(run_id)
+}
+
+// In-app terminal sessions
+#[tauri::command]
+async fn create_exec_session(
+    app: tauri::AppHandle,
+    engines: State<'_, EngineManager>,
+    sessions: State<'_, ExecSessionManager>,
+    request: ExecSessionRequest,
+) -> Result<String, String> {
+    let shell_cmd = request.shell.unwrap_or_else(|| "sh".to_string());
+    validate_shell_command(&shell_cmd)?;
+
+    let engine = engines.get(&request.project_path).await?;
+    let engine = engine.read().await;
+    let session = engine
+        .open_exec_session(&request.service_name, vec![shell_cmd], request.cols.unwrap_or(80), request.rows.unwrap_or(24))
+        .await
+        .map_err(|e| format!("Failed to open session in {}: {}", request.service_name, e))?;
+
+    Ok(sessions.register(app, session).await)
+}
+
+#[tauri::command]
+async fn write_to_session(
+    sessions: State<'_, ExecSessionManager>,
+    session_id: String,
+    data: String,
+) -> Result<(), String> {
+    sessions.write(&session_id, data.as_bytes()).await
+}
+
+#[tauri::command]
+async fn resize_session(
+    sessions: State<'_, ExecSessionManager>,
+    session_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    sessions.resize(&session_id, cols, rows).await
+}
+
+#[tauri::command]
+async fn close_session(
+    sessions: State<'_, ExecSessionManager>,
+    session_id: String,
+) -> Result<(), String> {
+    sessions.close(&session_id).await
+}
+
+// Recent projects
+#[tauri::command]
+async fn add_recent_project(
+    state: State<'_, Mutex<RecentProjectStore>>,
+    project_path: String,
+    name: Option<String>,
+    pinned: Option<bool>,
+) -> Result<RecentProject, String> {
+    // Prefer the name from zero.yml, then the folder name
+    let name = name
+        .or_else(|| {
+            ZeroConfig::discover_in(&project_path)
+                .ok()
+                .flatten()
+                .and_then(|config| config.metadata.name)
+        })
+        .or_else(|| {
+            std::path::Path::new(&project_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| project_path.clone());
+
+    state.lock().map_err(|_| "Failed to lock mutex".to_string())?
+        .add(project_path, name, pinned)
+}
+
+#[tauri::command]
+async fn list_recent_projects(state: State<'_, Mutex<RecentProjectStore>>) -> Result<Vec<RecentProject>, String> {
+    Ok(state.lock().map_err(|_| "Failed to lock mutex".to_string())?.list())
+}
+
+/// Summaries of every project with containers, plus all recent projects
+#[tauri::command]
+async fn list_all_projects_status(
+    state: State<'_, Mutex<RecentProjectStore>>,
+) -> Result<Vec<ProjectSummary>, String> {
+    let recent = state.lock().map_err(|_| "Failed to lock mutex".to_string())?.list();
+
+    let known: Vec<KnownProject> = recent.into_iter()
+        .map(|project| {
+            let config = ZeroConfig::discover_in(&project.path).ok().flatten();
+            let mut services: Vec<String> = config.as_ref()
+                .map(|config| config.services.keys().cloned().collect())
+                .unwrap_or_default();
+            services.sort();
+            KnownProject {
+                name: config
+                    .and_then(|config| config.metadata.name)
+                    .unwrap_or_else(|| "zeroconfig-project".to_string()),
+                path: Some(project.path),
+                services,
+            }
+        })
+        .collect();
+
+    zeroconfig::orchestrator::overview::project_summaries(&known).await
+        .map_err(|e| format!("Failed to summarize projects: {}", e))
+}
+
+#[tauri::command]
+async fn remove_recent_project(
+    state: State<'_, Mutex<RecentProjectStore>>,
+    project_path: String,
+) -> Result<bool, String> {
+    state.lock().map_err(|_| "Failed to lock mutex".to_string())?
+        .remove(&project_path)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(LogStreamManager::new())
+        .manage(ConfigWatchManager::new())
+        .manage(ServiceMonitorManager::new())
+        .manage(EngineManager::new())
+        .manage(ExecSessionManager::new())
+        .manage(StatsHistory::new())
+        .setup(|app| {
+            let store_path = app.path().app_data_dir()?.join(recent_projects::STORE_FILE);
+            app.manage(Mutex::new(RecentProjectStore::load(store_path)));
+
+            // Keep a rolling window of stats so charts have history when opened
+            tauri::async_runtime::spawn(stats_history::sample_loop(app.handle().clone()));
+
+            // Rebuild cached engines from disk whenever a project's config changes
+            let handle = app.handle().clone();
+            app.listen_any(engine_manager::CONFIG_CHANGED_EVENT, move |event| {
+                let project_path = serde_json::from_str::<serde_json::Value>(event.payload())
+                    .ok()
+                    .and_then(|payload| payload.get("project_path").and_then(|p| p.as_str()).map(str::to_string));
+
+                if let Some(project_path) = project_path {
+                    let handle = handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        handle.state::<EngineManager>().invalidate(&project_path).await;
+                    });
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             init_project,
+            detect_project_stack,
             list_services,
+            preflight_start,
             start_services,
             stop_services,
             start_service,
             stop_service,
             restart_service,
             get_service_logs,
+            search_logs,
+            export_logs,
             start_log_stream,
             stop_log_stream,
+            watch_project_config,
+            unwatch_project_config,
+            start_service_notifications,
+            stop_service_notifications,
             open_terminal_window,
             start_cloud_emulator,
             stop_cloud_emulator,
             get_cloud_status,
-            check_docker_status,
-            check_podman_status,
-            check_minikube_status,
+            get_project_cloud_status,
+            list_cloud_resources,
+            start_cloud_log_stream,
+            stop_cloud_log_stream,
+            run_doctor,
+            apply_doctor_fix,
+            install_runtime,
             detect_all_runtimes,
             load_template,
             list_templates,
             save_config,
+            set_config_value,
             load_config,
             validate_config,
+            get_config_schema,
             generate_dockerfile,
             generate_compose,
             generate_env_file,
             generate_github_actions,
             generate_all_configs,
             get_services_stats,
+            get_stats_history,
+            get_service_graph,
+            get_web_uis,
+            get_project_state,
+            inspect_service,
+            get_resolved_env,
+            backup_service,
+            list_backups,
+            restore_service,
+            list_project_volumes,
+            inspect_volume,
+            browse_volume_path,
+            delete_volume,
+            add_recent_project,
+            list_recent_projects,
+            remove_recent_project,
+            list_all_projects_status,
+            exec_in_service,
+            create_exec_session,
+            write_to_session,
+            resize_session,
+            close_session,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File name of the store inside the app data directory
+pub const STORE_FILE: &str = "recent_projects.json";
+
+/// Unpinned projects beyond this count are dropped, oldest first
+const MAX_RECENT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentProject {
+    pub path: String,
+    pub name: String,
+    /// Unix timestamp (seconds) of the last time the project was opened
+    pub last_opened: u64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Recently opened projects, persisted as JSON in the app data directory
+pub struct RecentProjectStore {
+    file: PathBuf,
+    projects: Vec<RecentProject>,
+}
+
+impl RecentProjectStore {
+    /// Load the store, starting empty if the file is missing or unreadable
+    pub fn load(file: PathBuf) -> Self {
+        let projects = std::fs::read_to_string(&file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { file, projects }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(parent) = self.file.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+        }
+
+        let content = serde_json::to_string_pretty(&self.projects)
+            .map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+        std::fs::write(&self.file, content)
+            .map_err(|e| format!("Failed to save recent projects: {}", e))
+    }
+
+    /// Projects sorted with pinned first, then most recently opened
+    pub fn list(&self) -> Vec<RecentProject> {
+        let mut projects = self.projects.clone();
+        projects.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.last_opened.cmp(&a.last_opened)));
+        projects
+    }
+
+    /// Record that a project was opened, keeping its pin unless one is given
+    pub fn add(&mut self, path: String, name: String, pinned: Option<bool>) -> Result<RecentProject, String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let project = match self.projects.iter_mut().find(|p| p.path == path) {
+            Some(existing) => {
+                existing.name = name;
+                existing.last_opened = now;
+                if let Some(pinned) = pinned {
+                    existing.pinned = pinned;
+                }
+                existing.clone()
+            }
+            None => {
+                let project = RecentProject {
+                    path,
+                    name,
+                    last_opened: now,
+                    pinned: pinned.unwrap_or(false),
+                };
+                self.projects.push(project.clone());
+                project
+            }
+        };
+
+        self.prune();
+        self.save()?;
+        Ok(project)
+    }
+
+    /// Forget a project; returns whether it was present
+    pub fn remove(&mut self, path: &str) -> Result<bool, String> {
+        let before = self.projects.len();
+        self.projects.retain(|p| p.path != path);
+        let removed = self.projects.len() != before;
+
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn prune(&mut self) {
+        let mut unpinned: Vec<(u64, String)> = self.projects
+            .iter()
+            .filter(|p| !p.pinned)
+            .map(|p| (p.last_opened, p.path.clone()))
+            .collect();
+
+        if unpinned.len() <= MAX_RECENT {
+            return;
+        }
+
+        unpinned.sort();
+        let excess = unpinned.len() - MAX_RECENT;
+        let stale: Vec<String> = unpinned.into_iter().take(excess).map(|(_, path)| path).collect();
+        self.projects.retain(|p| !stale.contains(&p.path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(path: &str, last_opened: u64, pinned: bool) -> RecentProject {
+        RecentProject { path: path.to_string(), name: path.to_string(), last_opened, pinned }
+    }
+
+    #[test]
+    fn test_add_dedupes_by_path_and_keeps_the_pin() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = RecentProjectStore::load(dir.path().join(STORE_FILE));
+        store.add("/work/shop".to_string(), "shop".to_string(), Some(true)).unwrap();
+        let again = store.add("/work/shop".to_string(), "storefront".to_string(), None).unwrap();
+
+        assert_eq!(store.list().len(), 1);
+        assert_eq!(again.name, "storefront");
+        assert!(again.pinned);
+    }
+
+    #[test]
+    fn test_list_puts_pinned_then_most_recent_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = RecentProjectStore::load(dir.path().join(STORE_FILE));
+        store.projects = vec![project("/old", 1, false), project("/pinned", 0, true), project("/new", 2, false)];
+
+        let paths: Vec<String> = store.list().into_iter().map(|project| project.path).collect();
+        assert_eq!(paths, vec!["/pinned", "/new", "/old"]);
+    }
+
+    #[test]
+    fn test_prune_drops_the_oldest_unpinned_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = RecentProjectStore::load(dir.path().join(STORE_FILE));
+        store.projects = (0..MAX_RECENT as u64 + 2).map(|i| project(&format!("/p{}", i), i, false)).collect();
+        store.projects.push(project("/pinned", 0, true));
+        store.prune();
+
+        assert_eq!(store.projects.len(), MAX_RECENT + 1);
+        assert!(!store.projects.iter().any(|project| project.path == "/p0" || project.path == "/p1"));
+        assert!(store.projects.iter().any(|project| project.path == "/pinned"));
+    }
+
+    #[test]
+    fn test_store_persists_across_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app").join(STORE_FILE);
+        let mut store = RecentProjectStore::load(file.clone());
+        store.add("/work/shop".to_string(), "shop".to_string(), None).unwrap();
+        store.add("/work/blog".to_string(), "blog".to_string(), None).unwrap();
+        assert!(store.remove("/work/blog").unwrap());
+        assert!(!store.remove("/work/blog").unwrap());
+
+        let reloaded = RecentProjectStore::load(file);
+        let paths: Vec<String> = reloaded.list().into_iter().map(|project| project.path).collect();
+        assert_eq!(paths, vec!["/work/shop"]);
+    }
+}
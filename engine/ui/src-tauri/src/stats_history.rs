@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use zeroconfig::orchestrator::overview::project_stats;
+use zeroconfig::orchestrator::ResourceUsage;
+
+use crate::engine_manager::{cache_key, EngineManager};
+
+/// How far back samples are kept
+pub const WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Time between samples of every open project's containers
+const INTERVAL: Duration = Duration::from_secs(5);
+
+/// Most samples a history request returns; longer ranges are averaged down
+pub const MAX_POINTS: usize = 180;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsSample {
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub cpu: f64,
+    pub memory_usage: u64,
+    pub memory_limit: u64,
+    /// Cumulative network bytes; charts derive rates from consecutive samples
+    pub rx: u64,
+    pub tx: u64,
+}
+
+/// Rolling window of stats samples per project and service, kept in memory
+#[derive(Default)]
+pub struct StatsHistory {
+    // project key -> service -> samples, oldest first
+    series: Mutex<HashMap<String, HashMap<String, VecDeque<StatsSample>>>>,
+}
+
+impl StatsHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one sample of every container of a project, dropping samples that
+    /// fell out of the window and containers that are gone
+    fn record(&self, project_key: &str, samples: Vec<(String, StatsSample)>) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        let services = series.entry(project_key.to_string()).or_default();
+        services.retain(|service, _| samples.iter().any(|(sampled, _)| sampled == service));
+
+        for (service, sample) in samples {
+            let cutoff = sample.timestamp.saturating_sub(WINDOW.as_millis() as u64);
+            let history = services.entry(service).or_default();
+            history.push_back(sample);
+            while history.front().is_some_and(|oldest| oldest.timestamp < cutoff) {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Forget projects whose engine is no longer cached
+    fn retain_projects(&self, project_keys: &[String]) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        series.retain(|project_key, _| project_keys.contains(project_key));
+    }
+
+    /// Samples of a service from the last `range`, oldest first and at most [`MAX_POINTS`]
+    pub fn range(&self, project_path: &str, service: &str, range: Duration) -> Vec<StatsSample> {
+        let cutoff = now_millis().saturating_sub(range.as_millis() as u64);
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+
+        let samples = series.get(&cache_key(project_path))
+            .and_then(|services| services.get(service))
+            .map(|samples| samples.iter().filter(|sample| sample.timestamp >= cutoff).cloned().collect())
+            .unwrap_or_default();
+        downsample(samples, MAX_POINTS)
+    }
+}
+
+/// Average runs of consecutive samples so at most `points` remain
+///
+/// Network counters are cumulative, so each run keeps its last value.
+fn downsample(samples: Vec<StatsSample>, points: usize) -> Vec<StatsSample> {
+    if samples.len() <= points || points == 0 {
+        return samples;
+    }
+
+    samples.chunks(samples.len().div_ceil(points))
+        .map(|run| {
+            let last = &run[run.len() - 1];
+            let count = run.len() as u64;
+            StatsSample {
+                timestamp: last.timestamp,
+                cpu: run.iter().map(|sample| sample.cpu).sum::<f64>() / run.len() as f64,
+                memory_usage: run.iter().map(|sample| sample.memory_usage).sum::<u64>() / count,
+                memory_limit: last.memory_limit,
+                rx: last.rx,
+                tx: last.tx,
+            }
+        })
+        .collect()
+}
+
+/// Sample the containers of every project with a cached engine, forever
+///
+/// Only projects the app has opened have engines, so idle projects cost nothing.
+pub async fn sample_loop(app: AppHandle) {
+    let mut interval = tokio::time::interval(INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let engines = app.state::<EngineManager>().cached().await;
+        let history = app.state::<StatsHistory>();
+        history.retain_projects(&engines.iter().map(|(project_key, _)| project_key.clone()).collect::<Vec<_>>());
+
+        for (project_key, engine) in engines {
+            // Only hold the engine while copying its connection, so sampling
+            // never makes a start or stop wait
+            let (docker, project_name) = {
+                let engine = engine.read().await;
+                (engine.docker(), engine.project_name().to_string())
+            };
+            let stats = match project_stats(&docker, &project_name).await {
+                Ok(stats) => stats,
+                Err(_) => continue,
+            };
+
+            let timestamp = now_millis();
+            let samples = stats.into_iter()
+                .map(|(service, stats)| {
+                    let usage = ResourceUsage::from_stats(&stats);
+                    (service, StatsSample {
+                        timestamp,
+                        cpu: usage.cpu_percent,
+                        memory_usage: usage.memory_usage,
+                        memory_limit: usage.memory_limit,
+                        rx: usage.rx_bytes,
+                        tx: usage.tx_bytes,
+                    })
+                })
+                .collect();
+            history.record(&project_key, samples);
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, cpu: f64, rx: u64) -> StatsSample {
+        StatsSample { timestamp, cpu, memory_usage: 100, memory_limit: 1000, rx, tx: 0 }
+    }
+
+    fn timestamps(history: &StatsHistory, service: &str) -> Vec<u64> {
+        history.series.lock().unwrap()["/project"][service].iter().map(|sample| sample.timestamp).collect()
+    }
+
+    #[test]
+    fn test_record_keeps_the_window_and_sampled_services() {
+        let history = StatsHistory::new();
+        let window = WINDOW.as_millis() as u64;
+        history.record("/project", vec![("db".to_string(), sample(1_000, 1.0, 0)), ("cache".to_string(), sample(1_000, 1.0, 0))]);
+        history.record("/project", vec![("db".to_string(), sample(2_000, 1.0, 0)), ("cache".to_string(), sample(2_000, 1.0, 0))]);
+        history.record("/project", vec![("db".to_string(), sample(1_500 + window, 1.0, 0))]);
+
+        assert_eq!(timestamps(&history, "db"), vec![2_000, 1_500 + window]);
+        assert!(!history.series.lock().unwrap()["/project"].contains_key("cache"));
+
+        history.retain_projects(&[]);
+        assert!(history.series.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_downsample_averages_runs() {
+        let samples: Vec<StatsSample> = (0..6).map(|i| sample(i * 5_000, i as f64, i * 10)).collect();
+        assert_eq!(downsample(samples.clone(), 10).len(), 6);
+
+        let points = downsample(samples, 3);
+        assert_eq!(points.iter().map(|point| point.timestamp).collect::<Vec<_>>(), vec![5_000, 15_000, 25_000]);
+        assert_eq!(points.iter().map(|point| point.cpu).collect::<Vec<_>>(), vec![0.5, 2.5, 4.5]);
+        assert_eq!(points.iter().map(|point| point.rx).collect::<Vec<_>>(), vec![10, 30, 50]);
+    }
+}
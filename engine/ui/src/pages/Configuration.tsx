@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react';
 import { FileText, Download, Upload, Check, AlertCircle, Code, Eye, Wand2, History, Save, RefreshCw, Box, Cloud } from 'lucide-react';
-import { tauriApi } from '../services/tauri';
+import { tauriApi, type ConfigDiagnostic } from '../services/tauri';
 import { AVAILABLE_TEMPLATES } from '../types';
 import Editor from '@monaco-editor/react';
 import yaml from 'js-yaml';
@@ -81,8 +81,13 @@ export function Configuration({ projectPath = '' }: ConfigurationProps) {
     setValidationMessage(null);
 
     try {
-      const result = await tauriApi.validateConfig(configContent);
-      setValidationMessage({ type: 'success', message: result });
+      const diagnostics = await tauriApi.validateConfig(configContent);
+      const errors = diagnostics.filter((d) => d.severity === 'error');
+      if (errors.length > 0) {
+        setValidationMessage({ type: 'error', message: formatDiagnostics(errors) });
+      } else {
+        setValidationMessage({ type: 'success', message: 'Configuration is valid' });
+      }
     } catch (error) {
       setValidationMessage({ type: 'error', message: `${error}` });
     } finally {
@@ -105,7 +110,10 @@ export function Configuration({ projectPath = '' }: ConfigurationProps) {
     setValidationMessage(null);
 
     try {
-      await tauriApi.validateConfig(configContent);
+      const errors = (await tauriApi.validateConfig(configContent)).filter((d) => d.severity === 'error');
+      if (errors.length > 0) {
+        throw formatDiagnostics(errors);
+      }
       const result = await tauriApi.saveConfig(projectPath, configContent);
       setValidationMessage({ type: 'success', message: result });
       addToHistory(configContent);
@@ -267,6 +275,12 @@ export function Configuration({ projectPath = '' }: ConfigurationProps) {
   );
 }
 
+function formatDiagnostics(diagnostics: ConfigDiagnostic[]): string {
+  return diagnostics
+    .map((d) => (d.line != null ? `Line ${d.line}: ${d.message}` : d.message))
+    .join('\n');
+}
+
 function TabButton({ active, onClick, icon, label }: { active: boolean; onClick: () => void; icon: React.ReactNode; label: string }) {
   return (
     <button
@@ -43,30 +43,7 @@ export function Dashboard({
       setRuntimes(detected);
     } catch (err) {
       console.error('Failed to detect runtimes:', err);
-      // Fallback to legacy detection
-      try {
-        const dockerVersion = await invoke<string>('check_docker_status');
-        setRuntimes([{
-          name: 'Docker',
-          installed: true,
-          running: true,
-          version: dockerVersion,
-          is_preferred: true,
-        }]);
-      } catch {
-        try {
-          const podmanVersion = await invoke<string>('check_podman_status');
-          setRuntimes([{
-            name: 'Podman',
-            installed: true,
-            running: true,
-            version: podmanVersion,
-            is_preferred: true,
-          }]);
-        } catch {
-          setRuntimes([]);
-        }
-      }
+      setRuntimes([]);
     } finally {
       setIsCheckingRuntimes(false);
     }
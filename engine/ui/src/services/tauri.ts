@@ -5,6 +5,37 @@ import { invoke } from '@tauri-apps/api/core';
  * Provides type-safe wrappers for all Tauri commands
  */
 
+export interface ConfigDiagnostic {
+  path: string;
+  line: number | null;
+  column: number | null;
+  severity: 'info' | 'warning' | 'error';
+  message: string;
+}
+
+export type DoctorAction =
+  | { type: 'install_runtime'; runtime: string; version: string }
+  | { type: 'start_runtime'; runtime: string }
+  | { type: 'run_command'; command: string }
+  | { type: 'edit_config'; path: string | null };
+
+export interface DoctorCheck {
+  id: string;
+  category: 'runtime' | 'daemon' | 'disk' | 'config' | 'languages' | 'ports';
+  title: string;
+  status: 'pass' | 'warn' | 'fail';
+  message: string;
+  action: DoctorAction | null;
+}
+
+export interface CloudStatus {
+  provider: string;
+  running: boolean;
+  endpoint: string;
+  hostname: string;
+  services: string[];
+}
+
 export const tauriApi = {
   // Project Management
   async initProject(projectPath: string): Promise<string> {
@@ -98,12 +129,20 @@ export const tauriApi = {
     }
   },
 
+  async getProjectCloudStatus(projectPath: string): Promise<CloudStatus[]> {
+    try {
+      return await invoke<CloudStatus[]>('get_project_cloud_status', { projectPath });
+    } catch (error) {
+      throw new Error(`Failed to get cloud status: ${error}`);
+    }
+  },
+
   // System Health
-  async checkDockerStatus(): Promise<string> {
+  async runDoctor(projectPath?: string): Promise<DoctorCheck[]> {
     try {
-      return await invoke<string>('check_docker_status');
+      return await invoke<DoctorCheck[]>('run_doctor', { projectPath });
     } catch (error) {
-      throw new Error(`Failed to check Docker status: ${error}`);
+      throw new Error(`Failed to run doctor checks: ${error}`);
     }
   },
 
@@ -132,6 +171,14 @@ export const tauriApi = {
     }
   },
 
+  async setConfigValue(projectPath: string, key: string, value: string): Promise<string> {
+    try {
+      return await invoke<string>('set_config_value', { projectPath, key, value });
+    } catch (error) {
+      throw new Error(`Failed to set ${key}: ${error}`);
+    }
+  },
+
   async loadConfig(projectPath: string): Promise<string> {
     try {
       return await invoke<string>('load_config', { projectPath });
@@ -140,9 +187,9 @@ export const tauriApi = {
     }
   },
 
-  async validateConfig(configContent: string): Promise<string> {
+  async validateConfig(configContent: string): Promise<ConfigDiagnostic[]> {
     try {
-      return await invoke<string>('validate_config', { configContent });
+      return await invoke<ConfigDiagnostic[]>('validate_config', { configContent });
     } catch (error) {
       throw new Error(`Failed to validate configuration: ${error}`);
     }